@@ -1,11 +1,25 @@
 use std::fmt::Display;
 
+use crate::error::{Error, Result};
+use crate::internal_err;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum JoinType {
     Left,
     Right,
     Inner,
     Full,
+    /// the left rows having a match in the right side
+    LeftSemi,
+    /// the left rows having no match in the right side
+    LeftAnti,
+}
+
+impl JoinType {
+    /// semi and anti joins only output the columns of the left side
+    pub fn is_semi_or_anti(&self) -> bool {
+        matches!(self, JoinType::LeftSemi | JoinType::LeftAnti)
+    }
 }
 
 impl Display for JoinType {
@@ -15,18 +29,25 @@ impl Display for JoinType {
             JoinType::Right => write!(f, "Right Join"),
             JoinType::Inner => write!(f, "Inner Join"),
             JoinType::Full => write!(f, "Full Join"),
+            JoinType::LeftSemi => write!(f, "Left Semi Join"),
+            JoinType::LeftAnti => write!(f, "Left Anti Join"),
         }
     }
 }
 
-impl From<sqlparser::ast::JoinType> for JoinType {
-    fn from(value: sqlparser::ast::JoinType) -> Self {
+impl TryFrom<sqlparser::ast::JoinType> for JoinType {
+    type Error = Error;
+
+    fn try_from(value: sqlparser::ast::JoinType) -> Result<Self> {
         match value {
-            sqlparser::ast::JoinType::Inner => JoinType::Inner,
-            sqlparser::ast::JoinType::Left => JoinType::Left,
-            sqlparser::ast::JoinType::Right => JoinType::Right,
-            sqlparser::ast::JoinType::Full => JoinType::Full,
-            _ => unimplemented!(),
+            sqlparser::ast::JoinType::Inner => Ok(JoinType::Inner),
+            sqlparser::ast::JoinType::Left => Ok(JoinType::Left),
+            sqlparser::ast::JoinType::Right => Ok(JoinType::Right),
+            sqlparser::ast::JoinType::Full => Ok(JoinType::Full),
+            sqlparser::ast::JoinType::LeftSemi => Ok(JoinType::LeftSemi),
+            sqlparser::ast::JoinType::LeftAnti => Ok(JoinType::LeftAnti),
+            // a cross join has no condition and is planned as a `CrossJoin`
+            sqlparser::ast::JoinType::Cross => internal_err!("CROSS JOIN is not a conditional join"),
         }
     }
 }
@@ -1,4 +1,7 @@
 pub mod join_type;
+pub mod sample_method;
+pub mod set_operator;
 pub mod table_relation;
 pub mod table_schema;
 pub mod transformed;
+pub mod window_frame;
@@ -0,0 +1,28 @@
+use std::fmt::Display;
+
+/// How the rows of a table are sampled by `TABLESAMPLE`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SampleMethod {
+    /// each row is selected independently
+    Bernoulli,
+    /// each batch of rows is selected or skipped as a whole
+    System,
+}
+
+impl Display for SampleMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SampleMethod::Bernoulli => write!(f, "BERNOULLI"),
+            SampleMethod::System => write!(f, "SYSTEM"),
+        }
+    }
+}
+
+impl From<sqlparser::ast::SampleMethod> for SampleMethod {
+    fn from(value: sqlparser::ast::SampleMethod) -> Self {
+        match value {
+            sqlparser::ast::SampleMethod::Bernoulli => SampleMethod::Bernoulli,
+            sqlparser::ast::SampleMethod::System => SampleMethod::System,
+        }
+    }
+}
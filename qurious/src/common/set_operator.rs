@@ -0,0 +1,28 @@
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SetOperator {
+    Union,
+    Intersect,
+    Except,
+}
+
+impl Display for SetOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetOperator::Union => write!(f, "UNION"),
+            SetOperator::Intersect => write!(f, "INTERSECT"),
+            SetOperator::Except => write!(f, "EXCEPT"),
+        }
+    }
+}
+
+impl From<sqlparser::ast::SetOperator> for SetOperator {
+    fn from(value: sqlparser::ast::SetOperator) -> Self {
+        match value {
+            sqlparser::ast::SetOperator::Union => SetOperator::Union,
+            sqlparser::ast::SetOperator::Intersect => SetOperator::Intersect,
+            sqlparser::ast::SetOperator::Except => SetOperator::Except,
+        }
+    }
+}
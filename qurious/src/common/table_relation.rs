@@ -75,6 +75,20 @@ impl TableRelation {
         }
     }
 
+    /// Whether a reference to a table, which may omit the catalog and the schema, refers to this relation,
+    /// eg. `t` and `public.t` refer to `public.t`
+    pub fn matches(&self, reference: &TableRelation) -> bool {
+        if self.is_file_source || reference.is_file_source {
+            return self == reference;
+        }
+        let part_matches = |a: Option<&str>, b: Option<&str>| a.zip(b).is_none_or(|(a, b)| a == b);
+
+        self.table() == reference.table()
+            && part_matches(self.schema(), reference.schema())
+            && part_matches(self.catalog(), reference.catalog())
+            && (reference.schema().is_none() || self.schema().is_some())
+    }
+
     /// Return the fully qualified name of the table
     pub fn to_quanlify_name(&self) -> String {
         if self.is_file_source {
@@ -153,6 +167,17 @@ mod tests {
         assert_eq!(table.to_quanlify_name(), "catalog.schema.table");
     }
 
+    #[test]
+    fn test_matches() {
+        let table = TableRelation::from("public.t");
+        assert!(table.matches(&"t".into()));
+        assert!(table.matches(&"public.t".into()));
+        assert!(table.matches(&"qurious.public.t".into()));
+        assert!(!table.matches(&"other.t".into()));
+        assert!(!table.matches(&"u".into()));
+        assert!(!TableRelation::from("t").matches(&"public.t".into()));
+    }
+
     #[test]
     fn test_parse_file_path() {
         let table = TableRelation::parse_file_path("./tests/testdata/file/case1.csv");
@@ -0,0 +1,117 @@
+use std::fmt::Display;
+
+use crate::error::{Error, Result};
+use crate::internal_err;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum WindowFrameUnits {
+    Rows,
+    Range,
+}
+
+impl Display for WindowFrameUnits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowFrameUnits::Rows => write!(f, "ROWS"),
+            WindowFrameUnits::Range => write!(f, "RANGE"),
+        }
+    }
+}
+
+/// `None` offset means `UNBOUNDED`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum WindowFrameBound {
+    Preceding(Option<u64>),
+    CurrentRow,
+    Following(Option<u64>),
+}
+
+impl Display for WindowFrameBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowFrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+            WindowFrameBound::Preceding(None) => write!(f, "UNBOUNDED PRECEDING"),
+            WindowFrameBound::Preceding(Some(n)) => write!(f, "{n} PRECEDING"),
+            WindowFrameBound::Following(None) => write!(f, "UNBOUNDED FOLLOWING"),
+            WindowFrameBound::Following(Some(n)) => write!(f, "{n} FOLLOWING"),
+        }
+    }
+}
+
+impl From<sqlparser::ast::WindowFrameBound> for WindowFrameBound {
+    fn from(value: sqlparser::ast::WindowFrameBound) -> Self {
+        match value {
+            sqlparser::ast::WindowFrameBound::CurrentRow => WindowFrameBound::CurrentRow,
+            sqlparser::ast::WindowFrameBound::Preceding(n) => WindowFrameBound::Preceding(n),
+            sqlparser::ast::WindowFrameBound::Following(n) => WindowFrameBound::Following(n),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct WindowFrame {
+    pub units: WindowFrameUnits,
+    pub start_bound: WindowFrameBound,
+    pub end_bound: WindowFrameBound,
+}
+
+impl WindowFrame {
+    /// The frame used when the window has no frame clause: `RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW`
+    /// if the window is ordered, otherwise the whole partition.
+    pub fn new(has_order_by: bool) -> Self {
+        Self {
+            units: WindowFrameUnits::Range,
+            start_bound: WindowFrameBound::Preceding(None),
+            end_bound: if has_order_by {
+                WindowFrameBound::CurrentRow
+            } else {
+                WindowFrameBound::Following(None)
+            },
+        }
+    }
+}
+
+impl Display for WindowFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} BETWEEN {} AND {}", self.units, self.start_bound, self.end_bound)
+    }
+}
+
+impl TryFrom<sqlparser::ast::WindowFrame> for WindowFrame {
+    type Error = Error;
+
+    fn try_from(value: sqlparser::ast::WindowFrame) -> Result<Self> {
+        let units = match value.units {
+            sqlparser::ast::WindowFrameUnits::Rows => WindowFrameUnits::Rows,
+            sqlparser::ast::WindowFrameUnits::Range => WindowFrameUnits::Range,
+        };
+        let start_bound = WindowFrameBound::from(value.start_bound);
+        let end_bound = value
+            .end_bound
+            .map(WindowFrameBound::from)
+            .unwrap_or(WindowFrameBound::CurrentRow);
+
+        if start_bound == WindowFrameBound::Following(None) {
+            return internal_err!("Invalid window frame: start bound cannot be UNBOUNDED FOLLOWING");
+        }
+        if end_bound == WindowFrameBound::Preceding(None) {
+            return internal_err!("Invalid window frame: end bound cannot be UNBOUNDED PRECEDING");
+        }
+        if units == WindowFrameUnits::Range
+            && [start_bound, end_bound].iter().any(|b| {
+                matches!(
+                    b,
+                    WindowFrameBound::Preceding(Some(_)) | WindowFrameBound::Following(Some(_))
+                )
+            })
+        {
+            return internal_err!("RANGE window frame with offset is not supported, use ROWS instead");
+        }
+
+        Ok(Self {
+            units,
+            start_bound,
+            end_bound,
+        })
+    }
+}
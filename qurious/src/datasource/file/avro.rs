@@ -0,0 +1,568 @@
+use std::sync::Arc;
+use url::Url;
+
+#[cfg(feature = "kafka")]
+use apache_avro::from_avro_datum;
+use apache_avro::schema::{NamesRef, ResolvedSchema, Schema as AvroSchema};
+use apache_avro::types::Value;
+use apache_avro::Reader;
+use arrow::array::{
+    ArrayRef, BinaryArray, BooleanArray, Date32Array, Decimal128Array, FixedSizeBinaryArray, Float32Array,
+    Float64Array, Int32Array, Int64Array, ListArray, MapArray, NullArray, RecordBatch, RecordBatchOptions, StringArray,
+    StructArray, Time32MillisecondArray, Time64MicrosecondArray, TimestampMicrosecondArray, TimestampMillisecondArray,
+    TimestampNanosecondArray,
+};
+use arrow::buffer::{NullBuffer, OffsetBuffer};
+use arrow::datatypes::{DataType, Field, Fields, Schema, SchemaRef, TimeUnit};
+
+use crate::arrow_err;
+use crate::datasource::file::object_store::{get_store, read_object, ObjectStore};
+use crate::datasource::file::DataFilePath;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::logical::expr::LogicalExpr;
+use crate::provider::table::TableProvider;
+
+/// the maximum number of rows of a batch of a scan
+const BATCH_SIZE: usize = 8192;
+/// the time zone of the instants of `timestamp-millis`, `timestamp-micros` and `timestamp-nanos`
+const UTC: &str = "+00:00";
+
+/// An Avro object container file read with the `apache-avro` crate, the columns are the fields of the top-level
+/// record of the schema written in the file header.
+#[derive(Debug)]
+pub struct AvroTable {
+    url: Url,
+    schema: SchemaRef,
+    store: Arc<dyn ObjectStore>,
+}
+
+impl TableProvider for AvroTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Option<Vec<String>>, _filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        let data = read_object(self.store.as_ref(), &self.url)?;
+        let indices = match projection {
+            Some(projection) => projection
+                .iter()
+                .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+                .collect::<Result<Vec<_>>>()?,
+            None => (0..self.schema.fields().len()).collect(),
+        };
+        let schema = Arc::new(self.schema.project(&indices)?);
+        let build_batch = |rows: &[Value]| {
+            let columns = indices
+                .iter()
+                .map(|i| {
+                    let values = rows.iter().map(|row| field(row, *i)).collect::<Vec<_>>();
+                    build_array(self.schema.field(*i).data_type(), &values)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+            RecordBatch::try_new_with_options(schema.clone(), columns, &options).map_err(|e| arrow_err!(e))
+        };
+
+        let reader = Reader::new(data.as_ref()).map_err(|e| avro_err(&self.url, e))?;
+        let mut batches = vec![];
+        let mut rows = Vec::with_capacity(BATCH_SIZE);
+        for row in reader {
+            rows.push(row.map_err(|e| avro_err(&self.url, e))?);
+            if rows.len() == BATCH_SIZE {
+                batches.push(build_batch(&rows)?);
+                rows.clear();
+            }
+        }
+        if !rows.is_empty() {
+            batches.push(build_batch(&rows)?);
+        }
+
+        Ok(batches)
+    }
+}
+
+pub fn read_avro<T: DataFilePath>(path: T) -> Result<Arc<dyn TableProvider>> {
+    let url = path.to_url()?;
+    let store = get_store(&url)?;
+    let data = read_object(store.as_ref(), &url)?;
+    let reader = Reader::new(data.as_ref())
+        .map_err(|e| Error::InternalError(format!("{} is not an Avro file: {}", url.path(), e)))?;
+    let fields = record_fields(reader.writer_schema())
+        .map_err(|e| Error::InternalError(format!("the schema of the Avro file {}: {}", url.path(), e)))?;
+
+    Ok(Arc::new(AvroTable {
+        url,
+        schema: Arc::new(Schema::new(fields)),
+        store,
+    }))
+}
+
+/// A decoder of the records of a schema encoded one by one without the header of a container file, like the values
+/// of the messages of a stream
+#[cfg(feature = "kafka")]
+#[derive(Debug)]
+pub(crate) struct AvroRecordDecoder {
+    schema: SchemaRef,
+    avro_schema: AvroSchema,
+}
+
+#[cfg(feature = "kafka")]
+impl AvroRecordDecoder {
+    /// the decoder of the records of the json Avro schema, the schema must be a record
+    pub(crate) fn try_new(schema: &str) -> Result<Self> {
+        let avro_schema =
+            AvroSchema::parse_str(schema).map_err(|e| Error::InternalError(format!("invalid Avro schema: {}", e)))?;
+        let fields = record_fields(&avro_schema)?;
+
+        Ok(Self {
+            schema: Arc::new(Schema::new(fields)),
+            avro_schema,
+        })
+    }
+
+    pub(crate) fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// the columns of the fields of the encoded records, the fields of a null record are null
+    pub(crate) fn decode(&self, records: &[Option<&[u8]>]) -> Result<Vec<ArrayRef>> {
+        let rows = records
+            .iter()
+            .map(|record| match record {
+                Some(data) => from_avro_datum(&self.avro_schema, &mut &data[..], None)
+                    .map_err(|e| Error::InternalError(format!("invalid Avro record: {}", e))),
+                None => Ok(Value::Null),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let values = rows.iter().map(|row| field(row, i)).collect::<Vec<_>>();
+                build_array(f.data_type(), &values)
+            })
+            .collect()
+    }
+}
+
+fn avro_err(url: &Url, e: apache_avro::Error) -> Error {
+    Error::InternalError(format!("{}: {}", url.path(), e))
+}
+
+/// the arrow fields of the fields of a record schema, the named types referenced by name are resolved
+fn record_fields(schema: &AvroSchema) -> Result<Vec<Field>> {
+    let resolved =
+        ResolvedSchema::try_from(schema).map_err(|e| Error::InternalError(format!("invalid Avro schema: {}", e)))?;
+    let AvroSchema::Record(record) = schema else {
+        return internal_err!("the Avro schema is not a record: {:?}", schema);
+    };
+
+    record
+        .fields
+        .iter()
+        .map(|field| to_arrow_field(&field.name, &field.schema, resolved.get_names()))
+        .collect()
+}
+
+fn to_arrow_field(name: &str, schema: &AvroSchema, names: &NamesRef) -> Result<Field> {
+    let (data_type, nullable) = match schema {
+        AvroSchema::Null => (DataType::Null, true),
+        AvroSchema::Union(union) => {
+            let branches = union
+                .variants()
+                .iter()
+                .filter(|branch| !matches!(branch, AvroSchema::Null))
+                .collect::<Vec<_>>();
+            match branches[..] {
+                [] => (DataType::Null, true),
+                [branch] => (to_arrow_type(branch, names)?, union.is_nullable()),
+                _ => return internal_err!("Avro union of field {} is not supported: {:?}", name, schema),
+            }
+        }
+        schema => (to_arrow_type(schema, names)?, false),
+    };
+
+    Ok(Field::new(name, data_type, nullable))
+}
+
+fn to_arrow_type(schema: &AvroSchema, names: &NamesRef) -> Result<DataType> {
+    Ok(match schema {
+        AvroSchema::Null => DataType::Null,
+        AvroSchema::Boolean => DataType::Boolean,
+        AvroSchema::Int => DataType::Int32,
+        AvroSchema::Long => DataType::Int64,
+        AvroSchema::Float => DataType::Float32,
+        AvroSchema::Double => DataType::Float64,
+        AvroSchema::Bytes => DataType::Binary,
+        AvroSchema::String | AvroSchema::Enum(_) | AvroSchema::Uuid => DataType::Utf8,
+        AvroSchema::Fixed(fixed) => DataType::FixedSizeBinary(fixed.size as i32),
+        AvroSchema::Record(record) => DataType::Struct(
+            record
+                .fields
+                .iter()
+                .map(|field| to_arrow_field(&field.name, &field.schema, names))
+                .collect::<Result<Fields>>()?,
+        ),
+        AvroSchema::Array(array) => DataType::List(Arc::new(to_arrow_field("item", &array.items, names)?)),
+        AvroSchema::Map(map) => {
+            let entries = Fields::from(vec![
+                Field::new("key", DataType::Utf8, false),
+                to_arrow_field("value", &map.types, names)?,
+            ]);
+            DataType::Map(Arc::new(Field::new("entries", DataType::Struct(entries), false)), false)
+        }
+        AvroSchema::Union(_) => to_arrow_field("", schema, names)?.data_type().clone(),
+        AvroSchema::Decimal(decimal) => DataType::Decimal128(decimal.precision as u8, decimal.scale as i8),
+        AvroSchema::Date => DataType::Date32,
+        AvroSchema::TimeMillis => DataType::Time32(TimeUnit::Millisecond),
+        AvroSchema::TimeMicros => DataType::Time64(TimeUnit::Microsecond),
+        AvroSchema::TimestampMillis => DataType::Timestamp(TimeUnit::Millisecond, Some(UTC.into())),
+        AvroSchema::TimestampMicros => DataType::Timestamp(TimeUnit::Microsecond, Some(UTC.into())),
+        AvroSchema::TimestampNanos => DataType::Timestamp(TimeUnit::Nanosecond, Some(UTC.into())),
+        AvroSchema::LocalTimestampMillis => DataType::Timestamp(TimeUnit::Millisecond, None),
+        AvroSchema::LocalTimestampMicros => DataType::Timestamp(TimeUnit::Microsecond, None),
+        AvroSchema::LocalTimestampNanos => DataType::Timestamp(TimeUnit::Nanosecond, None),
+        AvroSchema::Ref { name } => match names.get(name) {
+            Some(schema) => to_arrow_type(schema, names)?,
+            None => return internal_err!("Avro named type {} is not defined", name),
+        },
+        schema => return internal_err!("Avro type {:?} is not supported", schema),
+    })
+}
+
+/// the value of a union is the value of its branch
+fn unwrap(value: &Value) -> &Value {
+    match value {
+        Value::Union(_, value) => unwrap(value),
+        value => value,
+    }
+}
+
+/// the i-th field of a record, the fields of a null record are null
+fn field(value: &Value, i: usize) -> &Value {
+    match unwrap(value) {
+        Value::Record(fields) => &fields[i].1,
+        _ => &Value::Null,
+    }
+}
+
+macro_rules! primitive_array {
+    ($values:expr, $array:ty, $($variant:ident)|+) => {
+        $values
+            .iter()
+            .map(|value| match unwrap(value) {
+                $(Value::$variant(v))|+ => Some(*v),
+                _ => None,
+            })
+            .collect::<$array>()
+    };
+}
+
+/// build the array of `data_type` from the decoded values, a value that does not match the type is null
+fn build_array(data_type: &DataType, values: &[&Value]) -> Result<ArrayRef> {
+    let nulls = || {
+        let nulls = NullBuffer::from(
+            values
+                .iter()
+                .map(|v| !matches!(unwrap(v), Value::Null))
+                .collect::<Vec<_>>(),
+        );
+        Some(nulls).filter(|nulls| nulls.null_count() > 0)
+    };
+
+    Ok(match data_type {
+        DataType::Null => Arc::new(NullArray::new(values.len())),
+        DataType::Boolean => Arc::new(primitive_array!(values, BooleanArray, Boolean)),
+        DataType::Int32 => Arc::new(primitive_array!(values, Int32Array, Int)),
+        DataType::Int64 => Arc::new(primitive_array!(values, Int64Array, Long)),
+        DataType::Float32 => Arc::new(primitive_array!(values, Float32Array, Float)),
+        DataType::Float64 => Arc::new(primitive_array!(values, Float64Array, Double)),
+        DataType::Date32 => Arc::new(primitive_array!(values, Date32Array, Date)),
+        DataType::Time32(_) => Arc::new(primitive_array!(values, Time32MillisecondArray, TimeMillis)),
+        DataType::Time64(_) => Arc::new(primitive_array!(values, Time64MicrosecondArray, TimeMicros)),
+        DataType::Timestamp(TimeUnit::Millisecond, tz) => Arc::new(
+            primitive_array!(
+                values,
+                TimestampMillisecondArray,
+                TimestampMillis | LocalTimestampMillis
+            )
+            .with_timezone_opt(tz.clone()),
+        ),
+        DataType::Timestamp(TimeUnit::Microsecond, tz) => Arc::new(
+            primitive_array!(
+                values,
+                TimestampMicrosecondArray,
+                TimestampMicros | LocalTimestampMicros
+            )
+            .with_timezone_opt(tz.clone()),
+        ),
+        DataType::Timestamp(_, tz) => Arc::new(
+            primitive_array!(values, TimestampNanosecondArray, TimestampNanos | LocalTimestampNanos)
+                .with_timezone_opt(tz.clone()),
+        ),
+        DataType::Decimal128(precision, scale) => Arc::new(
+            values
+                .iter()
+                .map(|value| match unwrap(value) {
+                    Value::Decimal(decimal) => <Vec<u8>>::try_from(decimal).ok().and_then(|v| unscaled(&v)),
+                    _ => None,
+                })
+                .collect::<Decimal128Array>()
+                .with_precision_and_scale(*precision, *scale)?,
+        ),
+        DataType::Utf8 => Arc::new(
+            values
+                .iter()
+                .map(|value| match unwrap(value) {
+                    Value::String(v) | Value::Enum(_, v) => Some(v.clone()),
+                    Value::Uuid(v) => Some(v.to_string()),
+                    _ => None,
+                })
+                .collect::<StringArray>(),
+        ),
+        DataType::Binary => Arc::new(values.iter().map(|value| bytes(value)).collect::<BinaryArray>()),
+        DataType::FixedSizeBinary(size) => Arc::new(FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+            values.iter().map(|value| bytes(value)),
+            *size,
+        )?),
+        DataType::Struct(fields) => {
+            let columns = fields
+                .iter()
+                .enumerate()
+                .map(|(i, f)| {
+                    let children = values.iter().map(|value| field(value, i)).collect::<Vec<_>>();
+                    build_array(f.data_type(), &children)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Arc::new(StructArray::try_new(fields.clone(), columns, nulls())?)
+        }
+        DataType::List(item) => {
+            let mut children = vec![];
+            let offsets = OffsetBuffer::from_lengths(values.iter().map(|value| match unwrap(value) {
+                Value::Array(items) => {
+                    children.extend(items);
+                    items.len()
+                }
+                _ => 0,
+            }));
+            let items = build_array(item.data_type(), &children)?;
+
+            Arc::new(ListArray::try_new(item.clone(), offsets, items, nulls())?)
+        }
+        DataType::Map(entries, _) => {
+            let DataType::Struct(fields) = entries.data_type() else {
+                return internal_err!("invalid map type {}", data_type);
+            };
+            let (mut keys, mut children) = (vec![], vec![]);
+            let offsets = OffsetBuffer::from_lengths(values.iter().map(|value| match unwrap(value) {
+                Value::Map(map) => {
+                    for (key, value) in map {
+                        keys.push(key.as_str());
+                        children.push(value);
+                    }
+                    map.len()
+                }
+                _ => 0,
+            }));
+            let columns = vec![
+                Arc::new(StringArray::from(keys)) as ArrayRef,
+                build_array(fields[1].data_type(), &children)?,
+            ];
+            let entries_array = StructArray::try_new(fields.clone(), columns, None)?;
+
+            Arc::new(MapArray::try_new(
+                entries.clone(),
+                offsets,
+                entries_array,
+                nulls(),
+                false,
+            )?)
+        }
+        _ => return internal_err!("unsupported Avro column type: {}", data_type),
+    })
+}
+
+fn bytes(value: &Value) -> Option<&[u8]> {
+    match unwrap(value) {
+        Value::Bytes(v) | Value::Fixed(_, v) => Some(v.as_slice()),
+        _ => None,
+    }
+}
+
+/// the unscaled value of a decimal, the big-endian two's complement bytes
+fn unscaled(bytes: &[u8]) -> Option<i128> {
+    if bytes.len() > 16 {
+        return None;
+    }
+    let negative = bytes.first().is_some_and(|b| b & 0x80 != 0);
+    let mut buf = [if negative { 0xff } else { 0 }; 16];
+    buf[16 - bytes.len()..].copy_from_slice(bytes);
+
+    Some(i128::from_be_bytes(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+
+    use apache_avro::{Codec, Decimal, Writer};
+
+    use super::*;
+    use crate::test_utils::assert_batch_eq;
+
+    const SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "Event",
+        "namespace": "com.example",
+        "fields": [
+            {"name": "id", "type": "long"},
+            {"name": "name", "type": ["null", "string"]},
+            {"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["A", "B"]}},
+            {"name": "tags", "type": {"type": "array", "items": "string"}},
+            {"name": "attrs", "type": {"type": "map", "values": "int"}},
+            {"name": "user", "type": ["null", {"type": "record", "name": "User", "fields": [{"name": "age", "type": "int"}]}]},
+            {"name": "amount", "type": {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}},
+            {"name": "ts", "type": {"type": "long", "logicalType": "timestamp-millis"}},
+            {"name": "day", "type": {"type": "int", "logicalType": "date"}}
+        ]
+    }"#;
+
+    fn rows() -> Vec<Value> {
+        let record = |fields: Vec<(&str, Value)>| {
+            Value::Record(
+                fields
+                    .into_iter()
+                    .map(|(name, value)| (name.to_owned(), value))
+                    .collect(),
+            )
+        };
+        vec![
+            record(vec![
+                ("id", Value::Long(1)),
+                ("name", Value::Union(1, Box::new(Value::String("x".to_owned())))),
+                ("kind", Value::Enum(1, "B".to_owned())),
+                (
+                    "tags",
+                    Value::Array(vec![Value::String("a".to_owned()), Value::String("b".to_owned())]),
+                ),
+                ("attrs", Value::Map(HashMap::from([("k".to_owned(), Value::Int(7))]))),
+                ("user", Value::Union(1, Box::new(record(vec![("age", Value::Int(30))])))),
+                ("amount", Value::Decimal(Decimal::from(vec![0x30, 0x39]))),
+                ("ts", Value::TimestampMillis(0)),
+                ("day", Value::Date(1)),
+            ]),
+            record(vec![
+                ("id", Value::Long(2)),
+                ("name", Value::Union(0, Box::new(Value::Null))),
+                ("kind", Value::Enum(0, "A".to_owned())),
+                ("tags", Value::Array(vec![])),
+                ("attrs", Value::Map(HashMap::new())),
+                ("user", Value::Union(0, Box::new(Value::Null))),
+                ("amount", Value::Decimal(Decimal::from(vec![0xff]))),
+                ("ts", Value::TimestampMillis(1000)),
+                ("day", Value::Date(0)),
+            ]),
+        ]
+    }
+
+    /// write an object container file of the rows
+    fn write_avro(name: &str, codec: Codec) -> Result<String> {
+        let schema = AvroSchema::parse_str(SCHEMA).unwrap();
+        let mut writer = Writer::with_codec(&schema, vec![], codec);
+        for row in rows() {
+            writer.append(row).unwrap();
+        }
+
+        let path = std::env::temp_dir().join(name);
+        fs::write(&path, writer.into_inner().unwrap())?;
+        Ok(path.to_str().unwrap().to_owned())
+    }
+
+    #[test]
+    fn test_avro_schema() -> Result<()> {
+        let source = read_avro(write_avro("qurious_test_avro_schema.avro", Codec::Null)?.as_str())?;
+        let schema = source.schema();
+
+        assert_eq!(schema.field_with_name("id")?, &Field::new("id", DataType::Int64, false));
+        assert_eq!(
+            schema.field_with_name("name")?,
+            &Field::new("name", DataType::Utf8, true)
+        );
+        assert_eq!(schema.field_with_name("kind")?.data_type(), &DataType::Utf8);
+        assert_eq!(
+            schema.field_with_name("tags")?.data_type(),
+            &DataType::List(Arc::new(Field::new("item", DataType::Utf8, false)))
+        );
+        assert!(matches!(
+            schema.field_with_name("attrs")?.data_type(),
+            DataType::Map(..)
+        ));
+        assert_eq!(
+            schema.field_with_name("user")?,
+            &Field::new(
+                "user",
+                DataType::Struct(Fields::from(vec![Field::new("age", DataType::Int32, false)])),
+                true
+            )
+        );
+        assert_eq!(
+            schema.field_with_name("amount")?.data_type(),
+            &DataType::Decimal128(10, 2)
+        );
+        assert_eq!(
+            schema.field_with_name("ts")?.data_type(),
+            &DataType::Timestamp(TimeUnit::Millisecond, Some(UTC.into()))
+        );
+        assert_eq!(schema.field_with_name("day")?.data_type(), &DataType::Date32);
+
+        assert_batch_eq(
+            &source.scan(None, &[])?,
+            vec![
+                "+----+------+------+--------+--------+-----------+--------+----------------------+------------+",
+                "| id | name | kind | tags   | attrs  | user      | amount | ts                   | day        |",
+                "+----+------+------+--------+--------+-----------+--------+----------------------+------------+",
+                "| 1  | x    | B    | [a, b] | {k: 7} | {age: 30} | 123.45 | 1970-01-01T00:00:00Z | 1970-01-02 |",
+                "| 2  |      | A    | []     | {}     |           | -0.01  | 1970-01-01T00:00:01Z | 1970-01-01 |",
+                "+----+------+------+--------+--------+-----------+--------+----------------------+------------+",
+            ],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_avro_projection() -> Result<()> {
+        let path = write_avro("qurious_test_read_avro_projection.avro", Codec::Deflate)?;
+        let batches = read_avro(path.as_str())?.scan(Some(vec!["user".to_owned(), "id".to_owned()]), &[])?;
+
+        assert_batch_eq(
+            &batches,
+            vec![
+                "+-----------+----+",
+                "| user      | id |",
+                "+-----------+----+",
+                "| {age: 30} | 1  |",
+                "|           | 2  |",
+                "+-----------+----+",
+            ],
+        );
+        assert_batch_eq(
+            &read_avro(path.as_str())?.scan(Some(vec!["day".to_owned()]), &[])?,
+            vec![
+                "+------------+",
+                "| day        |",
+                "+------------+",
+                "| 1970-01-02 |",
+                "| 1970-01-01 |",
+                "+------------+",
+            ],
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use std::fs;
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+use arrow::datatypes::SchemaRef;
+use dashmap::DashMap;
+use parquet::arrow::arrow_reader::ArrowReaderMetadata;
+use url::Url;
+
+use super::object_store::{get_store, to_file_path};
+use crate::error::Result;
+
+/// The metadata of the data files kept across queries: the objects under the prefixes of the object stores, the
+/// footers of the parquet files and the schemas inferred from the files. The entries are kept until they are
+/// invalidated, except that the footer or the schema of a local file is read again when the size or the
+/// modification time of the file changed. The listings of local directories are not cached.
+#[derive(Debug, Default)]
+pub struct FileMetadataCache {
+    /// the objects under a prefix, by the URL of the prefix
+    listings: DashMap<String, Vec<Url>>,
+    /// the footer of a parquet file with its page index, by the URL of the file
+    parquet_metadata: DashMap<String, (Option<FileVersion>, ArrowReaderMetadata)>,
+    /// the schema of a file, by the URL of the file and the options the schema is inferred with
+    schemas: DashMap<(String, String), (Option<FileVersion>, SchemaRef)>,
+}
+
+/// the size and the modification time of a local file
+type FileVersion = (u64, SystemTime);
+
+impl FileMetadataCache {
+    /// the objects under the prefix in its store, listed again once invalidated
+    pub fn list(&self, prefix: &Url) -> Result<Vec<Url>> {
+        if let Some(urls) = self.listings.get(prefix.as_str()) {
+            return Ok(urls.clone());
+        }
+        let urls = get_store(prefix)?.list(prefix)?;
+        self.listings.insert(prefix.to_string(), urls.clone());
+
+        Ok(urls)
+    }
+
+    /// the footer of the parquet file, read by `load` if it is not cached
+    pub fn parquet_metadata<F>(&self, url: &Url, load: F) -> Result<ArrowReaderMetadata>
+    where
+        F: FnOnce() -> Result<ArrowReaderMetadata>,
+    {
+        let version = file_version(url);
+        if let Some(entry) = self.parquet_metadata.get(url.as_str()) {
+            if is_fresh(url, &entry.0, &version) {
+                return Ok(entry.1.clone());
+            }
+        }
+        let metadata = load()?;
+        self.parquet_metadata
+            .insert(url.to_string(), (version, metadata.clone()));
+
+        Ok(metadata)
+    }
+
+    /// the schema of the file inferred with the options, inferred by `infer` if it is not cached
+    pub fn schema<F>(&self, url: &Url, options: String, infer: F) -> Result<SchemaRef>
+    where
+        F: FnOnce() -> Result<SchemaRef>,
+    {
+        let version = file_version(url);
+        let key = (url.to_string(), options);
+        if let Some(entry) = self.schemas.get(&key) {
+            if is_fresh(url, &entry.0, &version) {
+                return Ok(entry.1.clone());
+            }
+        }
+        let schema = infer()?;
+        self.schemas.insert(key, (version, schema.clone()));
+
+        Ok(schema)
+    }
+
+    /// Remove the metadata of the files under the path, a URL or a prefix of URLs, and the listings of the prefixes
+    /// the path is under. The files and the objects changed since they were cached are read again by the next query.
+    pub fn invalidate(&self, path: &str) {
+        self.listings
+            .retain(|prefix, _| !prefix.starts_with(path) && !path.starts_with(prefix.as_str()));
+        self.parquet_metadata.retain(|url, _| !url.starts_with(path));
+        self.schemas.retain(|(url, _), _| !url.starts_with(path));
+    }
+
+    /// remove all the cached metadata
+    pub fn clear(&self) {
+        self.listings.clear();
+        self.parquet_metadata.clear();
+        self.schemas.clear();
+    }
+}
+
+/// the version of a local file, an object of another store is unversioned
+fn file_version(url: &Url) -> Option<FileVersion> {
+    if url.scheme() != "file" {
+        return None;
+    }
+    let metadata = fs::metadata(to_file_path(url).ok()?).ok()?;
+
+    Some((metadata.len(), metadata.modified().ok()?))
+}
+
+/// whether the entry cached for the version of the file is the entry of its current version, a local file whose
+/// version is unknown is read again
+fn is_fresh(url: &Url, cached: &Option<FileVersion>, version: &Option<FileVersion>) -> bool {
+    cached == version && (version.is_some() || url.scheme() != "file")
+}
+
+/// the cache of the metadata of the data files, shared by the sessions of the process
+pub fn file_metadata_cache() -> &'static FileMetadataCache {
+    static CACHE: OnceLock<FileMetadataCache> = OnceLock::new();
+    CACHE.get_or_init(FileMetadataCache::default)
+}
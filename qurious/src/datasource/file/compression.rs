@@ -0,0 +1,83 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// The compression of a text file, detected from the extension of the file or given as an option. A compressed
+/// file is decompressed as it is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileCompression {
+    Uncompressed,
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl FileCompression {
+    /// the compression of the file with the extension, like `data.csv.gz`
+    pub fn from_path(path: &str) -> Self {
+        match path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).as_deref() {
+            Some("gz" | "gzip") => FileCompression::Gzip,
+            Some("zst" | "zstd") => FileCompression::Zstd,
+            Some("bz2") => FileCompression::Bzip2,
+            _ => FileCompression::Uncompressed,
+        }
+    }
+
+    /// the reader of the decompressed bytes of the reader
+    pub fn decoder<R: Read + 'static>(self, reader: R) -> Result<Box<dyn Read>> {
+        Ok(match self {
+            FileCompression::Uncompressed => Box::new(reader),
+            // the members of a gzip file are concatenated
+            FileCompression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(BufReader::new(reader))),
+            FileCompression::Zstd => Box::new(zstd::stream::read::Decoder::new(reader)?),
+            // the streams of a bzip2 file are concatenated
+            FileCompression::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+        })
+    }
+
+    /// write the file compressed, the bytes written by `write` are compressed as they are written. The bytes
+    /// appended to a file are a new gzip member, zstd frame or bzip2 stream, read back after the bytes before them.
+    pub fn write_file<F>(self, file: File, write: F) -> Result<()>
+    where
+        F: FnOnce(&mut dyn Write) -> Result<()>,
+    {
+        let mut file = BufWriter::new(file);
+        match self {
+            FileCompression::Uncompressed => write(&mut file)?,
+            FileCompression::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(&mut file, flate2::Compression::default());
+                write(&mut encoder)?;
+                encoder.finish()?;
+            }
+            FileCompression::Zstd => {
+                let mut encoder = zstd::stream::write::Encoder::new(&mut file, 0)?;
+                write(&mut encoder)?;
+                encoder.finish()?;
+            }
+            FileCompression::Bzip2 => {
+                let mut encoder = bzip2::write::BzEncoder::new(&mut file, bzip2::Compression::default());
+                write(&mut encoder)?;
+                encoder.finish()?;
+            }
+        }
+        file.flush()?;
+
+        Ok(())
+    }
+}
+
+impl FromStr for FileCompression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "none" | "uncompressed" => Ok(FileCompression::Uncompressed),
+            "gzip" | "gz" => Ok(FileCompression::Gzip),
+            "zstd" | "zst" => Ok(FileCompression::Zstd),
+            "bzip2" | "bz2" => Ok(FileCompression::Bzip2),
+            _ => Err(Error::InternalError(format!("unknown compression {}", s))),
+        }
+    }
+}
@@ -1,23 +1,36 @@
-use std::fs::File;
-use std::io::Seek;
+use std::fs::{File, OpenOptions};
 use std::sync::Arc;
+use url::Url;
 
+use arrow::array::RecordBatch;
 use arrow::csv::reader::Format;
-use arrow::csv::ReaderBuilder;
+use arrow::csv::{ReaderBuilder, WriterBuilder};
+use arrow::datatypes::SchemaRef;
+use regex::Regex;
 
 use crate::arrow_err;
-use crate::datasource::memory::MemoryTable;
 use crate::error::{Error, Result};
+use crate::logical::expr::LogicalExpr;
+use crate::physical::plan::PhysicalPlan;
 use crate::provider::table::TableProvider;
 
+use super::cache::file_metadata_cache;
+use super::compression::FileCompression;
+use super::object_store::{get_store, to_file_path, ObjectReader, ObjectStore};
 use super::DataFilePath;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CsvReadOptions {
     pub has_header: bool,
     pub delimiter: u8,
     pub quote: Option<u8>,
     pub escape: Option<u8>,
+    /// the values read as NULL, by default only an empty field of a non-string column is NULL
+    pub null_values: Vec<String>,
+    /// the number of rows the types of the columns are inferred from, `None` reads the whole file
+    pub schema_infer_max_records: Option<usize>,
+    /// the compression of the file, `None` detects it from the extension of the file
+    pub compression: Option<FileCompression>,
 }
 
 impl Default for CsvReadOptions {
@@ -27,56 +40,260 @@ impl Default for CsvReadOptions {
             delimiter: b',',
             quote: None,
             escape: None,
+            null_values: vec![],
+            schema_infer_max_records: Some(1000),
+            compression: None,
         }
     }
 }
 
-pub fn read_csv<T: DataFilePath>(path: T, options: CsvReadOptions) -> Result<Arc<dyn TableProvider>> {
-    let url = path.to_url()?;
+impl CsvReadOptions {
+    fn format(&self) -> Result<Format> {
+        let mut format = Format::default()
+            .with_header(self.has_header)
+            .with_delimiter(self.delimiter);
+
+        if let Some(quote) = self.quote {
+            format = format.with_quote(quote);
+        }
+        if let Some(escape) = self.escape {
+            format = format.with_escape(escape);
+        }
+        if !self.null_values.is_empty() {
+            let values = self.null_values.iter().map(|v| regex::escape(v)).collect::<Vec<_>>();
+            let null_regex = Regex::new(&format!("^(?:{})$", values.join("|")))
+                .map_err(|e| Error::InternalError(format!("invalid null values: {}", e)))?;
+            format = format.with_null_regex(null_regex);
+        }
+
+        Ok(format)
+    }
+}
+
+/// A csv file, the schema is inferred when the table is created and the file is read again by each scan. The rows
+/// inserted into a local file are appended to it.
+#[derive(Debug)]
+pub struct CsvTable {
+    url: Url,
+    schema: SchemaRef,
+    options: CsvReadOptions,
+    compression: FileCompression,
+    store: Arc<dyn ObjectStore>,
+}
+
+impl TableProvider for CsvTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
 
-    match url.scheme() {
-        "file" => {
-            // FIXME this may not ok when csv file is too big to read into memory
-            let mut file = File::open(url.path()).map_err(|e| Error::InternalError(e.to_string()))?;
+    fn scan(&self, projection: Option<Vec<String>>, _filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        let file = self
+            .compression
+            .decoder(ObjectReader::try_new(self.store.clone(), &self.url)?)?;
+        let mut builder = ReaderBuilder::new(self.schema.clone()).with_format(self.options.format()?);
+        if let Some(projection) = projection {
+            let indices = projection
+                .iter()
+                .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+                .collect::<Result<Vec<_>>>()?;
+            builder = builder.with_projection(indices);
+        }
+
+        builder
+            .build(file)
+            .and_then(|reader| reader.collect())
+            .map_err(|e| arrow_err!(e))
+    }
 
-            let mut format = Format::default()
-                .with_header(options.has_header)
-                .with_delimiter(options.delimiter);
+    fn insert(&self, input: Arc<dyn PhysicalPlan>) -> Result<u64> {
+        let batches = input
+            .execute()?
+            .into_iter()
+            .map(|batch| RecordBatch::try_new(self.schema.clone(), batch.columns().to_vec()).map_err(|e| arrow_err!(e)))
+            .collect::<Result<Vec<_>>>()?;
 
-            if let Some(quote) = options.quote {
-                format = format.with_quote(quote);
+        let path = to_file_path(&self.url)?;
+        let file = OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .map_err(|e| Error::InternalError(format!("{}: {}", path.display(), e)))?;
+        // the header is only written to an empty file
+        let has_header = self.options.has_header && file.metadata()?.len() == 0;
+        self.compression.write_file(file, |file| {
+            let mut builder = WriterBuilder::new()
+                .with_header(has_header)
+                .with_delimiter(self.options.delimiter);
+            if let Some(quote) = self.options.quote {
+                builder = builder.with_quote(quote);
+            }
+            if let Some(escape) = self.options.escape {
+                builder = builder.with_escape(escape);
             }
-            if let Some(escape) = options.escape {
-                format = format.with_escape(escape);
+            if let Some(null) = self.options.null_values.first() {
+                builder = builder.with_null(null.clone());
             }
+            let mut writer = builder.build(file);
+            batches
+                .iter()
+                .try_for_each(|batch| writer.write(batch))
+                .map_err(|e| arrow_err!(e))
+        })?;
 
-            // max records set 2 means we only read the first 2 records to infer the schema
-            // first line is header
-            // second line is data to infer the data type
-            let (schema, _) = format.infer_schema(&mut file, None).map_err(|e| arrow_err!(e))?;
+        Ok(batches.iter().map(|batch| batch.num_rows() as u64).sum())
+    }
+}
 
-            // rewind the file to the beginning because the schema inference
-            file.rewind().unwrap();
+pub fn read_csv<T: DataFilePath>(path: T, options: CsvReadOptions) -> Result<Arc<dyn TableProvider>> {
+    let url = path.to_url()?;
+    let store = get_store(&url)?;
 
-            let schema = Arc::new(schema);
+    let format = options.format()?;
+    let compression = options
+        .compression
+        .unwrap_or_else(|| FileCompression::from_path(url.path()));
+    let schema = file_metadata_cache().schema(&url, format!("csv {:?}", options), || {
+        let mut file = compression.decoder(ObjectReader::try_new(store.clone(), &url)?)?;
+        let (schema, _) = format
+            .infer_schema(&mut file, options.schema_infer_max_records)
+            .map_err(|e| arrow_err!(e))?;
+        Ok(Arc::new(schema))
+    })?;
 
-            ReaderBuilder::new(schema.clone())
-                .with_format(format)
-                .build(file)
-                .and_then(|reader| reader.into_iter().collect())
-                .map_err(|e| arrow_err!(e))
-                .and_then(|data| MemoryTable::try_new(schema, data).map(|v| Arc::new(v) as Arc<dyn TableProvider>))
+    Ok(Arc::new(CsvTable {
+        url,
+        schema,
+        options,
+        compression,
+        store,
+    }))
+}
+
+#[derive(Debug)]
+pub struct CsvWriteOptions {
+    pub has_header: bool,
+    pub delimiter: u8,
+    pub compression: FileCompression,
+}
+
+impl Default for CsvWriteOptions {
+    fn default() -> Self {
+        Self {
+            has_header: true,
+            delimiter: b',',
+            compression: FileCompression::Uncompressed,
         }
-        _ => unimplemented!(),
     }
 }
 
+/// write the batches to the file, the file is created or truncated
+pub fn write_csv(path: &str, batches: &[RecordBatch], options: CsvWriteOptions) -> Result<()> {
+    let file = File::create(path)?;
+    options.compression.write_file(file, |file| {
+        let mut writer = WriterBuilder::new()
+            .with_header(options.has_header)
+            .with_delimiter(options.delimiter)
+            .build(file);
+
+        batches
+            .iter()
+            .try_for_each(|batch| writer.write(batch))
+            .map_err(|e| arrow_err!(e))
+    })
+}
+
 #[cfg(test)]
 mod tests {
+    use arrow::datatypes::DataType;
     use arrow::util;
+    use std::io::Write;
+
+    use crate::test_utils::assert_batch_eq;
 
     use super::*;
 
+    #[test]
+    fn test_write_csv() {
+        let source = read_csv("tests/testdata/file/case1.csv", CsvReadOptions::default()).unwrap();
+        let batches = source.scan(None, &[]).unwrap();
+        let path = std::env::temp_dir().join("qurious_test_write_csv.csv");
+        let path = path.to_str().unwrap();
+
+        let options = CsvWriteOptions {
+            delimiter: b'|',
+            ..Default::default()
+        };
+        write_csv(path, &batches, options).unwrap();
+
+        let options = CsvReadOptions {
+            delimiter: b'|',
+            ..Default::default()
+        };
+        let written = read_csv(path, options).unwrap().scan(None, &[]).unwrap();
+        assert_eq!(written, batches);
+    }
+
+    #[test]
+    fn test_read_csv_options() -> Result<()> {
+        let path = std::env::temp_dir().join("qurious_test_read_csv_options.csv");
+        std::fs::write(&path, "id;name;score\n1;'a;b';NA\n2;NA;2.5\n3;c;-\n")?;
+
+        let options = CsvReadOptions {
+            delimiter: b';',
+            quote: Some(b'\''),
+            null_values: vec!["NA".to_owned(), "-".to_owned()],
+            ..Default::default()
+        };
+        let source = read_csv(path.to_str().unwrap(), options)?;
+        let schema = source.schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(2).data_type(), &DataType::Float64);
+
+        let batches = source.scan(Some(vec!["name".to_owned(), "score".to_owned()]), &[])?;
+        assert_batch_eq(
+            &batches,
+            vec![
+                "+------+-------+",
+                "| name | score |",
+                "+------+-------+",
+                "| a;b  |       |",
+                "|      | 2.5   |",
+                "| c    |       |",
+                "+------+-------+",
+            ],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_compressed_csv() -> Result<()> {
+        let data = std::fs::read("tests/testdata/file/case1.csv")?;
+        let expected = read_csv("tests/testdata/file/case1.csv", CsvReadOptions::default())?.scan(None, &[])?;
+
+        let mut gzip = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+        gzip.write_all(&data)?;
+        let gzip_path = std::env::temp_dir().join("qurious_test_read_compressed_csv.csv.gz");
+        std::fs::write(&gzip_path, gzip.finish()?)?;
+        let batches = read_csv(gzip_path.to_str().unwrap(), CsvReadOptions::default())?.scan(None, &[])?;
+        assert_eq!(batches, expected);
+
+        // the compression of a file without its extension is given by the options
+        let zstd_path = std::env::temp_dir().join("qurious_test_read_compressed_csv.data");
+        std::fs::write(&zstd_path, zstd::stream::encode_all(data.as_slice(), 0)?)?;
+        let options = CsvReadOptions {
+            compression: Some(FileCompression::Zstd),
+            ..Default::default()
+        };
+        let batches = read_csv(zstd_path.to_str().unwrap(), options)?.scan(None, &[])?;
+        assert_eq!(batches, expected);
+
+        let source = read_csv("tests/testdata/file/case3.csv.bz2", CsvReadOptions::default())?;
+        let batches = source.scan(Some(vec!["id".to_owned()]), &[])?;
+        assert_eq!(batches.iter().map(|batch| batch.num_rows()).sum::<usize>(), 120);
+
+        Ok(())
+    }
+
     #[test]
     fn test_read_csv() {
         let options = CsvReadOptions::default();
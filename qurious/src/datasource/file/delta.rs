@@ -0,0 +1,424 @@
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, RecordBatch, RecordBatchOptions, StringArray};
+use arrow::compute::cast;
+use arrow::datatypes::{Field, Schema, SchemaRef};
+use arrow::temporal_conversions::timestamp_ms_to_datetime;
+use deltalake::kernel::{Add, ReaderFeatures};
+use deltalake::DeltaTableError;
+use url::Url;
+
+use super::listing::{cast_to_schema, ListingTable};
+use super::parquet::read_parquet;
+use super::runtime;
+use crate::arrow_err;
+use crate::datasource::file::DataFilePath;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::logical::expr::LogicalExpr;
+use crate::provider::table::{RecordBatchStream, TableProvider};
+
+/// whether a table of the reader protocol version 3 may use the feature, the data files are read the same way,
+/// `deltalake` has no variant for `vacuumProtocolCheck`
+fn is_supported_reader_feature(feature: &ReaderFeatures) -> bool {
+    match feature {
+        ReaderFeatures::TimestampWithoutTimezone | ReaderFeatures::V2Checkpoint => true,
+        ReaderFeatures::Other(feature) => feature == "vacuumProtocolCheck",
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DeltaReadOptions {
+    /// the version of the table read, `None` reads the latest version
+    pub version: Option<u64>,
+    /// read the latest version committed at or before the timestamp, in milliseconds since the epoch
+    pub timestamp: Option<i64>,
+}
+
+/// A Delta Lake table, the parquet files of a directory and the `_delta_log` of the commits adding and removing
+/// them. The log is replayed by the `deltalake` crate when the table is created, the data files of the snapshot are
+/// read as a `ListingTable` with the partition values of the log, so a scan skips the files whose partitions the
+/// filters reject. An earlier version of the table is read by its version or the timestamp of its commit.
+#[derive(Debug)]
+pub struct DeltaTable {
+    schema: SchemaRef,
+    version: u64,
+    /// the data files of the snapshot, `None` when the snapshot has no files
+    files: Option<ListingTable>,
+}
+
+impl DeltaTable {
+    /// the version of the snapshot read
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+}
+
+impl TableProvider for DeltaTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        self.scan_stream(projection, filters)?.collect()
+    }
+
+    fn scan_stream(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<RecordBatchStream> {
+        let schema = match &projection {
+            Some(projection) => {
+                let indices = projection
+                    .iter()
+                    .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+                    .collect::<Result<Vec<_>>>()?;
+                Arc::new(self.schema.project(&indices)?)
+            }
+            None => self.schema.clone(),
+        };
+        let Some(files) = &self.files else {
+            return Ok(Box::new(std::iter::empty()));
+        };
+
+        // a column added to the table after the files were written is not in any file
+        let files_schema = files.schema();
+        let projection = schema
+            .fields()
+            .iter()
+            .filter(|field| files_schema.field_with_name(field.name()).is_ok())
+            .map(|field| field.name().clone())
+            .collect();
+        let stream = files.scan_stream(Some(projection), filters)?;
+
+        Ok(Box::new(stream.map(move |batch| {
+            batch.and_then(|batch| cast_to_schema(&schema, batch))
+        })))
+    }
+
+    fn supports_filters_pushdown(&self) -> bool {
+        self.files
+            .as_ref()
+            .is_some_and(|files| files.supports_filters_pushdown())
+    }
+}
+
+/// Read the directory of a Delta Lake table, the latest version or the version of the options.
+pub fn read_delta<T: DataFilePath>(path: T, options: DeltaReadOptions) -> Result<Arc<dyn TableProvider>> {
+    let mut root = path.to_url()?;
+    if !root.path().ends_with('/') {
+        root.set_path(&format!("{}/", root.path()));
+    }
+    if options.version.is_some() && options.timestamp.is_some() {
+        return internal_err!("the version and the timestamp of a delta table can not both be read");
+    }
+    let datetime = options
+        .timestamp
+        .map(|timestamp| {
+            timestamp_ms_to_datetime(timestamp)
+                .ok_or_else(|| Error::InternalError(format!("invalid timestamp {}", timestamp)))
+        })
+        .transpose()?;
+
+    let uri = root.as_str().trim_end_matches('/');
+    let table = runtime()?
+        .block_on(async {
+            match (options.version, datetime) {
+                (Some(version), _) => deltalake::open_table_with_version(uri, version as i64).await,
+                (None, Some(datetime)) => deltalake::open_table_with_ds(uri, datetime.and_utc().to_rfc3339()).await,
+                (None, None) => deltalake::open_table(uri).await,
+            }
+        })
+        .map_err(|e| Error::InternalError(format!("{}: {}", root, e)))?;
+    check_protocol(&table)?;
+
+    let schema = Arc::new(Schema::try_from(table.get_schema().map_err(delta_err)?).map_err(|e| arrow_err!(e))?);
+    let partition_columns = table.metadata().map_err(delta_err)?.partition_columns.clone();
+    let adds = table.snapshot().map_err(delta_err)?.file_actions().map_err(delta_err)?;
+    let files = match adds.is_empty() {
+        true => None,
+        false => Some(read_files(&root, &adds, &schema, &partition_columns)?),
+    };
+
+    Ok(Arc::new(DeltaTable {
+        schema,
+        version: table.version() as u64,
+        files,
+    }))
+}
+
+fn delta_err(e: DeltaTableError) -> Error {
+    Error::InternalError(format!("delta table: {}", e))
+}
+
+/// the tables using the features changing how the data files are read, like column mapping or deletion vectors,
+/// are not supported
+fn check_protocol(table: &deltalake::DeltaTable) -> Result<()> {
+    let mode = table
+        .metadata()
+        .map_err(delta_err)?
+        .configuration
+        .get("delta.columnMapping.mode")
+        .cloned()
+        .flatten();
+    if mode.is_some_and(|mode| mode != "none") {
+        return internal_err!("the column mapping of delta tables is not supported");
+    }
+    let protocol = table.protocol().map_err(delta_err)?;
+    if let Some(feature) = protocol
+        .reader_features
+        .iter()
+        .flatten()
+        .find(|feature| !is_supported_reader_feature(feature))
+    {
+        return internal_err!("the delta table feature {:?} is not supported", feature);
+    }
+
+    Ok(())
+}
+
+/// the data files as a table, with the values of the partition columns of each file
+fn read_files(root: &Url, adds: &[Add], schema: &SchemaRef, partition_columns: &[String]) -> Result<ListingTable> {
+    let files = adds
+        .iter()
+        .map(|add| {
+            let url = root
+                .join(&add.path)
+                .map_err(|e| Error::InternalError(format!("{}: {}", add.path, e)))?;
+            read_parquet(url.as_str())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // the partition values are strings in the log, cast to the types of the partition columns
+    let (fields, columns): (Vec<_>, Vec<_>) = partition_columns
+        .iter()
+        .map(|name| {
+            let field = schema.field_with_name(name).map_err(|e| arrow_err!(e))?;
+            let values = adds
+                .iter()
+                .map(|add| add.partition_values.get(name).cloned().flatten())
+                .collect::<StringArray>();
+            let column = cast(&values, field.data_type()).map_err(|e| arrow_err!(e))?;
+            Ok((field.clone().with_nullable(true), column))
+        })
+        .collect::<Result<Vec<(Field, ArrayRef)>>>()?
+        .into_iter()
+        .unzip();
+    let options = RecordBatchOptions::new().with_row_count(Some(files.len()));
+    let partitions = RecordBatch::try_new_with_options(Arc::new(Schema::new(fields)), columns, &options)
+        .map_err(|e| arrow_err!(e))?;
+
+    ListingTable::try_new(files, partitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_schema;
+    use crate::datasource::file::parquet::write_parquet;
+    use crate::datatypes::operator::Operator;
+    use crate::logical::expr::{column, literal, BinaryExpr};
+    use crate::test_utils::{assert_batch_eq, build_record_i32};
+    use arrow::datatypes::DataType;
+    use std::fs::{self, File};
+    use std::path::Path;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    const SCHEMA: &str = r#"{"type":"struct","fields":[{"name":"a","type":"integer","nullable":true,"metadata":{}},{"name":"country","type":"string","nullable":true,"metadata":{}}]}"#;
+    const SCHEMA_B: &str = r#"{"type":"struct","fields":[{"name":"a","type":"integer","nullable":true,"metadata":{}},{"name":"country","type":"string","nullable":true,"metadata":{}},{"name":"b","type":"long","nullable":true,"metadata":{}}]}"#;
+
+    fn metadata(schema: &str) -> String {
+        serde_json::json!({"metaData": {
+            "id": "test",
+            "format": {"provider": "parquet", "options": {}},
+            "schemaString": schema,
+            "partitionColumns": ["country"],
+            "configuration": {},
+            "createdTime": 0,
+        }})
+        .to_string()
+    }
+
+    fn add(path: &str, country: &str) -> String {
+        serde_json::json!({"add": {
+            "path": path,
+            "partitionValues": {"country": country},
+            "size": 0,
+            "modificationTime": 0,
+            "dataChange": true,
+        }})
+        .to_string()
+    }
+
+    fn write_commit(dir: &Path, version: u64, timestamp: i64, actions: &[String]) -> Result<()> {
+        let commit_info = serde_json::json!({"commitInfo": {"timestamp": timestamp}}).to_string();
+        let lines = std::iter::once(commit_info)
+            .chain(actions.iter().cloned())
+            .collect::<Vec<_>>();
+        let path = dir.join(format!("_delta_log/{:020}.json", version));
+        fs::write(&path, lines.join("\n"))?;
+        // the time a version was committed is the modification time of its commit file
+        File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(UNIX_EPOCH + Duration::from_millis(timestamp as u64))?;
+        Ok(())
+    }
+
+    fn write_file(dir: &Path, path: &str, columns: Vec<Vec<i32>>) -> Result<()> {
+        let schema = match columns.len() {
+            1 => Arc::new(build_schema!(("a", DataType::Int32))),
+            _ => Arc::new(build_schema!(("a", DataType::Int32), ("b", DataType::Int32))),
+        };
+        fs::create_dir_all(dir.join(path).parent().unwrap())?;
+        let batch = build_record_i32(schema.clone(), columns);
+        write_parquet(dir.join(path).to_str().unwrap(), schema, &[batch])
+    }
+
+    /// a table of three commits, the second replaces a file and the third adds the column `b`
+    fn write_table(name: &str) -> Result<std::path::PathBuf> {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("_delta_log"))?;
+        write_file(&dir, "country=US/part-0.parquet", vec![vec![1, 2]])?;
+        write_file(&dir, "country=CN/part-1.parquet", vec![vec![3]])?;
+        write_file(&dir, "country=US/part-2.parquet", vec![vec![4]])?;
+        write_file(&dir, "country=CN/part-3.parquet", vec![vec![5], vec![50]])?;
+
+        let protocol = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#.to_owned();
+        let add0 = add("country=US/part-0.parquet", "US");
+        let add1 = add("country=CN/part-1.parquet", "CN");
+        write_commit(&dir, 0, 1000, &[protocol, metadata(SCHEMA), add0, add1])?;
+        let remove = r#"{"remove":{"path":"country=US/part-0.parquet","dataChange":true}}"#.to_owned();
+        write_commit(&dir, 1, 2000, &[remove, add("country=US/part-2.parquet", "US")])?;
+        write_commit(
+            &dir,
+            2,
+            3000,
+            &[metadata(SCHEMA_B), add("country=CN/part-3.parquet", "CN")],
+        )?;
+
+        Ok(dir)
+    }
+
+    fn scan(source: &Arc<dyn TableProvider>, filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        let projection = source.schema().fields().iter().map(|f| f.name().clone()).collect();
+        source.scan(Some(projection), filters)
+    }
+
+    #[test]
+    fn test_read_delta() -> Result<()> {
+        let dir = write_table("qurious_test_read_delta")?;
+        let path = dir.to_str().unwrap();
+
+        let source = read_delta(path, DeltaReadOptions::default())?;
+        assert_eq!(
+            source.schema().as_ref(),
+            &build_schema!(
+                ("a", DataType::Int32, true),
+                ("country", DataType::Utf8, true),
+                ("b", DataType::Int64, true)
+            )
+        );
+        assert_batch_eq(
+            &scan(&source, &[])?,
+            vec![
+                "+---+---------+----+",
+                "| a | country | b  |",
+                "+---+---------+----+",
+                "| 3 | CN      |    |",
+                "| 5 | CN      | 50 |",
+                "| 4 | US      |    |",
+                "+---+---------+----+",
+            ],
+        );
+
+        // the files of the other partitions are not read
+        let filter = LogicalExpr::BinaryExpr(BinaryExpr::new(column("country"), Operator::Eq, literal("US")));
+        assert_batch_eq(
+            &scan(&source, &[filter])?,
+            vec![
+                "+---+---------+---+",
+                "| a | country | b |",
+                "+---+---------+---+",
+                "| 4 | US      |   |",
+                "+---+---------+---+",
+            ],
+        );
+
+        let expected = vec![
+            "+---+---------+",
+            "| a | country |",
+            "+---+---------+",
+            "| 3 | CN      |",
+            "| 1 | US      |",
+            "| 2 | US      |",
+            "+---+---------+",
+        ];
+        let options = DeltaReadOptions {
+            version: Some(0),
+            ..Default::default()
+        };
+        assert_batch_eq(&scan(&read_delta(path, options)?, &[])?, expected.clone());
+        let options = DeltaReadOptions {
+            timestamp: Some(1999),
+            ..Default::default()
+        };
+        assert_batch_eq(&scan(&read_delta(path, options)?, &[])?, expected);
+
+        let options = DeltaReadOptions {
+            version: Some(3),
+            ..Default::default()
+        };
+        assert!(read_delta(path, options).is_err());
+        assert!(read_delta(dir.join("country=US").to_str().unwrap(), DeltaReadOptions::default()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_delta_checkpoint() -> Result<()> {
+        let dir = write_table("qurious_test_read_delta_checkpoint")?;
+        let path = dir.to_str().unwrap();
+
+        // the checkpoint of the version 1 replaces the commits before it
+        runtime()?
+            .block_on(async {
+                let table = deltalake::open_table_with_version(path, 1).await?;
+                deltalake::checkpoints::create_checkpoint(&table, None).await?;
+                Ok(())
+            })
+            .map_err(delta_err)?;
+        fs::remove_file(dir.join(format!("_delta_log/{:020}.json", 0)))?;
+        fs::remove_file(dir.join(format!("_delta_log/{:020}.json", 1)))?;
+
+        let options = DeltaReadOptions {
+            version: Some(1),
+            ..Default::default()
+        };
+        assert_batch_eq(
+            &scan(&read_delta(path, options)?, &[])?,
+            vec![
+                "+---+---------+",
+                "| a | country |",
+                "+---+---------+",
+                "| 3 | CN      |",
+                "| 4 | US      |",
+                "+---+---------+",
+            ],
+        );
+        assert_eq!(
+            scan(&read_delta(path, DeltaReadOptions::default())?, &[])?
+                .iter()
+                .map(|batch| batch.num_rows())
+                .sum::<usize>(),
+            3
+        );
+        // the commits before the checkpoint were removed
+        let options = DeltaReadOptions {
+            version: Some(0),
+            ..Default::default()
+        };
+        assert!(read_delta(path, options).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,410 @@
+use std::io::Cursor;
+use std::sync::Arc;
+use url::Url;
+
+use arrow::array::{ArrayRef, BooleanArray, Date32Array, Float64Array, Int64Array, RecordBatch, StringArray};
+use arrow::array::{RecordBatchOptions, TimestampMillisecondArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::temporal_conversions::{date32_to_datetime, timestamp_ms_to_datetime};
+use bytes::Bytes;
+use calamine::{open_workbook_auto_from_rs, Data, Reader, Sheets};
+
+use crate::arrow_err;
+use crate::datasource::file::object_store::{get_store, read_object, ObjectStore};
+use crate::datasource::file::DataFilePath;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::logical::expr::LogicalExpr;
+use crate::provider::table::TableProvider;
+
+/// the days between 1899-12-30, the day 0 of a workbook, and 1970-01-01
+const UNIX_EPOCH_DAYS: f64 = 25569.0;
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+#[derive(Debug, Clone)]
+pub struct ExcelReadOptions {
+    /// the name of the sheet read, the first sheet of the workbook by default
+    pub sheet: Option<String>,
+    /// whether the first row of the sheet holds the names of the columns
+    pub has_header: bool,
+    /// the number of rows the types of the columns are inferred from, `None` reads the whole sheet
+    pub schema_infer_max_records: Option<usize>,
+}
+
+impl Default for ExcelReadOptions {
+    fn default() -> Self {
+        Self {
+            sheet: None,
+            has_header: true,
+            schema_infer_max_records: Some(1000),
+        }
+    }
+}
+
+/// A sheet of an Excel workbook read with the `calamine` crate. The types of the columns are inferred from the
+/// cells when the table is created and the sheet is read again by each scan, a cell whose value does not fit the type
+/// of its column is NULL.
+#[derive(Debug)]
+pub struct ExcelTable {
+    url: Url,
+    /// the name of the sheet in the workbook
+    sheet: String,
+    schema: SchemaRef,
+    has_header: bool,
+    store: Arc<dyn ObjectStore>,
+}
+
+impl TableProvider for ExcelTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Option<Vec<String>>, _filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        let rows = read_sheet(&mut open_workbook(self.store.as_ref(), &self.url)?, &self.sheet)?;
+        let rows = &rows[(self.has_header as usize).min(rows.len())..];
+
+        let indices = match projection {
+            Some(projection) => projection
+                .iter()
+                .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+                .collect::<Result<Vec<_>>>()?,
+            None => (0..self.schema.fields().len()).collect(),
+        };
+        let schema = Arc::new(self.schema.project(&indices)?);
+        let columns = indices
+            .iter()
+            .map(|i| {
+                to_array(
+                    rows.iter().map(|row| row.get(*i).unwrap_or(&Cell::Empty)),
+                    self.schema.field(*i),
+                )
+            })
+            .collect();
+        let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+
+        RecordBatch::try_new_with_options(schema, columns, &options)
+            .map(|batch| vec![batch])
+            .map_err(|e| arrow_err!(e))
+    }
+}
+
+pub fn read_excel<T: DataFilePath>(path: T, options: ExcelReadOptions) -> Result<Arc<dyn TableProvider>> {
+    let url = path.to_url()?;
+    let store = get_store(&url)?;
+    let mut workbook = open_workbook(store.as_ref(), &url)?;
+    let sheet_names = workbook.sheet_names();
+    let sheet = match &options.sheet {
+        Some(sheet) => sheet_names.iter().find(|name| *name == sheet),
+        None => sheet_names.first(),
+    };
+    let Some(sheet) = sheet.cloned() else {
+        return internal_err!(
+            "the workbook {} has no sheet {}",
+            url.path(),
+            options.sheet.as_deref().unwrap_or_default()
+        );
+    };
+    let rows = read_sheet(&mut workbook, &sheet)?;
+
+    Ok(Arc::new(ExcelTable {
+        url,
+        sheet,
+        schema: Arc::new(infer_schema(&rows, &options)),
+        has_header: options.has_header,
+        store,
+    }))
+}
+
+/// the value of a cell, a date is the days since the day 0 of the workbook
+#[derive(Debug, Clone, PartialEq)]
+enum Cell {
+    Empty,
+    String(String),
+    Number(f64),
+    Boolean(bool),
+    Date(f64),
+}
+
+impl Cell {
+    fn to_string(&self) -> Option<String> {
+        match self {
+            Cell::Empty => None,
+            Cell::String(s) => Some(s.clone()),
+            Cell::Number(n) => Some(n.to_string()),
+            Cell::Boolean(b) => Some(b.to_string()),
+            Cell::Date(days) if days.fract() == 0.0 => to_days(*days)
+                .and_then(date32_to_datetime)
+                .map(|date| date.date().to_string()),
+            Cell::Date(days) => to_millis(*days)
+                .and_then(timestamp_ms_to_datetime)
+                .map(|datetime| datetime.to_string()),
+        }
+    }
+}
+
+impl From<&Data> for Cell {
+    fn from(data: &Data) -> Self {
+        match data {
+            Data::Empty | Data::Error(_) => Cell::Empty,
+            Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => Cell::String(s.clone()),
+            Data::Int(n) => Cell::Number(*n as f64),
+            Data::Float(n) => Cell::Number(*n),
+            Data::Bool(b) => Cell::Boolean(*b),
+            Data::DateTime(datetime) if datetime.is_duration() => Cell::Number(datetime.as_f64()),
+            // the date of a workbook in the 1904 date system is shifted to the day 0 of the 1900 date system
+            Data::DateTime(datetime) => match datetime.as_datetime() {
+                Some(datetime) => {
+                    Cell::Date(datetime.and_utc().timestamp_millis() as f64 / MILLIS_PER_DAY + UNIX_EPOCH_DAYS)
+                }
+                None => Cell::Empty,
+            },
+        }
+    }
+}
+
+fn open_workbook(store: &dyn ObjectStore, url: &Url) -> Result<Sheets<Cursor<Bytes>>> {
+    open_workbook_auto_from_rs(Cursor::new(read_object(store, url)?))
+        .map_err(|e| Error::InternalError(format!("{} is not an Excel workbook: {}", url.path(), e)))
+}
+
+/// the rows of the cells of the sheet from its first row that is not empty, the first column of a row is the
+/// column `A` of the sheet
+fn read_sheet(workbook: &mut Sheets<Cursor<Bytes>>, sheet: &str) -> Result<Vec<Vec<Cell>>> {
+    let range = workbook
+        .worksheet_range(sheet)
+        .map_err(|e| Error::InternalError(format!("the sheet {}: {}", sheet, e)))?;
+    let first_column = range.start().map(|(_, column)| column as usize).unwrap_or(0);
+
+    Ok(range
+        .rows()
+        .map(|row| {
+            let mut cells = vec![Cell::Empty; first_column];
+            cells.extend(row.iter().map(Cell::from));
+            cells
+        })
+        .collect())
+}
+
+/// the days since 1970-01-01 of the days since the day 0 of a workbook
+fn to_days(days: f64) -> Option<i32> {
+    let days = days.floor() - UNIX_EPOCH_DAYS;
+    (days >= i32::MIN as f64 && days <= i32::MAX as f64).then_some(days as i32)
+}
+
+fn to_millis(days: f64) -> Option<i64> {
+    let millis = ((days - UNIX_EPOCH_DAYS) * MILLIS_PER_DAY).round();
+    (millis >= i64::MIN as f64 && millis <= i64::MAX as f64).then_some(millis as i64)
+}
+
+fn to_int(n: f64) -> Option<i64> {
+    (n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64).then_some(n as i64)
+}
+
+/// The columns of the sheet, named by the first row if the sheet has a header. A column of booleans, integers or
+/// dates has their type, a column of other numbers is `Float64` and any other column is `Utf8`.
+fn infer_schema(rows: &[Vec<Cell>], options: &ExcelReadOptions) -> Schema {
+    let (header, rows) = match (options.has_header, rows.split_first()) {
+        (true, Some((header, rows))) => (header.as_slice(), rows),
+        _ => (&[][..], rows),
+    };
+    let rows = &rows[..options.schema_infer_max_records.unwrap_or(rows.len()).min(rows.len())];
+    let num_columns = rows
+        .iter()
+        .map(|row| row.len())
+        .chain([header.len()])
+        .max()
+        .unwrap_or(0);
+
+    let fields = (0..num_columns).map(|i| {
+        let name = header
+            .get(i)
+            .and_then(|cell| cell.to_string())
+            .unwrap_or_else(|| format!("column_{}", i + 1));
+        let mut cells = rows
+            .iter()
+            .filter_map(|row| row.get(i))
+            .filter(|cell| **cell != Cell::Empty);
+        let data_type = match cells.next() {
+            Some(Cell::Boolean(_)) if cells.all(|cell| matches!(cell, Cell::Boolean(_))) => DataType::Boolean,
+            Some(Cell::Number(n)) => match cells.try_fold(to_int(*n).is_some(), |is_int, cell| match cell {
+                Cell::Number(n) => Some(is_int && to_int(*n).is_some()),
+                _ => None,
+            }) {
+                Some(true) => DataType::Int64,
+                Some(false) => DataType::Float64,
+                None => DataType::Utf8,
+            },
+            Some(Cell::Date(days)) => match cells.try_fold(days.fract() == 0.0, |is_date, cell| match cell {
+                Cell::Date(days) => Some(is_date && days.fract() == 0.0),
+                _ => None,
+            }) {
+                Some(true) => DataType::Date32,
+                Some(false) => DataType::Timestamp(TimeUnit::Millisecond, None),
+                None => DataType::Utf8,
+            },
+            _ => DataType::Utf8,
+        };
+        Field::new(name, data_type, true)
+    });
+
+    Schema::new(fields.collect::<Vec<_>>())
+}
+
+/// the array of the cells of a column with the type of the column
+fn to_array<'a>(cells: impl Iterator<Item = &'a Cell>, field: &Field) -> ArrayRef {
+    match field.data_type() {
+        DataType::Boolean => Arc::new(
+            cells
+                .map(|cell| match cell {
+                    Cell::Boolean(b) => Some(*b),
+                    _ => None,
+                })
+                .collect::<BooleanArray>(),
+        ),
+        DataType::Int64 => Arc::new(
+            cells
+                .map(|cell| match cell {
+                    Cell::Number(n) => to_int(*n),
+                    _ => None,
+                })
+                .collect::<Int64Array>(),
+        ),
+        DataType::Float64 => Arc::new(
+            cells
+                .map(|cell| match cell {
+                    Cell::Number(n) | Cell::Date(n) => Some(*n),
+                    _ => None,
+                })
+                .collect::<Float64Array>(),
+        ),
+        DataType::Date32 => Arc::new(
+            cells
+                .map(|cell| match cell {
+                    Cell::Date(days) | Cell::Number(days) => to_days(*days),
+                    _ => None,
+                })
+                .collect::<Date32Array>(),
+        ),
+        DataType::Timestamp(_, _) => Arc::new(
+            cells
+                .map(|cell| match cell {
+                    Cell::Date(days) | Cell::Number(days) => to_millis(*days),
+                    _ => None,
+                })
+                .collect::<TimestampMillisecondArray>(),
+        ),
+        _ => Arc::new(cells.map(|cell| cell.to_string()).collect::<StringArray>()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::{DataType, TimeUnit};
+    use rust_xlsxwriter::{Format, Formula, Workbook};
+
+    use super::{read_excel, ExcelReadOptions};
+    use crate::error::Result;
+    use crate::test_utils::assert_batch_eq;
+
+    fn write_xlsx(name: &str) -> String {
+        let mut workbook = Workbook::new();
+        let people = workbook.add_worksheet().set_name("people").unwrap();
+        people.write_string(0, 0, "id").unwrap();
+        people.write_string(0, 1, "name").unwrap();
+        people.write_string(0, 2, "active").unwrap();
+        people.write_number(1, 0, 1).unwrap();
+        people.write_string(1, 1, "Alice <A>").unwrap();
+        people.write_boolean(1, 2, true).unwrap();
+        people.write_number(3, 0, 3).unwrap();
+        people.write_string(3, 1, "Carol").unwrap();
+        people.write_boolean(3, 2, false).unwrap();
+        people
+            .write_formula(4, 0, Formula::new("=A4+1").set_result("4"))
+            .unwrap();
+        people.write_string(4, 1, "Bob").unwrap();
+
+        let (decimal, date, datetime) = (
+            Format::new().set_num_format("0.00"),
+            Format::new().set_num_format("yyyy-mm-dd"),
+            Format::new().set_num_format("yyyy-mm-dd hh:mm"),
+        );
+        let scores = workbook.add_worksheet().set_name("Scores & Dates").unwrap();
+        scores.write_number_with_format(1, 1, 1.5, &decimal).unwrap();
+        scores.write_number_with_format(1, 2, 45292, &date).unwrap();
+        scores.write_number_with_format(1, 3, 45292.75, &datetime).unwrap();
+        scores.write_number(1, 4, 7).unwrap();
+        scores.write_number(2, 1, -2).unwrap();
+        scores.write_number_with_format(2, 2, 61, &date).unwrap();
+        scores.write_number_with_format(2, 3, 61.5, &datetime).unwrap();
+        scores.write_string(2, 4, "x").unwrap();
+
+        let path = std::env::temp_dir().join(name);
+        workbook.save(&path).unwrap();
+        path.to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn test_read_excel() -> Result<()> {
+        let path = write_xlsx("qurious_test_read_excel.xlsx");
+
+        let source = read_excel(path.as_str(), ExcelReadOptions::default())?;
+        let schema = source.schema();
+        assert_eq!(schema.field_with_name("id")?.data_type(), &DataType::Int64);
+        assert_eq!(schema.field_with_name("active")?.data_type(), &DataType::Boolean);
+        assert_batch_eq(
+            &source.scan(None, &[])?,
+            vec![
+                "+----+-----------+--------+",
+                "| id | name      | active |",
+                "+----+-----------+--------+",
+                "| 1  | Alice <A> | true   |",
+                "|    |           |        |",
+                "| 3  | Carol     | false  |",
+                "| 4  | Bob       |        |",
+                "+----+-----------+--------+",
+            ],
+        );
+
+        let options = ExcelReadOptions {
+            sheet: Some("Scores & Dates".to_owned()),
+            has_header: false,
+            ..Default::default()
+        };
+        let source = read_excel(path.as_str(), options)?;
+        let schema = source.schema();
+        assert_eq!(schema.field_with_name("column_2")?.data_type(), &DataType::Float64);
+        assert_eq!(schema.field_with_name("column_3")?.data_type(), &DataType::Date32);
+        assert_eq!(
+            schema.field_with_name("column_4")?.data_type(),
+            &DataType::Timestamp(TimeUnit::Millisecond, None)
+        );
+        assert_eq!(schema.field_with_name("column_5")?.data_type(), &DataType::Utf8);
+        assert_batch_eq(
+            &source.scan(
+                Some(vec![
+                    "column_5".to_owned(),
+                    "column_4".to_owned(),
+                    "column_3".to_owned(),
+                    "column_2".to_owned(),
+                ]),
+                &[],
+            )?,
+            vec![
+                "+----------+---------------------+------------+----------+",
+                "| column_5 | column_4            | column_3   | column_2 |",
+                "+----------+---------------------+------------+----------+",
+                "| 7        | 2024-01-01T18:00:00 | 2024-01-01 | 1.5      |",
+                "| x        | 1900-03-01T12:00:00 | 1900-03-01 | -2.0     |",
+                "+----------+---------------------+------------+----------+",
+            ],
+        );
+
+        let options = ExcelReadOptions {
+            sheet: Some("missing".to_owned()),
+            ..Default::default()
+        };
+        assert!(read_excel(path.as_str(), options).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,543 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::RecordBatch;
+use arrow::datatypes::{Field, Schema, SchemaRef};
+use futures::StreamExt;
+use iceberg::arrow::schema_to_arrow_schema;
+use iceberg::expr::{Predicate as IcebergPredicate, Reference};
+use iceberg::io::FileIO;
+use iceberg::spec::Datum;
+use iceberg::table::{StaticTable, Table};
+use iceberg::TableIdent;
+use url::Url;
+
+use super::listing::cast_to_schema;
+use super::object_store::{get_store, read_object};
+use super::parquet::predicate::Predicate;
+use super::runtime;
+use crate::arrow_err;
+use crate::datasource::file::DataFilePath;
+use crate::datatypes::operator::Operator;
+use crate::datatypes::scalar::ScalarValue;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::logical::expr::LogicalExpr;
+use crate::provider::table::{RecordBatchStream, TableProvider};
+
+#[derive(Debug, Clone, Default)]
+pub struct IcebergReadOptions {
+    /// the snapshot of the table read, `None` reads the current snapshot
+    pub snapshot_id: Option<i64>,
+    /// read the latest snapshot committed at or before the timestamp, in milliseconds since the epoch
+    pub timestamp: Option<i64>,
+}
+
+/// An Apache Iceberg table, read with the `iceberg` crate from the `metadata.json` of the table. A scan plans the
+/// data files of the snapshot from its manifests, the filters comparing a column with a literal are pushed to the
+/// scan to skip the data files and the rows they reject.
+#[derive(Debug)]
+pub struct IcebergTable {
+    table: Table,
+    schema: SchemaRef,
+    snapshot_id: Option<i64>,
+}
+
+impl IcebergTable {
+    /// the snapshot of the options of the table at the path, see `read_iceberg`
+    pub fn try_new<T: DataFilePath>(path: T, options: IcebergReadOptions) -> Result<Self> {
+        let location = metadata_location(path.to_url()?)?;
+        let file_io = FileIO::from_path(location.as_str())
+            .and_then(|builder| builder.build())
+            .map_err(iceberg_err)?;
+        // the table is read without a catalog, the identifier is only used in the messages of the errors
+        let ident = TableIdent::from_strs(["qurious", "iceberg"]).map_err(iceberg_err)?;
+        let table = runtime()?
+            .block_on(StaticTable::from_metadata_file(location.as_str(), ident, file_io))
+            .map_err(iceberg_err)?
+            .into_table();
+
+        let metadata = table.metadata();
+        let snapshot = match (options.snapshot_id, options.timestamp) {
+            (Some(_), Some(_)) => {
+                return internal_err!("the snapshot and the timestamp of an iceberg table can not both be read")
+            }
+            (Some(id), None) => match metadata.snapshot_by_id(id) {
+                Some(snapshot) => Some(snapshot),
+                None => return internal_err!("the snapshot {} of the iceberg table does not exist", id),
+            },
+            (None, Some(timestamp)) => {
+                let snapshot = metadata
+                    .snapshots()
+                    .filter(|s| s.timestamp_ms() <= timestamp)
+                    .max_by_key(|s| s.timestamp_ms());
+                match snapshot {
+                    Some(snapshot) => Some(snapshot),
+                    None => {
+                        return internal_err!(
+                            "no snapshot of the iceberg table was committed at or before {}",
+                            timestamp
+                        )
+                    }
+                }
+            }
+            (None, None) => metadata.current_snapshot(),
+        };
+
+        // the schema of the snapshot, or the current schema of the table
+        let schema = match snapshot.and_then(|s| s.schema_id()) {
+            Some(id) => match metadata.schema_by_id(id) {
+                Some(schema) => schema.clone(),
+                None => return internal_err!("the iceberg metadata has no schema {}", id),
+            },
+            None => metadata.current_schema().clone(),
+        };
+        // the field ids of the columns are in the metadata of the fields, the columns are read by name
+        let fields = schema_to_arrow_schema(&schema)
+            .map_err(iceberg_err)?
+            .fields()
+            .iter()
+            .map(|field| field.as_ref().clone().with_metadata(HashMap::new()))
+            .collect::<Vec<Field>>();
+        let snapshot_id = snapshot.map(|s| s.snapshot_id());
+
+        Ok(Self {
+            table,
+            schema: Arc::new(Schema::new(fields)),
+            snapshot_id,
+        })
+    }
+
+    /// the snapshot read, `None` for a table without snapshots
+    pub fn snapshot_id(&self) -> Option<i64> {
+        self.snapshot_id
+    }
+}
+
+impl TableProvider for IcebergTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        self.scan_stream(projection, filters)?.collect()
+    }
+
+    fn scan_stream(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<RecordBatchStream> {
+        let schema = match &projection {
+            Some(projection) => {
+                let indices = projection
+                    .iter()
+                    .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+                    .collect::<Result<Vec<_>>>()?;
+                Arc::new(self.schema.project(&indices)?)
+            }
+            None => self.schema.clone(),
+        };
+        let Some(snapshot_id) = self.snapshot_id else {
+            return Ok(Box::new(std::iter::empty()));
+        };
+
+        let mut scan = self
+            .table
+            .scan()
+            .snapshot_id(snapshot_id)
+            .select(schema.fields().iter().map(|field| field.name().clone()));
+        if let Some(predicate) = Predicate::try_new(filters, &self.schema).and_then(|p| to_iceberg_predicate(&p)) {
+            scan = scan.with_filter(predicate);
+        }
+        let scan = scan.build().map_err(iceberg_err)?;
+        // the batches are read on the runtime as the stream is advanced
+        let runtime = runtime()?;
+        let mut stream = runtime.block_on(scan.to_arrow()).map_err(iceberg_err)?;
+
+        Ok(Box::new(
+            std::iter::from_fn(move || runtime.block_on(stream.next())).map(move |batch| {
+                batch
+                    .map_err(iceberg_err)
+                    .and_then(|batch| cast_to_schema(&schema, batch))
+            }),
+        ))
+    }
+
+    fn supports_filters_pushdown(&self) -> bool {
+        true
+    }
+}
+
+/// Read an Iceberg table, the directory of the table or its `metadata.json` file. The metadata file of a directory
+/// is the version of `metadata/version-hint.text`, or the latest version of the `metadata` directory.
+pub fn read_iceberg<T: DataFilePath>(path: T, options: IcebergReadOptions) -> Result<Arc<dyn TableProvider>> {
+    IcebergTable::try_new(path, options).map(|table| Arc::new(table) as Arc<dyn TableProvider>)
+}
+
+/// the metadata file of the path, the path of a metadata file or the directory of a table
+fn metadata_location(mut url: Url) -> Result<Url> {
+    if url.path().ends_with(".metadata.json") {
+        return Ok(url);
+    }
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    let dir = url
+        .join("metadata/")
+        .map_err(|e| Error::InternalError(format!("{}: {}", url, e)))?;
+    let join = |name: String| {
+        dir.join(&name)
+            .map_err(|e| Error::InternalError(format!("{}: {}", name, e)))
+    };
+
+    // the version hint of the tables of a hadoop catalog
+    let hint = dir
+        .join("version-hint.text")
+        .ok()
+        .and_then(|hint| read_object(get_store(&hint).ok()?.as_ref(), &hint).ok());
+    if let Some(version) = hint.and_then(|hint| String::from_utf8_lossy(&hint).trim().parse::<u64>().ok()) {
+        return join(format!("v{}.metadata.json", version));
+    }
+
+    // the metadata files are `v{version}.metadata.json` or `{version}-{uuid}.metadata.json`
+    let latest = get_store(&dir)?
+        .list(&dir)
+        .map_err(|e| Error::InternalError(format!("{} is not an iceberg table: {}", url, e)))?
+        .into_iter()
+        .filter_map(|location| {
+            let name = location.path_segments()?.next_back()?.to_owned();
+            let version = name
+                .strip_suffix(".metadata.json")?
+                .trim_start_matches('v')
+                .split('-')
+                .next()?
+                .parse::<u64>()
+                .ok()?;
+            Some((version, name))
+        })
+        .max();
+    match latest {
+        Some((_, name)) => join(name),
+        None => internal_err!("{} is not an iceberg table, it has no metadata file", url),
+    }
+}
+
+/// the predicate of the iceberg scan, the comparisons of a cast column are left out
+fn to_iceberg_predicate(predicate: &Predicate) -> Option<IcebergPredicate> {
+    match predicate {
+        Predicate::Compare { column, op, value } if column.cast.is_none() => {
+            let reference = Reference::new(column.name.clone());
+            let datum = match ScalarValue::try_from_array(value, 0).ok()? {
+                ScalarValue::Boolean(Some(v)) => Datum::bool(v),
+                ScalarValue::Int32(Some(v)) => Datum::int(v),
+                ScalarValue::Int64(Some(v)) => Datum::long(v),
+                ScalarValue::Float32(Some(v)) => Datum::float(v),
+                ScalarValue::Float64(Some(v)) => Datum::double(v),
+                ScalarValue::Utf8(Some(v)) => Datum::string(v),
+                ScalarValue::Date32(Some(v)) => Datum::date(v),
+                _ => return None,
+            };
+            Some(match op {
+                Operator::Eq => reference.equal_to(datum),
+                Operator::NotEq => reference.not_equal_to(datum),
+                Operator::Lt => reference.less_than(datum),
+                Operator::LtEq => reference.less_than_or_equal_to(datum),
+                Operator::Gt => reference.greater_than(datum),
+                Operator::GtEq => reference.greater_than_or_equal_to(datum),
+                _ => return None,
+            })
+        }
+        Predicate::IsNull { column, negated } if column.cast.is_none() => {
+            let reference = Reference::new(column.name.clone());
+            match negated {
+                true => Some(reference.is_not_null()),
+                false => Some(reference.is_null()),
+            }
+        }
+        Predicate::And(l, r) => match (to_iceberg_predicate(l), to_iceberg_predicate(r)) {
+            (Some(l), Some(r)) => Some(l.and(r)),
+            (l, r) => l.or(r),
+        },
+        Predicate::Or(l, r) => Some(to_iceberg_predicate(l)?.or(to_iceberg_predicate(r)?)),
+        _ => None,
+    }
+}
+
+fn iceberg_err(e: iceberg::Error) -> Error {
+    Error::InternalError(format!("iceberg: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use arrow::array::{AsArray, Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Int32Type};
+    use iceberg::spec::{
+        DataContentType, DataFile, DataFileBuilder, DataFileFormat, Literal, Manifest, ManifestContentType,
+        ManifestEntry, ManifestFile, ManifestListWriter, ManifestMetadata, ManifestStatus, ManifestWriter, Struct,
+        TableMetadata,
+    };
+    use parquet::arrow::PARQUET_FIELD_ID_META_KEY;
+    use serde_json::{json, Value as Json};
+
+    use super::*;
+    use crate::build_schema;
+    use crate::datasource::file::parquet::write_parquet;
+    use crate::logical::expr::{column, literal, BinaryExpr};
+
+    /// the metadata of a table partitioned by `country`, with the snapshots
+    fn table_metadata(dir: &Path, snapshots: Vec<Json>) -> Json {
+        let current = snapshots.last().map(|s| s["snapshot-id"].clone());
+        json!({
+            "format-version": 2,
+            "table-uuid": "9c12d441-03fe-4693-9a96-a0705ddf69c1",
+            "location": dir.display().to_string(),
+            "last-sequence-number": snapshots.len(),
+            "last-updated-ms": 2000,
+            "last-column-id": 3,
+            "current-schema-id": 0,
+            "schemas": [{"type": "struct", "schema-id": 0, "fields": [
+                {"id": 1, "name": "a", "required": false, "type": "int"},
+                {"id": 2, "name": "country", "required": false, "type": "string"},
+                {"id": 3, "name": "b", "required": false, "type": "long"},
+            ]}],
+            "default-spec-id": 0,
+            "partition-specs": [{"spec-id": 0, "fields": [
+                {"name": "country", "transform": "identity", "source-id": 2, "field-id": 1000},
+            ]}],
+            "last-partition-id": 1000,
+            "default-sort-order-id": 0,
+            "sort-orders": [{"order-id": 0, "fields": []}],
+            "properties": {},
+            "current-snapshot-id": current,
+            "refs": match &current {
+                Some(id) => json!({"main": {"snapshot-id": id, "type": "branch"}}),
+                None => json!({}),
+            },
+            "snapshots": snapshots,
+            "snapshot-log": [],
+            "metadata-log": [],
+        })
+    }
+
+    /// write a parquet data file with the field ids of the columns
+    fn write_data_file(path: &Path, values: Vec<i32>, country: &str) -> Result<DataFile> {
+        let field = |name: &str, data_type: DataType, id: i32| {
+            Field::new(name, data_type, true)
+                .with_metadata(HashMap::from([(PARQUET_FIELD_ID_META_KEY.to_owned(), id.to_string())]))
+        };
+        let schema = Arc::new(Schema::new(vec![
+            field("a", DataType::Int32, 1),
+            field("country", DataType::Utf8, 2),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(values.clone())),
+                Arc::new(StringArray::from(vec![country; values.len()])),
+            ],
+        )?;
+        write_parquet(path.to_str().unwrap(), schema, &[batch])?;
+
+        Ok(DataFileBuilder::default()
+            .content(DataContentType::Data)
+            .file_path(path.display().to_string())
+            .file_format(DataFileFormat::Parquet)
+            .partition(Struct::from_iter([Some(Literal::string(country))]))
+            .record_count(values.len() as u64)
+            .file_size_in_bytes(fs::metadata(path)?.len())
+            .build()
+            .unwrap())
+    }
+
+    async fn write_manifest(
+        file_io: &FileIO,
+        metadata: &TableMetadata,
+        path: &Path,
+        snapshot_id: i64,
+        files: Vec<DataFile>,
+    ) -> iceberg::Result<ManifestFile> {
+        let manifest_metadata = ManifestMetadata::builder()
+            .schema(metadata.current_schema().clone())
+            .schema_id(metadata.current_schema_id())
+            .partition_spec(metadata.default_partition_spec().as_ref().clone())
+            .format_version(metadata.format_version())
+            .content(ManifestContentType::Data)
+            .build();
+        let entries = files
+            .into_iter()
+            .map(|file| {
+                ManifestEntry::builder()
+                    .status(ManifestStatus::Added)
+                    .snapshot_id(snapshot_id)
+                    .data_file(file)
+                    .build()
+            })
+            .collect();
+        ManifestWriter::new(file_io.new_output(path.display().to_string())?, snapshot_id, vec![])
+            .write(Manifest::new(manifest_metadata, entries))
+            .await
+    }
+
+    async fn write_manifest_list(
+        file_io: &FileIO,
+        path: &Path,
+        snapshot_id: i64,
+        manifests: Vec<ManifestFile>,
+    ) -> iceberg::Result<()> {
+        let parent = (snapshot_id > 1).then_some(snapshot_id - 1);
+        let output = file_io.new_output(path.display().to_string())?;
+        let mut writer = ManifestListWriter::v2(output, snapshot_id, parent, snapshot_id);
+        writer.add_manifests(manifests.into_iter())?;
+        writer.close().await
+    }
+
+    /// a table of two snapshots, the second appends a file to the files of the first
+    fn write_table(name: &str) -> Result<PathBuf> {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("metadata"))?;
+        fs::create_dir_all(dir.join("data"))?;
+        let metadata_dir = dir.join("metadata");
+        let empty = table_metadata(&dir, vec![]);
+        fs::write(metadata_dir.join("v1.metadata.json"), empty.to_string())?;
+
+        let files = vec![
+            write_data_file(&dir.join("data/0.parquet"), vec![1, 2], "US")?,
+            write_data_file(&dir.join("data/1.parquet"), vec![3], "CN")?,
+            write_data_file(&dir.join("data/2.parquet"), vec![4, 5], "US")?,
+        ];
+        let metadata: TableMetadata = serde_json::from_value(empty).unwrap();
+        let file_io = FileIO::from_path(dir.to_str().unwrap())
+            .and_then(|builder| builder.build())
+            .map_err(iceberg_err)?;
+        runtime()?
+            .block_on(async {
+                let m0 = write_manifest(
+                    &file_io,
+                    &metadata,
+                    &metadata_dir.join("m0.avro"),
+                    1,
+                    files[..2].to_vec(),
+                )
+                .await?;
+                let m1 = write_manifest(
+                    &file_io,
+                    &metadata,
+                    &metadata_dir.join("m1.avro"),
+                    2,
+                    files[2..].to_vec(),
+                )
+                .await?;
+                write_manifest_list(&file_io, &metadata_dir.join("snap-1.avro"), 1, vec![m0.clone()]).await?;
+                // the manifest of the first snapshot has the sequence number of its snapshot in the second
+                let mut existing = m0;
+                existing.sequence_number = 1;
+                existing.min_sequence_number = 1;
+                write_manifest_list(&file_io, &metadata_dir.join("snap-2.avro"), 2, vec![existing, m1]).await
+            })
+            .map_err(iceberg_err)?;
+
+        let snapshot = |id: i64, timestamp: i64| {
+            json!({
+                "snapshot-id": id,
+                "parent-snapshot-id": (id > 1).then_some(id - 1),
+                "sequence-number": id,
+                "timestamp-ms": timestamp,
+                "manifest-list": metadata_dir.join(format!("snap-{}.avro", id)).display().to_string(),
+                "summary": {"operation": "append"},
+                "schema-id": 0,
+            })
+        };
+        let table = table_metadata(&dir, vec![snapshot(1, 1000), snapshot(2, 2000)]);
+        fs::write(metadata_dir.join("v2.metadata.json"), table.to_string())?;
+
+        Ok(dir)
+    }
+
+    /// the sorted values of the column `a`
+    fn values(batches: &[RecordBatch]) -> Vec<i32> {
+        let mut values = batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column_by_name("a")
+                    .unwrap()
+                    .as_primitive::<Int32Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn test_read_iceberg() -> Result<()> {
+        let dir = write_table("qurious_test_read_iceberg")?;
+        let path = dir.to_str().unwrap();
+
+        let source = read_iceberg(path, IcebergReadOptions::default())?;
+        assert_eq!(
+            source.schema().as_ref(),
+            &build_schema!(
+                ("a", DataType::Int32, true),
+                ("country", DataType::Utf8, true),
+                ("b", DataType::Int64, true)
+            )
+        );
+        let batches = source.scan(None, &[])?;
+        assert_eq!(values(&batches), vec![1, 2, 3, 4, 5]);
+        assert!(batches
+            .iter()
+            .all(|batch| batch.column(2).null_count() == batch.num_rows()));
+
+        // the filters are pushed to the scan of the files
+        let country = LogicalExpr::BinaryExpr(BinaryExpr::new(column("country"), Operator::Eq, literal("US")));
+        let a = LogicalExpr::BinaryExpr(BinaryExpr::new(column("a"), Operator::Lt, literal(5)));
+        assert_eq!(
+            values(&source.scan(None, std::slice::from_ref(&country))?),
+            vec![1, 2, 4, 5]
+        );
+        assert_eq!(
+            values(&source.scan(Some(vec!["a".to_owned()]), &[country, a])?),
+            vec![1, 2, 4]
+        );
+        let table = IcebergTable::try_new(path, IcebergReadOptions::default())?;
+        assert_eq!(table.snapshot_id(), Some(2));
+
+        let options = IcebergReadOptions {
+            snapshot_id: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(values(&read_iceberg(path, options)?.scan(None, &[])?), vec![1, 2, 3]);
+        let options = IcebergReadOptions {
+            timestamp: Some(1500),
+            ..Default::default()
+        };
+        assert_eq!(values(&read_iceberg(path, options)?.scan(None, &[])?), vec![1, 2, 3]);
+
+        let options = IcebergReadOptions {
+            snapshot_id: Some(3),
+            ..Default::default()
+        };
+        assert!(read_iceberg(path, options).is_err());
+        let options = IcebergReadOptions {
+            timestamp: Some(999),
+            ..Default::default()
+        };
+        assert!(read_iceberg(path, options).is_err());
+
+        // the version hint selects the metadata file, the first version has no snapshot
+        fs::write(dir.join("metadata/version-hint.text"), "1")?;
+        let table = IcebergTable::try_new(path, IcebergReadOptions::default())?;
+        assert_eq!(table.snapshot_id(), None);
+        assert!(table.scan(None, &[])?.is_empty());
+        let metadata = dir.join("metadata/v2.metadata.json");
+        assert_eq!(
+            values(&read_iceberg(metadata.to_str().unwrap(), IcebergReadOptions::default())?.scan(None, &[])?),
+            vec![1, 2, 3, 4, 5]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use std::io::{BufReader, Read};
+use std::sync::Arc;
+use url::Url;
+
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+use arrow::ipc::reader::{FileReader, StreamReader};
+
+use crate::arrow_err;
+use crate::datasource::file::object_store::{get_store, ObjectReader, ObjectStore};
+use crate::datasource::file::DataFilePath;
+use crate::error::{Error, Result};
+use crate::logical::expr::LogicalExpr;
+use crate::provider::table::TableProvider;
+
+/// the magic bytes an Arrow IPC file (Feather v2) starts with, a stream has no magic bytes
+const ARROW_MAGIC: &[u8; 6] = b"ARROW1";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IpcFormat {
+    File,
+    Stream,
+}
+
+/// An Arrow IPC file or stream. The format is detected from the magic bytes of the file, the batches are read with
+/// the schema written in the file and only the projected columns are decoded.
+#[derive(Debug)]
+pub struct IpcTable {
+    url: Url,
+    schema: SchemaRef,
+    format: IpcFormat,
+    store: Arc<dyn ObjectStore>,
+}
+
+impl TableProvider for IpcTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Option<Vec<String>>, _filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        let file = ObjectReader::try_new(self.store.clone(), &self.url)?;
+        let indices = projection
+            .map(|projection| {
+                projection
+                    .iter()
+                    .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+
+        let batches = match self.format {
+            IpcFormat::File => FileReader::try_new(BufReader::new(file), indices)
+                .map_err(|e| arrow_err!(e))?
+                .collect::<std::result::Result<_, _>>(),
+            IpcFormat::Stream => StreamReader::try_new(BufReader::new(file), indices)
+                .map_err(|e| arrow_err!(e))?
+                .collect::<std::result::Result<_, _>>(),
+        };
+
+        batches.map_err(|e| arrow_err!(e))
+    }
+}
+
+pub fn read_ipc<T: DataFilePath>(path: T) -> Result<Arc<dyn TableProvider>> {
+    let url = path.to_url()?;
+    let store = get_store(&url)?;
+    let mut file = ObjectReader::try_new(store.clone(), &url)?;
+
+    let mut magic = [0; ARROW_MAGIC.len()];
+    let format = match file.read_exact(&mut magic) {
+        Ok(_) if &magic == ARROW_MAGIC => IpcFormat::File,
+        _ => IpcFormat::Stream,
+    };
+    let file = ObjectReader::try_new(store.clone(), &url)?;
+    let schema = match format {
+        IpcFormat::File => FileReader::try_new(BufReader::new(file), None).map(|reader| reader.schema()),
+        IpcFormat::Stream => StreamReader::try_new(BufReader::new(file), None).map(|reader| reader.schema()),
+    }
+    .map_err(|e| Error::InternalError(format!("{} is not an Arrow IPC file: {}", url.path(), e)))?;
+
+    Ok(Arc::new(IpcTable {
+        url,
+        schema,
+        format,
+        store,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_schema;
+    use crate::test_utils::{assert_batch_eq, build_record_i32};
+    use arrow::datatypes::DataType;
+    use arrow::ipc::writer::{FileWriter, StreamWriter};
+    use std::fs::File;
+
+    #[test]
+    fn test_read_ipc() -> Result<()> {
+        let schema = Arc::new(build_schema!(("a", DataType::Int32), ("b", DataType::Int32)));
+        let batches = [build_record_i32(schema.clone(), vec![vec![1, 2], vec![3, 4]])];
+
+        let file_path = std::env::temp_dir().join("qurious_test_read_ipc.arrow");
+        let mut writer = FileWriter::try_new(File::create(&file_path)?, &schema)?;
+        batches.iter().try_for_each(|batch| writer.write(batch))?;
+        writer.finish()?;
+
+        let stream_path = std::env::temp_dir().join("qurious_test_read_ipc.arrows");
+        let mut writer = StreamWriter::try_new(File::create(&stream_path)?, &schema)?;
+        batches.iter().try_for_each(|batch| writer.write(batch))?;
+        writer.finish()?;
+
+        for path in [file_path, stream_path] {
+            let source = read_ipc(path.to_str().unwrap())?;
+            assert_eq!(source.schema(), schema);
+            assert_batch_eq(
+                &source.scan(Some(vec!["b".to_owned()]), &[])?,
+                vec!["+---+", "| b |", "+---+", "| 3 |", "| 4 |", "+---+"],
+            );
+        }
+
+        Ok(())
+    }
+}
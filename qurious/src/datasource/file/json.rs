@@ -1,41 +1,160 @@
 use std::fs::File;
 use std::io::BufReader;
 use std::sync::Arc;
+use url::Url;
 
-use arrow::json::reader::infer_json_schema_from_seekable;
-use arrow::json::ReaderBuilder;
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+use arrow::json::reader::infer_json_schema;
+use arrow::json::{LineDelimitedWriter, ReaderBuilder};
 
 use crate::arrow_err;
+use crate::datasource::file::cache::file_metadata_cache;
+use crate::datasource::file::compression::FileCompression;
+use crate::datasource::file::object_store::{get_store, ObjectReader, ObjectStore};
 use crate::datasource::file::DataFilePath;
-use crate::datasource::memory::MemoryTable;
 use crate::error::{Error, Result};
+use crate::logical::expr::LogicalExpr;
 use crate::provider::table::TableProvider;
 
-#[derive(Default)]
-pub struct JsonReadOptions {}
+#[derive(Debug, Clone)]
+pub struct JsonReadOptions {
+    /// the number of lines the schema is inferred from, `None` reads the whole file
+    pub schema_infer_max_records: Option<usize>,
+    /// the compression of the file, `None` detects it from the extension of the file
+    pub compression: Option<FileCompression>,
+}
+
+impl Default for JsonReadOptions {
+    fn default() -> Self {
+        Self {
+            schema_infer_max_records: Some(1000),
+            compression: None,
+        }
+    }
+}
+
+/// A newline-delimited json file, one object per line. The schema is inferred when the table is created, a nested
+/// object is a struct and an array is a list. The file is read again by each scan.
+#[derive(Debug)]
+pub struct JsonTable {
+    url: Url,
+    schema: SchemaRef,
+    compression: FileCompression,
+    store: Arc<dyn ObjectStore>,
+}
+
+impl TableProvider for JsonTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Option<Vec<String>>, _filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        let file = self
+            .compression
+            .decoder(ObjectReader::try_new(self.store.clone(), &self.url)?)?;
+        // the fields not projected are skipped by the decoder
+        let schema = match projection {
+            Some(projection) => {
+                let indices = projection
+                    .iter()
+                    .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+                    .collect::<Result<Vec<_>>>()?;
+                Arc::new(self.schema.project(&indices)?)
+            }
+            None => self.schema.clone(),
+        };
+
+        ReaderBuilder::new(schema)
+            .build(BufReader::new(file))
+            .map_err(|e| arrow_err!(e))?
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|e| arrow_err!(e))
+    }
+}
 
-pub fn read_json<T: DataFilePath>(path: T) -> Result<Arc<dyn TableProvider>> {
+pub fn read_json<T: DataFilePath>(path: T, options: JsonReadOptions) -> Result<Arc<dyn TableProvider>> {
     let url = path.to_url()?;
-    let file = File::open(url.path())?;
-    let mut reader = BufReader::new(file);
-    let (schema, _) = infer_json_schema_from_seekable(&mut reader, None)?;
-
-    let schema = Arc::new(schema);
-    ReaderBuilder::new(schema.clone())
-        .build(reader)
-        .and_then(|builder| builder.into_iter().collect())
+    let store = get_store(&url)?;
+    let compression = options
+        .compression
+        .unwrap_or_else(|| FileCompression::from_path(url.path()));
+    let schema = file_metadata_cache().schema(&url, format!("json {:?}", options), || {
+        let file = compression.decoder(ObjectReader::try_new(store.clone(), &url)?)?;
+        let (schema, _) = infer_json_schema(BufReader::new(file), options.schema_infer_max_records)?;
+        Ok(Arc::new(schema))
+    })?;
+
+    Ok(Arc::new(JsonTable {
+        url,
+        schema,
+        compression,
+        store,
+    }))
+}
+
+/// write the batches to the file as one json object per line, the file is created or truncated
+pub fn write_json(path: &str, batches: &[RecordBatch]) -> Result<()> {
+    let file = File::create(path)?;
+    let mut writer = LineDelimitedWriter::new(file);
+    writer
+        .write_batches(&batches.iter().collect::<Vec<_>>())
+        .and_then(|_| writer.finish())
         .map_err(|e| arrow_err!(e))
-        .and_then(|data| MemoryTable::try_new(schema, data).map(|v| Arc::new(v) as Arc<dyn TableProvider>))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::assert_batch_eq;
+    use arrow::datatypes::{DataType, Field, Fields};
     use arrow::util;
 
+    #[test]
+    fn test_read_nested_json() -> Result<()> {
+        let path = std::env::temp_dir().join("qurious_test_read_nested_json.json");
+        std::fs::write(
+            &path,
+            "{\"id\": 1, \"tags\": [\"a\", \"b\"], \"user\": {\"name\": \"x\", \"age\": 30}}\n\
+             {\"id\": 2, \"tags\": [], \"user\": {\"name\": \"y\"}}\n",
+        )?;
+
+        let source = read_json(path.to_str().unwrap(), JsonReadOptions::default())?;
+        let schema = source.schema();
+        assert_eq!(
+            schema.field_with_name("tags")?.data_type(),
+            &DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)))
+        );
+        assert_eq!(
+            schema.field_with_name("user")?.data_type(),
+            &DataType::Struct(Fields::from(vec![
+                Field::new("age", DataType::Int64, true),
+                Field::new("name", DataType::Utf8, true),
+            ]))
+        );
+
+        let batches = source.scan(Some(vec!["user".to_owned(), "id".to_owned()]), &[])?;
+        assert_batch_eq(
+            &batches,
+            vec![
+                "+--------------------+----+",
+                "| user               | id |",
+                "+--------------------+----+",
+                "| {age: 30, name: x} | 1  |",
+                "| {age: , name: y}   | 2  |",
+                "+--------------------+----+",
+            ],
+        );
+        // a projection with no column only counts the rows
+        let batches = source.scan(Some(vec![]), &[])?;
+        assert_eq!(batches.iter().map(|batch| batch.num_rows()).sum::<usize>(), 2);
+
+        Ok(())
+    }
+
     #[test]
     fn test_read_json() {
-        let source = read_json("tests/testdata/file/case1.json").unwrap();
+        let source = read_json("tests/testdata/file/case1.json", JsonReadOptions::default()).unwrap();
 
         println!(
             "{}",
@@ -0,0 +1,822 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::array::{
+    new_null_array, Array, ArrayRef, BooleanArray, Int64Array, RecordBatch, RecordBatchOptions, StringArray,
+    UInt32Array,
+};
+use arrow::compute::{cast, take};
+use arrow::datatypes::{Field, Schema, SchemaRef};
+use url::Url;
+
+use super::cache::file_metadata_cache;
+use super::object_store::is_object_url;
+
+use crate::arrow_err;
+use crate::datatypes::scalar::ScalarValue;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::logical::expr::{split_conjunction, LogicalExpr};
+use crate::planner::{DefaultQueryPlanner, QueryPlanner};
+use crate::provider::table::{ColumnStatistics, RecordBatchStream, Statistics, TableProvider};
+use crate::utils::type_coercion::get_common_type;
+
+/// the partition value of a NULL in the directory name of a Hive partition
+const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// The files of a directory or a glob pattern read as a single table. The schema is the merge of the schemas of the
+/// files, a column missing from a file is NULL in its rows. When the schema is merged, a column may have different
+/// types in the files, it has their common type, an Int64 for an Int32 and an Int64. A scan reads the files one after
+/// another.
+///
+/// The files of a dataset partitioned like Hive, as `date=2024-01-01/country=US/part-0.parquet`, have the partition
+/// columns after the columns of the files. A scan skips the files whose partition values the filters reject.
+#[derive(Debug)]
+pub struct ListingTable {
+    schema: SchemaRef,
+    files: Vec<Arc<dyn TableProvider>>,
+    /// the values of the partition columns, a row for each file
+    partitions: RecordBatch,
+}
+
+impl ListingTable {
+    pub fn try_new(files: Vec<Arc<dyn TableProvider>>, partitions: RecordBatch) -> Result<Self> {
+        Self::try_new_with_merge_schema(files, partitions, false)
+    }
+
+    /// The table of the files. With `merge_schema` the files may have different types for a column, the column has
+    /// the common type of its types and the rows of each file are cast to it, otherwise the types must be the same.
+    pub fn try_new_with_merge_schema(
+        files: Vec<Arc<dyn TableProvider>>,
+        partitions: RecordBatch,
+        merge_schema: bool,
+    ) -> Result<Self> {
+        if partitions.num_rows() != files.len() {
+            return internal_err!(
+                "the partition values of {} files are given for {} files",
+                partitions.num_rows(),
+                files.len()
+            );
+        }
+        let partition_schema = partitions.schema();
+        // the metadata of the files may conflict, only the fields are merged, a partition column of the file is
+        // replaced by the partition column
+        let schemas = files.iter().map(|file| {
+            let schema = file.schema();
+            let fields = schema
+                .fields()
+                .iter()
+                .filter(|field| partition_schema.field_with_name(field.name()).is_err())
+                .cloned()
+                .collect::<Vec<_>>();
+            Schema::new(fields)
+        });
+        let schema = match merge_schema {
+            true => merge_schemas(schemas)?,
+            false => Schema::try_merge(schemas)
+                .map_err(|e| Error::InternalError(format!("the schemas of the files can not be merged: {}", e)))?,
+        };
+        let fields = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let missing = files
+                    .iter()
+                    .any(|file| file.schema().field_with_name(field.name()).is_err());
+                match missing {
+                    true => Arc::new(field.as_ref().clone().with_nullable(true)),
+                    false => field.clone(),
+                }
+            })
+            .chain(partition_schema.fields().iter().cloned())
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            schema: Arc::new(Schema::new(fields)),
+            files,
+            partitions,
+        })
+    }
+
+    /// Whether each file may have a row the filters accept. The conjuncts of the filters that only read the
+    /// partition columns are evaluated against the partition values of the files, a conjunct that can not be
+    /// evaluated keeps all files.
+    fn prune_files(&self, filters: &[LogicalExpr]) -> Vec<bool> {
+        let mut keep = vec![true; self.files.len()];
+        if self.partitions.num_columns() == 0 {
+            return keep;
+        }
+
+        let schema = self.partitions.schema();
+        let planner = DefaultQueryPlanner::default();
+        for filter in filters.iter().flat_map(split_conjunction) {
+            let columns = filter.column_refs();
+            if columns.is_empty()
+                || columns
+                    .iter()
+                    .any(|column| column.is_outer_ref || schema.field_with_name(&column.name).is_err())
+            {
+                continue;
+            }
+            let Ok(result) = planner
+                .create_physical_expr(&schema, filter)
+                .and_then(|expr| expr.evaluate(&self.partitions))
+            else {
+                continue;
+            };
+            if let Some(result) = result.as_any().downcast_ref::<BooleanArray>() {
+                keep.iter_mut()
+                    .zip(result)
+                    .for_each(|(keep, accepted)| *keep &= accepted.unwrap_or(false));
+            }
+        }
+
+        keep
+    }
+}
+
+impl TableProvider for ListingTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        self.scan_stream(projection, filters)?.collect()
+    }
+
+    fn scan_stream(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<RecordBatchStream> {
+        let schema = match &projection {
+            Some(projection) => {
+                let indices = projection
+                    .iter()
+                    .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+                    .collect::<Result<Vec<_>>>()?;
+                Arc::new(self.schema.project(&indices)?)
+            }
+            None => self.schema.clone(),
+        };
+        let filters = filters.to_vec();
+        let partitions = self.partitions.clone();
+        let files = self
+            .files
+            .iter()
+            .cloned()
+            .enumerate()
+            .zip(self.prune_files(&filters))
+            .filter_map(|(file, keep)| keep.then_some(file))
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(files.into_iter().flat_map(move |(i, file)| {
+            // a file is only asked for the columns it has
+            let file_schema = file.schema();
+            let projection = schema
+                .fields()
+                .iter()
+                .filter(|field| {
+                    file_schema.field_with_name(field.name()).is_ok()
+                        && partitions.column_by_name(field.name()).is_none()
+                })
+                .map(|field| field.name().clone())
+                .collect();
+            let schema = schema.clone();
+            let partitions = partitions.clone();
+
+            match file.scan_stream(Some(projection), &filters) {
+                Ok(stream) => Box::new(
+                    stream.map(move |batch| batch.and_then(|batch| adapt_batch(&schema, batch, &partitions, i))),
+                ) as RecordBatchStream,
+                Err(e) => Box::new(std::iter::once(Err(e))),
+            }
+        })))
+    }
+
+    fn supports_filters_pushdown(&self) -> bool {
+        self.partitions.num_columns() > 0 || self.files.iter().any(|file| file.supports_filters_pushdown())
+    }
+
+    /// the statistics of the files added up, a statistic is unknown if a file does not know it. A column a file does
+    /// not have is NULL in all its rows.
+    fn statistics(&self) -> Option<Statistics> {
+        let statistics = self
+            .files
+            .iter()
+            .map(|file| file.statistics())
+            .collect::<Option<Vec<_>>>()?;
+        let column_statistics = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| {
+                if self.partitions.column_by_name(field.name()).is_some() {
+                    return ColumnStatistics::default();
+                }
+                let columns = self
+                    .files
+                    .iter()
+                    .zip(&statistics)
+                    .map(|(file, statistics)| match file.schema().index_of(field.name()) {
+                        Ok(i) if file.schema().field(i).data_type() == field.data_type() => {
+                            Some(statistics.column_statistics.get(i).cloned().unwrap_or_default())
+                        }
+                        Ok(_) => Some(ColumnStatistics::default()),
+                        Err(_) => None,
+                    })
+                    .collect::<Vec<_>>();
+                let values = |value: fn(&ColumnStatistics) -> Option<ScalarValue>| {
+                    columns.iter().flatten().map(value).collect::<Option<Vec<_>>>()
+                };
+
+                ColumnStatistics {
+                    min_value: values(|c| c.min_value.clone())
+                        .and_then(|mins| mins.into_iter().reduce(|a, b| if b < a { b } else { a })),
+                    max_value: values(|c| c.max_value.clone())
+                        .and_then(|maxes| maxes.into_iter().reduce(|a, b| if b > a { b } else { a })),
+                    null_count: columns
+                        .iter()
+                        .zip(&statistics)
+                        .map(|(column, statistics)| match column {
+                            Some(column) => column.null_count,
+                            None => statistics.num_rows,
+                        })
+                        .sum(),
+                }
+            })
+            .collect();
+
+        Some(Statistics {
+            num_rows: statistics.iter().map(|statistics| statistics.num_rows).sum(),
+            total_byte_size: statistics.iter().map(|statistics| statistics.total_byte_size).sum(),
+            column_statistics,
+        })
+    }
+}
+
+/// the fields of the schemas in the order they first appear, a field of several schemas has the common type of its
+/// types and is nullable if it is nullable in any of them
+fn merge_schemas(schemas: impl Iterator<Item = Schema>) -> Result<Schema> {
+    let mut fields: Vec<Field> = vec![];
+    for schema in schemas {
+        for field in schema.fields() {
+            match fields.iter_mut().find(|merged| merged.name() == field.name()) {
+                Some(merged) => {
+                    let data_type = get_common_type(merged.data_type(), field.data_type()).map_err(|e| {
+                        Error::InternalError(format!(
+                            "the column {} of the files can not be merged: {}",
+                            field.name(),
+                            e
+                        ))
+                    })?;
+                    let nullable = merged.is_nullable() || field.is_nullable();
+                    *merged = Field::new(field.name(), data_type, nullable);
+                }
+                None => fields.push(field.as_ref().clone()),
+            }
+        }
+    }
+
+    Ok(Schema::new(fields))
+}
+
+/// the batch of a file with the columns of the schema, a partition column has the partition value of the file, a
+/// column of another type is cast to the type of the schema and a column the file does not have is NULL
+fn adapt_batch(schema: &SchemaRef, batch: RecordBatch, partitions: &RecordBatch, file: usize) -> Result<RecordBatch> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| match partitions.column_by_name(field.name()) {
+            Some(values) => take(values, &UInt32Array::from(vec![file as u32; batch.num_rows()]), None),
+            None => match batch.column_by_name(field.name()) {
+                Some(column) if column.data_type() == field.data_type() => Ok(column.clone()),
+                Some(column) => cast(column, field.data_type()),
+                None => Ok(new_null_array(field.data_type(), batch.num_rows())),
+            },
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+
+    RecordBatch::try_new_with_options(schema.clone(), columns, &options).map_err(|e| arrow_err!(e))
+}
+
+/// the batch with the columns and the types of the schema, a column the batch does not have is NULL
+pub fn cast_to_schema(schema: &SchemaRef, batch: RecordBatch) -> Result<RecordBatch> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| match batch.column_by_name(field.name()) {
+            Some(column) if column.data_type() == field.data_type() => Ok(column.clone()),
+            Some(column) => cast(column, field.data_type()).map_err(|e| arrow_err!(e)),
+            None => Ok(new_null_array(field.data_type(), batch.num_rows())),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+
+    RecordBatch::try_new_with_options(schema.clone(), columns, &options).map_err(|e| arrow_err!(e))
+}
+
+/// Whether the path is a glob pattern, a `*` matches any characters of a file or directory name, `**` any number of
+/// directories and `?` a single character
+pub fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?'])
+}
+
+/// Read the path as a table with the function reading a file. A directory or a glob pattern is read as a
+/// `ListingTable`, of the files of the directory with one of the extensions, listed recursively, or of the files
+/// the pattern matches. The hidden files, whose names start with `.` or `_`, are skipped. The `key=value`
+/// directories between the directory, or the directory of the pattern, and the files are the partitions of the files.
+pub fn read_files<F>(path: &str, extensions: &[&str], read_file: F) -> Result<Arc<dyn TableProvider>>
+where
+    F: Fn(&str) -> Result<Arc<dyn TableProvider>>,
+{
+    read_files_with_merge_schema(path, extensions, false, read_file)
+}
+
+/// Read the path as a table like `read_files`, with `merge_schema` the files may have different types for a column.
+pub fn read_files_with_merge_schema<F>(
+    path: &str,
+    extensions: &[&str],
+    merge_schema: bool,
+    read_file: F,
+) -> Result<Arc<dyn TableProvider>>
+where
+    F: Fn(&str) -> Result<Arc<dyn TableProvider>>,
+{
+    if is_object_url(path) {
+        return read_objects(path, extensions, merge_schema, read_file);
+    }
+    if !is_glob(path) && !Path::new(path).is_dir() {
+        return read_file(path);
+    }
+
+    let files = list_files(path, extensions)?;
+    if files.is_empty() {
+        return Err(Error::InternalError(format!("no file matches the path {}", path)));
+    }
+    let partitions = parse_partitions(&base_dir(Path::new(path)), &files)?;
+    let files = files
+        .iter()
+        .map(|file| read_file(&file.to_string_lossy()))
+        .collect::<Result<Vec<_>>>()?;
+
+    ListingTable::try_new_with_merge_schema(files, partitions, merge_schema)
+        .map(|table| Arc::new(table) as Arc<dyn TableProvider>)
+}
+
+/// Read the URL of an object store as a table, a URL ending with `/` is a directory and a glob pattern matches the
+/// keys of the objects. The listing of the objects is cached until it is invalidated.
+fn read_objects<F>(path: &str, extensions: &[&str], merge_schema: bool, read_file: F) -> Result<Arc<dyn TableProvider>>
+where
+    F: Fn(&str) -> Result<Arc<dyn TableProvider>>,
+{
+    if !is_glob(path) && !path.ends_with('/') {
+        return read_file(path);
+    }
+
+    // the components of the pattern after the directory the objects are listed from
+    let (scheme, key) = path.split_once("://").unwrap_or(("", path));
+    let components = key.split('/').collect::<Vec<_>>();
+    let base = components
+        .iter()
+        .position(|c| is_glob(c))
+        .unwrap_or(components.len() - 1);
+    let patterns = components[base..].iter().map(|c| c.to_string()).collect::<Vec<_>>();
+    let prefix = format!("{}://{}/", scheme, components[..base].join("/"));
+    let prefix = Url::parse(&prefix).map_err(|e| Error::InternalError(format!("{}: {}", prefix, e)))?;
+
+    let mut files = vec![];
+    for url in file_metadata_cache().list(&prefix)? {
+        let Some(relative) = url.as_str().strip_prefix(prefix.as_str()) else {
+            continue;
+        };
+        let components = relative.split('/').collect::<Vec<_>>();
+        if components.iter().any(|c| c.starts_with(['.', '_'])) {
+            continue;
+        }
+        let matched = if is_glob(path) {
+            match_components(&patterns, &components)
+        } else {
+            has_extension(Path::new(relative), extensions)
+        };
+        if matched {
+            files.push(PathBuf::from(relative));
+        }
+    }
+    if files.is_empty() {
+        return Err(Error::InternalError(format!("no file matches the path {}", path)));
+    }
+    files.sort();
+
+    let partitions = parse_partitions(Path::new(""), &files)?;
+    let files = files
+        .iter()
+        .map(|file| read_file(&format!("{}{}", prefix, file.to_string_lossy())))
+        .collect::<Result<Vec<_>>>()?;
+
+    ListingTable::try_new_with_merge_schema(files, partitions, merge_schema)
+        .map(|table| Arc::new(table) as Arc<dyn TableProvider>)
+}
+
+/// The partition values of the files, from the `key=value` directories between the base directory and the files.
+/// All files must have the same partition columns. A partition column is an Int64 if all its values are integers,
+/// otherwise a Utf8.
+fn parse_partitions(base: &Path, files: &[PathBuf]) -> Result<RecordBatch> {
+    let mut columns: Option<Vec<String>> = None;
+    let mut values = vec![];
+    for file in files {
+        let relative = file.strip_prefix(base).unwrap_or(file);
+        let pairs = relative
+            .parent()
+            .into_iter()
+            .flat_map(|dir| dir.components())
+            .filter_map(|component| {
+                let component = component.as_os_str().to_string_lossy();
+                let (key, value) = component.split_once('=')?;
+                let value = percent_decode(value);
+                Some((percent_decode(key), (value != HIVE_DEFAULT_PARTITION).then_some(value)))
+            })
+            .collect::<Vec<_>>();
+        let keys = pairs.iter().map(|(key, _)| key.clone()).collect::<Vec<_>>();
+        match &columns {
+            Some(columns) if columns != &keys => {
+                return internal_err!(
+                    "the partitions [{}] of the file {} are not the partitions [{}] of the other files",
+                    keys.join(", "),
+                    file.display(),
+                    columns.join(", ")
+                );
+            }
+            Some(_) => {}
+            None => columns = Some(keys),
+        }
+        values.push(pairs.into_iter().map(|(_, value)| value).collect::<Vec<_>>());
+    }
+
+    let columns = columns.unwrap_or_default();
+    if columns.iter().collect::<HashSet<_>>().len() != columns.len() {
+        return internal_err!("the partition columns [{}] are not unique", columns.join(", "));
+    }
+    let (fields, arrays): (Vec<_>, Vec<_>) = columns
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let values = values.iter().map(|values| values[i].as_deref()).collect::<Vec<_>>();
+            let integers = values
+                .iter()
+                .map(|value| value.map(|v| v.parse::<i64>()).transpose())
+                .collect::<std::result::Result<Vec<_>, _>>();
+            let array = match integers {
+                Ok(integers) => Arc::new(Int64Array::from(integers)) as ArrayRef,
+                Err(_) => Arc::new(StringArray::from(values)) as ArrayRef,
+            };
+            (Field::new(name, array.data_type().clone(), true), array)
+        })
+        .unzip();
+    let options = RecordBatchOptions::new().with_row_count(Some(files.len()));
+
+    RecordBatch::try_new_with_options(Arc::new(Schema::new(fields)), arrays, &options).map_err(|e| arrow_err!(e))
+}
+
+/// the escaped characters of a Hive partition directory, like `%2F` for `/`
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// the directory a path is listed from, the directory itself or the components of a glob pattern before the first
+/// pattern
+fn base_dir(path: &Path) -> PathBuf {
+    if !is_glob(&path.to_string_lossy()) {
+        return path.to_path_buf();
+    }
+    let base = path
+        .components()
+        .take_while(|c| !is_glob(&c.as_os_str().to_string_lossy()))
+        .collect::<PathBuf>();
+    if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    }
+}
+
+/// the files of a directory with one of the extensions or the files matching a glob pattern, sorted by path
+pub fn list_files(path: &str, extensions: &[&str]) -> Result<Vec<PathBuf>> {
+    let path = Path::new(path);
+    let mut files = vec![];
+    if is_glob(&path.to_string_lossy()) {
+        let base = base_dir(path);
+        let patterns = path
+            .components()
+            .skip_while(|c| !is_glob(&c.as_os_str().to_string_lossy()))
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        match_glob(&base, &patterns, &mut files)?;
+        files.retain(|file| file.is_file());
+    } else {
+        list_dir(path, extensions, &mut files)?;
+    }
+    files.sort();
+    files.dedup();
+
+    Ok(files)
+}
+
+fn list_dir(dir: &Path, extensions: &[&str], files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in read_dir(dir)? {
+        if entry.is_dir() {
+            list_dir(&entry, extensions, files)?;
+        } else if has_extension(&entry, extensions) {
+            files.push(entry);
+        }
+    }
+
+    Ok(())
+}
+
+/// whether the name of the file ends with one of the extensions, an extension like `csv.gz` has several parts
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    extensions.iter().any(|ext| {
+        name.strip_suffix(ext)
+            .is_some_and(|name| name.len() > 1 && name.ends_with('.'))
+    })
+}
+
+fn match_glob(dir: &Path, patterns: &[String], files: &mut Vec<PathBuf>) -> Result<()> {
+    let Some((pattern, rest)) = patterns.split_first() else {
+        files.push(dir.to_path_buf());
+        return Ok(());
+    };
+
+    if pattern == "**" {
+        // `**` matches no directory or any directory below
+        match_glob(dir, rest, files)?;
+        for entry in read_dir(dir)? {
+            if entry.is_dir() {
+                match_glob(&entry, patterns, files)?;
+            }
+        }
+        return Ok(());
+    }
+    if !is_glob(pattern) {
+        let entry = dir.join(pattern);
+        if entry.exists() {
+            match_glob(&entry, rest, files)?;
+        }
+        return Ok(());
+    }
+    for entry in read_dir(dir)? {
+        let name = entry.file_name().unwrap_or_default().to_string_lossy();
+        if wildcard_match(pattern.as_bytes(), name.as_bytes()) {
+            match_glob(&entry, rest, files)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// the entries of a directory without the hidden files
+fn read_dir(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = fs::read_dir(dir).map_err(|e| Error::InternalError(format!("{}: {}", dir.display(), e)))?;
+    let mut paths = vec![];
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name();
+        if !name.to_string_lossy().starts_with(['.', '_']) {
+            paths.push(entry.path());
+        }
+    }
+
+    Ok(paths)
+}
+
+/// whether the components of a key match the components of a glob pattern
+fn match_components(patterns: &[String], components: &[&str]) -> bool {
+    match patterns.split_first() {
+        None => components.is_empty(),
+        Some((pattern, rest)) if pattern == "**" => {
+            (0..=components.len()).any(|i| match_components(rest, &components[i..]))
+        }
+        Some((pattern, rest)) => components.split_first().is_some_and(|(component, components)| {
+            wildcard_match(pattern.as_bytes(), component.as_bytes()) && match_components(rest, components)
+        }),
+    }
+}
+
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            wildcard_match(rest, name) || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        }
+        (Some((b'?', rest)), Some((_, name))) => wildcard_match(rest, name),
+        (Some((p, rest)), Some((n, name))) if p == n => wildcard_match(rest, name),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_schema;
+    use crate::datasource::file::parquet::{read_parquet, write_parquet};
+    use crate::datatypes::operator::Operator;
+    use crate::logical::expr::{column, literal, BinaryExpr};
+    use crate::test_utils::{assert_batch_eq, build_record_i32};
+    use arrow::datatypes::DataType;
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match(b"*.parquet", b"part-0.parquet"));
+        assert!(wildcard_match(b"part-?.parquet", b"part-1.parquet"));
+        assert!(!wildcard_match(b"part-?.parquet", b"part-10.parquet"));
+        assert!(!wildcard_match(b"*.parquet", b"part-0.csv"));
+
+        let patterns = ["**".to_owned(), "*.parquet".to_owned()];
+        assert!(match_components(&patterns, &["part-0.parquet"]));
+        assert!(match_components(&patterns, &["year=2024", "month=1", "part-0.parquet"]));
+        assert!(!match_components(&patterns, &["year=2024", "part-0.csv"]));
+    }
+
+    #[test]
+    fn test_read_files() -> Result<()> {
+        let dir = std::env::temp_dir().join("qurious_test_read_files");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub"))?;
+        let schema_a = Arc::new(build_schema!(("a", DataType::Int32)));
+        let schema_ab = Arc::new(build_schema!(("a", DataType::Int32), ("b", DataType::Int32)));
+        let write = |path: PathBuf, schema: &SchemaRef, columns| {
+            let batch = build_record_i32(schema.clone(), columns);
+            write_parquet(path.to_str().unwrap(), schema.clone(), &[batch])
+        };
+        write(dir.join("0.parquet"), &schema_a, vec![vec![1, 2]])?;
+        write(dir.join("sub").join("1.parquet"), &schema_ab, vec![vec![3], vec![30]])?;
+        write(dir.join("_SUCCESS.parquet"), &schema_a, vec![vec![0]])?;
+        fs::write(dir.join("readme.txt"), "not a parquet file")?;
+
+        let source = read_files(dir.to_str().unwrap(), &["parquet"], |path| read_parquet(path))?;
+        assert_eq!(
+            source.schema().as_ref(),
+            &build_schema!(("a", DataType::Int32, false), ("b", DataType::Int32, true))
+        );
+        assert_batch_eq(
+            &source.scan(Some(vec!["b".to_owned(), "a".to_owned()]), &[])?,
+            vec![
+                "+----+---+",
+                "| b  | a |",
+                "+----+---+",
+                "|    | 1 |",
+                "|    | 2 |",
+                "| 30 | 3 |",
+                "+----+---+",
+            ],
+        );
+
+        let pattern = dir.join("*.parquet");
+        let source = read_files(pattern.to_str().unwrap(), &["parquet"], |path| read_parquet(path))?;
+        assert_eq!(source.scan(None, &[])?.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+        let pattern = dir.join("**").join("?.parquet");
+        assert_eq!(list_files(pattern.to_str().unwrap(), &["parquet"])?.len(), 2);
+
+        let pattern = dir.join("*.csv");
+        assert!(read_files(pattern.to_str().unwrap(), &["csv"], |path| read_parquet(path)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_files_with_merge_schema() -> Result<()> {
+        let dir = std::env::temp_dir().join("qurious_test_read_files_with_merge_schema");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        let schema = Arc::new(build_schema!(("a", DataType::Int32), ("b", DataType::Float32)));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(arrow::array::Int32Array::from(vec![1])),
+                Arc::new(arrow::array::Float32Array::from(vec![1.5])),
+            ],
+        )?;
+        write_parquet(dir.join("0.parquet").to_str().unwrap(), schema, &[batch])?;
+        let schema = Arc::new(build_schema!(("c", DataType::Utf8), ("a", DataType::Int64)));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["x"])),
+                Arc::new(Int64Array::from(vec![5_000_000_000])),
+            ],
+        )?;
+        write_parquet(dir.join("1.parquet").to_str().unwrap(), schema, &[batch])?;
+
+        let path = dir.to_str().unwrap();
+        assert!(read_files(path, &["parquet"], |path| read_parquet(path)).is_err());
+
+        let source = read_files_with_merge_schema(path, &["parquet"], true, |path| read_parquet(path))?;
+        assert_eq!(
+            source.schema().as_ref(),
+            &build_schema!(
+                ("a", DataType::Int64, false),
+                ("b", DataType::Float32, true),
+                ("c", DataType::Utf8, true)
+            )
+        );
+        assert_batch_eq(
+            &source.scan(None, &[])?,
+            vec![
+                "+------------+-----+---+",
+                "| a          | b   | c |",
+                "+------------+-----+---+",
+                "| 1          | 1.5 |   |",
+                "| 5000000000 |     | x |",
+                "+------------+-----+---+",
+            ],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_partitioned_files() -> Result<()> {
+        let dir = std::env::temp_dir().join("qurious_test_read_partitioned_files");
+        let _ = fs::remove_dir_all(&dir);
+        let schema = Arc::new(build_schema!(("a", DataType::Int32)));
+        for (i, partition) in [
+            "year=2023/country=US",
+            "year=2024/country=US",
+            "year=2024/country=__HIVE_DEFAULT_PARTITION__",
+        ]
+        .iter()
+        .enumerate()
+        {
+            fs::create_dir_all(dir.join(partition))?;
+            let batch = build_record_i32(schema.clone(), vec![vec![i as i32]]);
+            let path = dir.join(partition).join("part-0.parquet");
+            write_parquet(path.to_str().unwrap(), schema.clone(), &[batch])?;
+        }
+
+        let source = read_files(dir.to_str().unwrap(), &["parquet"], |path| read_parquet(path))?;
+        assert_eq!(
+            source.schema().as_ref(),
+            &build_schema!(
+                ("a", DataType::Int32, false),
+                ("year", DataType::Int64, true),
+                ("country", DataType::Utf8, true)
+            )
+        );
+        assert_batch_eq(
+            &source.scan(None, &[])?,
+            vec![
+                "+---+------+---------+",
+                "| a | year | country |",
+                "+---+------+---------+",
+                "| 0 | 2023 | US      |",
+                "| 1 | 2024 | US      |",
+                "| 2 | 2024 |         |",
+                "+---+------+---------+",
+            ],
+        );
+
+        // the files of the rejected partitions are not read
+        let year = LogicalExpr::BinaryExpr(BinaryExpr::new(column("year"), Operator::Eq, literal(2024_i64)));
+        let country = LogicalExpr::BinaryExpr(BinaryExpr::new(column("country"), Operator::Eq, literal("US")));
+        let a = LogicalExpr::BinaryExpr(BinaryExpr::new(column("a"), Operator::Lt, literal(5_i64)));
+        let filter = LogicalExpr::BinaryExpr(BinaryExpr::new(year, Operator::And, a));
+        assert_batch_eq(
+            &source.scan(Some(vec!["country".to_owned(), "a".to_owned()]), &[filter, country])?,
+            vec![
+                "+---------+---+",
+                "| country | a |",
+                "+---------+---+",
+                "| US      | 1 |",
+                "+---------+---+",
+            ],
+        );
+
+        fs::create_dir_all(dir.join("year=2025"))?;
+        let batch = build_record_i32(schema.clone(), vec![vec![3]]);
+        let path = dir.join("year=2025").join("part-0.parquet");
+        write_parquet(path.to_str().unwrap(), schema.clone(), &[batch])?;
+        assert!(read_files(dir.to_str().unwrap(), &["parquet"], |path| read_parquet(path)).is_err());
+
+        Ok(())
+    }
+}
@@ -1,5 +1,20 @@
+#[cfg(feature = "avro")]
+pub mod avro;
+pub mod cache;
+pub mod compression;
 pub mod csv;
+#[cfg(feature = "delta")]
+pub mod delta;
+#[cfg(feature = "excel")]
+pub mod excel;
+#[cfg(feature = "iceberg")]
+pub mod iceberg;
+pub mod ipc;
 pub mod json;
+pub mod listing;
+pub mod object_store;
+#[cfg(feature = "orc")]
+pub mod orc;
 pub mod parquet;
 
 use std::fs::{self};
@@ -38,3 +53,12 @@ pub fn parse_path<S: AsRef<str>>(path: S) -> Result<Url> {
         Err(e) => Err(Error::InternalError(e.to_string())),
     }
 }
+
+/// the runtime of the async readers of the table formats and of the object stores, the scans block on their futures
+#[cfg(any(feature = "delta", feature = "iceberg", feature = "s3", feature = "gcs", feature = "azure"))]
+pub(crate) fn runtime() -> Result<tokio::runtime::Runtime> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| Error::InternalError(format!("failed to create the runtime: {}", e)))
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::TryStreamExt;
+use object_store::path::Path;
+use tokio::runtime::Runtime;
+use url::Url;
+
+use super::ObjectStore;
+use crate::datasource::file::runtime;
+use crate::error::{Error, Result};
+use crate::internal_err;
+
+/// The objects of a store of the `object_store` crate, like S3, GCS or Azure Blob Storage. The requests are async,
+/// the calls block on them, so a reader only downloads the ranges of an object it decodes. The store of a bucket is
+/// configured by the options and then by the environment, like `AWS_ACCESS_KEY_ID` or `GOOGLE_SERVICE_ACCOUNT`.
+#[derive(Debug)]
+pub struct CloudStore {
+    store: Arc<dyn object_store::ObjectStore>,
+    runtime: Runtime,
+}
+
+impl CloudStore {
+    pub fn new(store: Arc<dyn object_store::ObjectStore>) -> Result<Self> {
+        Ok(Self {
+            store,
+            runtime: runtime()?,
+        })
+    }
+
+    /// whether a store can be created for the URL, with the schemes of the enabled features
+    pub fn is_supported(url: &Url) -> bool {
+        let scheme = url.scheme();
+        (cfg!(feature = "s3") && matches!(scheme, "s3" | "s3a"))
+            || (cfg!(feature = "gcs") && scheme == "gs")
+            || (cfg!(feature = "azure") && matches!(scheme, "az" | "adl" | "azure" | "abfs" | "abfss"))
+    }
+
+    /// The store of the bucket or the container of the URL. The options not known by the store are ignored, so the
+    /// options of the stores of all the schemes can be given at once.
+    pub fn try_new(url: &Url, options: &HashMap<String, String>) -> Result<Self> {
+        let store: Arc<dyn object_store::ObjectStore> = match url.scheme() {
+            #[cfg(feature = "s3")]
+            "s3" | "s3a" => {
+                use object_store::aws::{AmazonS3Builder, AmazonS3ConfigKey};
+
+                let builder = options.iter().fold(
+                    AmazonS3Builder::from_env().with_url(url.as_str()),
+                    |builder, (key, value)| match key.parse::<AmazonS3ConfigKey>() {
+                        Ok(key) => builder.with_config(key, value),
+                        Err(_) => builder,
+                    },
+                );
+                Arc::new(builder.build().map_err(|e| store_err(url, e))?)
+            }
+            #[cfg(feature = "gcs")]
+            "gs" => {
+                use object_store::gcp::{GoogleCloudStorageBuilder, GoogleConfigKey};
+
+                let builder = options.iter().fold(
+                    GoogleCloudStorageBuilder::from_env().with_url(url.as_str()),
+                    |builder, (key, value)| match key.parse::<GoogleConfigKey>() {
+                        Ok(key) => builder.with_config(key, value),
+                        Err(_) => builder,
+                    },
+                );
+                Arc::new(builder.build().map_err(|e| store_err(url, e))?)
+            }
+            #[cfg(feature = "azure")]
+            "az" | "adl" | "azure" | "abfs" | "abfss" => {
+                use object_store::azure::{AzureConfigKey, MicrosoftAzureBuilder};
+
+                let builder = options.iter().fold(
+                    MicrosoftAzureBuilder::from_env().with_url(url.as_str()),
+                    |builder, (key, value)| match key.parse::<AzureConfigKey>() {
+                        Ok(key) => builder.with_config(key, value),
+                        Err(_) => builder,
+                    },
+                );
+                Arc::new(builder.build().map_err(|e| store_err(url, e))?)
+            }
+            scheme => return internal_err!("no object store can be created for the scheme {}", scheme),
+        };
+
+        Self::new(store)
+    }
+}
+
+fn store_err(url: &Url, e: object_store::Error) -> Error {
+    Error::InternalError(format!("{}: {}", url, e))
+}
+
+/// the path of the object in its bucket, the path of the URL
+fn object_path(url: &Url) -> Result<Path> {
+    Path::from_url_path(url.path()).map_err(|e| Error::InternalError(format!("{}: {}", url, e)))
+}
+
+impl ObjectStore for CloudStore {
+    fn size(&self, location: &Url) -> Result<u64> {
+        let path = object_path(location)?;
+        let meta = self
+            .runtime
+            .block_on(self.store.head(&path))
+            .map_err(|e| store_err(location, e))?;
+
+        Ok(meta.size as u64)
+    }
+
+    fn get_range(&self, location: &Url, range: Range<u64>) -> Result<Bytes> {
+        let path = object_path(location)?;
+        self.runtime
+            .block_on(self.store.get_range(&path, range.start as usize..range.end as usize))
+            .map_err(|e| store_err(location, e))
+    }
+
+    fn list(&self, prefix: &Url) -> Result<Vec<Url>> {
+        let path = object_path(prefix)?;
+        let objects = self
+            .runtime
+            .block_on(self.store.list(Some(&path)).try_collect::<Vec<_>>())
+            .map_err(|e| store_err(prefix, e))?;
+
+        Ok(objects
+            .into_iter()
+            .map(|meta| {
+                let mut url = prefix.clone();
+                url.set_path(&format!("/{}", meta.location));
+                url
+            })
+            .collect())
+    }
+
+    fn get(&self, location: &Url) -> Result<Bytes> {
+        let path = object_path(location)?;
+        self.runtime
+            .block_on(async { self.store.get(&path).await?.bytes().await })
+            .map_err(|e| store_err(location, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_schema;
+    use crate::datasource::file::parquet::write_parquet;
+    use crate::execution::session::ExecuteSession;
+    use crate::test_utils::{assert_batch_eq, build_record_i32};
+    use arrow::datatypes::DataType;
+    use object_store::memory::InMemory;
+    use object_store::{ObjectStore as _, PutPayload};
+
+    #[test]
+    fn test_cloud_store() -> Result<()> {
+        let memory = Arc::new(InMemory::new());
+        let path = std::env::temp_dir().join("qurious_test_cloud_store.parquet");
+        let schema = Arc::new(build_schema!(("a", DataType::Int32)));
+        write_parquet(
+            path.to_str().unwrap(),
+            schema.clone(),
+            &[build_record_i32(schema, vec![vec![1, 2, 3]])],
+        )?;
+        let store = Arc::new(CloudStore::new(memory.clone())?);
+        store
+            .runtime
+            .block_on(memory.put(
+                &Path::from("data/part-0.parquet"),
+                PutPayload::from(std::fs::read(&path)?),
+            ))
+            .map_err(|e| Error::InternalError(e.to_string()))?;
+        let url = Url::parse("memory://bucket/data/part-0.parquet").unwrap();
+        assert_eq!(store.get_range(&url, 0..4)?, Bytes::from_static(b"PAR1"));
+        assert_eq!(store.list(&Url::parse("memory://bucket/data/").unwrap())?, vec![url]);
+
+        // the ranges of the object are read by the scans of the session the store is registered on
+        let session = ExecuteSession::new()?;
+        session.register_object_store("memory://bucket", store)?;
+        let batches = session.sql("SELECT a FROM read_parquet('memory://bucket/data/')")?;
+        assert_batch_eq(
+            &batches,
+            vec!["+---+", "| a |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+"],
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "s3")]
+    #[test]
+    fn test_s3_options() {
+        let url = Url::parse("s3://bucket/data/part-0.parquet").unwrap();
+        let options = HashMap::from([
+            ("aws_region".to_owned(), "eu-west-1".to_owned()),
+            ("aws_access_key_id".to_owned(), "key".to_owned()),
+            ("aws_secret_access_key".to_owned(), "secret".to_owned()),
+            ("google_service_account".to_owned(), "ignored".to_owned()),
+        ]);
+        assert!(CloudStore::is_supported(&url));
+        assert!(CloudStore::try_new(&url, &options).is_ok());
+        assert!(!CloudStore::is_supported(&Url::parse("ftp://host/file").unwrap()));
+    }
+}
@@ -0,0 +1,191 @@
+use std::ops::Range;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use bytes::Bytes;
+use reqwest::blocking::{Client, Response};
+use reqwest::header::{CONTENT_LENGTH, RANGE};
+use reqwest::StatusCode;
+use url::Url;
+
+use super::ObjectStore;
+use crate::error::{Error, Result};
+use crate::internal_err;
+
+/// The files of the HTTP and HTTPS servers, read with range requests so only the parts of a file a reader decodes
+/// are downloaded, like the footer and the row groups of a parquet file. The redirects are followed, the files of a
+/// server can not be listed.
+#[derive(Debug)]
+pub struct HttpStore {
+    timeout: Duration,
+    /// the client of the requests, created by the first request
+    client: OnceLock<Client>,
+}
+
+impl Default for HttpStore {
+    fn default() -> Self {
+        Self::with_timeout(Duration::from_secs(30))
+    }
+}
+
+impl HttpStore {
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            client: OnceLock::new(),
+        }
+    }
+
+    fn client(&self) -> Result<&Client> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+        let client = Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .map_err(|e| Error::InternalError(format!("failed to create the HTTP client: {}", e)))?;
+
+        Ok(self.client.get_or_init(|| client))
+    }
+
+    /// send the request, an error status is an error
+    fn send(&self, location: &Url, request: reqwest::blocking::RequestBuilder) -> Result<Response> {
+        request
+            .send()
+            .and_then(Response::error_for_status)
+            .map_err(|e| Error::InternalError(format!("{}: {}", location, e)))
+    }
+
+    fn bytes(location: &Url, response: Response) -> Result<Bytes> {
+        response
+            .bytes()
+            .map_err(|e| Error::InternalError(format!("{}: {}", location, e)))
+    }
+}
+
+impl ObjectStore for HttpStore {
+    fn size(&self, location: &Url) -> Result<u64> {
+        let response = self.send(location, self.client()?.head(location.clone()))?;
+        // the body of the response to a HEAD request is empty, the size is the header
+        let size = response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|len| len.to_str().ok()?.parse().ok());
+        match size {
+            Some(size) => Ok(size),
+            None => internal_err!("the size of {} is unknown", location),
+        }
+    }
+
+    fn get_range(&self, location: &Url, range: Range<u64>) -> Result<Bytes> {
+        if range.is_empty() {
+            return Ok(Bytes::new());
+        }
+        let request = self
+            .client()?
+            .get(location.clone())
+            .header(RANGE, format!("bytes={}-{}", range.start, range.end - 1));
+        let response = self.send(location, request)?;
+        let partial = response.status() == StatusCode::PARTIAL_CONTENT;
+        let body = Self::bytes(location, response)?;
+        if partial {
+            return Ok(body);
+        }
+
+        // the server ignored the range and returned the whole file
+        let end = (range.end as usize).min(body.len());
+        Ok(body.slice((range.start as usize).min(end)..end))
+    }
+
+    fn list(&self, prefix: &Url) -> Result<Vec<Url>> {
+        internal_err!("the files of the HTTP server of {} can not be listed", prefix)
+    }
+
+    fn get(&self, location: &Url) -> Result<Bytes> {
+        let response = self.send(location, self.client()?.get(location.clone()))?;
+        Self::bytes(location, response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_schema;
+    use crate::datasource::file::parquet::{read_parquet, write_parquet};
+    use crate::test_utils::{assert_batch_eq, build_record_i32};
+    use arrow::datatypes::DataType;
+    use std::io::{BufRead, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    /// serve the file on a local port, the requested ranges are recorded
+    fn serve(file: Vec<u8>, ranges: Arc<Mutex<Vec<String>>>) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/data/file.parquet", listener.local_addr().unwrap())).unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut lines = std::io::BufReader::new(&stream).lines().map_while(|line| line.ok());
+                let method = lines.next().unwrap_or_default();
+                let range = lines.take_while(|line| !line.is_empty()).find_map(|line| {
+                    line.to_lowercase()
+                        .strip_prefix("range: bytes=")
+                        .map(|range| range.to_owned())
+                });
+
+                let response = match range.and_then(|range| {
+                    let (start, end) = range.split_once('-')?;
+                    Some((start.parse::<usize>().ok()?, end.parse::<usize>().ok()?))
+                }) {
+                    Some((start, end)) => {
+                        ranges.lock().unwrap().push(format!("{}-{}", start, end));
+                        let body = &file[start..=end.min(file.len() - 1)];
+                        [
+                            format!(
+                                "HTTP/1.1 206 Partial Content\r\nConnection: close\r\nContent-Length: {}\r\n\r\n",
+                                body.len()
+                            )
+                            .into_bytes(),
+                            body.to_vec(),
+                        ]
+                        .concat()
+                    }
+                    None if method.starts_with("HEAD") => format!(
+                        "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: {}\r\n\r\n",
+                        file.len()
+                    )
+                    .into_bytes(),
+                    None => b"HTTP/1.1 404 Not Found\r\nConnection: close\r\nContent-Length: 0\r\n\r\n".to_vec(),
+                };
+                let _ = stream.write_all(&response);
+            }
+        });
+
+        url
+    }
+
+    #[test]
+    fn test_read_http_parquet() -> Result<()> {
+        let schema = Arc::new(build_schema!(("a", DataType::Int32)));
+        let batch = build_record_i32(schema.clone(), vec![vec![1, 2, 3]]);
+        let path = std::env::temp_dir().join("qurious_test_read_http_parquet.parquet");
+        write_parquet(path.to_str().unwrap(), schema, &[batch])?;
+        let file = std::fs::read(&path)?;
+
+        let ranges = Arc::new(Mutex::new(vec![]));
+        let url = serve(file.clone(), ranges.clone());
+        let batches = read_parquet(url.as_str())?.scan(None, &[])?;
+        assert_batch_eq(
+            &batches,
+            vec!["+---+", "| a |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+"],
+        );
+        // the file is read by ranges, the footer first
+        let ranges = ranges.lock().unwrap();
+        assert_eq!(ranges[0], format!("{}-{}", file.len() - 8, file.len() - 1));
+
+        assert!(HttpStore::default().get(&url).is_err());
+        assert!(HttpStore::default().list(&url).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+#[cfg(any(feature = "s3", feature = "gcs", feature = "azure"))]
+pub mod cloud;
+#[cfg(feature = "http")]
+pub mod http;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::sync::{Arc, OnceLock};
+
+use bytes::{Buf, Bytes};
+use dashmap::DashMap;
+use parquet::file::reader::{ChunkReader, Length};
+use url::Url;
+
+use crate::error::{Error, Result};
+use crate::internal_err;
+
+/// the number of bytes an `ObjectReader` reads from the store at once
+const READ_SIZE: u64 = 8 * 1024 * 1024;
+
+/// The storage of the data files, the local file system or a remote object store like S3, GCS or Azure Blob Storage.
+/// An object is read by ranges of bytes, a reader only fetches the parts of a file it decodes.
+pub trait ObjectStore: Debug + Send + Sync {
+    /// the size in bytes of the object
+    fn size(&self, location: &Url) -> Result<u64>;
+
+    /// the bytes of a range of the object
+    fn get_range(&self, location: &Url, range: Range<u64>) -> Result<Bytes>;
+
+    /// the objects under the prefix, listed recursively
+    fn list(&self, prefix: &Url) -> Result<Vec<Url>>;
+
+    /// the bytes of the whole object
+    fn get(&self, location: &Url) -> Result<Bytes> {
+        let size = self.size(location)?;
+        self.get_range(location, 0..size)
+    }
+}
+
+/// The object stores of the URLs, by the scheme and the authority of the URL like `s3://bucket`, or by the scheme
+/// only for the URLs of any authority. The local file system is registered for `file://` and, with the `http`
+/// feature, the HTTP servers for `http://` and `https://`. The store of an `s3://`, `gs://` or `az://` URL not registered is created when the URL is first read,
+/// with the credentials of the options of the registry or of the environment.
+#[derive(Debug)]
+pub struct ObjectStoreRegistry {
+    stores: DashMap<String, Arc<dyn ObjectStore>>,
+    /// the options of the stores created by the registry, like `aws_access_key_id` or `google_service_account`
+    options: HashMap<String, String>,
+}
+
+impl Default for ObjectStoreRegistry {
+    fn default() -> Self {
+        Self::new(HashMap::new())
+    }
+}
+
+thread_local! {
+    /// the registry of the session planning or executing a statement on the thread
+    static CURRENT_REGISTRY: RefCell<Option<Arc<ObjectStoreRegistry>>> = const { RefCell::new(None) };
+}
+
+impl ObjectStoreRegistry {
+    pub fn new(options: HashMap<String, String>) -> Self {
+        let stores: DashMap<String, Arc<dyn ObjectStore>> = DashMap::new();
+        stores.insert("file://".to_owned(), Arc::new(LocalFileSystem));
+        #[cfg(feature = "http")]
+        {
+            let http = Arc::new(http::HttpStore::default());
+            stores.insert("http://".to_owned(), http.clone());
+            stores.insert("https://".to_owned(), http);
+        }
+
+        Self { stores, options }
+    }
+
+    /// the options of the stores created by the registry
+    pub fn options(&self) -> &HashMap<String, String> {
+        &self.options
+    }
+
+    /// register the store for the URLs with the scheme and the authority of the URL, return the replaced store
+    pub fn register_store(&self, url: &Url, store: Arc<dyn ObjectStore>) -> Option<Arc<dyn ObjectStore>> {
+        self.stores.insert(store_key(url), store)
+    }
+
+    pub fn get_store(&self, url: &Url) -> Result<Arc<dyn ObjectStore>> {
+        let key = store_key(url);
+        let store = self
+            .stores
+            .get(&key)
+            .or_else(|| self.stores.get(&format!("{}://", url.scheme())))
+            .map(|store| store.value().clone());
+        match store {
+            Some(store) => Ok(store),
+            None => self.create_store(url, key),
+        }
+    }
+
+    #[cfg(any(feature = "s3", feature = "gcs", feature = "azure"))]
+    fn create_store(&self, url: &Url, key: String) -> Result<Arc<dyn ObjectStore>> {
+        if !cloud::CloudStore::is_supported(url) {
+            return internal_err!("no object store is registered for {}", key);
+        }
+        let store = self.stores.entry(key).or_try_insert_with(|| {
+            cloud::CloudStore::try_new(url, &self.options).map(|store| Arc::new(store) as Arc<dyn ObjectStore>)
+        })?;
+
+        Ok(store.value().clone())
+    }
+
+    #[cfg(not(any(feature = "s3", feature = "gcs", feature = "azure")))]
+    fn create_store(&self, _url: &Url, key: String) -> Result<Arc<dyn ObjectStore>> {
+        internal_err!("no object store is registered for {}", key)
+    }
+
+    /// Run the function with the registry resolving the stores of the files read on the thread, the registry entered
+    /// before is restored when it returns. A session enters its registry while it plans and executes a statement.
+    pub fn enter<T>(self: &Arc<Self>, f: impl FnOnce() -> T) -> T {
+        struct Restore(Option<Arc<ObjectStoreRegistry>>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT_REGISTRY.with(|current| *current.borrow_mut() = self.0.take());
+            }
+        }
+
+        let _restore = Restore(CURRENT_REGISTRY.with(|current| current.replace(Some(self.clone()))));
+        f()
+    }
+}
+
+fn store_key(url: &Url) -> String {
+    format!(
+        "{}://{}",
+        url.scheme(),
+        &url[url::Position::BeforeHost..url::Position::AfterPort]
+    )
+}
+
+/// the store of the URL in the registry entered on the thread, the local file system and the HTTP servers out of a
+/// session
+pub fn get_store(url: &Url) -> Result<Arc<dyn ObjectStore>> {
+    static DEFAULT: OnceLock<Arc<ObjectStoreRegistry>> = OnceLock::new();
+
+    CURRENT_REGISTRY
+        .with(|current| current.borrow().clone())
+        .unwrap_or_else(|| DEFAULT.get_or_init(Default::default).clone())
+        .get_store(url)
+}
+
+/// Whether the path is the URL of an object in a store other than the local file system
+pub fn is_object_url(path: &str) -> bool {
+    path.split_once("://")
+        .is_some_and(|(scheme, _)| scheme != "file" && scheme.len() > 1)
+}
+
+/// the bytes of the whole object of the URL in the store
+pub fn read_object(store: &dyn ObjectStore, url: &Url) -> Result<Bytes> {
+    store
+        .get(url)
+        .map_err(|e| Error::InternalError(format!("{}: {}", url, e)))
+}
+
+/// The local file system, the URLs are `file://` URLs.
+#[derive(Debug, Default)]
+pub struct LocalFileSystem;
+
+impl LocalFileSystem {
+    fn open(location: &Url) -> Result<File> {
+        File::open(to_file_path(location)?).map_err(|e| Error::InternalError(format!("{}: {}", location.path(), e)))
+    }
+}
+
+impl ObjectStore for LocalFileSystem {
+    fn size(&self, location: &Url) -> Result<u64> {
+        Ok(Self::open(location)?.metadata()?.len())
+    }
+
+    fn get_range(&self, location: &Url, range: Range<u64>) -> Result<Bytes> {
+        let mut file = Self::open(location)?;
+        file.seek(SeekFrom::Start(range.start))?;
+        let mut buf = Vec::with_capacity((range.end - range.start) as usize);
+        file.take(range.end - range.start).read_to_end(&mut buf)?;
+
+        Ok(buf.into())
+    }
+
+    fn list(&self, prefix: &Url) -> Result<Vec<Url>> {
+        fn list_dir(dir: &std::path::Path, urls: &mut Vec<Url>) -> Result<()> {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    list_dir(&path, urls)?;
+                } else if let Ok(url) = Url::from_file_path(&path) {
+                    urls.push(url);
+                }
+            }
+            Ok(())
+        }
+
+        let mut urls = vec![];
+        list_dir(&to_file_path(prefix)?, &mut urls)?;
+
+        Ok(urls)
+    }
+}
+
+/// the path of a local file, the files of the other stores can not be written
+pub fn to_file_path(url: &Url) -> Result<std::path::PathBuf> {
+    url.to_file_path()
+        .map_err(|_| Error::InternalError(format!("{} is not a local file", url)))
+}
+
+/// A reader of an object, the bytes are fetched from the store by ranges as they are read. It is a `Read` and
+/// `Seek` for the readers of streams and a `ChunkReader` for the reader of parquet files.
+#[derive(Debug)]
+pub struct ObjectReader {
+    store: Arc<dyn ObjectStore>,
+    location: Url,
+    size: u64,
+    position: u64,
+    /// the bytes fetched at the offset
+    buffer: Bytes,
+    offset: u64,
+}
+
+impl ObjectReader {
+    pub fn try_new(store: Arc<dyn ObjectStore>, location: &Url) -> Result<Self> {
+        let size = store
+            .size(location)
+            .map_err(|e| Error::InternalError(format!("{}: {}", location, e)))?;
+
+        Ok(Self {
+            store,
+            location: location.clone(),
+            size,
+            position: 0,
+            buffer: Bytes::new(),
+            offset: 0,
+        })
+    }
+
+    fn get_range(&self, range: Range<u64>) -> io::Result<Bytes> {
+        self.store
+            .get_range(&self.location, range)
+            .map_err(|e| io::Error::other(e.to_string()))
+    }
+}
+
+impl Read for ObjectReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.size {
+            return Ok(0);
+        }
+        let end = self.offset + self.buffer.len() as u64;
+        if self.position < self.offset || self.position >= end {
+            self.buffer = self.get_range(self.position..self.size.min(self.position + READ_SIZE))?;
+            self.offset = self.position;
+        }
+
+        let start = (self.position - self.offset) as usize;
+        let len = buf.len().min(self.buffer.len() - start);
+        buf[..len].copy_from_slice(&self.buffer[start..start + len]);
+        self.position += len as u64;
+
+        Ok(len)
+    }
+}
+
+impl Seek for ObjectReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        match position {
+            Some(position) => {
+                self.position = position;
+                Ok(position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before the start of the object",
+            )),
+        }
+    }
+}
+
+impl Length for ObjectReader {
+    fn len(&self) -> u64 {
+        self.size
+    }
+}
+
+impl ChunkReader for ObjectReader {
+    type T = bytes::buf::Reader<Bytes>;
+
+    fn get_read(&self, start: u64) -> parquet::errors::Result<Self::T> {
+        Ok(self.get_range(start..self.size)?.reader())
+    }
+
+    fn get_bytes(&self, start: u64, length: usize) -> parquet::errors::Result<Bytes> {
+        Ok(self.get_range(start..start + length as u64)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_schema;
+    use crate::datasource::file::cache::file_metadata_cache;
+    use crate::datasource::file::listing::read_files;
+    use crate::datasource::file::parquet::{read_parquet, write_parquet};
+    use crate::test_utils::{assert_batch_eq, build_record_i32};
+    use arrow::datatypes::DataType;
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::RwLock;
+
+    /// a store of the objects in memory, counting the listings and the ranges read
+    #[derive(Debug, Default)]
+    struct MemoryStore {
+        objects: RwLock<BTreeMap<String, Bytes>>,
+        lists: AtomicUsize,
+        ranges: AtomicUsize,
+    }
+
+    impl MemoryStore {
+        fn put_parquet(&self, url: &str, values: Vec<i32>) -> Result<()> {
+            let path = std::env::temp_dir().join(format!("qurious_test_{}.parquet", url.replace(['/', ':'], "_")));
+            let schema = Arc::new(build_schema!(("a", DataType::Int32)));
+            let batch = build_record_i32(schema.clone(), vec![values]);
+            write_parquet(path.to_str().unwrap(), schema, &[batch])?;
+            let object = Bytes::from(fs::read(&path)?);
+            self.objects.write().unwrap().insert(url.to_owned(), object);
+            Ok(())
+        }
+    }
+
+    impl ObjectStore for MemoryStore {
+        fn size(&self, location: &Url) -> Result<u64> {
+            self.get(location).map(|object| object.len() as u64)
+        }
+
+        fn get_range(&self, location: &Url, range: Range<u64>) -> Result<Bytes> {
+            self.ranges.fetch_add(1, Ordering::SeqCst);
+            self.get(location)
+                .map(|object| object.slice(range.start as usize..range.end as usize))
+        }
+
+        fn list(&self, prefix: &Url) -> Result<Vec<Url>> {
+            self.lists.fetch_add(1, Ordering::SeqCst);
+            let objects = self.objects.read().unwrap();
+            Ok(objects
+                .keys()
+                .filter(|key| key.starts_with(prefix.as_str()))
+                .map(|key| Url::parse(key).unwrap())
+                .collect())
+        }
+
+        fn get(&self, location: &Url) -> Result<Bytes> {
+            match self.objects.read().unwrap().get(location.as_str()) {
+                Some(object) => Ok(object.clone()),
+                None => internal_err!("{} not found", location),
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_object_store() -> Result<()> {
+        let store = Arc::new(MemoryStore::default());
+        let url = Url::parse("memory://test/data/part-0.parquet").unwrap();
+        let registry = Arc::new(ObjectStoreRegistry::default());
+        registry.register_store(&url, store.clone());
+
+        store.put_parquet("memory://test/data/part-0.parquet", vec![1, 2, 3])?;
+        store.put_parquet("memory://test/more/year=2024/part-1.parquet", vec![4])?;
+
+        // the stores are resolved in the registry entered on the thread
+        assert!(read_parquet(url.as_str()).is_err());
+        let table = registry.enter(|| read_parquet(url.as_str()))?;
+        assert_batch_eq(
+            &table.scan(None, &[])?,
+            vec!["+---+", "| a |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+"],
+        );
+
+        // a directory and a glob pattern list the objects of the store
+        let batches = registry
+            .enter(|| read_files("memory://test/data/", &["parquet"], |path| read_parquet(path)))?
+            .scan(None, &[])?;
+        assert_batch_eq(
+            &batches,
+            vec!["+---+", "| a |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+"],
+        );
+        let batches = registry
+            .enter(|| {
+                read_files("memory://test/*/year=*/*.parquet", &["parquet"], |path| {
+                    read_parquet(path)
+                })
+            })?
+            .scan(None, &[])?;
+        assert_batch_eq(
+            &batches,
+            vec![
+                "+---+------+",
+                "| a | year |",
+                "+---+------+",
+                "| 4 | 2024 |",
+                "+---+------+",
+            ],
+        );
+
+        let mut reader = ObjectReader::try_new(store, &url)?;
+        let mut magic = [0; 4];
+        reader.seek(SeekFrom::End(-4))?;
+        reader.read_exact(&mut magic)?;
+        assert_eq!(&magic, b"PAR1");
+
+        assert!(registry
+            .enter(|| read_parquet("memory://unknown/part-0.parquet"))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_metadata_cache() -> Result<()> {
+        let store = Arc::new(MemoryStore::default());
+        let url = Url::parse("memory://cache/").unwrap();
+        let registry = Arc::new(ObjectStoreRegistry::default());
+        registry.register_store(&url, store.clone());
+        store.put_parquet("memory://cache/data/part-0.parquet", vec![1, 2])?;
+        let read = || {
+            registry
+                .enter(|| read_files("memory://cache/data/", &["parquet"], |path| read_parquet(path)))?
+                .scan(None, &[])
+        };
+
+        let expected = vec!["+---+", "| a |", "+---+", "| 1 |", "| 2 |", "+---+"];
+        assert_batch_eq(&read()?, expected.clone());
+        assert_eq!(store.lists.load(Ordering::SeqCst), 1);
+
+        // the listing and the footer are cached, an object added is not seen until the listing is invalidated
+        store.put_parquet("memory://cache/data/part-1.parquet", vec![3])?;
+        assert_batch_eq(&read()?, expected);
+        assert_eq!(store.lists.load(Ordering::SeqCst), 1);
+        let ranges = store.ranges.load(Ordering::SeqCst);
+        registry.enter(|| read_parquet("memory://cache/data/part-0.parquet"))?;
+        assert_eq!(store.ranges.load(Ordering::SeqCst), ranges);
+
+        file_metadata_cache().invalidate("memory://cache/data/part-1.parquet");
+        assert_batch_eq(
+            &read()?,
+            vec!["+---+", "| a |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+"],
+        );
+        assert_eq!(store.lists.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+}
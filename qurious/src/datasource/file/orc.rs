@@ -0,0 +1,153 @@
+use std::sync::Arc;
+use url::Url;
+
+use arrow::array::{RecordBatch, RecordBatchOptions};
+use arrow::datatypes::{Schema, SchemaRef};
+use orc_rust::projection::ProjectionMask;
+use orc_rust::ArrowReaderBuilder;
+
+use crate::arrow_err;
+use crate::datasource::file::object_store::{get_store, read_object, ObjectStore};
+use crate::datasource::file::DataFilePath;
+use crate::error::{Error, Result};
+use crate::logical::expr::LogicalExpr;
+use crate::provider::table::TableProvider;
+
+/// the maximum number of rows of a batch of a scan
+const BATCH_SIZE: usize = 8192;
+
+/// An ORC file read with the `orc-rust` crate, the columns are the fields of the root struct type and only the
+/// streams of the projected columns are decoded.
+#[derive(Debug)]
+pub struct OrcTable {
+    url: Url,
+    schema: SchemaRef,
+    store: Arc<dyn ObjectStore>,
+}
+
+impl TableProvider for OrcTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Option<Vec<String>>, _filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        let builder = ArrowReaderBuilder::try_new(read_object(self.store.as_ref(), &self.url)?)
+            .map_err(|e| orc_err(&self.url, e))?;
+        let names = match projection {
+            Some(projection) => projection,
+            None => self.schema.fields().iter().map(|f| f.name().clone()).collect(),
+        };
+        let mask = ProjectionMask::named_roots(builder.file_metadata().root_data_type(), &names);
+        let schema = Arc::new(Schema::new(
+            names
+                .iter()
+                .map(|name| self.schema.field_with_name(name).cloned())
+                .collect::<std::result::Result<Vec<_>, _>>()?,
+        ));
+
+        // the columns of the batches of the reader follow the order of the file, not of the projection
+        builder
+            .with_projection(mask)
+            .with_batch_size(BATCH_SIZE)
+            .build()
+            .map(|batch| {
+                let batch = batch.map_err(|e| arrow_err!(e))?;
+                let columns = names
+                    .iter()
+                    .map(|name| batch.column_by_name(name).cloned())
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| Error::InternalError(format!("{}: a projected column is not read", self.url)))?;
+                let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+                RecordBatch::try_new_with_options(schema.clone(), columns, &options).map_err(|e| arrow_err!(e))
+            })
+            .collect()
+    }
+}
+
+pub fn read_orc<T: DataFilePath>(path: T) -> Result<Arc<dyn TableProvider>> {
+    let url = path.to_url()?;
+    let store = get_store(&url)?;
+    let builder = ArrowReaderBuilder::try_new(read_object(store.as_ref(), &url)?)
+        .map_err(|e| Error::InternalError(format!("{} is not an ORC file: {}", url.path(), e)))?;
+    let schema = Arc::new(Schema::new(builder.schema().fields().clone()));
+
+    Ok(Arc::new(OrcTable { url, schema, store }))
+}
+
+fn orc_err(url: &Url, e: orc_rust::error::OrcError) -> Error {
+    Error::InternalError(format!("{}: {}", url.path(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use arrow::array::{BooleanArray, Float64Array, Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field};
+    use orc_rust::ArrowWriterBuilder;
+
+    use super::*;
+    use crate::test_utils::assert_batch_eq;
+
+    fn write_orc(name: &str) -> String {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("score", DataType::Float64, true),
+            Field::new("flag", DataType::Boolean, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec![Some("ab"), None, Some("c")])),
+                Arc::new(Float64Array::from(vec![1.5, 2.5, 3.5])),
+                Arc::new(BooleanArray::from(vec![Some(true), Some(false), None])),
+            ],
+        )
+        .unwrap();
+
+        let path = std::env::temp_dir().join(name);
+        let mut writer = ArrowWriterBuilder::new(File::create(&path).unwrap(), schema)
+            .try_build()
+            .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        path.to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn test_read_orc() -> Result<()> {
+        let source = read_orc(write_orc("qurious_test_read_orc.orc").as_str())?;
+        let schema = source.schema();
+        assert_eq!(schema.field_with_name("id")?.data_type(), &DataType::Int64);
+        assert_eq!(schema.field_with_name("name")?.data_type(), &DataType::Utf8);
+
+        assert_batch_eq(
+            &source.scan(None, &[])?,
+            vec![
+                "+----+------+-------+-------+",
+                "| id | name | score | flag  |",
+                "+----+------+-------+-------+",
+                "| 1  | ab   | 1.5   | true  |",
+                "| 2  |      | 2.5   | false |",
+                "| 3  | c    | 3.5   |       |",
+                "+----+------+-------+-------+",
+            ],
+        );
+        assert_batch_eq(
+            &source.scan(Some(vec!["flag".to_owned(), "name".to_owned()]), &[])?,
+            vec![
+                "+-------+------+",
+                "| flag  | name |",
+                "+-------+------+",
+                "| true  | ab   |",
+                "| false |      |",
+                "|       | c    |",
+                "+-------+------+",
+            ],
+        );
+
+        Ok(())
+    }
+}
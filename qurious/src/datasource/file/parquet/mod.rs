@@ -0,0 +1,458 @@
+pub mod predicate;
+
+use crate::arrow_err;
+use crate::datasource::file::cache::file_metadata_cache;
+use crate::datasource::file::object_store::{get_store, to_file_path, ObjectReader, ObjectStore};
+use crate::datasource::file::DataFilePath;
+use crate::datatypes::scalar::ScalarValue;
+use crate::error::{Error, Result};
+use crate::logical::expr::LogicalExpr;
+use crate::physical::plan::PhysicalPlan;
+use crate::provider::table::{ColumnStatistics, RecordBatchStream, Statistics, TableProvider};
+use arrow::array::{Array, ArrayRef, RecordBatch};
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::error::ArrowError;
+use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+use parquet::arrow::arrow_reader::{
+    ArrowPredicateFn, ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder, RowFilter,
+};
+use parquet::arrow::{ArrowWriter, ProjectionMask};
+use parquet::basic::Compression;
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::properties::{WriterProperties, DEFAULT_MAX_ROW_GROUP_SIZE};
+use predicate::Predicate;
+use std::fs::{self, File};
+use std::sync::Arc;
+use url::Url;
+
+/// the maximum number of rows of a batch read from a parquet file
+const BATCH_SIZE: usize = 8192;
+
+/// A parquet file. A scan opens the file and reads the row groups as its batches are consumed, only the column
+/// chunks of the projected columns are read. The footer of the file is read once and cached across queries. The comparisons of a column with a literal in the filters skip the
+/// row groups and pages their statistics rule out, and the rows they reject before the other columns are decoded.
+/// The rows inserted into a local file are written with the rows of the file to a new file replacing it.
+#[derive(Debug)]
+pub struct ParquetTable {
+    url: Url,
+    schema: SchemaRef,
+    /// the store of the file, resolved when the table is created so the scans on other threads read from it
+    store: Arc<dyn ObjectStore>,
+}
+
+impl TableProvider for ParquetTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        self.scan_stream(projection, filters)?.collect()
+    }
+
+    fn scan_stream(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<RecordBatchStream> {
+        let file = ObjectReader::try_new(self.store.clone(), &self.url)?;
+        let predicate = Predicate::try_new(filters, &self.schema);
+        let metadata = reader_metadata(&self.store, &self.url)?;
+        let mut builder =
+            ParquetRecordBatchReaderBuilder::new_with_metadata(file, metadata).with_batch_size(BATCH_SIZE);
+        if let Some(predicate) = predicate {
+            builder = self.with_predicate(builder, predicate)?;
+        }
+
+        let Some(projection) = projection else {
+            return Ok(Box::new(builder.build()?.map(|batch| batch.map_err(|e| arrow_err!(e)))));
+        };
+        let indices = projection
+            .iter()
+            .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+            .collect::<Result<Vec<_>>>()?;
+        // the reader returns the projected columns in the order of the file
+        let mut roots = indices.clone();
+        roots.sort_unstable();
+        roots.dedup();
+        let order = indices
+            .iter()
+            .map(|i| roots.binary_search(i).unwrap())
+            .collect::<Vec<_>>();
+
+        let mask = ProjectionMask::roots(builder.parquet_schema(), roots);
+        let reader = builder.with_projection(mask).build()?;
+
+        Ok(Box::new(reader.map(move |batch| {
+            batch.and_then(|batch| batch.project(&order)).map_err(|e| arrow_err!(e))
+        })))
+    }
+
+    fn supports_filters_pushdown(&self) -> bool {
+        true
+    }
+
+    /// the statistics of the footer of the file, the sizes and the minimums, maximums and null counts of the row
+    /// groups
+    fn statistics(&self) -> Option<Statistics> {
+        let metadata = reader_metadata(&self.store, &self.url).ok()?;
+        let metadata = metadata.metadata();
+        let column_statistics = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| column_statistics(field.name(), &self.schema, metadata))
+            .collect();
+
+        Some(Statistics {
+            num_rows: Some(metadata.file_metadata().num_rows() as usize),
+            total_byte_size: Some(
+                metadata
+                    .row_groups()
+                    .iter()
+                    .map(|row_group| row_group.total_byte_size() as usize)
+                    .sum(),
+            ),
+            column_statistics,
+        })
+    }
+
+    fn insert(&self, input: Arc<dyn PhysicalPlan>) -> Result<u64> {
+        let batches = input
+            .execute()?
+            .into_iter()
+            .map(|batch| RecordBatch::try_new(self.schema.clone(), batch.columns().to_vec()).map_err(|e| arrow_err!(e)))
+            .collect::<Result<Vec<_>>>()?;
+        let path = to_file_path(&self.url)?;
+
+        // a parquet file can not be appended, it is written again with the rows before the inserted ones and
+        // replaced when complete. The compression of the file is kept.
+        let builder = ParquetRecordBatchReaderBuilder::try_new(ObjectReader::try_new(self.store.clone(), &self.url)?)?;
+        let compression = builder
+            .metadata()
+            .row_groups()
+            .first()
+            .and_then(|row_group| row_group.columns().first())
+            .map(|column| column.compression());
+        let mut rows = builder
+            .with_batch_size(BATCH_SIZE)
+            .build()?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| arrow_err!(e))?;
+        rows.extend(batches.iter().cloned());
+
+        let options = ParquetWriteOptions {
+            compression: compression.unwrap_or(Compression::UNCOMPRESSED),
+            ..Default::default()
+        };
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp = path.with_file_name(format!(".{}.inserting", name));
+        write_parquet_with_options(&temp.to_string_lossy(), self.schema.clone(), &rows, &options)?;
+        fs::rename(&temp, &path)?;
+
+        Ok(batches.iter().map(|batch| batch.num_rows() as u64).sum())
+    }
+}
+
+impl ParquetTable {
+    fn with_predicate(
+        &self,
+        builder: ParquetRecordBatchReaderBuilder<ObjectReader>,
+        predicate: Predicate,
+    ) -> Result<ParquetRecordBatchReaderBuilder<ObjectReader>> {
+        let metadata = builder.metadata().clone();
+        let row_groups = predicate
+            .prune_row_groups(&metadata, &self.schema)?
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, keep)| keep.then_some(i))
+            .collect::<Vec<_>>();
+        let selection = predicate.prune_pages(&metadata, &self.schema, &row_groups)?;
+
+        let roots = predicate
+            .columns()
+            .into_iter()
+            .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+            .collect::<Result<Vec<_>>>()?;
+        let mask = ProjectionMask::roots(builder.parquet_schema(), roots);
+        let filter = ArrowPredicateFn::new(mask, move |batch| {
+            predicate
+                .evaluate(&batch)
+                .map_err(|e| ArrowError::ComputeError(e.to_string()))
+        });
+
+        Ok(builder
+            .with_row_groups(row_groups)
+            .with_row_selection(selection)
+            .with_row_filter(RowFilter::new(vec![Box::new(filter)])))
+    }
+}
+
+/// the statistics of a column from the statistics of the row groups, the minimum or the maximum is unknown if a row
+/// group with a non NULL value does not have it
+fn column_statistics(name: &str, schema: &Schema, metadata: &ParquetMetaData) -> ColumnStatistics {
+    let Ok(converter) = StatisticsConverter::try_new(name, schema, metadata.file_metadata().schema_descr()) else {
+        return ColumnStatistics::default();
+    };
+    let row_groups = metadata.row_groups();
+    let null_counts = converter.row_group_null_counts(row_groups).ok();
+    // a row group of NULLs has no minimum and maximum
+    let all_null = |i: usize| {
+        null_counts
+            .as_ref()
+            .is_some_and(|counts| counts.is_valid(i) && counts.value(i) == row_groups[i].num_rows() as u64)
+    };
+    let reduce = |values: Option<ArrayRef>, pick: fn(ScalarValue, ScalarValue) -> ScalarValue| {
+        let values = values?;
+        (0..values.len())
+            .filter(|i| !all_null(*i))
+            .map(|i| {
+                values
+                    .is_valid(i)
+                    .then(|| ScalarValue::try_from_array(&values, i).ok())
+                    .flatten()
+            })
+            .reduce(|a, b| Some(pick(a?, b?)))?
+    };
+
+    ColumnStatistics {
+        min_value: reduce(
+            converter.row_group_mins(row_groups).ok(),
+            |a, b| if b < a { b } else { a },
+        ),
+        max_value: reduce(
+            converter.row_group_maxes(row_groups).ok(),
+            |a, b| if b > a { b } else { a },
+        ),
+        null_count: null_counts
+            .filter(|counts| counts.null_count() == 0)
+            .map(|counts| counts.values().iter().map(|count| *count as usize).sum()),
+    }
+}
+
+/// the footer of the file with its page index, read once and cached across queries
+fn reader_metadata(store: &Arc<dyn ObjectStore>, url: &Url) -> Result<ArrowReaderMetadata> {
+    file_metadata_cache().parquet_metadata(url, || {
+        let options = ArrowReaderOptions::new().with_page_index(true);
+        Ok(ArrowReaderMetadata::load(
+            &ObjectReader::try_new(store.clone(), url)?,
+            options,
+        )?)
+    })
+}
+
+pub fn read_parquet<T: DataFilePath>(path: T) -> Result<Arc<dyn TableProvider>> {
+    let url = path.to_url()?;
+    let store = get_store(&url)?;
+    let schema = reader_metadata(&store, &url)?.schema().clone();
+
+    Ok(Arc::new(ParquetTable { url, schema, store }))
+}
+
+/// the options of the parquet files read as a table, with `merge_schema` the files may have different types for a
+/// column
+#[derive(Debug, Clone, Default)]
+pub struct ParquetReadOptions {
+    pub merge_schema: bool,
+}
+
+/// the options of a written parquet file, the compression of the column chunks and the maximum number of rows of a
+/// row group
+#[derive(Debug, Clone)]
+pub struct ParquetWriteOptions {
+    pub compression: Compression,
+    pub max_row_group_size: usize,
+}
+
+impl Default for ParquetWriteOptions {
+    fn default() -> Self {
+        Self {
+            compression: Compression::UNCOMPRESSED,
+            max_row_group_size: DEFAULT_MAX_ROW_GROUP_SIZE,
+        }
+    }
+}
+
+/// the compression of the column chunks, like `snappy`, `zstd` or `zstd(3)`, the default level is used without one
+pub fn parse_compression(s: &str) -> Result<Compression> {
+    match s.to_lowercase().as_str() {
+        "none" => Ok(Compression::UNCOMPRESSED),
+        "gzip" => Ok(Compression::GZIP(Default::default())),
+        "brotli" => Ok(Compression::BROTLI(Default::default())),
+        "zstd" => Ok(Compression::ZSTD(Default::default())),
+        s => s.parse().map_err(|e| Error::InternalError(format!("{}", e))),
+    }
+}
+
+/// write the batches to the file, the file is created or truncated
+pub fn write_parquet(path: &str, schema: SchemaRef, batches: &[RecordBatch]) -> Result<()> {
+    write_parquet_with_options(path, schema, batches, &ParquetWriteOptions::default())
+}
+
+pub fn write_parquet_with_options(
+    path: &str,
+    schema: SchemaRef,
+    batches: &[RecordBatch],
+    options: &ParquetWriteOptions,
+) -> Result<()> {
+    let properties = WriterProperties::builder()
+        .set_compression(options.compression)
+        .set_max_row_group_size(options.max_row_group_size)
+        .build();
+    let file = File::create(path)?;
+    let mut writer = ArrowWriter::try_new(file, schema, Some(properties))?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_schema;
+    use crate::datatypes::operator::Operator;
+    use crate::logical::expr::{column, literal, BinaryExpr, InList};
+    use crate::test_utils::{assert_batch_eq, build_record_i32};
+    use arrow::datatypes::DataType;
+    use parquet::file::properties::WriterProperties;
+
+    #[test]
+    fn test_read_parquet() {
+        let source = read_parquet("tests/testdata/file/case1.parquet").unwrap();
+
+        println!(
+            "{}",
+            arrow::util::pretty::pretty_format_batches(&source.scan(None, &[]).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_scan_parquet_stream() -> Result<()> {
+        let schema = Arc::new(build_schema!(("a", DataType::Int32), ("b", DataType::Int32)));
+        let batch = build_record_i32(schema.clone(), vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let path = std::env::temp_dir().join("qurious_test_scan_parquet_stream.parquet");
+        let properties = WriterProperties::builder().set_max_row_group_size(2).build();
+        let mut writer = ArrowWriter::try_new(File::create(&path)?, schema, Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let source = read_parquet(path.to_str().unwrap())?;
+        let mut stream = source.scan_stream(Some(vec!["b".to_owned(), "a".to_owned()]), &[])?;
+        assert_batch_eq(
+            &[stream.next().unwrap()?],
+            vec![
+                "+---+---+",
+                "| b | a |",
+                "+---+---+",
+                "| 4 | 1 |",
+                "| 5 | 2 |",
+                "| 6 | 3 |",
+                "+---+---+",
+            ],
+        );
+        assert!(stream.next().is_none());
+
+        let batches = source.scan(Some(vec![]), &[])?;
+        assert_eq!(batches.iter().map(|batch| batch.num_rows()).sum::<usize>(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parquet_statistics() -> Result<()> {
+        let schema = Arc::new(build_schema!(("a", DataType::Int32, true), ("b", DataType::Utf8, true)));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(arrow::array::Int32Array::from(vec![
+                    Some(3),
+                    None,
+                    Some(-1),
+                    Some(7),
+                    None,
+                ])),
+                Arc::new(arrow::array::StringArray::from(vec!["x", "b", "y", "c", "a"])),
+            ],
+        )?;
+        let path = std::env::temp_dir().join("qurious_test_parquet_statistics.parquet");
+        let properties = WriterProperties::builder().set_max_row_group_size(2).build();
+        let mut writer = ArrowWriter::try_new(File::create(&path)?, schema, Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let statistics = read_parquet(path.to_str().unwrap())?.statistics().unwrap();
+        assert_eq!(statistics.num_rows, Some(5));
+        assert!(statistics.total_byte_size.is_some_and(|size| size > 0));
+        assert_eq!(
+            statistics.column_statistics,
+            vec![
+                ColumnStatistics {
+                    min_value: Some(ScalarValue::Int32(Some(-1))),
+                    max_value: Some(ScalarValue::Int32(Some(7))),
+                    null_count: Some(2),
+                },
+                ColumnStatistics {
+                    min_value: Some(ScalarValue::Utf8(Some("a".to_owned()))),
+                    max_value: Some(ScalarValue::Utf8(Some("y".to_owned()))),
+                    null_count: Some(0),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_parquet_filters() -> Result<()> {
+        let schema = Arc::new(build_schema!(("a", DataType::Int32), ("b", DataType::Int32)));
+        let batch = build_record_i32(schema.clone(), vec![(0..10).collect(), (10..20).collect()]);
+        let path = std::env::temp_dir().join("qurious_test_scan_parquet_filters.parquet");
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(4)
+            .set_data_page_row_count_limit(2)
+            .set_write_batch_size(2)
+            .build();
+        let mut writer = ArrowWriter::try_new(File::create(&path)?, schema.clone(), Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let source = read_parquet(path.to_str().unwrap())?;
+        let compare = |op, value: i64| LogicalExpr::BinaryExpr(BinaryExpr::new(column("a"), op, literal(value)));
+        let in_list = LogicalExpr::InList(InList::new(column("a"), vec![literal(1_i64), literal(9_i64)], false));
+
+        // the row groups have the rows 0 to 3, 4 to 7 and 8 to 9, the pages of each have 2 rows
+        let options = ArrowReaderOptions::new().with_page_index(true);
+        let metadata = ParquetRecordBatchReaderBuilder::try_new_with_options(File::open(&path)?, options)?
+            .metadata()
+            .clone();
+        let predicate = Predicate::try_new(&[compare(Operator::Gt, 5)], &schema).unwrap();
+        assert_eq!(predicate.prune_row_groups(&metadata, &schema)?, vec![false, true, true]);
+        let selection = predicate.prune_pages(&metadata, &schema, &[1, 2])?;
+        assert_eq!((selection.skipped_row_count(), selection.row_count()), (2, 4));
+        let predicate = Predicate::try_new(std::slice::from_ref(&in_list), &schema).unwrap();
+        assert_eq!(predicate.prune_row_groups(&metadata, &schema)?, vec![true, false, true]);
+
+        assert_batch_eq(
+            &source.scan(
+                Some(vec!["b".to_owned()]),
+                &[compare(Operator::Gt, 5), compare(Operator::NotEq, 8)],
+            )?,
+            vec!["+----+", "| b  |", "+----+", "| 16 |", "| 17 |", "| 19 |", "+----+"],
+        );
+        assert_batch_eq(
+            &source.scan(None, &[in_list])?,
+            vec![
+                "+---+----+",
+                "| a | b  |",
+                "+---+----+",
+                "| 1 | 11 |",
+                "| 9 | 19 |",
+                "+---+----+",
+            ],
+        );
+        // a filter that is not a comparison of a column with a literal does not skip rows
+        let filter = LogicalExpr::BinaryExpr(BinaryExpr::new(column("a"), Operator::Lt, column("b")));
+        let batches = source.scan(None, &[filter])?;
+        assert_eq!(batches.iter().map(|batch| batch.num_rows()).sum::<usize>(), 10);
+
+        Ok(())
+    }
+}
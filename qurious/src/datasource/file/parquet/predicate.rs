@@ -0,0 +1,395 @@
+use arrow::array::{Array, ArrayRef, BooleanArray, RecordBatch, Scalar, UInt64Array};
+use arrow::compute::kernels::cmp::{eq, gt, gt_eq, lt, lt_eq, neq};
+use arrow::compute::{and, and_kleene, cast, cast_with_options, is_not_null, is_null, not, or_kleene, CastOptions};
+use arrow::datatypes::{DataType, Schema};
+use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+use parquet::arrow::arrow_reader::{RowSelection, RowSelector};
+use parquet::file::metadata::ParquetMetaData;
+
+use crate::datatypes::operator::Operator;
+use crate::error::{Error, Result};
+use crate::logical::expr::{BinaryExpr, CastExpr, InList, LogicalExpr};
+
+/// A column of a predicate, cast to another type if the filter compares the cast column
+#[derive(Debug, Clone)]
+pub struct ColumnRef {
+    pub(crate) name: String,
+    pub(crate) cast: Option<DataType>,
+}
+
+/// The part of the filters of a scan a parquet file can evaluate, the comparisons of a column with a literal.
+/// It is evaluated against the statistics of the row groups and pages to skip them, and against the rows read
+/// to skip decoding the other columns of the rejected rows.
+#[derive(Debug, Clone)]
+pub enum Predicate {
+    Compare {
+        column: ColumnRef,
+        op: Operator,
+        /// the literal cast to the type of the column, a single value array
+        value: ArrayRef,
+    },
+    IsNull {
+        column: ColumnRef,
+        negated: bool,
+    },
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+}
+
+impl Predicate {
+    /// The predicate of the conjunction of the filters, the filters or their conjuncts that are not comparisons of
+    /// a column with a literal are left out. Returns `None` if nothing is left.
+    pub fn try_new(filters: &[LogicalExpr], schema: &Schema) -> Option<Self> {
+        filters
+            .iter()
+            .filter_map(|filter| Self::from_expr(filter, schema))
+            .reduce(|l, r| Predicate::And(Box::new(l), Box::new(r)))
+    }
+
+    fn from_expr(expr: &LogicalExpr, schema: &Schema) -> Option<Self> {
+        match expr {
+            LogicalExpr::BinaryExpr(BinaryExpr {
+                left,
+                op: Operator::And,
+                right,
+            }) => match (Self::from_expr(left, schema), Self::from_expr(right, schema)) {
+                (Some(l), Some(r)) => Some(Predicate::And(Box::new(l), Box::new(r))),
+                (l, r) => l.or(r),
+            },
+            LogicalExpr::BinaryExpr(BinaryExpr {
+                left,
+                op: Operator::Or,
+                right,
+            }) => Some(Predicate::Or(
+                Box::new(Self::from_expr(left, schema)?),
+                Box::new(Self::from_expr(right, schema)?),
+            )),
+            LogicalExpr::BinaryExpr(BinaryExpr { left, op, right }) => {
+                let (column, op, value) = match (column_ref(left, schema), column_ref(right, schema)) {
+                    (Some(column), None) => (column, *op, right.as_ref()),
+                    (None, Some(column)) => (column, swap(*op)?, left.as_ref()),
+                    _ => return None,
+                };
+                if !matches!(
+                    op,
+                    Operator::Eq | Operator::NotEq | Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq
+                ) {
+                    return None;
+                }
+                let value = literal(value, column_type(&column, schema)?)?;
+
+                Some(Predicate::Compare { column, op, value })
+            }
+            LogicalExpr::IsNull(expr) => Some(Predicate::IsNull {
+                column: column_ref(expr, schema)?,
+                negated: false,
+            }),
+            LogicalExpr::IsNotNull(expr) => Some(Predicate::IsNull {
+                column: column_ref(expr, schema)?,
+                negated: true,
+            }),
+            // `a IN (1, 2)` is `a = 1 OR a = 2` and `a NOT IN (1, 2)` is `a <> 1 AND a <> 2`
+            LogicalExpr::InList(InList { expr, list, negated }) => {
+                let column = column_ref(expr, schema)?;
+                let data_type = column_type(&column, schema)?;
+                let op = if *negated { Operator::NotEq } else { Operator::Eq };
+                list.iter()
+                    .map(|value| {
+                        Some(Predicate::Compare {
+                            column: column.clone(),
+                            op,
+                            value: literal(value, data_type)?,
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>()?
+                    .into_iter()
+                    .reduce(|l, r| match negated {
+                        true => Predicate::And(Box::new(l), Box::new(r)),
+                        false => Predicate::Or(Box::new(l), Box::new(r)),
+                    })
+            }
+            _ => None,
+        }
+    }
+
+    /// The names of the columns the predicate reads
+    pub fn columns(&self) -> Vec<&str> {
+        let mut columns = match self {
+            Predicate::Compare { column, .. } | Predicate::IsNull { column, .. } => vec![column.name.as_str()],
+            Predicate::And(l, r) | Predicate::Or(l, r) => {
+                let mut columns = l.columns();
+                columns.extend(r.columns());
+                columns
+            }
+        };
+        columns.sort_unstable();
+        columns.dedup();
+        columns
+    }
+
+    /// Evaluate the predicate against the rows of the batch, a row is rejected if the predicate is false or NULL
+    pub fn evaluate(&self, batch: &RecordBatch) -> Result<BooleanArray> {
+        let result = self.evaluate_kleene(batch)?;
+        Ok(match result.null_count() {
+            0 => result,
+            _ => arrow::compute::prep_null_mask_filter(&result),
+        })
+    }
+
+    fn evaluate_kleene(&self, batch: &RecordBatch) -> Result<BooleanArray> {
+        match self {
+            Predicate::Compare { column, op, value } => {
+                let array = column.array(batch)?;
+                Ok(compare(&array, *op, &Scalar::new(value.clone()))?)
+            }
+            Predicate::IsNull { column, negated } => {
+                let array = column.array(batch)?;
+                Ok(if *negated {
+                    is_not_null(&array)?
+                } else {
+                    is_null(&array)?
+                })
+            }
+            Predicate::And(l, r) => Ok(and_kleene(&l.evaluate_kleene(batch)?, &r.evaluate_kleene(batch)?)?),
+            Predicate::Or(l, r) => Ok(or_kleene(&l.evaluate_kleene(batch)?, &r.evaluate_kleene(batch)?)?),
+        }
+    }
+
+    /// Whether each row group may contain a row the predicate accepts, according to the statistics of its column
+    /// chunks. A row group without statistics is kept.
+    pub fn prune_row_groups(&self, metadata: &ParquetMetaData, schema: &Schema) -> Result<Vec<bool>> {
+        let row_groups = metadata.row_groups();
+        let prune = |column: &ColumnRef, f: &dyn Fn(&StatisticsConverter) -> Result<Vec<bool>>| {
+            match StatisticsConverter::try_new(&column.name, schema, metadata.file_metadata().schema_descr()) {
+                Ok(converter) => f(&converter),
+                Err(_) => Ok(vec![true; row_groups.len()]),
+            }
+        };
+
+        match self {
+            Predicate::Compare { column, op, value } => prune(column, &|converter| {
+                let mins = column.cast_statistics(converter.row_group_mins(row_groups)?)?;
+                let maxes = column.cast_statistics(converter.row_group_maxes(row_groups)?)?;
+                may_match(&mins, &maxes, *op, value)
+            }),
+            Predicate::IsNull { column, negated } => prune(column, &|converter| {
+                let null_counts = converter.row_group_null_counts(row_groups)?;
+                let row_counts = row_groups.iter().map(|row_group| Some(row_group.num_rows() as u64));
+                Ok(may_be_null(&null_counts, row_counts, *negated))
+            }),
+            Predicate::And(l, r) => Ok(zip(
+                l.prune_row_groups(metadata, schema)?,
+                r.prune_row_groups(metadata, schema)?,
+                |l, r| l && r,
+            )),
+            Predicate::Or(l, r) => Ok(zip(
+                l.prune_row_groups(metadata, schema)?,
+                r.prune_row_groups(metadata, schema)?,
+                |l, r| l || r,
+            )),
+        }
+    }
+
+    /// The rows of the row groups that may be accepted by the predicate, according to the statistics of the pages
+    /// in the page index. The pages of the columns do not have the same boundaries, so the rows of each comparison
+    /// are selected before they are combined. All rows are selected if the file has no page index.
+    pub fn prune_pages(
+        &self,
+        metadata: &ParquetMetaData,
+        schema: &Schema,
+        row_groups: &[usize],
+    ) -> Result<RowSelection> {
+        let select_all = || {
+            let rows = row_groups
+                .iter()
+                .map(|i| metadata.row_group(*i).num_rows() as usize)
+                .sum::<usize>();
+            RowSelection::from(vec![RowSelector::select(rows)])
+        };
+        let (Some(column_index), Some(offset_index)) = (metadata.column_index(), metadata.offset_index()) else {
+            return Ok(select_all());
+        };
+        let prune = |column: &ColumnRef, f: &dyn Fn(&StatisticsConverter) -> Result<Vec<bool>>| {
+            let parquet_schema = metadata.file_metadata().schema_descr();
+            let Ok(converter) = StatisticsConverter::try_new(&column.name, schema, parquet_schema) else {
+                return Ok(select_all());
+            };
+            let Some(row_counts) = converter.data_page_row_counts(offset_index, metadata.row_groups(), row_groups)?
+            else {
+                return Ok(select_all());
+            };
+            let keep = f(&converter)?;
+
+            Ok(RowSelection::from(
+                keep.into_iter()
+                    .zip(row_counts.iter())
+                    .filter_map(|(keep, rows)| match (keep, rows.unwrap_or_default() as usize) {
+                        (_, 0) => None,
+                        (true, rows) => Some(RowSelector::select(rows)),
+                        (false, rows) => Some(RowSelector::skip(rows)),
+                    })
+                    .collect::<Vec<_>>(),
+            ))
+        };
+
+        match self {
+            Predicate::Compare { column, op, value } => prune(column, &|converter| {
+                let mins = converter.data_page_mins(column_index, offset_index, row_groups)?;
+                let maxes = converter.data_page_maxes(column_index, offset_index, row_groups)?;
+                may_match(
+                    &column.cast_statistics(mins)?,
+                    &column.cast_statistics(maxes)?,
+                    *op,
+                    value,
+                )
+            }),
+            Predicate::IsNull { column, negated } => prune(column, &|converter| {
+                let null_counts = converter.data_page_null_counts(column_index, offset_index, row_groups)?;
+                let row_counts = converter.data_page_row_counts(offset_index, metadata.row_groups(), row_groups)?;
+                Ok(may_be_null(&null_counts, row_counts.iter().flatten(), *negated))
+            }),
+            Predicate::And(l, r) => Ok(l
+                .prune_pages(metadata, schema, row_groups)?
+                .intersection(&r.prune_pages(metadata, schema, row_groups)?)),
+            Predicate::Or(l, r) => Ok(l
+                .prune_pages(metadata, schema, row_groups)?
+                .union(&r.prune_pages(metadata, schema, row_groups)?)),
+        }
+    }
+}
+
+impl ColumnRef {
+    fn array(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let array = batch
+            .column_by_name(&self.name)
+            .ok_or_else(|| Error::InternalError(format!("column {} not found", self.name)))?;
+        match &self.cast {
+            Some(data_type) => Ok(cast(array, data_type)?),
+            None => Ok(array.clone()),
+        }
+    }
+
+    fn cast_statistics(&self, statistics: ArrayRef) -> Result<ArrayRef> {
+        match &self.cast {
+            Some(data_type) => Ok(cast(&statistics, data_type)?),
+            None => Ok(statistics),
+        }
+    }
+}
+
+/// The column of a comparison, a column of the file or a column cast to a type that keeps the order of its values
+fn column_ref(expr: &LogicalExpr, schema: &Schema) -> Option<ColumnRef> {
+    match expr {
+        LogicalExpr::Column(column) if !column.is_outer_ref && schema.field_with_name(&column.name).is_ok() => {
+            Some(ColumnRef {
+                name: column.name.clone(),
+                cast: None,
+            })
+        }
+        LogicalExpr::Cast(CastExpr { expr, data_type }) => {
+            let column = column_ref(expr, schema)?;
+            let from = schema.field_with_name(&column.name).ok()?.data_type();
+            if column.cast.is_some() || !keeps_order(from, data_type) {
+                return None;
+            }
+            Some(ColumnRef {
+                cast: Some(data_type.clone()),
+                ..column
+            })
+        }
+        _ => None,
+    }
+}
+
+fn keeps_order(from: &DataType, to: &DataType) -> bool {
+    (from.is_numeric() && to.is_numeric())
+        || (from.is_temporal() && to.is_temporal())
+        || (matches!(from, DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View)
+            && matches!(to, DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View))
+}
+
+fn column_type<'a>(column: &'a ColumnRef, schema: &'a Schema) -> Option<&'a DataType> {
+    match &column.cast {
+        Some(data_type) => Some(data_type),
+        None => schema.field_with_name(&column.name).ok().map(|field| field.data_type()),
+    }
+}
+
+/// The literal as a single value array of the type, if the literal can be cast to the type without changing it
+fn literal(expr: &LogicalExpr, data_type: &DataType) -> Option<ArrayRef> {
+    let LogicalExpr::Literal(value) = expr else {
+        return None;
+    };
+    let array = value.to_array(1).ok()?;
+    if array.data_type() == data_type {
+        return Some(array);
+    }
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let casted = cast_with_options(&array, data_type, &options).ok()?;
+    // a cast that loses precision, like 1.5 to 1, would change the result of the comparison
+    let restored = cast_with_options(&casted, array.data_type(), &options).ok()?;
+
+    (restored.as_ref() == array.as_ref()).then_some(casted)
+}
+
+/// the operator with its operands swapped, `1 < a` is `a > 1`
+fn swap(op: Operator) -> Option<Operator> {
+    Some(match op {
+        Operator::Eq | Operator::NotEq => op,
+        Operator::Lt => Operator::Gt,
+        Operator::LtEq => Operator::GtEq,
+        Operator::Gt => Operator::Lt,
+        Operator::GtEq => Operator::LtEq,
+        _ => return None,
+    })
+}
+
+fn compare(left: &dyn arrow::array::Datum, op: Operator, right: &dyn arrow::array::Datum) -> Result<BooleanArray> {
+    Ok(match op {
+        Operator::Eq => eq(left, right)?,
+        Operator::NotEq => neq(left, right)?,
+        Operator::Lt => lt(left, right)?,
+        Operator::LtEq => lt_eq(left, right)?,
+        Operator::Gt => gt(left, right)?,
+        _ => gt_eq(left, right)?,
+    })
+}
+
+/// Whether values between the minimums and maximums may be accepted by the comparison with the value, unknown
+/// statistics may match
+fn may_match(mins: &ArrayRef, maxes: &ArrayRef, op: Operator, value: &ArrayRef) -> Result<Vec<bool>> {
+    let value = Scalar::new(value.clone());
+    let result = match op {
+        Operator::Eq => and(&lt_eq(mins, &value)?, &gt_eq(maxes, &value)?)?,
+        // all values are equal to the value only if the minimum and maximum are
+        Operator::NotEq => not(&and(&eq(mins, &value)?, &eq(maxes, &value)?)?)?,
+        Operator::Lt | Operator::LtEq => compare(mins, op, &value)?,
+        _ => compare(maxes, op, &value)?,
+    };
+
+    Ok(result.iter().map(|v| v.unwrap_or(true)).collect())
+}
+
+/// Whether a container may have a null value, or a non null value if negated, unknown counts may
+fn may_be_null(
+    null_counts: &UInt64Array,
+    row_counts: impl IntoIterator<Item = Option<u64>>,
+    negated: bool,
+) -> Vec<bool> {
+    null_counts
+        .iter()
+        .zip(row_counts)
+        .map(|(nulls, rows)| match (nulls, rows, negated) {
+            (Some(nulls), _, false) => nulls > 0,
+            (Some(nulls), Some(rows), true) => nulls < rows,
+            _ => true,
+        })
+        .collect()
+}
+
+fn zip(l: Vec<bool>, r: Vec<bool>, f: impl Fn(bool, bool) -> bool) -> Vec<bool> {
+    l.into_iter().zip(r).map(|(l, r)| f(l, r)).collect()
+}
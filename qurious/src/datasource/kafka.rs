@@ -0,0 +1,514 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use arrow::array::{ArrayRef, Int32Array, Int64Array, RecordBatch, TimestampMillisecondArray};
+use arrow::compute::concat_batches;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::json::reader::infer_json_schema;
+use arrow::json::ReaderBuilder;
+use rdkafka::consumer::{BaseConsumer, Consumer as _};
+use rdkafka::error::KafkaError;
+use rdkafka::{ClientConfig, Message, Offset, TopicPartitionList};
+
+use crate::arrow_err;
+#[cfg(feature = "avro")]
+use crate::datasource::file::avro::AvroRecordDecoder;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::logical::expr::LogicalExpr;
+use crate::provider::table::{TableProvider, TableType};
+
+/// the columns of the partition, the offset and the timestamp of the messages following the fields of the values
+const PARTITION: &str = "_partition";
+const OFFSET: &str = "_offset";
+const TIMESTAMP: &str = "_timestamp";
+
+/// the time the metadata of the topic and the first messages the json schema is inferred from are waited for
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// the format of the values of the messages of a topic
+#[derive(Debug, Clone)]
+pub enum KafkaFormat {
+    /// a json object per message, the schema is inferred from the first messages of the topic without a schema
+    Json(Option<SchemaRef>),
+    /// the Avro binary encoding of a record of the json Avro schema, without the framing of a schema registry
+    #[cfg(feature = "avro")]
+    Avro(String),
+}
+
+/// the offset the partitions of a topic are consumed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KafkaStartOffset {
+    Earliest,
+    Latest,
+}
+
+#[derive(Debug, Clone)]
+pub struct KafkaReadOptions {
+    pub format: KafkaFormat,
+    pub start_offset: KafkaStartOffset,
+    /// the number of micro-batches kept by the table, the oldest batches are dropped by a poll buffering more
+    pub max_buffered_batches: usize,
+    /// the time a poll waits for new messages
+    pub max_wait: Duration,
+    /// the number of messages the schema of the json values is inferred from
+    pub schema_infer_max_records: usize,
+}
+
+impl Default for KafkaReadOptions {
+    fn default() -> Self {
+        Self {
+            format: KafkaFormat::Json(None),
+            start_offset: KafkaStartOffset::Earliest,
+            max_buffered_batches: 64,
+            max_wait: Duration::from_millis(100),
+            schema_infer_max_records: 1000,
+        }
+    }
+}
+
+/// the decoder of the values of the messages
+#[derive(Debug)]
+enum ValueDecoder {
+    Json(SchemaRef),
+    #[cfg(feature = "avro")]
+    Avro(AvroRecordDecoder),
+}
+
+impl ValueDecoder {
+    /// the columns of the fields of the values, the fields of a tombstone are null
+    fn decode(&self, values: &[Option<&[u8]>]) -> Result<Vec<ArrayRef>> {
+        match self {
+            ValueDecoder::Json(schema) => {
+                let batches = ReaderBuilder::new(schema.clone())
+                    .with_batch_size(values.len().max(1))
+                    .build(Cursor::new(json_lines(values)))
+                    .map_err(|e| arrow_err!(e))?
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|e| arrow_err!(e))?;
+                let batch = concat_batches(schema, &batches)?;
+                if batch.num_rows() != values.len() {
+                    return internal_err!("a value of the messages of the kafka topic is not a json object");
+                }
+
+                Ok(batch.columns().to_vec())
+            }
+            #[cfg(feature = "avro")]
+            ValueDecoder::Avro(decoder) => decoder.decode(values),
+        }
+    }
+}
+
+/// the values as newline-delimited json, a tombstone is an empty object
+fn json_lines(values: &[Option<&[u8]>]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.unwrap_or(b"{}").iter().chain(b"\n"))
+        .copied()
+        .collect()
+}
+
+/// a message of a partition of the topic, the timestamp is in milliseconds since the epoch
+#[derive(Debug)]
+struct Record {
+    partition: i32,
+    offset: i64,
+    timestamp: i64,
+    value: Option<Vec<u8>>,
+}
+
+fn kafka_err(topic: &str, e: KafkaError) -> Error {
+    Error::InternalError(format!("kafka topic {}: {}", topic, e))
+}
+
+/// the consumer of the partitions of a topic assigned to the table, and the buffered micro-batches
+struct Consumer {
+    consumer: BaseConsumer,
+    /// the messages read by the inference of the schema, buffered by the first poll
+    pending: Vec<Record>,
+    batches: VecDeque<RecordBatch>,
+}
+
+impl Consumer {
+    /// the messages received within the time, the messages already received by the client are not waited for
+    fn poll(&mut self, topic: &str, max_wait: Duration, max_records: usize) -> Result<Vec<Record>> {
+        let mut records = std::mem::take(&mut self.pending);
+        let mut timeout = max_wait;
+        while records.len() < max_records {
+            let message = match self.consumer.poll(timeout) {
+                Some(message) => message.map_err(|e| kafka_err(topic, e))?,
+                None => break,
+            };
+            records.push(Record {
+                partition: message.partition(),
+                offset: message.offset(),
+                timestamp: message.timestamp().to_millis().unwrap_or_default(),
+                value: message.payload().map(<[u8]>::to_vec),
+            });
+            timeout = Duration::ZERO;
+        }
+
+        Ok(records)
+    }
+}
+
+impl fmt::Debug for Consumer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("pending", &self.pending.len())
+            .field("batches", &self.batches.len())
+            .finish()
+    }
+}
+
+/// An experimental append-only table of the messages of a kafka topic read with the `rdkafka` crate. The partitions of
+/// the topic are assigned to a consumer without a consumer group, a poll buffers the messages received since the
+/// previous poll as a micro-batch and a scan polls and returns the buffered micro-batches. The columns are the fields
+/// of the values followed by the partition, the offset and the timestamp of the messages.
+#[derive(Debug)]
+pub struct KafkaTable {
+    topic: String,
+    schema: SchemaRef,
+    decoder: ValueDecoder,
+    max_buffered_batches: usize,
+    max_wait: Duration,
+    consumer: Mutex<Consumer>,
+}
+
+/// the table of the topic of the comma separated brokers like `localhost:9092,localhost:9093`
+pub fn read_kafka(brokers: &str, topic: &str, options: KafkaReadOptions) -> Result<Arc<dyn TableProvider>> {
+    KafkaTable::try_new(brokers, topic, options).map(|table| Arc::new(table) as _)
+}
+
+impl KafkaTable {
+    pub fn try_new(brokers: &str, topic: &str, options: KafkaReadOptions) -> Result<Self> {
+        if brokers.split(',').all(|broker| broker.trim().is_empty()) {
+            return internal_err!("no kafka broker to connect to");
+        }
+
+        // the offsets are not committed, a table reads the topic from the start offset each time it is created
+        let consumer: BaseConsumer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("group.id", "qurious")
+            .set("enable.auto.commit", "false")
+            .create()
+            .map_err(|e| kafka_err(topic, e))?;
+        let metadata = consumer
+            .fetch_metadata(Some(topic), CONNECT_TIMEOUT)
+            .map_err(|e| kafka_err(topic, e))?;
+        let partitions = match metadata.topics().first() {
+            Some(metadata) if metadata.error().is_none() && !metadata.partitions().is_empty() => {
+                metadata.partitions().iter().map(|p| p.id()).collect::<Vec<_>>()
+            }
+            _ => return internal_err!("the kafka topic {} does not exist", topic),
+        };
+        let offset = match options.start_offset {
+            KafkaStartOffset::Earliest => Offset::Beginning,
+            KafkaStartOffset::Latest => Offset::End,
+        };
+        let mut assignment = TopicPartitionList::new();
+        for partition in partitions {
+            assignment
+                .add_partition_offset(topic, partition, offset)
+                .map_err(|e| kafka_err(topic, e))?;
+        }
+        consumer.assign(&assignment).map_err(|e| kafka_err(topic, e))?;
+        let mut consumer = Consumer {
+            consumer,
+            pending: vec![],
+            batches: VecDeque::new(),
+        };
+
+        let decoder = match options.format {
+            KafkaFormat::Json(Some(schema)) => ValueDecoder::Json(nullable(&schema)),
+            KafkaFormat::Json(None) => {
+                let records = consumer.poll(topic, CONNECT_TIMEOUT, options.schema_infer_max_records)?;
+                if records.is_empty() {
+                    return internal_err!(
+                        "the schema of the json messages of the kafka topic {} can not be inferred without messages",
+                        topic
+                    );
+                }
+                let values = records.iter().map(|record| record.value.as_deref()).collect::<Vec<_>>();
+                let (schema, _) =
+                    infer_json_schema(Cursor::new(json_lines(&values)), Some(options.schema_infer_max_records))?;
+                consumer.pending = records;
+                ValueDecoder::Json(nullable(&schema))
+            }
+            #[cfg(feature = "avro")]
+            KafkaFormat::Avro(schema) => ValueDecoder::Avro(AvroRecordDecoder::try_new(&schema)?),
+        };
+        let values = match &decoder {
+            ValueDecoder::Json(schema) => schema.clone(),
+            #[cfg(feature = "avro")]
+            ValueDecoder::Avro(decoder) => nullable(&decoder.schema()),
+        };
+        let mut fields = values.fields().iter().map(|f| f.as_ref().clone()).collect::<Vec<_>>();
+        for field in [
+            Field::new(PARTITION, DataType::Int32, false),
+            Field::new(OFFSET, DataType::Int64, false),
+            Field::new(
+                TIMESTAMP,
+                DataType::Timestamp(TimeUnit::Millisecond, Some("+00:00".into())),
+                false,
+            ),
+        ] {
+            if values.field_with_name(field.name()).is_ok() {
+                return internal_err!("the values of the kafka topic {} have a field {}", topic, field.name());
+            }
+            fields.push(field);
+        }
+
+        Ok(Self {
+            topic: topic.to_owned(),
+            schema: Arc::new(Schema::new(fields)),
+            decoder,
+            max_buffered_batches: options.max_buffered_batches.max(1),
+            max_wait: options.max_wait,
+            consumer: Mutex::new(consumer),
+        })
+    }
+
+    /// buffer the messages received since the previous poll as a micro-batch, return the number of messages
+    pub fn poll(&self) -> Result<usize> {
+        let mut consumer = self.consumer.lock().map_err(|e| Error::InternalError(e.to_string()))?;
+        let records = consumer.poll(&self.topic, self.max_wait, usize::MAX)?;
+        if records.is_empty() {
+            return Ok(0);
+        }
+
+        let values = records.iter().map(|record| record.value.as_deref()).collect::<Vec<_>>();
+        let mut columns = self.decoder.decode(&values)?;
+        columns.push(Arc::new(records.iter().map(|r| r.partition).collect::<Int32Array>()));
+        columns.push(Arc::new(records.iter().map(|r| r.offset).collect::<Int64Array>()));
+        columns.push(Arc::new(
+            TimestampMillisecondArray::from_iter_values(records.iter().map(|r| r.timestamp)).with_timezone("+00:00"),
+        ));
+        consumer
+            .batches
+            .push_back(RecordBatch::try_new(self.schema.clone(), columns)?);
+        while consumer.batches.len() > self.max_buffered_batches {
+            consumer.batches.pop_front();
+        }
+
+        Ok(records.len())
+    }
+}
+
+impl TableProvider for KafkaTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Option<Vec<String>>, _filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        self.poll()?;
+        let indices = match projection {
+            Some(projection) => projection
+                .iter()
+                .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+                .collect::<Result<Vec<_>>>()?,
+            None => (0..self.schema.fields().len()).collect(),
+        };
+
+        let consumer = self.consumer.lock().map_err(|e| Error::InternalError(e.to_string()))?;
+        consumer
+            .batches
+            .iter()
+            .map(|batch| batch.project(&indices).map_err(|e| arrow_err!(e)))
+            .collect()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::External
+    }
+}
+
+/// the schema of the same fields, all nullable since the fields of a tombstone are null
+fn nullable(schema: &Schema) -> SchemaRef {
+    Arc::new(Schema::new(
+        schema
+            .fields()
+            .iter()
+            .map(|field| field.as_ref().clone().with_nullable(true))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
+    use rdkafka::client::DefaultClientContext;
+    use rdkafka::producer::{BaseProducer, BaseRecord, Producer};
+
+    use super::*;
+    use crate::execution::session::ExecuteSession;
+    use crate::test_utils::assert_batch_eq;
+
+    /// the broker of the kafka service of docker-compose.yaml
+    const BROKERS: &str = "localhost:9092";
+
+    /// a new topic of the partitions, the messages of a partition are its values
+    fn create_topic(partitions: Vec<Vec<Option<&[u8]>>>) -> String {
+        let topic = format!(
+            "qurious_test_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        );
+        let config = ClientConfig::new().set("bootstrap.servers", BROKERS).clone();
+        let admin: AdminClient<DefaultClientContext> = config.create().unwrap();
+        let new_topic = NewTopic::new(&topic, partitions.len() as i32, TopicReplication::Fixed(1));
+        for result in futures::executor::block_on(admin.create_topics([&new_topic], &AdminOptions::new())).unwrap() {
+            result.unwrap();
+        }
+        for (partition, values) in partitions.into_iter().enumerate() {
+            produce(&topic, partition as i32, values);
+        }
+        topic
+    }
+
+    fn produce(topic: &str, partition: i32, values: Vec<Option<&[u8]>>) {
+        let producer: BaseProducer = ClientConfig::new().set("bootstrap.servers", BROKERS).create().unwrap();
+        for value in values {
+            let record = BaseRecord::<(), [u8]>::to(topic).partition(partition);
+            let record = match value {
+                Some(value) => record.payload(value),
+                None => record,
+            };
+            producer.send(record).map_err(|(e, _)| e).unwrap();
+        }
+        producer.flush(CONNECT_TIMEOUT).unwrap();
+    }
+
+    #[test]
+    fn test_read_kafka() -> Result<()> {
+        let topic = create_topic(vec![
+            vec![Some(br#"{"id": 1, "name": "a"}"#), Some(br#"{"id": 2, "name": "b"}"#)],
+            vec![Some(br#"{"id": 3}"#)],
+        ]);
+
+        let table = read_kafka(BROKERS, &topic, KafkaReadOptions::default())?;
+        assert_eq!(
+            table
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.name().as_str())
+                .collect::<Vec<_>>(),
+            vec!["id", "name", "_partition", "_offset", "_timestamp"]
+        );
+        let session = ExecuteSession::new()?;
+        session.register_table("events", table)?;
+        let batches = session.sql("SELECT id, name, _partition, _offset FROM events ORDER BY id")?;
+        assert_batch_eq(
+            &batches,
+            vec![
+                "+----+------+------------+---------+",
+                "| id | name | _partition | _offset |",
+                "+----+------+------------+---------+",
+                "| 1  | a    | 0          | 0       |",
+                "| 2  | b    | 0          | 1       |",
+                "| 3  |      | 1          | 0       |",
+                "+----+------+------------+---------+",
+            ],
+        );
+
+        // the messages written since the previous scan are buffered as another micro-batch
+        produce(&topic, 1, vec![None, Some(br#"{"id": 4, "name": "d"}"#)]);
+        std::thread::sleep(Duration::from_secs(1));
+        let batches = session.sql("SELECT count(*) AS c, count(id) AS n, sum(id) AS s FROM events")?;
+        assert_batch_eq(
+            &batches,
+            vec![
+                "+---+---+----+",
+                "| c | n | s  |",
+                "+---+---+----+",
+                "| 5 | 4 | 10 |",
+                "+---+---+----+",
+            ],
+        );
+
+        let options = KafkaReadOptions {
+            format: KafkaFormat::Json(Some(Arc::new(Schema::new(vec![Field::new(
+                "id",
+                DataType::Int64,
+                false,
+            )])))),
+            start_offset: KafkaStartOffset::Latest,
+            max_buffered_batches: 1,
+            max_wait: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let table = KafkaTable::try_new(BROKERS, &topic, options.clone())?;
+        assert_eq!(table.poll()?, 0);
+        produce(&topic, 0, vec![Some(br#"{"id": 5}"#)]);
+        assert_eq!(table.poll()?, 1);
+        produce(&topic, 0, vec![Some(br#"{"id": 6}"#)]);
+        produce(&topic, 1, vec![Some(br#"{"id": 7}"#)]);
+        std::thread::sleep(Duration::from_secs(1));
+        assert_eq!(table.poll()?, 2);
+        // the oldest micro-batch is dropped
+        let batches = table.scan(Some(vec!["id".to_owned()]), &[])?;
+        assert_batch_eq(
+            &batches,
+            vec!["+----+", "| id |", "+----+", "| 6  |", "| 7  |", "+----+"],
+        );
+
+        produce(&topic, 0, vec![Some(b"[1]")]);
+        assert!(table.poll().is_err());
+        assert!(KafkaTable::try_new(BROKERS, "missing", options.clone()).is_err());
+        assert!(KafkaTable::try_new("", &topic, options).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "avro")]
+    fn test_read_kafka_avro() -> Result<()> {
+        use apache_avro::types::Value;
+        use apache_avro::{to_avro_datum, Schema as AvroSchema};
+
+        let schema = r#"{"type": "record", "name": "User", "fields": [
+            {"name": "id", "type": "long"},
+            {"name": "name", "type": "string"}
+        ]}"#;
+        let avro_schema = AvroSchema::parse_str(schema).unwrap();
+        let value = |id: i64, name: &str| {
+            let record = Value::Record(vec![
+                ("id".to_owned(), Value::Long(id)),
+                ("name".to_owned(), Value::String(name.to_owned())),
+            ]);
+            to_avro_datum(&avro_schema, record).unwrap()
+        };
+        let topic = create_topic(vec![vec![Some(&value(1, "a")), None, Some(&value(-2, "bc"))]]);
+
+        let options = KafkaReadOptions {
+            format: KafkaFormat::Avro(schema.to_owned()),
+            max_wait: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let table = read_kafka(BROKERS, &topic, options)?;
+        let batches = table.scan(
+            Some(vec!["id".to_owned(), "name".to_owned(), "_offset".to_owned()]),
+            &[],
+        )?;
+        assert_batch_eq(
+            &batches,
+            vec![
+                "+----+------+---------+",
+                "| id | name | _offset |",
+                "+----+------+---------+",
+                "| 1  | a    | 0       |",
+                "|    |      | 1       |",
+                "| -2 | bc   | 2       |",
+                "+----+------+---------+",
+            ],
+        );
+
+        Ok(())
+    }
+}
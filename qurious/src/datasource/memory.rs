@@ -2,56 +2,238 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::RwLock;
 
-use arrow::array::AsArray;
-use arrow::compute::filter_record_batch;
+use arrow::array::{Array, ArrayRef, AsArray, UInt32Array};
+use arrow::compute::kernels::zip::zip;
+use arrow::compute::{cast, concat_batches, filter_record_batch, interleave, prep_null_mask_filter};
 use arrow::datatypes::Schema;
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
+use arrow::row::{OwnedRow, RowConverter, SortField};
 
 use crate::arrow_err;
+use crate::datatypes::operator::Operator;
 use crate::datatypes::scalar::ScalarValue;
 use crate::error::Error;
 use crate::error::Result;
-use crate::logical::expr::LogicalExpr;
+use crate::internal_err;
+use crate::logical::expr::{split_conjunction, BinaryExpr, LogicalExpr};
 use crate::physical::expr::PhysicalExpr;
 use crate::physical::plan::PhysicalPlan;
-use crate::provider::table::{TableProvider, TableType};
+use crate::provider::table::{ColumnStatistics, FilterPushdown, Statistics, TableProvider, TableType};
 use std::fmt::{self, Debug, Formatter};
 
 #[derive(Clone)]
 pub struct MemoryTable {
     schema: SchemaRef,
-    data: Arc<RwLock<Vec<RecordBatch>>>,
+    /// the batches of each partition of the table, a scan reads the partitions in order
+    data: Arc<RwLock<Vec<Vec<RecordBatch>>>>,
     column_defaults: HashMap<String, ScalarValue>,
+    /// the hash indexes of the rows, rebuilt when the rows change
+    indexes: Arc<RwLock<Vec<HashIndex>>>,
 }
 
 impl MemoryTable {
     pub fn try_new(schema: SchemaRef, data: Vec<RecordBatch>) -> Result<Self> {
+        Self::try_new_partitioned(schema, vec![data])
+    }
+
+    /// A table of several partitions, the partitions are scanned independently and can be read in parallel.
+    /// The rows inserted later are appended to the partition with the fewest rows.
+    pub fn try_new_partitioned(schema: SchemaRef, partitions: Vec<Vec<RecordBatch>>) -> Result<Self> {
+        if partitions.is_empty() {
+            return internal_err!("A memory table must have at least one partition");
+        }
+
         Ok(Self {
             schema,
-            data: Arc::new(RwLock::new(data)),
+            data: Arc::new(RwLock::new(partitions)),
             column_defaults: HashMap::new(),
+            indexes: Arc::new(RwLock::new(vec![])),
         })
     }
 
+    /// replace all the data of the table, used as the work table of a recursive query
+    pub(crate) fn replace(&self, data: Vec<RecordBatch>) -> Result<()> {
+        let mut partitions = self.data.write().map_err(|e| Error::InternalError(e.to_string()))?;
+        *partitions = split_partitions(data, partitions.len());
+        self.update_indexes(&partitions)
+    }
+
     pub fn with_default_values(self, columns_defaults: HashMap<String, ScalarValue>) -> Self {
         Self {
             column_defaults: columns_defaults,
             ..self
         }
     }
+
+    /// Keep a hash index of the columns, the rows with given values of the columns are looked up without a scan,
+    /// by the equalities of the columns with literals in the filters of a scan and by the joins on the columns.
+    pub fn with_index(self, columns: &[String]) -> Result<Self> {
+        let columns = columns
+            .iter()
+            .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+            .collect::<Result<Vec<_>>>()?;
+        {
+            let partitions = self.data.read().map_err(|e| Error::InternalError(e.to_string()))?;
+            let mut indexes = self.indexes.write().map_err(|e| Error::InternalError(e.to_string()))?;
+            indexes.push(HashIndex::try_new(&self.schema, columns, &partitions)?);
+        }
+
+        Ok(self)
+    }
+
+    /// rebuild the indexes from the rows of the table, called with the lock of the rows held
+    fn update_indexes(&self, partitions: &[Vec<RecordBatch>]) -> Result<()> {
+        let mut indexes = self.indexes.write().map_err(|e| Error::InternalError(e.to_string()))?;
+        for index in indexes.iter_mut() {
+            *index = HashIndex::try_new(&self.schema, std::mem::take(&mut index.columns), partitions)?;
+        }
+
+        Ok(())
+    }
+
+    /// the rows of the scan found in an index by the equalities of the filters, None if no index has a value for
+    /// each of its columns
+    fn scan_index(&self, filters: &[LogicalExpr]) -> Result<Option<RecordBatch>> {
+        let values = filters
+            .iter()
+            .flat_map(split_conjunction)
+            .filter_map(equality_literal)
+            .collect::<Vec<_>>();
+        let indexes = self.indexes.read().map_err(|e| Error::InternalError(e.to_string()))?;
+        let Some(index) = indexes.iter().find(|index| {
+            index
+                .columns
+                .iter()
+                .all(|i| values.iter().any(|(name, _)| name == self.schema.field(*i).name()))
+        }) else {
+            return Ok(None);
+        };
+        let (columns, keys): (Vec<_>, Vec<_>) = index
+            .columns
+            .iter()
+            .map(|i| {
+                let name = self.schema.field(*i).name();
+                let (_, value) = values.iter().find(|(column, _)| column == name).unwrap();
+                value.to_array(1).map(|key| (name.clone(), key))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+        drop(indexes);
+
+        self.lookup(&columns, &keys).map(|(_, batch)| Some(batch))
+    }
+
+    /// the batches with the columns of the projection, all the columns without a projection
+    fn project(&self, batches: Vec<RecordBatch>, projection: Option<Vec<String>>) -> Result<Vec<RecordBatch>> {
+        let Some(projection) = projection else {
+            return Ok(batches);
+        };
+        let indices = projection
+            .iter()
+            .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
+            .collect::<Result<Vec<_>>>()?;
+
+        batches
+            .iter()
+            .map(|batch| batch.project(&indices).map_err(|e| arrow_err!(e)))
+            .collect()
+    }
 }
 
 impl Default for MemoryTable {
     fn default() -> Self {
         Self {
             schema: Arc::new(Schema::empty()),
-            data: Arc::new(RwLock::new(vec![])),
+            data: Arc::new(RwLock::new(vec![vec![]])),
             column_defaults: HashMap::new(),
+            indexes: Arc::new(RwLock::new(vec![])),
         }
     }
 }
 
+/// A hash index of the rows of a table by the values of some of its columns
+struct HashIndex {
+    columns: Vec<usize>,
+    converter: RowConverter,
+    /// the batch, numbered across the partitions, and the row of the rows with each values of the columns, the rows
+    /// with a NULL are not indexed
+    rows: HashMap<OwnedRow, Vec<(u32, u32)>>,
+}
+
+impl HashIndex {
+    fn try_new(schema: &Schema, columns: Vec<usize>, partitions: &[Vec<RecordBatch>]) -> Result<Self> {
+        let fields = columns
+            .iter()
+            .map(|i| SortField::new(schema.field(*i).data_type().clone()))
+            .collect();
+        let converter = RowConverter::new(fields).map_err(|e| arrow_err!(e))?;
+        let mut rows: HashMap<OwnedRow, Vec<(u32, u32)>> = HashMap::new();
+        for (b, batch) in partitions.iter().flatten().enumerate() {
+            let keys = columns.iter().map(|i| batch.column(*i).clone()).collect::<Vec<_>>();
+            for (r, row) in key_rows(&converter, &keys)?.into_iter().enumerate() {
+                if let Some(row) = row {
+                    rows.entry(row).or_default().push((b as u32, r as u32));
+                }
+            }
+        }
+
+        Ok(Self {
+            columns,
+            converter,
+            rows,
+        })
+    }
+}
+
+/// the row format of the keys of each row, None if any key is NULL
+fn key_rows(converter: &RowConverter, keys: &[ArrayRef]) -> Result<Vec<Option<OwnedRow>>> {
+    let rows = converter.convert_columns(keys).map_err(|e| arrow_err!(e))?;
+
+    Ok(rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| keys.iter().all(|key| key.is_valid(i)).then(|| row.owned()))
+        .collect())
+}
+
+/// the batches in contiguous runs of about the same number of rows, one for each of the partitions
+fn split_partitions(batches: Vec<RecordBatch>, partitions: usize) -> Vec<Vec<RecordBatch>> {
+    let num_rows = batches.iter().map(|batch| batch.num_rows()).sum::<usize>();
+    let partition_rows = num_rows.div_ceil(partitions).max(1);
+    let mut split = vec![vec![]; partitions];
+    let mut rows = 0;
+    for batch in batches {
+        // a batch goes to the partition of its first row
+        let partition = (rows / partition_rows).min(partitions - 1);
+        rows += batch.num_rows();
+        split[partition].push(batch);
+    }
+
+    split
+}
+
+/// the column and the literal of an equality of a column with a literal
+fn equality_literal(expr: &LogicalExpr) -> Option<(String, ScalarValue)> {
+    match expr {
+        LogicalExpr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Eq,
+            right,
+        }) => match (left.as_ref(), right.as_ref()) {
+            (LogicalExpr::Column(column), LogicalExpr::Literal(value))
+            | (LogicalExpr::Literal(value), LogicalExpr::Column(column))
+                if !column.is_outer_ref =>
+            {
+                Some((column.name.clone(), value.clone()))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 impl Debug for MemoryTable {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("MemoryTable")
@@ -67,22 +249,144 @@ impl TableProvider for MemoryTable {
         self.schema.clone()
     }
 
-    fn scan(&self, projection: Option<Vec<String>>, _filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
-        let batches = self.data.read().map_err(|e| Error::InternalError(e.to_string()))?;
+    fn scan(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        let batches = match self.scan_index(filters)? {
+            Some(batch) => vec![batch],
+            None => self
+                .data
+                .read()
+                .map_err(|e| Error::InternalError(e.to_string()))?
+                .concat(),
+        };
 
-        if let Some(projection) = projection {
-            let indices = projection
-                .iter()
-                .map(|name| self.schema.index_of(name).map_err(|e| arrow_err!(e)))
-                .collect::<Result<Vec<_>>>()?;
+        self.project(batches, projection)
+    }
+
+    fn output_partitions(&self) -> usize {
+        self.data.read().map_or(1, |partitions| partitions.len())
+    }
+
+    /// the rows of a partition are not looked up in the indexes
+    fn scan_partition(
+        &self,
+        partition: usize,
+        projection: Option<Vec<String>>,
+        _filters: &[LogicalExpr],
+    ) -> Result<Vec<RecordBatch>> {
+        let partitions = self.data.read().map_err(|e| Error::InternalError(e.to_string()))?;
+        let Some(batches) = partitions.get(partition) else {
+            return internal_err!("The table has no partition {}", partition);
+        };
+        let batches = batches.clone();
+        drop(partitions);
 
-            batches
+        self.project(batches, projection)
+    }
+
+    /// an equality of an indexed column with a literal is looked up in the index, the rows are filtered again
+    fn supports_filter_pushdown(&self, filter: &LogicalExpr) -> FilterPushdown {
+        let Some((name, _)) = equality_literal(filter) else {
+            return FilterPushdown::Unsupported;
+        };
+        let Ok(indexes) = self.indexes.read() else {
+            return FilterPushdown::Unsupported;
+        };
+        match indexes
+            .iter()
+            .any(|index| index.columns.iter().any(|i| self.schema.field(*i).name() == &name))
+        {
+            true => FilterPushdown::Inexact,
+            false => FilterPushdown::Unsupported,
+        }
+    }
+
+    fn statistics(&self) -> Option<Statistics> {
+        let partitions = self.data.read().ok()?;
+        let batches = partitions.iter().flatten().collect::<Vec<_>>();
+        let column_statistics = (0..self.schema.fields().len())
+            .map(|i| ColumnStatistics {
+                null_count: Some(batches.iter().map(|batch| batch.column(i).null_count()).sum()),
+                ..Default::default()
+            })
+            .collect();
+        Some(Statistics {
+            num_rows: Some(batches.iter().map(|batch| batch.num_rows()).sum()),
+            total_byte_size: Some(batches.iter().map(|batch| batch.get_array_memory_size()).sum()),
+            column_statistics,
+        })
+    }
+
+    fn has_index(&self, columns: &[String]) -> bool {
+        let Ok(indexes) = self.indexes.read() else {
+            return false;
+        };
+        indexes.iter().any(|index| {
+            index.columns.len() == columns.len()
+                && index
+                    .columns
+                    .iter()
+                    .all(|i| columns.contains(self.schema.field(*i).name()))
+        })
+    }
+
+    fn lookup(&self, columns: &[String], keys: &[ArrayRef]) -> Result<(UInt32Array, RecordBatch)> {
+        let partitions = self.data.read().map_err(|e| Error::InternalError(e.to_string()))?;
+        let indexes = self.indexes.read().map_err(|e| Error::InternalError(e.to_string()))?;
+        // the keys in the order and with the types of the columns of the index
+        let index_keys = |index: &HashIndex| {
+            index
+                .columns
                 .iter()
-                .map(|batch| batch.project(&indices).map_err(|e| arrow_err!(e)))
-                .collect()
-        } else {
-            Ok(batches.clone())
+                .map(|i| {
+                    let field = self.schema.field(*i);
+                    let key = columns.iter().position(|name| name == field.name())?;
+                    Some(cast(&keys[key], field.data_type()).map_err(|e| arrow_err!(e)))
+                })
+                .collect::<Option<Result<Vec<_>>>>()
+        };
+        let Some((index, keys)) = indexes
+            .iter()
+            .filter(|index| index.columns.len() == columns.len())
+            .find_map(|index| index_keys(index).map(|keys| (index, keys)))
+        else {
+            return internal_err!("The table has no index of the columns [{}]", columns.join(", "));
+        };
+
+        // (batch, row, key) of each row equal to a key, in the order of the table
+        let mut found = key_rows(&index.converter, &keys?)?
+            .iter()
+            .enumerate()
+            .filter_map(|(key, row)| Some((key, index.rows.get(row.as_ref()?)?)))
+            .flat_map(|(key, rows)| rows.iter().map(move |(batch, row)| (*batch, *row, key as u32)))
+            .collect::<Vec<_>>();
+        found.sort_unstable();
+        if found.is_empty() {
+            return Ok((
+                UInt32Array::from(Vec::<u32>::new()),
+                RecordBatch::new_empty(self.schema.clone()),
+            ));
         }
+
+        let rows = found
+            .iter()
+            .map(|(batch, row, _)| (*batch as usize, *row as usize))
+            .collect::<Vec<_>>();
+        let columns = (0..self.schema.fields().len())
+            .map(|i| {
+                let arrays = partitions
+                    .iter()
+                    .flatten()
+                    .map(|batch| batch.column(i).as_ref())
+                    .collect::<Vec<_>>();
+                interleave(&arrays, &rows).map_err(|e| arrow_err!(e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let batch = RecordBatch::try_new(self.schema.clone(), columns).map_err(|e| arrow_err!(e))?;
+
+        Ok((
+            UInt32Array::from_iter_values(found.iter().map(|(_, _, key)| *key)),
+            batch,
+        ))
     }
 
     fn get_column_default(&self, column: &str) -> Option<ScalarValue> {
@@ -90,41 +394,240 @@ impl TableProvider for MemoryTable {
     }
 
     fn insert(&self, input: Arc<dyn PhysicalPlan>) -> Result<u64> {
-        let mut batces = self.data.write().map_err(|e| Error::InternalError(e.to_string()))?;
-        let mut input_batch = input.execute()?;
+        // the input may scan this table, execute it before locking the data
+        // the rows are stored with the schema of the table, a NULL value of a NOT NULL column is an error
+        let mut input_batch = input
+            .execute()?
+            .into_iter()
+            .map(|batch| RecordBatch::try_new(self.schema.clone(), batch.columns().to_vec()).map_err(|e| arrow_err!(e)))
+            .collect::<Result<Vec<_>>>()?;
+        let row_effected = input_batch.iter().map(|batch| batch.num_rows()).sum::<usize>() as u64;
+
+        let mut partitions = self.data.write().map_err(|e| Error::InternalError(e.to_string()))?;
+        let mut partition_rows = partitions
+            .iter()
+            .map(|batches| batches.iter().map(|batch| batch.num_rows()).sum::<usize>())
+            .collect::<Vec<_>>();
+        for batch in input_batch.drain(..) {
+            // the first of the partitions with the fewest rows
+            let (partition, _) = partition_rows
+                .iter()
+                .enumerate()
+                .min_by_key(|(i, rows)| (**rows, *i))
+                .unwrap();
+            partition_rows[partition] += batch.num_rows();
+            partitions[partition].push(batch);
+        }
+        self.update_indexes(&partitions)?;
+
+        Ok(row_effected)
+    }
+
+    fn update(&self, values: Vec<Arc<dyn PhysicalExpr>>, filter: Option<Arc<dyn PhysicalExpr>>) -> Result<u64> {
+        // the new values may scan this table, evaluate them before locking the data
+        let partitions = self
+            .data
+            .read()
+            .map_err(|e| Error::InternalError(e.to_string()))?
+            .clone();
+
+        let mut row_effected = 0;
+        let mut update_batch = |batch: &RecordBatch| {
+            let columns = values
+                .iter()
+                .map(|value| value.evaluate(batch))
+                .collect::<Result<Vec<_>>>()?;
+            let columns = if let Some(predicate) = &filter {
+                // a row is not updated if the predicate is NULL
+                let mask = predicate.evaluate(batch)?;
+                let mask = match mask.as_boolean() {
+                    mask if mask.null_count() > 0 => prep_null_mask_filter(mask),
+                    mask => mask.clone(),
+                };
+                row_effected += mask.true_count();
+
+                columns
+                    .iter()
+                    .zip(batch.columns())
+                    .map(|(new, old)| zip(&mask, new, old).map_err(|e| arrow_err!(e)))
+                    .collect::<Result<Vec<_>>>()?
+            } else {
+                row_effected += batch.num_rows();
+                columns
+            };
+
+            RecordBatch::try_new(self.schema.clone(), columns).map_err(|e| arrow_err!(e))
+        };
+        let new_partitions = partitions
+            .iter()
+            .map(|batches| batches.iter().map(&mut update_batch).collect::<Result<Vec<_>>>())
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut data = self.data.write().map_err(|e| Error::InternalError(e.to_string()))?;
+        *data = new_partitions;
+        self.update_indexes(&data)?;
+
+        Ok(row_effected as u64)
+    }
+
+    fn upsert(&self, input: Arc<dyn PhysicalPlan>, keys: Vec<usize>) -> Result<u64> {
+        // the input joins this table, execute it before locking the data
+        let input_batch = concat_batches(&input.schema(), &input.execute()?).map_err(|e| arrow_err!(e))?;
+        let partitions = self
+            .data
+            .read()
+            .map_err(|e| Error::InternalError(e.to_string()))?
+            .clone();
+        let batch = concat_batches(&self.schema, partitions.iter().flatten()).map_err(|e| arrow_err!(e))?;
+
+        let fields = keys
+            .iter()
+            .map(|i| SortField::new(self.schema.field(*i).data_type().clone()))
+            .collect();
+        let converter = RowConverter::new(fields).map_err(|e| arrow_err!(e))?;
+        let batch_key_rows = |batch: &RecordBatch| {
+            let columns = keys.iter().map(|i| batch.column(*i).clone()).collect::<Vec<_>>();
+            key_rows(&converter, &columns)
+        };
+
+        let mut new_rows = HashMap::new();
+        for (index, row) in batch_key_rows(&input_batch)?.into_iter().enumerate() {
+            if let Some(row) = row {
+                if new_rows.insert(row, index).is_some() {
+                    return internal_err!("ON CONFLICT DO UPDATE command cannot affect row a second time");
+                }
+            }
+        }
 
-        batces.append(&mut input_batch);
+        // (0, i) keeps the i-th record, (1, j) takes the j-th input row
+        let mut replaced = vec![false; input_batch.num_rows()];
+        let mut indices = batch_key_rows(&batch)?
+            .iter()
+            .enumerate()
+            .map(|(i, row)| match row.as_ref().and_then(|row| new_rows.get(row)) {
+                Some(j) => {
+                    replaced[*j] = true;
+                    (1, *j)
+                }
+                None => (0, i),
+            })
+            .collect::<Vec<_>>();
+        indices.extend((0..input_batch.num_rows()).filter(|j| !replaced[*j]).map(|j| (1, j)));
 
-        Ok(input_batch.iter().map(|batch| batch.num_rows()).sum::<usize>() as u64)
+        let columns = (0..self.schema.fields().len())
+            .map(|i| {
+                interleave(&[batch.column(i).as_ref(), input_batch.column(i).as_ref()], &indices)
+                    .map_err(|e| arrow_err!(e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let new_batch = RecordBatch::try_new(self.schema.clone(), columns).map_err(|e| arrow_err!(e))?;
+
+        // the rows are split again into the partitions of the table
+        let partition_rows = new_batch.num_rows().div_ceil(partitions.len()).max(1);
+        let new_batches = (0..new_batch.num_rows())
+            .step_by(partition_rows)
+            .map(|offset| new_batch.slice(offset, partition_rows.min(new_batch.num_rows() - offset)))
+            .collect();
+
+        let mut data = self.data.write().map_err(|e| Error::InternalError(e.to_string()))?;
+        *data = split_partitions(new_batches, partitions.len());
+        self.update_indexes(&data)?;
+
+        Ok(input_batch.num_rows() as u64)
     }
 
     fn delete(&self, filter: Option<Arc<dyn PhysicalExpr>>) -> Result<u64> {
+        // the predicate may scan this table, evaluate it before locking the data
+        let partitions = self
+            .data
+            .read()
+            .map_err(|e| Error::InternalError(format!("delete error: {}", e)))?
+            .clone();
+
+        let (new_partitions, row_effected) = if let Some(predicate) = filter {
+            let mut row_effected = 0;
+            let mut delete_rows = |batch: &RecordBatch| {
+                // a row is kept if the predicate is NULL
+                let mask = predicate.evaluate(batch)?;
+                let mask = match mask.as_boolean() {
+                    mask if mask.null_count() > 0 => prep_null_mask_filter(mask),
+                    mask => mask.clone(),
+                };
+                row_effected += mask.true_count();
+
+                let mask = arrow::compute::not(&mask)?;
+                let filtered_batch = filter_record_batch(batch, &mask)?;
+                Ok(filtered_batch)
+            };
+            let new_partitions = partitions
+                .iter()
+                .map(|batches| {
+                    batches
+                        .iter()
+                        .map(&mut delete_rows)
+                        .collect::<Result<Vec<RecordBatch>>>()
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            (new_partitions, row_effected)
+        } else {
+            (
+                vec![vec![]; partitions.len()],
+                partitions.iter().flatten().map(|batch| batch.num_rows()).sum::<usize>(),
+            )
+        };
+
         let mut data = self
             .data
             .write()
             .map_err(|e| Error::InternalError(format!("delete error: {}", e)))?;
+        *data = new_partitions;
+        self.update_indexes(&data)?;
 
-        if let Some(predicate) = filter {
-            let new_batch = data
-                .iter()
-                .map(|batch| {
-                    let mask = predicate.evaluate(batch)?;
-                    let mask = arrow::compute::not(mask.as_boolean())?;
-                    let filtered_batch = filter_record_batch(batch, &mask)?;
-                    Ok(filtered_batch)
-                })
-                .collect::<Result<Vec<RecordBatch>>>()?;
+        Ok(row_effected as u64)
+    }
 
-            data.clear();
-            data.extend(new_batch);
+    fn truncate(&self) -> Result<u64> {
+        let mut data = self
+            .data
+            .write()
+            .map_err(|e| Error::InternalError(format!("truncate error: {}", e)))?;
 
-            Ok(data.iter().map(|batch| batch.num_rows()).sum::<usize>() as u64)
-        } else {
-            let row_effected = data.iter().map(|batch| batch.num_rows()).sum::<usize>() as u64;
-            data.clear();
+        let empty = vec![vec![]; data.len()];
+        let partitions = std::mem::replace(&mut *data, empty);
+        let rows = partitions.iter().flatten().map(|batch| batch.num_rows() as u64).sum();
+        self.update_indexes(&data)?;
 
-            Ok(row_effected)
-        }
+        Ok(rows)
+    }
+
+    /// the copy shares the arrays of the immutable batches with the table, its own changes replace them
+    fn fork(&self) -> Result<Option<Arc<dyn TableProvider>>> {
+        let data = self.data.read().map_err(|e| Error::InternalError(e.to_string()))?;
+        let indexes = self
+            .indexes
+            .read()
+            .map_err(|e| Error::InternalError(e.to_string()))?
+            .iter()
+            .map(|index| HashIndex::try_new(&self.schema, index.columns.clone(), &data))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(Arc::new(MemoryTable {
+            schema: self.schema.clone(),
+            data: Arc::new(RwLock::new(data.clone())),
+            column_defaults: self.column_defaults.clone(),
+            indexes: Arc::new(RwLock::new(indexes)),
+        })))
+    }
+
+    /// the batches are immutable, a snapshot shares their arrays with the table
+    fn snapshot(&self) -> Result<Option<Vec<RecordBatch>>> {
+        let data = self.data.read().map_err(|e| Error::InternalError(e.to_string()))?;
+        Ok(Some(data.concat()))
+    }
+
+    fn restore(&self, snapshot: Vec<RecordBatch>) -> Result<()> {
+        self.replace(snapshot)
     }
 
     fn table_type(&self) -> TableType {
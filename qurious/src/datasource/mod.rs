@@ -1,4 +1,9 @@
 #[cfg(feature = "connectorx")]
 pub mod connectorx;
 pub mod file;
+#[cfg(feature = "kafka")]
+pub mod kafka;
 pub mod memory;
+#[cfg(any(feature = "connectorx", feature = "mysql", feature = "sqlite"))]
+pub mod remote;
+pub mod view;
@@ -0,0 +1,316 @@
+#[cfg(feature = "mysql")]
+pub mod mysql;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use arrow::datatypes::{Decimal128Type, DecimalType, Schema};
+use arrow::temporal_conversions::{date32_to_datetime, time64us_to_time, timestamp_ms_to_datetime};
+use itertools::Itertools;
+
+use crate::datatypes::operator::Operator;
+use crate::datatypes::scalar::ScalarValue;
+use crate::logical::expr::{BinaryExpr, CastExpr, InList, Like, LogicalExpr};
+
+/// The SQL dialect of a remote database, the projection and the filters of a scan are sent to the database in it.
+/// The time zone of the sessions of the connectors is UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Dialect {
+    #[cfg(feature = "connectorx")]
+    Postgres,
+    #[cfg(feature = "mysql")]
+    MySql,
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+}
+
+impl Dialect {
+    pub(crate) fn quote_identifier(self, name: &str) -> String {
+        match self {
+            #[cfg(feature = "connectorx")]
+            Dialect::Postgres => format!("\"{}\"", name.replace('"', "\"\"")),
+            #[cfg(feature = "mysql")]
+            Dialect::MySql => format!("`{}`", name.replace('`', "``")),
+            #[cfg(feature = "sqlite")]
+            Dialect::Sqlite => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+
+    pub(crate) fn quote_literal(self, value: &str) -> String {
+        match self {
+            #[cfg(feature = "connectorx")]
+            Dialect::Postgres => format!("'{}'", value.replace('\'', "''")),
+            // a backslash escapes the next character of a mysql string
+            #[cfg(feature = "mysql")]
+            Dialect::MySql => format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''")),
+            #[cfg(feature = "sqlite")]
+            Dialect::Sqlite => format!("'{}'", value.replace('\'', "''")),
+        }
+    }
+
+    /// the query selecting the columns of the projected schema from the relation, the rows are filtered by the
+    /// filters that can be evaluated by the database. Only the number of rows is selected without any column.
+    pub(crate) fn select_sql(
+        self,
+        relation: &str,
+        projected: &Schema,
+        filters: &[LogicalExpr],
+        schema: &Schema,
+    ) -> String {
+        let selection = match projected.fields().is_empty() {
+            true => "count(*)".to_owned(),
+            false => projected
+                .fields()
+                .iter()
+                .map(|field| self.quote_identifier(field.name()))
+                .join(", "),
+        };
+        let predicate = filters
+            .iter()
+            .filter_map(|filter| self.filter_to_sql(filter, schema))
+            .join(" AND ");
+
+        match predicate.is_empty() {
+            true => format!("SELECT {} FROM {} AS t", selection, relation),
+            false => format!("SELECT {} FROM {} AS t WHERE {}", selection, relation, predicate),
+        }
+    }
+
+    /// the SQL of a filter, none if a part of the filter can not be evaluated by the database
+    pub(crate) fn filter_to_sql(self, expr: &LogicalExpr, schema: &Schema) -> Option<String> {
+        match expr {
+            LogicalExpr::Column(column) => schema
+                .field_with_name(&column.name)
+                .ok()
+                .map(|_| self.quote_identifier(&column.name)),
+            LogicalExpr::Literal(value) => self.literal_to_sql(value),
+            LogicalExpr::BinaryExpr(BinaryExpr { left, op, right }) => {
+                let (left, right) = (self.filter_to_sql(left, schema)?, self.filter_to_sql(right, schema)?);
+                match (self, op) {
+                    (
+                        _,
+                        Operator::Eq
+                        | Operator::NotEq
+                        | Operator::Gt
+                        | Operator::GtEq
+                        | Operator::Lt
+                        | Operator::LtEq
+                        | Operator::And
+                        | Operator::Or,
+                    ) => Some(format!("({} {} {})", left, op, right)),
+                    #[cfg(feature = "connectorx")]
+                    (Dialect::Postgres, Operator::IsDistinctFrom) => {
+                        Some(format!("({} IS DISTINCT FROM {})", left, right))
+                    }
+                    #[cfg(feature = "connectorx")]
+                    (Dialect::Postgres, Operator::IsNotDistinctFrom) => {
+                        Some(format!("({} IS NOT DISTINCT FROM {})", left, right))
+                    }
+                    // `<=>` is the null safe equality of mysql
+                    #[cfg(feature = "mysql")]
+                    (Dialect::MySql, Operator::IsDistinctFrom) => Some(format!("(NOT ({} <=> {}))", left, right)),
+                    #[cfg(feature = "mysql")]
+                    (Dialect::MySql, Operator::IsNotDistinctFrom) => Some(format!("({} <=> {})", left, right)),
+                    // `IS` is the null safe equality of sqlite
+                    #[cfg(feature = "sqlite")]
+                    (Dialect::Sqlite, Operator::IsDistinctFrom) => Some(format!("({} IS NOT {})", left, right)),
+                    #[cfg(feature = "sqlite")]
+                    (Dialect::Sqlite, Operator::IsNotDistinctFrom) => Some(format!("({} IS {})", left, right)),
+                    _ => None,
+                }
+            }
+            LogicalExpr::IsNull(expr) => Some(format!("({} IS NULL)", self.filter_to_sql(expr, schema)?)),
+            LogicalExpr::IsNotNull(expr) => Some(format!("({} IS NOT NULL)", self.filter_to_sql(expr, schema)?)),
+            LogicalExpr::InList(InList { expr, list, negated }) => Some(format!(
+                "({} {}IN ({}))",
+                self.filter_to_sql(expr, schema)?,
+                if *negated { "NOT " } else { "" },
+                list.iter()
+                    .map(|expr| self.filter_to_sql(expr, schema))
+                    .collect::<Option<Vec<_>>>()?
+                    .join(", ")
+            )),
+            LogicalExpr::Like(Like {
+                negated,
+                case_insensitive,
+                expr,
+                pattern,
+                escape_char,
+            }) => {
+                let like = match (self, case_insensitive) {
+                    (_, false) => "LIKE",
+                    #[cfg(feature = "connectorx")]
+                    (Dialect::Postgres, true) => "ILIKE",
+                    // the case sensitivity of a mysql comparison depends on the collation of the column
+                    #[cfg(feature = "mysql")]
+                    (Dialect::MySql, true) => return None,
+                    // the LIKE of sqlite is case insensitive for ascii, its rows are filtered again by the query
+                    #[cfg(feature = "sqlite")]
+                    (Dialect::Sqlite, true) => return None,
+                };
+                let escape = match escape_char {
+                    Some(c) => format!(" ESCAPE {}", self.quote_literal(&c.to_string())),
+                    None => String::new(),
+                };
+                Some(format!(
+                    "({} {}{} {}{})",
+                    self.filter_to_sql(expr, schema)?,
+                    if *negated { "NOT " } else { "" },
+                    like,
+                    self.filter_to_sql(pattern, schema)?,
+                    escape
+                ))
+            }
+            // the database compares the numbers of different types without the cast
+            LogicalExpr::Cast(CastExpr { expr, data_type }) if data_type.is_numeric() => match expr.as_ref() {
+                LogicalExpr::Column(column)
+                    if schema
+                        .field_with_name(&column.name)
+                        .is_ok_and(|field| field.data_type().is_numeric()) =>
+                {
+                    self.filter_to_sql(expr, schema)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn literal_to_sql(self, value: &ScalarValue) -> Option<String> {
+        Some(match value {
+            ScalarValue::Boolean(Some(v)) => v.to_string().to_uppercase(),
+            ScalarValue::Int8(Some(v)) => v.to_string(),
+            ScalarValue::Int16(Some(v)) => v.to_string(),
+            ScalarValue::Int32(Some(v)) => v.to_string(),
+            ScalarValue::Int64(Some(v)) => v.to_string(),
+            ScalarValue::UInt8(Some(v)) => v.to_string(),
+            ScalarValue::UInt16(Some(v)) => v.to_string(),
+            ScalarValue::UInt32(Some(v)) => v.to_string(),
+            ScalarValue::UInt64(Some(v)) => v.to_string(),
+            ScalarValue::Float32(Some(v)) if v.is_finite() => v.to_string(),
+            ScalarValue::Float64(Some(v)) if v.is_finite() => v.to_string(),
+            ScalarValue::Decimal128(Some(v), precision, scale) => {
+                Decimal128Type::format_decimal(*v, *precision, *scale)
+            }
+            ScalarValue::Utf8(Some(v)) => self.quote_literal(v),
+            // sqlite has no date and time types, their values are stored as text or numbers
+            #[cfg(feature = "sqlite")]
+            ScalarValue::Date32(_) | ScalarValue::Time64Microsecond(_) | ScalarValue::TimestampMillisecond(..)
+                if self == Dialect::Sqlite =>
+            {
+                return None
+            }
+            ScalarValue::Date32(Some(v)) => format!("DATE '{}'", date32_to_datetime(*v)?.date()),
+            ScalarValue::Time64Microsecond(Some(v)) => format!("TIME '{}'", time64us_to_time(*v)?),
+            ScalarValue::TimestampMillisecond(Some(v), _) => {
+                format!("TIMESTAMP '{}'", timestamp_ms_to_datetime(*v)?)
+            }
+            ScalarValue::Null
+            | ScalarValue::Boolean(None)
+            | ScalarValue::Int8(None)
+            | ScalarValue::Int16(None)
+            | ScalarValue::Int32(None)
+            | ScalarValue::Int64(None)
+            | ScalarValue::UInt8(None)
+            | ScalarValue::UInt16(None)
+            | ScalarValue::UInt32(None)
+            | ScalarValue::UInt64(None)
+            | ScalarValue::Utf8(None) => "NULL".to_owned(),
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical::expr::Column;
+    use arrow::datatypes::{DataType, Field};
+
+    #[test]
+    #[cfg(feature = "connectorx")]
+    fn test_filter_to_sql() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]);
+        let column = |name: &str| Box::new(LogicalExpr::Column(Column::new(name, None::<&str>, false)));
+        let to_sql = |expr: &LogicalExpr| Dialect::Postgres.filter_to_sql(expr, &schema);
+
+        let expr = LogicalExpr::InList(InList::new(
+            LogicalExpr::Cast(CastExpr::new(*column("a"), DataType::Int64)),
+            vec![
+                LogicalExpr::Literal(ScalarValue::Int64(Some(1))),
+                LogicalExpr::Literal(ScalarValue::Int64(Some(2))),
+            ],
+            true,
+        ));
+        assert_eq!(to_sql(&expr).as_deref(), Some("(\"a\" NOT IN (1, 2))"));
+
+        let expr = LogicalExpr::Like(Like {
+            negated: false,
+            case_insensitive: true,
+            expr: column("b"),
+            pattern: Box::new(LogicalExpr::Literal(ScalarValue::from("a%"))),
+            escape_char: Some('!'),
+        });
+        assert_eq!(to_sql(&expr).as_deref(), Some("(\"b\" ILIKE 'a%' ESCAPE '!')"));
+
+        let expr = LogicalExpr::BinaryExpr(BinaryExpr::new(
+            LogicalExpr::IsNull(column("a")),
+            Operator::Or,
+            LogicalExpr::BinaryExpr(BinaryExpr::new(
+                *column("a"),
+                Operator::GtEq,
+                LogicalExpr::Literal(ScalarValue::Date32(Some(19723))),
+            )),
+        ));
+        assert_eq!(
+            to_sql(&expr).as_deref(),
+            Some("((\"a\" IS NULL) OR (\"a\" >= DATE '2024-01-01'))")
+        );
+
+        // the cast of a string and the arithmetic are evaluated by the query
+        assert_eq!(
+            to_sql(&LogicalExpr::Cast(CastExpr::new(*column("b"), DataType::Int64))),
+            None
+        );
+        assert_eq!(
+            to_sql(&LogicalExpr::BinaryExpr(BinaryExpr::new(
+                *column("a"),
+                Operator::Div,
+                *column("a")
+            ))),
+            None
+        );
+        assert_eq!(
+            to_sql(&LogicalExpr::Column(Column::new("c", None::<&str>, false))),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "mysql")]
+    fn test_mysql_filter_to_sql() {
+        let schema = Schema::new(vec![Field::new("b", DataType::Utf8, true)]);
+        let column = || LogicalExpr::Column(Column::new("b", None::<&str>, false));
+
+        let expr = LogicalExpr::BinaryExpr(BinaryExpr::new(
+            column(),
+            Operator::IsDistinctFrom,
+            LogicalExpr::Literal(ScalarValue::from("it's a \\")),
+        ));
+        assert_eq!(
+            Dialect::MySql.filter_to_sql(&expr, &schema).as_deref(),
+            Some("(NOT (`b` <=> 'it''s a \\\\'))")
+        );
+
+        let expr = LogicalExpr::Like(Like {
+            negated: true,
+            case_insensitive: true,
+            expr: Box::new(column()),
+            pattern: Box::new(LogicalExpr::Literal(ScalarValue::from("a%"))),
+            escape_char: None,
+        });
+        assert_eq!(Dialect::MySql.filter_to_sql(&expr, &schema), None);
+    }
+}
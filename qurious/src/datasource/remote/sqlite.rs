@@ -0,0 +1,352 @@
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, BinaryArray, BooleanArray, Float64Array, Int64Array, RecordBatch, RecordBatchOptions, StringArray,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use rusqlite::types::Value;
+use rusqlite::{Connection, OpenFlags};
+
+use crate::datasource::remote::Dialect;
+use crate::error::{Error, Result};
+use crate::logical::expr::LogicalExpr;
+use crate::provider::table::{TableProvider, TableType};
+
+/// the number of rows of a batch of a scan
+const BATCH_SIZE: usize = 8192;
+
+/// A table or a view of a SQLite database file read with the `rusqlite` crate, the rows are selected by each scan.
+/// The type of a column is the arrow type of the affinity of its declared type, a value that can not be converted to
+/// it is an error. The projection and the filters that can be translated to SQLite SQL are evaluated by SQLite.
+#[derive(Debug)]
+pub struct SqliteTable {
+    path: String,
+    /// the quoted name of the table
+    relation: String,
+    schema: SchemaRef,
+}
+
+/// the table `table` of the SQLite database file
+pub fn read_sqlite(path: &str, table: &str) -> Result<Arc<dyn TableProvider>> {
+    SqliteTable::try_new(path, table).map(|table| Arc::new(table) as _)
+}
+
+impl SqliteTable {
+    pub fn try_new(path: &str, table: &str) -> Result<Self> {
+        let relation = Dialect::Sqlite.quote_identifier(table);
+        let conn = open(path)?;
+        let stmt = conn
+            .prepare(&format!("SELECT * FROM {}", relation))
+            .map_err(|e| sqlite_err(path, e))?;
+        let fields = stmt
+            .columns()
+            .iter()
+            .map(|column| {
+                Field::new(
+                    column.name(),
+                    to_arrow_type(column.decl_type().unwrap_or_default()),
+                    true,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            path: path.to_owned(),
+            relation,
+            schema: Arc::new(Schema::new(fields)),
+        })
+    }
+}
+
+impl TableProvider for SqliteTable {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn scan(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        let indices = match projection {
+            Some(columns) => columns
+                .iter()
+                .map(|column| self.schema.index_of(column))
+                .collect::<std::result::Result<Vec<_>, _>>()?,
+            None => (0..self.schema.fields().len()).collect(),
+        };
+        let schema = Arc::new(self.schema.project(&indices)?);
+        let sql = Dialect::Sqlite.select_sql(&self.relation, &schema, filters, &self.schema);
+
+        let conn = open(&self.path)?;
+        let mut stmt = conn.prepare(&sql).map_err(|e| sqlite_err(&self.path, e))?;
+        if schema.fields().is_empty() {
+            let count = stmt
+                .query_row([], |row| row.get::<_, i64>(0))
+                .map_err(|e| sqlite_err(&self.path, e))?;
+            let options = RecordBatchOptions::new().with_row_count(Some(count as usize));
+            return Ok(vec![RecordBatch::try_new_with_options(schema, vec![], &options)?]);
+        }
+
+        let mut batches = vec![];
+        let mut columns = vec![vec![]; indices.len()];
+        let mut flush = |columns: &mut Vec<Vec<Value>>| -> Result<()> {
+            let arrays = columns
+                .iter_mut()
+                .zip(schema.fields())
+                .map(|(values, field)| to_array(std::mem::take(values), field))
+                .collect::<Result<Vec<_>>>()?;
+            batches.push(RecordBatch::try_new(schema.clone(), arrays)?);
+            Ok(())
+        };
+
+        let mut rows = stmt.query([]).map_err(|e| sqlite_err(&self.path, e))?;
+        while let Some(row) = rows.next().map_err(|e| sqlite_err(&self.path, e))? {
+            for (i, column) in columns.iter_mut().enumerate() {
+                column.push(row.get::<_, Value>(i).map_err(|e| sqlite_err(&self.path, e))?);
+            }
+            if columns[0].len() == BATCH_SIZE {
+                flush(&mut columns)?;
+            }
+        }
+        if !columns[0].is_empty() {
+            flush(&mut columns)?;
+        }
+
+        Ok(batches)
+    }
+
+    fn supports_filters_pushdown(&self) -> bool {
+        true
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::External
+    }
+}
+
+fn sqlite_err(path: &str, e: rusqlite::Error) -> Error {
+    Error::InternalError(format!("SQLite database {}: {}", path, e))
+}
+
+/// the connection of a scan, the database is only read
+fn open(path: &str) -> Result<Connection> {
+    Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+        .map_err(|e| sqlite_err(path, e))
+}
+
+/// the arrow type of the affinity of a declared type, a column without a declared type is read as text
+fn to_arrow_type(declared_type: &str) -> DataType {
+    let declared_type = declared_type.to_uppercase();
+    if declared_type.contains("INT") {
+        DataType::Int64
+    } else if ["CHAR", "CLOB", "TEXT", "DATE", "TIME"]
+        .iter()
+        .any(|name| declared_type.contains(name))
+        || declared_type.is_empty()
+    {
+        DataType::Utf8
+    } else if declared_type.contains("BLOB") {
+        DataType::Binary
+    } else if declared_type.contains("BOOL") {
+        DataType::Boolean
+    } else {
+        DataType::Float64
+    }
+}
+
+fn to_array(values: Vec<Value>, field: &Field) -> Result<ArrayRef> {
+    let invalid = |value: &Value| {
+        Error::InternalError(format!(
+            "the value {:?} of the column {} is not a {}",
+            value,
+            field.name(),
+            field.data_type()
+        ))
+    };
+    let array: ArrayRef = match field.data_type() {
+        DataType::Int64 => Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Integer(v) => Ok(Some(*v)),
+                    Value::Real(v) if v.fract() == 0.0 => Ok(Some(*v as i64)),
+                    Value::Text(v) => v.trim().parse().map(Some).map_err(|_| invalid(value)),
+                    _ => Err(invalid(value)),
+                })
+                .collect::<Result<Int64Array>>()?,
+        ),
+        DataType::Float64 => Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Integer(v) => Ok(Some(*v as f64)),
+                    Value::Real(v) => Ok(Some(*v)),
+                    Value::Text(v) => v.trim().parse().map(Some).map_err(|_| invalid(value)),
+                    _ => Err(invalid(value)),
+                })
+                .collect::<Result<Float64Array>>()?,
+        ),
+        DataType::Boolean => Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Integer(v) => Ok(Some(*v != 0)),
+                    Value::Text(v) if v.eq_ignore_ascii_case("true") => Ok(Some(true)),
+                    Value::Text(v) if v.eq_ignore_ascii_case("false") => Ok(Some(false)),
+                    _ => Err(invalid(value)),
+                })
+                .collect::<Result<BooleanArray>>()?,
+        ),
+        DataType::Binary => Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Blob(v) => Ok(Some(v.as_slice())),
+                    Value::Text(v) => Ok(Some(v.as_bytes())),
+                    _ => Err(invalid(value)),
+                })
+                .collect::<Result<BinaryArray>>()?,
+        ),
+        _ => Arc::new(
+            values
+                .into_iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Integer(v) => Ok(Some(v.to_string())),
+                    Value::Real(v) => Ok(Some(v.to_string())),
+                    Value::Text(v) => Ok(Some(v)),
+                    Value::Blob(v) => String::from_utf8(v).map(Some).map_err(|e| {
+                        Error::InternalError(format!("invalid text of the column {}: {}", field.name(), e))
+                    }),
+                })
+                .collect::<Result<StringArray>>()?,
+        ),
+    };
+
+    Ok(array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datatypes::operator::Operator;
+    use crate::datatypes::scalar::ScalarValue;
+    use crate::execution::session::ExecuteSession;
+    use crate::logical::expr::{BinaryExpr, Column};
+    use crate::test_utils::assert_batch_eq;
+    use arrow::array::AsArray;
+
+    /// a database with a table of 500 rows written before a column was added and a row written after, a table
+    /// without rowid and a view
+    fn create_database(path: &str) -> Result<()> {
+        let _ = std::fs::remove_file(path);
+        let conn = Connection::open(path).map_err(|e| sqlite_err(path, e))?;
+        conn.execute_batch(
+            "CREATE TABLE \"users\" (id INTEGER PRIMARY KEY, name TEXT NOT NULL, score REAL, active BOOLEAN, \
+                avatar BLOB, note VARCHAR(20) DEFAULT '');
+            WITH RECURSIVE n(id) AS (SELECT 1 UNION ALL SELECT id + 1 FROM n WHERE id < 500)
+            INSERT INTO users (id, name, score, active, avatar, note)
+                SELECT id, 'user' || id, CASE WHEN id % 10 = 0 THEN NULL ELSE id / 2.0 END, id % 2 = 1,
+                    CASE WHEN id = 1 THEN X'0102' END, CASE WHEN id = 2 THEN printf('%.3000c', 'x') ELSE 'n' || id END
+                FROM n;
+            ALTER TABLE users ADD COLUMN age INT;
+            INSERT INTO users (id, name, age) VALUES (501, 'late', 30);
+            CREATE TABLE kv (k TEXT PRIMARY KEY, v) WITHOUT ROWID;
+            INSERT INTO kv VALUES ('a', 1);
+            CREATE VIEW adults AS SELECT * FROM users WHERE age >= 18;",
+        )
+        .map_err(|e| sqlite_err(path, e))
+    }
+
+    #[test]
+    fn test_read_sqlite() -> Result<()> {
+        let path = std::env::temp_dir().join("qurious_test_read_sqlite.db");
+        let path = path.to_str().unwrap();
+        create_database(path)?;
+
+        let table = read_sqlite(path, "users")?;
+        assert_eq!(
+            table
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| (field.name().as_str(), field.data_type().clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("id", DataType::Int64),
+                ("name", DataType::Utf8),
+                ("score", DataType::Float64),
+                ("active", DataType::Boolean),
+                ("avatar", DataType::Binary),
+                ("note", DataType::Utf8),
+                ("age", DataType::Int64),
+            ]
+        );
+
+        // the filters are evaluated by SQLite
+        let filters = vec![LogicalExpr::BinaryExpr(BinaryExpr::new(
+            LogicalExpr::Column(Column::new("score", None::<&str>, false)),
+            Operator::Gt,
+            LogicalExpr::Literal(ScalarValue::Float64(Some(249.0))),
+        ))];
+        let batches = table.scan(Some(vec!["id".to_owned()]), &filters)?;
+        assert_batch_eq(&batches, vec!["+-----+", "| id  |", "+-----+", "| 499 |", "+-----+"]);
+        assert_eq!(table.scan(Some(vec![]), &filters)?[0].num_rows(), 1);
+
+        let session = ExecuteSession::new()?;
+        session.register_table("users", table)?;
+        let batches = session.sql("SELECT count(*) AS c, sum(id) AS s, count(score) AS n, sum(age) AS a FROM users")?;
+        assert_batch_eq(
+            &batches,
+            vec![
+                "+-----+--------+-----+----+",
+                "| c   | s      | n   | a  |",
+                "+-----+--------+-----+----+",
+                "| 501 | 125751 | 450 | 30 |",
+                "+-----+--------+-----+----+",
+            ],
+        );
+        let batches = session.sql("SELECT note FROM users WHERE id = 2")?;
+        let note = batches[0].column(0).as_string::<i32>();
+        assert_eq!(note.value(0), "x".repeat(3000));
+        let batches = session.sql("SELECT id, name, score, active, avatar, age FROM users WHERE id IN (1, 10, 501)")?;
+        assert_batch_eq(
+            &batches,
+            vec![
+                "+-----+--------+-------+--------+--------+-----+",
+                "| id  | name   | score | active | avatar | age |",
+                "+-----+--------+-------+--------+--------+-----+",
+                "| 1   | user1  | 0.5   | true   | 0102   |     |",
+                "| 10  | user10 |       | false  |        |     |",
+                "| 501 | late   |       |        |        | 30  |",
+                "+-----+--------+-------+--------+--------+-----+",
+            ],
+        );
+
+        assert_eq!(read_sqlite(path, "kv")?.scan(None, &[])?[0].num_rows(), 1);
+        assert_eq!(read_sqlite(path, "adults")?.scan(None, &[])?[0].num_rows(), 1);
+        assert!(read_sqlite(path, "missing").is_err());
+        assert!(read_sqlite("./tests/testdata/file/case1.csv", "users").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_arrow_type() {
+        assert_eq!(
+            ["INTEGER", "DECIMAL(10, 2)", "", "VARCHAR(20)", "BOOLEAN", "BLOB"]
+                .into_iter()
+                .map(to_arrow_type)
+                .collect::<Vec<_>>(),
+            vec![
+                DataType::Int64,
+                DataType::Float64,
+                DataType::Utf8,
+                DataType::Utf8,
+                DataType::Boolean,
+                DataType::Binary
+            ]
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::logical::expr::LogicalExpr;
+use crate::logical::plan::LogicalPlan;
+use crate::provider::table::{TableProvider, TableType};
+
+/// A view created by `CREATE VIEW`, it only holds the logical plan of the view query. The plan is inlined
+/// by the planner wherever the view is referenced, so it always reads the current data of the base tables.
+#[derive(Debug)]
+pub struct ViewTable {
+    logical_plan: LogicalPlan,
+    definition: Option<String>,
+}
+
+impl ViewTable {
+    pub fn new(logical_plan: LogicalPlan, definition: Option<String>) -> Self {
+        Self {
+            logical_plan,
+            definition,
+        }
+    }
+}
+
+impl TableProvider for ViewTable {
+    fn schema(&self) -> SchemaRef {
+        self.logical_plan.schema()
+    }
+
+    fn scan(&self, _projection: Option<Vec<String>>, _filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+        internal_err!("A view can not be scanned, its logical plan should be inlined")
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    fn get_logical_plan(&self) -> Option<&LogicalPlan> {
+        Some(&self.logical_plan)
+    }
+
+    fn get_table_definition(&self) -> Option<&str> {
+        self.definition.as_deref()
+    }
+}
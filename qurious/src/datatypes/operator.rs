@@ -10,12 +10,17 @@ pub enum Operator {
     LtEq,
     And,
     Or,
+    IsDistinctFrom,
+    IsNotDistinctFrom,
 
     Add,
     Sub,
     Mul,
     Div,
     Mod,
+
+    /// `||`, concatenates the strings
+    StringConcat,
 }
 
 impl Display for Operator {
@@ -29,11 +34,14 @@ impl Display for Operator {
             Operator::LtEq => write!(f, "<="),
             Operator::And => write!(f, "AND"),
             Operator::Or => write!(f, "OR"),
+            Operator::IsDistinctFrom => write!(f, "IS DISTINCT FROM"),
+            Operator::IsNotDistinctFrom => write!(f, "IS NOT DISTINCT FROM"),
             Operator::Add => write!(f, "+"),
             Operator::Sub => write!(f, "-"),
             Operator::Mul => write!(f, "*"),
             Operator::Div => write!(f, "/"),
             Operator::Mod => write!(f, "%"),
+            Operator::StringConcat => write!(f, "||"),
         }
     }
 }
@@ -1,23 +1,27 @@
 use crate::error::{Error, Result};
 use arrow::{
     array::{
-        new_null_array, Array, ArrayRef, ArrowPrimitiveType, BooleanArray, Decimal128Array, Decimal256Array, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, LargeStringArray, PrimitiveArray, StringArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array
+        new_null_array, Array, ArrayRef, ArrowPrimitiveType, BooleanArray, Date32Array, Decimal128Array, Decimal256Array, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, IntervalMonthDayNanoArray, IntervalYearMonthArray, LargeStringArray, PrimitiveArray, StringArray, Time64MicrosecondArray, TimestampMillisecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array
     },
-    datatypes::{i256, DataType, Field},
+    datatypes::{i256, DataType, Field, IntervalMonthDayNano, IntervalUnit, TimeUnit},
+    temporal_conversions::{date32_to_datetime, time64us_to_time, timestamp_ms_to_datetime},
 };
 use std::any::type_name;
 use std::{fmt::Display, sync::Arc};
 
 macro_rules! typed_cast {
-    ($array:expr, $index:expr, $ARRAYTYPE:ident, $SCALE:ident) => {{
+    ($array:expr, $index:expr, $ARRAYTYPE:ident, $SCALE:ident $(, $ARGS:expr)*) => {{
         let array = $array
             .as_any()
             .downcast_ref::<$ARRAYTYPE>()
             .ok_or_else(|| Error::InternalError(format!("could not cast value to {}", type_name::<$ARRAYTYPE>())))?;
-        Ok::<ScalarValue, Error>(ScalarValue::$SCALE(match array.is_null($index) {
-            true => None,
-            false => Some(array.value($index).into()),
-        }))
+        Ok::<ScalarValue, Error>(ScalarValue::$SCALE(
+            match array.is_null($index) {
+                true => None,
+                false => Some(array.value($index).into()),
+            },
+            $($ARGS),*
+        ))
     }};
 }
 
@@ -98,6 +102,15 @@ pub enum ScalarValue {
     /// 256bit decimal, using the i256 to represent the decimal, precision scale
     Decimal256(Option<i256>, u8, i8),
     Utf8(Option<String>),
+    /// number of days since the UNIX epoch
+    Date32(Option<i32>),
+    /// number of microseconds since midnight
+    Time64Microsecond(Option<i64>),
+    /// number of milliseconds since the UNIX epoch, and the optional timezone
+    TimestampMillisecond(Option<i64>, Option<Arc<str>>),
+    /// number of months
+    IntervalYearMonth(Option<i32>),
+    IntervalMonthDayNano(Option<IntervalMonthDayNano>),
 }
 
 impl ScalarValue {
@@ -128,6 +141,21 @@ impl ScalarValue {
             ScalarValue::Utf8(_) => Field::new("utf8", DataType::Utf8, true),
             ScalarValue::Decimal128(_, p, s) => Field::new("decimal128", DataType::Decimal128(*p, *s), true),
             ScalarValue::Decimal256(_, p, s) => Field::new("decimal256", DataType::Decimal256(*p, *s), true),
+            ScalarValue::Date32(_) => Field::new("date32", DataType::Date32, true),
+            ScalarValue::Time64Microsecond(_) => Field::new("time64", DataType::Time64(TimeUnit::Microsecond), true),
+            ScalarValue::TimestampMillisecond(_, tz) => Field::new(
+                "timestamp",
+                DataType::Timestamp(TimeUnit::Millisecond, tz.clone()),
+                true,
+            ),
+            ScalarValue::IntervalYearMonth(_) => {
+                Field::new("interval_year_month", DataType::Interval(IntervalUnit::YearMonth), true)
+            }
+            ScalarValue::IntervalMonthDayNano(_) => Field::new(
+                "interval_month_day_nano",
+                DataType::Interval(IntervalUnit::MonthDayNano),
+                true,
+            ),
         }
     }
 
@@ -148,6 +176,11 @@ impl ScalarValue {
             ScalarValue::Utf8(_) => DataType::Utf8,
             ScalarValue::Decimal128(_, p, s) => DataType::Decimal128(*p, *s),
             ScalarValue::Decimal256(_, p, s) => DataType::Decimal256(*p, *s),
+            ScalarValue::Date32(_) => DataType::Date32,
+            ScalarValue::Time64Microsecond(_) => DataType::Time64(TimeUnit::Microsecond),
+            ScalarValue::TimestampMillisecond(_, tz) => DataType::Timestamp(TimeUnit::Millisecond, tz.clone()),
+            ScalarValue::IntervalYearMonth(_) => DataType::Interval(IntervalUnit::YearMonth),
+            ScalarValue::IntervalMonthDayNano(_) => DataType::Interval(IntervalUnit::MonthDayNano),
         }
     }
 
@@ -172,6 +205,15 @@ impl ScalarValue {
             ScalarValue::Decimal256(v, p, s) => {
                 Arc::new(build_decimal_array!(*v, Decimal256Array, num_row, *p, *s)) as ArrayRef
             }
+            ScalarValue::Date32(v) => Arc::new(Date32Array::from(vec![*v; num_row])) as ArrayRef,
+            ScalarValue::Time64Microsecond(v) => Arc::new(Time64MicrosecondArray::from(vec![*v; num_row])) as ArrayRef,
+            ScalarValue::TimestampMillisecond(v, tz) => {
+                Arc::new(TimestampMillisecondArray::from(vec![*v; num_row]).with_timezone_opt(tz.clone())) as ArrayRef
+            }
+            ScalarValue::IntervalYearMonth(v) => Arc::new(IntervalYearMonthArray::from(vec![*v; num_row])) as ArrayRef,
+            ScalarValue::IntervalMonthDayNano(v) => {
+                Arc::new(IntervalMonthDayNanoArray::from(vec![*v; num_row])) as ArrayRef
+            }
         })
     }
 
@@ -198,6 +240,25 @@ impl ScalarValue {
             DataType::LargeUtf8 => typed_cast!(array, index, LargeStringArray, Utf8),
             DataType::Decimal128(p, s) => typed_cast_decimal!(Decimal128Array, Decimal128, array, index, *p, *s),
             DataType::Decimal256(p, s) => typed_cast_decimal!(Decimal256Array, Decimal256, array, index, *p, *s),
+            DataType::Date32 => typed_cast!(array, index, Date32Array, Date32),
+            DataType::Time64(TimeUnit::Microsecond) => {
+                typed_cast!(array, index, Time64MicrosecondArray, Time64Microsecond)
+            }
+            DataType::Timestamp(TimeUnit::Millisecond, tz) => {
+                typed_cast!(
+                    array,
+                    index,
+                    TimestampMillisecondArray,
+                    TimestampMillisecond,
+                    tz.clone()
+                )
+            }
+            DataType::Interval(IntervalUnit::YearMonth) => {
+                typed_cast!(array, index, IntervalYearMonthArray, IntervalYearMonth)
+            }
+            DataType::Interval(IntervalUnit::MonthDayNano) => {
+                typed_cast!(array, index, IntervalMonthDayNanoArray, IntervalMonthDayNano)
+            }
             _ => unimplemented!("data type {} not supported", array.data_type()),
         }
     }
@@ -242,6 +303,13 @@ impl TryFrom<&DataType> for ScalarValue {
             DataType::Float64 => Ok(ScalarValue::Float64(None)),
             DataType::Utf8 => Ok(ScalarValue::Utf8(None)),
             DataType::LargeUtf8 => Ok(ScalarValue::Utf8(None)),
+            DataType::Decimal128(p, s) => Ok(ScalarValue::Decimal128(None, *p, *s)),
+            DataType::Decimal256(p, s) => Ok(ScalarValue::Decimal256(None, *p, *s)),
+            DataType::Date32 => Ok(ScalarValue::Date32(None)),
+            DataType::Time64(TimeUnit::Microsecond) => Ok(ScalarValue::Time64Microsecond(None)),
+            DataType::Timestamp(TimeUnit::Millisecond, tz) => Ok(ScalarValue::TimestampMillisecond(None, tz.clone())),
+            DataType::Interval(IntervalUnit::YearMonth) => Ok(ScalarValue::IntervalYearMonth(None)),
+            DataType::Interval(IntervalUnit::MonthDayNano) => Ok(ScalarValue::IntervalMonthDayNano(None)),
             _ => unimplemented!("data type {} not supported", value),
         }
     }
@@ -273,6 +341,16 @@ impl Display for ScalarValue {
             ScalarValue::Decimal128(v, p, s) => format_decimal!(f, v, "Decimal128", p, s),
             ScalarValue::Decimal256(v, p, s) => format_decimal!(f, v, "Decimal256", p, s),
             ScalarValue::Utf8(v) => format_string!(f, v, "Utf8"),
+            ScalarValue::Date32(v) => format_string!(f, v.and_then(date32_to_datetime).map(|v| v.date()), "Date32"),
+            ScalarValue::Time64Microsecond(v) => format_string!(f, v.and_then(time64us_to_time), "Time64"),
+            ScalarValue::TimestampMillisecond(v, _) => {
+                format_string!(f, v.and_then(timestamp_ms_to_datetime), "TimestampMillisecond")
+            }
+            ScalarValue::IntervalYearMonth(v) => format_option!(f, v, "IntervalYearMonth"),
+            ScalarValue::IntervalMonthDayNano(v) => match v {
+                Some(v) => write!(f, "IntervalMonthDayNano({}, {}, {})", v.months, v.days, v.nanoseconds),
+                None => write!(f, "IntervalMonthDayNano(NULL)"),
+            },
         }
     }
 }
@@ -50,6 +50,7 @@ pub enum Error {
     SQLParseError(sqlparser::error::Error),
     PlanError(String),
     TableNotFound(String),
+    ResourcesExhausted(String),
 }
 
 impl Error {
@@ -90,6 +91,7 @@ impl Display for Error {
             Error::DuplicateColumn(c) => write!(f, "Duplicate column: {}", c),
             Error::TableNotFound(e) => write!(f, "Table Not Found: {}", e),
             Error::InvalidArgumentError(e) => write!(f, "Invalid Argument Error: {}", e),
+            Error::ResourcesExhausted(e) => write!(f, "Resources Exhausted: {}", e),
         }
     }
 }
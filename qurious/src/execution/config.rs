@@ -1,6 +1,48 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow::array::timezone::Tz;
+use sqlparser::dialect::{dialect_from_str, Dialect, GenericDialect};
+
+use crate::error::{Error, Result};
+use crate::physical::expr::HyperLogLog;
+
+#[derive(Clone)]
 pub struct SessionConfig {
     pub default_catalog: String,
     pub default_schema: String,
+    /// the schemas searched in order for a table without a schema, after the default schema
+    pub search_path: Vec<String>,
+    /// the maximum number of rows of a batch produced by a table scan
+    pub batch_size: usize,
+    /// the number of partitions a plan is split into for parallel execution
+    pub target_partitions: usize,
+    /// the time zone of `NOW()`, an offset like `+08:00`, timestamps have no time zone if not set
+    pub timezone: Option<String>,
+    /// whether NULL sorts before other values for an ORDER BY without NULLS FIRST or NULLS LAST
+    pub default_nulls_first: bool,
+    /// the SQL dialect the statements are parsed with
+    pub dialect: Arc<dyn Dialect>,
+    /// the maximum number of iterations of a recursive CTE before the query fails
+    pub max_recursion_depth: usize,
+    /// allow DELETE without WHERE, which deletes all rows of the table
+    pub allow_delete_without_where: bool,
+    /// the equality joins whose sides are both estimated to have at least this many rows are sort-merge joins
+    pub sort_merge_join_rows: usize,
+    /// the memory size in bytes of the input rows a grouped aggregation buffers, the rows are spilled to disk beyond it
+    pub aggregate_memory_limit: usize,
+    /// the memory size in bytes of the rows of a side a hash join buffers, both sides are spilled to disk beyond it
+    pub join_memory_limit: usize,
+    /// the memory size in bytes the operators of a query reserve, an operator spills to disk or the query fails
+    /// beyond it, no limit if not set
+    pub memory_limit: Option<usize>,
+    /// the number of bits of the hashes indexing the registers of the sketches of `APPROX_DISTINCT`, a sketch has
+    /// `2^precision` registers and a standard error of about `1.04 / sqrt(2^precision)`
+    pub approx_distinct_precision: u8,
+    /// the options of the stores of the `s3://`, `gs://` and `az://` URLs, like `aws_access_key_id`, `aws_region` or
+    /// `google_service_account`, the options not set are read from the environment
+    pub object_store_options: HashMap<String, String>,
 }
 
 impl Default for SessionConfig {
@@ -8,6 +50,171 @@ impl Default for SessionConfig {
         Self {
             default_catalog: "qurious".to_string(),
             default_schema: "public".to_string(),
+            search_path: vec![],
+            batch_size: 8192,
+            target_partitions: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            timezone: None,
+            default_nulls_first: true,
+            dialect: Arc::new(GenericDialect),
+            max_recursion_depth: 1000,
+            allow_delete_without_where: false,
+            sort_merge_join_rows: 1_000_000,
+            aggregate_memory_limit: 1 << 30,
+            join_memory_limit: 1 << 30,
+            memory_limit: None,
+            approx_distinct_precision: 14,
+            object_store_options: HashMap::new(),
+        }
+    }
+}
+
+impl SessionConfig {
+    /// the names and values of the configuration parameters, a parameter without a value is `None`
+    pub fn options(&self) -> Vec<(&'static str, Option<String>)> {
+        let null_ordering = if self.default_nulls_first {
+            "nulls_first"
+        } else {
+            "nulls_last"
+        };
+        vec![
+            ("default_catalog", Some(self.default_catalog.clone())),
+            ("default_schema", Some(self.default_schema.clone())),
+            ("search_path", Some(self.search_path.join(","))),
+            ("batch_size", Some(self.batch_size.to_string())),
+            ("target_partitions", Some(self.target_partitions.to_string())),
+            ("timezone", self.timezone.clone()),
+            ("default_null_ordering", Some(null_ordering.to_string())),
+            ("dialect", Some(self.dialect.name().to_owned())),
+            ("max_recursion_depth", Some(self.max_recursion_depth.to_string())),
+            (
+                "allow_delete_without_where",
+                Some(self.allow_delete_without_where.to_string()),
+            ),
+            ("sort_merge_join_rows", Some(self.sort_merge_join_rows.to_string())),
+            ("aggregate_memory_limit", Some(self.aggregate_memory_limit.to_string())),
+            ("join_memory_limit", Some(self.join_memory_limit.to_string())),
+            ("memory_limit", self.memory_limit.map(|limit| limit.to_string())),
+            (
+                "approx_distinct_precision",
+                Some(self.approx_distinct_precision.to_string()),
+            ),
+        ]
+    }
+
+    /// the value of the configuration parameter `name`
+    pub fn get(&self, name: &str) -> Result<Option<String>> {
+        self.options()
+            .into_iter()
+            .find(|(option, _)| option.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+            .ok_or_else(|| Error::PlanError(format!("unrecognized configuration parameter: {name}")))
+    }
+
+    /// change the configuration parameter `name`, the value is parsed as the type of the parameter
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        let invalid = || Error::PlanError(format!("invalid value for parameter {name}: {value}"));
+        match name.to_ascii_lowercase().as_str() {
+            "default_catalog" => self.default_catalog = value.to_ascii_lowercase(),
+            "default_schema" => self.default_schema = value.to_ascii_lowercase(),
+            "search_path" => {
+                self.search_path = value
+                    .split(',')
+                    .map(|schema| schema.trim().to_ascii_lowercase())
+                    .filter(|schema| !schema.is_empty())
+                    .collect()
+            }
+            "batch_size" => self.batch_size = parse_positive(value).ok_or_else(invalid)?,
+            "target_partitions" => self.target_partitions = parse_positive(value).ok_or_else(invalid)?,
+            "timezone" => {
+                Tz::from_str(value).map_err(|_| invalid())?;
+                self.timezone = Some(value.to_string());
+            }
+            "default_null_ordering" => {
+                self.default_nulls_first = match value.to_ascii_lowercase().as_str() {
+                    "nulls_first" => true,
+                    "nulls_last" => false,
+                    _ => return Err(invalid()),
+                }
+            }
+            "dialect" => self.dialect = dialect_from_str(value).ok_or_else(invalid)?.into(),
+            "max_recursion_depth" => self.max_recursion_depth = parse_positive(value).ok_or_else(invalid)?,
+            "allow_delete_without_where" => {
+                self.allow_delete_without_where = value.to_ascii_lowercase().parse().map_err(|_| invalid())?
+            }
+            "sort_merge_join_rows" => self.sort_merge_join_rows = parse_positive(value).ok_or_else(invalid)?,
+            "aggregate_memory_limit" => self.aggregate_memory_limit = parse_positive(value).ok_or_else(invalid)?,
+            "join_memory_limit" => self.join_memory_limit = parse_positive(value).ok_or_else(invalid)?,
+            "memory_limit" => {
+                self.memory_limit = match value.eq_ignore_ascii_case("unlimited") {
+                    true => None,
+                    false => Some(parse_positive(value).ok_or_else(invalid)?),
+                }
+            }
+            "approx_distinct_precision" => {
+                self.approx_distinct_precision = value
+                    .parse()
+                    .ok()
+                    .filter(|precision| (HyperLogLog::MIN_PRECISION..=HyperLogLog::MAX_PRECISION).contains(precision))
+                    .ok_or_else(invalid)?
+            }
+            _ => {
+                return Err(Error::PlanError(format!(
+                    "unrecognized configuration parameter: {name}"
+                )))
+            }
         }
+
+        Ok(())
+    }
+}
+
+fn parse_positive(value: &str) -> Option<usize> {
+    value.parse().ok().filter(|v| *v > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_option() -> Result<()> {
+        let mut config = SessionConfig::default();
+        config.set("batch_size", "1024")?;
+        config.set("DIALECT", "MySQL")?;
+        config.set("search_path", "s1, S2")?;
+        config.set("timezone", "+08:00")?;
+        config.set("default_null_ordering", "nulls_last")?;
+        config.set("memory_limit", "1048576")?;
+        config.set("approx_distinct_precision", "10")?;
+
+        assert_eq!(config.batch_size, 1024);
+        assert_eq!(config.dialect.name(), "mysql");
+        assert_eq!(config.search_path, vec!["s1", "s2"]);
+        assert_eq!(config.get("timezone")?, Some("+08:00".to_string()));
+        assert_eq!(config.get("default_null_ordering")?, Some("nulls_last".to_string()));
+        assert_eq!(config.get("dialect")?, Some("mysql".to_string()));
+        assert_eq!(config.memory_limit, Some(1048576));
+        assert_eq!(config.approx_distinct_precision, 10);
+        config.set("memory_limit", "UNLIMITED")?;
+        assert_eq!(config.get("memory_limit")?, None);
+
+        assert_eq!(
+            config.set("batch_size", "0").unwrap_err().to_string(),
+            "Plan Error: invalid value for parameter batch_size: 0"
+        );
+        assert_eq!(
+            config.set("timezone", "Mars/Olympus").unwrap_err().to_string(),
+            "Plan Error: invalid value for parameter timezone: Mars/Olympus"
+        );
+        assert_eq!(
+            config.set("approx_distinct_precision", "19").unwrap_err().to_string(),
+            "Plan Error: invalid value for parameter approx_distinct_precision: 19"
+        );
+        assert_eq!(
+            config.get("unknown").unwrap_err().to_string(),
+            "Plan Error: unrecognized configuration parameter: unknown"
+        );
+
+        Ok(())
     }
 }
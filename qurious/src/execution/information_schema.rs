@@ -1,4 +1,4 @@
-use arrow::array::StringBuilder;
+use arrow::array::{StringBuilder, UInt64Builder};
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use std::sync::Arc;
@@ -34,6 +34,8 @@ impl SchemaProvider for InformationSchemaProvider {
     fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
         match name {
             TABLES => Some(self.build_tables()),
+            COLUMNS => Some(self.build_columns()),
+            VIEWS => Some(self.build_views()),
             _ => None,
         }
     }
@@ -44,15 +46,9 @@ impl SchemaProvider for InformationSchemaProvider {
 }
 
 impl InformationSchemaProvider {
-    fn build_tables(&self) -> Arc<dyn TableProvider> {
-        let schema = Arc::new(Schema::new(vec![
-            Field::new("catalog_name", DataType::Utf8, false),
-            Field::new("schema_name", DataType::Utf8, false),
-            Field::new("table_name", DataType::Utf8, false),
-            Field::new("table_type", DataType::Utf8, false),
-        ]));
-        let mut builder = TablesBuilder::new(schema.clone());
-
+    /// call `f` with the catalog name, the schema name, the table name and the table of every table, the
+    /// tables of the information schema are not included
+    fn for_each_table(&self, mut f: impl FnMut(&str, &str, &str, &Arc<dyn TableProvider>)) {
         for catalog_name in self.catalog_list.catalog_names() {
             if let Some(catalog) = self.catalog_list.catalog(&catalog_name) {
                 for schema_name in catalog.schema_names() {
@@ -60,20 +56,31 @@ impl InformationSchemaProvider {
                         if let Some(schema) = catalog.schema(&schema_name) {
                             for table_name in schema.table_names() {
                                 if let Some(table) = schema.table(&table_name) {
-                                    builder.append(
-                                        &catalog_name,
-                                        &schema_name,
-                                        &table_name,
-                                        &table.table_type().to_string(),
-                                    );
+                                    f(&catalog_name, &schema_name, &table_name, &table);
                                 }
                             }
                         }
                     }
                 }
             }
+        }
+    }
+
+    fn build_tables(&self) -> Arc<dyn TableProvider> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("catalog_name", DataType::Utf8, false),
+            Field::new("schema_name", DataType::Utf8, false),
+            Field::new("table_name", DataType::Utf8, false),
+            Field::new("table_type", DataType::Utf8, false),
+        ]));
+        let mut builder = TablesBuilder::new(schema.clone());
 
-            // Add information schema tables
+        self.for_each_table(|catalog_name, schema_name, table_name, table| {
+            builder.append(catalog_name, schema_name, table_name, &table.table_type().to_string());
+        });
+
+        // Add information schema tables
+        for catalog_name in self.catalog_list.catalog_names() {
             for &table_name in INFORMATION_SCHEMA_TABLES {
                 builder.append(
                     &catalog_name,
@@ -86,6 +93,63 @@ impl InformationSchemaProvider {
 
         Arc::new(MemoryTable::try_new(schema, vec![builder.build()]).unwrap())
     }
+
+    /// one row per column of every table, the ordinal position starts from 1
+    fn build_columns(&self) -> Arc<dyn TableProvider> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("catalog_name", DataType::Utf8, false),
+            Field::new("schema_name", DataType::Utf8, false),
+            Field::new("table_name", DataType::Utf8, false),
+            Field::new("column_name", DataType::Utf8, false),
+            Field::new("ordinal_position", DataType::UInt64, false),
+            Field::new("data_type", DataType::Utf8, false),
+            Field::new("is_nullable", DataType::Utf8, false),
+        ]));
+        let mut builder = ColumnsBuilder::new(schema.clone());
+
+        self.for_each_table(|catalog_name, schema_name, table_name, table| {
+            for (i, field) in table.schema().fields().iter().enumerate() {
+                builder.append(catalog_name, schema_name, table_name, i as u64 + 1, field);
+            }
+        });
+
+        Arc::new(MemoryTable::try_new(schema, vec![builder.build()]).unwrap())
+    }
+
+    fn build_views(&self) -> Arc<dyn TableProvider> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("catalog_name", DataType::Utf8, false),
+            Field::new("schema_name", DataType::Utf8, false),
+            Field::new("table_name", DataType::Utf8, false),
+            Field::new("definition", DataType::Utf8, true),
+        ]));
+        let mut catalog_names = StringBuilder::new();
+        let mut schema_names = StringBuilder::new();
+        let mut table_names = StringBuilder::new();
+        let mut definitions = StringBuilder::new();
+
+        self.for_each_table(|catalog_name, schema_name, table_name, table| {
+            if table.table_type() == TableType::View {
+                catalog_names.append_value(catalog_name);
+                schema_names.append_value(schema_name);
+                table_names.append_value(table_name);
+                definitions.append_option(table.get_table_definition());
+            }
+        });
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(catalog_names.finish()),
+                Arc::new(schema_names.finish()),
+                Arc::new(table_names.finish()),
+                Arc::new(definitions.finish()),
+            ],
+        )
+        .unwrap();
+
+        Arc::new(MemoryTable::try_new(schema, vec![batch]).unwrap())
+    }
 }
 
 struct TablesBuilder {
@@ -127,3 +191,56 @@ impl TablesBuilder {
         .unwrap()
     }
 }
+
+struct ColumnsBuilder {
+    schema: SchemaRef,
+    catalog_names: StringBuilder,
+    schema_names: StringBuilder,
+    table_names: StringBuilder,
+    column_names: StringBuilder,
+    ordinal_positions: UInt64Builder,
+    data_types: StringBuilder,
+    is_nullables: StringBuilder,
+}
+
+impl ColumnsBuilder {
+    fn new(schema: SchemaRef) -> Self {
+        Self {
+            schema,
+            catalog_names: StringBuilder::new(),
+            schema_names: StringBuilder::new(),
+            table_names: StringBuilder::new(),
+            column_names: StringBuilder::new(),
+            ordinal_positions: UInt64Builder::new(),
+            data_types: StringBuilder::new(),
+            is_nullables: StringBuilder::new(),
+        }
+    }
+
+    fn append(&mut self, catalog: &str, schema: &str, table: &str, ordinal_position: u64, field: &Field) {
+        self.catalog_names.append_value(catalog);
+        self.schema_names.append_value(schema);
+        self.table_names.append_value(table);
+        self.column_names.append_value(field.name());
+        self.ordinal_positions.append_value(ordinal_position);
+        self.data_types.append_value(field.data_type().to_string());
+        self.is_nullables
+            .append_value(if field.is_nullable() { "YES" } else { "NO" });
+    }
+
+    fn build(mut self) -> RecordBatch {
+        RecordBatch::try_new(
+            self.schema,
+            vec![
+                Arc::new(self.catalog_names.finish()),
+                Arc::new(self.schema_names.finish()),
+                Arc::new(self.table_names.finish()),
+                Arc::new(self.column_names.finish()),
+                Arc::new(self.ordinal_positions.finish()),
+                Arc::new(self.data_types.finish()),
+                Arc::new(self.is_nullables.finish()),
+            ],
+        )
+        .unwrap()
+    }
+}
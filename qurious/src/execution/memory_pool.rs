@@ -0,0 +1,143 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+
+/// The memory shared by the operators of a query. An operator buffering batches registers a reservation with the
+/// pool and grows it before buffering more, growing a reservation fails once the reservations would exceed the limit
+/// of the pool, the operator then spills its state to disk or the query fails.
+#[derive(Debug)]
+pub struct MemoryPool {
+    /// the maximum memory size in bytes of the reservations
+    limit: usize,
+    /// the memory size in bytes of the reservations
+    reserved: AtomicUsize,
+}
+
+impl MemoryPool {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            reserved: AtomicUsize::new(0),
+        }
+    }
+
+    /// a pool whose reservations never fail
+    pub fn unbounded() -> Self {
+        Self::new(usize::MAX)
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// the memory size in bytes of the reservations of the operators
+    pub fn reserved(&self) -> usize {
+        self.reserved.load(Ordering::Acquire)
+    }
+
+    /// reserve the bytes if the reservations do not exceed the limit afterwards
+    fn try_reserve(&self, bytes: usize) -> bool {
+        self.reserved
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |reserved| {
+                reserved.checked_add(bytes).filter(|reserved| *reserved <= self.limit)
+            })
+            .is_ok()
+    }
+
+    fn release(&self, bytes: usize) {
+        self.reserved.fetch_sub(bytes, Ordering::AcqRel);
+    }
+}
+
+/// The memory reserved by an operator in a pool, the memory is returned to the pool when the reservation is dropped
+#[derive(Debug)]
+pub struct MemoryReservation {
+    pool: Arc<MemoryPool>,
+    /// the operator the memory is reserved for, named by the error when the pool is exhausted
+    consumer: String,
+    size: usize,
+}
+
+impl MemoryReservation {
+    pub fn new(pool: &Arc<MemoryPool>, consumer: impl Into<String>) -> Self {
+        Self {
+            pool: pool.clone(),
+            consumer: consumer.into(),
+            size: 0,
+        }
+    }
+
+    /// the memory size in bytes of the reservation
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Reserve `bytes` more, fails without reserving anything if the reservations of the pool would exceed its limit
+    pub fn try_grow(&mut self, bytes: usize) -> Result<()> {
+        if !self.pool.try_reserve(bytes) {
+            return Err(Error::ResourcesExhausted(format!(
+                "{} failed to reserve {} bytes, {} of the {} bytes of the query memory limit are reserved",
+                self.consumer,
+                bytes,
+                self.pool.reserved(),
+                self.pool.limit()
+            )));
+        }
+        self.size += bytes;
+        Ok(())
+    }
+
+    /// return `bytes` of the reservation to the pool, at most the size of the reservation
+    pub fn shrink(&mut self, bytes: usize) {
+        let bytes = bytes.min(self.size);
+        self.pool.release(bytes);
+        self.size -= bytes;
+    }
+
+    /// return the whole reservation to the pool
+    pub fn free(&mut self) {
+        self.shrink(self.size);
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.free();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_reservation() -> Result<()> {
+        let pool = Arc::new(MemoryPool::new(100));
+        let mut sort = MemoryReservation::new(&pool, "Sort");
+        let mut join = MemoryReservation::new(&pool, "HashJoin");
+
+        sort.try_grow(60)?;
+        join.try_grow(30)?;
+        assert_eq!(pool.reserved(), 90);
+
+        // a failed reservation reserves nothing
+        assert_eq!(
+            join.try_grow(20).unwrap_err().to_string(),
+            "Resources Exhausted: HashJoin failed to reserve 20 bytes, 90 of the 100 bytes of the query memory limit are reserved"
+        );
+        assert_eq!(join.size(), 30);
+
+        sort.shrink(40);
+        join.try_grow(20)?;
+        assert_eq!(pool.reserved(), 70);
+
+        // the memory of a dropped reservation is returned to the pool
+        drop(sort);
+        assert_eq!(pool.reserved(), 50);
+        join.free();
+        assert_eq!(pool.reserved(), 0);
+
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
+pub mod config;
+pub mod memory_pool;
 pub mod session;
 
-mod config;
 mod providers;
-mod information_schema;
\ No newline at end of file
+mod information_schema;
+mod transaction;
\ No newline at end of file
@@ -1,5 +1,11 @@
+#[cfg(feature = "delta")]
+use crate::datasource::file::delta::DeltaReadOptions;
+#[cfg(feature = "excel")]
+use crate::datasource::file::excel::ExcelReadOptions;
+#[cfg(feature = "iceberg")]
+use crate::datasource::file::iceberg::IcebergReadOptions;
 use crate::{
-    datasource::file::{self, csv::CsvReadOptions},
+    datasource::file::{self, csv::CsvReadOptions, json::JsonReadOptions, parquet::ParquetReadOptions},
     provider::{catalog::CatalogProvider, schema::SchemaProvider, table::TableProvider},
 };
 use dashmap::DashMap;
@@ -76,6 +82,10 @@ impl SchemaProvider for MemorySchemaProvider {
     }
 }
 
+/// the extensions of the csv and json files, uncompressed or compressed
+const CSV_EXTENSIONS: &[&str] = &["csv", "csv.gz", "csv.gzip", "csv.zst", "csv.zstd", "csv.bz2"];
+const JSON_EXTENSIONS: &[&str] = &["json", "json.gz", "json.gzip", "json.zst", "json.zstd", "json.bz2"];
+
 pub struct DefaultTableFactory {}
 
 impl DefaultTableFactory {
@@ -84,14 +94,47 @@ impl DefaultTableFactory {
     }
 
     pub fn create_csv_table(&self, path: &str, opts: CsvReadOptions) -> Result<Arc<dyn TableProvider>> {
-        file::csv::read_csv(path, opts)
+        file::listing::read_files(path, CSV_EXTENSIONS, |path| file::csv::read_csv(path, opts.clone()))
+    }
+
+    pub fn create_parquet_table(&self, path: &str, opts: ParquetReadOptions) -> Result<Arc<dyn TableProvider>> {
+        file::listing::read_files_with_merge_schema(path, &["parquet"], opts.merge_schema, |path| {
+            file::parquet::read_parquet(path)
+        })
+    }
+
+    #[cfg(feature = "delta")]
+    pub fn create_delta_table(&self, path: &str, opts: DeltaReadOptions) -> Result<Arc<dyn TableProvider>> {
+        file::delta::read_delta(path, opts)
+    }
+
+    #[cfg(feature = "iceberg")]
+    pub fn create_iceberg_table(&self, path: &str, opts: IcebergReadOptions) -> Result<Arc<dyn TableProvider>> {
+        file::iceberg::read_iceberg(path, opts)
+    }
+
+    #[cfg(feature = "avro")]
+    pub fn create_avro_table(&self, path: &str) -> Result<Arc<dyn TableProvider>> {
+        file::listing::read_files(path, &["avro"], |path| file::avro::read_avro(path))
+    }
+
+    #[cfg(feature = "orc")]
+    pub fn create_orc_table(&self, path: &str) -> Result<Arc<dyn TableProvider>> {
+        file::listing::read_files(path, &["orc"], |path| file::orc::read_orc(path))
+    }
+
+    #[cfg(feature = "excel")]
+    pub fn create_excel_table(&self, path: &str, opts: ExcelReadOptions) -> Result<Arc<dyn TableProvider>> {
+        file::listing::read_files(path, &["xlsx"], |path| file::excel::read_excel(path, opts.clone()))
     }
 
-    pub fn create_parquet_table(&self, path: &str) -> Result<Arc<dyn TableProvider>> {
-        file::parquet::read_parquet(path)
+    pub fn create_ipc_table(&self, path: &str) -> Result<Arc<dyn TableProvider>> {
+        file::listing::read_files(path, &["arrow", "arrows", "ipc", "feather"], |path| {
+            file::ipc::read_ipc(path)
+        })
     }
 
-    pub fn create_json_table(&self, path: &str) -> Result<Arc<dyn TableProvider>> {
-        file::json::read_json(path)
+    pub fn create_json_table(&self, path: &str, opts: JsonReadOptions) -> Result<Arc<dyn TableProvider>> {
+        file::listing::read_files(path, JSON_EXTENSIONS, |path| file::json::read_json(path, opts.clone()))
     }
 }
@@ -1,41 +1,67 @@
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::vec;
 
-use arrow::array::RecordBatch;
-use sqlparser::ast::Statement;
+use arrow::array::{ArrayRef, RecordBatch, RecordBatchOptions, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use sqlparser::ast::{Expression, Literal, Statement};
 use sqlparser::parser::{Parser, TableInfo};
+use url::Url;
 
 use crate::common::table_relation::TableRelation;
+use crate::datasource::file::cache::file_metadata_cache;
+use crate::datasource::file::object_store::{is_object_url, ObjectStore, ObjectStoreRegistry};
+use crate::datasource::file::{self, csv::CsvWriteOptions, parquet::ParquetWriteOptions};
 use crate::datasource::memory::MemoryTable;
+use crate::datasource::view::ViewTable;
+use crate::datatypes::scalar::ScalarValue;
 use crate::error::Error;
 use crate::functions::{all_builtin_functions, UserDefinedFunction};
+use crate::logical::expr::LogicalExpr;
 use crate::logical::plan::{
-    CreateMemoryTable, DdlStatement, DmlOperator, DmlStatement, DropTable, Filter, LogicalPlan,
+    CopyTo, CreateMemoryTable, CreateSchema, CreateView, DdlStatement, Deallocate, DmlOperator, DmlStatement,
+    DropSchema, DropTable, DropView, Execute, Explain, FileFormat, Filter, LogicalPlan, Prepare, PreparedStatement,
+    Projection,
 };
 use crate::optimizer::Optimizer;
-use crate::planner::sql::{parse_csv_options, parse_file_path, SqlQueryPlanner};
+use crate::physical::plan::{batches_stream, PhysicalPlan, SendableRecordBatchStream};
+#[cfg(feature = "delta")]
+use crate::planner::sql::parse_delta_options;
+#[cfg(feature = "excel")]
+use crate::planner::sql::parse_excel_options;
+#[cfg(feature = "iceberg")]
+use crate::planner::sql::parse_iceberg_options;
+use crate::planner::sql::{
+    parse_csv_options, parse_file_path, parse_json_options, parse_parquet_options, SqlQueryPlanner,
+};
 use crate::planner::QueryPlanner;
 use crate::provider::catalog::CatalogProvider;
 use crate::provider::schema::SchemaProvider;
-use crate::provider::table::TableProvider;
+use crate::provider::table::{TableProvider, TableType};
 use crate::utils::batch::make_count_batch;
+use crate::{arrow_err, internal_err, utils};
 use crate::{error::Result, planner::DefaultQueryPlanner};
-use crate::{internal_err, utils};
 
 use crate::execution::providers::CatalogProviderList;
 
 use super::config::SessionConfig;
 use super::information_schema::{InformationSchemaProvider, INFORMATION_SCHEMA};
 use super::providers::{DefaultTableFactory, MemoryCatalogProvider, MemorySchemaProvider};
+use super::transaction::Transaction;
 
 pub struct ExecuteSession {
-    config: SessionConfig,
-    planner: Arc<dyn QueryPlanner>,
+    /// the configuration of the session, changed by `SET`
+    config: RwLock<SessionConfig>,
     table_factory: DefaultTableFactory,
     catalog_list: Arc<CatalogProviderList>,
     optimizer: Optimizer,
     udfs: RwLock<HashMap<String, Arc<dyn UserDefinedFunction>>>,
+    /// the prepared statements of the session by name
+    prepared: RwLock<HashMap<String, Prepare>>,
+    /// the transaction in progress, started by `BEGIN`
+    transaction: RwLock<Option<Transaction>>,
+    /// the stores the data files are read from, shared with the sessions created by `new_session`
+    object_stores: Arc<ObjectStoreRegistry>,
 }
 
 impl ExecuteSession {
@@ -61,47 +87,166 @@ impl ExecuteSession {
         );
 
         Ok(Self {
-            config,
-            planner: Arc::new(DefaultQueryPlanner::default()),
+            object_stores: Arc::new(ObjectStoreRegistry::new(config.object_store_options.clone())),
+            config: RwLock::new(config),
             catalog_list,
             table_factory: DefaultTableFactory::new(),
             optimizer: Optimizer::new(),
             udfs,
+            prepared: RwLock::new(HashMap::new()),
+            transaction: RwLock::new(None),
+        })
+    }
+
+    /// Another session on the catalogs of this session, with a copy of its configuration and functions and its own
+    /// prepared statements and transaction. The sessions see the tables each other create and the committed
+    /// changes of each other.
+    pub fn new_session(&self) -> Result<Self> {
+        let udfs = self
+            .udfs
+            .read()
+            .map_err(|e| Error::InternalError(format!("failed to get udfs: {}", e)))?
+            .clone();
+
+        Ok(Self {
+            config: RwLock::new(self.config()?.clone()),
+            catalog_list: self.catalog_list.clone(),
+            table_factory: DefaultTableFactory::new(),
+            optimizer: Optimizer::new(),
+            udfs: RwLock::new(udfs),
+            prepared: RwLock::new(HashMap::new()),
+            transaction: RwLock::new(None),
+            object_stores: self.object_stores.clone(),
         })
     }
 
     pub fn sql(&self, sql: &str) -> Result<Vec<RecordBatch>> {
         // parse sql collect tables
-        let mut parser = Parser::new(sql);
-        let stmt = match parser.parse().map_err(|e| Error::SQLParseError(e))? {
+        let dialect = self.config()?.dialect.clone();
+        let mut parser = Parser::with_dialect(sql, dialect.as_ref());
+        let stmt = parser.parse().map_err(Error::SQLParseError)?;
+        self.execute_statement(stmt, parser.tables)
+    }
+
+    /// Execute a script of statements separated by semicolons, returns the result of each statement. A statement
+    /// is planned after the previous ones are executed, so it can use the tables they create. The script stops
+    /// at the first error.
+    pub fn execute_script(&self, sql: &str) -> Result<Vec<Vec<RecordBatch>>> {
+        let dialect = self.config()?.dialect.clone();
+        let mut parser = Parser::with_dialect(sql, dialect.as_ref());
+        let mut results = vec![];
+        while let Some(stmt) = parser.parse_next_statement().map_err(Error::SQLParseError)? {
+            let tables = std::mem::take(&mut parser.tables);
+            results.push(self.execute_statement(stmt, tables)?);
+        }
+
+        Ok(results)
+    }
+
+    /// a failed statement aborts the transaction in progress, the later statements fail until it ends
+    fn execute_statement(&self, stmt: Statement, tables: Vec<TableInfo>) -> Result<Vec<RecordBatch>> {
+        match stmt {
+            Statement::StartTransaction => return self.begin_transaction(),
+            Statement::Commit => return self.end_transaction(true),
+            Statement::Rollback => return self.end_transaction(false),
+            _ => {}
+        }
+        self.check_not_aborted()?;
+        let result = self.plan_and_execute(stmt, tables);
+        self.abort_on_error(result)
+    }
+
+    /// Execute the statement like `sql` but stream the rows of a query. The scans, filters, projections, limits and
+    /// aggregations of sorted input compute their batches as the stream is polled, the other operators (sorts, joins,
+    /// hash aggregations, ...) still compute their whole output when the stream is created, so a query is only
+    /// consumed incrementally up to its first such operator. The other statements are executed before the stream is
+    /// returned, an error while polling the stream does not abort the transaction in progress.
+    pub fn sql_stream(&self, sql: &str) -> Result<SendableRecordBatchStream> {
+        let dialect = self.config()?.dialect.clone();
+        let mut parser = Parser::with_dialect(sql, dialect.as_ref());
+        let stmt = parser.parse().map_err(Error::SQLParseError)?;
+        if !matches!(
+            stmt,
+            Statement::Select(_) | Statement::SetOperation { .. } | Statement::Values(_)
+        ) {
+            return self.execute_statement(stmt, parser.tables).map(batches_stream);
+        }
+
+        self.check_not_aborted()?;
+        let result = self.create_logical_plan(stmt, parser.tables).and_then(|plan| {
+            let plan = self.create_physical_plan(&plan)?;
+            self.object_stores.enter(|| plan.execute_stream())
+        });
+        self.abort_on_error(result)
+    }
+
+    fn check_not_aborted(&self) -> Result<()> {
+        if self
+            .transaction()?
+            .as_ref()
+            .is_some_and(|transaction| transaction.aborted)
+        {
+            return Err(Error::PlanError(
+                "current transaction is aborted, commands ignored until end of transaction block".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn abort_on_error<T>(&self, result: Result<T>) -> Result<T> {
+        if result.is_err() {
+            if let Some(transaction) = self.transaction()?.as_mut() {
+                transaction.aborted = true;
+            }
+        }
+        result
+    }
+
+    fn plan_and_execute(&self, stmt: Statement, tables: Vec<TableInfo>) -> Result<Vec<RecordBatch>> {
+        let (stmt, tables) = match stmt {
             Statement::ShowTables => {
-                parser = Parser::new("SELECT * FROM information_schema.tables");
-                parser.parse().map_err(|e| Error::SQLParseError(e))?
+                let mut parser = Parser::new("SELECT * FROM information_schema.tables");
+                let stmt = parser.parse().map_err(Error::SQLParseError)?;
+                (stmt, parser.tables)
             }
-            stmt => stmt,
+            Statement::ShowColumns { table } => return self.execute_show_columns(&table.to_string().into()),
+            Statement::SetVariable { variable, value } => return self.execute_set_variable(&variable.value, value),
+            Statement::ShowVariable { variable } => return self.execute_show_variable(&variable.value),
+            stmt => (stmt, tables),
         };
+        let logical_plan = self.create_logical_plan(stmt, tables)?;
+        self.execute_logical_plan(&logical_plan)
+    }
+
+    fn create_logical_plan(&self, stmt: Statement, tables: Vec<TableInfo>) -> Result<LogicalPlan> {
         // register tables for statement if there are any file source tables to be registered
-        let relations = self.resolve_tables(parser.tables)?;
+        let relations = self.object_stores.enter(|| self.resolve_tables(tables))?;
         let udfs = &self
             .udfs
             .read()
             .map_err(|e| Error::InternalError(format!("failed to get udfs: {}", e)))?;
         // create logical plan
-        SqlQueryPlanner::create_logical_plan(stmt, relations, udfs)
-            .and_then(|logical_plan| self.execute_logical_plan(&logical_plan))
+        SqlQueryPlanner::create_logical_plan(stmt, relations, udfs, &*self.config()?)
+    }
+
+    /// the optimized physical plan of a query
+    fn create_physical_plan(&self, plan: &LogicalPlan) -> Result<Arc<dyn PhysicalPlan>> {
+        log::debug!("before optimize: \n{}", utils::format(plan, 0));
+        let plan = self.optimizer.optimize(plan)?;
+        log::debug!("after optimize: \n{}", utils::format(&plan, 0));
+        self.planner()?.create_physical_plan(&plan)
     }
 
     pub fn execute_logical_plan(&self, plan: &LogicalPlan) -> Result<Vec<RecordBatch>> {
-        match &plan {
+        // the files are read from the stores of the session
+        self.object_stores.enter(|| match &plan {
             LogicalPlan::Ddl(ddl) => self.execute_ddl(ddl),
             LogicalPlan::Dml(stmt) => self.execute_dml(stmt),
-            plan => {
-                println!("before optimize: \n{}", utils::format(&plan, 0));
-                let plan = self.optimizer.optimize(plan)?;
-                println!("after optimize: \n{}", utils::format(&plan, 0));
-                self.planner.create_physical_plan(&plan)?.execute()
-            }
-        }
+            LogicalPlan::Explain(explain) => self.execute_explain(explain),
+            LogicalPlan::CopyTo(copy) => self.execute_copy_to(copy),
+            LogicalPlan::Prepared(stmt) => self.execute_prepared(stmt),
+            plan => self.create_physical_plan(plan)?.execute(),
+        })
     }
 
     pub fn register_table(&self, name: &str, table_provider: Arc<dyn TableProvider>) -> Result<()> {
@@ -123,31 +268,359 @@ impl ExecuteSession {
         udfs.insert(name.to_string(), udf);
         Ok(())
     }
+
+    /// register the store the files of the URLs with the scheme and the authority of the URL, like `s3://bucket`, are
+    /// read from. The stores are shared with the sessions created by `new_session`.
+    pub fn register_object_store(&self, url: &str, store: Arc<dyn ObjectStore>) -> Result<()> {
+        let url = Url::parse(url).map_err(|e| Error::InternalError(format!("{}: {}", url, e)))?;
+        self.object_stores.register_store(&url, store);
+        Ok(())
+    }
+
+    /// Forget the cached metadata of the files under the path, a file, a directory or the URL of an object or of a
+    /// prefix of objects, so that the next query lists the objects and reads the footers and the schemas again. The
+    /// cache is shared by the sessions of the process.
+    pub fn invalidate_file_metadata(&self, path: &str) -> Result<()> {
+        let url = match is_object_url(path) {
+            true => Url::parse(path).map_err(|e| Error::InternalError(format!("{}: {}", path, e)))?,
+            false => Url::from_file_path(std::fs::canonicalize(path).or_else(|_| std::path::absolute(path))?)
+                .map_err(|_| Error::InternalError(format!("{} is not a local path", path)))?,
+        };
+        file_metadata_cache().invalidate(url.as_str());
+        Ok(())
+    }
 }
 
 impl ExecuteSession {
+    /// the optimized logical plan and the physical plan of the query, one row per line of the plans, VERBOSE
+    /// adds the plan before optimization and the schema of each operator. ANALYZE executes the query with the
+    /// operators instrumented and shows their metrics, the result of the query is discarded.
+    fn execute_explain(&self, explain: &Explain) -> Result<Vec<RecordBatch>> {
+        let optimized = self.optimizer.optimize(&explain.plan)?;
+        let physical_plan = if explain.analyze {
+            let physical_plan = self.planner()?.with_metrics().create_physical_plan(&optimized)?;
+            physical_plan.execute()?;
+            physical_plan
+        } else {
+            self.planner()?.create_physical_plan(&optimized)?
+        };
+
+        let mut plans = vec![];
+        if explain.verbose {
+            plans.push(("initial_logical_plan", utils::format_with_schema(&explain.plan, 1)));
+            plans.push(("logical_plan", utils::format_with_schema(&optimized, 1)));
+        } else {
+            plans.push(("logical_plan", utils::format(&optimized, 1)));
+        }
+        plans.push((
+            "physical_plan",
+            utils::format_physical(&physical_plan, 1, explain.verbose),
+        ));
+
+        let lines = plans
+            .iter()
+            .flat_map(|(plan_type, plan)| std::iter::once(*plan_type).chain(plan.lines()))
+            .collect::<Vec<_>>();
+
+        RecordBatch::try_new(explain.schema(), vec![Arc::new(StringArray::from(lines))])
+            .map(|batch| vec![batch])
+            .map_err(|e| arrow_err!(e))
+    }
+
+    /// one row per column of the table, its name, its arrow data type and whether it is nullable
+    fn execute_show_columns(&self, table: &TableRelation) -> Result<Vec<RecordBatch>> {
+        let schema = self.find_table_provider(table)?.schema();
+        let fields = schema.fields();
+        let output = Arc::new(Schema::new(vec![
+            Field::new("column_name", DataType::Utf8, false),
+            Field::new("data_type", DataType::Utf8, false),
+            Field::new("is_nullable", DataType::Utf8, false),
+        ]));
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from_iter_values(fields.iter().map(|f| f.name()))),
+            Arc::new(StringArray::from_iter_values(
+                fields.iter().map(|f| f.data_type().to_string()),
+            )),
+            Arc::new(StringArray::from_iter_values(fields.iter().map(|f| {
+                if f.is_nullable() {
+                    "YES"
+                } else {
+                    "NO"
+                }
+            }))),
+        ];
+
+        RecordBatch::try_new(output, columns)
+            .map(|batch| vec![batch])
+            .map_err(|e| arrow_err!(e))
+    }
+
+    fn begin_transaction(&self) -> Result<Vec<RecordBatch>> {
+        let mut transaction = self.transaction()?;
+        if transaction.is_some() {
+            return Err(Error::PlanError(
+                "there is already a transaction in progress".to_owned(),
+            ));
+        }
+        *transaction = Some(Transaction::default());
+        Ok(vec![])
+    }
+
+    /// COMMIT writes the changes of the transaction to the tables, ROLLBACK or the COMMIT of an aborted transaction
+    /// discards them
+    fn end_transaction(&self, commit: bool) -> Result<Vec<RecordBatch>> {
+        let Some(transaction) = self.transaction()?.take() else {
+            return Err(Error::PlanError("there is no transaction in progress".to_owned()));
+        };
+        if commit && !transaction.aborted {
+            transaction.commit()?;
+        }
+        Ok(vec![])
+    }
+
+    fn transaction(&self) -> Result<RwLockWriteGuard<'_, Option<Transaction>>> {
+        self.transaction
+            .write()
+            .map_err(|e| Error::InternalError(format!("failed to get transaction: {}", e)))
+    }
+
+    /// `SET name = value`, a list of values is joined with commas, eg. `SET search_path = s1, s2`
+    fn execute_set_variable(&self, name: &str, value: Vec<Expression>) -> Result<Vec<RecordBatch>> {
+        let value = value
+            .into_iter()
+            .map(|expr| match expr {
+                Expression::Literal(Literal::String(s)) => Ok(s),
+                Expression::Literal(Literal::Null) => internal_err!("SET {} does not accept NULL", name),
+                Expression::Literal(literal) => Ok(literal.to_string()),
+                Expression::Identifier(ident) => Ok(ident.value),
+                expr => internal_err!("SET {} requires a literal or an identifier, but got {}", name, expr),
+            })
+            .collect::<Result<Vec<_>>>()?
+            .join(",");
+        // the default catalog and schema must exist
+        let missing = match name.to_ascii_lowercase().as_str() {
+            "default_catalog" => self.catalog_list.catalog(&value.to_ascii_lowercase()).is_none(),
+            "default_schema" => self.default_catalog()?.schema(&value.to_ascii_lowercase()).is_none(),
+            _ => false,
+        };
+        if missing {
+            return Err(Error::PlanError(format!("invalid value for parameter {name}: {value}")));
+        }
+
+        let mut config = self
+            .config
+            .write()
+            .map_err(|e| Error::InternalError(format!("failed to set config: {}", e)))?;
+        config.set(name, &value)?;
+
+        Ok(vec![])
+    }
+
+    /// `SHOW name` is the value of a configuration parameter, `SHOW ALL` lists all of them
+    fn execute_show_variable(&self, name: &str) -> Result<Vec<RecordBatch>> {
+        let config = self.config()?;
+        let options = if name.eq_ignore_ascii_case("all") {
+            config.options()
+        } else {
+            let value = config.get(name)?;
+            vec![(name, value)]
+        };
+        let output = Arc::new(Schema::new(vec![
+            Field::new("name", DataType::Utf8, false),
+            Field::new("value", DataType::Utf8, true),
+        ]));
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from_iter_values(
+                options.iter().map(|(name, _)| name.to_ascii_lowercase()),
+            )),
+            Arc::new(StringArray::from_iter(options.into_iter().map(|(_, value)| value))),
+        ];
+
+        RecordBatch::try_new(output, columns)
+            .map(|batch| vec![batch])
+            .map_err(|e| arrow_err!(e))
+    }
+
+    /// COPY ... TO executes the query and writes all of its rows to the file at once
+    fn execute_copy_to(&self, copy: &CopyTo) -> Result<Vec<RecordBatch>> {
+        let input = self.optimizer.optimize(&copy.input)?;
+        let batches = self.planner()?.create_physical_plan(&input)?.execute()?;
+        let path = copy.file_path.as_str();
+        match copy.format {
+            FileFormat::Csv {
+                has_header,
+                delimiter,
+                compression,
+            } => file::csv::write_csv(
+                path,
+                &batches,
+                CsvWriteOptions {
+                    has_header,
+                    delimiter,
+                    compression,
+                },
+            ),
+            FileFormat::Json => file::json::write_json(path, &batches),
+            FileFormat::Parquet {
+                ref compression,
+                row_group_size,
+            } => {
+                let mut options = ParquetWriteOptions::default();
+                if let Some(compression) = compression {
+                    options.compression = file::parquet::parse_compression(compression)?;
+                }
+                if let Some(row_group_size) = row_group_size {
+                    options.max_row_group_size = row_group_size;
+                }
+                file::parquet::write_parquet_with_options(path, input.schema(), &batches, &options)
+            }
+        }?;
+
+        let rows = batches.iter().map(|batch| batch.num_rows() as u64).sum();
+        Ok(vec![make_count_batch(rows)])
+    }
+
+    /// PREPARE keeps the plan of the statement, EXECUTE replaces the placeholders of the plan with the parameters
+    /// and executes it, so the statement is parsed and planned only once.
+    fn execute_prepared(&self, stmt: &PreparedStatement) -> Result<Vec<RecordBatch>> {
+        match stmt {
+            PreparedStatement::Prepare(prepare) => {
+                let mut prepared = self
+                    .prepared
+                    .write()
+                    .map_err(|e| Error::InternalError(format!("failed to get prepared statements: {}", e)))?;
+                if prepared.contains_key(&prepare.name) {
+                    return Err(Error::PlanError(format!(
+                        "Prepared statement already exists: {}",
+                        prepare.name
+                    )));
+                }
+                prepared.insert(prepare.name.clone(), prepare.clone());
+                Ok(vec![])
+            }
+            PreparedStatement::Execute(Execute { name, parameters }) => {
+                let prepare = self
+                    .prepared
+                    .read()
+                    .map_err(|e| Error::InternalError(format!("failed to get prepared statements: {}", e)))?
+                    .get(name)
+                    .cloned()
+                    .ok_or(Error::PlanError(format!("Prepared statement not found: {}", name)))?;
+                if parameters.len() != prepare.data_types.len() {
+                    return Err(Error::PlanError(format!(
+                        "Prepared statement {} expects {} parameters, but got {}",
+                        name,
+                        prepare.data_types.len(),
+                        parameters.len()
+                    )));
+                }
+
+                let values = parameters
+                    .iter()
+                    .zip(prepare.data_types.iter())
+                    .map(|(parameter, data_type)| self.evaluate_parameter(parameter, data_type))
+                    .collect::<Result<Vec<_>>>()?;
+                self.execute_logical_plan(&(*prepare.input).with_param_values(&values)?)
+            }
+            PreparedStatement::Deallocate(Deallocate { name }) => self
+                .prepared
+                .write()
+                .map_err(|e| Error::InternalError(format!("failed to get prepared statements: {}", e)))?
+                .remove(name)
+                .map(|_| vec![])
+                .ok_or(Error::PlanError(format!("Prepared statement not found: {}", name))),
+        }
+    }
+
+    /// the value of a parameter of EXECUTE cast to the data type of its placeholder
+    fn evaluate_parameter(&self, parameter: &LogicalExpr, data_type: &DataType) -> Result<ScalarValue> {
+        let schema = Arc::new(Schema::empty());
+        let batch = RecordBatch::try_new_with_options(
+            schema.clone(),
+            vec![],
+            &RecordBatchOptions::new().with_row_count(Some(1)),
+        )
+        .map_err(|e| arrow_err!(e))?;
+        let array = self
+            .planner()?
+            .create_physical_expr(&schema, &parameter.clone().cast_to(data_type))?
+            .evaluate(&batch)?;
+
+        ScalarValue::try_from_array(&array, 0)
+    }
+
     fn execute_dml(&self, stmt: &DmlStatement) -> Result<Vec<RecordBatch>> {
-        let source = self.find_table_provider(&stmt.relation)?;
-        let rows_affected = match stmt.op {
+        let mut source = self.find_table_provider(&stmt.relation)?;
+        if let Some(transaction) = self.transaction()?.as_mut() {
+            source = transaction.table_mut(&source)?;
+        }
+        let rows_affected = match &stmt.op {
             DmlOperator::Insert => self.execute_insert(source, &stmt.input),
-            DmlOperator::Delete => self.execute_delete(source, &stmt.input),
-            _ => internal_err!("Unsupported DML {} operation", stmt.op),
+            DmlOperator::Update => self.execute_update(source, &stmt.input),
+            DmlOperator::Upsert(keys) => self.execute_upsert(source, keys, &stmt.input),
+            DmlOperator::Truncate => source.truncate(),
+            DmlOperator::Delete => {
+                if !matches!(*stmt.input, LogicalPlan::Filter(_)) && !self.config()?.allow_delete_without_where {
+                    return internal_err!(
+                        "DELETE without WHERE deletes all rows of table [{}], use WHERE true to confirm",
+                        stmt.relation
+                    );
+                }
+                self.execute_delete(source, &stmt.input)
+            }
         }?;
 
         Ok(vec![make_count_batch(rows_affected)])
     }
 
     fn execute_delete(&self, source: Arc<dyn TableProvider>, input: &LogicalPlan) -> Result<u64> {
-        let predicate = if let LogicalPlan::Filter(Filter { input, expr }) = input {
-            Some(self.planner.create_physical_expr(&input.schema(), expr)?)
+        let input = self.optimizer.optimize(input)?;
+        let predicate = if let LogicalPlan::Filter(Filter { input, expr }) = &input {
+            Some(self.planner()?.create_physical_expr(&input.schema(), expr)?)
         } else {
             None
         };
         source.delete(predicate)
     }
 
+    /// the input is the projection of the new values of all columns over the scan of the rows to update
+    fn execute_update(&self, source: Arc<dyn TableProvider>, input: &LogicalPlan) -> Result<u64> {
+        let input = self.optimizer.optimize(input)?;
+        let LogicalPlan::Projection(Projection { input, exprs, .. }) = &input else {
+            return internal_err!("UPDATE requires a projection of the new values, but got {}", input);
+        };
+        let (scan, predicate) = match input.as_ref() {
+            LogicalPlan::Filter(Filter { input, expr }) => (input.as_ref(), Some(expr)),
+            plan => (plan, None),
+        };
+
+        let schema = scan.schema();
+        let planner = self.planner()?;
+        let values = exprs
+            .iter()
+            .map(|expr| planner.create_physical_expr(&schema, expr))
+            .collect::<Result<Vec<_>>>()?;
+        let predicate = predicate
+            .map(|expr| planner.create_physical_expr(&schema, expr))
+            .transpose()?;
+
+        source.update(values, predicate)
+    }
+
+    fn execute_upsert(&self, source: Arc<dyn TableProvider>, keys: &[String], input: &LogicalPlan) -> Result<u64> {
+        let schema = source.schema();
+        let keys = keys
+            .iter()
+            .map(|key| schema.index_of(key).map_err(|e| arrow_err!(e)))
+            .collect::<Result<Vec<_>>>()?;
+        let input = self.optimizer.optimize(input)?;
+        let physical_plan = self.planner()?.create_physical_plan(&input)?;
+        source.upsert(physical_plan, keys)
+    }
+
     fn execute_insert(&self, source: Arc<dyn TableProvider>, input: &LogicalPlan) -> Result<u64> {
-        let physical_plan = self.planner.create_physical_plan(input)?;
+        let input = self.optimizer.optimize(input)?;
+        let physical_plan = self.planner()?.create_physical_plan(&input)?;
         source.insert(physical_plan)
     }
     /// Resolve tables from the table registry
@@ -170,7 +643,12 @@ impl ExecuteSession {
     fn resolve_table(&self, mut table: TableInfo) -> Result<(TableRelation, Arc<dyn TableProvider>)> {
         if table.args.is_empty() {
             let relation = table.name.into();
-            self.find_table_provider(&relation).map(|provider| (relation, provider))
+            let provider = self.find_table_provider(&relation)?;
+            // the statements of a transaction read the tables with its changes
+            match self.transaction()?.as_ref() {
+                Some(transaction) => Ok((relation, transaction.table(&provider))),
+                None => Ok((relation, provider)),
+            }
         } else {
             let path = parse_file_path(&mut table.args)?;
 
@@ -181,19 +659,49 @@ impl ExecuteSession {
                     .map(|provider| (TableRelation::parse_file_path(&path), provider)),
                 "read_json" => self
                     .table_factory
-                    .create_json_table(&path)
+                    .create_json_table(&path, parse_json_options(table.args)?)
                     .map(|provider| (TableRelation::parse_file_path(&path), provider)),
                 "read_parquet" => self
                     .table_factory
-                    .create_parquet_table(&path)
+                    .create_parquet_table(&path, parse_parquet_options(table.args)?)
+                    .map(|provider| (TableRelation::parse_file_path(&path), provider)),
+                #[cfg(feature = "delta")]
+                "read_delta" => self
+                    .table_factory
+                    .create_delta_table(&path, parse_delta_options(table.args)?)
+                    .map(|provider| (TableRelation::parse_file_path(&path), provider)),
+                #[cfg(feature = "iceberg")]
+                "read_iceberg" => self
+                    .table_factory
+                    .create_iceberg_table(&path, parse_iceberg_options(table.args)?)
                     .map(|provider| (TableRelation::parse_file_path(&path), provider)),
-                _ => unimplemented!("not support table function: {}", table.name),
+                "read_ipc" => self
+                    .table_factory
+                    .create_ipc_table(&path)
+                    .map(|provider| (TableRelation::parse_file_path(&path), provider)),
+                #[cfg(feature = "avro")]
+                "read_avro" => self
+                    .table_factory
+                    .create_avro_table(&path)
+                    .map(|provider| (TableRelation::parse_file_path(&path), provider)),
+                #[cfg(feature = "orc")]
+                "read_orc" => self
+                    .table_factory
+                    .create_orc_table(&path)
+                    .map(|provider| (TableRelation::parse_file_path(&path), provider)),
+                #[cfg(feature = "excel")]
+                "read_excel" => self
+                    .table_factory
+                    .create_excel_table(&path, parse_excel_options(table.args)?)
+                    .map(|provider| (TableRelation::parse_file_path(&path), provider)),
+                // a table function of a format whose feature is not enabled is not supported either
+                _ => internal_err!("not support table function: {}", table.name),
             }
         }
     }
 
     fn find_table_provider(&self, table: &TableRelation) -> Result<Arc<dyn TableProvider>> {
-        self.find_schema_provider(table)?
+        self.find_table_schema(table)?
             .table(table.table())
             .ok_or(Error::InternalError(format!(
                 "failed to resolve table: {}",
@@ -201,14 +709,48 @@ impl ExecuteSession {
             )))
     }
 
+    fn config(&self) -> Result<RwLockReadGuard<'_, SessionConfig>> {
+        self.config
+            .read()
+            .map_err(|e| Error::InternalError(format!("failed to get config: {}", e)))
+    }
+
+    /// the physical planner with the current configuration of the session
+    fn planner(&self) -> Result<DefaultQueryPlanner> {
+        Ok(DefaultQueryPlanner::new(&*self.config()?))
+    }
+
+    /// the schema a table belongs to, a table without a schema is looked up in the default schema
+    /// and then in the schemas of the search path
+    fn find_table_schema(&self, table: &TableRelation) -> Result<Arc<dyn SchemaProvider>> {
+        if table.schema().is_some() {
+            return self.find_schema_provider(table);
+        }
+
+        let config = self.config()?;
+        if let Some(catalog) = self.catalog_list.catalog(&config.default_catalog) {
+            let found = std::iter::once(&config.default_schema)
+                .chain(config.search_path.iter())
+                .filter_map(|name| catalog.schema(name))
+                .find(|schema| schema.table(table.table()).is_some());
+            if let Some(schema) = found {
+                return Ok(schema);
+            }
+        }
+        drop(config);
+
+        self.find_schema_provider(table)
+    }
+
     fn find_schema_provider(&self, table: &TableRelation) -> Result<Arc<dyn SchemaProvider>> {
+        let config = self.config()?;
         self.catalog_list
-            .catalog(table.catalog().unwrap_or(&self.config.default_catalog))
+            .catalog(table.catalog().unwrap_or(&config.default_catalog))
             .ok_or(Error::InternalError(format!(
                 "failed to resolve catalog: {}",
                 table.to_quanlify_name()
             )))?
-            .schema(table.schema().unwrap_or(&self.config.default_schema))
+            .schema(table.schema().unwrap_or(&config.default_schema))
             .ok_or(Error::PlanError(format!(
                 "failed to resolve schema: {}",
                 table.to_quanlify_name()
@@ -217,43 +759,162 @@ impl ExecuteSession {
 
     fn execute_ddl(&self, ddl: &DdlStatement) -> Result<Vec<RecordBatch>> {
         match ddl {
-            DdlStatement::CreateMemoryTable(CreateMemoryTable { schema, name, input }) => {
+            DdlStatement::CreateMemoryTable(CreateMemoryTable {
+                schema,
+                name,
+                if_not_exists,
+                indexes,
+                input,
+            }) => {
                 let table: TableRelation = name.to_ascii_lowercase().into();
                 let schema_provider = self.find_schema_provider(&table)?;
-                let batch = self.execute_logical_plan(input)?;
+                if schema_provider.table(table.table()).is_some() {
+                    return if *if_not_exists {
+                        Ok(vec![])
+                    } else {
+                        Err(Error::PlanError(format!(
+                            "Create table failed, table already exists: {}",
+                            table.to_quanlify_name()
+                        )))
+                    };
+                }
+                // the rows are stored with the schema of the table, a NULL value of a NOT NULL column is an error
+                let batch = self
+                    .execute_logical_plan(input)?
+                    .into_iter()
+                    .map(|batch| {
+                        RecordBatch::try_new(schema.clone(), batch.columns().to_vec()).map_err(|e| arrow_err!(e))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                let mut memory_table = MemoryTable::try_new(schema.clone(), batch)?;
+                for columns in indexes {
+                    memory_table = memory_table.with_index(columns)?;
+                }
+
+                schema_provider
+                    .register_table(table.table().to_owned(), Arc::new(memory_table))
+                    .map(|_| vec![])
+            }
+            DdlStatement::CreateView(CreateView {
+                name,
+                or_replace,
+                input,
+                definition,
+            }) => {
+                let table: TableRelation = name.to_ascii_lowercase().into();
+                let schema_provider = self.find_schema_provider(&table)?;
+                // only a view can be replaced
+                if let Some(existing) = schema_provider.table(table.table()) {
+                    if !or_replace || existing.table_type() != TableType::View {
+                        return Err(Error::PlanError(format!(
+                            "Create view failed, table already exists: {}",
+                            table.to_quanlify_name()
+                        )));
+                    }
+                }
 
                 schema_provider
                     .register_table(
                         table.table().to_owned(),
-                        Arc::new(MemoryTable::try_new(schema.clone(), batch)?),
+                        Arc::new(ViewTable::new(input.as_ref().clone(), Some(definition.clone()))),
                     )
                     .map(|_| vec![])
             }
-            DdlStatement::DropTable(DropTable { name, if_exists }) => {
-                let table: TableRelation = name.to_ascii_lowercase().into();
-                let schema_provider = self.find_schema_provider(&table)?;
-                let provider = schema_provider.deregister_table(table.table())?;
+            DdlStatement::DropTable(DropTable { name, if_exists }) => self.drop_table(name, *if_exists, false),
+            DdlStatement::DropView(DropView { name, if_exists }) => self.drop_table(name, *if_exists, true),
+            DdlStatement::CreateSchema(CreateSchema { name, if_not_exists }) => {
+                let name = name.to_ascii_lowercase();
+                let catalog = self.default_catalog()?;
+                if catalog.schema(&name).is_some() {
+                    return if *if_not_exists {
+                        Ok(vec![])
+                    } else {
+                        Err(Error::PlanError(format!(
+                            "Create schema failed, schema already exists: {name}"
+                        )))
+                    };
+                }
 
-                if provider.is_some() || *if_exists {
-                    Ok(vec![])
-                } else {
-                    Err(Error::PlanError(format!(
-                        "Drop table failed, table not found: {}",
-                        table.to_quanlify_name()
-                    )))
+                catalog
+                    .register_schema(&name, Arc::new(MemorySchemaProvider::default()))
+                    .map(|_| vec![])
+            }
+            DdlStatement::DropSchema(DropSchema { name, if_exists }) => {
+                let name = name.to_ascii_lowercase();
+                if name == self.config()?.default_schema || name == INFORMATION_SCHEMA {
+                    return Err(Error::PlanError(format!(
+                        "Drop schema failed, can not drop schema: {name}"
+                    )));
+                }
+
+                let catalog = self.default_catalog()?;
+                match catalog.schema(&name) {
+                    Some(schema) if !schema.table_names().is_empty() => Err(Error::PlanError(format!(
+                        "Drop schema failed, schema is not empty: {name}"
+                    ))),
+                    Some(_) => catalog.deregister_schema(&name, false).map(|_| vec![]),
+                    None if *if_exists => Ok(vec![]),
+                    None => Err(Error::PlanError(format!(
+                        "Drop schema failed, schema not found: {name}"
+                    ))),
                 }
             }
         }
     }
+
+    fn default_catalog(&self) -> Result<Arc<dyn CatalogProvider>> {
+        let config = self.config()?;
+        self.catalog_list
+            .catalog(&config.default_catalog)
+            .ok_or(Error::InternalError(format!(
+                "failed to resolve catalog: {}",
+                config.default_catalog
+            )))
+    }
+
+    /// deregister a table or a view, dropping a table with `DROP VIEW` or a view with `DROP TABLE` is an error
+    fn drop_table(&self, name: &str, if_exists: bool, is_view: bool) -> Result<Vec<RecordBatch>> {
+        let table: TableRelation = name.into();
+        let kind = if is_view { "view" } else { "table" };
+        let config = self.config()?;
+        let qualified_name = format!(
+            "{}.{}.{}",
+            table.catalog().unwrap_or(&config.default_catalog),
+            table.schema().unwrap_or(&config.default_schema),
+            table.table()
+        );
+        drop(config);
+
+        let schema_provider = self.find_table_schema(&table)?;
+        match schema_provider.table(table.table()) {
+            Some(provider) if (provider.table_type() == TableType::View) == is_view => {
+                schema_provider.deregister_table(table.table()).map(|_| vec![])
+            }
+            Some(_) => Err(Error::PlanError(format!(
+                "Drop {kind} failed, {qualified_name} is not a {kind}"
+            ))),
+            None if if_exists => Ok(vec![]),
+            None => Err(Error::PlanError(format!(
+                "Drop {kind} failed, {kind} not found: {qualified_name}"
+            ))),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::datatypes::operator::Operator;
+    use crate::logical::expr::BinaryExpr;
+    use crate::provider::table::{FilterPushdown, Statistics};
     use crate::{build_schema, datasource::memory::MemoryTable, test_utils::assert_batch_eq};
     use arrow::{
         array::{Int32Array, StringArray},
+        datatypes::SchemaRef,
         util::pretty::print_batches,
     };
+    use futures::executor::block_on;
+    use futures::{StreamExt, TryStreamExt};
 
     use super::*;
 
@@ -440,6 +1101,87 @@ order by
         Ok(())
     }
 
+    /// a table of the ids from 1 to `rows`, evaluating the filters `id > n` exactly
+    #[derive(Debug)]
+    struct IdTable {
+        rows: i64,
+        scans: std::sync::atomic::AtomicUsize,
+    }
+
+    impl TableProvider for IdTable {
+        fn schema(&self) -> SchemaRef {
+            Arc::new(build_schema!(("id", arrow::datatypes::DataType::Int64, false)))
+        }
+
+        fn scan(&self, _projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+            self.scans.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let min = filters
+                .iter()
+                .filter_map(|filter| match filter {
+                    LogicalExpr::BinaryExpr(BinaryExpr {
+                        right,
+                        op: Operator::Gt,
+                        ..
+                    }) => match right.as_ref() {
+                        LogicalExpr::Literal(ScalarValue::Int64(Some(n))) => Some(*n),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .max()
+                .unwrap_or(0);
+            let ids = arrow::array::Int64Array::from_iter_values(min + 1..=self.rows);
+            Ok(vec![RecordBatch::try_new(self.schema(), vec![Arc::new(ids)])?])
+        }
+
+        fn supports_filter_pushdown(&self, filter: &LogicalExpr) -> FilterPushdown {
+            match filter {
+                LogicalExpr::BinaryExpr(BinaryExpr {
+                    left,
+                    op: Operator::Gt,
+                    right,
+                }) if matches!(left.as_ref(), LogicalExpr::Column(column) if column.name == "id")
+                    && matches!(right.as_ref(), LogicalExpr::Literal(ScalarValue::Int64(Some(_)))) =>
+                {
+                    FilterPushdown::Exact
+                }
+                _ => FilterPushdown::Unsupported,
+            }
+        }
+
+        fn statistics(&self) -> Option<Statistics> {
+            Some(Statistics {
+                num_rows: Some(self.rows as usize),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_register_custom_table() -> Result<()> {
+        let session = ExecuteSession::new()?;
+        let table = Arc::new(IdTable {
+            rows: 5,
+            scans: Default::default(),
+        });
+        session.register_table("t", table.clone())?;
+        let scans = || table.scans.load(std::sync::atomic::Ordering::SeqCst);
+
+        // the number of rows is read from the statistics
+        let batches = session.sql("SELECT count(*) AS c FROM t")?;
+        assert_batch_eq(&batches, vec!["+---+", "| c |", "+---+", "| 5 |", "+---+"]);
+        assert_eq!(scans(), 0);
+
+        let batches = session.sql("SELECT count(*) AS c, sum(id) AS s FROM t WHERE id > 3 AND id < 5")?;
+        assert_batch_eq(
+            &batches,
+            vec!["+---+---+", "| c | s |", "+---+---+", "| 1 | 4 |", "+---+---+"],
+        );
+        assert_eq!(scans(), 1);
+
+        Ok(())
+    }
+
     #[test]
     fn test_read_csv_sql() {
         execute_and_assert(
@@ -454,6 +1196,20 @@ order by
         );
     }
 
+    #[test]
+    fn test_read_csv_options_sql() {
+        let path = std::env::temp_dir().join("qurious_test_read_csv_options_sql.csv");
+        std::fs::write(&path, "a|b\n1|NA\n2|x\nNA|y\n").unwrap();
+
+        execute_and_assert(
+            &format!(
+                "SELECT b FROM read_csv('{}', delim = '|', nullstr = 'NA', sample_size = -1) WHERE a IS NULL",
+                path.display()
+            ),
+            vec!["+---+", "| b |", "+---+", "| y |", "+---+"],
+        );
+    }
+
     #[test]
     fn test_read_json_sql() {
         execute_and_assert(
@@ -468,6 +1224,16 @@ order by
                 "+----+-----------+",
             ],
         );
+        execute_and_assert(
+            "SELECT name FROM read_json('./tests/testdata/file/case1.json', sample_size = -1) WHERE id > 2",
+            vec![
+                "+-----------+",
+                "| name      |",
+                "+-----------+",
+                "| ChongQing |",
+                "+-----------+",
+            ],
+        );
     }
 
     #[test]
@@ -484,6 +1250,523 @@ order by
         );
     }
 
+    #[test]
+    fn test_write_files_sql() -> Result<()> {
+        let session = ExecuteSession::new()?;
+        session.sql("CREATE TABLE src (id INT, name VARCHAR)")?;
+        session.sql("INSERT INTO src VALUES (1, 'a'), (2, 'b'), (3, 'c')")?;
+
+        let parquet = std::env::temp_dir().join("qurious_test_write_files_sql.parquet");
+        session.sql(&format!(
+            "COPY src TO '{}' (COMPRESSION zstd, ROW_GROUP_SIZE 2)",
+            parquet.display()
+        ))?;
+        let metadata = parquet::file::reader::FileReader::metadata(&parquet::file::reader::SerializedFileReader::new(
+            std::fs::File::open(&parquet)?,
+        )?)
+        .clone();
+        assert_eq!(metadata.num_row_groups(), 2);
+        assert!(matches!(
+            metadata.row_group(0).column(0).compression(),
+            parquet::basic::Compression::ZSTD(_)
+        ));
+
+        let csv = std::env::temp_dir().join("qurious_test_write_files_sql.csv.gz");
+        session.sql(&format!("COPY src TO '{}' (HEADER)", csv.display()))?;
+
+        // the rows inserted into a table of files are written to the files
+        session.register_table("t_parquet", file::parquet::read_parquet(parquet.to_str().unwrap())?)?;
+        session.register_table("t_csv", file::csv::read_csv(csv.to_str().unwrap(), Default::default())?)?;
+        session.sql("INSERT INTO t_parquet VALUES (4, 'd')")?;
+        session.sql("INSERT INTO t_csv SELECT id, name FROM t_parquet WHERE id > 2")?;
+
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  | b    |",
+            "| 3  | c    |",
+            "| 3  | c    |",
+            "| 4  | d    |",
+            "+----+------+",
+        ];
+        let batches = session.sql(&format!(
+            "SELECT id, name FROM read_csv('{}') ORDER BY id",
+            csv.display()
+        ))?;
+        assert_batch_eq(&batches, expected);
+        let batches = session.sql(&format!(
+            "SELECT count(*) AS c FROM read_parquet('{}')",
+            parquet.display()
+        ))?;
+        assert_batch_eq(&batches, vec!["+---+", "| c |", "+---+", "| 4 |", "+---+"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_compressed_files_sql() -> Result<()> {
+        execute_and_assert(
+            "SELECT count(*) AS rows, max(id) AS max_id FROM read_csv('tests/testdata/file/case3.csv.bz2')",
+            vec![
+                "+------+--------+",
+                "| rows | max_id |",
+                "+------+--------+",
+                "| 120  | 119    |",
+                "+------+--------+",
+            ],
+        );
+
+        let path = std::env::temp_dir().join("qurious_test_read_compressed_files_sql.ndjson");
+        let mut gzip = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+        std::io::Write::write_all(&mut gzip, b"{\"a\": 1}\n{\"a\": 2}\n")?;
+        std::fs::write(&path, gzip.finish()?)?;
+        execute_and_assert(
+            &format!(
+                "SELECT sum(a) AS a FROM read_json('{}', compression = 'gzip')",
+                path.display()
+            ),
+            vec!["+---+", "| a |", "+---+", "| 3 |", "+---+"],
+        );
+
+        // the rows appended to a bzip2 file are another stream of the file
+        let session = ExecuteSession::new()?;
+        let path = std::env::temp_dir().join("qurious_test_read_compressed_files_sql.csv.bz2");
+        session.sql(&format!("COPY (SELECT 1 AS a) TO '{}' (HEADER)", path.display()))?;
+        session.register_table("t", file::csv::read_csv(path.to_str().unwrap(), Default::default())?)?;
+        session.sql("INSERT INTO t VALUES (2)")?;
+        assert_batch_eq(
+            &session.sql("SELECT sum(a) AS a FROM t")?,
+            vec!["+---+", "| a |", "+---+", "| 3 |", "+---+"],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_parquet_glob_sql() -> Result<()> {
+        let session = ExecuteSession::new()?;
+        let dir = std::env::temp_dir().join("qurious_test_read_parquet_glob_sql");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir)?;
+        for (i, sql) in ["SELECT 1 AS id, 'a' AS name", "SELECT 2 AS id, 'b' AS name"]
+            .iter()
+            .enumerate()
+        {
+            let batches = session.sql(sql)?;
+            let path = dir.join(format!("part-{}.parquet", i));
+            crate::datasource::file::parquet::write_parquet(path.to_str().unwrap(), batches[0].schema(), &batches)?;
+        }
+
+        let expected = vec![
+            "+----+------+",
+            "| id | name |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  | b    |",
+            "+----+------+",
+        ];
+        execute_and_assert(
+            &format!(
+                "SELECT * FROM read_parquet('{}') ORDER BY id",
+                dir.join("*.parquet").display()
+            ),
+            expected.clone(),
+        );
+        execute_and_assert(
+            &format!("SELECT * FROM read_parquet('{}') ORDER BY id", dir.display()),
+            expected,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_parquet_merge_schema_sql() -> Result<()> {
+        let session = ExecuteSession::new()?;
+        let dir = std::env::temp_dir().join("qurious_test_read_parquet_merge_schema_sql");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir)?;
+        for (i, sql) in ["SELECT 1 AS id", "SELECT 2.5 AS id, 'b' AS name"].iter().enumerate() {
+            let batches = session.sql(sql)?;
+            let path = dir.join(format!("part-{}.parquet", i));
+            crate::datasource::file::parquet::write_parquet(path.to_str().unwrap(), batches[0].schema(), &batches)?;
+        }
+
+        let sql = format!("SELECT * FROM read_parquet('{}') ORDER BY id", dir.display());
+        assert!(session.sql(&sql).is_err());
+        execute_and_assert(
+            &format!(
+                "SELECT * FROM read_parquet('{}', merge_schema = true) ORDER BY id",
+                dir.display()
+            ),
+            vec![
+                "+-----+------+",
+                "| id  | name |",
+                "+-----+------+",
+                "| 1.0 |      |",
+                "| 2.5 | b    |",
+                "+-----+------+",
+            ],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_partitioned_parquet_sql() -> Result<()> {
+        let session = ExecuteSession::new()?;
+        let dir = std::env::temp_dir().join("qurious_test_read_partitioned_parquet_sql");
+        let _ = std::fs::remove_dir_all(&dir);
+        for (i, partition) in ["date=2024-01-01/country=US", "date=2024-01-02/country=CN"]
+            .iter()
+            .enumerate()
+        {
+            std::fs::create_dir_all(dir.join(partition))?;
+            let batches = session.sql(&format!("SELECT {} AS id", i))?;
+            let path = dir.join(partition).join("part-0.parquet");
+            crate::datasource::file::parquet::write_parquet(path.to_str().unwrap(), batches[0].schema(), &batches)?;
+        }
+
+        execute_and_assert(
+            &format!(
+                "SELECT id, date FROM read_parquet('{}') WHERE country = 'CN'",
+                dir.display()
+            ),
+            vec![
+                "+----+------------+",
+                "| id | date       |",
+                "+----+------------+",
+                "| 1  | 2024-01-02 |",
+                "+----+------------+",
+            ],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "delta")]
+    fn test_read_delta_sql() -> Result<()> {
+        let session = ExecuteSession::new()?;
+        let dir = std::env::temp_dir().join("qurious_test_read_delta_sql");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("_delta_log"))?;
+        let schema = r#"{"type":"struct","fields":[{"name":"id","type":"long","nullable":true,"metadata":{}}]}"#;
+        for (version, timestamp) in [1704067200000_i64, 1704153600000].into_iter().enumerate() {
+            let batches = session.sql(&format!("SELECT {} AS id", version))?;
+            let path = dir.join(format!("part-{}.parquet", version));
+            crate::datasource::file::parquet::write_parquet(path.to_str().unwrap(), batches[0].schema(), &batches)?;
+
+            let mut actions = vec![
+                serde_json::json!({"commitInfo": {"timestamp": timestamp}}),
+                serde_json::json!({"add": {
+                    "path": format!("part-{}.parquet", version),
+                    "partitionValues": {},
+                    "size": 0,
+                    "modificationTime": 0,
+                    "dataChange": true,
+                }}),
+            ];
+            if version == 0 {
+                actions.push(serde_json::json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}));
+                actions.push(serde_json::json!({"metaData": {
+                    "id": "test",
+                    "format": {"provider": "parquet", "options": {}},
+                    "schemaString": schema,
+                    "partitionColumns": [],
+                    "configuration": {},
+                }}));
+            }
+            let actions = actions.iter().map(|action| action.to_string()).collect::<Vec<_>>();
+            let commit = dir.join(format!("_delta_log/{:020}.json", version));
+            std::fs::write(&commit, actions.join("\n"))?;
+            // the time a version was committed is the modification time of its commit file
+            std::fs::File::options()
+                .write(true)
+                .open(&commit)?
+                .set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_millis(timestamp as u64))?;
+        }
+
+        let count = |options: &str| -> Result<Vec<RecordBatch>> {
+            session.sql(&format!(
+                "SELECT count(*) AS c FROM read_delta('{}'{})",
+                dir.display(),
+                options
+            ))
+        };
+        assert_batch_eq(&count("")?, vec!["+---+", "| c |", "+---+", "| 2 |", "+---+"]);
+        assert_batch_eq(
+            &count(", version = 0")?,
+            vec!["+---+", "| c |", "+---+", "| 1 |", "+---+"],
+        );
+        assert_batch_eq(
+            &count(", timestamp = '2024-01-01 12:00:00'")?,
+            vec!["+---+", "| c |", "+---+", "| 1 |", "+---+"],
+        );
+        assert!(count(", version = 2").is_err());
+        assert!(count(", timestamp = 'yesterday'").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_parquet_filter_sql() -> Result<()> {
+        let session = ExecuteSession::new()?;
+        let batches = session.sql("SELECT * FROM (VALUES (1, 'a'), (2, 'b'), (3, NULL)) AS t(id, name)")?;
+        let path = std::env::temp_dir().join("qurious_test_read_parquet_filter_sql.parquet");
+        crate::datasource::file::parquet::write_parquet(path.to_str().unwrap(), batches[0].schema(), &batches)?;
+
+        execute_and_assert(
+            &format!(
+                "SELECT name FROM read_parquet('{}') WHERE id > 1 AND name IS NOT NULL",
+                path.display()
+            ),
+            vec!["+------+", "| name |", "+------+", "| b    |", "+------+"],
+        );
+        execute_and_assert(
+            &format!(
+                "SELECT id FROM read_parquet('{}') WHERE id IN (1, 3) AND id != 3",
+                path.display()
+            ),
+            vec!["+----+", "| id |", "+----+", "| 1  |", "+----+"],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_ipc_sql() -> Result<()> {
+        let session = ExecuteSession::new()?;
+        let batches = session.sql("SELECT * FROM (VALUES (1, 'a'), (2, 'b')) AS t(id, name)")?;
+        let path = std::env::temp_dir().join("qurious_test_read_ipc_sql.arrow");
+        let mut writer = arrow::ipc::writer::FileWriter::try_new(std::fs::File::create(&path)?, &batches[0].schema())?;
+        batches.iter().try_for_each(|batch| writer.write(batch))?;
+        writer.finish()?;
+
+        execute_and_assert(
+            &format!("SELECT name FROM read_ipc('{}') WHERE id = 2", path.display()),
+            vec!["+------+", "| name |", "+------+", "| b    |", "+------+"],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_without_where() -> Result<()> {
+        let sqls = [
+            "CREATE TABLE t(a INT)",
+            "INSERT INTO t VALUES (1), (2), (3)",
+            "DELETE FROM t",
+        ];
+
+        let session = ExecuteSession::new()?;
+        let mut results = sqls.iter().map(|sql| session.sql(sql)).collect::<Vec<_>>();
+        assert_eq!(
+            results.pop().unwrap().unwrap_err().to_string(),
+            "Internal Error: DELETE without WHERE deletes all rows of table [t], use WHERE true to confirm"
+        );
+
+        let session = ExecuteSession::new_with_config(SessionConfig {
+            allow_delete_without_where: true,
+            ..Default::default()
+        })?;
+        let mut results = sqls.iter().map(|sql| session.sql(sql)).collect::<Result<Vec<_>>>()?;
+        assert_batch_eq(
+            &results.pop().unwrap(),
+            vec!["+-----+", "| row |", "+-----+", "| 3   |", "+-----+"],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_path() -> Result<()> {
+        let session = ExecuteSession::new_with_config(SessionConfig {
+            search_path: vec!["s".to_string()],
+            ..Default::default()
+        })?;
+        session.sql("CREATE SCHEMA s")?;
+        session.sql("CREATE TABLE s.t(a INT)")?;
+        session.sql("INSERT INTO s.t VALUES (1)")?;
+
+        // a table of the default schema shadows a table of the search path
+        assert_batch_eq(
+            &session.sql("SELECT a FROM t")?,
+            vec!["+---+", "| a |", "+---+", "| 1 |", "+---+"],
+        );
+        session.sql("CREATE TABLE t(a INT)")?;
+        assert_batch_eq(&session.sql("SELECT a FROM t")?, vec!["++", "++"]);
+
+        assert_eq!(
+            session.sql("DROP SCHEMA s").unwrap_err().to_string(),
+            "Plan Error: Drop schema failed, schema is not empty: s"
+        );
+        session.sql("DROP TABLE s.t")?;
+        session.sql("DROP SCHEMA s")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_script() -> Result<()> {
+        let session = ExecuteSession::new()?;
+
+        // the later statements use the table created by the first one
+        let results = session.execute_script(
+            "CREATE TABLE t(a INT, b VARCHAR);
+            INSERT INTO t VALUES (1, 'a'), (2, 'b');
+            -- comment
+            ;
+            SELECT a, b FROM t WHERE a > 1;",
+        )?;
+        assert_eq!(results.len(), 3);
+        assert_batch_eq(
+            &results[2],
+            vec!["+---+---+", "| a | b |", "+---+---+", "| 2 | b |", "+---+---+"],
+        );
+
+        // the statements before the error are executed
+        assert!(session
+            .execute_script("INSERT INTO t VALUES (3, 'c'); SELECT * FROM x; INSERT INTO t VALUES (4, 'd')")
+            .is_err());
+        assert_batch_eq(
+            &session.sql("SELECT count(*) AS n FROM t")?,
+            vec!["+---+", "| n |", "+---+", "| 3 |", "+---+"],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sql_stream() -> Result<()> {
+        let session = ExecuteSession::new()?;
+        session.sql("SET batch_size = 2")?;
+
+        // the statements which are not queries are executed before the stream is returned
+        block_on(session.sql_stream("CREATE TABLE t(a INT)")?.try_collect::<Vec<_>>())?;
+        session.sql("INSERT INTO t VALUES (1), (2), (3), (4), (5)")?;
+
+        let mut stream = session.sql_stream("SELECT a * 10 AS b FROM t WHERE a > 1 LIMIT 3")?;
+        let first = block_on(stream.next()).unwrap()?;
+        assert_batch_eq(&[first], vec!["+----+", "| b  |", "+----+", "| 20 |", "+----+"]);
+        assert_batch_eq(
+            &block_on(stream.try_collect::<Vec<_>>())?,
+            vec!["+----+", "| b  |", "+----+", "| 30 |", "| 40 |", "+----+"],
+        );
+
+        // the stream can be consumed on another thread
+        let stream = session.sql_stream("SELECT a FROM t ORDER BY a DESC")?;
+        let batches = std::thread::spawn(move || block_on(stream.try_collect::<Vec<_>>()))
+            .join()
+            .unwrap()?;
+        assert_eq!(batches, session.sql("SELECT a FROM t ORDER BY a DESC")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain_analyze() -> Result<()> {
+        let session = ExecuteSession::new()?;
+        session.sql("CREATE TABLE t(a INT)")?;
+        session.sql("INSERT INTO t VALUES (1), (2), (3)")?;
+
+        let batches = session.sql("EXPLAIN ANALYZE SELECT a FROM t WHERE a > 1")?;
+        let lines = batches[0].column(0).as_any().downcast_ref::<StringArray>().unwrap();
+        let lines = lines.iter().flatten().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], "logical_plan");
+        let physical = lines.iter().position(|line| *line == "physical_plan").unwrap();
+        let metrics = lines[physical + 1..]
+            .iter()
+            .map(|line| line.split_once(", elapsed=").unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            metrics,
+            vec![
+                "  Projection: (a(0)), metrics=[output_rows=2, output_batches=1",
+                "    Filter: a(0) > Int64(1), metrics=[output_rows=2, output_batches=1",
+                "      Scan, metrics=[output_rows=3, output_batches=1",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_table_index() -> Result<()> {
+        let session = ExecuteSession::new()?;
+        session.sql("CREATE TABLE dim(id INT PRIMARY KEY, name VARCHAR)")?;
+        session.sql("CREATE TABLE fact(k INT, v INT)")?;
+        session.sql("INSERT INTO dim VALUES (1, 'a'), (2, 'b'), (3, 'c')")?;
+        session.sql("INSERT INTO fact VALUES (2, 20), (4, 40), (1, 10), (2, 21)")?;
+        let physical_plan = |sql: &str| -> Result<Vec<String>> {
+            let batches = session.sql(&format!("EXPLAIN ANALYZE {}", sql))?;
+            let lines = batches[0].column(0).as_any().downcast_ref::<StringArray>().unwrap();
+            let lines = lines.iter().flatten().collect::<Vec<_>>();
+            let physical = lines.iter().position(|line| *line == "physical_plan").unwrap();
+            Ok(lines[physical + 1..]
+                .iter()
+                .map(|line| line.split(", metrics=").next().unwrap().to_owned())
+                .collect())
+        };
+
+        // the equality of the primary key is looked up in its index
+        let sql = "SELECT name FROM dim WHERE id = 2";
+        assert_batch_eq(
+            &session.sql(sql)?,
+            vec!["+------+", "| name |", "+------+", "| b    |", "+------+"],
+        );
+        let batches = session.sql(&format!("EXPLAIN ANALYZE {}", sql))?;
+        let lines = batches[0].column(0).as_any().downcast_ref::<StringArray>().unwrap();
+        assert!(lines
+            .iter()
+            .flatten()
+            .any(|line| line.starts_with("      Scan: filter=[dim.id = Int64(2)], metrics=[output_rows=1,")));
+
+        // the rows of dim are looked up for each row of fact, in the order of a nested loop join
+        let sql = "SELECT k, v, name FROM fact LEFT JOIN dim ON k = id";
+        assert_eq!(
+            physical_plan(sql)?,
+            vec![
+                "  Projection: (k(0), v(1), name(3))",
+                "    IndexJoin: type=Left Join, on=[k(0) = id]",
+                "      Scan",
+            ]
+        );
+        assert_batch_eq(
+            &session.sql(sql)?,
+            vec![
+                "+---+----+------+",
+                "| k | v  | name |",
+                "+---+----+------+",
+                "| 1 | 10 | a    |",
+                "| 2 | 20 | b    |",
+                "| 2 | 21 | b    |",
+                "| 4 | 40 |      |",
+                "+---+----+------+",
+            ],
+        );
+
+        // the index follows the changes of the rows
+        session.sql("UPDATE dim SET id = 4 WHERE id = 3")?;
+        session.sql("DELETE FROM dim WHERE id = 1")?;
+        assert_batch_eq(
+            &session.sql("SELECT k, v, name FROM fact JOIN dim ON k = id AND v > 20")?,
+            vec![
+                "+---+----+------+",
+                "| k | v  | name |",
+                "+---+----+------+",
+                "| 2 | 21 | b    |",
+                "| 4 | 40 | c    |",
+                "+---+----+------+",
+            ],
+        );
+
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "connectorx")]
     fn test_postgres() {
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+use crate::provider::table::TableProvider;
+
+/// A transaction of a session started by `BEGIN`. The statements change private copies of the tables, made at
+/// their first change, which the session reads instead of the tables. `COMMIT` writes the rows of the copies back
+/// to the tables, the other sessions don't see the changes before, and `ROLLBACK` drops the copies. The last
+/// transaction to commit a table wins. DDL is not transactional.
+#[derive(Default)]
+pub(crate) struct Transaction {
+    /// the tables changed in the transaction and their copies
+    changes: Vec<(Arc<dyn TableProvider>, Arc<dyn TableProvider>)>,
+    /// a statement of the transaction failed, only `COMMIT` or `ROLLBACK` are accepted until it ends
+    pub(crate) aborted: bool,
+}
+
+impl Transaction {
+    /// the table as the transaction sees it, its copy if it is changed in the transaction
+    pub(crate) fn table(&self, table: &Arc<dyn TableProvider>) -> Arc<dyn TableProvider> {
+        self.changes
+            .iter()
+            .find(|(changed, _)| Arc::ptr_eq(changed, table))
+            .map(|(_, copy)| copy.clone())
+            .unwrap_or_else(|| table.clone())
+    }
+
+    /// the copy of the table the changes of the transaction are made to, a table that cannot be copied cannot be
+    /// changed in a transaction
+    pub(crate) fn table_mut(&mut self, table: &Arc<dyn TableProvider>) -> Result<Arc<dyn TableProvider>> {
+        if let Some((_, copy)) = self.changes.iter().find(|(changed, _)| Arc::ptr_eq(changed, table)) {
+            return Ok(copy.clone());
+        }
+        let Some(copy) = table.fork()? else {
+            return Err(Error::PlanError(
+                "the table does not support transactions, it cannot be changed in a transaction".to_owned(),
+            ));
+        };
+        self.changes.push((table.clone(), copy.clone()));
+
+        Ok(copy)
+    }
+
+    /// write the rows of the copies back to the tables
+    pub(crate) fn commit(self) -> Result<()> {
+        for (table, copy) in self.changes {
+            if let Some(rows) = copy.snapshot()? {
+                table.restore(rows)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
-use arrow::datatypes::Schema;
+use arrow::datatypes::{Fields, Schema};
 use std::sync::Arc;
 
 use super::{
-    expr::{LogicalExpr, SortExpr},
-    plan::{Aggregate, CrossJoin, EmptyRelation, Filter, Join, Limit, LogicalPlan, Projection, Sort, TableScan},
+    expr::{LogicalExpr, SortExpr, SubQuery},
+    plan::{
+        Aggregate, CrossJoin, EmptyRelation, Filter, Join, LateralJoin, Limit, LogicalPlan, Projection, Sort,
+        TableScan, Window,
+    },
 };
 use crate::{common::table_relation::TableRelation, error::Result};
 use crate::{
@@ -74,9 +77,24 @@ impl LogicalPlanBuilder {
         })
     }
 
+    pub fn lateral_join(self, subquery: SubQuery) -> Result<Self> {
+        let schema = TableSchema::merge(vec![self.plan.table_schema(), subquery.subquery.table_schema()])?;
+        Ok(LogicalPlanBuilder {
+            plan: LogicalPlan::LateralJoin(LateralJoin {
+                left: Arc::new(self.plan),
+                subquery,
+                schema: Arc::new(schema),
+            }),
+        })
+    }
+
     pub fn join_on(self, right: LogicalPlan, join_type: JoinType, on: LogicalExpr) -> Result<Self> {
         let left_fields = self.plan.schema().fields.clone();
-        let right_fields = right.schema().fields.clone();
+        let right_fields = if join_type.is_semi_or_anti() {
+            Fields::empty()
+        } else {
+            right.schema().fields.clone()
+        };
 
         // left then right
         let schema = Schema::new(
@@ -103,6 +121,10 @@ impl LogicalPlanBuilder {
             .map(|s| LogicalPlanBuilder::from(LogicalPlan::Aggregate(s)))
     }
 
+    pub fn window(self, window_expr: Vec<LogicalExpr>) -> Result<Self> {
+        Window::try_new(self.plan, window_expr).map(|s| LogicalPlanBuilder::from(LogicalPlan::Window(s)))
+    }
+
     pub fn sort(self, order_by: Vec<SortExpr>) -> Result<Self> {
         // TODO
         // we need check if the column is ambiguous and columns is present in the schema
@@ -121,6 +143,7 @@ impl LogicalPlanBuilder {
             plan: LogicalPlan::Sort(Sort {
                 exprs: order_by,
                 input: Box::new(self.plan),
+                fetch: None,
             }),
         })
     }
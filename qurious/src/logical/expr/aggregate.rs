@@ -20,16 +20,76 @@ pub enum AggregateOperator {
     Max,
     Avg,
     Count,
+    /// `GROUPING(expr)` is 1 if the rows are aggregated over the expression of the grouping sets, 0 if they are
+    /// grouped by it
+    Grouping,
+    /// the sample standard deviation, also `STDDEV_SAMP`
+    Stddev,
+    StddevPop,
+    /// the sample variance, also `VAR_SAMP`
+    Variance,
+    VariancePop,
+    /// `CORR(y, x)` the correlation coefficient of the pairs
+    Corr,
+    /// `COVAR_SAMP(y, x)` the sample covariance of the pairs
+    CovarSamp,
+    CovarPop,
+    /// the middle value, or the average of the two middle values of an even number of values
+    Median,
+    /// `PERCENTILE_CONT(fraction) WITHIN GROUP (ORDER BY expr)` the value at the fraction of the ordered values,
+    /// interpolated between the two nearest values
+    PercentileCont {
+        descending: bool,
+    },
+    /// `PERCENTILE_DISC(fraction) WITHIN GROUP (ORDER BY expr)` the first of the ordered values whose position is at
+    /// least the fraction
+    PercentileDisc {
+        descending: bool,
+    },
+    /// `APPROX_PERCENTILE(expr, fraction)` the percentile estimated from a t-digest of the values
+    ApproxPercentile,
+    /// `APPROX_DISTINCT(expr)` the number of distinct values estimated from a HyperLogLog sketch of the values
+    ApproxDistinct,
 }
 
 impl AggregateOperator {
     pub fn infer_type(&self, expr_data_type: &DataType) -> Result<DataType> {
         match self {
-            AggregateOperator::Count => Ok(DataType::Int64),
+            AggregateOperator::Count | AggregateOperator::Grouping | AggregateOperator::ApproxDistinct => {
+                Ok(DataType::Int64)
+            }
             AggregateOperator::Avg => avg_return_type(expr_data_type),
+            AggregateOperator::Stddev
+            | AggregateOperator::StddevPop
+            | AggregateOperator::Variance
+            | AggregateOperator::VariancePop
+            | AggregateOperator::Corr
+            | AggregateOperator::CovarSamp
+            | AggregateOperator::CovarPop
+            | AggregateOperator::Median
+            | AggregateOperator::PercentileCont { .. }
+            | AggregateOperator::ApproxPercentile => {
+                if !expr_data_type.is_numeric() && !expr_data_type.is_null() {
+                    return internal_err!("{self} does not support {expr_data_type:?}");
+                }
+                Ok(DataType::Float64)
+            }
             _ => Ok(expr_data_type.clone()),
         }
     }
+
+    /// the number of arguments of the aggregate, the aggregated expression and the arguments after it
+    pub fn num_args(&self) -> usize {
+        match self {
+            AggregateOperator::Corr
+            | AggregateOperator::CovarSamp
+            | AggregateOperator::CovarPop
+            | AggregateOperator::PercentileCont { .. }
+            | AggregateOperator::PercentileDisc { .. }
+            | AggregateOperator::ApproxPercentile => 2,
+            _ => 1,
+        }
+    }
 }
 
 fn avg_return_type(expr_data_type: &DataType) -> Result<DataType> {
@@ -57,6 +117,19 @@ impl Display for AggregateOperator {
             AggregateOperator::Max => write!(f, "MAX"),
             AggregateOperator::Avg => write!(f, "AVG"),
             AggregateOperator::Count => write!(f, "COUNT"),
+            AggregateOperator::Grouping => write!(f, "GROUPING"),
+            AggregateOperator::Stddev => write!(f, "STDDEV"),
+            AggregateOperator::StddevPop => write!(f, "STDDEV_POP"),
+            AggregateOperator::Variance => write!(f, "VARIANCE"),
+            AggregateOperator::VariancePop => write!(f, "VAR_POP"),
+            AggregateOperator::Corr => write!(f, "CORR"),
+            AggregateOperator::CovarSamp => write!(f, "COVAR_SAMP"),
+            AggregateOperator::CovarPop => write!(f, "COVAR_POP"),
+            AggregateOperator::Median => write!(f, "MEDIAN"),
+            AggregateOperator::PercentileCont { .. } => write!(f, "PERCENTILE_CONT"),
+            AggregateOperator::PercentileDisc { .. } => write!(f, "PERCENTILE_DISC"),
+            AggregateOperator::ApproxPercentile => write!(f, "APPROX_PERCENTILE"),
+            AggregateOperator::ApproxDistinct => write!(f, "APPROX_DISTINCT"),
         }
     }
 }
@@ -71,6 +144,17 @@ impl TryFrom<String> for AggregateOperator {
             "max" => Ok(AggregateOperator::Max),
             "avg" => Ok(AggregateOperator::Avg),
             "count" => Ok(AggregateOperator::Count),
+            "grouping" => Ok(AggregateOperator::Grouping),
+            "stddev" | "stddev_samp" => Ok(AggregateOperator::Stddev),
+            "stddev_pop" => Ok(AggregateOperator::StddevPop),
+            "variance" | "var_samp" => Ok(AggregateOperator::Variance),
+            "var_pop" => Ok(AggregateOperator::VariancePop),
+            "corr" => Ok(AggregateOperator::Corr),
+            "covar_samp" => Ok(AggregateOperator::CovarSamp),
+            "covar_pop" => Ok(AggregateOperator::CovarPop),
+            "median" => Ok(AggregateOperator::Median),
+            "approx_percentile" | "approx_percentile_cont" => Ok(AggregateOperator::ApproxPercentile),
+            "approx_distinct" | "approx_count_distinct" => Ok(AggregateOperator::ApproxDistinct),
             _ => Err(Error::InternalError(format!(
                 "{} is not a valid aggregate operator",
                 value
@@ -91,6 +175,12 @@ impl TryFrom<&str> for AggregateOperator {
 pub struct AggregateExpr {
     pub op: AggregateOperator,
     pub expr: Box<LogicalExpr>,
+    /// the arguments after the aggregated expression, eg. `x` of `CORR(y, x)`
+    pub args: Vec<LogicalExpr>,
+    /// `op(DISTINCT expr)` aggregates each distinct value of the expression once
+    pub distinct: bool,
+    /// `op(expr) FILTER (WHERE filter)` only aggregates the rows the filter is true for
+    pub filter: Option<Box<LogicalExpr>>,
 }
 
 impl AggregateExpr {
@@ -103,7 +193,7 @@ impl AggregateExpr {
             };
 
             Ok(Arc::new(Field::new(
-                format!("{}({})", self.op, col_name),
+                self.name(col_name),
                 self.op.infer_type(field.data_type())?,
                 true,
             )))
@@ -113,16 +203,54 @@ impl AggregateExpr {
     pub(crate) fn as_column(&self) -> Result<LogicalExpr> {
         self.expr.as_column().map(|inner_col| {
             LogicalExpr::Column(Column {
-                name: format!("{}({})", self.op, inner_col),
+                name: self.name(inner_col),
                 relation: None,
                 is_outer_ref: false,
             })
         })
     }
+
+    fn name(&self, expr: impl Display) -> String {
+        if let AggregateOperator::PercentileCont { descending } | AggregateOperator::PercentileDisc { descending } =
+            self.op
+        {
+            let fraction = self
+                .args
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let order = if descending { " DESC" } else { "" };
+            return format!(
+                "{}({}) WITHIN GROUP (ORDER BY {}{}){}",
+                self.op,
+                fraction,
+                expr,
+                order,
+                self.filter_name()
+            );
+        }
+        let args = std::iter::once(expr.to_string())
+            .chain(self.args.iter().map(|arg| arg.to_string()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if self.distinct {
+            format!("{}(DISTINCT {}){}", self.op, args, self.filter_name())
+        } else {
+            format!("{}({}){}", self.op, args, self.filter_name())
+        }
+    }
+
+    fn filter_name(&self) -> String {
+        self.filter
+            .as_ref()
+            .map(|filter| format!(" FILTER (WHERE {})", filter))
+            .unwrap_or_default()
+    }
 }
 
 impl Display for AggregateExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}({})", self.op, self.expr)
+        write!(f, "{}", self.name(&self.expr))
     }
 }
@@ -66,8 +66,27 @@ make_binary_expr_fn!(lt, Operator::Lt);
 make_binary_expr_fn!(lt_eq, Operator::LtEq);
 make_binary_expr_fn!(and, Operator::And);
 make_binary_expr_fn!(or, Operator::Or);
+make_binary_expr_fn!(is_distinct_from, Operator::IsDistinctFrom);
+make_binary_expr_fn!(is_not_distinct_from, Operator::IsNotDistinctFrom);
 make_binary_expr_fn!(add, Operator::Add);
 make_binary_expr_fn!(sub, Operator::Sub);
 make_binary_expr_fn!(mul, Operator::Mul);
 make_binary_expr_fn!(div, Operator::Div);
 make_binary_expr_fn!(r#mod, Operator::Mod);
+make_binary_expr_fn!(string_concat, Operator::StringConcat);
+
+/// the conjuncts of an expression, `a AND (b AND c)` is `[a, b, c]`
+pub fn split_conjunction(expr: &LogicalExpr) -> Vec<&LogicalExpr> {
+    match expr {
+        LogicalExpr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        }) => {
+            let mut exprs = split_conjunction(left);
+            exprs.extend(split_conjunction(right));
+            exprs
+        }
+        _ => vec![expr],
+    }
+}
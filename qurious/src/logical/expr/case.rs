@@ -0,0 +1,66 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::error::Result;
+use crate::logical::expr::LogicalExpr;
+use crate::logical::plan::LogicalPlan;
+use crate::utils::type_coercion::get_common_type;
+use arrow::datatypes::{DataType, Field, FieldRef, Schema};
+
+/// `CASE [operand] WHEN condition THEN result ... [ELSE result] END`
+///
+/// With an operand each condition is a value compared with the operand, otherwise each condition is a boolean expression.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CaseExpr {
+    pub operand: Option<Box<LogicalExpr>>,
+    pub when_then: Vec<(LogicalExpr, LogicalExpr)>,
+    pub else_expr: Option<Box<LogicalExpr>>,
+}
+
+impl CaseExpr {
+    pub fn new(
+        operand: Option<LogicalExpr>,
+        when_then: Vec<(LogicalExpr, LogicalExpr)>,
+        else_expr: Option<LogicalExpr>,
+    ) -> Self {
+        Self {
+            operand: operand.map(Box::new),
+            when_then,
+            else_expr: else_expr.map(Box::new),
+        }
+    }
+
+    pub fn field(&self, plan: &LogicalPlan) -> Result<FieldRef> {
+        Ok(Arc::new(Field::new(
+            self.to_string(),
+            self.data_type(&plan.schema())?,
+            true,
+        )))
+    }
+
+    /// the common type of all the results
+    pub fn data_type(&self, schema: &Arc<Schema>) -> Result<DataType> {
+        self.when_then
+            .iter()
+            .map(|(_, then)| then)
+            .chain(self.else_expr.as_deref())
+            .try_fold(DataType::Null, |data_type, expr| {
+                get_common_type(&data_type, &expr.data_type(schema)?)
+            })
+    }
+}
+
+impl Display for CaseExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CASE")?;
+        if let Some(operand) = &self.operand {
+            write!(f, " {}", operand)?;
+        }
+        for (when, then) in &self.when_then {
+            write!(f, " WHEN {} THEN {}", when, then)?;
+        }
+        if let Some(else_expr) = &self.else_expr {
+            write!(f, " ELSE {}", else_expr)?;
+        }
+        write!(f, " END")
+    }
+}
@@ -0,0 +1,75 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::error::Result;
+use crate::logical::expr::{LogicalExpr, SubQuery};
+use crate::logical::plan::LogicalPlan;
+use crate::utils;
+use arrow::datatypes::{DataType, Field, FieldRef};
+
+/// `expr [NOT] IN (val1, val2, ...)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InList {
+    pub expr: Box<LogicalExpr>,
+    pub list: Vec<LogicalExpr>,
+    pub negated: bool,
+}
+
+impl InList {
+    pub fn new(expr: LogicalExpr, list: Vec<LogicalExpr>, negated: bool) -> Self {
+        Self {
+            expr: Box::new(expr),
+            list,
+            negated,
+        }
+    }
+
+    pub fn field(&self, _plan: &LogicalPlan) -> Result<FieldRef> {
+        Ok(Arc::new(Field::new(self.to_string(), DataType::Boolean, true)))
+    }
+}
+
+impl Display for InList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}IN ({})",
+            self.expr,
+            if self.negated { "NOT " } else { "" },
+            self.list.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ")
+        )
+    }
+}
+
+/// `expr [NOT] IN (SELECT ...)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InSubQuery {
+    pub expr: Box<LogicalExpr>,
+    pub subquery: SubQuery,
+    pub negated: bool,
+}
+
+impl InSubQuery {
+    pub fn new(expr: LogicalExpr, subquery: SubQuery, negated: bool) -> Self {
+        Self {
+            expr: Box::new(expr),
+            subquery,
+            negated,
+        }
+    }
+
+    pub fn field(&self, _plan: &LogicalPlan) -> Result<FieldRef> {
+        Ok(Arc::new(Field::new(self.to_string(), DataType::Boolean, true)))
+    }
+}
+
+impl Display for InSubQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}IN (\n{})\n",
+            self.expr,
+            if self.negated { "NOT " } else { "" },
+            utils::format(&self.subquery.subquery, 5)
+        )
+    }
+}
@@ -1,11 +1,15 @@
 mod aggregate;
 pub mod alias;
 mod binary;
+mod case;
 mod cast;
 mod column;
 mod function;
+mod in_list;
 mod literal;
+mod placeholder;
 mod sort;
+mod window;
 
 use std::collections::HashSet;
 use std::fmt::Display;
@@ -13,11 +17,15 @@ use std::sync::Arc;
 
 pub use aggregate::{AggregateExpr, AggregateOperator};
 pub use binary::*;
+pub use case::CaseExpr;
 pub use cast::*;
 pub use column::*;
 pub use function::Function;
+pub use in_list::{InList, InSubQuery};
 pub use literal::*;
+pub use placeholder::Placeholder;
 pub use sort::*;
+pub use window::{WindowFunction, WindowFunctionKind};
 
 use crate::common::table_relation::TableRelation;
 use crate::common::transformed::{TransformNode, Transformed, TransformedResult, TreeNodeRecursion};
@@ -34,10 +42,13 @@ pub enum LogicalExpr {
     Alias(Alias),
     Column(Column),
     Literal(ScalarValue),
+    /// parameter of a prepared statement, bound to a literal before the plan is executed
+    Placeholder(Placeholder),
     BinaryExpr(BinaryExpr),
     AggregateExpr(AggregateExpr),
     SortExpr(SortExpr),
     Cast(CastExpr),
+    Case(CaseExpr),
     Wildcard,
     Function(Function),
     IsNull(Box<LogicalExpr>),
@@ -45,6 +56,9 @@ pub enum LogicalExpr {
     Like(Like),
     Negative(Box<LogicalExpr>),
     SubQuery(SubQuery),
+    InList(InList),
+    InSubQuery(InSubQuery),
+    WindowFunction(WindowFunction),
 }
 
 macro_rules! impl_logical_expr_methods {
@@ -73,10 +87,17 @@ impl_logical_expr_methods! {
     AggregateExpr,
     Alias,
     Cast,
+    Case,
     Function,
     IsNotNull,
     IsNull,
     Negative,
+    WindowFunction,
+    SubQuery,
+    InList,
+    InSubQuery,
+    Like,
+    Placeholder,
 }
 
 impl Display for LogicalExpr {
@@ -84,6 +105,7 @@ impl Display for LogicalExpr {
         match self {
             LogicalExpr::Negative(e) => write!(f, "- {}", e),
             LogicalExpr::Literal(v) => write!(f, "{}", v),
+            LogicalExpr::Placeholder(p) => write!(f, "{}", p),
             LogicalExpr::Wildcard => write!(f, "*"),
             LogicalExpr::Alias(alias) => write!(f, "{} AS {}", alias.expr, alias.name),
             LogicalExpr::Column(column) => write!(f, "{column}"),
@@ -91,17 +113,15 @@ impl Display for LogicalExpr {
             LogicalExpr::AggregateExpr(aggregate_expr) => write!(f, "{aggregate_expr}",),
             LogicalExpr::SortExpr(sort_expr) => write!(f, "{sort_expr}",),
             LogicalExpr::Cast(cast_expr) => write!(f, "CAST({} AS {})", cast_expr.expr, cast_expr.data_type),
+            LogicalExpr::Case(case) => write!(f, "{case}"),
             LogicalExpr::Function(function) => write!(f, "{function}",),
+            LogicalExpr::WindowFunction(window) => write!(f, "{window}",),
             LogicalExpr::IsNull(logical_expr) => write!(f, "{} IS NULL", logical_expr),
-            LogicalExpr::IsNotNull(logical_expr) => write!(f, "{} IS NOT NULLni", logical_expr),
+            LogicalExpr::IsNotNull(logical_expr) => write!(f, "{} IS NOT NULL", logical_expr),
             LogicalExpr::SubQuery(subquery) => write!(f, "(\n{})\n", utils::format(&subquery.subquery, 5)),
-            LogicalExpr::Like(like) => {
-                if like.negated {
-                    write!(f, "{} NOT LIKE {}", like.expr, like.pattern)
-                } else {
-                    write!(f, "{} LIKE {}", like.expr, like.pattern)
-                }
-            }
+            LogicalExpr::InList(in_list) => write!(f, "{in_list}"),
+            LogicalExpr::InSubQuery(in_subquery) => write!(f, "{in_subquery}"),
+            LogicalExpr::Like(like) => write!(f, "{like}"),
         }
     }
 }
@@ -126,29 +146,7 @@ impl LogicalExpr {
     }
 
     pub fn using_columns(&self) -> HashSet<Column> {
-        let mut columns = HashSet::new();
-        let mut stack = vec![self];
-
-        while let Some(expr) = stack.pop() {
-            match expr {
-                LogicalExpr::Column(a) => {
-                    columns.insert(a.clone());
-                }
-                LogicalExpr::Alias(a) => {
-                    stack.push(&a.expr);
-                }
-                LogicalExpr::BinaryExpr(binary_op) => {
-                    stack.push(&binary_op.left);
-                    stack.push(&binary_op.right);
-                }
-                LogicalExpr::AggregateExpr(ag) => {
-                    stack.push(&ag.expr);
-                }
-                _ => {}
-            }
-        }
-
-        columns
+        self.column_refs().into_iter().cloned().collect()
     }
 
     pub fn cast_to(self, data_type: &DataType) -> LogicalExpr {
@@ -169,9 +167,10 @@ impl LogicalExpr {
         match self {
             LogicalExpr::Column(_) => Ok(self.clone()),
             LogicalExpr::AggregateExpr(agg) => agg.as_column(),
-            LogicalExpr::Literal(_) | LogicalExpr::Wildcard | LogicalExpr::BinaryExpr(_) => Ok(LogicalExpr::Column(
-                Column::new(format!("{}", self), None::<TableRelation>, false),
-            )),
+            LogicalExpr::WindowFunction(window) => window.as_column(),
+            LogicalExpr::Literal(_) | LogicalExpr::Wildcard | LogicalExpr::BinaryExpr(_) | LogicalExpr::Case(_) => Ok(
+                LogicalExpr::Column(Column::new(format!("{}", self), None::<TableRelation>, false)),
+            ),
             _ => Err(Error::InternalError(format!("Expect column, got {:?}", self))),
         }
     }
@@ -190,6 +189,21 @@ impl LogicalExpr {
         columns
     }
 
+    /// whether the expression has a subquery, the evaluations of a correlated subquery can not run in parallel
+    pub fn contains_subquery(&self) -> bool {
+        let mut found = false;
+        self.apply(|expr| {
+            found = matches!(expr, LogicalExpr::SubQuery(_) | LogicalExpr::InSubQuery(_));
+            Ok(match found {
+                true => TreeNodeRecursion::Stop,
+                false => TreeNodeRecursion::Continue,
+            })
+        })
+        .expect("[contains_subquery] failed to apply");
+
+        found
+    }
+
     pub fn data_type(&self, schema: &Arc<Schema>) -> Result<DataType> {
         match self {
             LogicalExpr::Alias(Alias { expr, .. }) => expr.data_type(schema),
@@ -198,12 +212,19 @@ impl LogicalExpr {
                 Ok(field.data_type().clone())
             }
             LogicalExpr::Literal(scalar_value) => Ok(scalar_value.data_type()),
+            LogicalExpr::Placeholder(placeholder) => Ok(placeholder.data_type.clone()),
             LogicalExpr::BinaryExpr(binary_expr) => binary_expr.get_result_type(schema),
             LogicalExpr::Cast(cast_expr) => Ok(cast_expr.data_type.clone()),
+            LogicalExpr::Case(case) => case.data_type(schema),
             LogicalExpr::Function(function) => Ok(function.func.return_type()),
-            LogicalExpr::AggregateExpr(AggregateExpr { op, expr }) => op.infer_type(&expr.data_type(schema)?),
+            LogicalExpr::WindowFunction(window) => window.data_type(schema),
+            LogicalExpr::AggregateExpr(AggregateExpr { op, expr, .. }) => op.infer_type(&expr.data_type(schema)?),
             LogicalExpr::SortExpr(SortExpr { expr, .. }) | LogicalExpr::Negative(expr) => expr.data_type(schema),
-            LogicalExpr::Like(_) | LogicalExpr::IsNull(_) | LogicalExpr::IsNotNull(_) => Ok(DataType::Boolean),
+            LogicalExpr::Like(_)
+            | LogicalExpr::IsNull(_)
+            | LogicalExpr::IsNotNull(_)
+            | LogicalExpr::InList(_)
+            | LogicalExpr::InSubQuery(_) => Ok(DataType::Boolean),
             LogicalExpr::SubQuery(subquery) => Ok(subquery.subquery.schema().fields[0].data_type().clone()),
             _ => internal_err!("[{}] has no data type", self),
         }
@@ -232,16 +253,35 @@ impl TransformNode for LogicalExpr {
                     transformed,
                 }
             }
-            LogicalExpr::AggregateExpr(AggregateExpr { op, expr }) => f(*expr)?.update(|expr| {
-                LogicalExpr::AggregateExpr(AggregateExpr {
-                    op,
-                    expr: Box::new(expr),
-                })
-            }),
-            LogicalExpr::SortExpr(SortExpr { expr, asc }) => f(*expr)?.update(|expr| {
+            LogicalExpr::AggregateExpr(AggregateExpr {
+                op,
+                expr,
+                args,
+                distinct,
+                filter,
+            }) => {
+                let expr = f(*expr)?;
+                let args = args.into_iter().map(&mut f).collect::<Result<Vec<_>>>()?;
+                let filter = filter.map(|filter| f(*filter)).transpose()?;
+                let transformed = expr.transformed
+                    || args.iter().any(|arg| arg.transformed)
+                    || filter.as_ref().is_some_and(|filter| filter.transformed);
+                Transformed {
+                    data: LogicalExpr::AggregateExpr(AggregateExpr {
+                        op,
+                        expr: expr.update(Box::new).data,
+                        args: args.into_iter().map(|arg| arg.data).collect(),
+                        distinct,
+                        filter: filter.map(|filter| Box::new(filter.data)),
+                    }),
+                    transformed,
+                }
+            }
+            LogicalExpr::SortExpr(SortExpr { expr, asc, nulls_first }) => f(*expr)?.update(|expr| {
                 LogicalExpr::SortExpr(SortExpr {
                     expr: Box::new(expr),
                     asc,
+                    nulls_first,
                 })
             }),
             LogicalExpr::Cast(CastExpr { expr, data_type }) => f(*expr)?.update(|expr| {
@@ -250,6 +290,19 @@ impl TransformNode for LogicalExpr {
                     data_type,
                 })
             }),
+            LogicalExpr::Case(CaseExpr {
+                operand,
+                when_then,
+                else_expr,
+            }) => {
+                let operand = operand.map(|expr| f(*expr).map(|expr| expr.data)).transpose()?;
+                let when_then = when_then
+                    .into_iter()
+                    .map(|(when, then)| Ok((f(when)?.data, f(then)?.data)))
+                    .collect::<Result<Vec<_>>>()?;
+                let else_expr = else_expr.map(|expr| f(*expr).map(|expr| expr.data)).transpose()?;
+                Transformed::yes(LogicalExpr::Case(CaseExpr::new(operand, when_then, else_expr)))
+            }
             LogicalExpr::Function(Function { func, args }) => {
                 let args = args
                     .into_iter()
@@ -257,6 +310,40 @@ impl TransformNode for LogicalExpr {
                     .collect::<Result<Vec<_>>>()?;
                 Transformed::yes(LogicalExpr::Function(Function { func, args }))
             }
+            LogicalExpr::WindowFunction(WindowFunction {
+                func,
+                args,
+                partition_by,
+                order_by,
+                window_frame,
+            }) => {
+                let args = args
+                    .into_iter()
+                    .map(|expr| f(expr).map(|expr| expr.data))
+                    .collect::<Result<Vec<_>>>()?;
+                let partition_by = partition_by
+                    .into_iter()
+                    .map(|expr| f(expr).map(|expr| expr.data))
+                    .collect::<Result<Vec<_>>>()?;
+                let order_by = order_by
+                    .into_iter()
+                    .map(|SortExpr { expr, asc, nulls_first }| {
+                        f(*expr).map(|expr| SortExpr {
+                            expr: Box::new(expr.data),
+                            asc,
+                            nulls_first,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                Transformed::yes(LogicalExpr::WindowFunction(WindowFunction {
+                    func,
+                    args,
+                    partition_by,
+                    order_by,
+                    window_frame,
+                }))
+            }
             LogicalExpr::IsNull(expr) => f(*expr)?.update(|expr| LogicalExpr::IsNull(Box::new(expr))),
             LogicalExpr::IsNotNull(expr) => f(*expr)?.update(|expr| LogicalExpr::IsNotNull(Box::new(expr))),
             LogicalExpr::Negative(expr) => f(*expr)?.update(|expr| LogicalExpr::Negative(Box::new(expr))),
@@ -266,15 +353,50 @@ impl TransformNode for LogicalExpr {
                     outer_ref_columns: subquery.outer_ref_columns,
                 })
             }),
+            LogicalExpr::InList(InList { expr, list, negated }) => {
+                let expr = f(*expr)?.data;
+                let list = list
+                    .into_iter()
+                    .map(|expr| f(expr).map(|expr| expr.data))
+                    .collect::<Result<Vec<_>>>()?;
+                Transformed::yes(LogicalExpr::InList(InList::new(expr, list, negated)))
+            }
+            LogicalExpr::InSubQuery(InSubQuery {
+                expr,
+                subquery,
+                negated,
+            }) => {
+                let expr = f(*expr)?.data;
+                let LogicalExpr::SubQuery(subquery) = f(LogicalExpr::SubQuery(subquery))?.data else {
+                    return internal_err!("The subquery of IN should be a subquery expression");
+                };
+                Transformed::yes(LogicalExpr::InSubQuery(InSubQuery::new(expr, subquery, negated)))
+            }
 
-            LogicalExpr::Wildcard | LogicalExpr::Column(_) | LogicalExpr::Literal(_) => Transformed::no(self),
-            LogicalExpr::Like(like) => f(*like.expr)?.update(|expr| {
-                LogicalExpr::Like(Like {
-                    negated: like.negated,
-                    expr: Box::new(expr),
-                    pattern: like.pattern,
-                })
-            }),
+            LogicalExpr::Wildcard | LogicalExpr::Column(_) | LogicalExpr::Literal(_) | LogicalExpr::Placeholder(_) => {
+                Transformed::no(self)
+            }
+            LogicalExpr::Like(Like {
+                negated,
+                case_insensitive,
+                expr,
+                pattern,
+                escape_char,
+            }) => {
+                let expr = f(*expr)?;
+                let pattern = f(*pattern)?;
+                let transformed = expr.transformed || pattern.transformed;
+                Transformed {
+                    data: LogicalExpr::Like(Like {
+                        negated,
+                        case_insensitive,
+                        expr: Box::new(expr.data),
+                        pattern: Box::new(pattern.data),
+                        escape_char,
+                    }),
+                    transformed,
+                }
+            }
         })
     }
 
@@ -285,16 +407,38 @@ impl TransformNode for LogicalExpr {
         let children = match self {
             LogicalExpr::BinaryExpr(BinaryExpr { left, right, .. }) => vec![left.as_ref(), right.as_ref()],
             LogicalExpr::Function(function) => function.args.iter().map(|expr| expr).collect(),
+            LogicalExpr::Case(case) => case
+                .operand
+                .as_deref()
+                .into_iter()
+                .chain(case.when_then.iter().flat_map(|(when, then)| [when, then]))
+                .chain(case.else_expr.as_deref())
+                .collect(),
+            LogicalExpr::InList(in_list) => std::iter::once(in_list.expr.as_ref())
+                .chain(in_list.list.iter())
+                .collect(),
+            LogicalExpr::AggregateExpr(AggregateExpr { expr, args, filter, .. }) => std::iter::once(expr.as_ref())
+                .chain(args.iter())
+                .chain(filter.as_deref())
+                .collect(),
+            LogicalExpr::WindowFunction(window) => window
+                .args
+                .iter()
+                .chain(window.partition_by.iter())
+                .chain(window.order_by.iter().map(|sort| sort.expr.as_ref()))
+                .collect(),
             LogicalExpr::Negative(expr)
             | LogicalExpr::Cast(CastExpr { expr, .. })
-            | LogicalExpr::AggregateExpr(AggregateExpr { expr, .. })
             | LogicalExpr::SortExpr(SortExpr { expr, .. })
             | LogicalExpr::IsNull(expr)
             | LogicalExpr::IsNotNull(expr)
+            | LogicalExpr::InSubQuery(InSubQuery { expr, .. })
             | LogicalExpr::Alias(Alias { expr, .. }) => vec![expr.as_ref()],
-            LogicalExpr::SubQuery(_) | LogicalExpr::Wildcard | LogicalExpr::Column(_) | LogicalExpr::Literal(_) => {
-                vec![]
-            }
+            LogicalExpr::SubQuery(_)
+            | LogicalExpr::Wildcard
+            | LogicalExpr::Column(_)
+            | LogicalExpr::Literal(_)
+            | LogicalExpr::Placeholder(_) => vec![],
             LogicalExpr::Like(like) => vec![like.expr.as_ref(), like.pattern.as_ref()],
         };
 
@@ -316,11 +460,37 @@ pub(crate) fn get_expr_value(expr: LogicalExpr) -> Result<i64> {
     }
 }
 
+/// `expr [NOT] LIKE pattern [ESCAPE escape_char]`, `ILIKE` if case insensitive
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Like {
     pub negated: bool,
+    pub case_insensitive: bool,
     pub expr: Box<LogicalExpr>,
     pub pattern: Box<LogicalExpr>,
+    pub escape_char: Option<char>,
+}
+
+impl Like {
+    pub fn field(&self, _plan: &LogicalPlan) -> Result<FieldRef> {
+        Ok(Arc::new(Field::new(self.to_string(), DataType::Boolean, true)))
+    }
+}
+
+impl Display for Like {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}{} {}",
+            self.expr,
+            if self.negated { "NOT " } else { "" },
+            if self.case_insensitive { "ILIKE" } else { "LIKE" },
+            self.pattern
+        )?;
+        if let Some(escape_char) = self.escape_char {
+            write!(f, " ESCAPE '{}'", escape_char)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -328,3 +498,18 @@ pub struct SubQuery {
     pub subquery: Box<LogicalPlan>,
     pub outer_ref_columns: Vec<LogicalExpr>,
 }
+
+impl SubQuery {
+    pub fn field(&self, _plan: &LogicalPlan) -> Result<FieldRef> {
+        let schema = self.subquery.schema();
+        let field = schema.fields().first().ok_or(Error::InternalError(
+            "Scalar subquery must return exactly one column".to_owned(),
+        ))?;
+
+        Ok(Arc::new(Field::new(
+            format!("({})", field.name()),
+            field.data_type().clone(),
+            true,
+        )))
+    }
+}
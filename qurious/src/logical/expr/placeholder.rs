@@ -0,0 +1,31 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::error::Result;
+use crate::logical::plan::LogicalPlan;
+use arrow::datatypes::{DataType, Field, FieldRef};
+
+/// A positional parameter of a prepared statement, `$1`, replaced by the bound value when the statement is executed
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Placeholder {
+    pub id: String,
+    pub data_type: DataType,
+}
+
+impl Placeholder {
+    pub fn new(id: impl Into<String>, data_type: DataType) -> Self {
+        Self {
+            id: id.into(),
+            data_type,
+        }
+    }
+
+    pub fn field(&self, _plan: &LogicalPlan) -> Result<FieldRef> {
+        Ok(Arc::new(Field::new(&self.id, self.data_type.clone(), true)))
+    }
+}
+
+impl Display for Placeholder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
@@ -6,11 +6,17 @@ use crate::logical::expr::LogicalExpr;
 pub struct SortExpr {
     pub expr: Box<LogicalExpr>,
     pub asc: bool,
+    /// NULLs are sorted before the other values by default
+    pub nulls_first: bool,
 }
 
 impl Display for SortExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let order = if self.asc { "ASC" } else { "DESC" };
-        write!(f, "{} {order}", self.expr)
+        write!(f, "{} {order}", self.expr)?;
+        if !self.nulls_first {
+            write!(f, " NULLS LAST")?;
+        }
+        Ok(())
     }
 }
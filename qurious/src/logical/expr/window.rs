@@ -0,0 +1,168 @@
+use std::fmt::Display;
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, FieldRef, Schema};
+
+use super::{AggregateOperator, Column, LogicalExpr, SortExpr};
+use crate::common::table_relation::TableRelation;
+use crate::common::window_frame::WindowFrame;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::logical::plan::LogicalPlan;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WindowFunctionKind {
+    RowNumber,
+    Rank,
+    DenseRank,
+    Lag,
+    Lead,
+    Aggregate(AggregateOperator),
+}
+
+impl WindowFunctionKind {
+    /// check the number of arguments accepted by the function
+    fn check_args(&self, args: &[LogicalExpr]) -> Result<()> {
+        let (min, max) = match self {
+            WindowFunctionKind::RowNumber | WindowFunctionKind::Rank | WindowFunctionKind::DenseRank => (0, 0),
+            WindowFunctionKind::Lag | WindowFunctionKind::Lead => (1, 3),
+            WindowFunctionKind::Aggregate(op) => (op.num_args(), op.num_args()),
+        };
+
+        if args.len() < min || args.len() > max {
+            return internal_err!(
+                "Window function {} expects {} arguments, got {}",
+                self,
+                if min == max {
+                    min.to_string()
+                } else {
+                    format!("{min} to {max}")
+                },
+                args.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for WindowFunctionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowFunctionKind::RowNumber => write!(f, "ROW_NUMBER"),
+            WindowFunctionKind::Rank => write!(f, "RANK"),
+            WindowFunctionKind::DenseRank => write!(f, "DENSE_RANK"),
+            WindowFunctionKind::Lag => write!(f, "LAG"),
+            WindowFunctionKind::Lead => write!(f, "LEAD"),
+            WindowFunctionKind::Aggregate(op) => write!(f, "{op}"),
+        }
+    }
+}
+
+impl TryFrom<&str> for WindowFunctionKind {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "row_number" => Ok(WindowFunctionKind::RowNumber),
+            "rank" => Ok(WindowFunctionKind::Rank),
+            "dense_rank" => Ok(WindowFunctionKind::DenseRank),
+            "lag" => Ok(WindowFunctionKind::Lag),
+            "lead" => Ok(WindowFunctionKind::Lead),
+            _ => AggregateOperator::try_from(value)
+                .map(WindowFunctionKind::Aggregate)
+                .map_err(|_| Error::InternalError(format!("{} is not a valid window function", value))),
+        }
+    }
+}
+
+/// A function evaluated over a window of rows, eg. `ROW_NUMBER() OVER (PARTITION BY a ORDER BY b)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WindowFunction {
+    pub func: WindowFunctionKind,
+    pub args: Vec<LogicalExpr>,
+    pub partition_by: Vec<LogicalExpr>,
+    pub order_by: Vec<SortExpr>,
+    pub window_frame: WindowFrame,
+}
+
+impl WindowFunction {
+    pub fn try_new(
+        func: WindowFunctionKind,
+        args: Vec<LogicalExpr>,
+        partition_by: Vec<LogicalExpr>,
+        order_by: Vec<SortExpr>,
+        window_frame: WindowFrame,
+    ) -> Result<Self> {
+        func.check_args(&args)?;
+
+        Ok(Self {
+            func,
+            args,
+            partition_by,
+            order_by,
+            window_frame,
+        })
+    }
+
+    pub fn field(&self, plan: &LogicalPlan) -> Result<FieldRef> {
+        Ok(Arc::new(Field::new(
+            self.to_string(),
+            self.data_type(&plan.schema())?,
+            true,
+        )))
+    }
+
+    pub fn data_type(&self, schema: &Arc<Schema>) -> Result<DataType> {
+        match &self.func {
+            WindowFunctionKind::RowNumber
+            | WindowFunctionKind::Rank
+            | WindowFunctionKind::DenseRank
+            | WindowFunctionKind::Aggregate(AggregateOperator::Count) => Ok(DataType::Int64),
+            WindowFunctionKind::Lag | WindowFunctionKind::Lead => self.args[0].data_type(schema),
+            WindowFunctionKind::Aggregate(op) => op.infer_type(&self.args[0].data_type(schema)?),
+        }
+    }
+
+    pub(crate) fn as_column(&self) -> Result<LogicalExpr> {
+        Ok(LogicalExpr::Column(Column::new(
+            self.to_string(),
+            None::<TableRelation>,
+            false,
+        )))
+    }
+}
+
+impl Display for WindowFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}({}) OVER (",
+            self.func,
+            self.args.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ")
+        )?;
+        if !self.partition_by.is_empty() {
+            write!(
+                f,
+                "PARTITION BY {} ",
+                self.partition_by
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        if !self.order_by.is_empty() {
+            write!(
+                f,
+                "ORDER BY {} ",
+                self.order_by
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        write!(f, "{})", self.window_frame)
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+
+use crate::datasource::file::compression::FileCompression;
+use crate::{impl_logical_plan, logical::plan::LogicalPlan};
+
+/// the format of the file written by `COPY ... TO`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FileFormat {
+    Csv {
+        has_header: bool,
+        delimiter: u8,
+        compression: FileCompression,
+    },
+    Json,
+    /// the compression of the column chunks, like `zstd`, and the maximum number of rows of a row group
+    Parquet {
+        compression: Option<String>,
+        row_group_size: Option<usize>,
+    },
+}
+
+impl Display for FileFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FileFormat::Csv { .. } => write!(f, "csv"),
+            FileFormat::Json => write!(f, "json"),
+            FileFormat::Parquet { .. } => write!(f, "parquet"),
+        }
+    }
+}
+
+/// `COPY {table | (query)} TO 'file' [WITH (options)]`, writes the rows of the input to the file, which is
+/// replaced if it exists, and produces the number of rows written.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CopyTo {
+    pub input: Box<LogicalPlan>,
+    pub file_path: String,
+    pub format: FileFormat,
+    pub schema: SchemaRef,
+}
+
+impl CopyTo {
+    pub fn new(input: LogicalPlan, file_path: String, format: FileFormat) -> Self {
+        Self {
+            input: Box::new(input),
+            file_path,
+            format,
+            schema: Arc::new(Schema::new(vec![Field::new("row", DataType::UInt64, false)])),
+        }
+    }
+}
+
+impl_logical_plan!(CopyTo);
+
+impl Display for CopyTo {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "CopyTo: file=[{}] format=[{}]", self.file_path, self.format)
+    }
+}
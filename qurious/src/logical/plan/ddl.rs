@@ -10,21 +10,33 @@ use crate::{impl_logical_plan, logical::plan::LogicalPlan};
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DdlStatement {
     CreateMemoryTable(CreateMemoryTable),
+    CreateView(CreateView),
     DropTable(DropTable),
+    DropView(DropView),
+    CreateSchema(CreateSchema),
+    DropSchema(DropSchema),
 }
 
 impl DdlStatement {
     pub fn schema(&self) -> SchemaRef {
         match self {
             DdlStatement::CreateMemoryTable(c) => c.schema(),
-            DdlStatement::DropTable(_) => Arc::new(Schema::empty()),
+            DdlStatement::CreateView(_)
+            | DdlStatement::DropTable(_)
+            | DdlStatement::DropView(_)
+            | DdlStatement::CreateSchema(_)
+            | DdlStatement::DropSchema(_) => Arc::new(Schema::empty()),
         }
     }
 
     pub fn children(&self) -> Option<Vec<&LogicalPlan>> {
         match self {
             DdlStatement::CreateMemoryTable(c) => c.children(),
-            DdlStatement::DropTable(_) => None,
+            DdlStatement::CreateView(CreateView { input, .. }) => Some(vec![input]),
+            DdlStatement::DropTable(_)
+            | DdlStatement::DropView(_)
+            | DdlStatement::CreateSchema(_)
+            | DdlStatement::DropSchema(_) => None,
         }
     }
 }
@@ -35,7 +47,11 @@ impl Display for DdlStatement {
             DdlStatement::CreateMemoryTable(CreateMemoryTable { name, .. }) => {
                 write!(f, "CreateMemoryTable: [{}]", name)
             }
+            DdlStatement::CreateView(CreateView { name, .. }) => write!(f, "CreateView: [{}]", name),
             DdlStatement::DropTable(DropTable { name, .. }) => write!(f, "DropTable: [{}]", name),
+            DdlStatement::DropView(DropView { name, .. }) => write!(f, "DropView: [{}]", name),
+            DdlStatement::CreateSchema(CreateSchema { name, .. }) => write!(f, "CreateSchema: [{}]", name),
+            DdlStatement::DropSchema(DropSchema { name, .. }) => write!(f, "DropSchema: [{}]", name),
         }
     }
 }
@@ -44,13 +60,48 @@ impl Display for DdlStatement {
 pub struct CreateMemoryTable {
     pub schema: SchemaRef,
     pub name: String,
+    /// do nothing if the table already exists, otherwise it is an error
+    pub if_not_exists: bool,
+    /// the columns of the hash indexes of the table
+    pub indexes: Vec<Vec<String>>,
     pub input: Box<LogicalPlan>,
 }
 
 impl_logical_plan!(CreateMemoryTable);
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CreateView {
+    pub name: String,
+    /// replace the view if it already exists, otherwise it is an error
+    pub or_replace: bool,
+    /// the plan of the view query, it is inlined wherever the view is referenced
+    pub input: Box<LogicalPlan>,
+    /// the SQL of the view query
+    pub definition: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DropTable {
     pub name: String,
     pub if_exists: bool,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DropView {
+    pub name: String,
+    pub if_exists: bool,
+}
+
+/// `CREATE SCHEMA [IF NOT EXISTS] name`, an empty schema of the default catalog
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CreateSchema {
+    pub name: String,
+    pub if_not_exists: bool,
+}
+
+/// `DROP SCHEMA [IF EXISTS] name`, only an empty schema can be dropped
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DropSchema {
+    pub name: String,
+    pub if_exists: bool,
+}
@@ -0,0 +1,45 @@
+use std::fmt::Display;
+
+use arrow::datatypes::SchemaRef;
+
+use crate::logical::expr::LogicalExpr;
+use crate::logical::plan::LogicalPlan;
+
+/// `DISTINCT ON (exprs)`, keeps the first row of the input for each distinct value of the expressions, the input is
+/// sorted by the `ORDER BY` clause before.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DistinctOn {
+    pub on_expr: Vec<LogicalExpr>,
+    pub input: Box<LogicalPlan>,
+}
+
+impl DistinctOn {
+    pub fn new(input: LogicalPlan, on_expr: Vec<LogicalExpr>) -> Self {
+        Self {
+            on_expr,
+            input: Box::new(input),
+        }
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    pub fn children(&self) -> Option<Vec<&LogicalPlan>> {
+        Some(vec![&self.input])
+    }
+}
+
+impl Display for DistinctOn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DistinctOn: on_expr=[{}]",
+            self.on_expr
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
@@ -9,6 +9,10 @@ pub enum DmlOperator {
     Insert,
     Update,
     Delete,
+    /// remove all the rows of the table
+    Truncate,
+    /// `INSERT ... ON CONFLICT (keys) DO UPDATE`, an input row replaces the row with the same keys or is appended
+    Upsert(Vec<String>),
 }
 
 impl Display for DmlOperator {
@@ -17,6 +21,8 @@ impl Display for DmlOperator {
             DmlOperator::Insert => write!(f, "INSERT"),
             DmlOperator::Update => write!(f, "UPDATE"),
             DmlOperator::Delete => write!(f, "DELETE"),
+            DmlOperator::Truncate => write!(f, "TRUNCATE"),
+            DmlOperator::Upsert(_) => write!(f, "UPSERT"),
         }
     }
 }
@@ -33,10 +39,17 @@ impl_logical_plan!(DmlStatement);
 
 impl Display for DmlStatement {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match self.op {
+        match &self.op {
             DmlOperator::Insert => write!(f, "Dml: op=[Insert Into] table=[{}]", self.relation),
             DmlOperator::Delete => write!(f, "Dml: op=[Delete From] table=[{}]", self.relation),
             DmlOperator::Update => write!(f, "Dml: op=[Update] table=[{}]", self.relation),
+            DmlOperator::Truncate => write!(f, "Dml: op=[Truncate] table=[{}]", self.relation),
+            DmlOperator::Upsert(keys) => write!(
+                f,
+                "Dml: op=[Upsert] table=[{}] keys=[{}]",
+                self.relation,
+                keys.join(", ")
+            ),
         }
     }
 }
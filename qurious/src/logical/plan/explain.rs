@@ -0,0 +1,41 @@
+use std::fmt::Display;
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+
+use crate::logical::plan::LogicalPlan;
+
+/// `EXPLAIN [ANALYZE] [VERBOSE] query`, produces the plans of the query as a single column of text, one line per
+/// row. ANALYZE executes the query and shows the runtime metrics of each physical operator.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Explain {
+    pub analyze: bool,
+    pub verbose: bool,
+    pub plan: Box<LogicalPlan>,
+    pub schema: SchemaRef,
+}
+
+impl Explain {
+    pub fn new(plan: LogicalPlan, analyze: bool, verbose: bool) -> Self {
+        Self {
+            analyze,
+            verbose,
+            plan: Box::new(plan),
+            schema: Arc::new(Schema::new(vec![Field::new("plan", DataType::Utf8, false)])),
+        }
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    pub fn children(&self) -> Option<Vec<&LogicalPlan>> {
+        Some(vec![&self.plan])
+    }
+}
+
+impl Display for Explain {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Explain: analyze={}, verbose={}", self.analyze, self.verbose)
+    }
+}
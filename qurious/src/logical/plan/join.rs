@@ -1,6 +1,12 @@
 use crate::{
-    common::{join_type::JoinType, table_schema::TableSchemaRef},
-    logical::{expr::LogicalExpr, plan::LogicalPlan},
+    common::{
+        join_type::JoinType,
+        table_schema::{TableSchema, TableSchemaRef},
+    },
+    logical::{
+        expr::{LogicalExpr, SubQuery},
+        plan::LogicalPlan,
+    },
 };
 use arrow::datatypes::SchemaRef;
 use std::{fmt::Display, sync::Arc};
@@ -42,6 +48,16 @@ impl Join {
         self.schema.clone()
     }
 
+    /// the columns of the left side then the right side, with their qualifiers
+    pub fn table_schema(&self) -> TableSchemaRef {
+        let mut field_qualifiers = self.left.table_schema().field_qualifiers.clone();
+        if !self.join_type.is_semi_or_anti() {
+            field_qualifiers.extend(self.right.table_schema().field_qualifiers.iter().cloned());
+        }
+
+        Arc::new(TableSchema::new(field_qualifiers, self.schema.clone()))
+    }
+
     pub fn children(&self) -> Option<Vec<&LogicalPlan>> {
         Some(vec![&self.left, &self.right])
     }
@@ -52,3 +68,37 @@ impl Display for Join {
         write!(f, "{}: Filter: {}", self.join_type, self.filter)
     }
 }
+
+/// `left CROSS JOIN LATERAL (subquery)`, the subquery references the columns of the left side
+/// and is evaluated for each row of it, the output is the row followed by each row of the subquery.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LateralJoin {
+    pub left: Arc<LogicalPlan>,
+    pub subquery: SubQuery,
+    pub schema: TableSchemaRef,
+}
+
+impl LateralJoin {
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.arrow_schema()
+    }
+
+    pub fn children(&self) -> Option<Vec<&LogicalPlan>> {
+        Some(vec![&self.left, &self.subquery.subquery])
+    }
+}
+
+impl Display for LateralJoin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "LateralJoin: Outer References: [{}]",
+            self.subquery
+                .outer_ref_columns
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
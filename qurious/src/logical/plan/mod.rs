@@ -1,34 +1,52 @@
 mod aggregate;
+mod copy;
 mod ddl;
+mod distinct;
 mod dml;
+mod explain;
 mod filter;
 mod join;
 mod limit;
+mod prepare;
 mod projection;
+mod recursive_query;
+mod sample;
 mod scan;
+mod set_operation;
 mod sort;
 mod sub_query;
+mod window;
 
 use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
 
 pub use aggregate::Aggregate;
+pub use copy::{CopyTo, FileFormat};
 pub use ddl::*;
+pub use distinct::DistinctOn;
 pub use dml::*;
+pub use explain::Explain;
 pub use filter::Filter;
 pub use join::*;
 pub use limit::Limit;
+pub use prepare::*;
 pub use projection::Projection;
+pub use recursive_query::RecursiveQuery;
+pub use sample::Sample;
 pub use scan::TableScan;
+pub use set_operation::SetOperation;
 pub use sort::*;
 pub use sub_query::SubqueryAlias;
+pub use window::Window;
 
 use arrow::datatypes::SchemaRef;
 
-use super::expr::{Column, LogicalExpr};
+use super::expr::{Column, LogicalExpr, Placeholder, SubQuery};
 use crate::common::table_relation::TableRelation;
 use crate::common::table_schema::TableSchemaRef;
 use crate::common::transformed::{TransformNode, Transformed, TransformedResult, TreeNodeContainer, TreeNodeRecursion};
-use crate::error::Result;
+use crate::datatypes::scalar::ScalarValue;
+use crate::error::{Error, Result};
 
 #[macro_export]
 macro_rules! impl_logical_plan {
@@ -50,6 +68,8 @@ pub enum LogicalPlan {
     /// Apply Cross Join to two logical plans.
     CrossJoin(CrossJoin),
     Join(Join),
+    /// Join each row of the left plan with the rows of a correlated subquery, `CROSS JOIN LATERAL`.
+    LateralJoin(LateralJoin),
     Projection(Projection),
     Filter(Filter),
     Aggregate(Aggregate),
@@ -63,10 +83,26 @@ pub enum LogicalPlan {
     Sort(Sort),
     /// Limit the number of rows in the result set, and optionally an offset.
     Limit(Limit),
+    /// Keep a random sample of the rows, `TABLESAMPLE`.
+    Sample(Sample),
+    /// Recursive CTE, iterates the recursive term until no new rows are produced.
+    RecursiveQuery(RecursiveQuery),
+    /// Evaluate window functions, appending one column per window expression to the input.
+    Window(Window),
+    /// Combine the rows of two plans, UNION, INTERSECT or EXCEPT.
+    SetOperation(SetOperation),
+    /// Keep the first row for each distinct value of the expressions, `DISTINCT ON (exprs)`.
+    DistinctOn(DistinctOn),
     /// Data Definition Language (DDL) statements. CREATE, DROP, etc.
     Ddl(DdlStatement),
     /// Data Manipulation Language (DML) statements. INSERT, UPDATE, DELETE, etc.
     Dml(DmlStatement),
+    /// Write the rows of a query to a file, `COPY ... TO`.
+    CopyTo(CopyTo),
+    /// Describe the plans of a query instead of executing it.
+    Explain(Explain),
+    /// PREPARE, EXECUTE and DEALLOCATE of prepared statements.
+    Prepared(PreparedStatement),
 }
 
 impl LogicalPlan {
@@ -77,12 +113,11 @@ impl LogicalPlan {
 
         while let Some(plan) = stack.pop() {
             match plan.apply_exprs(|expr| {
-                expr.apply_children(|expr| {
-                    match expr {
-                        LogicalExpr::Column(Column { is_outer_ref: true, .. }) => {
+                expr.apply(|expr| {
+                    if let LogicalExpr::Column(Column { is_outer_ref: true, .. }) = expr {
+                        if !outer_ref_columns.contains(expr) {
                             outer_ref_columns.push(expr.clone());
                         }
-                        _ => {}
                     }
 
                     Ok(TreeNodeRecursion::Continue)
@@ -119,11 +154,20 @@ impl LogicalPlan {
             LogicalPlan::CrossJoin(s) => s.schema(),
             LogicalPlan::SubqueryAlias(s) => s.schema(),
             LogicalPlan::Join(j) => j.schema(),
+            LogicalPlan::LateralJoin(j) => j.schema(),
             LogicalPlan::Sort(s) => s.schema(),
             LogicalPlan::Limit(l) => l.schema(),
+            LogicalPlan::Sample(s) => s.schema(),
             LogicalPlan::Ddl(d) => d.schema(),
             LogicalPlan::Dml(d) => d.schema(),
             LogicalPlan::Values(v) => v.schema.clone(),
+            LogicalPlan::RecursiveQuery(r) => r.schema(),
+            LogicalPlan::SetOperation(s) => s.schema(),
+            LogicalPlan::Window(w) => w.schema(),
+            LogicalPlan::DistinctOn(d) => d.schema(),
+            LogicalPlan::CopyTo(c) => c.schema(),
+            LogicalPlan::Explain(e) => e.schema(),
+            LogicalPlan::Prepared(p) => p.schema(),
         }
     }
 
@@ -131,8 +175,12 @@ impl LogicalPlan {
         match self {
             LogicalPlan::TableScan(s) => s.schema.clone(),
             LogicalPlan::CrossJoin(s) => s.schema.clone(),
+            LogicalPlan::Join(j) => j.table_schema(),
+            LogicalPlan::LateralJoin(j) => j.schema.clone(),
             LogicalPlan::SubqueryAlias(s) => s.schema.clone(),
             LogicalPlan::Filter(f) => f.input.table_schema(),
+            LogicalPlan::Sample(s) => s.input.table_schema(),
+            LogicalPlan::DistinctOn(d) => d.input.table_schema(),
             LogicalPlan::Projection(p) => p.schema.clone(),
             _ => todo!("[{}] not implement table_schema", self),
         }
@@ -148,10 +196,19 @@ impl LogicalPlan {
             LogicalPlan::CrossJoin(s) => s.children(),
             LogicalPlan::SubqueryAlias(s) => s.children(),
             LogicalPlan::Join(j) => j.children(),
+            LogicalPlan::LateralJoin(j) => j.children(),
             LogicalPlan::Sort(s) => s.children(),
             LogicalPlan::Limit(l) => l.children(),
+            LogicalPlan::Sample(s) => s.children(),
             LogicalPlan::Ddl(l) => l.children(),
             LogicalPlan::Dml(l) => l.children(),
+            LogicalPlan::RecursiveQuery(r) => r.children(),
+            LogicalPlan::SetOperation(s) => s.children(),
+            LogicalPlan::Window(w) => w.children(),
+            LogicalPlan::DistinctOn(d) => d.children(),
+            LogicalPlan::CopyTo(c) => c.children(),
+            LogicalPlan::Explain(e) => e.children(),
+            LogicalPlan::Prepared(p) => p.children(),
         }
     }
 
@@ -170,10 +227,40 @@ impl LogicalPlan {
                 Ok(TreeNodeRecursion::Continue)
             }
             LogicalPlan::Filter(Filter { expr, .. }) => f(expr),
+            LogicalPlan::Window(Window { window_expr, .. }) => window_expr.apply(f),
+            LogicalPlan::DistinctOn(DistinctOn { on_expr, .. }) => on_expr.apply(f),
             _ => Ok(TreeNodeRecursion::Continue),
         }
     }
 
+    /// replace the placeholder `$n` of a prepared statement with the n-th value, including the placeholders in
+    /// the subqueries
+    pub fn with_param_values(self, values: &[ScalarValue]) -> Result<LogicalPlan> {
+        self.transform(|plan| {
+            plan.map_exprs(|expr| {
+                expr.transform(|expr| match expr {
+                    LogicalExpr::Placeholder(Placeholder { id, .. }) => id[1..]
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|n| values.get(n.checked_sub(1)?))
+                        .map(|value| Transformed::yes(LogicalExpr::Literal(value.clone())))
+                        .ok_or_else(|| Error::PlanError(format!("No value found for placeholder {}", id))),
+                    LogicalExpr::SubQuery(SubQuery {
+                        subquery,
+                        outer_ref_columns,
+                    }) => subquery.with_param_values(values).map(|subquery| {
+                        Transformed::yes(LogicalExpr::SubQuery(SubQuery {
+                            subquery: Box::new(subquery),
+                            outer_ref_columns,
+                        }))
+                    }),
+                    _ => Ok(Transformed::no(expr)),
+                })
+            })
+        })
+        .data()
+    }
+
     pub fn map_exprs<F>(self, mut f: F) -> Result<Transformed<Self>>
     where
         F: FnMut(LogicalExpr) -> Result<Transformed<LogicalExpr>>,
@@ -210,6 +297,31 @@ impl LogicalPlan {
                 input,
                 expr: f(expr).data()?,
             }))),
+            LogicalPlan::Window(Window {
+                schema,
+                input,
+                window_expr,
+            }) => window_expr
+                .into_iter()
+                .map(|expr| f(expr).data())
+                .collect::<Result<Vec<_>>>()
+                .map(|window_expr| {
+                    Transformed::yes(LogicalPlan::Window(Window {
+                        schema,
+                        input,
+                        window_expr,
+                    }))
+                }),
+            LogicalPlan::DistinctOn(DistinctOn { on_expr, input }) => on_expr
+                .into_iter()
+                .map(|expr| f(expr).data())
+                .collect::<Result<Vec<_>>>()
+                .map(|on_expr| Transformed::yes(LogicalPlan::DistinctOn(DistinctOn { on_expr, input }))),
+            LogicalPlan::Values(Values { values, schema }) => values
+                .into_iter()
+                .map(|row| row.into_iter().map(|expr| f(expr).data()).collect::<Result<Vec<_>>>())
+                .collect::<Result<Vec<_>>>()
+                .map(|values| Transformed::yes(LogicalPlan::Values(Values { values, schema }))),
             _ => Ok(Transformed::no(self)),
         }
     }
@@ -238,10 +350,11 @@ impl TransformNode for LogicalPlan {
                     aggr_expr,
                 })
             }),
-            LogicalPlan::Sort(Sort { exprs, input }) => f(*input)?.update(|input| {
+            LogicalPlan::Sort(Sort { exprs, input, fetch }) => f(*input)?.update(|input| {
                 LogicalPlan::Sort(Sort {
                     exprs,
                     input: Box::new(input),
+                    fetch,
                 })
             }),
             LogicalPlan::Limit(Limit { input, fetch, skip }) => f(*input)?.update(|input| {
@@ -251,12 +364,137 @@ impl TransformNode for LogicalPlan {
                     skip,
                 })
             }),
+            LogicalPlan::Sample(Sample {
+                input,
+                method,
+                fraction,
+                seed,
+            }) => f(*input)?.update(|input| {
+                LogicalPlan::Sample(Sample {
+                    input: Box::new(input),
+                    method,
+                    fraction,
+                    seed,
+                })
+            }),
             LogicalPlan::Filter(Filter { expr, input }) => f(*input)?.update(|input| {
                 LogicalPlan::Filter(Filter {
                     expr,
                     input: Box::new(input),
                 })
             }),
+            LogicalPlan::Window(Window {
+                schema,
+                input,
+                window_expr,
+            }) => f(*input)?.update(|input| {
+                LogicalPlan::Window(Window {
+                    schema,
+                    input: Box::new(input),
+                    window_expr,
+                })
+            }),
+            LogicalPlan::DistinctOn(DistinctOn { on_expr, input }) => f(*input)?.update(|input| {
+                LogicalPlan::DistinctOn(DistinctOn {
+                    on_expr,
+                    input: Box::new(input),
+                })
+            }),
+            LogicalPlan::SubqueryAlias(SubqueryAlias { input, alias, schema }) => f(Arc::unwrap_or_clone(input))?
+                .update(|input| {
+                    LogicalPlan::SubqueryAlias(SubqueryAlias {
+                        input: Arc::new(input),
+                        alias,
+                        schema,
+                    })
+                }),
+            LogicalPlan::LateralJoin(LateralJoin { left, subquery, schema }) => {
+                let left = f(Arc::unwrap_or_clone(left))?;
+                let right = f(*subquery.subquery)?;
+                let transformed = left.transformed || right.transformed;
+
+                Transformed {
+                    data: LogicalPlan::LateralJoin(LateralJoin {
+                        left: Arc::new(left.data),
+                        subquery: SubQuery {
+                            subquery: Box::new(right.data),
+                            outer_ref_columns: subquery.outer_ref_columns,
+                        },
+                        schema,
+                    }),
+                    transformed,
+                }
+            }
+            LogicalPlan::RecursiveQuery(RecursiveQuery {
+                name,
+                static_term,
+                recursive_term,
+                is_distinct,
+                work_table,
+            }) => {
+                let static_term = f(*static_term)?;
+                let recursive_term = f(*recursive_term)?;
+                let transformed = static_term.transformed || recursive_term.transformed;
+
+                Transformed {
+                    data: LogicalPlan::RecursiveQuery(RecursiveQuery {
+                        name,
+                        static_term: Box::new(static_term.data),
+                        recursive_term: Box::new(recursive_term.data),
+                        is_distinct,
+                        work_table,
+                    }),
+                    transformed,
+                }
+            }
+            LogicalPlan::SetOperation(SetOperation {
+                op,
+                all,
+                left,
+                right,
+                schema,
+            }) => {
+                let left = f(*left)?;
+                let right = f(*right)?;
+                let transformed = left.transformed || right.transformed;
+
+                Transformed {
+                    data: LogicalPlan::SetOperation(SetOperation {
+                        op,
+                        all,
+                        left: Box::new(left.data),
+                        right: Box::new(right.data),
+                        schema,
+                    }),
+                    transformed,
+                }
+            }
+            LogicalPlan::Dml(DmlStatement {
+                relation,
+                op,
+                schema,
+                input,
+            }) => f(*input)?.update(|input| {
+                LogicalPlan::Dml(DmlStatement {
+                    relation,
+                    op,
+                    schema,
+                    input: Box::new(input),
+                })
+            }),
+            LogicalPlan::CopyTo(CopyTo {
+                input,
+                file_path,
+                format,
+                schema,
+            }) => f(*input)?.update(|input| {
+                LogicalPlan::CopyTo(CopyTo {
+                    input: Box::new(input),
+                    file_path,
+                    format,
+                    schema,
+                })
+            }),
             _ => Transformed::no(self),
         })
     }
@@ -303,11 +541,20 @@ impl std::fmt::Display for LogicalPlan {
             LogicalPlan::CrossJoin(s) => write!(f, "{}", s),
             LogicalPlan::SubqueryAlias(s) => write!(f, "{}", s),
             LogicalPlan::Join(j) => write!(f, "{}", j),
+            LogicalPlan::LateralJoin(j) => write!(f, "{}", j),
             LogicalPlan::Sort(s) => write!(f, "{}", s),
             LogicalPlan::Limit(l) => write!(f, "{}", l),
+            LogicalPlan::Sample(s) => write!(f, "{}", s),
             LogicalPlan::Ddl(l) => write!(f, "{}", l),
             LogicalPlan::Values(v) => write!(f, "{}", v),
             LogicalPlan::Dml(d) => write!(f, "{}", d),
+            LogicalPlan::RecursiveQuery(r) => write!(f, "{}", r),
+            LogicalPlan::SetOperation(s) => write!(f, "{}", s),
+            LogicalPlan::Window(w) => write!(f, "{}", w),
+            LogicalPlan::DistinctOn(d) => write!(f, "{}", d),
+            LogicalPlan::CopyTo(c) => write!(f, "{}", c),
+            LogicalPlan::Explain(e) => write!(f, "{}", e),
+            LogicalPlan::Prepared(p) => write!(f, "{}", p),
         }
     }
 }
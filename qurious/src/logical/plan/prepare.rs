@@ -0,0 +1,79 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    sync::Arc,
+};
+
+use arrow::datatypes::{DataType, Schema, SchemaRef};
+
+use crate::logical::expr::LogicalExpr;
+use crate::logical::plan::LogicalPlan;
+
+/// Statements managing the prepared statements of a session, PREPARE, EXECUTE and DEALLOCATE.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PreparedStatement {
+    Prepare(Prepare),
+    Execute(Execute),
+    Deallocate(Deallocate),
+}
+
+impl PreparedStatement {
+    pub fn schema(&self) -> SchemaRef {
+        Arc::new(Schema::empty())
+    }
+
+    pub fn children(&self) -> Option<Vec<&LogicalPlan>> {
+        match self {
+            PreparedStatement::Prepare(Prepare { input, .. }) => Some(vec![input]),
+            PreparedStatement::Execute(_) | PreparedStatement::Deallocate(_) => None,
+        }
+    }
+}
+
+impl Display for PreparedStatement {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            PreparedStatement::Prepare(Prepare { name, data_types, .. }) => write!(
+                f,
+                "Prepare: name=[{}], data_types=[{}]",
+                name,
+                data_types
+                    .iter()
+                    .map(|data_type| data_type.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            PreparedStatement::Execute(Execute { name, parameters }) => write!(
+                f,
+                "Execute: name=[{}], parameters=[{}]",
+                name,
+                parameters
+                    .iter()
+                    .map(|parameter| parameter.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            PreparedStatement::Deallocate(Deallocate { name }) => write!(f, "Deallocate: [{}]", name),
+        }
+    }
+}
+
+/// `PREPARE name [(data_type, ...)] AS statement`, the statement is planned once with the parameters as
+/// placeholders, the data type of `$n` is the n-th data type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Prepare {
+    pub name: String,
+    pub data_types: Vec<DataType>,
+    pub input: Box<LogicalPlan>,
+}
+
+/// `EXECUTE name [(parameter, ...)]`, the parameters are cast to the data types of the prepared statement.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Execute {
+    pub name: String,
+    pub parameters: Vec<LogicalExpr>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Deallocate {
+    pub name: String,
+}
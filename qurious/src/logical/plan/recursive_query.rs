@@ -0,0 +1,67 @@
+use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use arrow::datatypes::SchemaRef;
+
+use crate::datasource::memory::MemoryTable;
+use crate::logical::plan::LogicalPlan;
+use crate::provider::table::TableProvider;
+
+/// A recursive CTE, eg. `WITH RECURSIVE t AS (static_term UNION [ALL] recursive_term)`
+///
+/// The static term is evaluated once, then the recursive term is evaluated repeatedly against
+/// the rows produced by the previous iteration (the work table) until no new rows are produced.
+#[derive(Debug, Clone)]
+pub struct RecursiveQuery {
+    pub name: String,
+    pub static_term: Box<LogicalPlan>,
+    pub recursive_term: Box<LogicalPlan>,
+    /// `UNION` removes duplicate rows, `UNION ALL` keeps them
+    pub is_distinct: bool,
+    /// the table scanned by the self reference in the recursive term
+    pub work_table: Arc<MemoryTable>,
+}
+
+impl RecursiveQuery {
+    pub fn schema(&self) -> SchemaRef {
+        self.work_table.schema()
+    }
+
+    pub fn children(&self) -> Option<Vec<&LogicalPlan>> {
+        Some(vec![&self.static_term, &self.recursive_term])
+    }
+}
+
+impl Display for RecursiveQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "RecursiveQuery: name={}, is_distinct={}",
+            self.name, self.is_distinct
+        )
+    }
+}
+
+impl PartialEq for RecursiveQuery {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.static_term == other.static_term
+            && self.recursive_term == other.recursive_term
+            && self.is_distinct == other.is_distinct
+            && Arc::ptr_eq(&self.work_table, &other.work_table)
+    }
+}
+
+impl Eq for RecursiveQuery {}
+
+impl Hash for RecursiveQuery {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.static_term.hash(state);
+        self.recursive_term.hash(state);
+        self.is_distinct.hash(state);
+    }
+}
@@ -0,0 +1,58 @@
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+
+use arrow::datatypes::SchemaRef;
+
+use crate::common::sample_method::SampleMethod;
+use crate::logical::plan::LogicalPlan;
+
+/// `TABLESAMPLE method (percent) [REPEATABLE (seed)]`, keeps about `fraction` of the rows of the input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub input: Box<LogicalPlan>,
+    pub method: SampleMethod,
+    /// the fraction of the rows to keep, between 0 and 1
+    pub fraction: f64,
+    /// the seed of the random generator, the rows are sampled differently on each execution without it
+    pub seed: Option<u64>,
+}
+
+impl Sample {
+    pub fn new(input: LogicalPlan, method: SampleMethod, fraction: f64, seed: Option<u64>) -> Self {
+        Self {
+            input: Box::new(input),
+            method,
+            fraction,
+            seed,
+        }
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    pub fn children(&self) -> Option<Vec<&LogicalPlan>> {
+        Some(vec![&self.input])
+    }
+}
+
+impl Eq for Sample {}
+
+impl Hash for Sample {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.input.hash(state);
+        self.method.hash(state);
+        self.fraction.to_bits().hash(state);
+        self.seed.hash(state);
+    }
+}
+
+impl Display for Sample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Sample: method={}, fraction={}", self.method, self.fraction)?;
+        if let Some(seed) = self.seed {
+            write!(f, ", seed={}", seed)?;
+        }
+        Ok(())
+    }
+}
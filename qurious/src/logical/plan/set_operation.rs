@@ -0,0 +1,33 @@
+use std::fmt::Display;
+
+use arrow::datatypes::SchemaRef;
+
+use crate::common::set_operator::SetOperator;
+use crate::logical::plan::LogicalPlan;
+
+/// `left UNION | INTERSECT | EXCEPT [ALL] right`, the inputs are coerced to the schema of the operation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SetOperation {
+    pub op: SetOperator,
+    /// `ALL` keeps the duplicate rows, otherwise the result is distinct
+    pub all: bool,
+    pub left: Box<LogicalPlan>,
+    pub right: Box<LogicalPlan>,
+    pub schema: SchemaRef,
+}
+
+impl SetOperation {
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    pub fn children(&self) -> Option<Vec<&LogicalPlan>> {
+        Some(vec![&self.left, &self.right])
+    }
+}
+
+impl Display for SetOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SetOperation: op={}, all={}", self.op, self.all)
+    }
+}
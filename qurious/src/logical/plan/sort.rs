@@ -9,6 +9,8 @@ use crate::logical::plan::LogicalPlan;
 pub struct Sort {
     pub exprs: Vec<SortExpr>,
     pub input: Box<LogicalPlan>,
+    /// the number of the first rows of the order kept, set from a limit of the sorted rows
+    pub fetch: Option<usize>,
 }
 
 impl Sort {
@@ -31,6 +33,11 @@ impl Display for Sort {
                 .map(|e| e.to_string())
                 .collect::<Vec<String>>()
                 .join(", ")
-        )
+        )?;
+        if let Some(fetch) = self.fetch {
+            write!(f, ", fetch={}", fetch)?;
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,58 @@
+use arrow::datatypes::{Schema, SchemaRef};
+use itertools::Itertools;
+
+use super::LogicalPlan;
+use crate::error::Result;
+use crate::impl_logical_plan;
+use crate::logical::expr::LogicalExpr;
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// Evaluates window functions over the input, the output schema is the input columns
+/// followed by one column per window expression.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Window {
+    pub schema: SchemaRef,
+    pub input: Box<LogicalPlan>,
+    pub window_expr: Vec<LogicalExpr>,
+}
+
+impl Window {
+    pub fn try_new(input: LogicalPlan, window_expr: Vec<LogicalExpr>) -> Result<Self> {
+        let window_expr = window_expr.into_iter().unique().collect::<Vec<_>>();
+        let input_schema = input.schema();
+        let window_fields = window_expr
+            .iter()
+            .map(|e| e.field(&input))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            schema: Arc::new(Schema::new(
+                input_schema
+                    .fields()
+                    .iter()
+                    .cloned()
+                    .chain(window_fields)
+                    .collect::<Vec<_>>(),
+            )),
+            input: Box::new(input),
+            window_expr,
+        })
+    }
+}
+
+impl_logical_plan!(Window);
+
+impl Display for Window {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Window: window_expr=[{}]",
+            self.window_expr
+                .iter()
+                .map(|f| f.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
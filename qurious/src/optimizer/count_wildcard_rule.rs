@@ -2,7 +2,9 @@ use super::OptimizerRule;
 use crate::common::transformed::{TransformNode, Transformed, TransformedResult};
 use crate::datatypes::scalar::ScalarValue;
 use crate::error::Result;
-use crate::logical::expr::{AggregateExpr, AggregateOperator, LogicalExpr};
+use crate::logical::expr::{
+    AggregateExpr, AggregateOperator, LogicalExpr, SubQuery, WindowFunction, WindowFunctionKind,
+};
 use crate::logical::plan::LogicalPlan;
 
 pub struct CountWildcardRule;
@@ -13,7 +15,8 @@ impl OptimizerRule for CountWildcardRule {
     }
 
     fn optimize(&self, plan: LogicalPlan) -> Result<LogicalPlan> {
-        plan.transform(|input| input.map_exprs(count_wildcard_rule)).data()
+        plan.transform(|input| input.map_exprs(|expr| expr.transform(count_wildcard_rule)))
+            .data()
     }
 }
 
@@ -23,6 +26,29 @@ fn count_wildcard_rule(expr: LogicalExpr) -> Result<Transformed<LogicalExpr>> {
             return Ok(Transformed::yes(LogicalExpr::AggregateExpr(AggregateExpr {
                 op: AggregateOperator::Count,
                 expr: Box::new(LogicalExpr::Literal(ScalarValue::from(1))),
+                args: vec![],
+                distinct: false,
+                filter: agg.filter.clone(),
+            })));
+        }
+    }
+
+    if let LogicalExpr::SubQuery(subquery) = expr {
+        return CountWildcardRule.optimize(*subquery.subquery).map(|plan| {
+            Transformed::yes(LogicalExpr::SubQuery(SubQuery {
+                subquery: Box::new(plan),
+                outer_ref_columns: subquery.outer_ref_columns,
+            }))
+        });
+    }
+
+    if let LogicalExpr::WindowFunction(window) = &expr {
+        if WindowFunctionKind::Aggregate(AggregateOperator::Count) == window.func
+            && window.args == [LogicalExpr::Wildcard]
+        {
+            return Ok(Transformed::yes(LogicalExpr::WindowFunction(WindowFunction {
+                args: vec![LogicalExpr::Literal(ScalarValue::from(1))],
+                ..window.clone()
             })));
         }
     }
@@ -1,11 +1,15 @@
 mod count_wildcard_rule;
 mod pushdown_filter_inner_join;
+mod pushdown_filter_table_scan;
+mod pushdown_limit_sort;
 // mod scalar_subquery_to_join;
 mod type_coercion;
 
 use crate::{error::Result, logical::plan::LogicalPlan};
 use count_wildcard_rule::CountWildcardRule;
 use pushdown_filter_inner_join::PushdownFilterInnerJoin;
+use pushdown_filter_table_scan::PushdownFilterTableScan;
+use pushdown_limit_sort::PushdownLimitSort;
 use type_coercion::TypeCoercion;
 
 pub trait OptimizerRule {
@@ -25,6 +29,8 @@ impl Optimizer {
                 Box::new(CountWildcardRule),
                 Box::new(TypeCoercion),
                 Box::new(PushdownFilterInnerJoin),
+                Box::new(PushdownFilterTableScan),
+                Box::new(PushdownLimitSort),
             ],
         }
     }
@@ -0,0 +1,214 @@
+use super::OptimizerRule;
+use crate::common::transformed::{TransformNode, Transformed, TransformedResult, TreeNodeRecursion};
+use crate::datatypes::operator::Operator;
+use crate::error::Result;
+use crate::logical::expr::{split_conjunction, BinaryExpr, LogicalExpr};
+use crate::logical::plan::{Filter, LogicalPlan, TableScan};
+use crate::provider::table::FilterPushdown;
+
+/// Looks like this:
+/// ```text
+/// Filter(a.x > 10 AND a.y = b.y)
+///   TableScan a
+/// ```
+///
+/// After the rule is applied, the plan will look like this:
+/// ```text
+/// Filter(a.x > 10 AND a.y = b.y)
+///   TableScan a, filter=[a.x > 10]
+/// ```
+///
+/// A conjunct of the filter is only pushed to a data source that supports it, and the conjunct is kept because the
+/// data source may still return the rows it rejects, unless the data source evaluates it exactly. A conjunct with a
+/// subquery, an outer reference or a placeholder is not pushed.
+#[derive(Debug, Default, Clone)]
+pub struct PushdownFilterTableScan;
+
+impl OptimizerRule for PushdownFilterTableScan {
+    fn name(&self) -> &str {
+        "pushdown_filter_table_scan"
+    }
+
+    fn optimize(&self, plan: LogicalPlan) -> Result<LogicalPlan> {
+        plan.transform(|plan| match plan {
+            LogicalPlan::Filter(Filter { input, expr }) => match *input {
+                LogicalPlan::TableScan(scan) => {
+                    let mut filters = scan.filter.as_ref().map(split_conjunction).unwrap_or_default();
+                    let mut kept = vec![];
+                    let mut transformed = false;
+                    for conjunct in split_conjunction(&expr) {
+                        let pushdown = match can_pushdown(conjunct) {
+                            true => scan.source.supports_filter_pushdown(conjunct),
+                            false => FilterPushdown::Unsupported,
+                        };
+                        if pushdown != FilterPushdown::Unsupported && !filters.contains(&conjunct) {
+                            filters.push(conjunct);
+                            transformed = true;
+                        }
+                        // an exact filter is evaluated by the scan only
+                        match pushdown {
+                            FilterPushdown::Exact => transformed = true,
+                            _ => kept.push(conjunct),
+                        }
+                    }
+                    let filter = conjunction(filters);
+                    let kept = conjunction(kept);
+                    let scan = LogicalPlan::TableScan(TableScan { filter, ..scan });
+
+                    Ok(Transformed {
+                        data: match kept {
+                            Some(expr) => LogicalPlan::Filter(Filter {
+                                input: Box::new(scan),
+                                expr,
+                            }),
+                            None => scan,
+                        },
+                        transformed,
+                    })
+                }
+                input => Ok(Transformed::no(LogicalPlan::Filter(Filter {
+                    input: Box::new(input),
+                    expr,
+                }))),
+            },
+            _ => Ok(Transformed::no(plan)),
+        })
+        .data()
+    }
+}
+
+fn conjunction(exprs: Vec<&LogicalExpr>) -> Option<LogicalExpr> {
+    exprs
+        .into_iter()
+        .cloned()
+        .reduce(|l, r| LogicalExpr::BinaryExpr(BinaryExpr::new(l, Operator::And, r)))
+}
+
+fn can_pushdown(expr: &LogicalExpr) -> bool {
+    let mut pushdown = true;
+    expr.apply(|expr| {
+        if matches!(
+            expr,
+            LogicalExpr::SubQuery(_) | LogicalExpr::InSubQuery(_) | LogicalExpr::Placeholder(_)
+        ) || matches!(expr, LogicalExpr::Column(column) if column.is_outer_ref)
+        {
+            pushdown = false;
+            return Ok(TreeNodeRecursion::Stop);
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })
+    .expect("[can_pushdown] failed to apply");
+
+    pushdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::file::parquet::read_parquet;
+    use crate::datasource::memory::MemoryTable;
+    use crate::logical::expr::{column, literal, Column};
+    use crate::logical::LogicalPlanBuilder;
+    use crate::provider::table::TableProvider;
+    use crate::utils;
+    use arrow::array::RecordBatch;
+    use arrow::datatypes::SchemaRef;
+    use std::sync::Arc;
+
+    fn assert_after_optimizer(plan: LogicalPlan, expected: Vec<&str>) {
+        let plan = PushdownFilterTableScan.optimize(plan).unwrap();
+        let actual = utils::format(&plan, 0);
+        let actual = actual.trim().lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            expected, actual,
+            "\n\nexpected:\n\n{expected:#?}\nactual:\n\n{actual:#?}\n\n"
+        );
+    }
+
+    #[test]
+    fn test_pushdown_filter_table_scan() {
+        let source = read_parquet("tests/testdata/file/case2.parquet").unwrap();
+        let scan = LogicalPlanBuilder::scan("t", source.clone(), None).unwrap().build();
+        let market = LogicalExpr::BinaryExpr(BinaryExpr::new(column("market"), Operator::Eq, literal("SZ")));
+        let outer_ref = LogicalExpr::BinaryExpr(BinaryExpr::new(
+            column("currency"),
+            Operator::Eq,
+            LogicalExpr::Column(Column::new("currency", Some("o"), true)),
+        ));
+        let filter = LogicalExpr::BinaryExpr(BinaryExpr::new(market, Operator::And, outer_ref));
+
+        let plan = LogicalPlanBuilder::filter(scan, filter.clone()).unwrap();
+        assert_after_optimizer(
+            plan.clone(),
+            vec![
+                "Filter: market = Utf8('SZ') AND currency = o.currency",
+                "  TableScan: t, filter=[market = Utf8('SZ')]",
+            ],
+        );
+        // the rule can be applied again
+        let plan = PushdownFilterTableScan.optimize(plan).unwrap();
+        assert_after_optimizer(
+            plan,
+            vec![
+                "Filter: market = Utf8('SZ') AND currency = o.currency",
+                "  TableScan: t, filter=[market = Utf8('SZ')]",
+            ],
+        );
+
+        // the filter is not pushed to a data source that does not support it
+        let source = Arc::new(MemoryTable::try_new(source.schema(), vec![]).unwrap());
+        let scan = LogicalPlanBuilder::scan("t", source, None).unwrap().build();
+        assert_after_optimizer(
+            LogicalPlanBuilder::filter(scan, filter).unwrap(),
+            vec![
+                "Filter: market = Utf8('SZ') AND currency = o.currency",
+                "  TableScan: t",
+            ],
+        );
+    }
+
+    /// a data source evaluating the filters of the column `market` exactly
+    #[derive(Debug)]
+    struct MarketTable(MemoryTable);
+
+    impl TableProvider for MarketTable {
+        fn schema(&self) -> SchemaRef {
+            self.0.schema()
+        }
+
+        fn scan(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>> {
+            self.0.scan(projection, filters)
+        }
+
+        fn supports_filter_pushdown(&self, filter: &LogicalExpr) -> FilterPushdown {
+            match filter {
+                LogicalExpr::BinaryExpr(BinaryExpr { left, .. }) if **left == column("market") => FilterPushdown::Exact,
+                _ => FilterPushdown::Unsupported,
+            }
+        }
+    }
+
+    #[test]
+    fn test_pushdown_exact_filter() {
+        let schema = read_parquet("tests/testdata/file/case2.parquet").unwrap().schema();
+        let source = Arc::new(MarketTable(MemoryTable::try_new(schema, vec![]).unwrap()));
+        let scan = LogicalPlanBuilder::scan("t", source, None).unwrap().build();
+        let market = LogicalExpr::BinaryExpr(BinaryExpr::new(column("market"), Operator::Eq, literal("SZ")));
+        let currency = LogicalExpr::BinaryExpr(BinaryExpr::new(column("currency"), Operator::Eq, literal("CNY")));
+
+        let filter = LogicalExpr::BinaryExpr(BinaryExpr::new(market.clone(), Operator::And, currency));
+        assert_after_optimizer(
+            LogicalPlanBuilder::filter(scan.clone(), filter).unwrap(),
+            vec![
+                "Filter: currency = Utf8('CNY')",
+                "  TableScan: t, filter=[market = Utf8('SZ')]",
+            ],
+        );
+        // the filter is removed when all its conjuncts are exact
+        assert_after_optimizer(
+            LogicalPlanBuilder::filter(scan, market).unwrap(),
+            vec!["TableScan: t, filter=[market = Utf8('SZ')]"],
+        );
+    }
+}
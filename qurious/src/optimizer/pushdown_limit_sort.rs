@@ -0,0 +1,135 @@
+use super::OptimizerRule;
+use crate::common::transformed::{TransformNode, Transformed, TransformedResult};
+use crate::error::Result;
+use crate::logical::plan::{Limit, LogicalPlan, Projection, Sort};
+
+/// Looks like this:
+/// ```text
+/// Limit: fetch=10, skip=5
+///   Projection: (t.a)
+///     Sort: t.b DESC
+/// ```
+///
+/// After the rule is applied, the plan will look like this:
+/// ```text
+/// Limit: fetch=10, skip=5
+///   Projection: (t.a)
+///     Sort: t.b DESC, fetch=15
+/// ```
+///
+/// The sort keeps only the rows the limit skips or returns, so it is executed as a top-k sort. A projection between
+/// the limit and the sort keeps the number and the order of the rows, the fetch is pushed through it.
+#[derive(Debug, Default, Clone)]
+pub struct PushdownLimitSort;
+
+impl OptimizerRule for PushdownLimitSort {
+    fn name(&self) -> &str {
+        "pushdown_limit_sort"
+    }
+
+    fn optimize(&self, plan: LogicalPlan) -> Result<LogicalPlan> {
+        plan.transform(|plan| match plan {
+            LogicalPlan::Limit(Limit {
+                input,
+                fetch: Some(fetch),
+                skip,
+            }) => {
+                let rows = fetch.saturating_add(skip);
+                let (input, transformed) = match *input {
+                    LogicalPlan::Projection(Projection { schema, input, exprs }) => {
+                        let (input, transformed) = sort_with_fetch(*input, rows);
+                        let projection = LogicalPlan::Projection(Projection {
+                            schema,
+                            input: Box::new(input),
+                            exprs,
+                        });
+                        (projection, transformed)
+                    }
+                    input => sort_with_fetch(input, rows),
+                };
+
+                Ok(Transformed {
+                    data: LogicalPlan::Limit(Limit {
+                        input: Box::new(input),
+                        fetch: Some(fetch),
+                        skip,
+                    }),
+                    transformed,
+                })
+            }
+            _ => Ok(Transformed::no(plan)),
+        })
+        .data()
+    }
+}
+
+/// the sort keeping at most its first `fetch` rows and whether it is changed, any other plan is unchanged
+fn sort_with_fetch(plan: LogicalPlan, fetch: usize) -> (LogicalPlan, bool) {
+    match plan {
+        LogicalPlan::Sort(sort) if !matches!(sort.fetch, Some(rows) if rows <= fetch) => (
+            LogicalPlan::Sort(Sort {
+                fetch: Some(fetch),
+                ..sort
+            }),
+            true,
+        ),
+        plan => (plan, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        optimizer::{pushdown_limit_sort::PushdownLimitSort, OptimizerRule},
+        test_utils::sql_to_plan,
+        utils,
+    };
+
+    fn assert_after_optimizer(sql: &str, expected: Vec<&str>) {
+        let plan = sql_to_plan(sql);
+        let plan = PushdownLimitSort.optimize(plan).unwrap();
+        let actual = utils::format(&plan, 0);
+        let actual = actual.trim().lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            expected, actual,
+            "\n\nexpected:\n\n{expected:#?}\nactual:\n\n{actual:#?}\n\n"
+        );
+    }
+
+    #[test]
+    fn test_pushdown_limit_sort() {
+        assert_after_optimizer(
+            "SELECT name FROM users ORDER BY name LIMIT 3",
+            vec![
+                "Limit: fetch=3, skip=0",
+                "  Sort: users.name ASC, fetch=3",
+                "    Projection: (users.name)",
+                "      TableScan: users",
+            ],
+        );
+
+        // the skipped rows are kept by the sort, the fetch is pushed through the projection of the sorted columns
+        assert_after_optimizer(
+            "SELECT name FROM users ORDER BY id DESC LIMIT 3 OFFSET 2",
+            vec![
+                "Limit: fetch=3, skip=2",
+                "  Projection: (users.name)",
+                "    Sort: users.id DESC, fetch=5",
+                "      Projection: (users.name, users.id)",
+                "        TableScan: users",
+            ],
+        );
+
+        // a limit without a fetch keeps all the sorted rows
+        assert_after_optimizer(
+            "SELECT name FROM users ORDER BY name OFFSET 2",
+            vec![
+                "Limit: fetch=None, skip=2",
+                "  Sort: users.name ASC",
+                "    Projection: (users.name)",
+                "      TableScan: users",
+            ],
+        );
+    }
+}
@@ -1,15 +1,14 @@
 use std::sync::Arc;
 
-use arrow::datatypes::{DataType, Schema};
+use arrow::datatypes::{DataType, FieldRef, Schema};
 
 use super::OptimizerRule;
 use crate::common::transformed::{TransformNode, Transformed, TransformedResult};
 use crate::error::Result;
 use crate::logical::expr::alias::Alias;
-use crate::logical::expr::{AggregateExpr, BinaryExpr, LogicalExpr};
+use crate::logical::expr::{AggregateExpr, BinaryExpr, CaseExpr, InList, InSubQuery, LogicalExpr, SubQuery};
 use crate::logical::plan::LogicalPlan;
-use crate::utils::merge_schema;
-use crate::utils::type_coercion::get_input_types;
+use crate::utils::type_coercion::{get_common_type, get_input_types};
 
 pub struct TypeCoercion;
 
@@ -24,12 +23,18 @@ impl OptimizerRule for TypeCoercion {
                 if matches!(plan, LogicalPlan::TableScan(_)) {
                     return Ok(Transformed::no(plan));
                 }
-                let mut merged_schema = Arc::new(Schema::empty());
-                let schema = plan.schema();
-
-                for input in plan.children().into_iter().flat_map(|x| x) {
-                    merged_schema = merge_schema(&schema, &input.schema()).map(Arc::new)?;
+                // the expressions are evaluated on the inputs, a column of the output, e.g. a cast aliased with the
+                // name of an input column, may have another type than the input column
+                let mut fields: Vec<FieldRef> = vec![];
+                let inputs = plan.children().into_iter().flatten().map(|input| input.schema());
+                for schema in inputs.chain([plan.schema()]) {
+                    for field in schema.fields() {
+                        if fields.iter().all(|f| f.name() != field.name()) {
+                            fields.push(field.clone());
+                        }
+                    }
                 }
+                let merged_schema = Arc::new(Schema::new(fields));
 
                 plan.map_exprs(|expr| type_coercion(&merged_schema, expr))
             })
@@ -47,21 +52,141 @@ fn type_coercion(schema: &Arc<Schema>, expr: LogicalExpr) -> Result<Transformed<
                 .map(LogicalExpr::BinaryExpr)
                 .map(Transformed::yes)
         }
-        LogicalExpr::AggregateExpr(AggregateExpr { op, expr }) => type_coercion(schema, *expr)?.map_data(|expr| {
-            Ok(LogicalExpr::AggregateExpr(AggregateExpr {
+        LogicalExpr::AggregateExpr(AggregateExpr {
+            op,
+            expr,
+            args,
+            distinct,
+            filter,
+        }) => {
+            let expr = type_coercion(schema, *expr).data().map(Box::new)?;
+            let args = args
+                .into_iter()
+                .map(|arg| type_coercion(schema, arg).data())
+                .collect::<Result<Vec<_>>>()?;
+            let filter = filter
+                .map(|filter| type_coercion(schema, *filter).data().map(Box::new))
+                .transpose()?;
+            Ok(Transformed::yes(LogicalExpr::AggregateExpr(AggregateExpr {
                 op,
-                expr: Box::new(expr),
-            }))
-        }),
+                expr,
+                args,
+                distinct,
+                filter,
+            })))
+        }
         LogicalExpr::Alias(Alias { expr, name }) => {
             let expr = type_coercion(schema, *expr).data().map(Box::new)?;
             Ok(Transformed::yes(LogicalExpr::Alias(Alias { expr, name })))
         }
+        LogicalExpr::SubQuery(SubQuery {
+            subquery,
+            outer_ref_columns,
+        }) => TypeCoercion.optimize(*subquery).map(|subquery| {
+            Transformed::yes(LogicalExpr::SubQuery(SubQuery {
+                subquery: Box::new(subquery),
+                outer_ref_columns,
+            }))
+        }),
+        LogicalExpr::InList(InList { expr, list, negated }) => {
+            let expr = type_coercion(schema, *expr).data()?;
+            let list = list
+                .into_iter()
+                .map(|item| type_coercion(schema, item).data())
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Transformed::yes(LogicalExpr::InList(InList::new(expr, list, negated))))
+        }
+        LogicalExpr::InSubQuery(InSubQuery {
+            expr,
+            subquery,
+            negated,
+        }) => {
+            let expr = type_coercion(schema, *expr).data()?;
+            let subquery = SubQuery {
+                subquery: TypeCoercion.optimize(*subquery.subquery).map(Box::new)?,
+                outer_ref_columns: subquery.outer_ref_columns,
+            };
+            Ok(Transformed::yes(LogicalExpr::InSubQuery(InSubQuery::new(
+                expr, subquery, negated,
+            ))))
+        }
+        LogicalExpr::Case(CaseExpr {
+            operand,
+            when_then,
+            else_expr,
+        }) => {
+            let operand = operand.map(|expr| type_coercion(schema, *expr).data()).transpose()?;
+            let when_then = when_then
+                .into_iter()
+                .map(|(when, then)| Ok((type_coercion(schema, when).data()?, type_coercion(schema, then).data()?)))
+                .collect::<Result<Vec<_>>>()?;
+            let else_expr = else_expr.map(|expr| type_coercion(schema, *expr).data()).transpose()?;
+
+            coerce_case(schema, CaseExpr::new(operand, when_then, else_expr))
+                .map(LogicalExpr::Case)
+                .map(Transformed::yes)
+        }
         _ => Ok(Transformed::no(expr)),
     }
 }
 
+/// cast the results to their common type, and the operand and the values compared with it to their common type
+fn coerce_case(schema: &Arc<Schema>, case: CaseExpr) -> Result<CaseExpr> {
+    // the type of an outer reference is not known inside the subquery
+    if LogicalExpr::Case(case.clone())
+        .column_refs()
+        .iter()
+        .any(|c| c.is_outer_ref)
+    {
+        return Ok(case);
+    }
+
+    let result_type = case.data_type(schema)?;
+    let operand_type = match &case.operand {
+        Some(operand) => case
+            .when_then
+            .iter()
+            .try_fold(operand.data_type(schema)?, |data_type, (when, _)| {
+                get_common_type(&data_type, &when.data_type(schema)?)
+            })
+            .map(Some)?,
+        None => None,
+    };
+    let coerce = |expr: LogicalExpr, target_type: &DataType| -> Result<LogicalExpr> {
+        let data_type = expr.data_type(schema)?;
+        Ok(*cast_if_needed(Box::new(expr), &data_type, target_type))
+    };
+
+    let (operand, when_then) = match operand_type {
+        Some(operand_type) => (
+            case.operand.map(|expr| coerce(*expr, &operand_type)).transpose()?,
+            case.when_then
+                .into_iter()
+                .map(|(when, then)| Ok((coerce(when, &operand_type)?, coerce(then, &result_type)?)))
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        None => (
+            None,
+            case.when_then
+                .into_iter()
+                .map(|(when, then)| Ok((when, coerce(then, &result_type)?)))
+                .collect::<Result<Vec<_>>>()?,
+        ),
+    };
+    let else_expr = case.else_expr.map(|expr| coerce(*expr, &result_type)).transpose()?;
+
+    Ok(CaseExpr::new(operand, when_then, else_expr))
+}
+
 fn coerce_binary_op(schema: &Arc<Schema>, expr: BinaryExpr) -> Result<BinaryExpr> {
+    // the type of an outer reference is not known inside the subquery
+    if [&expr.left, &expr.right]
+        .iter()
+        .any(|e| e.column_refs().iter().any(|c| c.is_outer_ref))
+    {
+        return Ok(expr);
+    }
+
     let left_type = expr.left.data_type(schema)?;
     let right_type = expr.right.data_type(schema)?;
 
@@ -292,6 +417,9 @@ mod tests {
                 None::<TableRelation>,
                 false,
             ))),
+            args: vec![],
+            distinct: false,
+            filter: None,
         });
 
         // Create avg(float32_col)
@@ -302,6 +430,9 @@ mod tests {
                 None::<TableRelation>,
                 false,
             ))),
+            args: vec![],
+            distinct: false,
+            filter: None,
         });
 
         // Create count(double_col)
@@ -312,6 +443,9 @@ mod tests {
                 None::<TableRelation>,
                 false,
             ))),
+            args: vec![],
+            distinct: false,
+            filter: None,
         });
 
         // Create avg * count
@@ -363,6 +497,9 @@ mod tests {
                 None::<TableRelation>,
                 false,
             ))),
+            args: vec![],
+            distinct: false,
+            filter: None,
         });
 
         // Create sum(float32_col)
@@ -373,6 +510,9 @@ mod tests {
                 None::<TableRelation>,
                 false,
             ))),
+            args: vec![],
+            distinct: false,
+            filter: None,
         });
 
         // Create avg + sum
@@ -476,6 +616,9 @@ mod tests {
         let sum_expr = LogicalExpr::AggregateExpr(AggregateExpr {
             op: AggregateOperator::Sum,
             expr: Box::new(add_expr),
+            args: vec![],
+            distinct: false,
+            filter: None,
         });
 
         let plan = LogicalPlan::Projection(Projection {
@@ -494,4 +637,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_coerce_case_expression() -> Result<()> {
+        // CASE int_col WHEN 1.5 THEN int_col ELSE float_col END
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("int_col", DataType::Int32, false),
+            Field::new("float_col", DataType::Float64, false),
+        ]));
+        let int_col = LogicalExpr::Column(Column::new("int_col", None::<TableRelation>, false));
+        let float_col = LogicalExpr::Column(Column::new("float_col", None::<TableRelation>, false));
+        let expr = LogicalExpr::Case(CaseExpr::new(
+            Some(int_col.clone()),
+            vec![(LogicalExpr::Literal(ScalarValue::from(1.5f64)), int_col)],
+            Some(float_col),
+        ));
+        let plan = LogicalPlan::Projection(Projection {
+            exprs: vec![expr],
+            input: Box::new(LogicalPlan::EmptyRelation(EmptyRelation {
+                produce_one_row: true,
+                schema: Arc::new(Schema::empty()),
+            })),
+            schema: Arc::new(TableSchema::new(vec![], schema)),
+        });
+
+        assert_analyzed_plan_eq(
+            plan,
+            "Projection: (CASE CAST(int_col AS Float64) WHEN Float64(1.5) THEN CAST(int_col AS Float64) ELSE float_col END)\n  Empty Relation\n",
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,139 @@
+use std::fmt::Display;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, BinaryArray};
+use arrow::row::{RowConverter, SortField};
+
+use super::hyperloglog::HyperLogLog;
+use super::{Accumulator, AggregateExpr};
+use crate::arrow_err;
+use crate::error::{Error, Result};
+use crate::{datatypes::scalar::ScalarValue, physical::expr::PhysicalExpr};
+
+/// `APPROX_DISTINCT(expr)` the number of distinct values estimated from a HyperLogLog sketch, the memory of a group is
+/// `2^precision` bytes whatever the number of its values
+#[derive(Debug)]
+pub struct ApproxDistinctAggregateExpr {
+    pub expr: Arc<dyn PhysicalExpr>,
+    pub precision: u8,
+}
+
+impl ApproxDistinctAggregateExpr {
+    pub fn new(expr: Arc<dyn PhysicalExpr>, precision: u8) -> Self {
+        Self { expr, precision }
+    }
+}
+
+impl Display for ApproxDistinctAggregateExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "APPROX_DISTINCT({})", self.expr)
+    }
+}
+
+impl AggregateExpr for ApproxDistinctAggregateExpr {
+    fn expression(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expr
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(ApproxDistinctAccumulator {
+            sketch: HyperLogLog::try_new(self.precision)?,
+            converter: None,
+        }))
+    }
+}
+
+/// Adds the hashes of the row format of the values to the sketch, so the values of any type are hashed the same way
+/// in every batch
+struct ApproxDistinctAccumulator {
+    sketch: HyperLogLog,
+    /// converts the values to rows, created for the data type of the first values
+    converter: Option<RowConverter>,
+}
+
+impl Accumulator for ApproxDistinctAccumulator {
+    fn accumluate(&mut self, values: &ArrayRef) -> Result<()> {
+        let converter = match &mut self.converter {
+            Some(converter) => converter,
+            None => self.converter.insert(
+                RowConverter::new(vec![SortField::new(values.data_type().clone())]).map_err(|e| arrow_err!(e))?,
+            ),
+        };
+        let rows = converter
+            .convert_columns(std::slice::from_ref(values))
+            .map_err(|e| arrow_err!(e))?;
+
+        // the nulls are not counted
+        for (i, row) in rows.iter().enumerate() {
+            if values.is_valid(i) {
+                let mut hasher = DefaultHasher::new();
+                row.as_ref().hash(&mut hasher);
+                self.sketch.add(hasher.finish());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Int64(Some(self.sketch.count() as i64)))
+    }
+
+    fn supports_merge(&self) -> bool {
+        true
+    }
+
+    /// the registers of the sketch
+    fn state(&mut self) -> Result<ArrayRef> {
+        Ok(Arc::new(BinaryArray::from_vec(vec![self.sketch.registers()])))
+    }
+
+    fn merge(&mut self, states: &ArrayRef) -> Result<()> {
+        for registers in states.as_binary::<i32>().iter().flatten() {
+            self.sketch
+                .merge(&HyperLogLog::try_from_registers(registers.to_vec())?)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, StringArray};
+
+    use super::ApproxDistinctAggregateExpr;
+    use crate::datatypes::scalar::ScalarValue;
+    use crate::physical::expr::{AggregateExpr, Column};
+
+    #[test]
+    fn test_approx_distinct_accumulator() {
+        let aggregate = ApproxDistinctAggregateExpr::new(Arc::new(Column::new("a", 0)), 14);
+        assert_eq!(aggregate.to_string(), "APPROX_DISTINCT(a(0))");
+        assert!(ApproxDistinctAggregateExpr::new(Arc::new(Column::new("a", 0)), 20)
+            .create_accumulator()
+            .is_err());
+
+        let mut accumulator = aggregate.create_accumulator().unwrap();
+        // the values of the previous batches are not counted again
+        let batches: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec![Some("a"), None, Some("b"), Some("a")])),
+            Arc::new(StringArray::from(vec![Some("c"), Some("b"), None])),
+        ];
+        for values in &batches {
+            accumulator.accumluate(values).unwrap();
+        }
+
+        assert_eq!(accumulator.evaluate().unwrap(), ScalarValue::Int64(Some(3)));
+
+        // the sketches of other values are merged, the values of both are counted once
+        let mut other = aggregate.create_accumulator().unwrap();
+        other
+            .accumluate(&(Arc::new(StringArray::from(vec!["c", "d"])) as ArrayRef))
+            .unwrap();
+        accumulator.merge(&other.state().unwrap()).unwrap();
+        assert_eq!(accumulator.evaluate().unwrap(), ScalarValue::Int64(Some(4)));
+    }
+}
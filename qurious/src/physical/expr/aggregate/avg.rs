@@ -28,6 +28,12 @@ impl AvgAggregateExpr {
     }
 }
 
+impl std::fmt::Display for AvgAggregateExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AVG({})", self.expr)
+    }
+}
+
 impl AggregateExpr for AvgAggregateExpr {
     fn expression(&self) -> &Arc<dyn PhysicalExpr> {
         &self.expr
@@ -67,7 +73,9 @@ struct AvgAccumulator {
 
 impl Accumulator for AvgAccumulator {
     fn accumluate(&mut self, value: &ArrayRef) -> Result<()> {
-        accumluate::<Float64Type>(&mut self.sum, &mut self.count, value)
+        // integer inputs are averaged as floats
+        let value = compute::cast(value, &DataType::Float64)?;
+        accumluate::<Float64Type>(&mut self.sum, &mut self.count, &value)
     }
 
     fn evaluate(&mut self) -> Result<ScalarValue> {
@@ -3,7 +3,9 @@ use std::{fmt::Display, sync::Arc};
 use super::{Accumulator, AggregateExpr};
 use crate::error::Result;
 use crate::{datatypes::scalar::ScalarValue, physical::expr::PhysicalExpr};
-use arrow::array::ArrayRef;
+use arrow::array::{ArrayRef, AsArray, Int64Array};
+use arrow::compute;
+use arrow::datatypes::Int64Type;
 
 #[derive(Debug)]
 pub struct CountAggregateExpr {
@@ -46,4 +48,17 @@ impl Accumulator for CountAccumulator {
     fn evaluate(&mut self) -> Result<ScalarValue> {
         Ok(ScalarValue::Int64(Some(self.count)))
     }
+
+    fn supports_merge(&self) -> bool {
+        true
+    }
+
+    fn state(&mut self) -> Result<ArrayRef> {
+        Ok(Arc::new(Int64Array::from(vec![self.count])))
+    }
+
+    fn merge(&mut self, states: &ArrayRef) -> Result<()> {
+        self.count += compute::sum(states.as_primitive::<Int64Type>()).unwrap_or_default();
+        Ok(())
+    }
 }
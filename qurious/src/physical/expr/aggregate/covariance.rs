@@ -0,0 +1,179 @@
+use std::{fmt::Display, sync::Arc};
+
+use arrow::array::{ArrayRef, AsArray, RecordBatch, StructArray};
+use arrow::compute;
+use arrow::datatypes::{DataType, Field, Float64Type};
+
+use super::{Accumulator, AggregateExpr};
+use crate::arrow_err;
+use crate::error::{Error, Result};
+use crate::{datatypes::scalar::ScalarValue, physical::expr::PhysicalExpr};
+
+/// `COVAR_SAMP(y, x)` the sample covariance of the pairs, `COVAR_POP(y, x)` the population covariance
+#[derive(Debug)]
+pub struct CovarianceAggregateExpr {
+    pub expr: Arc<dyn PhysicalExpr>,
+    pub population: bool,
+}
+
+impl CovarianceAggregateExpr {
+    pub fn new(y: Arc<dyn PhysicalExpr>, x: Arc<dyn PhysicalExpr>, population: bool) -> Self {
+        Self {
+            expr: Arc::new(Pairs { y, x }),
+            population,
+        }
+    }
+}
+
+impl Display for CovarianceAggregateExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = if self.population { "COVAR_POP" } else { "COVAR_SAMP" };
+        write!(f, "{}({})", name, self.expr)
+    }
+}
+
+impl AggregateExpr for CovarianceAggregateExpr {
+    fn expression(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expr
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(CovarianceAccumulator::new(Statistic::Covariance {
+            population: self.population,
+        })))
+    }
+}
+
+/// `CORR(y, x)` the Pearson correlation coefficient of the pairs
+#[derive(Debug)]
+pub struct CorrelationAggregateExpr {
+    pub expr: Arc<dyn PhysicalExpr>,
+}
+
+impl CorrelationAggregateExpr {
+    pub fn new(y: Arc<dyn PhysicalExpr>, x: Arc<dyn PhysicalExpr>) -> Self {
+        Self {
+            expr: Arc::new(Pairs { y, x }),
+        }
+    }
+}
+
+impl Display for CorrelationAggregateExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CORR({})", self.expr)
+    }
+}
+
+impl AggregateExpr for CorrelationAggregateExpr {
+    fn expression(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expr
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(CovarianceAccumulator::new(Statistic::Correlation)))
+    }
+}
+
+/// the two arguments of the aggregate evaluated to a struct of their values as floats, so the pairs are passed to the
+/// accumulator as a single array
+#[derive(Debug)]
+struct Pairs {
+    y: Arc<dyn PhysicalExpr>,
+    x: Arc<dyn PhysicalExpr>,
+}
+
+impl Display for Pairs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, {}", self.y, self.x)
+    }
+}
+
+impl PhysicalExpr for Pairs {
+    fn evaluate(&self, input: &RecordBatch) -> Result<ArrayRef> {
+        let y = compute::cast(&self.y.evaluate(input)?, &DataType::Float64)?;
+        let x = compute::cast(&self.x.evaluate(input)?, &DataType::Float64)?;
+        let fields = vec![
+            Field::new("y", DataType::Float64, true),
+            Field::new("x", DataType::Float64, true),
+        ];
+
+        StructArray::try_new(fields.into(), vec![y, x], None)
+            .map(|array| Arc::new(array) as ArrayRef)
+            .map_err(|e| arrow_err!(e))
+    }
+}
+
+#[derive(Debug)]
+enum Statistic {
+    Covariance { population: bool },
+    Correlation,
+}
+
+/// The co-moment of the pairs and the squared differences of each side computed with Welford's algorithm, the means
+/// are updated with each pair so that the differences are taken from the running means.
+#[derive(Debug)]
+struct CovarianceAccumulator {
+    statistic: Statistic,
+    count: u64,
+    mean_y: f64,
+    mean_x: f64,
+    /// the sum of the products of the differences of y and x from their means
+    co_moment: f64,
+    m2_y: f64,
+    m2_x: f64,
+}
+
+impl CovarianceAccumulator {
+    fn new(statistic: Statistic) -> Self {
+        Self {
+            statistic,
+            count: 0,
+            mean_y: 0.0,
+            mean_x: 0.0,
+            co_moment: 0.0,
+            m2_y: 0.0,
+            m2_x: 0.0,
+        }
+    }
+}
+
+impl Accumulator for CovarianceAccumulator {
+    fn accumluate(&mut self, value: &ArrayRef) -> Result<()> {
+        let pairs = value.as_struct();
+        let y = pairs.column(0).as_primitive::<Float64Type>();
+        let x = pairs.column(1).as_primitive::<Float64Type>();
+
+        // the pairs with a null are ignored
+        for (y, x) in y.iter().zip(x.iter()) {
+            let (Some(y), Some(x)) = (y, x) else {
+                continue;
+            };
+            self.count += 1;
+            let n = self.count as f64;
+            let delta_y = y - self.mean_y;
+            let delta_x = x - self.mean_x;
+            self.mean_y += delta_y / n;
+            self.mean_x += delta_x / n;
+            self.co_moment += delta_x * (y - self.mean_y);
+            self.m2_y += delta_y * (y - self.mean_y);
+            self.m2_x += delta_x * (x - self.mean_x);
+        }
+
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let n = self.count as f64;
+        let value = match self.statistic {
+            Statistic::Covariance { population: true } if self.count > 0 => Some(self.co_moment / n),
+            Statistic::Covariance { population: false } if self.count > 1 => Some(self.co_moment / (n - 1.0)),
+            // the correlation is undefined when the values of a side are all equal
+            Statistic::Correlation if self.count > 1 && self.m2_y != 0.0 && self.m2_x != 0.0 => {
+                Some(self.co_moment / (self.m2_y * self.m2_x).sqrt())
+            }
+            _ => None,
+        };
+
+        Ok(ScalarValue::Float64(value))
+    }
+}
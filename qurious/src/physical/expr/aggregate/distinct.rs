@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BooleanArray};
+use arrow::compute::filter;
+use arrow::row::{OwnedRow, RowConverter, SortField};
+
+use super::{Accumulator, AggregateExpr};
+use crate::arrow_err;
+use crate::error::{Error, Result};
+use crate::{datatypes::scalar::ScalarValue, physical::expr::PhysicalExpr};
+
+/// `op(DISTINCT expr)`, each distinct value of a group is aggregated once by the accumulator of the aggregate
+#[derive(Debug)]
+pub struct DistinctAggregateExpr {
+    aggregate: Arc<dyn AggregateExpr>,
+}
+
+impl DistinctAggregateExpr {
+    pub fn new(aggregate: Arc<dyn AggregateExpr>) -> Self {
+        Self { aggregate }
+    }
+}
+
+impl Display for DistinctAggregateExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let aggregate = self.aggregate.to_string();
+        match aggregate.split_once('(') {
+            Some((name, args)) => write!(f, "{}(DISTINCT {}", name, args),
+            None => write!(f, "{}", aggregate),
+        }
+    }
+}
+
+impl AggregateExpr for DistinctAggregateExpr {
+    fn expression(&self) -> &Arc<dyn PhysicalExpr> {
+        self.aggregate.expression()
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(DistinctAccumulator {
+            accumulator: self.aggregate.create_accumulator()?,
+            converter: None,
+            seen: HashSet::new(),
+        }))
+    }
+}
+
+/// Keeps the values of a group in a hash set, the accumulator of the aggregate is only updated with the values not
+/// in the set yet
+pub struct DistinctAccumulator {
+    accumulator: Box<dyn Accumulator>,
+    /// converts the values to rows, created for the data type of the first values
+    converter: Option<RowConverter>,
+    /// the values aggregated so far
+    seen: HashSet<OwnedRow>,
+}
+
+impl Accumulator for DistinctAccumulator {
+    fn accumluate(&mut self, values: &ArrayRef) -> Result<()> {
+        let converter = match &mut self.converter {
+            Some(converter) => converter,
+            None => self.converter.insert(
+                RowConverter::new(vec![SortField::new(values.data_type().clone())]).map_err(|e| arrow_err!(e))?,
+            ),
+        };
+        let rows = converter
+            .convert_columns(std::slice::from_ref(values))
+            .map_err(|e| arrow_err!(e))?;
+
+        // the nulls are ignored like by the aggregates, a value repeated in the batch is only new the first time
+        let new = (0..values.len())
+            .map(|i| values.is_valid(i) && self.seen.insert(rows.row(i).owned()))
+            .collect::<Vec<_>>();
+        let new = BooleanArray::from(new);
+        if new.true_count() == 0 {
+            return Ok(());
+        }
+
+        let values = filter(values, &new).map_err(|e| arrow_err!(e))?;
+        self.accumulator.accumluate(&values)
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        self.accumulator.evaluate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, Int64Array};
+    use arrow::datatypes::DataType;
+
+    use super::DistinctAggregateExpr;
+    use crate::datatypes::scalar::ScalarValue;
+    use crate::physical::expr::{AggregateExpr, Column, CountAggregateExpr, MaxAggregateExpr, SumAggregateExpr};
+
+    #[test]
+    fn test_distinct_accumulator() {
+        let count = DistinctAggregateExpr::new(Arc::new(CountAggregateExpr::new(Arc::new(Column::new("a", 0)))));
+        let sum = DistinctAggregateExpr::new(Arc::new(SumAggregateExpr::new(
+            Arc::new(Column::new("a", 0)),
+            DataType::Int64,
+        )));
+        assert_eq!(count.to_string(), "COUNT(DISTINCT a(0))");
+
+        let mut count = count.create_accumulator().unwrap();
+        let mut sum = sum.create_accumulator().unwrap();
+        // the values already seen in a previous batch are not aggregated again
+        let batches: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from(vec![Some(1), Some(2), None, Some(2)])),
+            Arc::new(Int64Array::from(vec![Some(3), Some(1), None])),
+        ];
+        for values in &batches {
+            count.accumluate(values).unwrap();
+            sum.accumluate(values).unwrap();
+        }
+
+        assert_eq!(count.evaluate().unwrap(), ScalarValue::Int64(Some(3)));
+        assert_eq!(sum.evaluate().unwrap(), ScalarValue::Int64(Some(6)));
+
+        // the maximum of only nulls is a null of the type of the values
+        let max = DistinctAggregateExpr::new(Arc::new(MaxAggregateExpr::new(
+            Arc::new(Column::new("a", 0)),
+            DataType::Int64,
+        )));
+        let mut max = max.create_accumulator().unwrap();
+        max.accumluate(&(Arc::new(Int64Array::from(vec![None, None])) as ArrayRef))
+            .unwrap();
+        assert_eq!(max.evaluate().unwrap(), ScalarValue::Int64(None));
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt::Display;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, RecordBatch, StructArray};
+use arrow::compute;
+use arrow::datatypes::{DataType, Field};
+
+use super::{Accumulator, AggregateExpr};
+use crate::arrow_err;
+use crate::error::{Error, Result};
+use crate::{datatypes::scalar::ScalarValue, physical::expr::PhysicalExpr};
+
+/// `op(expr) FILTER (WHERE filter)`, the accumulator of the aggregate is only updated with the values of the rows the
+/// filter is true for
+#[derive(Debug)]
+pub struct FilteredAggregateExpr {
+    aggregate: Arc<dyn AggregateExpr>,
+    expr: Arc<dyn PhysicalExpr>,
+    return_type: DataType,
+}
+
+impl FilteredAggregateExpr {
+    pub fn new(aggregate: Arc<dyn AggregateExpr>, filter: Arc<dyn PhysicalExpr>, return_type: DataType) -> Self {
+        Self {
+            expr: Arc::new(FilteredValues {
+                values: aggregate.expression().clone(),
+                filter,
+            }),
+            aggregate,
+            return_type,
+        }
+    }
+}
+
+impl Display for FilteredAggregateExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.aggregate, self.expr)
+    }
+}
+
+impl AggregateExpr for FilteredAggregateExpr {
+    fn expression(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expr
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(FilteredAccumulator {
+            accumulator: self.aggregate.create_accumulator()?,
+            return_type: self.return_type.clone(),
+        }))
+    }
+}
+
+/// the values of the aggregate and the filter evaluated to a struct of both, so the filter of each value is passed to
+/// the accumulator with the values
+#[derive(Debug)]
+struct FilteredValues {
+    values: Arc<dyn PhysicalExpr>,
+    filter: Arc<dyn PhysicalExpr>,
+}
+
+impl Display for FilteredValues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, " FILTER (WHERE {})", self.filter)
+    }
+}
+
+impl PhysicalExpr for FilteredValues {
+    fn evaluate(&self, input: &RecordBatch) -> Result<ArrayRef> {
+        let values = self.values.evaluate(input)?;
+        let filter = compute::cast(&self.filter.evaluate(input)?, &DataType::Boolean)?;
+        let fields = vec![
+            Field::new("values", values.data_type().clone(), true),
+            Field::new("filter", DataType::Boolean, true),
+        ];
+
+        StructArray::try_new(fields.into(), vec![values, filter], None)
+            .map(|array| Arc::new(array) as ArrayRef)
+            .map_err(|e| arrow_err!(e))
+    }
+}
+
+struct FilteredAccumulator {
+    accumulator: Box<dyn Accumulator>,
+    return_type: DataType,
+}
+
+impl Accumulator for FilteredAccumulator {
+    fn accumluate(&mut self, value: &ArrayRef) -> Result<()> {
+        let value = value.as_struct();
+        // the rows the filter is false or null for are not aggregated
+        let values = compute::filter(value.column(0), value.column(1).as_boolean())?;
+        if values.is_empty() {
+            return Ok(());
+        }
+        self.accumulator.accumluate(&values)
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        // the accumulator of a group whose rows are all filtered out has not aggregated any value
+        match self.accumulator.evaluate()? {
+            ScalarValue::Null => ScalarValue::try_from(&self.return_type),
+            value => Ok(value),
+        }
+    }
+
+    fn supports_merge(&self) -> bool {
+        self.accumulator.supports_merge()
+    }
+
+    fn state(&mut self) -> Result<ArrayRef> {
+        self.accumulator.state()
+    }
+
+    /// the states only aggregate the rows the filter is true for
+    fn merge(&mut self, states: &ArrayRef) -> Result<()> {
+        self.accumulator.merge(states)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use super::FilteredAggregateExpr;
+    use crate::datatypes::operator::Operator;
+    use crate::datatypes::scalar::ScalarValue;
+    use crate::physical::expr::{
+        AggregateExpr, BinaryExpr, Column, CountAggregateExpr, DistinctAggregateExpr, Literal, SumAggregateExpr,
+    };
+
+    #[test]
+    fn test_filtered_accumulator() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+        let a = || Arc::new(Column::new("a", 0));
+        // a > value
+        let filter = |value: i64| {
+            Arc::new(BinaryExpr::new(
+                a(),
+                Operator::Gt,
+                Arc::new(Literal::new(ScalarValue::Int64(Some(value)))),
+            ))
+        };
+        let sum = |value: i64| {
+            FilteredAggregateExpr::new(
+                Arc::new(SumAggregateExpr::new(a(), DataType::Int64)),
+                filter(value),
+                DataType::Int64,
+            )
+        };
+        let count = FilteredAggregateExpr::new(
+            Arc::new(DistinctAggregateExpr::new(Arc::new(CountAggregateExpr::new(a())))),
+            filter(1),
+            DataType::Int64,
+        );
+        assert_eq!(count.to_string(), "COUNT(DISTINCT a(0)) FILTER (WHERE a(0) > Int64(1))");
+
+        let (sum, empty_sum) = (sum(1), sum(5));
+        let mut sum_accumulator = sum.create_accumulator().unwrap();
+        let mut empty_sum_accumulator = empty_sum.create_accumulator().unwrap();
+        let mut count_accumulator = count.create_accumulator().unwrap();
+        let batches = vec![
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int64Array::from(vec![Some(1), Some(2), None]))],
+            )
+            .unwrap(),
+            RecordBatch::try_new(
+                schema,
+                vec![Arc::new(Int64Array::from(vec![Some(3), Some(2), Some(0)]))],
+            )
+            .unwrap(),
+        ];
+        for batch in &batches {
+            sum_accumulator
+                .accumluate(&sum.expression().evaluate(batch).unwrap())
+                .unwrap();
+            empty_sum_accumulator
+                .accumluate(&empty_sum.expression().evaluate(batch).unwrap())
+                .unwrap();
+            count_accumulator
+                .accumluate(&count.expression().evaluate(batch).unwrap())
+                .unwrap();
+        }
+
+        assert_eq!(sum_accumulator.evaluate().unwrap(), ScalarValue::Int64(Some(7)));
+        assert_eq!(count_accumulator.evaluate().unwrap(), ScalarValue::Int64(Some(2)));
+        // no row is aggregated, the sum is a null of its type
+        assert_eq!(empty_sum_accumulator.evaluate().unwrap(), ScalarValue::Int64(None));
+    }
+}
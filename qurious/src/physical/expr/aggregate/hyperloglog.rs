@@ -0,0 +1,157 @@
+use crate::error::{Error, Result};
+use crate::internal_err;
+
+/// A HyperLogLog sketch estimates the number of distinct values from their hashes. The first `precision` bits of a
+/// hash select one of the `2^precision` registers, which keeps the largest number of leading zeros plus one of the
+/// remaining bits of its hashes. A value added again does not change the registers, so the estimate only depends on
+/// the distinct values.
+///
+/// The standard error of the estimate is about `1.04 / sqrt(2^precision)`, 0.8% for a precision of 14 with 16 KiB of
+/// registers. The sketches of the same precision are merged by keeping the largest value of each register, the
+/// merged sketch is the sketch of the values of both.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// the smallest and the largest number of bits of the hashes indexing the registers
+    pub const MIN_PRECISION: u8 = 4;
+    pub const MAX_PRECISION: u8 = 18;
+
+    pub fn try_new(precision: u8) -> Result<Self> {
+        if !(Self::MIN_PRECISION..=Self::MAX_PRECISION).contains(&precision) {
+            return internal_err!(
+                "HyperLogLog precision must be between {} and {}, got {}",
+                Self::MIN_PRECISION,
+                Self::MAX_PRECISION,
+                precision
+            );
+        }
+        Ok(Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        })
+    }
+
+    /// the sketch of the registers of another sketch, the precision is the number of bits of the number of registers
+    pub fn try_from_registers(registers: Vec<u8>) -> Result<Self> {
+        let precision = registers.len().trailing_zeros() as u8;
+        if !registers.len().is_power_of_two() || !(Self::MIN_PRECISION..=Self::MAX_PRECISION).contains(&precision) {
+            return internal_err!("{} is not a number of HyperLogLog registers", registers.len());
+        }
+        Ok(Self { precision, registers })
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    pub fn registers(&self) -> &[u8] {
+        &self.registers
+    }
+
+    /// adds the 64-bit hash of a value
+    pub fn add(&mut self, hash: u64) {
+        let index = (hash >> (64 - self.precision)) as usize;
+        // the bit after the remaining bits bounds the rank when they are all zeros
+        let remaining = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = remaining.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// merges the sketch of other values, e.g. of another partition, into this sketch
+    pub fn merge(&mut self, other: &HyperLogLog) -> Result<()> {
+        if self.precision != other.precision {
+            return internal_err!(
+                "cannot merge HyperLogLog sketches of precision {} and {}",
+                self.precision,
+                other.precision
+            );
+        }
+        for (register, other) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*other);
+        }
+        Ok(())
+    }
+
+    /// The estimated number of distinct values. The harmonic mean of the registers overestimates small numbers of
+    /// values, they are counted from the number of empty registers instead (linear counting).
+    pub fn count(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-(*register as i32)))
+            .sum::<f64>();
+        let estimate = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|register| **register == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            return (m * (m / zeros as f64).ln()).round() as u64;
+        }
+        estimate.round() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    use super::HyperLogLog;
+
+    fn hash(value: u64) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_hyperloglog_count() {
+        assert!(HyperLogLog::try_new(3).is_err());
+
+        let mut sketch = HyperLogLog::try_new(14).unwrap();
+        assert_eq!(sketch.count(), 0);
+        for value in 0..3 {
+            sketch.add(hash(value));
+        }
+        assert_eq!(sketch.count(), 3);
+
+        // the values are added twice, only the distinct values are counted
+        for value in (0..100_000).chain(0..100_000) {
+            sketch.add(hash(value));
+        }
+        let error = (sketch.count() as f64 - 100_000.0).abs() / 100_000.0;
+        assert!(error < 0.03, "estimated {}", sketch.count());
+    }
+
+    #[test]
+    fn test_hyperloglog_merge() {
+        let (mut left, mut right, mut all) = (
+            HyperLogLog::try_new(12).unwrap(),
+            HyperLogLog::try_new(12).unwrap(),
+            HyperLogLog::try_new(12).unwrap(),
+        );
+        for value in 0..6000 {
+            left.add(hash(value));
+            all.add(hash(value));
+        }
+        for value in 4000..10_000 {
+            right.add(hash(value));
+            all.add(hash(value));
+        }
+
+        left.merge(&HyperLogLog::try_from_registers(right.registers().to_vec()).unwrap())
+            .unwrap();
+        assert_eq!(left, all);
+        assert!(HyperLogLog::try_from_registers(vec![0; 100]).is_err());
+        assert!(left.merge(&HyperLogLog::try_new(13).unwrap()).is_err());
+    }
+}
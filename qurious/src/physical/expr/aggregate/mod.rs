@@ -1,28 +1,54 @@
+pub mod approx_distinct;
 pub mod avg;
 pub mod count;
+pub mod covariance;
+pub mod distinct;
+pub mod filter;
+pub mod hyperloglog;
 pub mod max;
 pub mod min;
+pub mod percentile;
 pub mod sum;
+mod tdigest;
+pub mod variance;
 
 use arrow::array::{ArrayRef, ArrowPrimitiveType, AsArray, PrimitiveArray};
 use arrow::datatypes::DataType;
 
 use super::PhysicalExpr;
 use crate::datatypes::scalar::ScalarValue;
-use crate::error::Result;
-use std::fmt::Debug;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use arrow::array::Array;
+use std::fmt::{Debug, Display};
 use std::sync::Arc;
 
-pub trait AggregateExpr: Debug {
+pub trait AggregateExpr: Debug + Display + Send + Sync {
     fn expression(&self) -> &Arc<dyn PhysicalExpr>;
     fn create_accumulator(&self) -> Result<Box<dyn Accumulator>>;
 }
 
-pub trait Accumulator {
+pub trait Accumulator: Send {
     /// Updates the aggregate with the provided value.
     fn accumluate(&mut self, value: &ArrayRef) -> Result<()>;
     /// Returns the final aggregate value.
     fn evaluate(&mut self) -> Result<ScalarValue>;
+
+    /// Whether the aggregate of some rows is the merge of the aggregates of parts of them, e.g. of the partitions of
+    /// the input. The accumulators of the parts give their `state` to the accumulator of the rows to `merge`.
+    fn supports_merge(&self) -> bool {
+        false
+    }
+
+    /// The intermediate state of the aggregate, an array of one row.
+    fn state(&mut self) -> Result<ArrayRef> {
+        internal_err!("The accumulator does not support merging")
+    }
+
+    /// Merges the states of other accumulators of the same aggregate, one row per accumulator.
+    fn merge(&mut self, _states: &ArrayRef) -> Result<()> {
+        internal_err!("The accumulator does not support merging")
+    }
 }
 
 #[derive(Debug)]
@@ -62,6 +88,10 @@ where
     F: Fn(&T::Native, &PrimitiveArray<T>) -> Result<T::Native> + Send + Sync,
 {
     fn accumluate(&mut self, value: &ArrayRef) -> Result<()> {
+        // the aggregate of only nulls is null, not the starting value
+        if value.null_count() == value.len() {
+            return Ok(());
+        }
         let value = value.as_primitive::<T>();
 
         if let Some(v) = &mut self.result {
@@ -79,7 +109,21 @@ where
             return ScalarValue::try_from_array(&array, 0);
         }
 
-        Ok(ScalarValue::Null)
+        ScalarValue::try_from(&self.data_type)
+    }
+
+    fn supports_merge(&self) -> bool {
+        true
+    }
+
+    fn state(&mut self) -> Result<ArrayRef> {
+        let array = PrimitiveArray::<T>::from_iter([self.result]).with_data_type(self.data_type.clone());
+        Ok(Arc::new(array))
+    }
+
+    /// the states of the accumulators without values are null, they do not change the result
+    fn merge(&mut self, states: &ArrayRef) -> Result<()> {
+        self.accumluate(states)
     }
 }
 
@@ -0,0 +1,283 @@
+use std::{fmt::Display, sync::Arc};
+
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::compute::{self, SortOptions};
+use arrow::datatypes::{DataType, Float64Type};
+
+use super::tdigest::TDigest;
+use super::{Accumulator, AggregateExpr};
+use crate::error::Result;
+use crate::{datatypes::scalar::ScalarValue, physical::expr::PhysicalExpr};
+
+/// the compression of the t-digests, the maximum number of centroids is a few times the compression
+const TDIGEST_COMPRESSION: f64 = 100.0;
+
+/// `MEDIAN(expr)`, `PERCENTILE_CONT(fraction) WITHIN GROUP (ORDER BY expr)` and `PERCENTILE_DISC`, the values of a
+/// group are kept and sorted when the group is evaluated
+#[derive(Debug)]
+pub struct PercentileAggregateExpr {
+    pub expr: Arc<dyn PhysicalExpr>,
+    pub fraction: f64,
+    /// the percentile is interpolated between the two nearest values, otherwise it is one of the values
+    pub interpolate: bool,
+    pub descending: bool,
+    pub return_type: DataType,
+    /// displayed as `MEDIAN(expr)`
+    median: bool,
+}
+
+impl PercentileAggregateExpr {
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        fraction: f64,
+        interpolate: bool,
+        descending: bool,
+        return_type: DataType,
+    ) -> Self {
+        Self {
+            expr,
+            fraction,
+            interpolate,
+            descending,
+            return_type,
+            median: false,
+        }
+    }
+
+    pub fn new_median(expr: Arc<dyn PhysicalExpr>) -> Self {
+        Self {
+            median: true,
+            ..Self::new(expr, 0.5, true, false, DataType::Float64)
+        }
+    }
+}
+
+impl Display for PercentileAggregateExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.median {
+            return write!(f, "MEDIAN({})", self.expr);
+        }
+        write!(
+            f,
+            "{}({}) WITHIN GROUP (ORDER BY {}{})",
+            if self.interpolate {
+                "PERCENTILE_CONT"
+            } else {
+                "PERCENTILE_DISC"
+            },
+            self.fraction,
+            self.expr,
+            if self.descending { " DESC" } else { "" }
+        )
+    }
+}
+
+impl AggregateExpr for PercentileAggregateExpr {
+    fn expression(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expr
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(PercentileAccumulator {
+            values: vec![],
+            fraction: self.fraction,
+            interpolate: self.interpolate,
+            descending: self.descending,
+            return_type: self.return_type.clone(),
+        }))
+    }
+}
+
+struct PercentileAccumulator {
+    /// the values of the group without the nulls
+    values: Vec<ArrayRef>,
+    fraction: f64,
+    interpolate: bool,
+    descending: bool,
+    return_type: DataType,
+}
+
+impl Accumulator for PercentileAccumulator {
+    fn accumluate(&mut self, value: &ArrayRef) -> Result<()> {
+        if value.null_count() == value.len() {
+            return Ok(());
+        }
+        // the values are copied so the batches they are sliced from are not kept
+        let valid = compute::is_not_null(value)?;
+        self.values.push(compute::filter(value, &valid)?);
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        if self.values.is_empty() {
+            return ScalarValue::try_from(&self.return_type);
+        }
+        let values = compute::concat(&self.values.iter().map(|v| v.as_ref()).collect::<Vec<_>>())?;
+
+        if self.interpolate {
+            let values = compute::cast(&values, &DataType::Float64)?;
+            let mut values = values.as_primitive::<Float64Type>().values().to_vec();
+            values.sort_by(|a, b| a.total_cmp(b));
+            // the fraction of the descending order is the complement of the fraction of the ascending order
+            let fraction = if self.descending {
+                1.0 - self.fraction
+            } else {
+                self.fraction
+            };
+            let position = fraction * (values.len() - 1) as f64;
+            let (lower, upper) = (values[position.floor() as usize], values[position.ceil() as usize]);
+
+            return Ok(ScalarValue::Float64(Some(
+                lower + (upper - lower) * (position - position.floor()),
+            )));
+        }
+
+        let options = SortOptions {
+            descending: self.descending,
+            nulls_first: false,
+        };
+        let indices = compute::sort_to_indices(&values, Some(options), None)?;
+        // the first value whose cumulative distribution is at least the fraction
+        let position = ((self.fraction * values.len() as f64).ceil() as usize)
+            .saturating_sub(1)
+            .min(values.len() - 1);
+
+        ScalarValue::try_from_array(&values, indices.value(position) as usize)
+    }
+}
+
+/// `APPROX_PERCENTILE(expr, fraction)` the percentile estimated from a t-digest of the values, the memory of a group is
+/// bounded by the compression of the digest instead of the number of its values
+#[derive(Debug)]
+pub struct ApproxPercentileAggregateExpr {
+    pub expr: Arc<dyn PhysicalExpr>,
+    pub fraction: f64,
+}
+
+impl ApproxPercentileAggregateExpr {
+    pub fn new(expr: Arc<dyn PhysicalExpr>, fraction: f64) -> Self {
+        Self { expr, fraction }
+    }
+}
+
+impl Display for ApproxPercentileAggregateExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "APPROX_PERCENTILE({}, {})", self.expr, self.fraction)
+    }
+}
+
+impl AggregateExpr for ApproxPercentileAggregateExpr {
+    fn expression(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expr
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(ApproxPercentileAccumulator {
+            digest: TDigest::new(TDIGEST_COMPRESSION),
+            fraction: self.fraction,
+        }))
+    }
+}
+
+struct ApproxPercentileAccumulator {
+    digest: TDigest,
+    fraction: f64,
+}
+
+impl Accumulator for ApproxPercentileAccumulator {
+    fn accumluate(&mut self, value: &ArrayRef) -> Result<()> {
+        let value = compute::cast(value, &DataType::Float64)?;
+        for v in value.as_primitive::<Float64Type>().iter().flatten() {
+            self.digest.add(v);
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Float64(self.digest.quantile(self.fraction)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, Int32Array};
+    use arrow::datatypes::DataType;
+
+    use super::PercentileAggregateExpr;
+    use crate::datatypes::scalar::ScalarValue;
+    use crate::physical::expr::{AggregateExpr, Column};
+
+    #[test]
+    fn test_percentile_accumulator() {
+        let evaluate = |aggregate: PercentileAggregateExpr| {
+            let mut accumulator = aggregate.create_accumulator().unwrap();
+            let batches: Vec<ArrayRef> = vec![
+                Arc::new(Int32Array::from(vec![Some(4), None, Some(1)])),
+                Arc::new(Int32Array::from(vec![Some(3), Some(2)])),
+            ];
+            for values in &batches {
+                accumulator.accumluate(values).unwrap();
+            }
+            accumulator.evaluate().unwrap()
+        };
+        let column = || Arc::new(Column::new("a", 0));
+
+        assert_eq!(
+            evaluate(PercentileAggregateExpr::new_median(column())),
+            ScalarValue::Float64(Some(2.5))
+        );
+        assert_eq!(
+            evaluate(PercentileAggregateExpr::new(
+                column(),
+                0.25,
+                true,
+                false,
+                DataType::Float64
+            )),
+            ScalarValue::Float64(Some(1.75))
+        );
+        assert_eq!(
+            evaluate(PercentileAggregateExpr::new(
+                column(),
+                0.25,
+                true,
+                true,
+                DataType::Float64
+            )),
+            ScalarValue::Float64(Some(3.25))
+        );
+        // the discrete percentiles are values of the group in its type
+        assert_eq!(
+            evaluate(PercentileAggregateExpr::new(
+                column(),
+                0.5,
+                false,
+                false,
+                DataType::Int32
+            )),
+            ScalarValue::Int32(Some(2))
+        );
+        assert_eq!(
+            evaluate(PercentileAggregateExpr::new(
+                column(),
+                0.5,
+                false,
+                true,
+                DataType::Int32
+            )),
+            ScalarValue::Int32(Some(3))
+        );
+        assert_eq!(
+            evaluate(PercentileAggregateExpr::new(
+                column(),
+                0.0,
+                false,
+                false,
+                DataType::Int32
+            )),
+            ScalarValue::Int32(Some(1))
+        );
+    }
+}
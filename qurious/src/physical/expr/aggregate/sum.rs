@@ -101,4 +101,16 @@ impl<T: ArrowNumericType> Accumulator for SumAccumulator<T> {
             None => ScalarValue::try_from(T::DATA_TYPE),
         }
     }
+    fn supports_merge(&self) -> bool {
+        true
+    }
+
+    fn state(&mut self) -> Result<ArrayRef> {
+        Ok(Arc::new(PrimitiveArray::<T>::from_iter([self.sum])))
+    }
+
+    /// the sum of the sums of the other accumulators
+    fn merge(&mut self, states: &ArrayRef) -> Result<()> {
+        self.accumluate(states)
+    }
 }
@@ -0,0 +1,141 @@
+/// the number of values buffered before they are merged into the centroids
+const BUFFER_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// A t-digest summarizes a distribution of values by clusters of adjacent values, the centroids. The clusters at the
+/// tails are kept small and the clusters near the median may be larger, so the quantiles near the tails are estimated
+/// more accurately. The number of centroids is bounded by the compression whatever the number of values.
+///
+/// The values are buffered and merged into the centroids in sorted order, a centroid absorbs the next value or
+/// centroid while its weight stays under the size limit of its quantile `4 * count * q * (1 - q) / compression`.
+#[derive(Debug, Clone)]
+pub struct TDigest {
+    compression: f64,
+    /// the centroids ordered by their means
+    centroids: Vec<Centroid>,
+    buffer: Vec<f64>,
+    count: f64,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    pub fn new(compression: f64) -> Self {
+        Self {
+            compression,
+            centroids: vec![],
+            buffer: vec![],
+            count: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    pub fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.buffer.push(value);
+        self.count += 1.0;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        if self.buffer.len() >= BUFFER_SIZE {
+            self.compress();
+        }
+    }
+
+    fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let mut centroids = std::mem::take(&mut self.centroids);
+        centroids.extend(self.buffer.drain(..).map(|value| Centroid {
+            mean: value,
+            weight: 1.0,
+        }));
+        centroids.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let mut centroids = centroids.into_iter();
+        let Some(mut current) = centroids.next() else {
+            return;
+        };
+        // the weight of the centroids before the current one
+        let mut cumulative = 0.0;
+        for next in centroids {
+            let weight = current.weight + next.weight;
+            let q = (cumulative + weight / 2.0) / self.count;
+            let limit = 4.0 * self.count * q * (1.0 - q) / self.compression;
+            if weight <= limit {
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                cumulative += current.weight;
+                self.centroids.push(current);
+                current = next;
+            }
+        }
+        self.centroids.push(current);
+    }
+
+    /// The estimated value at the quantile, between 0 and 1, None if there is no value. The value is interpolated
+    /// between the centers of the two centroids around the rank of the quantile, the minimum and the maximum are the
+    /// bounds of the first and the last centroid.
+    pub fn quantile(&mut self, quantile: f64) -> Option<f64> {
+        self.compress();
+        if self.centroids.is_empty() {
+            return None;
+        }
+
+        let rank = quantile.clamp(0.0, 1.0) * self.count;
+        let (mut previous_rank, mut previous_value) = (0.0, self.min);
+        let mut cumulative = 0.0;
+        for centroid in &self.centroids {
+            let center = cumulative + centroid.weight / 2.0;
+            if rank < center {
+                return Some(interpolate(previous_rank, previous_value, center, centroid.mean, rank));
+            }
+            (previous_rank, previous_value) = (center, centroid.mean);
+            cumulative += centroid.weight;
+        }
+
+        Some(interpolate(previous_rank, previous_value, self.count, self.max, rank))
+    }
+}
+
+fn interpolate(from_rank: f64, from: f64, to_rank: f64, to: f64, rank: f64) -> f64 {
+    if to_rank <= from_rank {
+        return to;
+    }
+    from + (to - from) * (rank - from_rank) / (to_rank - from_rank)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TDigest;
+
+    #[test]
+    fn test_tdigest_quantile() {
+        let mut digest = TDigest::new(100.0);
+        assert_eq!(digest.quantile(0.5), None);
+
+        // shuffle the values so they are not merged in order
+        for i in 0..10_000 {
+            digest.add(((i * 7919) % 10_000) as f64);
+        }
+        assert!(digest.centroids.len() < 500);
+        for quantile in [0.01, 0.25, 0.5, 0.75, 0.99] {
+            let estimate = digest.quantile(quantile).unwrap();
+            assert!(
+                (estimate - quantile * 10_000.0).abs() < 50.0,
+                "quantile {quantile} estimated {estimate}"
+            );
+        }
+        assert_eq!(digest.quantile(0.0), Some(0.0));
+        assert_eq!(digest.quantile(1.0), Some(9999.0));
+    }
+}
@@ -0,0 +1,125 @@
+use std::{fmt::Display, sync::Arc};
+
+use arrow::array::{ArrayRef, AsArray};
+use arrow::compute;
+use arrow::datatypes::{DataType, Float64Type};
+
+use super::{Accumulator, AggregateExpr};
+use crate::error::Result;
+use crate::{datatypes::scalar::ScalarValue, physical::expr::PhysicalExpr};
+
+/// `VARIANCE(expr)` the sample variance, `VAR_POP(expr)` the population variance
+#[derive(Debug)]
+pub struct VarianceAggregateExpr {
+    pub expr: Arc<dyn PhysicalExpr>,
+    pub population: bool,
+}
+
+impl VarianceAggregateExpr {
+    pub fn new(expr: Arc<dyn PhysicalExpr>, population: bool) -> Self {
+        Self { expr, population }
+    }
+}
+
+impl Display for VarianceAggregateExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = if self.population { "VAR_POP" } else { "VARIANCE" };
+        write!(f, "{}({})", name, self.expr)
+    }
+}
+
+impl AggregateExpr for VarianceAggregateExpr {
+    fn expression(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expr
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(VarianceAccumulator::new(self.population, false)))
+    }
+}
+
+/// `STDDEV(expr)` the sample standard deviation, `STDDEV_POP(expr)` the population standard deviation
+#[derive(Debug)]
+pub struct StddevAggregateExpr {
+    pub expr: Arc<dyn PhysicalExpr>,
+    pub population: bool,
+}
+
+impl StddevAggregateExpr {
+    pub fn new(expr: Arc<dyn PhysicalExpr>, population: bool) -> Self {
+        Self { expr, population }
+    }
+}
+
+impl Display for StddevAggregateExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = if self.population { "STDDEV_POP" } else { "STDDEV" };
+        write!(f, "{}({})", name, self.expr)
+    }
+}
+
+impl AggregateExpr for StddevAggregateExpr {
+    fn expression(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.expr
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(VarianceAccumulator::new(self.population, true)))
+    }
+}
+
+/// The variance computed with Welford's algorithm, the mean and the sum of the squared differences from the mean are
+/// updated with each value instead of summing the squares of the values, which loses precision when the values are
+/// large compared to their differences.
+#[derive(Debug)]
+struct VarianceAccumulator {
+    count: u64,
+    mean: f64,
+    /// the sum of the squared differences from the mean
+    m2: f64,
+    population: bool,
+    /// the square root of the variance is returned
+    stddev: bool,
+}
+
+impl VarianceAccumulator {
+    fn new(population: bool, stddev: bool) -> Self {
+        Self {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            population,
+            stddev,
+        }
+    }
+}
+
+impl Accumulator for VarianceAccumulator {
+    fn accumluate(&mut self, value: &ArrayRef) -> Result<()> {
+        let value = compute::cast(value, &DataType::Float64)?;
+
+        for v in value.as_primitive::<Float64Type>().iter().flatten() {
+            self.count += 1;
+            let delta = v - self.mean;
+            self.mean += delta / self.count as f64;
+            self.m2 += delta * (v - self.mean);
+        }
+
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        // the sample variance of a single value is undefined
+        let variance = match self.count {
+            0 => None,
+            1 if !self.population => None,
+            n if self.population => Some(self.m2 / n as f64),
+            n => Some(self.m2 / (n - 1) as f64),
+        };
+
+        if self.stddev {
+            return Ok(ScalarValue::Float64(variance.map(f64::sqrt)));
+        }
+        Ok(ScalarValue::Float64(variance))
+    }
+}
@@ -1,5 +1,6 @@
 use arrow::array::{ArrayRef, AsArray, BooleanArray, Datum};
 use arrow::compute::kernels::cmp::*;
+use arrow::compute::kernels::concat_elements::concat_elements_dyn;
 use arrow::compute::kernels::numeric::{add_wrapping, div, mul_wrapping, rem, sub_wrapping};
 use arrow::compute::{and_kleene, or_kleene};
 use arrow::error::ArrowError;
@@ -38,6 +39,8 @@ impl PhysicalExpr for BinaryExpr {
             Operator::GtEq => cmp(&l, &r, gt_eq),
             Operator::Lt => cmp(&l, &r, lt),
             Operator::LtEq => cmp(&l, &r, lt_eq),
+            Operator::IsDistinctFrom => cmp(&l, &r, distinct),
+            Operator::IsNotDistinctFrom => cmp(&l, &r, not_distinct),
             // logic
             Operator::And => and_kleene(l.as_boolean(), r.as_boolean())
                 .map(|a| Arc::new(a) as ArrayRef)
@@ -51,6 +54,8 @@ impl PhysicalExpr for BinaryExpr {
             Operator::Mul => mul_wrapping(&l, &r).map_err(|e| arrow_err!(e)),
             Operator::Div => div(&l, &r).map_err(|e| arrow_err!(e)),
             Operator::Mod => rem(&l, &r).map_err(|e| arrow_err!(e)),
+            // string
+            Operator::StringConcat => concat_elements_dyn(&l, &r).map_err(|e| arrow_err!(e)),
         }
     }
 }
@@ -110,6 +115,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_distinct_ops() {
+        let schema = Arc::new(arrow::datatypes::Schema::new(vec![
+            arrow::datatypes::Field::new("left", DataType::Int32, true),
+            arrow::datatypes::Field::new("right", DataType::Int32, true),
+        ]));
+        let data = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(arrow::array::Int32Array::from(vec![Some(1), Some(1), None, None])),
+                Arc::new(arrow::array::Int32Array::from(vec![Some(1), Some(2), Some(1), None])),
+            ],
+        )
+        .unwrap();
+
+        // NULLs are compared as values, the result is never NULL
+        for (op, expected) in [
+            (Operator::IsDistinctFrom, vec![false, true, true, false]),
+            (Operator::IsNotDistinctFrom, vec![true, false, false, true]),
+        ] {
+            let expr = BinaryExpr::new(Arc::new(Column::new("left", 0)), op, Arc::new(Column::new("right", 1)));
+            assert_eq!(expr.evaluate(&data).unwrap().as_ref(), &BooleanArray::from(expected));
+        }
+    }
+
     #[test]
     fn test_arithmetic_ops() {
         let test_cases = vec![
@@ -0,0 +1,216 @@
+use std::fmt::Display;
+use std::sync::Arc;
+
+use arrow::array::{new_null_array, Array, ArrayRef, AsArray, BooleanArray, RecordBatch};
+use arrow::compute::kernels::cmp::eq;
+use arrow::compute::{self, filter, filter_record_batch, interleave};
+use arrow::datatypes::DataType;
+
+use super::PhysicalExpr;
+use crate::arrow_err;
+use crate::error::{Error, Result};
+
+/// `CASE [operand] WHEN condition THEN result ... [ELSE result] END`
+///
+/// Each branch is only evaluated on the rows not matched by the previous branches, so a result is never computed
+/// for a row it is not chosen for, eg. `CASE WHEN b <> 0 THEN a / b END`.
+#[derive(Debug)]
+pub struct CaseExpr {
+    operand: Option<Arc<dyn PhysicalExpr>>,
+    when_then: Vec<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)>,
+    else_expr: Option<Arc<dyn PhysicalExpr>>,
+    data_type: DataType,
+}
+
+impl CaseExpr {
+    pub fn new(
+        operand: Option<Arc<dyn PhysicalExpr>>,
+        when_then: Vec<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)>,
+        else_expr: Option<Arc<dyn PhysicalExpr>>,
+        data_type: DataType,
+    ) -> Self {
+        Self {
+            operand,
+            when_then,
+            else_expr,
+            data_type,
+        }
+    }
+
+    /// evaluate the result on the rows selected by the mask
+    fn evaluate_result(&self, expr: &Arc<dyn PhysicalExpr>, input: &RecordBatch, mask: &[bool]) -> Result<ArrayRef> {
+        let batch = filter_record_batch(input, &BooleanArray::from(mask.to_vec())).map_err(|e| arrow_err!(e))?;
+        compute::cast(&expr.evaluate(&batch)?, &self.data_type).map_err(|e| arrow_err!(e))
+    }
+}
+
+impl PhysicalExpr for CaseExpr {
+    fn evaluate(&self, input: &RecordBatch) -> Result<ArrayRef> {
+        let operand = self.operand.as_ref().map(|expr| expr.evaluate(input)).transpose()?;
+
+        // the results of the branches, and for each row the result it takes and its offset in the result
+        let mut results = vec![];
+        let mut indices = vec![None; input.num_rows()];
+        let mut remaining = vec![true; input.num_rows()];
+
+        let mut push_result = |result: ArrayRef, mask: &[bool], indices: &mut Vec<Option<(usize, usize)>>| {
+            for (offset, row) in (0..mask.len()).filter(|row| mask[*row]).enumerate() {
+                indices[row] = Some((results.len(), offset));
+            }
+            results.push(result);
+        };
+
+        for (when, then) in &self.when_then {
+            let remaining_mask = BooleanArray::from(remaining.clone());
+            if remaining_mask.true_count() == 0 {
+                break;
+            }
+
+            let batch = filter_record_batch(input, &remaining_mask).map_err(|e| arrow_err!(e))?;
+            let condition = when.evaluate(&batch)?;
+            let condition = match &operand {
+                Some(operand) => {
+                    let operand = filter(operand, &remaining_mask).map_err(|e| arrow_err!(e))?;
+                    let condition = compute::cast(&condition, operand.data_type()).map_err(|e| arrow_err!(e))?;
+                    eq(&operand, &condition).map_err(|e| arrow_err!(e))?
+                }
+                None => condition.as_boolean_opt().cloned().ok_or(Error::InternalError(format!(
+                    "CASE condition [{}] must be a boolean expression",
+                    when
+                )))?,
+            };
+
+            // a NULL condition does not match
+            let rows = (0..input.num_rows()).filter(|row| remaining[*row]).collect::<Vec<_>>();
+            let mut matched = vec![false; input.num_rows()];
+            for (offset, row) in rows.into_iter().enumerate() {
+                matched[row] = condition.is_valid(offset) && condition.value(offset);
+                remaining[row] = !matched[row];
+            }
+            if matched.contains(&true) {
+                push_result(self.evaluate_result(then, input, &matched)?, &matched, &mut indices);
+            }
+        }
+
+        if let Some(else_expr) = self.else_expr.as_ref().filter(|_| remaining.contains(&true)) {
+            push_result(
+                self.evaluate_result(else_expr, input, &remaining)?,
+                &remaining,
+                &mut indices,
+            );
+        }
+
+        // the rows no branch matched are NULL
+        let null_index = (results.len(), 0);
+        results.push(new_null_array(&self.data_type, 1));
+        let indices = indices
+            .into_iter()
+            .map(|index| index.unwrap_or(null_index))
+            .collect::<Vec<_>>();
+
+        interleave(&results.iter().map(|a| a.as_ref()).collect::<Vec<_>>(), &indices).map_err(|e| arrow_err!(e))
+    }
+}
+
+impl Display for CaseExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CASE")?;
+        if let Some(operand) = &self.operand {
+            write!(f, " {}", operand)?;
+        }
+        for (when, then) in &self.when_then {
+            write!(f, " WHEN {} THEN {}", when, then)?;
+        }
+        if let Some(else_expr) = &self.else_expr {
+            write!(f, " ELSE {}", else_expr)?;
+        }
+        write!(f, " END")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datatypes::{operator::Operator, scalar::ScalarValue};
+    use crate::physical::expr::{BinaryExpr, Column, Literal};
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+
+    fn build_batch() -> RecordBatch {
+        RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("a", DataType::Int64, true),
+                Field::new("b", DataType::Int64, true),
+            ])),
+            vec![
+                Arc::new(Int64Array::from(vec![Some(1), Some(2), None, Some(4)])),
+                Arc::new(Int64Array::from(vec![Some(0), Some(2), Some(1), Some(0)])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn literal(value: ScalarValue) -> Arc<dyn PhysicalExpr> {
+        Arc::new(Literal::new(value))
+    }
+
+    #[test]
+    fn test_searched_case() {
+        // CASE WHEN b != 0 THEN a / b WHEN a > 3 THEN 100 END
+        let b_not_zero = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("b", 1)),
+            Operator::NotEq,
+            literal(ScalarValue::Int64(Some(0))),
+        ));
+        let a_div_b = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("a", 0)),
+            Operator::Div,
+            Arc::new(Column::new("b", 1)),
+        ));
+        let a_gt_3 = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("a", 0)),
+            Operator::Gt,
+            literal(ScalarValue::Int64(Some(3))),
+        ));
+        let expr = CaseExpr::new(
+            None,
+            vec![
+                (b_not_zero, a_div_b),
+                (a_gt_3, literal(ScalarValue::Int64(Some(100)))),
+            ],
+            None,
+            DataType::Int64,
+        );
+
+        assert_eq!(
+            expr.evaluate(&build_batch()).unwrap().as_ref(),
+            &Int64Array::from(vec![None, Some(1), None, Some(100)])
+        );
+    }
+
+    #[test]
+    fn test_simple_case() {
+        // CASE a WHEN 1 THEN 'one' WHEN 2 THEN 'two' ELSE 'other' END
+        let expr = CaseExpr::new(
+            Some(Arc::new(Column::new("a", 0))),
+            vec![
+                (
+                    literal(ScalarValue::Int64(Some(1))),
+                    literal(ScalarValue::Utf8(Some("one".to_owned()))),
+                ),
+                (
+                    literal(ScalarValue::Int64(Some(2))),
+                    literal(ScalarValue::Utf8(Some("two".to_owned()))),
+                ),
+            ],
+            Some(literal(ScalarValue::Utf8(Some("other".to_owned())))),
+            DataType::Utf8,
+        );
+
+        // a NULL operand matches no value and falls back to ELSE
+        assert_eq!(
+            expr.evaluate(&build_batch()).unwrap().as_ref(),
+            &StringArray::from(vec!["one", "two", "other", "other"])
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use arrow::array::{new_empty_array, Array, ArrayRef, BooleanArray};
+use arrow::compute::kernels::cmp::eq;
+use arrow::compute::{self, or_kleene};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use arrow::row::{OwnedRow, RowConverter, SortField};
+
+use super::PhysicalExpr;
+use crate::datatypes::scalar::ScalarValue;
+use crate::error::Result;
+
+/// A set of values for membership tests, the values are compared by their row format encoding
+/// so that any data type can be hashed.
+#[derive(Debug)]
+pub(crate) struct ValueSet {
+    data_type: DataType,
+    converter: RowConverter,
+    values: HashSet<OwnedRow>,
+    has_null: bool,
+}
+
+impl ValueSet {
+    pub(crate) fn try_new(values: &ArrayRef) -> Result<Self> {
+        let converter = RowConverter::new(vec![SortField::new(values.data_type().clone())])?;
+        let rows = converter.convert_columns(std::slice::from_ref(values))?;
+
+        Ok(Self {
+            data_type: values.data_type().clone(),
+            values: (0..values.len())
+                .filter(|i| values.is_valid(*i))
+                .map(|i| rows.row(i).owned())
+                .collect(),
+            has_null: values.null_count() > 0,
+            converter,
+        })
+    }
+
+    /// Follows the SQL semantics: the result is NULL if the value is NULL, or if the value is not found
+    /// and the set contains NULL.
+    pub(crate) fn contains(&self, array: &ArrayRef, negated: bool) -> Result<BooleanArray> {
+        let array = compute::cast(array, &self.data_type)?;
+        let rows = self.converter.convert_columns(std::slice::from_ref(&array))?;
+
+        Ok((0..array.len())
+            .map(|i| {
+                if array.is_null(i) {
+                    None
+                } else if self.values.contains(&rows.row(i).owned()) {
+                    Some(!negated)
+                } else if self.has_null {
+                    None
+                } else {
+                    Some(negated)
+                }
+            })
+            .collect())
+    }
+}
+
+/// `expr [NOT] IN (val1, val2, ...)`
+///
+/// A list of literals is converted to a [`ValueSet`] once, otherwise each item is compared with the expression.
+#[derive(Debug)]
+pub struct InList {
+    expr: Arc<dyn PhysicalExpr>,
+    list: Vec<Arc<dyn PhysicalExpr>>,
+    negated: bool,
+    set: Option<ValueSet>,
+}
+
+impl InList {
+    pub fn new(expr: Arc<dyn PhysicalExpr>, list: Vec<Arc<dyn PhysicalExpr>>, negated: bool) -> Self {
+        Self {
+            expr,
+            list,
+            negated,
+            set: None,
+        }
+    }
+
+    /// create an `IN` expression with a static list of values, the values are cast to `data_type`
+    pub fn try_new_with_values(
+        expr: Arc<dyn PhysicalExpr>,
+        values: &[ScalarValue],
+        data_type: &DataType,
+        negated: bool,
+    ) -> Result<Self> {
+        let arrays = values
+            .iter()
+            .map(|value| {
+                value
+                    .to_array(1)
+                    .and_then(|a| compute::cast(&a, data_type).map_err(|e| e.into()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let values = if arrays.is_empty() {
+            new_empty_array(data_type)
+        } else {
+            compute::concat(&arrays.iter().map(|a| a.as_ref()).collect::<Vec<_>>())?
+        };
+
+        Ok(Self {
+            expr,
+            list: vec![],
+            negated,
+            set: Some(ValueSet::try_new(&values)?),
+        })
+    }
+}
+
+impl PhysicalExpr for InList {
+    fn evaluate(&self, input: &RecordBatch) -> Result<ArrayRef> {
+        let value = self.expr.evaluate(input)?;
+
+        if let Some(set) = &self.set {
+            return set.contains(&value, self.negated).map(|a| Arc::new(a) as ArrayRef);
+        }
+
+        let mut found = BooleanArray::from(vec![false; value.len()]);
+        for item in &self.list {
+            let item = compute::cast(&item.evaluate(input)?, value.data_type())?;
+            found = or_kleene(&found, &eq(&value, &item)?)?;
+        }
+
+        if self.negated {
+            found = compute::not(&found)?;
+        }
+
+        Ok(Arc::new(found))
+    }
+}
+
+impl Display for InList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}IN ({})",
+            self.expr,
+            if self.negated { "NOT " } else { "" },
+            self.list.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical::expr::{Column, Literal};
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{Field, Schema};
+
+    fn build_batch() -> RecordBatch {
+        RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("a", DataType::Int64, true),
+                Field::new("b", DataType::Int64, true),
+            ])),
+            vec![
+                Arc::new(Int64Array::from(vec![Some(1), Some(2), None, Some(4)])),
+                Arc::new(Int64Array::from(vec![Some(4), Some(3), Some(2), Some(4)])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_in_list_with_values() {
+        let batch = build_batch();
+        let values = vec![ScalarValue::Int32(Some(1)), ScalarValue::Int32(Some(4))];
+
+        let expr =
+            InList::try_new_with_values(Arc::new(Column::new("a", 0)), &values, &DataType::Int64, false).unwrap();
+        assert_eq!(
+            expr.evaluate(&batch).unwrap().as_ref(),
+            &BooleanArray::from(vec![Some(true), Some(false), None, Some(true)])
+        );
+
+        // NOT IN a list containing NULL is never true
+        let values = vec![ScalarValue::Int64(Some(1)), ScalarValue::Int64(None)];
+        let expr = InList::try_new_with_values(Arc::new(Column::new("a", 0)), &values, &DataType::Int64, true).unwrap();
+        assert_eq!(
+            expr.evaluate(&batch).unwrap().as_ref(),
+            &BooleanArray::from(vec![Some(false), None, None, None])
+        );
+    }
+
+    #[test]
+    fn test_in_list_with_exprs() {
+        let batch = build_batch();
+        let list: Vec<Arc<dyn PhysicalExpr>> = vec![
+            Arc::new(Column::new("b", 1)),
+            Arc::new(Literal::new(ScalarValue::Int64(Some(2)))),
+        ];
+
+        let expr = InList::new(Arc::new(Column::new("a", 0)), list, true);
+        assert_eq!(
+            expr.evaluate(&batch).unwrap().as_ref(),
+            &BooleanArray::from(vec![Some(true), Some(false), None, Some(false)])
+        );
+    }
+}
@@ -4,49 +4,184 @@ use super::PhysicalExpr;
 use crate::{
     arrow_err,
     error::{Error, Result},
+    internal_err,
 };
 use arrow::{
-    array::{Array, ArrayRef},
+    array::{Array, ArrayRef, AsArray, StringArray},
     compute,
+    datatypes::DataType,
     record_batch::RecordBatch,
 };
 
+/// `expr [NOT] LIKE pattern [ESCAPE escape_char]`, `%` matches any sequence of characters and `_` matches
+/// any single character. `ILIKE` matches case-insensitively.
 #[derive(Debug)]
 pub struct Like {
     negated: bool,
+    case_insensitive: bool,
+    /// the character escaping the wildcards in the pattern, backslash by default
+    escape_char: Option<char>,
     expr: Arc<dyn PhysicalExpr>,
     pattern: Arc<dyn PhysicalExpr>,
 }
 
 impl Like {
-    pub fn new(negated: bool, expr: Arc<dyn PhysicalExpr>, pattern: Arc<dyn PhysicalExpr>) -> Self {
-        Self { negated, expr, pattern }
+    pub fn new(
+        negated: bool,
+        case_insensitive: bool,
+        escape_char: Option<char>,
+        expr: Arc<dyn PhysicalExpr>,
+        pattern: Arc<dyn PhysicalExpr>,
+    ) -> Self {
+        Self {
+            negated,
+            case_insensitive,
+            escape_char,
+            expr,
+            pattern,
+        }
+    }
+
+    /// the arrow kernels escape with a backslash, rewrite the patterns escaped with another character
+    fn escape_patterns(&self, patterns: ArrayRef) -> Result<ArrayRef> {
+        let escape_char = match self.escape_char {
+            Some(escape_char) if escape_char != '\\' => escape_char,
+            _ => return Ok(patterns),
+        };
+
+        let patterns = compute::cast(&patterns, &DataType::Utf8).map_err(|e| arrow_err!(e))?;
+        let escaped = patterns
+            .as_string::<i32>()
+            .iter()
+            .map(|pattern| pattern.map(|pattern| escape_pattern(pattern, escape_char)).transpose())
+            .collect::<Result<StringArray>>()?;
+
+        Ok(Arc::new(escaped))
+    }
+}
+
+fn escape_pattern(pattern: &str, escape_char: char) -> Result<String> {
+    let mut escaped = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        if c == escape_char {
+            match chars.next() {
+                Some(c @ ('%' | '_' | '\\')) => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                Some(c) => escaped.push(c),
+                None => return internal_err!("LIKE pattern [{}] must not end with escape character", pattern),
+            }
+        } else if c == '\\' {
+            escaped.push_str("\\\\");
+        } else {
+            escaped.push(c);
+        }
     }
+
+    Ok(escaped)
 }
 
 impl PhysicalExpr for Like {
     fn evaluate(&self, input: &RecordBatch) -> Result<ArrayRef> {
         let expr = self.expr.evaluate(input)?;
-        let pattern = self.pattern.evaluate(input)?;
+        let pattern = self.escape_patterns(self.pattern.evaluate(input)?)?;
+        let pattern = compute::cast(&pattern, expr.data_type()).map_err(|e| arrow_err!(e))?;
 
-        if self.negated {
-            compute::nlike(&expr, &pattern)
-                .map_err(|e| arrow_err!(e))
-                .map(|a| Arc::new(a) as Arc<dyn Array>)
-        } else {
-            compute::like(&expr, &pattern)
-                .map_err(|e| arrow_err!(e))
-                .map(|a| Arc::new(a) as Arc<dyn Array>)
+        match (self.negated, self.case_insensitive) {
+            (false, false) => compute::like(&expr, &pattern),
+            (true, false) => compute::nlike(&expr, &pattern),
+            (false, true) => compute::ilike(&expr, &pattern),
+            (true, true) => compute::nilike(&expr, &pattern),
         }
+        .map_err(|e| arrow_err!(e))
+        .map(|a| Arc::new(a) as Arc<dyn Array>)
     }
 }
 
 impl Display for Like {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.negated {
-            write!(f, "{} NOT LIKE {}", self.expr, self.pattern)
-        } else {
-            write!(f, "{} LIKE {}", self.expr, self.pattern)
+        write!(
+            f,
+            "{} {}{} {}",
+            self.expr,
+            if self.negated { "NOT " } else { "" },
+            if self.case_insensitive { "ILIKE" } else { "LIKE" },
+            self.pattern
+        )?;
+        if let Some(escape_char) = self.escape_char {
+            write!(f, " ESCAPE '{}'", escape_char)?;
         }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datatypes::scalar::ScalarValue;
+    use crate::physical::expr::{Column, Literal};
+    use arrow::array::BooleanArray;
+    use arrow::datatypes::{Field, Schema};
+
+    fn evaluate(negated: bool, case_insensitive: bool, escape_char: Option<char>, pattern: &str) -> ArrayRef {
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)])),
+            vec![Arc::new(StringArray::from(vec![
+                Some("abc"),
+                Some("ABC"),
+                Some("a%c"),
+                Some("a\\c"),
+                None,
+            ]))],
+        )
+        .unwrap();
+        let pattern = Arc::new(Literal::new(ScalarValue::Utf8(Some(pattern.to_owned()))));
+
+        Like::new(
+            negated,
+            case_insensitive,
+            escape_char,
+            Arc::new(Column::new("a", 0)),
+            pattern,
+        )
+        .evaluate(&batch)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_like() {
+        assert_eq!(
+            evaluate(false, false, None, "a_c").as_ref(),
+            &BooleanArray::from(vec![Some(true), Some(false), Some(true), Some(true), None])
+        );
+        assert_eq!(
+            evaluate(true, true, None, "a%").as_ref(),
+            &BooleanArray::from(vec![Some(false), Some(false), Some(false), Some(false), None])
+        );
+    }
+
+    #[test]
+    fn test_like_escape() {
+        assert_eq!(
+            evaluate(false, false, None, "a\\%c").as_ref(),
+            &BooleanArray::from(vec![Some(false), Some(false), Some(true), Some(false), None])
+        );
+        // with another escape character the backslash is a normal character
+        assert_eq!(
+            evaluate(false, false, Some('!'), "a!%c").as_ref(),
+            &BooleanArray::from(vec![Some(false), Some(false), Some(true), Some(false), None])
+        );
+        assert_eq!(
+            evaluate(false, true, Some('!'), "A\\_").as_ref(),
+            &BooleanArray::from(vec![Some(false), Some(false), Some(false), Some(true), None])
+        );
+
+        assert_eq!(
+            escape_pattern("a!!b!", '!').unwrap_err().to_string(),
+            "Internal Error: LIKE pattern [a!!b!] must not end with escape character"
+        );
     }
 }
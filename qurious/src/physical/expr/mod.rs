@@ -1,8 +1,10 @@
 mod aggregate;
 mod binary;
+mod case;
 mod cast;
 mod column;
 mod function;
+mod in_list;
 mod is_not_null;
 mod is_null;
 mod like;
@@ -10,17 +12,22 @@ mod literal;
 mod negative;
 mod subquery;
 
-pub use aggregate::{avg::*, count::*, max::*, min::*, sum::*, Accumulator, AggregateExpr};
+pub use aggregate::{
+    approx_distinct::*, avg::*, count::*, covariance::*, distinct::*, filter::*, hyperloglog::HyperLogLog, max::*,
+    min::*, percentile::*, sum::*, variance::*, Accumulator, AggregateExpr,
+};
 pub use binary::BinaryExpr;
+pub use case::CaseExpr;
 pub use cast::CastExpr;
 pub use column::Column;
 pub use function::*;
+pub use in_list::InList;
 pub use is_not_null::*;
 pub use is_null::*;
 pub use like::*;
 pub use literal::Literal;
 pub use negative::*;
-pub use subquery::SubQuery;
+pub use subquery::{InSubQuery, OuterColumn, OuterRefs, SubQuery};
 
 use std::fmt::{Debug, Display};
 
@@ -28,6 +35,6 @@ use arrow::{array::ArrayRef, record_batch::RecordBatch};
 
 use crate::error::Result;
 
-pub trait PhysicalExpr: Debug + Display {
+pub trait PhysicalExpr: Debug + Display + Send + Sync {
     fn evaluate(&self, input: &RecordBatch) -> Result<ArrayRef>;
 }
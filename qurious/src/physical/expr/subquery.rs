@@ -1,22 +1,271 @@
-use crate::error::Result;
-use arrow::array::{ArrayRef, RecordBatch};
+use crate::datatypes::scalar::ScalarValue;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use arrow::array::{new_empty_array, Array, ArrayRef, BooleanArray, RecordBatch, UInt32Array};
+use arrow::compute;
+use arrow::datatypes::SchemaRef;
 use std::{
+    collections::HashMap,
     fmt::{Debug, Display},
-    sync::Arc,
+    sync::{Arc, RwLock},
 };
 
 use crate::physical::plan::PhysicalPlan;
 
+use super::in_list::ValueSet;
 use super::PhysicalExpr;
 
+/// The values of the outer row referenced by a correlated subquery, they are bound by the [`SubQuery`]
+/// expression before executing the subquery plan and read by the [`OuterColumn`]s of the plan.
+#[derive(Debug, Default)]
+pub struct OuterRefs {
+    values: RwLock<HashMap<String, ScalarValue>>,
+}
+
+impl OuterRefs {
+    fn bind(&self, values: Vec<(String, ScalarValue)>) -> Result<()> {
+        let mut bound = self.values.write().map_err(|e| Error::InternalError(e.to_string()))?;
+        bound.extend(values);
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Result<ScalarValue> {
+        let bound = self.values.read().map_err(|e| Error::InternalError(e.to_string()))?;
+        match bound.get(name) {
+            Some(value) => Ok(value.clone()),
+            None => internal_err!("Outer reference [{}] is not bound", name),
+        }
+    }
+}
+
+/// A column of the outer query referenced inside a correlated subquery
+#[derive(Debug)]
+pub struct OuterColumn {
+    name: String,
+    outer_refs: Arc<OuterRefs>,
+}
+
+impl OuterColumn {
+    pub fn new(name: impl Into<String>, outer_refs: Arc<OuterRefs>) -> Self {
+        Self {
+            name: name.into(),
+            outer_refs,
+        }
+    }
+}
+
+impl PhysicalExpr for OuterColumn {
+    fn evaluate(&self, input: &RecordBatch) -> Result<ArrayRef> {
+        self.outer_refs.get(&self.name)?.to_array(input.num_rows())
+    }
+}
+
+impl Display for OuterColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "outer_ref({})", self.name)
+    }
+}
+
+/// A scalar subquery, produces exactly one value for each input row.
+///
+/// An uncorrelated subquery is executed once, a correlated subquery is executed for each
+/// distinct value of the outer columns it references.
 pub struct SubQuery {
-    pub plan: Arc<dyn PhysicalPlan>,
+    plan: Arc<dyn PhysicalPlan>,
+    /// the outer columns referenced by the subquery, evaluated against the input of the outer query
+    outer_ref_columns: Vec<(String, Arc<dyn PhysicalExpr>)>,
+    outer_refs: Arc<OuterRefs>,
 }
 
-impl PhysicalExpr for SubQuery {
-    fn evaluate(&self, _input: &RecordBatch) -> Result<ArrayRef> {
+impl SubQuery {
+    pub fn new(
+        plan: Arc<dyn PhysicalPlan>,
+        outer_ref_columns: Vec<(String, Arc<dyn PhysicalExpr>)>,
+        outer_refs: Arc<OuterRefs>,
+    ) -> Self {
+        Self {
+            plan,
+            outer_ref_columns,
+            outer_refs,
+        }
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        self.plan.schema()
+    }
+
+    /// execute the subquery and concatenate all of its rows into one batch
+    pub(crate) fn execute_batch(&self) -> Result<RecordBatch> {
+        let batches = self.plan.execute()?;
+        compute::concat_batches(&self.plan.schema(), &batches).map_err(|e| e.into())
+    }
+
+    /// execute the subquery, it must return at most one row, no row means NULL
+    fn execute_scalar(&self) -> Result<ScalarValue> {
+        let batches = self.plan.execute()?;
+
+        if batches.iter().map(|batch| batch.num_rows()).sum::<usize>() > 1 {
+            return internal_err!("Scalar subquery returned more than one row");
+        }
+
+        match batches.iter().find(|batch| batch.num_rows() > 0) {
+            Some(batch) => ScalarValue::try_from_array(batch.column(0), 0),
+            None => self.plan.schema().field(0).data_type().try_into(),
+        }
+    }
+
+    /// execute the subquery and collect the values of its only column
+    fn execute_column(&self) -> Result<ArrayRef> {
         let batches = self.plan.execute()?;
-        Ok(batches[0].column(0).clone())
+        if batches.is_empty() {
+            return Ok(new_empty_array(self.plan.schema().field(0).data_type()));
+        }
+
+        compute::concat(&batches.iter().map(|batch| batch.column(0).as_ref()).collect::<Vec<_>>()).map_err(|e| e.into())
+    }
+
+    /// Execute the subquery for each row of the input with the outer columns bound to the values of the row,
+    /// rows with the same outer values share one execution. Returns the results of the executions and the
+    /// index of the result for each row.
+    pub(crate) fn execute_correlated<T>(
+        &self,
+        input: &RecordBatch,
+        mut execute: impl FnMut(&Self) -> Result<T>,
+    ) -> Result<(Vec<T>, Vec<usize>)> {
+        let outer_values = self
+            .outer_ref_columns
+            .iter()
+            .map(|(_, expr)| expr.evaluate(input))
+            .collect::<Result<Vec<_>>>()?;
+
+        // ScalarValue is not hashable, the cache is keyed by the debug representation of the outer values
+        let mut cache: HashMap<String, usize> = HashMap::new();
+        let mut results = vec![];
+        let mut indices = Vec::with_capacity(input.num_rows());
+        for row in 0..input.num_rows() {
+            let key = outer_values
+                .iter()
+                .map(|values| ScalarValue::try_from_array(values, row))
+                .collect::<Result<Vec<_>>>()?;
+
+            let cache_key = format!("{:?}", key);
+            let index = match cache.get(&cache_key) {
+                Some(index) => *index,
+                None => {
+                    self.outer_refs.bind(
+                        self.outer_ref_columns
+                            .iter()
+                            .map(|(name, _)| name.clone())
+                            .zip(key)
+                            .collect(),
+                    )?;
+                    results.push(execute(self)?);
+                    cache.insert(cache_key, results.len() - 1);
+                    results.len() - 1
+                }
+            };
+            indices.push(index);
+        }
+
+        Ok((results, indices))
+    }
+}
+
+impl PhysicalExpr for SubQuery {
+    fn evaluate(&self, input: &RecordBatch) -> Result<ArrayRef> {
+        if self.outer_ref_columns.is_empty() {
+            return self.execute_scalar()?.to_array(input.num_rows());
+        }
+
+        let data_type = self.plan.schema().field(0).data_type().clone();
+        let (values, indices) = self.execute_correlated(input, |subquery| subquery.execute_scalar())?;
+        if indices.is_empty() {
+            return Ok(new_empty_array(&data_type));
+        }
+
+        let values = values
+            .iter()
+            .map(|value| {
+                value
+                    .to_array(1)
+                    .and_then(|a| compute::cast(&a, &data_type).map_err(|e| e.into()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let values = compute::concat(&values.iter().map(|a| a.as_ref()).collect::<Vec<_>>())?;
+
+        compute::take(
+            &values,
+            &UInt32Array::from_iter_values(indices.iter().map(|i| *i as u32)),
+            None,
+        )
+        .map_err(|e| e.into())
+    }
+}
+
+/// `expr [NOT] IN (SELECT ...)`, the values returned by the subquery are collected into a [`ValueSet`]
+pub struct InSubQuery {
+    expr: Arc<dyn PhysicalExpr>,
+    subquery: SubQuery,
+    negated: bool,
+}
+
+impl InSubQuery {
+    pub fn new(expr: Arc<dyn PhysicalExpr>, subquery: SubQuery, negated: bool) -> Self {
+        Self {
+            expr,
+            subquery,
+            negated,
+        }
+    }
+}
+
+impl PhysicalExpr for InSubQuery {
+    fn evaluate(&self, input: &RecordBatch) -> Result<ArrayRef> {
+        let value = self.expr.evaluate(input)?;
+        let data_type = value.data_type().clone();
+        let value_set = |subquery: &SubQuery| {
+            subquery
+                .execute_column()
+                .and_then(|values| compute::cast(&values, &data_type).map_err(|e| e.into()))
+                .and_then(|values| ValueSet::try_new(&values))
+        };
+
+        if self.subquery.outer_ref_columns.is_empty() {
+            return value_set(&self.subquery)?
+                .contains(&value, self.negated)
+                .map(|a| Arc::new(a) as ArrayRef);
+        }
+
+        let (sets, indices) = self.subquery.execute_correlated(input, value_set)?;
+        let result = indices
+            .iter()
+            .enumerate()
+            .map(|(row, index)| {
+                sets[*index]
+                    .contains(&value.slice(row, 1), self.negated)
+                    .map(|a| a.iter().next().flatten())
+            })
+            .collect::<Result<BooleanArray>>()?;
+
+        Ok(Arc::new(result))
+    }
+}
+
+impl Debug for InSubQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InSubQuery")
+    }
+}
+
+impl Display for InSubQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}IN ({})",
+            self.expr,
+            if self.negated { "NOT " } else { "" },
+            self.subquery
+        )
     }
 }
 
@@ -1,8 +1,13 @@
 use crate::error::{Error, Result};
+use crate::execution::memory_pool::{MemoryPool, MemoryReservation};
 use crate::physical::expr::Accumulator;
 use crate::physical::{
     expr::{AggregateExpr, PhysicalExpr},
-    plan::PhysicalPlan,
+    plan::{
+        format_exprs, parallel_map, partition_batches,
+        spill::{spill_partitioned, MAX_SPILL_LEVEL},
+        Partitioning, PhysicalPlan,
+    },
 };
 use crate::{arrow_err, hash_array};
 use arrow::compute::TakeOptions;
@@ -21,6 +26,7 @@ use std::{
     collections::HashMap,
     fmt::Display,
     hash::{DefaultHasher, Hash, Hasher},
+    sync::atomic::{AtomicUsize, Ordering},
     sync::Arc,
 };
 
@@ -76,9 +82,11 @@ impl<'a> GroupAccumulator<'a> {
                     accs_indices.entry(*group_index).or_insert(vec![]).push(*row as u64);
                 }
                 None => {
-                    self.map.insert(target_hash, *row);
+                    // the groups are numbered across the batches, a row index is only unique in its batch
+                    let group_index = self.accumulators.len();
+                    self.map.insert(target_hash, group_index);
 
-                    accs_indices.insert(*row, vec![*row as u64]);
+                    accs_indices.insert(group_index, vec![*row as u64]);
 
                     let accs = (self.accumlator_factory)()?;
                     let indices = UInt64Array::from_iter(vec![*row as u64]);
@@ -87,7 +95,7 @@ impl<'a> GroupAccumulator<'a> {
                         .map(|values| compute::take(&values, &indices, None).map_err(|e| arrow_err!(e)))
                         .collect::<Result<Vec<_>>>()?;
 
-                    self.accumulators.insert(*row, (group_values, accs));
+                    self.accumulators.insert(group_index, (group_values, accs));
                 }
             }
         }
@@ -134,6 +142,14 @@ pub struct HashAggregate {
     input: Arc<dyn PhysicalPlan>,
     group_exprs: Vec<Arc<dyn PhysicalExpr>>,
     aggregate_exprs: Vec<Arc<dyn AggregateExpr>>,
+    /// the number of partitions of groups aggregated in parallel, the rows are partitioned by the hash of their groups
+    target_partitions: usize,
+    /// the memory size of the input batches buffered before the rows are spilled to disk
+    memory_limit: usize,
+    /// the memory of the query the input batches are reserved in, the rows are also spilled when it is exhausted
+    memory_pool: Arc<MemoryPool>,
+    /// the number of files spilled by the executions
+    spilled_files: AtomicUsize,
 }
 
 impl HashAggregate {
@@ -148,17 +164,35 @@ impl HashAggregate {
             input,
             group_exprs,
             aggregate_exprs,
+            target_partitions: 1,
+            memory_limit: usize::MAX,
+            memory_pool: Arc::new(MemoryPool::unbounded()),
+            spilled_files: AtomicUsize::new(0),
         }
     }
-}
 
-impl PhysicalPlan for HashAggregate {
-    fn schema(&self) -> SchemaRef {
-        self.schema.clone()
+    pub fn with_memory_limit(self, memory_limit: usize) -> Self {
+        Self { memory_limit, ..self }
     }
 
-    fn execute(&self) -> Result<Vec<RecordBatch>> {
-        let batches = self.input.execute()?;
+    pub fn with_memory_pool(self, memory_pool: Arc<MemoryPool>) -> Self {
+        Self { memory_pool, ..self }
+    }
+
+    /// the number of files the rows were spilled to
+    pub fn spilled_files(&self) -> usize {
+        self.spilled_files.load(Ordering::Relaxed)
+    }
+
+    pub fn with_target_partitions(self, target_partitions: usize) -> Self {
+        Self {
+            target_partitions,
+            ..self
+        }
+    }
+
+    /// the groups of the rows of the batches aggregated into one batch
+    fn aggregate(&self, batches: Vec<RecordBatch>) -> Result<RecordBatch> {
         let accumlator_factory = || -> Result<Vec<Box<dyn Accumulator>>> {
             self.aggregate_exprs
                 .iter()
@@ -184,22 +218,90 @@ impl PhysicalPlan for HashAggregate {
             group_accumulator.update(&group_by_values, &input_values)?;
         }
 
-        RecordBatch::try_new(self.schema.clone(), group_accumulator.output(&self.schema)?)
-            .map(|v| vec![v])
-            .map_err(|e| arrow_err!(e))
+        RecordBatch::try_new(self.schema.clone(), group_accumulator.output(&self.schema)?).map_err(|e| arrow_err!(e))
+    }
+
+    /// the groups of the batches aggregated in parallel, the rows of a group are all in one partition
+    fn aggregate_partitions(&self, batches: Vec<RecordBatch>) -> Result<Vec<RecordBatch>> {
+        let partitions = self.target_partitions;
+        if partitions <= 1 {
+            return self.aggregate(batches).map(|batch| vec![batch]);
+        }
+
+        let partitioning = Partitioning::Hash(self.group_exprs.clone(), partitions);
+        parallel_map(partition_batches(batches, &partitioning)?, partitions, |batches| {
+            self.aggregate(batches)
+        })
+    }
+
+    /// The groups of the input aggregated in memory if the input batches do not exceed the memory limit and can be
+    /// reserved in the memory pool. Otherwise the rows are spilled to files by the hash of their groups, the rows of a
+    /// group are all in one file and the files are aggregated one after another. The aggregation fails if the pool is
+    /// exhausted once the files are spilled too many times.
+    fn aggregate_spilling(
+        &self,
+        mut input: impl Iterator<Item = Result<RecordBatch>>,
+        level: u64,
+    ) -> Result<Vec<RecordBatch>> {
+        let mut reservation = MemoryReservation::new(&self.memory_pool, "HashAggregate");
+        let mut batches = vec![];
+        loop {
+            let Some(batch) = input.next() else {
+                return self.aggregate_partitions(batches);
+            };
+            let batch = batch?;
+            let reserved = reservation.try_grow(batch.get_array_memory_size());
+            batches.push(batch);
+            match reserved {
+                Ok(()) if reservation.size() <= self.memory_limit || level >= MAX_SPILL_LEVEL => {}
+                Err(e) if level >= MAX_SPILL_LEVEL => return Err(e),
+                _ => break,
+            }
+        }
+
+        let files = spill_partitioned(
+            batches.into_iter().map(Ok).chain(input),
+            &self.input.schema(),
+            &self.group_exprs,
+            level,
+            &self.spilled_files,
+        )?;
+        reservation.free();
+
+        let mut output = vec![];
+        for file in files.into_iter().flatten() {
+            output.extend(self.aggregate_spilling(file.read()?, level + 1)?);
+        }
+
+        Ok(output)
+    }
+}
+
+impl PhysicalPlan for HashAggregate {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        self.aggregate_spilling(self.input.execute_iter()?, 0)
     }
 
     fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
         Some(vec![self.input.clone()])
     }
+
+    fn describe(&self) -> String {
+        self.to_string()
+    }
 }
 
 impl Display for HashAggregate {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "HashAggregateExec: groupExpr={:?}, aggrExpr={:?}",
-            self.group_exprs, self.aggregate_exprs
+            "HashAggregate: group_expr=[{}], aggr_expr=[{}]",
+            format_exprs(&self.group_exprs),
+            format_exprs(&self.aggregate_exprs)
         )
     }
 }
@@ -208,7 +310,8 @@ impl Display for HashAggregate {
 mod tests {
     use std::sync::Arc;
 
-    use arrow::datatypes::DataType;
+    use arrow::array::AsArray;
+    use arrow::datatypes::{DataType, Int32Type};
 
     use crate::{
         build_schema,
@@ -249,4 +352,49 @@ mod tests {
 
         assert_eq!(results.len(), 1);
     }
+
+    #[test]
+    fn test_group_by_partitions() {
+        let schema = Arc::new(build_schema!(("b1", DataType::Int32), ("MAX(a1)", DataType::Int32)));
+        let aggregate = |target_partitions: usize, memory_limit: usize| {
+            let input = build_table_scan_i32(vec![
+                ("a1", (0..100).collect()),
+                ("b1", (0..100).map(|i| i % 7).collect()),
+            ]);
+            let group_exprs = vec![Arc::new(physical::expr::Column::new("b1", 1)) as Arc<_>];
+            let aggregate_exprs = vec![Arc::new(MaxAggregateExpr {
+                expr: Arc::new(physical::expr::Column::new("a1", 0)),
+                return_type: DataType::Int32,
+            }) as Arc<_>];
+            let aggregate = HashAggregate::new(schema.clone(), input, group_exprs, aggregate_exprs)
+                .with_target_partitions(target_partitions)
+                .with_memory_limit(memory_limit);
+            let batches = aggregate.execute().unwrap();
+            // the input exceeding the memory limit is spilled
+            assert_eq!(aggregate.spilled_files() > 0, memory_limit < usize::MAX);
+
+            let mut groups = batches
+                .iter()
+                .flat_map(|batch| {
+                    let keys = batch.column(0).as_primitive::<Int32Type>().clone();
+                    let values = batch.column(1).as_primitive::<Int32Type>().clone();
+                    keys.values()
+                        .iter()
+                        .copied()
+                        .zip(values.values().to_vec())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            groups.sort();
+            groups
+        };
+
+        // the groups are aggregated in 4 buckets, each group is in one bucket
+        let expected = (0..7).map(|k| (k, 99 - (99 - k) % 7)).collect::<Vec<_>>();
+        assert_eq!(aggregate(1, usize::MAX), expected);
+        assert_eq!(aggregate(4, usize::MAX), expected);
+        // the rows of a group are spilled to one file, each file is aggregated on its own
+        assert_eq!(aggregate(1, 1), expected);
+        assert_eq!(aggregate(4, 1), expected);
+    }
 }
@@ -1,5 +1,7 @@
 mod hash;
 mod no_grouping;
+mod sort;
 
 pub use hash::HashAggregate;
 pub use no_grouping::NoGroupingAggregate;
+pub use sort::SortAggregate;
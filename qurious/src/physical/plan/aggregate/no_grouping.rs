@@ -1,8 +1,10 @@
 use crate::arrow_err;
 use crate::error::{Error, Result};
-use crate::physical::expr::AggregateExpr;
-use crate::physical::plan::PhysicalPlan;
-use arrow::array::RecordBatch;
+use crate::physical::expr::{Accumulator, AggregateExpr};
+use crate::physical::plan::{format_exprs, parallel_map, PhysicalPlan};
+use crate::provider::table::TableProvider;
+use arrow::array::{Int64Array, RecordBatch, RecordBatchOptions};
+use arrow::compute::{cast, concat};
 use arrow::datatypes::SchemaRef;
 use std::sync::Arc;
 
@@ -10,6 +12,9 @@ pub struct NoGroupingAggregate {
     schema: SchemaRef,
     input: Arc<dyn PhysicalPlan>,
     aggr_expr: Vec<Arc<dyn AggregateExpr>>,
+    /// the table scanned by the input when the aggregate expressions all count its rows, its number of rows is
+    /// taken from its statistics when they know it
+    counted_table: Option<Arc<dyn TableProvider>>,
 }
 
 impl NoGroupingAggregate {
@@ -18,8 +23,29 @@ impl NoGroupingAggregate {
             schema,
             input,
             aggr_expr,
+            counted_table: None,
         }
     }
+
+    pub(crate) fn with_counted_table(self, table: Arc<dyn TableProvider>) -> Self {
+        Self {
+            counted_table: Some(table),
+            ..self
+        }
+    }
+
+    fn create_accumulators(&self) -> Result<Vec<Box<dyn Accumulator>>> {
+        self.aggr_expr.iter().map(|expr| expr.create_accumulator()).collect()
+    }
+
+    fn accumulate(&self, accums: &mut [Box<dyn Accumulator>], batches: &[RecordBatch]) -> Result<()> {
+        for (accum, expr) in accums.iter_mut().zip(&self.aggr_expr) {
+            for batch in batches {
+                accum.accumluate(&expr.expression().evaluate(batch)?)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl PhysicalPlan for NoGroupingAggregate {
@@ -28,27 +54,36 @@ impl PhysicalPlan for NoGroupingAggregate {
     }
 
     fn execute(&self) -> Result<Vec<RecordBatch>> {
-        let batches = self.input.execute()?;
-        let mut accums = self
-            .aggr_expr
-            .iter()
-            .map(|expr| expr.create_accumulator())
-            .collect::<Result<Vec<_>>>()?;
-        let values = self
-            .aggr_expr
-            .iter()
-            .map(|expr| {
-                batches
-                    .iter()
-                    .map(|batch| expr.expression().evaluate(&batch))
-                    .collect::<Result<Vec<_>>>()
-            })
-            .collect::<Result<Vec<_>>>()?;
+        let num_rows = self
+            .counted_table
+            .as_ref()
+            .and_then(|table| table.statistics())
+            .and_then(|statistics| statistics.num_rows);
+        if let Some(num_rows) = num_rows {
+            let columns = self
+                .schema
+                .fields()
+                .iter()
+                .map(|field| cast(&Int64Array::from(vec![num_rows as i64]), field.data_type()))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            return Ok(vec![RecordBatch::try_new(self.schema.clone(), columns)?]);
+        }
 
-        for (accum, values) in accums.iter_mut().zip(values.iter()) {
-            for ele in values {
-                accum.accumluate(ele)?;
+        let mut accums = self.create_accumulators()?;
+        let partitions = self.input.output_partitions();
+        if partitions > 1 && accums.iter().all(|accum| accum.supports_merge()) {
+            // partial aggregation of each partition of the input, the states of the partitions are merged
+            let states = parallel_map((0..partitions).collect(), partitions, |partition| {
+                let mut accums = self.create_accumulators()?;
+                self.accumulate(&mut accums, &self.input.execute_partition(partition)?)?;
+                accums.iter_mut().map(|accum| accum.state()).collect::<Result<Vec<_>>>()
+            })?;
+            for (i, accum) in accums.iter_mut().enumerate() {
+                let states = states.iter().map(|states| states[i].as_ref()).collect::<Vec<_>>();
+                accum.merge(&concat(&states)?)?;
             }
+        } else {
+            self.accumulate(&mut accums, &self.input.execute()?)?;
         }
 
         let columns = accums
@@ -56,12 +91,89 @@ impl PhysicalPlan for NoGroupingAggregate {
             .map(|mut accum| accum.evaluate().and_then(|v| v.to_array(1)))
             .collect::<Result<Vec<_>>>()?;
 
-        RecordBatch::try_new(self.schema.clone(), columns)
+        // a single row even without aggregate expressions, eg. `SELECT 1 HAVING 1 > 0`
+        let options = RecordBatchOptions::new().with_row_count(Some(1));
+        RecordBatch::try_new_with_options(self.schema.clone(), columns, &options)
             .map(|b| vec![b])
             .map_err(|e| arrow_err!(e))
     }
 
     fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
-        None
+        Some(vec![self.input.clone()])
+    }
+
+    fn describe(&self) -> String {
+        format!("NoGroupingAggregate: aggr_expr=[{}]", format_exprs(&self.aggr_expr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use super::NoGroupingAggregate;
+    use crate::datasource::memory::MemoryTable;
+    use crate::error::Result;
+    use crate::physical::expr::{
+        AggregateExpr, ApproxDistinctAggregateExpr, AvgAggregateExpr, Column, CountAggregateExpr, MaxAggregateExpr,
+        SumAggregateExpr,
+    };
+    use crate::physical::plan::{PhysicalPlan, Scan};
+    use crate::test_utils::assert_batch_eq;
+
+    #[test]
+    fn test_merge_partitions() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+        let batch =
+            |values: Vec<Option<i64>>| RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(values))]);
+        let partitions = vec![
+            vec![batch(vec![Some(1), Some(2), None])?],
+            vec![batch(vec![Some(3), Some(2)])?, batch(vec![Some(7)])?],
+            vec![],
+        ];
+        let table = MemoryTable::try_new_partitioned(schema.clone(), partitions)?;
+        let input = Arc::new(Scan::new(schema, Arc::new(table), None));
+        let a = || Arc::new(Column::new("a", 0));
+        let aggregate = |aggr_expr: Vec<Arc<dyn AggregateExpr>>| {
+            let fields = (0..aggr_expr.len())
+                .map(|i| Field::new(format!("c{i}"), DataType::Int64, true))
+                .collect::<Vec<_>>();
+            NoGroupingAggregate::new(Arc::new(Schema::new(fields)), input.clone(), aggr_expr).execute()
+        };
+
+        // the partitions are aggregated separately and their states merged
+        let merged = aggregate(vec![
+            Arc::new(CountAggregateExpr::new(a())),
+            Arc::new(SumAggregateExpr::new(a(), DataType::Int64)),
+            Arc::new(MaxAggregateExpr::new(a(), DataType::Int64)),
+            Arc::new(ApproxDistinctAggregateExpr::new(a(), 14)),
+        ])?;
+        assert_batch_eq(
+            &merged,
+            vec![
+                "+----+----+----+----+",
+                "| c0 | c1 | c2 | c3 |",
+                "+----+----+----+----+",
+                "| 5  | 15 | 7  | 4  |",
+                "+----+----+----+----+",
+            ],
+        );
+
+        // AVG does not merge, the partitions are aggregated together
+        let fields = vec![Field::new("c0", DataType::Float64, true)];
+        let avg = NoGroupingAggregate::new(
+            Arc::new(Schema::new(fields)),
+            input.clone(),
+            vec![Arc::new(AvgAggregateExpr::new(a(), DataType::Int64, DataType::Float64))],
+        );
+        assert_batch_eq(
+            &avg.execute()?,
+            vec!["+-----+", "| c0  |", "+-----+", "| 3.0 |", "+-----+"],
+        );
+
+        Ok(())
     }
 }
@@ -0,0 +1,285 @@
+use std::fmt::Display;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, RecordBatch};
+use arrow::datatypes::{FieldRef, SchemaRef};
+use arrow::row::{OwnedRow, RowConverter, Rows, SortField};
+
+use crate::arrow_err;
+use crate::error::{Error, Result};
+use crate::physical::expr::{Accumulator, AggregateExpr, PhysicalExpr};
+use crate::physical::plan::{format_exprs, PhysicalPlan};
+use crate::provider::table::RecordBatchStream;
+
+/// Aggregate an input sorted on the group expressions, the rows of a group are next to each other so only the group
+/// being aggregated is kept instead of a hash table of all the groups. A group is output once a row of the next group
+/// is read, the groups are in the order of the input.
+pub struct SortAggregate {
+    schema: SchemaRef,
+    input: Arc<dyn PhysicalPlan>,
+    group_exprs: Vec<Arc<dyn PhysicalExpr>>,
+    aggregate_exprs: Vec<Arc<dyn AggregateExpr>>,
+}
+
+impl SortAggregate {
+    pub fn new(
+        schema: SchemaRef,
+        input: Arc<dyn PhysicalPlan>,
+        group_exprs: Vec<Arc<dyn PhysicalExpr>>,
+        aggregate_exprs: Vec<Arc<dyn AggregateExpr>>,
+    ) -> Self {
+        Self {
+            schema,
+            input,
+            group_exprs,
+            aggregate_exprs,
+        }
+    }
+}
+
+/// the group being aggregated, its rows may span several input batches
+struct Group {
+    /// the group values converted to a row, compared with the rows of the next batches
+    key: OwnedRow,
+    /// the group values of the first row of the group
+    values: Vec<ArrayRef>,
+    accumulators: Vec<Box<dyn Accumulator>>,
+}
+
+/// the state of the aggregation while the input is read
+struct SortAggregateState {
+    schema: SchemaRef,
+    group_exprs: Vec<Arc<dyn PhysicalExpr>>,
+    aggregate_exprs: Vec<Arc<dyn AggregateExpr>>,
+    /// converts the group values to rows to compare them
+    group_converter: RowConverter,
+    /// converts the finished groups to the output columns
+    output_converter: RowConverter,
+    /// the groups finished since the last output batch
+    output: Rows,
+    current: Option<Group>,
+}
+
+impl SortAggregateState {
+    fn try_new(aggregate: &SortAggregate) -> Result<Self> {
+        let sort_fields = |fields: &[FieldRef]| {
+            fields
+                .iter()
+                .map(|field| SortField::new(field.data_type().clone()))
+                .collect::<Vec<_>>()
+        };
+        let fields = aggregate.schema.fields();
+        let group_converter = RowConverter::new(sort_fields(&fields[..aggregate.group_exprs.len()]))?;
+        let output_converter = RowConverter::new(sort_fields(&fields[..]))?;
+        let output = output_converter.empty_rows(64, 1024);
+
+        Ok(Self {
+            schema: aggregate.schema.clone(),
+            group_exprs: aggregate.group_exprs.clone(),
+            aggregate_exprs: aggregate.aggregate_exprs.clone(),
+            group_converter,
+            output_converter,
+            output,
+            current: None,
+        })
+    }
+
+    /// update the groups with the rows of the batch, the groups finished by the batch are output
+    fn aggregate_batch(&mut self, batch: &RecordBatch) -> Result<Option<RecordBatch>> {
+        let group_values = self
+            .group_exprs
+            .iter()
+            .map(|expr| expr.evaluate(batch))
+            .collect::<Result<Vec<_>>>()?;
+        let input_values = self
+            .aggregate_exprs
+            .iter()
+            .map(|expr| expr.expression().evaluate(batch))
+            .collect::<Result<Vec<_>>>()?;
+        let keys = self.group_converter.convert_columns(&group_values)?;
+
+        // each run of equal keys is accumulated at once
+        let mut start = 0;
+        while start < batch.num_rows() {
+            let mut end = start + 1;
+            while end < batch.num_rows() && keys.row(end) == keys.row(start) {
+                end += 1;
+            }
+
+            let same_group = self
+                .current
+                .as_ref()
+                .is_some_and(|group| group.key.row() == keys.row(start));
+            if !same_group {
+                self.finish_group()?;
+                self.current = Some(Group {
+                    key: keys.row(start).owned(),
+                    values: group_values.iter().map(|values| values.slice(start, 1)).collect(),
+                    accumulators: self
+                        .aggregate_exprs
+                        .iter()
+                        .map(|expr| expr.create_accumulator())
+                        .collect::<Result<Vec<_>>>()?,
+                });
+            }
+            if let Some(group) = &mut self.current {
+                for (accumulator, values) in group.accumulators.iter_mut().zip(&input_values) {
+                    accumulator.accumluate(&values.slice(start, end - start))?;
+                }
+            }
+            start = end;
+        }
+
+        self.output_batch()
+    }
+
+    /// the aggregate values of the current group appended to the output
+    fn finish_group(&mut self) -> Result<()> {
+        let Some(Group {
+            mut values,
+            accumulators,
+            ..
+        }) = self.current.take()
+        else {
+            return Ok(());
+        };
+        for mut accumulator in accumulators {
+            values.push(accumulator.evaluate().and_then(|value| value.to_array(1))?);
+        }
+
+        self.output_converter
+            .append(&mut self.output, &values)
+            .map_err(|e| arrow_err!(e))
+    }
+
+    /// the finished groups not output yet, None if there is none
+    fn output_batch(&mut self) -> Result<Option<RecordBatch>> {
+        if self.output.num_rows() == 0 {
+            return Ok(None);
+        }
+        let output = std::mem::replace(&mut self.output, self.output_converter.empty_rows(64, 1024));
+        let columns = self.output_converter.convert_rows(&output)?;
+
+        RecordBatch::try_new(self.schema.clone(), columns)
+            .map(Some)
+            .map_err(|e| arrow_err!(e))
+    }
+}
+
+impl PhysicalPlan for SortAggregate {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        self.execute_iter()?.collect()
+    }
+
+    /// the groups finished by an input batch are output when the iterator is advanced, the last group at the end
+    fn execute_iter(&self) -> Result<RecordBatchStream> {
+        let mut input = self.input.execute_iter()?;
+        let mut state = Some(SortAggregateState::try_new(self)?);
+
+        Ok(Box::new(std::iter::from_fn(move || loop {
+            let output = match input.next() {
+                Some(Ok(batch)) => state.as_mut()?.aggregate_batch(&batch),
+                Some(Err(e)) => Err(e),
+                None => {
+                    let mut state = state.take()?;
+                    state.finish_group().and_then(|_| state.output_batch())
+                }
+            };
+            match output {
+                Ok(Some(batch)) => return Some(Ok(batch)),
+                Ok(None) if state.is_some() => continue,
+                Ok(None) => return None,
+                Err(e) => {
+                    state = None;
+                    return Some(Err(e));
+                }
+            }
+        })))
+    }
+
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        Some(vec![self.input.clone()])
+    }
+
+    fn describe(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for SortAggregate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SortAggregate: group_expr=[{}], aggr_expr=[{}]",
+            format_exprs(&self.group_exprs),
+            format_exprs(&self.aggregate_exprs)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::datatypes::DataType;
+
+    use super::SortAggregate;
+    use crate::build_schema;
+    use crate::datasource::memory::MemoryTable;
+    use crate::physical::expr::{Column, CountAggregateExpr, MaxAggregateExpr};
+    use crate::physical::plan::{PhysicalPlan, Scan};
+    use crate::test_utils::{assert_batch_eq, build_record_i32};
+
+    #[test]
+    fn test_sort_aggregate() {
+        let schema = Arc::new(build_schema!(
+            ("b", DataType::Int32),
+            ("MAX(a)", DataType::Int32),
+            ("COUNT(a)", DataType::Int64)
+        ));
+        let input_schema = Arc::new(build_schema!(("a", DataType::Int32), ("b", DataType::Int32)));
+        let batch = build_record_i32(
+            input_schema.clone(),
+            vec![vec![5, 1, 3, 9, 2, 4, 8], vec![1, 1, 1, 2, 3, 3, 3]],
+        );
+        let table = Arc::new(MemoryTable::try_new(input_schema.clone(), vec![batch]).unwrap());
+        let aggregate = |batch_size: usize| {
+            let scan = Scan::new(input_schema.clone(), table.clone(), None).with_batch_size(batch_size);
+            SortAggregate::new(
+                schema.clone(),
+                Arc::new(scan),
+                vec![Arc::new(Column::new("b", 1))],
+                vec![
+                    Arc::new(MaxAggregateExpr::new(Arc::new(Column::new("a", 0)), DataType::Int32)),
+                    Arc::new(CountAggregateExpr::new(Arc::new(Column::new("a", 0)))),
+                ],
+            )
+        };
+        let expected = vec![
+            "+---+--------+----------+",
+            "| b | MAX(a) | COUNT(a) |",
+            "+---+--------+----------+",
+            "| 1 | 5      | 3        |",
+            "| 2 | 9      | 1        |",
+            "| 3 | 8      | 3        |",
+            "+---+--------+----------+",
+        ];
+
+        assert_batch_eq(&aggregate(100).execute().unwrap(), expected.clone());
+        // the groups span the batches, a group is output once the next group is read
+        let batches = aggregate(2)
+            .execute_iter()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            batches.iter().map(|batch| batch.num_rows()).collect::<Vec<_>>(),
+            vec![1, 1, 1]
+        );
+        assert_batch_eq(&batches, expected);
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, BooleanArray, RecordBatch};
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::SchemaRef;
+use arrow::row::{RowConverter, SortField};
+
+use crate::arrow_err;
+use crate::error::{Error, Result};
+use crate::physical::expr::PhysicalExpr;
+use crate::physical::plan::PhysicalPlan;
+
+/// Keeps the first row of the input for each distinct value of the `DISTINCT ON` expressions, the values are compared
+/// by their row format encoding in which NULLs are equal to each other.
+pub struct DistinctOn {
+    input: Arc<dyn PhysicalPlan>,
+    on_expr: Vec<Arc<dyn PhysicalExpr>>,
+}
+
+impl DistinctOn {
+    pub fn new(input: Arc<dyn PhysicalPlan>, on_expr: Vec<Arc<dyn PhysicalExpr>>) -> Self {
+        Self { input, on_expr }
+    }
+}
+
+impl PhysicalPlan for DistinctOn {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let mut converter = None;
+        let mut seen = HashSet::new();
+        let mut batches = vec![];
+
+        for batch in self.input.execute()? {
+            let keys = self
+                .on_expr
+                .iter()
+                .map(|expr| expr.evaluate(&batch))
+                .collect::<Result<Vec<ArrayRef>>>()?;
+
+            let converter = match converter.as_mut() {
+                Some(converter) => converter,
+                None => {
+                    let fields = keys.iter().map(|key| SortField::new(key.data_type().clone())).collect();
+                    converter.insert(RowConverter::new(fields).map_err(|e| arrow_err!(e))?)
+                }
+            };
+            let rows = converter.convert_columns(&keys).map_err(|e| arrow_err!(e))?;
+
+            let mask = rows
+                .iter()
+                .map(|row| Some(seen.insert(row.owned())))
+                .collect::<BooleanArray>();
+            let batch = filter_record_batch(&batch, &mask).map_err(|e| arrow_err!(e))?;
+            if batch.num_rows() > 0 {
+                batches.push(batch);
+            }
+        }
+
+        Ok(batches)
+    }
+
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        Some(vec![self.input.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use super::DistinctOn;
+    use crate::datasource::memory::MemoryTable;
+    use crate::physical::expr::Column;
+    use crate::physical::plan::{PhysicalPlan, Scan};
+    use crate::test_utils::assert_batch_eq;
+
+    #[test]
+    fn test_distinct_on() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+        let batch = |a: Vec<Option<i32>>, b: Vec<&str>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(a)), Arc::new(StringArray::from(b))],
+            )
+            .unwrap()
+        };
+        let batches = vec![
+            batch(vec![Some(1), None, Some(1)], vec!["x", "y", "z"]),
+            batch(vec![Some(2), None, Some(1)], vec!["u", "v", "w"]),
+        ];
+        let source = Arc::new(MemoryTable::try_new(schema.clone(), batches).unwrap());
+        let input = Arc::new(Scan::new(schema, source, None));
+
+        let distinct = DistinctOn::new(input, vec![Arc::new(Column::new("a", 0))]);
+
+        assert_batch_eq(
+            &distinct.execute().unwrap(),
+            vec![
+                "+---+---+",
+                "| a | b |",
+                "+---+---+",
+                "| 1 | x |",
+                "|   | y |",
+                "| 2 | u |",
+                "+---+---+",
+            ],
+        );
+    }
+}
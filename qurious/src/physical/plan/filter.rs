@@ -1,49 +1,145 @@
 use std::sync::Arc;
 
+use super::{execute_partitions, parallel_map, PhysicalPlan};
+use crate::error::{Error, Result};
+use crate::physical::expr::PhysicalExpr;
+use crate::provider::table::RecordBatchStream;
 use arrow::array::AsArray;
 use arrow::compute::filter_record_batch;
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
 
-use super::PhysicalPlan;
-use crate::error::{Error, Result};
-use crate::physical::expr::PhysicalExpr;
-
 pub struct Filter {
     input: Arc<dyn PhysicalPlan>,
     predicate: Arc<dyn PhysicalExpr>,
+    /// the number of threads filtering the batches, the partitions of the input are kept when more than one
+    target_partitions: usize,
 }
 
 impl Filter {
     pub fn new(input: Arc<dyn PhysicalPlan>, predicate: Arc<dyn PhysicalExpr>) -> Self {
-        Self { input, predicate }
+        Self {
+            input,
+            predicate,
+            target_partitions: 1,
+        }
+    }
+
+    pub fn with_target_partitions(self, target_partitions: usize) -> Self {
+        Self {
+            target_partitions,
+            ..self
+        }
     }
 }
 
+fn filter_batch(predicate: &dyn PhysicalExpr, batch: RecordBatch) -> Result<RecordBatch> {
+    let ary = predicate.evaluate(&batch)?;
+    filter_record_batch(&batch, ary.as_boolean()).map_err(|e| {
+        Error::ArrowError(
+            e,
+            Some("physical::plan::filter.rs: Filter::execute: filter_record_batch error".to_owned()),
+        )
+    })
+}
+
 impl PhysicalPlan for Filter {
     fn schema(&self) -> SchemaRef {
         self.input.schema()
     }
 
     fn execute(&self) -> Result<Vec<RecordBatch>> {
+        if self.output_partitions() > 1 {
+            return execute_partitions(self).map(|partitions| partitions.concat());
+        }
+        parallel_map(self.input.execute()?, self.target_partitions, |batch| {
+            filter_batch(self.predicate.as_ref(), batch)
+        })
+    }
+
+    /// each batch of the input is filtered when the iterator is advanced
+    fn execute_iter(&self) -> Result<RecordBatchStream> {
+        let predicate = self.predicate.clone();
+        Ok(Box::new(self.input.execute_iter()?.map(move |batch| {
+            batch.and_then(|batch| filter_batch(predicate.as_ref(), batch))
+        })))
+    }
+
+    fn output_partitions(&self) -> usize {
+        match self.target_partitions {
+            1 => 1,
+            _ => self.input.output_partitions(),
+        }
+    }
+
+    fn execute_partition(&self, partition: usize) -> Result<Vec<RecordBatch>> {
+        if self.output_partitions() == 1 {
+            return self.execute();
+        }
         self.input
-            .execute()?
+            .execute_partition(partition)?
             .into_iter()
-            .map(|batch| {
-                let ary = self.predicate.evaluate(&batch)?;
-                filter_record_batch(&batch, &ary.as_boolean()).map_err(|e| {
-                    Error::ArrowError(
-                        e,
-                        Some(format!(
-                            "physical::plan::filter.rs: Filter::execute: filter_record_batch error"
-                        )),
-                    )
-                })
-            })
+            .map(|batch| filter_batch(self.predicate.as_ref(), batch))
             .collect()
     }
 
     fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
         Some(vec![self.input.clone()])
     }
+
+    fn describe(&self) -> String {
+        format!("Filter: {}", self.predicate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{BooleanArray, Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use super::Filter;
+    use crate::datasource::memory::MemoryTable;
+    use crate::error::Result;
+    use crate::physical::expr::Column;
+    use crate::physical::plan::{PhysicalPlan, Scan};
+
+    #[test]
+    fn test_filter_partitions() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("keep", DataType::Boolean, false),
+        ]));
+        let batch = |values: Vec<i64>| {
+            let keep = BooleanArray::from(values.iter().map(|v| v % 2 == 0).collect::<Vec<_>>());
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(values)), Arc::new(keep)])
+        };
+        let source = Arc::new(MemoryTable::try_new_partitioned(
+            schema.clone(),
+            vec![
+                vec![batch(vec![1, 2, 3])?, batch(vec![4])?],
+                vec![batch(vec![5, 6, 8])?],
+            ],
+        )?);
+        let filter = |target_partitions: usize| {
+            Filter::new(
+                Arc::new(Scan::new(schema.clone(), source.clone(), None)),
+                Arc::new(Column::new("keep", 1)),
+            )
+            .with_target_partitions(target_partitions)
+        };
+
+        // the partitions of the input are filtered in parallel, the batches stay in the order of the partitions
+        assert_eq!(filter(1).output_partitions(), 1);
+        assert_eq!(filter(4).output_partitions(), 2);
+        assert_eq!(filter(4).execute_partition(1)?, vec![batch(vec![6, 8])?]);
+        assert_eq!(filter(4).execute()?, filter(1).execute()?);
+        assert_eq!(
+            filter(4).execute()?,
+            vec![batch(vec![2])?, batch(vec![4])?, batch(vec![6, 8])?]
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,627 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, RecordBatch, UInt32Array, UInt32Builder, UInt64Array, UInt64Builder};
+use arrow::compute::concat_batches;
+use arrow::datatypes::SchemaRef;
+use arrow::row::{OwnedRow, RowConverter, SortField};
+
+use super::nest_loop_join::{
+    build_batch_from_indices, join_filter_indices, join_schema, semi_anti_join_output, unmatched_indices,
+};
+use super::{ColumnIndex, JoinFilter, JoinSide};
+use crate::arrow_err;
+use crate::common::join_type::JoinType;
+use crate::error::{Error, Result};
+use crate::execution::memory_pool::{MemoryPool, MemoryReservation};
+use crate::internal_err;
+use crate::physical::expr::PhysicalExpr;
+use crate::physical::plan::spill::{spill_partitioned, SpillFile, MAX_SPILL_LEVEL, SPILL_PARTITIONS};
+use crate::physical::plan::{hash_partition, parallel_map, PhysicalPlan};
+
+pub type JoinOn = Vec<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)>;
+
+/// Hash join matching the rows on the equality of the `on` keys, the left side is built into a hash table probed by
+/// the right rows. The rows of a pair of matching keys must also satisfy the optional filter.
+///
+/// A NULL key matches nothing unless the key is null-safe (`IS NOT DISTINCT FROM`), then it matches the NULL keys.
+/// The rows are in the order of a nested loop join: the matched pairs by the right row and then the left row,
+/// followed by the unmatched left rows and the unmatched right rows of an outer join.
+///
+/// When a side exceeds the memory limit, both sides are spilled to files by the hash of their keys and the pairs of
+/// files are joined one after another (Grace hash join), the rows are then in the order of the files.
+pub struct HashJoin {
+    left: Arc<dyn PhysicalPlan>,
+    right: Arc<dyn PhysicalPlan>,
+    join_type: JoinType,
+    on: JoinOn,
+    /// whether the NULLs of each key are equal
+    null_safe: Vec<bool>,
+    filter: Option<JoinFilter>,
+    schema: SchemaRef,
+    column_indices: Vec<ColumnIndex>,
+    /// the number of threads probing the hash table, the inputs are also executed in parallel when more than one
+    target_partitions: usize,
+    /// the memory size of the batches of a side buffered before both sides are spilled to disk
+    memory_limit: usize,
+    /// the memory of the query the batches of the sides are reserved in, both sides are also spilled when it is
+    /// exhausted
+    memory_pool: Arc<MemoryPool>,
+    /// the number of files spilled by the executions
+    spilled_files: AtomicUsize,
+}
+
+/// the batches of a side of the join, buffered in memory or spilled to files by the hash of the keys
+enum SideBatches {
+    /// the batches and their memory reserved in the pool until they are joined
+    Memory(Vec<RecordBatch>, MemoryReservation),
+    Spilled(Vec<Option<SpillFile>>),
+}
+
+/// the batches of a partition of a side, `None` for a partition without rows
+type PartitionBatches = Option<Box<dyn Iterator<Item = Result<RecordBatch>>>>;
+
+impl HashJoin {
+    pub fn try_new(
+        left: Arc<dyn PhysicalPlan>,
+        right: Arc<dyn PhysicalPlan>,
+        join_type: JoinType,
+        on: JoinOn,
+        filter: Option<JoinFilter>,
+    ) -> Result<Self> {
+        if on.is_empty() {
+            return internal_err!("HashJoin requires at least one equality key");
+        }
+        let (schema, column_indices) = join_schema(&left.schema(), &right.schema(), &join_type);
+
+        Ok(Self {
+            left,
+            right,
+            join_type,
+            null_safe: vec![false; on.len()],
+            on,
+            filter,
+            schema,
+            column_indices,
+            target_partitions: 1,
+            memory_limit: usize::MAX,
+            memory_pool: Arc::new(MemoryPool::unbounded()),
+            spilled_files: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn with_memory_limit(self, memory_limit: usize) -> Self {
+        Self { memory_limit, ..self }
+    }
+
+    pub fn with_memory_pool(self, memory_pool: Arc<MemoryPool>) -> Self {
+        Self { memory_pool, ..self }
+    }
+
+    /// the number of files the rows of both sides were spilled to
+    pub fn spilled_files(&self) -> usize {
+        self.spilled_files.load(Ordering::Relaxed)
+    }
+
+    pub fn with_target_partitions(self, target_partitions: usize) -> Self {
+        Self {
+            target_partitions,
+            ..self
+        }
+    }
+
+    /// Match the NULLs of the keys flagged, the keys compared with `IS NOT DISTINCT FROM`.
+    pub fn with_null_safe(mut self, null_safe: Vec<bool>) -> Result<Self> {
+        if null_safe.len() != self.on.len() {
+            return internal_err!(
+                "HashJoin has {} keys but {} null-safe flags",
+                self.on.len(),
+                null_safe.len()
+            );
+        }
+        self.null_safe = null_safe;
+        Ok(self)
+    }
+
+    /// the row format of the keys of each row, None if a key which is not null-safe is NULL
+    fn key_rows(&self, keys: &[ArrayRef], converter: &RowConverter) -> Result<Vec<Option<OwnedRow>>> {
+        let rows = converter.convert_columns(keys).map_err(|e| arrow_err!(e))?;
+
+        Ok(rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                keys.iter()
+                    .zip(&self.null_safe)
+                    .all(|(key, null_safe)| *null_safe || key.is_valid(i))
+                    .then(|| row.owned())
+            })
+            .collect())
+    }
+
+    /// the join of the rows of both sides in memory
+    fn join(&self, left: Vec<RecordBatch>, right: Vec<RecordBatch>) -> Result<Vec<RecordBatch>> {
+        let left_batch = concat_batches(&self.left.schema(), &left).map_err(|e| arrow_err!(e))?;
+        let right_batch = concat_batches(&self.right.schema(), &right).map_err(|e| arrow_err!(e))?;
+
+        // build the hash table of the left side, key -> row indices
+        let left_keys = self
+            .on
+            .iter()
+            .map(|(left, _)| left.evaluate(&left_batch))
+            .collect::<Result<Vec<_>>>()?;
+        let fields = left_keys
+            .iter()
+            .map(|key| SortField::new(key.data_type().clone()))
+            .collect();
+        let converter = RowConverter::new(fields).map_err(|e| arrow_err!(e))?;
+
+        let mut hash_table: HashMap<OwnedRow, Vec<u64>> = HashMap::new();
+        for (index, row) in self.key_rows(&left_keys, &converter)?.into_iter().enumerate() {
+            if let Some(row) = row {
+                hash_table.entry(row).or_default().push(index as u64);
+            }
+        }
+
+        // probe the hash table with contiguous chunks of the right rows, the candidate pairs of rows with equal keys
+        let right_keys = self
+            .on
+            .iter()
+            .map(|(_, right)| right.evaluate(&right_batch))
+            .collect::<Result<Vec<_>>>()?;
+        let right_rows = self.key_rows(&right_keys, &converter)?;
+        let chunk_size = right_rows.len().div_ceil(self.target_partitions).max(1);
+        let chunks = (0..right_rows.len()).step_by(chunk_size).collect::<Vec<_>>();
+        let pairs = parallel_map(chunks, self.target_partitions, |start| {
+            let mut li = UInt64Builder::new();
+            let mut ri = UInt32Builder::new();
+            for (index, row) in right_rows.iter().enumerate().skip(start).take(chunk_size) {
+                if let Some(indices) = row.as_ref().and_then(|row| hash_table.get(row)) {
+                    li.append_slice(indices);
+                    ri.append_value_n(index as u32, indices.len());
+                }
+            }
+            match &self.filter {
+                Some(filter) => join_filter_indices(&left_batch, &right_batch, li.finish(), ri.finish(), filter),
+                None => Ok((li.finish(), ri.finish())),
+            }
+        })?;
+        let li = pairs
+            .iter()
+            .flat_map(|(li, _)| li.values().iter().copied())
+            .collect::<UInt64Array>();
+        let ri = pairs
+            .iter()
+            .flat_map(|(_, ri)| ri.values().iter().copied())
+            .collect::<UInt32Array>();
+
+        if self.join_type.is_semi_or_anti() {
+            let mut matched = vec![false; left_batch.num_rows()];
+            li.values().iter().for_each(|i| matched[*i as usize] = true);
+            return semi_anti_join_output(&self.join_type, &left_batch, &matched).map(|batch| vec![batch]);
+        }
+
+        let matched_batch = build_batch_from_indices(
+            self.schema.clone(),
+            &self.column_indices,
+            &left_batch,
+            &right_batch,
+            &li,
+            &ri,
+        )?;
+        if self.join_type == JoinType::Inner {
+            return Ok(vec![matched_batch]);
+        }
+
+        let (li, ri) = unmatched_indices(&self.join_type, &li, &ri, left_batch.num_rows(), right_batch.num_rows());
+        let unmatched_batch = build_batch_from_indices(
+            self.schema.clone(),
+            &self.column_indices,
+            &left_batch,
+            &right_batch,
+            &li,
+            &ri,
+        )?;
+
+        Ok(vec![matched_batch, unmatched_batch])
+    }
+
+    /// the keys of the rows of a side
+    fn side_keys(&self, side: JoinSide) -> Vec<Arc<dyn PhysicalExpr>> {
+        self.on
+            .iter()
+            .map(|(left, right)| match side {
+                JoinSide::Left => left.clone(),
+                JoinSide::Right => right.clone(),
+            })
+            .collect()
+    }
+
+    /// The batches of a side buffered in memory if they do not exceed the memory limit and can be reserved in the
+    /// memory pool, otherwise all the rows of the side are spilled to files by the hash of their keys. The join fails
+    /// if the pool is exhausted once the files are spilled too many times.
+    fn buffer_or_spill(
+        &self,
+        mut input: impl Iterator<Item = Result<RecordBatch>>,
+        side: JoinSide,
+        level: u64,
+    ) -> Result<SideBatches> {
+        let mut reservation = MemoryReservation::new(&self.memory_pool, "HashJoin");
+        let mut batches = vec![];
+        loop {
+            let Some(batch) = input.next() else {
+                return Ok(SideBatches::Memory(batches, reservation));
+            };
+            let batch = batch?;
+            let reserved = reservation.try_grow(batch.get_array_memory_size());
+            batches.push(batch);
+            match reserved {
+                Ok(()) if reservation.size() <= self.memory_limit || level >= MAX_SPILL_LEVEL => {}
+                Err(e) if level >= MAX_SPILL_LEVEL => return Err(e),
+                _ => break,
+            }
+        }
+
+        let schema = match side {
+            JoinSide::Left => self.left.schema(),
+            JoinSide::Right => self.right.schema(),
+        };
+        spill_partitioned(
+            batches.into_iter().map(Ok).chain(input),
+            &schema,
+            &self.side_keys(side),
+            level,
+            &self.spilled_files,
+        )
+        .map(SideBatches::Spilled)
+    }
+
+    /// the batches of a side split by the hash of the keys like the files of a spilled side
+    fn partitions(&self, batches: SideBatches, side: JoinSide, level: u64) -> Result<Vec<PartitionBatches>> {
+        match batches {
+            SideBatches::Memory(batches, _) => {
+                let keys = self.side_keys(side);
+                let mut partitions = vec![vec![]; SPILL_PARTITIONS];
+                for batch in batches {
+                    for (partition, batch) in hash_partition(&batch, &keys, SPILL_PARTITIONS, level + 1)?
+                        .into_iter()
+                        .enumerate()
+                    {
+                        if batch.num_rows() > 0 {
+                            partitions[partition].push(batch);
+                        }
+                    }
+                }
+                Ok(partitions
+                    .into_iter()
+                    .map(|batches| {
+                        (!batches.is_empty())
+                            .then(|| Box::new(batches.into_iter().map(Ok)) as Box<dyn Iterator<Item = _>>)
+                    })
+                    .collect())
+            }
+            SideBatches::Spilled(files) => files
+                .into_iter()
+                .map(|file| {
+                    file.map(|file| Ok(Box::new(file.read()?) as Box<dyn Iterator<Item = _>>))
+                        .transpose()
+                })
+                .collect(),
+        }
+    }
+
+    /// The join of the sides in memory if neither side was spilled. Otherwise the rows with equal keys are in the
+    /// partitions of the same number of both sides, each pair of partitions is joined on its own and spilled again
+    /// if a side still exceeds the memory limit.
+    fn join_spilling(&self, left: SideBatches, right: SideBatches, level: u64) -> Result<Vec<RecordBatch>> {
+        let (left, right) = match (left, right) {
+            (SideBatches::Memory(left, _left_reservation), SideBatches::Memory(right, _right_reservation)) => {
+                return self.join(left, right)
+            }
+            sides => sides,
+        };
+
+        let left_partitions = self.partitions(left, JoinSide::Left, level)?;
+        let right_partitions = self.partitions(right, JoinSide::Right, level)?;
+        let mut output = vec![];
+        for (left, right) in left_partitions.into_iter().zip(right_partitions) {
+            if left.is_none() && right.is_none() {
+                continue;
+            }
+            let left = self.buffer_or_spill(left.into_iter().flatten(), JoinSide::Left, level + 1)?;
+            let right = self.buffer_or_spill(right.into_iter().flatten(), JoinSide::Right, level + 1)?;
+            output.extend(self.join_spilling(left, right, level + 1)?);
+        }
+
+        Ok(output)
+    }
+}
+
+impl PhysicalPlan for HashJoin {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let threads = self.target_partitions.min(2);
+        let mut sides = parallel_map(vec![JoinSide::Left, JoinSide::Right], threads, |side| {
+            let input = match side {
+                JoinSide::Left => &self.left,
+                JoinSide::Right => &self.right,
+            };
+            self.buffer_or_spill(input.execute_iter()?, side, 0)
+        })?;
+        let (right, left) = (sides.pop(), sides.pop());
+        match (left, right) {
+            (Some(left), Some(right)) => self.join_spilling(left, right, 0),
+            _ => internal_err!("HashJoin expects the batches of two sides"),
+        }
+    }
+
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        Some(vec![self.left.clone(), self.right.clone()])
+    }
+
+    fn describe(&self) -> String {
+        let on = self
+            .on
+            .iter()
+            .zip(&self.null_safe)
+            .map(|((left, right), null_safe)| match null_safe {
+                true => format!("{} IS NOT DISTINCT FROM {}", left, right),
+                false => format!("{} = {}", left, right),
+            })
+            .collect::<Vec<_>>();
+        match &self.filter {
+            Some(filter) => format!(
+                "HashJoin: type={}, on=[{}], filter={}",
+                self.join_type,
+                on.join(", "),
+                filter.expr
+            ),
+            None => format!("HashJoin: type={}, on=[{}]", self.join_type, on.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::util::pretty::pretty_format_batches;
+
+    use super::HashJoin;
+    use crate::common::join_type::JoinType;
+    use crate::datasource::memory::MemoryTable;
+    use crate::physical::expr::Column;
+    use crate::physical::plan::{PhysicalPlan, Scan};
+    use crate::test_utils::{assert_batch_eq, build_table_scan_i32, build_table_scan_nullable_i32};
+
+    fn execute(join_type: JoinType) -> Vec<arrow::array::RecordBatch> {
+        let left = build_table_scan_i32(vec![("a", vec![1, 2, 3, 4]), ("b", vec![10, 20, 30, 40])]);
+        let right = build_table_scan_i32(vec![("c", vec![1, 3, 3, 5])]);
+
+        HashJoin::try_new(
+            left,
+            right,
+            join_type,
+            vec![(Arc::new(Column::new("a", 0)), Arc::new(Column::new("c", 0)))],
+            None,
+        )
+        .unwrap()
+        .execute()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_semi_join() {
+        // a left row is output once however many right rows it matches
+        assert_batch_eq(
+            &execute(JoinType::LeftSemi),
+            vec![
+                "+---+----+",
+                "| a | b  |",
+                "+---+----+",
+                "| 1 | 10 |",
+                "| 3 | 30 |",
+                "+---+----+",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_anti_join() {
+        assert_batch_eq(
+            &execute(JoinType::LeftAnti),
+            vec![
+                "+---+----+",
+                "| a | b  |",
+                "+---+----+",
+                "| 2 | 20 |",
+                "| 4 | 40 |",
+                "+---+----+",
+            ],
+        );
+    }
+
+    /// the left keys have duplicates and a NULL, so have the right keys
+    fn execute_nullable(join_type: JoinType, null_safe: bool) -> Vec<RecordBatch> {
+        let left = build_table_scan_nullable_i32(vec![
+            ("a", vec![Some(1), Some(2), None, Some(3), Some(3)]),
+            ("b", vec![Some(10), Some(20), Some(30), Some(40), Some(50)]),
+        ]);
+        let right = build_table_scan_nullable_i32(vec![
+            ("c", vec![Some(3), None, Some(1), Some(3), Some(6)]),
+            ("d", vec![Some(100), Some(200), Some(300), Some(400), Some(500)]),
+        ]);
+
+        HashJoin::try_new(
+            left,
+            right,
+            join_type,
+            vec![(Arc::new(Column::new("a", 0)), Arc::new(Column::new("c", 0)))],
+            None,
+        )
+        .unwrap()
+        .with_null_safe(vec![null_safe])
+        .unwrap()
+        .execute()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_inner_join_duplicate_keys() {
+        // every pair of rows with equal keys is output, the NULL keys match nothing
+        assert_batch_eq(
+            &execute_nullable(JoinType::Inner, false),
+            vec![
+                "+---+----+---+-----+",
+                "| a | b  | c | d   |",
+                "+---+----+---+-----+",
+                "| 3 | 40 | 3 | 100 |",
+                "| 3 | 50 | 3 | 100 |",
+                "| 1 | 10 | 1 | 300 |",
+                "| 3 | 40 | 3 | 400 |",
+                "| 3 | 50 | 3 | 400 |",
+                "+---+----+---+-----+",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_outer_joins_null_keys() {
+        // the rows with a NULL key are unmatched and joined with NULLs
+        assert_batch_eq(
+            &execute_nullable(JoinType::Left, false),
+            vec![
+                "+---+----+---+-----+",
+                "| a | b  | c | d   |",
+                "+---+----+---+-----+",
+                "| 3 | 40 | 3 | 100 |",
+                "| 3 | 50 | 3 | 100 |",
+                "| 1 | 10 | 1 | 300 |",
+                "| 3 | 40 | 3 | 400 |",
+                "| 3 | 50 | 3 | 400 |",
+                "| 2 | 20 |   |     |",
+                "|   | 30 |   |     |",
+                "+---+----+---+-----+",
+            ],
+        );
+        assert_batch_eq(
+            &execute_nullable(JoinType::Right, false),
+            vec![
+                "+---+----+---+-----+",
+                "| a | b  | c | d   |",
+                "+---+----+---+-----+",
+                "| 3 | 40 | 3 | 100 |",
+                "| 3 | 50 | 3 | 100 |",
+                "| 1 | 10 | 1 | 300 |",
+                "| 3 | 40 | 3 | 400 |",
+                "| 3 | 50 | 3 | 400 |",
+                "|   |    |   | 200 |",
+                "|   |    | 6 | 500 |",
+                "+---+----+---+-----+",
+            ],
+        );
+        assert_batch_eq(
+            &execute_nullable(JoinType::Full, false),
+            vec![
+                "+---+----+---+-----+",
+                "| a | b  | c | d   |",
+                "+---+----+---+-----+",
+                "| 3 | 40 | 3 | 100 |",
+                "| 3 | 50 | 3 | 100 |",
+                "| 1 | 10 | 1 | 300 |",
+                "| 3 | 40 | 3 | 400 |",
+                "| 3 | 50 | 3 | 400 |",
+                "| 2 | 20 |   |     |",
+                "|   | 30 |   |     |",
+                "|   |    |   | 200 |",
+                "|   |    | 6 | 500 |",
+                "+---+----+---+-----+",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_null_safe_join() {
+        assert_batch_eq(
+            &execute_nullable(JoinType::Full, true),
+            vec![
+                "+---+----+---+-----+",
+                "| a | b  | c | d   |",
+                "+---+----+---+-----+",
+                "| 3 | 40 | 3 | 100 |",
+                "| 3 | 50 | 3 | 100 |",
+                "|   | 30 |   | 200 |",
+                "| 1 | 10 | 1 | 300 |",
+                "| 3 | 40 | 3 | 400 |",
+                "| 3 | 50 | 3 | 400 |",
+                "| 2 | 20 |   |     |",
+                "|   |    | 6 | 500 |",
+                "+---+----+---+-----+",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_spilling_join() {
+        let table = |names: (&str, &str), modulo: i32, nulls: i32| {
+            let schema = Arc::new(Schema::new(vec![
+                Field::new(names.0, DataType::Int32, true),
+                Field::new(names.1, DataType::Int32, true),
+            ]));
+            let keys = (0..60)
+                .map(|i| (i % nulls != 0).then_some(i % modulo))
+                .collect::<Int32Array>();
+            let values = Int32Array::from_iter_values(0..60);
+            let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(keys), Arc::new(values)]).unwrap();
+            let table = MemoryTable::try_new(schema.clone(), vec![batch]).unwrap();
+            Arc::new(Scan::new(schema, Arc::new(table), None).with_batch_size(7)) as Arc<dyn PhysicalPlan>
+        };
+        let execute = |join_type: JoinType, null_safe: bool, memory_limit: usize| {
+            let join = HashJoin::try_new(
+                table(("a", "b"), 13, 17),
+                table(("c", "d"), 11, 19),
+                join_type,
+                vec![(Arc::new(Column::new("a", 0)), Arc::new(Column::new("c", 0)))],
+                None,
+            )
+            .unwrap()
+            .with_null_safe(vec![null_safe])
+            .unwrap()
+            .with_memory_limit(memory_limit);
+            let batches = join.execute().unwrap();
+            // the sides exceeding the memory limit are spilled
+            assert_eq!(join.spilled_files() > 0, memory_limit < usize::MAX);
+
+            let output = pretty_format_batches(&batches).unwrap().to_string();
+            let mut rows = output.lines().skip(3).map(str::to_owned).collect::<Vec<_>>();
+            rows.sort();
+            rows
+        };
+
+        // the rows of the partitions joined one after another are the rows joined in memory
+        for join_type in [
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+        ] {
+            for null_safe in [false, true] {
+                assert_eq!(
+                    execute(join_type, null_safe, 1),
+                    execute(join_type, null_safe, usize::MAX),
+                    "{} null_safe={}",
+                    join_type,
+                    null_safe
+                );
+            }
+        }
+    }
+}
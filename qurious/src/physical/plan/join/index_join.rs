@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use arrow::array::{RecordBatch, UInt32Array, UInt32Builder, UInt64Array};
+use arrow::compute::concat_batches;
+use arrow::datatypes::SchemaRef;
+
+use super::nest_loop_join::{build_batch_from_indices, join_filter_indices, join_schema};
+use super::{ColumnIndex, JoinFilter};
+use crate::arrow_err;
+use crate::common::join_type::JoinType;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::physical::expr::PhysicalExpr;
+use crate::physical::plan::PhysicalPlan;
+use crate::provider::table::TableProvider;
+
+/// Join looking up the rows of the right table equal to the `on` keys of each left row in an index of the table,
+/// the table is not scanned. The rows of a pair of matching keys must also satisfy the optional filter.
+///
+/// Only `INNER` and `LEFT` joins are supported, the rows are in the order of a nested loop join.
+pub struct IndexJoin {
+    left: Arc<dyn PhysicalPlan>,
+    table: Arc<dyn TableProvider>,
+    /// the scanned columns of the table
+    table_schema: SchemaRef,
+    join_type: JoinType,
+    /// the expressions of the left rows equal to the indexed columns of the table
+    on: Vec<(Arc<dyn PhysicalExpr>, String)>,
+    filter: Option<JoinFilter>,
+    schema: SchemaRef,
+    column_indices: Vec<ColumnIndex>,
+}
+
+impl IndexJoin {
+    pub fn try_new(
+        left: Arc<dyn PhysicalPlan>,
+        table: Arc<dyn TableProvider>,
+        table_schema: SchemaRef,
+        join_type: JoinType,
+        on: Vec<(Arc<dyn PhysicalExpr>, String)>,
+        filter: Option<JoinFilter>,
+    ) -> Result<Self> {
+        if join_type != JoinType::Inner && join_type != JoinType::Left {
+            return internal_err!("IndexJoin does not support {}", join_type);
+        }
+        let columns = on.iter().map(|(_, column)| column.clone()).collect::<Vec<_>>();
+        if !table.has_index(&columns) {
+            return internal_err!("The table has no index of the columns [{}]", columns.join(", "));
+        }
+        let (schema, column_indices) = join_schema(&left.schema(), &table_schema, &join_type);
+
+        Ok(Self {
+            left,
+            table,
+            table_schema,
+            join_type,
+            on,
+            filter,
+            schema,
+            column_indices,
+        })
+    }
+}
+
+impl PhysicalPlan for IndexJoin {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let left_batch = concat_batches(&self.left.schema(), &self.left.execute()?).map_err(|e| arrow_err!(e))?;
+        let keys = self
+            .on
+            .iter()
+            .map(|(key, _)| key.evaluate(&left_batch))
+            .collect::<Result<Vec<_>>>()?;
+        let columns = self.on.iter().map(|(_, column)| column.clone()).collect::<Vec<_>>();
+
+        let (li, right_batch) = self.table.lookup(&columns, &keys)?;
+        let indices = self
+            .table_schema
+            .fields()
+            .iter()
+            .map(|field| right_batch.schema().index_of(field.name()).map_err(|e| arrow_err!(e)))
+            .collect::<Result<Vec<_>>>()?;
+        let right_batch = right_batch.project(&indices).map_err(|e| arrow_err!(e))?;
+
+        let li = UInt64Array::from_iter_values(li.values().iter().map(|i| *i as u64));
+        let ri = UInt32Array::from_iter_values(0..right_batch.num_rows() as u32);
+        let (li, ri) = match &self.filter {
+            Some(filter) => join_filter_indices(&left_batch, &right_batch, li, ri, filter)?,
+            None => (li, ri),
+        };
+        let matched_batch = build_batch_from_indices(
+            self.schema.clone(),
+            &self.column_indices,
+            &left_batch,
+            &right_batch,
+            &li,
+            &ri,
+        )?;
+        if self.join_type == JoinType::Inner {
+            return Ok(vec![matched_batch]);
+        }
+
+        // the left rows without a matching row are joined with NULLs
+        let mut matched = vec![false; left_batch.num_rows()];
+        li.values().iter().for_each(|i| matched[*i as usize] = true);
+        let li = (0..left_batch.num_rows() as u64)
+            .filter(|i| !matched[*i as usize])
+            .collect::<UInt64Array>();
+        let mut ri = UInt32Builder::with_capacity(li.len());
+        ri.append_nulls(li.len());
+        let unmatched_batch = build_batch_from_indices(
+            self.schema.clone(),
+            &self.column_indices,
+            &left_batch,
+            &right_batch,
+            &li,
+            &ri.finish(),
+        )?;
+
+        Ok(vec![matched_batch, unmatched_batch])
+    }
+
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        Some(vec![self.left.clone()])
+    }
+
+    fn describe(&self) -> String {
+        let on = self
+            .on
+            .iter()
+            .map(|(left, column)| format!("{} = {}", left, column))
+            .collect::<Vec<_>>();
+        match &self.filter {
+            Some(filter) => format!(
+                "IndexJoin: type={}, on=[{}], filter={}",
+                self.join_type,
+                on.join(", "),
+                filter.expr
+            ),
+            None => format!("IndexJoin: type={}, on=[{}]", self.join_type, on.join(", ")),
+        }
+    }
+}
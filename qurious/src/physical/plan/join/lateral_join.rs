@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use arrow::array::{RecordBatch, UInt32Builder};
+use arrow::compute::{concat_batches, take_record_batch};
+use arrow::datatypes::{Schema, SchemaRef};
+
+use crate::arrow_err;
+use crate::error::{Error, Result};
+use crate::physical::expr::SubQuery;
+use crate::physical::plan::PhysicalPlan;
+
+/// Nested loop apply of a correlated subquery, `left CROSS JOIN LATERAL (subquery)`.
+///
+/// The subquery is executed with its outer references bound to the values of each left row, rows with
+/// the same outer values share one execution. Each left row is repeated for every row the subquery
+/// returns for it, a left row is dropped if the subquery returns no row.
+pub struct LateralJoin {
+    left: Arc<dyn PhysicalPlan>,
+    subquery: SubQuery,
+    schema: SchemaRef,
+}
+
+impl LateralJoin {
+    pub fn new(left: Arc<dyn PhysicalPlan>, subquery: SubQuery) -> Self {
+        let schema = Schema::new(
+            left.schema()
+                .fields()
+                .iter()
+                .chain(subquery.schema().fields().iter())
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+
+        Self {
+            left,
+            subquery,
+            schema: Arc::new(schema),
+        }
+    }
+
+    fn apply(&self, left_batch: &RecordBatch) -> Result<Option<RecordBatch>> {
+        let (results, indices) = self
+            .subquery
+            .execute_correlated(left_batch, |subquery| subquery.execute_batch())?;
+
+        // the results are concatenated, pair each left row with the rows of its result
+        let mut offset = 0;
+        let offsets = results
+            .iter()
+            .map(|result| {
+                offset += result.num_rows();
+                offset - result.num_rows()
+            })
+            .collect::<Vec<_>>();
+        let mut li = UInt32Builder::new();
+        let mut ri = UInt32Builder::new();
+        for (row, index) in indices.iter().enumerate() {
+            let start = offsets[*index];
+            for i in start..start + results[*index].num_rows() {
+                li.append_value(row as u32);
+                ri.append_value(i as u32);
+            }
+        }
+        if li.values_slice().is_empty() {
+            return Ok(None);
+        }
+
+        let right_batch = concat_batches(&self.subquery.schema(), &results).map_err(|e| arrow_err!(e))?;
+        let left = take_record_batch(left_batch, &li.finish()).map_err(|e| arrow_err!(e))?;
+        let right = take_record_batch(&right_batch, &ri.finish()).map_err(|e| arrow_err!(e))?;
+
+        RecordBatch::try_new(
+            self.schema(),
+            left.columns().iter().chain(right.columns()).cloned().collect(),
+        )
+        .map(Some)
+        .map_err(|e| arrow_err!(e))
+    }
+}
+
+impl PhysicalPlan for LateralJoin {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let mut batches = vec![];
+        for left_batch in self.left.execute()? {
+            if let Some(batch) = self.apply(&left_batch)? {
+                batches.push(batch);
+            }
+        }
+
+        Ok(batches)
+    }
+
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        Some(vec![self.left.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::LateralJoin;
+    use crate::datatypes::operator::Operator;
+    use crate::physical::expr::{BinaryExpr, Column, OuterColumn, OuterRefs, SubQuery};
+    use crate::physical::plan::{Filter, PhysicalPlan};
+    use crate::test_utils::{assert_batch_eq, build_table_scan_i32};
+
+    #[test]
+    fn test_lateral_join() {
+        let left = build_table_scan_i32(vec![("a", vec![1, 0, 2, 1])]);
+        let right = build_table_scan_i32(vec![("c", vec![1, 2, 3])]);
+
+        // SELECT * FROM left CROSS JOIN LATERAL (SELECT c FROM right WHERE c <= left.a)
+        let outer_refs = Arc::new(OuterRefs::default());
+        let predicate = BinaryExpr::new(
+            Arc::new(Column::new("c", 0)),
+            Operator::LtEq,
+            Arc::new(OuterColumn::new("left.a", outer_refs.clone())),
+        );
+        let subquery = SubQuery::new(
+            Arc::new(Filter::new(right, Arc::new(predicate))),
+            vec![("left.a".to_owned(), Arc::new(Column::new("a", 0)))],
+            outer_refs,
+        );
+
+        // the row without any match is dropped
+        assert_batch_eq(
+            &LateralJoin::new(left, subquery).execute().unwrap(),
+            vec![
+                "+---+---+",
+                "| a | c |",
+                "+---+---+",
+                "| 1 | 1 |",
+                "| 2 | 1 |",
+                "| 2 | 2 |",
+                "| 1 | 1 |",
+                "+---+---+",
+            ],
+        );
+    }
+}
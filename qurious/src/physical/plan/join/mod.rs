@@ -1,8 +1,16 @@
 mod cross_join;
+mod hash_join;
+mod index_join;
+mod lateral_join;
 mod nest_loop_join;
+mod sort_merge_join;
 
 pub use cross_join::CrossJoin;
+pub use hash_join::{HashJoin, JoinOn};
+pub use index_join::IndexJoin;
+pub use lateral_join::LateralJoin;
 pub use nest_loop_join::*;
+pub use sort_merge_join::SortMergeJoin;
 
 use crate::common::join_type::JoinType;
 
@@ -2,16 +2,17 @@ use std::sync::Arc;
 
 use arrow::{
     array::{
-        downcast_array, new_null_array, ArrayData, AsArray, BooleanBufferBuilder, RecordBatch, RecordBatchOptions,
-        UInt32Array, UInt32Builder, UInt64Array, UInt64Builder,
+        downcast_array, new_null_array, ArrayData, ArrayRef, AsArray, BooleanArray, BooleanBufferBuilder, RecordBatch,
+        RecordBatchOptions, UInt32Array, UInt32Builder, UInt64Array, UInt64Builder,
     },
-    compute::{self, concat_batches},
+    compute::{self, concat_batches, SortColumn},
     datatypes::{DataType, Field, Schema, SchemaBuilder, SchemaRef},
 };
 
 use crate::{
     common::join_type::JoinType,
-    error::Result,
+    error::{Error, Result},
+    internal_err,
     physical::{expr::PhysicalExpr, plan::PhysicalPlan},
 };
 
@@ -19,7 +20,7 @@ use super::need_produce_result_in_final;
 
 pub type ColumnIndex = (usize, JoinSide);
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum JoinSide {
     Left,
     Right,
@@ -46,6 +47,8 @@ pub struct Join {
     schema: SchemaRef,
     // Schema Indices of left and right, placement of columns
     column_indices: Vec<ColumnIndex>,
+    /// the side whose rows are matched one at a time with all the rows of the other side
+    loop_side: JoinSide,
 }
 
 impl Join {
@@ -66,8 +69,40 @@ impl Join {
             filter,
             schema,
             column_indices,
+            loop_side: JoinSide::Right,
         })
     }
+
+    /// Match the rows of the side one at a time, the smaller side takes fewer iterations. The rows are produced in
+    /// the same order whichever side is looped.
+    pub fn with_loop_side(mut self, loop_side: JoinSide) -> Self {
+        self.loop_side = loop_side;
+        self
+    }
+}
+
+impl Join {
+    /// keep the left rows which have (semi join) or have not (anti join) a matching right row
+    fn semi_anti_join(&self, left_batch: RecordBatch, right_batch: &RecordBatch) -> Result<RecordBatch> {
+        let mut matched = vec![false; left_batch.num_rows()];
+        if right_batch.num_rows() > 0 {
+            let (li, _) = build_join_indices(&left_batch, right_batch, self.filter.as_ref(), self.loop_side)?;
+            li.values().iter().for_each(|i| matched[*i as usize] = true);
+        }
+
+        semi_anti_join_output(&self.join_type, &left_batch, &matched)
+    }
+}
+
+/// the left rows which are matched for a semi join, or the unmatched ones for an anti join
+pub(super) fn semi_anti_join_output(join_type: &JoinType, left: &RecordBatch, matched: &[bool]) -> Result<RecordBatch> {
+    let keep = join_type == &JoinType::LeftSemi;
+    let mask = matched
+        .iter()
+        .map(|matched| Some(*matched == keep))
+        .collect::<BooleanArray>();
+
+    Ok(compute::filter_record_batch(left, &mask)?)
 }
 
 impl PhysicalPlan for Join {
@@ -82,6 +117,10 @@ impl PhysicalPlan for Join {
         let left_batch = concat_batches(&left_schema, self.left.execute()?.as_slice())?;
         let right_batch = concat_batches(&right_schema, self.right.execute()?.as_slice())?;
 
+        if self.join_type.is_semi_or_anti() {
+            return self.semi_anti_join(left_batch, &right_batch).map(|batch| vec![batch]);
+        }
+
         if right_batch.num_rows() == 0 && self.join_type != JoinType::Left && self.join_type != JoinType::Full {
             return Ok(vec![]);
         }
@@ -90,7 +129,7 @@ impl PhysicalPlan for Join {
         // create intermediate record batches for indices and filter
         // apply mask to left and right record batches and take columns
         // join columns to a record batch
-        let (li, ri) = build_join_indices(&left_batch, &right_batch, self.filter.as_ref())?;
+        let (li, ri) = build_join_indices(&left_batch, &right_batch, self.filter.as_ref(), self.loop_side)?;
         let matched_batch = build_batch_from_indices(
             self.schema.clone(),
             &self.column_indices,
@@ -104,56 +143,14 @@ impl PhysicalPlan for Join {
             return Ok(vec![matched_batch]);
         }
 
-        let mut visited_left_indices = build_bitmap(&self.join_type, left_batch.num_rows());
-        let mut visited_right_indices = build_bitmap(&self.join_type, right_batch.num_rows());
-
-        li.values().iter().for_each(|i| {
-            if visited_left_indices.len() > 0 {
-                visited_left_indices.set_bit(*i as usize, true)
-            }
-        });
-        ri.values().iter().for_each(|i| {
-            if visited_right_indices.len() > 0 {
-                visited_right_indices.set_bit(*i as usize, true)
-            }
-        });
-
-        let mut l = UInt64Builder::new();
-        let mut r = UInt32Builder::new();
-
-        if self.join_type == JoinType::Left || self.join_type == JoinType::Full {
-            let left_indices = (0..visited_left_indices.len())
-                .filter_map(|i| (!visited_left_indices.get_bit(i)).then_some(i as u64))
-                .collect::<UInt64Array>();
-
-            let mut right_indices = UInt32Builder::with_capacity(left_indices.len());
-            right_indices.append_nulls(left_indices.len());
-            let right_indices = right_indices.finish();
-
-            l.extend(left_indices.iter());
-            r.extend(right_indices.iter());
-        }
-
-        if self.join_type == JoinType::Right || self.join_type == JoinType::Full {
-            let right_indices = (0..visited_right_indices.len())
-                .filter_map(|i| (!visited_right_indices.get_bit(i)).then_some(i as u32))
-                .collect::<UInt32Array>();
-
-            let mut left_indices = UInt64Builder::with_capacity(right_indices.len());
-            left_indices.append_nulls(right_indices.len());
-            let left_indices = left_indices.finish();
-
-            l.extend(left_indices.iter());
-            r.extend(right_indices.iter());
-        }
-
+        let (l, r) = unmatched_indices(&self.join_type, &li, &ri, left_batch.num_rows(), right_batch.num_rows());
         let unmatched_batch = build_batch_from_indices(
             self.schema.clone(),
             &self.column_indices,
             &left_batch,
             &right_batch,
-            &l.finish(),
-            &r.finish(),
+            &l,
+            &r,
         )?;
 
         Ok(vec![matched_batch, unmatched_batch])
@@ -162,12 +159,28 @@ impl PhysicalPlan for Join {
     fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
         Some(vec![self.left.clone(), self.right.clone()])
     }
+
+    fn describe(&self) -> String {
+        let loop_side = match self.loop_side {
+            JoinSide::Left => ", loop=left",
+            JoinSide::Right => "",
+        };
+        match &self.filter {
+            Some(filter) => format!(
+                "NestedLoopJoin: type={}, filter={}{}",
+                self.join_type, filter.expr, loop_side
+            ),
+            None => format!("NestedLoopJoin: type={}{}", self.join_type, loop_side),
+        }
+    }
 }
 
+/// the indices of the pairs of rows the filter accepts, ordered by the right row and then the left row
 fn build_join_indices(
     left: &RecordBatch,
     right: &RecordBatch,
     filter_join: Option<&JoinFilter>,
+    loop_side: JoinSide,
 ) -> Result<(UInt64Array, UInt32Array)> {
     if right.num_rows() == 0 {
         return Ok((
@@ -176,10 +189,23 @@ fn build_join_indices(
         ));
     }
 
-    let indices = (0..right.num_rows())
-        .map(|row_index| {
-            let li = UInt64Array::from_iter_values(0..(left.num_rows() as u64));
-            let ri = UInt32Array::from(vec![row_index as u32; left.num_rows()]);
+    let pairs = match loop_side {
+        JoinSide::Right => (0..right.num_rows())
+            .map(|row_index| {
+                let li = UInt64Array::from_iter_values(0..(left.num_rows() as u64));
+                (li, UInt32Array::from(vec![row_index as u32; left.num_rows()]))
+            })
+            .collect::<Vec<_>>(),
+        JoinSide::Left => (0..left.num_rows())
+            .map(|row_index| {
+                let ri = UInt32Array::from_iter_values(0..(right.num_rows() as u32));
+                (UInt64Array::from(vec![row_index as u64; right.num_rows()]), ri)
+            })
+            .collect::<Vec<_>>(),
+    };
+    let indices = pairs
+        .into_iter()
+        .map(|(li, ri)| {
             if let Some(filter) = filter_join {
                 join_filter_indices(left, right, li, ri, filter)
             } else {
@@ -194,11 +220,22 @@ fn build_join_indices(
         l.extend(left.iter());
         r.extend(right.iter());
     }
+    let (li, ri) = (l.finish(), r.finish());
+    if loop_side == JoinSide::Right {
+        return Ok((li, ri));
+    }
 
-    Ok((l.finish(), r.finish()))
+    let columns =
+        [Arc::new(ri.clone()) as ArrayRef, Arc::new(li.clone())].map(|values| SortColumn { values, options: None });
+    let order = compute::lexsort_to_indices(&columns, None)?;
+
+    Ok((
+        downcast_array(&compute::take(&li, &order, None)?),
+        downcast_array(&compute::take(&ri, &order, None)?),
+    ))
 }
 
-fn join_filter_indices(
+pub(super) fn join_filter_indices(
     lb: &RecordBatch,
     rb: &RecordBatch,
     li: UInt64Array,
@@ -219,14 +256,20 @@ fn join_filter_indices(
     )?;
 
     let filter_results = filter.expr.evaluate(&intermediate_batch)?;
-    let mast = filter_results.as_boolean();
+    let Some(mast) = filter_results.as_boolean_opt() else {
+        return internal_err!(
+            "The join condition {} must be a boolean, got {}",
+            filter.expr,
+            filter_results.data_type()
+        );
+    };
     let li = compute::filter(&li, &mast)?;
     let ri = compute::filter(&ri, &mast)?;
 
     Ok((downcast_array(&li), downcast_array(&ri)))
 }
 
-fn build_batch_from_indices(
+pub(super) fn build_batch_from_indices(
     schema: SchemaRef,
     columns_index: &[ColumnIndex],
     lb: &RecordBatch,
@@ -267,6 +310,61 @@ fn build_batch_from_indices(
     Ok(RecordBatch::try_new(schema, columns)?)
 }
 
+/// The indices of the rows without a matching row paired with NULLs: the left rows of a left or full join followed
+/// by the right rows of a right or full join.
+pub(super) fn unmatched_indices(
+    join_type: &JoinType,
+    li: &UInt64Array,
+    ri: &UInt32Array,
+    left_rows: usize,
+    right_rows: usize,
+) -> (UInt64Array, UInt32Array) {
+    let mut visited_left_indices = build_bitmap(join_type, left_rows);
+    let mut visited_right_indices = build_bitmap(join_type, right_rows);
+
+    li.values().iter().for_each(|i| {
+        if !visited_left_indices.is_empty() {
+            visited_left_indices.set_bit(*i as usize, true)
+        }
+    });
+    ri.values().iter().for_each(|i| {
+        if !visited_right_indices.is_empty() {
+            visited_right_indices.set_bit(*i as usize, true)
+        }
+    });
+
+    let mut l = UInt64Builder::new();
+    let mut r = UInt32Builder::new();
+
+    if *join_type == JoinType::Left || *join_type == JoinType::Full {
+        let left_indices = (0..visited_left_indices.len())
+            .filter_map(|i| (!visited_left_indices.get_bit(i)).then_some(i as u64))
+            .collect::<UInt64Array>();
+
+        let mut right_indices = UInt32Builder::with_capacity(left_indices.len());
+        right_indices.append_nulls(left_indices.len());
+        let right_indices = right_indices.finish();
+
+        l.extend(left_indices.iter());
+        r.extend(right_indices.iter());
+    }
+
+    if *join_type == JoinType::Right || *join_type == JoinType::Full {
+        let right_indices = (0..visited_right_indices.len())
+            .filter_map(|i| (!visited_right_indices.get_bit(i)).then_some(i as u32))
+            .collect::<UInt32Array>();
+
+        let mut left_indices = UInt64Builder::with_capacity(right_indices.len());
+        left_indices.append_nulls(right_indices.len());
+        let left_indices = left_indices.finish();
+
+        l.extend(left_indices.iter());
+        r.extend(right_indices.iter());
+    }
+
+    (l.finish(), r.finish())
+}
+
 fn build_bitmap(join_type: &JoinType, num_rows: usize) -> BooleanBufferBuilder {
     if need_produce_result_in_final(join_type) {
         let mut buffer = BooleanBufferBuilder::new(num_rows);
@@ -277,11 +375,11 @@ fn build_bitmap(join_type: &JoinType, num_rows: usize) -> BooleanBufferBuilder {
     BooleanBufferBuilder::new(0)
 }
 
-fn join_schema(left: &Schema, right: &Schema, join_type: &JoinType) -> (SchemaRef, Vec<ColumnIndex>) {
+pub(super) fn join_schema(left: &Schema, right: &Schema, join_type: &JoinType) -> (SchemaRef, Vec<ColumnIndex>) {
     let (left_nullable, right_nullable) = match join_type {
         JoinType::Left => (false, true),
         JoinType::Right => (true, false),
-        JoinType::Inner => (false, false),
+        JoinType::Inner | JoinType::LeftSemi | JoinType::LeftAnti => (false, false),
         JoinType::Full => (true, true),
     };
 
@@ -300,9 +398,11 @@ fn join_schema(left: &Schema, right: &Schema, join_type: &JoinType) -> (SchemaRe
         .enumerate()
         .map(|(index, f)| (f, (index, JoinSide::Left)));
 
+    // semi and anti joins only output the left side
     let right_fields = right
         .fields()
         .iter()
+        .filter(|_| !join_type.is_semi_or_anti())
         .map(with_nullable(right_nullable))
         .enumerate()
         .map(|(index, f)| (f, (index, JoinSide::Right)));
@@ -0,0 +1,327 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, RecordBatch, UInt32Builder, UInt64Builder};
+use arrow::compute::concat_batches;
+use arrow::datatypes::SchemaRef;
+use arrow::row::{RowConverter, Rows, SortField};
+
+use super::nest_loop_join::{
+    build_batch_from_indices, join_filter_indices, join_schema, semi_anti_join_output, unmatched_indices,
+};
+use super::{ColumnIndex, JoinFilter, JoinOn};
+use crate::arrow_err;
+use crate::common::join_type::JoinType;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::physical::plan::PhysicalPlan;
+
+/// Sort-merge join matching the rows on the equality of the `on` keys, both sides are sorted on the keys and the
+/// runs of equal keys are merged, no hash table is built. A side already sorted on the keys, ascending with the
+/// NULLs first, is not sorted again. The rows of a pair of matching keys must also satisfy the optional filter.
+///
+/// A NULL key matches nothing unless the key is null-safe (`IS NOT DISTINCT FROM`). The matched pairs are in the
+/// order of the keys, followed by the unmatched left rows and the unmatched right rows of an outer join.
+pub struct SortMergeJoin {
+    left: Arc<dyn PhysicalPlan>,
+    right: Arc<dyn PhysicalPlan>,
+    join_type: JoinType,
+    on: JoinOn,
+    /// whether the NULLs of each key are equal
+    null_safe: Vec<bool>,
+    filter: Option<JoinFilter>,
+    schema: SchemaRef,
+    column_indices: Vec<ColumnIndex>,
+}
+
+/// the keys of the rows of a side in the row format, with the row indices in the order of the keys
+struct SortedKeys {
+    rows: Rows,
+    /// whether the keys of a row can match, a key which is not null-safe is not NULL
+    valid: Vec<bool>,
+    order: Vec<u32>,
+}
+
+impl SortMergeJoin {
+    pub fn try_new(
+        left: Arc<dyn PhysicalPlan>,
+        right: Arc<dyn PhysicalPlan>,
+        join_type: JoinType,
+        on: JoinOn,
+        filter: Option<JoinFilter>,
+    ) -> Result<Self> {
+        if on.is_empty() {
+            return internal_err!("SortMergeJoin requires at least one equality key");
+        }
+        let (schema, column_indices) = join_schema(&left.schema(), &right.schema(), &join_type);
+
+        Ok(Self {
+            left,
+            right,
+            join_type,
+            null_safe: vec![false; on.len()],
+            on,
+            filter,
+            schema,
+            column_indices,
+        })
+    }
+
+    /// Match the NULLs of the keys flagged, the keys compared with `IS NOT DISTINCT FROM`.
+    pub fn with_null_safe(mut self, null_safe: Vec<bool>) -> Result<Self> {
+        if null_safe.len() != self.on.len() {
+            return internal_err!(
+                "SortMergeJoin has {} keys but {} null-safe flags",
+                self.on.len(),
+                null_safe.len()
+            );
+        }
+        self.null_safe = null_safe;
+        Ok(self)
+    }
+
+    fn sorted_keys(&self, keys: &[ArrayRef], converter: &RowConverter) -> Result<SortedKeys> {
+        let rows = converter.convert_columns(keys).map_err(|e| arrow_err!(e))?;
+        let valid = (0..rows.num_rows())
+            .map(|i| {
+                keys.iter()
+                    .zip(&self.null_safe)
+                    .all(|(key, null_safe)| *null_safe || key.is_valid(i))
+            })
+            .collect();
+
+        // the rows of a sorted input are only compared once
+        let mut order = (0..rows.num_rows() as u32).collect::<Vec<_>>();
+        if !order
+            .windows(2)
+            .all(|w| rows.row(w[0] as usize) <= rows.row(w[1] as usize))
+        {
+            order.sort_by(|a, b| rows.row(*a as usize).cmp(&rows.row(*b as usize)));
+        }
+
+        Ok(SortedKeys { rows, valid, order })
+    }
+}
+
+impl PhysicalPlan for SortMergeJoin {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let left_batch = concat_batches(&self.left.schema(), &self.left.execute()?).map_err(|e| arrow_err!(e))?;
+        let right_batch = concat_batches(&self.right.schema(), &self.right.execute()?).map_err(|e| arrow_err!(e))?;
+
+        let left_keys = self
+            .on
+            .iter()
+            .map(|(left, _)| left.evaluate(&left_batch))
+            .collect::<Result<Vec<_>>>()?;
+        let right_keys = self
+            .on
+            .iter()
+            .map(|(_, right)| right.evaluate(&right_batch))
+            .collect::<Result<Vec<_>>>()?;
+        let fields = left_keys
+            .iter()
+            .map(|key| SortField::new(key.data_type().clone()))
+            .collect();
+        let converter = RowConverter::new(fields).map_err(|e| arrow_err!(e))?;
+        let left = self.sorted_keys(&left_keys, &converter)?;
+        let right = self.sorted_keys(&right_keys, &converter)?;
+
+        // merge the sides, each run of equal keys of the left side is paired with the run of the right side
+        let mut li = UInt64Builder::new();
+        let mut ri = UInt32Builder::new();
+        let (mut i, mut j) = (0, 0);
+        while i < left.order.len() && j < right.order.len() {
+            let (l, r) = (left.order[i] as usize, right.order[j] as usize);
+            match left.rows.row(l).cmp(&right.rows.row(r)) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    let key = left.rows.row(l);
+                    let left_end = i + left.order[i..]
+                        .iter()
+                        .take_while(|index| left.rows.row(**index as usize) == key)
+                        .count();
+                    let right_end = j + right.order[j..]
+                        .iter()
+                        .take_while(|index| right.rows.row(**index as usize) == key)
+                        .count();
+                    // the keys of the runs are equal, the rows of both runs are valid or none are
+                    if left.valid[l] && right.valid[r] {
+                        for index in &left.order[i..left_end] {
+                            li.append_value_n(*index as u64, right_end - j);
+                            ri.append_slice(&right.order[j..right_end]);
+                        }
+                    }
+                    (i, j) = (left_end, right_end);
+                }
+            }
+        }
+        let (li, ri) = match &self.filter {
+            Some(filter) => join_filter_indices(&left_batch, &right_batch, li.finish(), ri.finish(), filter)?,
+            None => (li.finish(), ri.finish()),
+        };
+
+        if self.join_type.is_semi_or_anti() {
+            let mut matched = vec![false; left_batch.num_rows()];
+            li.values().iter().for_each(|i| matched[*i as usize] = true);
+            return semi_anti_join_output(&self.join_type, &left_batch, &matched).map(|batch| vec![batch]);
+        }
+
+        let matched_batch = build_batch_from_indices(
+            self.schema.clone(),
+            &self.column_indices,
+            &left_batch,
+            &right_batch,
+            &li,
+            &ri,
+        )?;
+        if self.join_type == JoinType::Inner {
+            return Ok(vec![matched_batch]);
+        }
+
+        let (li, ri) = unmatched_indices(&self.join_type, &li, &ri, left_batch.num_rows(), right_batch.num_rows());
+        let unmatched_batch = build_batch_from_indices(
+            self.schema.clone(),
+            &self.column_indices,
+            &left_batch,
+            &right_batch,
+            &li,
+            &ri,
+        )?;
+
+        Ok(vec![matched_batch, unmatched_batch])
+    }
+
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        Some(vec![self.left.clone(), self.right.clone()])
+    }
+
+    fn describe(&self) -> String {
+        let on = self
+            .on
+            .iter()
+            .zip(&self.null_safe)
+            .map(|((left, right), null_safe)| match null_safe {
+                true => format!("{} IS NOT DISTINCT FROM {}", left, right),
+                false => format!("{} = {}", left, right),
+            })
+            .collect::<Vec<_>>();
+        match &self.filter {
+            Some(filter) => format!(
+                "SortMergeJoin: type={}, on=[{}], filter={}",
+                self.join_type,
+                on.join(", "),
+                filter.expr
+            ),
+            None => format!("SortMergeJoin: type={}, on=[{}]", self.join_type, on.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::RecordBatch;
+
+    use super::SortMergeJoin;
+    use crate::common::join_type::JoinType;
+    use crate::physical::expr::Column;
+    use crate::physical::plan::PhysicalPlan;
+    use crate::test_utils::{assert_batch_eq, build_table_scan_nullable_i32};
+
+    /// the left side is sorted on the key, the right side is not
+    fn execute(join_type: JoinType, null_safe: bool) -> Vec<RecordBatch> {
+        let left = build_table_scan_nullable_i32(vec![
+            ("a", vec![None, Some(1), Some(2), Some(3), Some(3)]),
+            ("b", vec![Some(10), Some(20), Some(30), Some(40), Some(50)]),
+        ]);
+        let right = build_table_scan_nullable_i32(vec![
+            ("c", vec![Some(3), None, Some(1), Some(3), Some(6)]),
+            ("d", vec![Some(100), Some(200), Some(300), Some(400), Some(500)]),
+        ]);
+
+        SortMergeJoin::try_new(
+            left,
+            right,
+            join_type,
+            vec![(Arc::new(Column::new("a", 0)), Arc::new(Column::new("c", 0)))],
+            None,
+        )
+        .unwrap()
+        .with_null_safe(vec![null_safe])
+        .unwrap()
+        .execute()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sort_merge_join() {
+        assert_batch_eq(
+            &execute(JoinType::Inner, false),
+            vec![
+                "+---+----+---+-----+",
+                "| a | b  | c | d   |",
+                "+---+----+---+-----+",
+                "| 1 | 20 | 1 | 300 |",
+                "| 3 | 40 | 3 | 100 |",
+                "| 3 | 40 | 3 | 400 |",
+                "| 3 | 50 | 3 | 100 |",
+                "| 3 | 50 | 3 | 400 |",
+                "+---+----+---+-----+",
+            ],
+        );
+        assert_batch_eq(
+            &execute(JoinType::Full, false),
+            vec![
+                "+---+----+---+-----+",
+                "| a | b  | c | d   |",
+                "+---+----+---+-----+",
+                "| 1 | 20 | 1 | 300 |",
+                "| 3 | 40 | 3 | 100 |",
+                "| 3 | 40 | 3 | 400 |",
+                "| 3 | 50 | 3 | 100 |",
+                "| 3 | 50 | 3 | 400 |",
+                "|   | 10 |   |     |",
+                "| 2 | 30 |   |     |",
+                "|   |    |   | 200 |",
+                "|   |    | 6 | 500 |",
+                "+---+----+---+-----+",
+            ],
+        );
+        assert_batch_eq(
+            &execute(JoinType::LeftAnti, false),
+            vec![
+                "+---+----+",
+                "| a | b  |",
+                "+---+----+",
+                "|   | 10 |",
+                "| 2 | 30 |",
+                "+---+----+",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_sort_merge_join_null_safe() {
+        assert_batch_eq(
+            &execute(JoinType::Inner, true),
+            vec![
+                "+---+----+---+-----+",
+                "| a | b  | c | d   |",
+                "+---+----+---+-----+",
+                "|   | 10 |   | 200 |",
+                "| 1 | 20 | 1 | 300 |",
+                "| 3 | 40 | 3 | 100 |",
+                "| 3 | 40 | 3 | 400 |",
+                "| 3 | 50 | 3 | 100 |",
+                "| 3 | 50 | 3 | 400 |",
+                "+---+----+---+-----+",
+            ],
+        );
+    }
+}
@@ -3,9 +3,9 @@ use arrow::datatypes::SchemaRef;
 
 use crate::error::Result;
 use crate::physical::plan::PhysicalPlan;
+use crate::provider::table::RecordBatchStream;
 
 use std::sync::Arc;
-use std::usize;
 
 pub struct Limit {
     pub input: Arc<dyn PhysicalPlan>,
@@ -25,45 +25,89 @@ impl PhysicalPlan for Limit {
     }
 
     fn execute(&self) -> Result<Vec<RecordBatch>> {
-        let max_fetch = self.fetch.unwrap_or(usize::MAX);
         let batchs = self.input.execute()?;
 
         let mut results = vec![];
-        let mut fetched = 0;
+        // the rows still to skip and to fetch, both span the batch boundaries
         let mut skip = self.skip;
+        let mut remaining = self.fetch.unwrap_or(usize::MAX);
 
         for batch in batchs {
-            let rows = batch.num_rows();
+            if remaining == 0 {
+                break;
+            }
 
+            let rows = batch.num_rows();
             if rows <= skip {
                 skip -= rows;
                 continue;
             }
 
-            let new_batch = batch.slice(skip, rows - skip);
-
-            let new_rows = new_batch.num_rows();
-            let remaining = max_fetch - fetched;
-
-            if new_rows <= remaining {
-                results.push(new_batch);
-                fetched += new_rows;
-            } else {
-                results.push(new_batch.slice(0, remaining));
-                break;
-            }
+            let len = (rows - skip).min(remaining);
+            results.push(batch.slice(skip, len));
+            remaining -= len;
+            skip = 0;
         }
 
         Ok(results)
     }
 
+    /// the input is not advanced once the rows are fetched
+    fn execute_iter(&self) -> Result<RecordBatchStream> {
+        let mut input = self.input.execute_iter()?;
+        let mut skip = self.skip;
+        let mut remaining = self.fetch.unwrap_or(usize::MAX);
+
+        Ok(Box::new(std::iter::from_fn(move || {
+            while remaining > 0 {
+                let batch = match input.next()? {
+                    Ok(batch) => batch,
+                    Err(e) => {
+                        remaining = 0;
+                        return Some(Err(e));
+                    }
+                };
+                let rows = batch.num_rows();
+                if rows <= skip {
+                    skip -= rows;
+                    continue;
+                }
+
+                let len = (rows - skip).min(remaining);
+                let batch = batch.slice(skip, len);
+                remaining -= len;
+                skip = 0;
+                return Some(Ok(batch));
+            }
+            None
+        })))
+    }
+
     fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
-        self.input.children()
+        Some(vec![self.input.clone()])
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "Limit: fetch={}, skip={}",
+            self.fetch.map(|x| x.to_string()).unwrap_or("None".to_owned()),
+            self.skip
+        )
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+
+    use crate::datasource::memory::MemoryTable;
+    use crate::error::{Error, Result};
+    use crate::physical::plan::Scan;
+    use crate::provider::table::RecordBatchStream;
+    use crate::{arrow_err, internal_err};
     use crate::{build_table_scan, physical::plan::PhysicalPlan, test_utils::assert_batch_eq};
 
     use super::Limit;
@@ -93,4 +137,75 @@ mod test {
             ],
         )
     }
+
+    #[test]
+    fn test_limit_across_batches() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batches = [vec![1, 2], vec![3], vec![4, 5, 6], vec![7, 8]]
+            .into_iter()
+            .map(|data| RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(data))]).unwrap())
+            .collect();
+        let source = Arc::new(MemoryTable::try_new(schema.clone(), batches).unwrap());
+        let input: Arc<dyn PhysicalPlan> = Arc::new(Scan::new(schema, source, None));
+
+        let execute = |fetch, skip| Limit::new(input.clone(), fetch, skip).execute().unwrap();
+
+        assert_batch_eq(
+            &execute(Some(4), 2),
+            vec!["+---+", "| a |", "+---+", "| 3 |", "| 4 |", "| 5 |", "| 6 |", "+---+"],
+        );
+        assert_batch_eq(
+            &execute(None, 5),
+            vec!["+---+", "| a |", "+---+", "| 6 |", "| 7 |", "| 8 |", "+---+"],
+        );
+        assert!(execute(Some(0), 0).is_empty());
+        assert!(execute(Some(3), 8).is_empty());
+    }
+
+    /// batches of one row followed by an error, the error is only returned if the iterator is advanced past the rows
+    struct RowsThenError(SchemaRef, usize);
+
+    impl PhysicalPlan for RowsThenError {
+        fn schema(&self) -> SchemaRef {
+            self.0.clone()
+        }
+
+        fn execute(&self) -> Result<Vec<RecordBatch>> {
+            internal_err!("the rows are streamed")
+        }
+
+        fn execute_iter(&self) -> Result<RecordBatchStream> {
+            let batches = (0..self.1 as i32)
+                .map(|i| {
+                    RecordBatch::try_new(self.0.clone(), vec![Arc::new(Int32Array::from(vec![i]))])
+                        .map_err(|e| arrow_err!(e))
+                })
+                .chain([internal_err!("polled past the rows")])
+                .collect::<Vec<_>>();
+            Ok(Box::new(batches.into_iter()))
+        }
+
+        fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_limit_iter() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let input: Arc<dyn PhysicalPlan> = Arc::new(RowsThenError(schema, 4));
+        let collect = |fetch, skip| {
+            let batches = Limit::new(input.clone(), fetch, skip).execute_iter().unwrap();
+            batches.collect::<Result<Vec<_>>>()
+        };
+
+        // the input is not advanced once the rows are fetched
+        assert_batch_eq(
+            &collect(Some(2), 1).unwrap(),
+            vec!["+---+", "| a |", "+---+", "| 1 |", "| 2 |", "+---+"],
+        );
+        assert_eq!(collect(Some(4), 0).unwrap().len(), 4);
+        assert!(collect(Some(5), 0).is_err());
+        assert!(collect(None, 3).is_err());
+    }
 }
@@ -0,0 +1,149 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+
+use crate::error::Result;
+use crate::physical::plan::PhysicalPlan;
+
+/// Runtime metrics of an operator, accumulated over all the executions of the operator
+#[derive(Debug, Default)]
+pub struct Metrics {
+    output_rows: AtomicUsize,
+    output_batches: AtomicUsize,
+    /// the elapsed time of the executions, including the time spent in the inputs
+    elapsed_nanos: AtomicU64,
+    /// the memory size of the output batches
+    memory_bytes: AtomicUsize,
+}
+
+impl Metrics {
+    fn record(&self, batches: &[RecordBatch], elapsed: Duration) {
+        self.output_rows
+            .fetch_add(batches.iter().map(|batch| batch.num_rows()).sum(), Ordering::Relaxed);
+        self.output_batches.fetch_add(batches.len(), Ordering::Relaxed);
+        self.elapsed_nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+        self.memory_bytes.fetch_add(
+            batches.iter().map(|batch| batch.get_array_memory_size()).sum(),
+            Ordering::Relaxed,
+        );
+    }
+
+    pub fn output_rows(&self) -> usize {
+        self.output_rows.load(Ordering::Relaxed)
+    }
+
+    pub fn output_batches(&self) -> usize {
+        self.output_batches.load(Ordering::Relaxed)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed_nanos.load(Ordering::Relaxed))
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.memory_bytes.load(Ordering::Relaxed)
+    }
+}
+
+impl std::fmt::Display for Metrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "output_rows={}, output_batches={}, elapsed={:?}, memory={}B",
+            self.output_rows(),
+            self.output_batches(),
+            self.elapsed(),
+            self.memory_bytes()
+        )
+    }
+}
+
+/// Collect the metrics of the wrapped operator for `EXPLAIN ANALYZE`, the planner wraps each operator so the
+/// inputs of an operator are also instrumented.
+pub struct Instrumented {
+    input: Arc<dyn PhysicalPlan>,
+    metrics: Metrics,
+}
+
+impl Instrumented {
+    pub fn new(input: Arc<dyn PhysicalPlan>) -> Self {
+        Self {
+            input,
+            metrics: Metrics::default(),
+        }
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+}
+
+impl PhysicalPlan for Instrumented {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let start = Instant::now();
+        let batches = self.input.execute()?;
+        self.metrics.record(&batches, start.elapsed());
+
+        Ok(batches)
+    }
+
+    fn output_partitions(&self) -> usize {
+        self.input.output_partitions()
+    }
+
+    fn execute_partition(&self, partition: usize) -> Result<Vec<RecordBatch>> {
+        let start = Instant::now();
+        let batches = self.input.execute_partition(partition)?;
+        self.metrics.record(&batches, start.elapsed());
+
+        Ok(batches)
+    }
+
+    /// the children of the wrapped operator, so the instrumented operators form the same tree
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        self.input.children()
+    }
+
+    fn describe(&self) -> String {
+        format!("{}, metrics=[{}]", self.input.describe(), self.metrics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::Instrumented;
+    use crate::physical::plan::{Limit, PhysicalPlan};
+    use crate::test_utils::build_table_scan_i32;
+
+    #[test]
+    fn test_instrumented() {
+        let scan: Arc<dyn PhysicalPlan> =
+            Arc::new(Instrumented::new(build_table_scan_i32(vec![("a", vec![1, 2, 3, 4])])));
+        let limit: Arc<dyn PhysicalPlan> = Arc::new(Limit::new(scan, Some(3), 0));
+        let limit = Instrumented::new(limit);
+
+        limit.execute().unwrap();
+        limit.execute().unwrap();
+
+        assert_eq!(limit.metrics().output_rows(), 6);
+        assert_eq!(limit.metrics().output_batches(), 2);
+        assert!(limit.metrics().memory_bytes() > 0);
+        assert!(limit
+            .describe()
+            .starts_with("Limit: fetch=3, skip=0, metrics=[output_rows=6, output_batches=2"));
+
+        // the children are the instrumented inputs of the wrapped operator
+        let scan = limit.children().unwrap().remove(0);
+        assert!(scan.describe().contains("metrics=[output_rows=8, output_batches=2"));
+    }
+}
@@ -1,29 +1,94 @@
 mod aggregate;
+mod distinct;
 mod empty;
 mod filter;
 mod join;
 mod limit;
+mod metrics;
+mod parallel;
 mod projection;
+mod recursive_query;
+mod repartition;
+mod sample;
 mod scan;
+mod set_operation;
 mod sort;
+mod spill;
+mod stream;
+mod topk;
 mod values;
+mod window;
 
 pub use aggregate::*;
+pub use distinct::DistinctOn;
 pub use empty::EmptyRelation;
 pub use filter::Filter;
 pub use join::*;
 pub use limit::Limit;
+pub use metrics::{Instrumented, Metrics};
+pub use parallel::execute_partitions;
+pub(crate) use parallel::parallel_map;
 pub use projection::Projection;
+pub use recursive_query::RecursiveQuery;
+pub(crate) use repartition::{hash_partition, partition_batches};
+pub use repartition::{Partitioning, Repartition};
+pub use sample::Sample;
 pub use scan::Scan;
+pub use set_operation::SetOperation;
 pub use sort::*;
+pub use stream::{batches_stream, SendableRecordBatchStream};
+pub use topk::TopK;
 pub use values::*;
+pub use window::{Window, WindowExpr, WindowFunction};
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::provider::table::RecordBatchStream;
 use arrow::{datatypes::SchemaRef, record_batch::RecordBatch};
+use std::fmt::Display;
 use std::sync::Arc;
 
-pub trait PhysicalPlan {
+pub trait PhysicalPlan: Send + Sync {
     fn schema(&self) -> SchemaRef;
     fn execute(&self) -> Result<Vec<RecordBatch>>;
     fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>>;
+
+    /// the number of partitions of the output, the partitions can be executed in parallel by `execute_partition`
+    fn output_partitions(&self) -> usize {
+        1
+    }
+
+    /// The batches of one partition of the output, the whole output for an operator with one partition.
+    fn execute_partition(&self, partition: usize) -> Result<Vec<RecordBatch>> {
+        match partition {
+            0 => self.execute(),
+            _ => internal_err!("[{}] has no output partition {}", self.describe(), partition),
+        }
+    }
+
+    /// The output as an iterator of batches. The scans, filters, projections, limits and aggregations of sorted input
+    /// compute a batch when the iterator is advanced, the other operators compute their whole output before the
+    /// iterator is returned. An operator consuming its input one batch at a time advances the iterator of its input
+    /// on its own thread, there is no executor to block on.
+    fn execute_iter(&self) -> Result<RecordBatchStream> {
+        let batches = self.execute()?;
+        Ok(Box::new(batches.into_iter().map(Ok)))
+    }
+
+    /// The output as a stream of batches, the batches of `execute_iter` computed as the stream is polled.
+    fn execute_stream(&self) -> Result<SendableRecordBatchStream> {
+        let batches = self.execute_iter()?;
+        Ok(Box::pin(futures::stream::iter(batches)))
+    }
+
+    /// One line description of the operator shown by `EXPLAIN`, the name of the operator by default
+    fn describe(&self) -> String {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name).to_owned()
+    }
+}
+
+/// the expressions separated by commas, used to describe an operator
+pub(crate) fn format_exprs<T: Display + ?Sized>(exprs: &[Arc<T>]) -> String {
+    exprs.iter().map(|expr| expr.to_string()).collect::<Vec<_>>().join(", ")
 }
@@ -0,0 +1,81 @@
+use arrow::record_batch::RecordBatch;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+
+use super::PhysicalPlan;
+use crate::error::Result;
+
+/// the batches of each output partition of the plan, the partitions are executed in parallel
+pub fn execute_partitions(plan: &dyn PhysicalPlan) -> Result<Vec<Vec<RecordBatch>>> {
+    let partitions = plan.output_partitions();
+    parallel_map((0..partitions).collect(), partitions, |partition| {
+        plan.execute_partition(partition)
+    })
+}
+
+/// Apply the function to the items in at most `threads` tasks of the global rayon thread pool, each task maps a
+/// contiguous chunk of the items. The pool is shared by the plans of all the sessions, `threads` bounds the tasks of
+/// one call and not the threads the sessions use. A task waiting for its nested tasks runs other tasks of the pool
+/// meanwhile, so a plan must not be executed by a task of the pool that is itself blocked on an executor. The results
+/// are in the order of the items, an error of a task is returned.
+pub(crate) fn parallel_map<T, R, F>(items: Vec<T>, threads: usize, f: F) -> Result<Vec<R>>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> Result<R> + Sync,
+{
+    if threads <= 1 || items.len() <= 1 {
+        return items.into_iter().map(f).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(threads);
+    items.into_par_iter().with_min_len(chunk_size).map(&f).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use super::{execute_partitions, parallel_map};
+    use crate::datasource::memory::MemoryTable;
+    use crate::error::{Error, Result};
+    use crate::internal_err;
+    use crate::physical::plan::{PhysicalPlan, Scan};
+
+    #[test]
+    fn test_parallel_map() -> Result<()> {
+        let squares = parallel_map((0..10).collect(), 4, |i: i32| Ok(i * i))?;
+        assert_eq!(squares, (0..10).map(|i| i * i).collect::<Vec<_>>());
+
+        let result = parallel_map((0..10).collect(), 3, |i: i32| match i {
+            7 => internal_err!("failed at {}", i),
+            _ => Ok(i),
+        });
+        assert!(matches!(result, Err(Error::InternalError(msg)) if msg == "failed at 7"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_partitions() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch = |values: Vec<i64>| RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(values))]);
+        let source = Arc::new(MemoryTable::try_new_partitioned(
+            schema.clone(),
+            vec![vec![batch(vec![1, 2])?], vec![batch(vec![3])?, batch(vec![4])?]],
+        )?);
+
+        let scan = Scan::new(schema.clone(), source, None);
+        assert_eq!(scan.output_partitions(), 2);
+        let partitions = execute_partitions(&scan)?;
+        assert_eq!(
+            partitions,
+            vec![vec![batch(vec![1, 2])?], vec![batch(vec![3])?, batch(vec![4])?]]
+        );
+        assert!(scan.execute_partition(2).is_err());
+
+        Ok(())
+    }
+}
@@ -1,22 +1,49 @@
 use std::sync::Arc;
 
-use arrow::datatypes::SchemaRef;
-use arrow::record_batch::RecordBatch;
-
-use super::PhysicalPlan;
+use super::{execute_partitions, format_exprs, parallel_map, PhysicalPlan};
 use crate::error::{Error, Result};
 use crate::physical::expr::PhysicalExpr;
+use crate::provider::table::RecordBatchStream;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
 
 pub struct Projection {
     input: Arc<dyn PhysicalPlan>,
     schema: SchemaRef,
     exprs: Vec<Arc<dyn PhysicalExpr>>,
+    /// the number of threads projecting the batches, the partitions of the input are kept when more than one
+    target_partitions: usize,
 }
 
 impl Projection {
     pub fn new(schema: SchemaRef, input: Arc<dyn PhysicalPlan>, exprs: Vec<Arc<dyn PhysicalExpr>>) -> Self {
-        Self { input, schema, exprs }
+        Self {
+            input,
+            schema,
+            exprs,
+            target_partitions: 1,
+        }
+    }
+
+    pub fn with_target_partitions(self, target_partitions: usize) -> Self {
+        Self {
+            target_partitions,
+            ..self
+        }
+    }
+}
+
+fn project_batch(schema: &SchemaRef, exprs: &[Arc<dyn PhysicalExpr>], batch: RecordBatch) -> Result<RecordBatch> {
+    let mut columns = vec![];
+    for expr in exprs {
+        columns.push(expr.evaluate(&batch)?);
     }
+    RecordBatch::try_new(schema.clone(), columns).map_err(|e| {
+        Error::ArrowError(
+            e,
+            Some("physical::plan::projection.rs: Projection::execute: RecordBatch::try_new error".to_owned()),
+        )
+    })
 }
 
 impl PhysicalPlan for Projection {
@@ -25,27 +52,45 @@ impl PhysicalPlan for Projection {
     }
 
     fn execute(&self) -> Result<Vec<RecordBatch>> {
+        if self.output_partitions() > 1 {
+            return execute_partitions(self).map(|partitions| partitions.concat());
+        }
+        parallel_map(self.input.execute()?, self.target_partitions, |batch| {
+            project_batch(&self.schema, &self.exprs, batch)
+        })
+    }
+
+    /// each batch of the input is projected when the iterator is advanced
+    fn execute_iter(&self) -> Result<RecordBatchStream> {
+        let (schema, exprs) = (self.schema.clone(), self.exprs.clone());
+        Ok(Box::new(self.input.execute_iter()?.map(move |batch| {
+            batch.and_then(|batch| project_batch(&schema, &exprs, batch))
+        })))
+    }
+
+    fn output_partitions(&self) -> usize {
+        match self.target_partitions {
+            1 => 1,
+            _ => self.input.output_partitions(),
+        }
+    }
+
+    fn execute_partition(&self, partition: usize) -> Result<Vec<RecordBatch>> {
+        if self.output_partitions() == 1 {
+            return self.execute();
+        }
         self.input
-            .execute()?
+            .execute_partition(partition)?
             .into_iter()
-            .map(|batch| {
-                let mut columns = vec![];
-                for expr in &self.exprs {
-                    columns.push(expr.evaluate(&batch)?);
-                }
-                RecordBatch::try_new(self.schema(), columns).map_err(|e| {
-                    Error::ArrowError(
-                        e,
-                        Some(format!(
-                            "physical::plan::projection.rs: Projection::execute: RecordBatch::try_new error"
-                        )),
-                    )
-                })
-            })
+            .map(|batch| project_batch(&self.schema, &self.exprs, batch))
             .collect()
     }
 
     fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
         Some(vec![self.input.clone()])
     }
+
+    fn describe(&self) -> String {
+        format!("Projection: ({})", format_exprs(&self.exprs))
+    }
 }
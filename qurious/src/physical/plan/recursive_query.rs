@@ -0,0 +1,173 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arrow::array::{BooleanArray, RecordBatch};
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::SchemaRef;
+use arrow::row::{OwnedRow, RowConverter, SortField};
+
+use crate::arrow_err;
+use crate::datasource::memory::MemoryTable;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::physical::plan::PhysicalPlan;
+
+pub struct RecursiveQuery {
+    name: String,
+    schema: SchemaRef,
+    static_term: Arc<dyn PhysicalPlan>,
+    recursive_term: Arc<dyn PhysicalPlan>,
+    work_table: Arc<MemoryTable>,
+    is_distinct: bool,
+    max_recursion_depth: usize,
+}
+
+impl RecursiveQuery {
+    pub fn new(
+        name: String,
+        schema: SchemaRef,
+        static_term: Arc<dyn PhysicalPlan>,
+        recursive_term: Arc<dyn PhysicalPlan>,
+        work_table: Arc<MemoryTable>,
+        is_distinct: bool,
+        max_recursion_depth: usize,
+    ) -> Self {
+        Self {
+            name,
+            schema,
+            static_term,
+            recursive_term,
+            work_table,
+            is_distinct,
+            max_recursion_depth,
+        }
+    }
+
+    /// rebuild the batches with the output schema of the recursive query and remove the rows
+    /// that have been seen before if `UNION` is used
+    fn normalize(
+        &self,
+        batches: Vec<RecordBatch>,
+        seen: &mut Option<(RowConverter, HashSet<OwnedRow>)>,
+    ) -> Result<Vec<RecordBatch>> {
+        let mut results = vec![];
+
+        for batch in batches {
+            let mut batch =
+                RecordBatch::try_new(self.schema.clone(), batch.columns().to_vec()).map_err(|e| arrow_err!(e))?;
+
+            if let Some((converter, seen)) = seen {
+                let rows = converter.convert_columns(batch.columns()).map_err(|e| arrow_err!(e))?;
+                let mask = rows
+                    .iter()
+                    .map(|row| Some(seen.insert(row.owned())))
+                    .collect::<BooleanArray>();
+
+                batch = filter_record_batch(&batch, &mask).map_err(|e| arrow_err!(e))?;
+            }
+
+            if batch.num_rows() > 0 {
+                results.push(batch);
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+impl PhysicalPlan for RecursiveQuery {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let mut seen = if self.is_distinct {
+            let fields = self
+                .schema
+                .fields()
+                .iter()
+                .map(|f| SortField::new(f.data_type().clone()))
+                .collect();
+            Some((RowConverter::new(fields).map_err(|e| arrow_err!(e))?, HashSet::new()))
+        } else {
+            None
+        };
+
+        let mut results = vec![];
+        let mut batches = self.normalize(self.static_term.execute()?, &mut seen)?;
+        let mut depth = 0;
+
+        while !batches.is_empty() {
+            if depth == self.max_recursion_depth {
+                self.work_table.replace(vec![])?;
+                return internal_err!(
+                    "Recursive query [{}] exceeded the maximum recursion depth of {}",
+                    self.name,
+                    self.max_recursion_depth
+                );
+            }
+
+            results.extend(batches.iter().cloned());
+            // the recursive term only sees the rows produced by the previous iteration
+            self.work_table.replace(batches)?;
+            batches = self.normalize(self.recursive_term.execute()?, &mut seen)?;
+            depth += 1;
+        }
+
+        self.work_table.replace(vec![])?;
+
+        Ok(results)
+    }
+
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        Some(vec![self.static_term.clone(), self.recursive_term.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use super::RecursiveQuery;
+    use crate::datasource::memory::MemoryTable;
+    use crate::physical::plan::{PhysicalPlan, Scan};
+    use crate::test_utils::assert_batch_eq;
+
+    fn build_query(is_distinct: bool, max_recursion_depth: usize) -> RecursiveQuery {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(vec![1, 2, 2]))]).unwrap();
+        let source = Arc::new(MemoryTable::try_new(schema.clone(), vec![batch]).unwrap());
+        let work_table = Arc::new(MemoryTable::try_new(schema.clone(), vec![]).unwrap());
+
+        // the recursive term returns the rows of the previous iteration as is
+        RecursiveQuery::new(
+            "t".to_owned(),
+            schema.clone(),
+            Arc::new(Scan::new(schema.clone(), source, None)),
+            Arc::new(Scan::new(schema, work_table.clone(), None)),
+            work_table,
+            is_distinct,
+            max_recursion_depth,
+        )
+    }
+
+    #[test]
+    fn test_recursive_query_distinct() {
+        let results = build_query(true, 10).execute().unwrap();
+
+        assert_batch_eq(&results, vec!["+---+", "| a |", "+---+", "| 1 |", "| 2 |", "+---+"]);
+    }
+
+    #[test]
+    fn test_recursive_query_max_depth() {
+        let err = build_query(false, 3).execute().unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Internal Error: Recursive query [t] exceeded the maximum recursion depth of 3"
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt::Display;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use arrow::array::{ArrayRef, UInt32Array};
+use arrow::compute;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, SortField};
+
+use super::{format_exprs, parallel_map, PhysicalPlan};
+use crate::arrow_err;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::physical::expr::PhysicalExpr;
+
+/// How the rows are distributed to the output partitions
+#[derive(Clone)]
+pub enum Partitioning {
+    /// the batches are distributed in turn to the partitions
+    RoundRobin(usize),
+    /// the rows are distributed by the hash of the values of the expressions, the rows with equal values are in one
+    /// partition
+    Hash(Vec<Arc<dyn PhysicalExpr>>, usize),
+}
+
+impl Partitioning {
+    pub fn partitions(&self) -> usize {
+        match self {
+            Partitioning::RoundRobin(partitions) | Partitioning::Hash(_, partitions) => *partitions,
+        }
+    }
+}
+
+impl Display for Partitioning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Partitioning::RoundRobin(partitions) => write!(f, "RoundRobin({})", partitions),
+            Partitioning::Hash(exprs, partitions) => write!(f, "Hash([{}], {})", format_exprs(exprs), partitions),
+        }
+    }
+}
+
+/// Redistribute the rows of the input to the output partitions, so the partitions can be processed in parallel.
+/// The input is executed once for all the partitions, each partition is kept until it is executed.
+pub struct Repartition {
+    input: Arc<dyn PhysicalPlan>,
+    partitioning: Partitioning,
+    /// the partitions of the current execution not executed yet
+    partitions: Mutex<Option<Vec<Option<Vec<RecordBatch>>>>>,
+}
+
+impl Repartition {
+    pub fn try_new(input: Arc<dyn PhysicalPlan>, partitioning: Partitioning) -> Result<Self> {
+        if partitioning.partitions() == 0 {
+            return internal_err!("Repartition requires at least one partition");
+        }
+
+        Ok(Self {
+            input,
+            partitioning,
+            partitions: Mutex::new(None),
+        })
+    }
+
+    /// the batches of the input distributed to the partitions
+    fn repartition(&self) -> Result<Vec<Vec<RecordBatch>>> {
+        partition_batches(self.input.execute()?, &self.partitioning)
+    }
+}
+
+/// the batches distributed to the partitions, the batches are hash partitioned in parallel
+pub(crate) fn partition_batches(
+    batches: Vec<RecordBatch>,
+    partitioning: &Partitioning,
+) -> Result<Vec<Vec<RecordBatch>>> {
+    let mut partitions = vec![vec![]; partitioning.partitions()];
+    match partitioning {
+        Partitioning::RoundRobin(n) => batches
+            .into_iter()
+            .enumerate()
+            .for_each(|(i, batch)| partitions[i % n].push(batch)),
+        Partitioning::Hash(exprs, n) => {
+            for batch_partitions in parallel_map(batches, *n, |batch| hash_partition(&batch, exprs, *n, 0))? {
+                for (partition, batch) in batch_partitions.into_iter().enumerate() {
+                    partitions[partition].push(batch);
+                }
+            }
+        }
+    }
+
+    Ok(partitions)
+}
+
+/// The rows of the batch split into `partitions` batches by the hash of the values of the expressions. The hash is
+/// seeded by the salt, the rows of a partition are split again by another salt.
+pub(crate) fn hash_partition(
+    batch: &RecordBatch,
+    exprs: &[Arc<dyn PhysicalExpr>],
+    partitions: usize,
+    salt: u64,
+) -> Result<Vec<RecordBatch>> {
+    let values = exprs
+        .iter()
+        .map(|e| e.evaluate(batch))
+        .collect::<Result<Vec<ArrayRef>>>()?;
+    let converter = RowConverter::new(
+        values
+            .iter()
+            .map(|values| SortField::new(values.data_type().clone()))
+            .collect(),
+    )
+    .map_err(|e| arrow_err!(e))?;
+    let rows = converter.convert_columns(&values).map_err(|e| arrow_err!(e))?;
+
+    let mut indices = vec![vec![]; partitions];
+    for (index, row) in rows.iter().enumerate() {
+        let mut hasher = DefaultHasher::new();
+        salt.hash(&mut hasher);
+        row.as_ref().hash(&mut hasher);
+        indices[hasher.finish() as usize % partitions].push(index as u32);
+    }
+
+    indices
+        .into_iter()
+        .map(|indices| compute::take_record_batch(batch, &UInt32Array::from(indices)).map_err(|e| arrow_err!(e)))
+        .collect()
+}
+
+impl PhysicalPlan for Repartition {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        self.repartition().map(|partitions| partitions.concat())
+    }
+
+    fn output_partitions(&self) -> usize {
+        self.partitioning.partitions()
+    }
+
+    /// The input is executed by the first partition executed, or again by a partition executed twice.
+    fn execute_partition(&self, partition: usize) -> Result<Vec<RecordBatch>> {
+        if partition >= self.output_partitions() {
+            return internal_err!(
+                "Repartition has {} partitions, no partition {}",
+                self.output_partitions(),
+                partition
+            );
+        }
+        let mut guard = self
+            .partitions
+            .lock()
+            .map_err(|e| Error::InternalError(e.to_string()))?;
+        let partitions = match guard.take() {
+            Some(partitions) if partitions[partition].is_some() => partitions,
+            _ => self.repartition()?.into_iter().map(Some).collect(),
+        };
+        let partitions = guard.insert(partitions);
+        let batches = partitions[partition].take().unwrap_or_default();
+        if partitions.iter().all(Option::is_none) {
+            *guard = None;
+        }
+
+        Ok(batches)
+    }
+
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        Some(vec![self.input.clone()])
+    }
+
+    fn describe(&self) -> String {
+        format!("Repartition: partitioning={}", self.partitioning)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{AsArray, Int32Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Int32Type, Schema};
+
+    use super::{Partitioning, Repartition};
+    use crate::datasource::memory::MemoryTable;
+    use crate::error::Result;
+    use crate::physical::expr::Column;
+    use crate::physical::plan::{execute_partitions, PhysicalPlan, Scan};
+    use crate::test_utils::build_table_scan_i32;
+
+    fn values(batches: &[RecordBatch], column: usize) -> Vec<i32> {
+        batches
+            .iter()
+            .flat_map(|batch| batch.column(column).as_primitive::<Int32Type>().values().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn test_hash_repartition() -> Result<()> {
+        let input = build_table_scan_i32(vec![("a", (0..40).collect()), ("b", (0..40).map(|i| i % 5).collect())]);
+        let partitioning = Partitioning::Hash(vec![Arc::new(Column::new("b", 1))], 3);
+        let repartition = Repartition::try_new(input, partitioning)?;
+        assert_eq!(repartition.describe(), "Repartition: partitioning=Hash([b(1)], 3)");
+        assert_eq!(repartition.output_partitions(), 3);
+
+        // the rows of a key are all in one partition
+        let partitions = execute_partitions(&repartition)?;
+        let mut keys = partitions
+            .iter()
+            .map(|batches| {
+                let mut keys = values(batches, 1);
+                keys.sort();
+                keys.dedup();
+                keys
+            })
+            .collect::<Vec<_>>()
+            .concat();
+        keys.sort();
+        assert_eq!(keys, vec![0, 1, 2, 3, 4]);
+
+        let mut rows = values(&partitions.concat(), 0);
+        rows.sort();
+        assert_eq!(rows, (0..40).collect::<Vec<_>>());
+
+        // the partitions of an execution are kept until they are executed
+        assert!(repartition.partitions.lock().unwrap().is_none());
+        assert_eq!(repartition.execute_partition(1)?, partitions[1]);
+        assert!(repartition.partitions.lock().unwrap().is_some());
+        assert_eq!(repartition.execute_partition(1)?, partitions[1]);
+        assert!(repartition.execute_partition(3).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_robin_repartition() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from_iter_values(0..10))])?;
+        let source = Arc::new(MemoryTable::try_new(schema.clone(), vec![batch])?);
+        let input = Arc::new(Scan::new(schema, source, None).with_batch_size(3));
+        let repartition = Repartition::try_new(input, Partitioning::RoundRobin(2))?;
+
+        let partitions = execute_partitions(&repartition)?;
+        assert_eq!(values(&partitions[0], 0), vec![0, 1, 2, 6, 7, 8]);
+        assert_eq!(values(&partitions[1], 0), vec![3, 4, 5, 9]);
+        assert_eq!(values(&repartition.execute()?, 0), vec![0, 1, 2, 6, 7, 8, 3, 4, 5, 9]);
+
+        Ok(())
+    }
+}
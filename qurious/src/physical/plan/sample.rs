@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use arrow::array::{BooleanArray, RecordBatch};
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::SchemaRef;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::arrow_err;
+use crate::common::sample_method::SampleMethod;
+use crate::error::{Error, Result};
+use crate::physical::plan::PhysicalPlan;
+
+/// Keep a random sample of the rows of the input, each row is kept with the probability of `fraction` by
+/// `BERNOULLI` sampling, each batch as a whole by `SYSTEM` sampling which is cheaper but less uniform.
+///
+/// The rows are sampled the same way on each execution if a seed is given.
+pub struct Sample {
+    input: Arc<dyn PhysicalPlan>,
+    method: SampleMethod,
+    fraction: f64,
+    seed: Option<u64>,
+}
+
+impl Sample {
+    pub fn new(input: Arc<dyn PhysicalPlan>, method: SampleMethod, fraction: f64, seed: Option<u64>) -> Self {
+        Self {
+            input,
+            method,
+            fraction,
+            seed,
+        }
+    }
+}
+
+impl PhysicalPlan for Sample {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        let mut batches = vec![];
+        for batch in self.input.execute()? {
+            let batch = match self.method {
+                SampleMethod::Bernoulli => {
+                    let mask = (0..batch.num_rows())
+                        .map(|_| Some(rng.gen_bool(self.fraction)))
+                        .collect::<BooleanArray>();
+                    filter_record_batch(&batch, &mask).map_err(|e| arrow_err!(e))?
+                }
+                SampleMethod::System if rng.gen_bool(self.fraction) => batch,
+                SampleMethod::System => continue,
+            };
+            if batch.num_rows() > 0 {
+                batches.push(batch);
+            }
+        }
+
+        Ok(batches)
+    }
+
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        Some(vec![self.input.clone()])
+    }
+
+    fn describe(&self) -> String {
+        match self.seed {
+            Some(seed) => format!(
+                "Sample: method={}, fraction={}, seed={}",
+                self.method, self.fraction, seed
+            ),
+            None => format!("Sample: method={}, fraction={}", self.method, self.fraction),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::RecordBatch;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use super::Sample;
+    use crate::common::sample_method::SampleMethod;
+    use crate::datasource::memory::MemoryTable;
+    use crate::physical::plan::{PhysicalPlan, Scan};
+    use crate::test_utils::build_record_i32;
+
+    /// a scan of the numbers 0..rows split into batches of 10 rows
+    fn build_scan(rows: i32) -> Arc<dyn PhysicalPlan> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let batches = (0..rows)
+            .step_by(10)
+            .map(|start| build_record_i32(schema.clone(), vec![(start..(start + 10).min(rows)).collect()]))
+            .collect::<Vec<_>>();
+        let datasource = MemoryTable::try_new(schema.clone(), batches).unwrap();
+
+        Arc::new(Scan::new(schema, Arc::new(datasource), None))
+    }
+
+    fn num_rows(batches: &[RecordBatch]) -> usize {
+        batches.iter().map(|batch| batch.num_rows()).sum()
+    }
+
+    #[test]
+    fn test_bernoulli_sample() {
+        let sample = Sample::new(build_scan(1000), SampleMethod::Bernoulli, 0.3, Some(42));
+        let rows = num_rows(&sample.execute().unwrap());
+        assert!((200..400).contains(&rows), "sampled {} rows", rows);
+
+        // the same seed samples the same rows
+        assert_eq!(sample.execute().unwrap(), sample.execute().unwrap());
+
+        let sample = Sample::new(build_scan(100), SampleMethod::Bernoulli, 0.0, None);
+        assert_eq!(num_rows(&sample.execute().unwrap()), 0);
+        let sample = Sample::new(build_scan(100), SampleMethod::Bernoulli, 1.0, None);
+        assert_eq!(num_rows(&sample.execute().unwrap()), 100);
+    }
+
+    #[test]
+    fn test_system_sample() {
+        let sample = Sample::new(build_scan(1000), SampleMethod::System, 0.5, Some(7));
+        let batches = sample.execute().unwrap();
+
+        // the batches are kept or skipped as a whole
+        assert!(batches.iter().all(|batch| batch.num_rows() == 10));
+        assert!((20..80).contains(&batches.len()), "sampled {} batches", batches.len());
+        assert_eq!(batches, sample.execute().unwrap());
+    }
+}
@@ -2,16 +2,23 @@ use std::sync::Arc;
 
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
+use itertools::Itertools;
 
-use crate::error::Result;
-use crate::provider::table::TableProvider;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::logical::expr::LogicalExpr;
+use crate::provider::table::{RecordBatchStream, TableProvider};
 
-use super::PhysicalPlan;
+use super::{execute_partitions, PhysicalPlan};
 
 pub struct Scan {
     schema: SchemaRef,
     datasource: Arc<dyn TableProvider>,
     projections: Option<Vec<String>>,
+    /// the filters passed to the data source to skip data, the rows they reject may still be returned
+    filters: Vec<LogicalExpr>,
+    /// the maximum number of rows of an output batch, a larger batch of the source is split
+    batch_size: Option<usize>,
 }
 
 impl Scan {
@@ -20,8 +27,42 @@ impl Scan {
             schema,
             datasource,
             projections,
+            filters: vec![],
+            batch_size: None,
         }
     }
+
+    pub fn with_filters(self, filters: Vec<LogicalExpr>) -> Self {
+        Self { filters, ..self }
+    }
+
+    pub fn with_batch_size(self, batch_size: usize) -> Self {
+        Self {
+            batch_size: Some(batch_size),
+            ..self
+        }
+    }
+
+    /// the batches of the source split into batches of at most `batch_size` rows
+    fn split_batches(&self, source_batches: RecordBatchStream) -> Result<Vec<RecordBatch>> {
+        let mut batches = vec![];
+        for batch in source_batches {
+            batches.extend(split_batch(batch?, self.batch_size));
+        }
+
+        Ok(batches)
+    }
+}
+
+/// the batch split into batches of at most `batch_size` rows
+fn split_batch(batch: RecordBatch, batch_size: Option<usize>) -> Vec<RecordBatch> {
+    match batch_size {
+        Some(batch_size) => (0..batch.num_rows())
+            .step_by(batch_size)
+            .map(|offset| batch.slice(offset, batch_size.min(batch.num_rows() - offset)))
+            .collect(),
+        None => vec![batch],
+    }
 }
 
 impl PhysicalPlan for Scan {
@@ -30,11 +71,134 @@ impl PhysicalPlan for Scan {
     }
 
     fn execute(&self) -> Result<Vec<RecordBatch>> {
-        self.datasource.scan(self.projections.clone(), &vec![])
+        match self.output_partitions() {
+            1 => self.split_batches(self.datasource.scan_stream(self.projections.clone(), &self.filters)?),
+            _ => execute_partitions(self).map(|partitions| partitions.concat()),
+        }
+    }
+
+    /// the batches of the source read as the iterator is advanced, the partitions are read one after another
+    fn execute_iter(&self) -> Result<RecordBatchStream> {
+        let source_batches: RecordBatchStream = match self.output_partitions() {
+            1 => self.datasource.scan_stream(self.projections.clone(), &self.filters)?,
+            partitions => {
+                let (datasource, projections, filters) =
+                    (self.datasource.clone(), self.projections.clone(), self.filters.clone());
+                Box::new(
+                    (0..partitions)
+                        .map(move |partition| datasource.scan_partition(partition, projections.clone(), &filters))
+                        .flatten_ok(),
+                )
+            }
+        };
+        let batch_size = self.batch_size;
+
+        Ok(Box::new(
+            source_batches
+                .map(move |batch| batch.map(|batch| split_batch(batch, batch_size)))
+                .flatten_ok(),
+        ))
+    }
+
+    /// the partitions of the data source
+    fn output_partitions(&self) -> usize {
+        self.datasource.output_partitions()
+    }
+
+    fn execute_partition(&self, partition: usize) -> Result<Vec<RecordBatch>> {
+        let partitions = self.output_partitions();
+        if partition >= partitions {
+            return internal_err!("Scan has {} partitions, no partition {}", partitions, partition);
+        }
+        if partitions == 1 {
+            return self.execute();
+        }
+        let batches = self
+            .datasource
+            .scan_partition(partition, self.projections.clone(), &self.filters)?;
+
+        self.split_batches(Box::new(batches.into_iter().map(Ok)))
     }
 
     /// Scan is a leaf node and has no child plans
     fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
         None
     }
+
+    fn describe(&self) -> String {
+        let mut options = vec![];
+        if let Some(projections) = &self.projections {
+            options.push(format!("projection=[{}]", projections.join(", ")));
+        }
+        if self.output_partitions() > 1 {
+            options.push(format!("partitions={}", self.output_partitions()));
+        }
+        if !self.filters.is_empty() {
+            let filters = self.filters.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+            options.push(format!("filter=[{}]", filters.join(" AND ")));
+        }
+
+        match options.is_empty() {
+            true => "Scan".to_owned(),
+            false => format!("Scan: {}", options.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use crate::datasource::memory::MemoryTable;
+    use crate::error::Result;
+    use crate::physical::plan::PhysicalPlan;
+    use crate::provider::table::TableProvider;
+
+    use super::Scan;
+
+    #[test]
+    fn test_scan_batch_size() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from_iter_values(0..10))])?;
+        let source = Arc::new(MemoryTable::try_new(schema.clone(), vec![batch])?);
+
+        let batches = Scan::new(schema, source, None).with_batch_size(4).execute()?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(), vec![4, 4, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_partitions() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch = |values: Vec<i64>| RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(values))]);
+        let source = Arc::new(MemoryTable::try_new_partitioned(
+            schema.clone(),
+            vec![
+                vec![batch(vec![1, 2])?, batch(vec![3])?],
+                vec![],
+                vec![batch(vec![4, 5, 6])?],
+            ],
+        )?);
+
+        let scan = Scan::new(schema.clone(), source.clone(), None);
+        assert_eq!(scan.describe(), "Scan: partitions=3");
+        let batches = scan.execute()?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(), vec![2, 1, 3]);
+
+        // an inserted batch goes to the partition with the fewest rows
+        let values = Arc::new(Scan::new(
+            schema.clone(),
+            Arc::new(MemoryTable::try_new(schema.clone(), vec![batch(vec![7])?])?),
+            None,
+        ));
+        source.insert(values)?;
+        assert_eq!(source.scan_partition(1, None, &[])?, vec![batch(vec![7])?]);
+        assert_eq!(source.scan(None, &[])?.iter().map(|b| b.num_rows()).sum::<usize>(), 7);
+
+        Ok(())
+    }
 }
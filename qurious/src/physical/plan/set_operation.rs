@@ -0,0 +1,206 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use arrow::array::{BooleanArray, RecordBatch};
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::SchemaRef;
+use arrow::row::{OwnedRow, RowConverter, SortField};
+
+use crate::arrow_err;
+use crate::common::set_operator::SetOperator;
+use crate::error::{Error, Result};
+use crate::physical::plan::PhysicalPlan;
+
+/// `UNION ALL` concatenates the rows of both inputs, the other operations compare whole rows by hashing
+/// their row format encoding, in which NULLs are equal to each other.
+pub struct SetOperation {
+    op: SetOperator,
+    all: bool,
+    schema: SchemaRef,
+    left: Arc<dyn PhysicalPlan>,
+    right: Arc<dyn PhysicalPlan>,
+}
+
+impl SetOperation {
+    pub fn new(
+        op: SetOperator,
+        all: bool,
+        schema: SchemaRef,
+        left: Arc<dyn PhysicalPlan>,
+        right: Arc<dyn PhysicalPlan>,
+    ) -> Self {
+        Self {
+            op,
+            all,
+            schema,
+            left,
+            right,
+        }
+    }
+
+    /// rebuild the batches with the output schema, the inputs may use different column names
+    fn normalize(&self, batches: Vec<RecordBatch>) -> Result<Vec<RecordBatch>> {
+        batches
+            .into_iter()
+            .filter(|batch| batch.num_rows() > 0)
+            .map(|batch| RecordBatch::try_new(self.schema.clone(), batch.columns().to_vec()).map_err(|e| arrow_err!(e)))
+            .collect()
+    }
+
+    /// keep the rows of the batch for which `keep` returns true
+    fn filter_rows(
+        converter: &RowConverter,
+        batch: &RecordBatch,
+        mut keep: impl FnMut(OwnedRow) -> bool,
+    ) -> Result<RecordBatch> {
+        let rows = converter.convert_columns(batch.columns()).map_err(|e| arrow_err!(e))?;
+        let mask = rows.iter().map(|row| Some(keep(row.owned()))).collect::<BooleanArray>();
+
+        filter_record_batch(batch, &mask).map_err(|e| arrow_err!(e))
+    }
+}
+
+impl PhysicalPlan for SetOperation {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let left = self.normalize(self.left.execute()?)?;
+        let right = self.normalize(self.right.execute()?)?;
+
+        if self.op == SetOperator::Union && self.all {
+            return Ok(left.into_iter().chain(right).collect());
+        }
+
+        let fields = self
+            .schema
+            .fields()
+            .iter()
+            .map(|f| SortField::new(f.data_type().clone()))
+            .collect();
+        let converter = RowConverter::new(fields).map_err(|e| arrow_err!(e))?;
+
+        let batches = match self.op {
+            SetOperator::Union => {
+                let mut seen = HashSet::new();
+                left.iter()
+                    .chain(right.iter())
+                    .map(|batch| Self::filter_rows(&converter, batch, |row| seen.insert(row)))
+                    .collect::<Result<Vec<_>>>()?
+            }
+            SetOperator::Intersect | SetOperator::Except => {
+                // the number of times each row of the right input occurs
+                let mut counts: HashMap<OwnedRow, usize> = HashMap::new();
+                for batch in &right {
+                    let rows = converter.convert_columns(batch.columns()).map_err(|e| arrow_err!(e))?;
+                    for row in rows.iter() {
+                        *counts.entry(row.owned()).or_default() += 1;
+                    }
+                }
+
+                let mut seen = HashSet::new();
+                left.iter()
+                    .map(|batch| {
+                        Self::filter_rows(&converter, batch, |row| {
+                            let count = counts.get_mut(&row);
+                            match (self.op, self.all) {
+                                // each occurrence in the right input matches one occurrence in the left input
+                                (SetOperator::Intersect, true) => match count {
+                                    Some(count) if *count > 0 => {
+                                        *count -= 1;
+                                        true
+                                    }
+                                    _ => false,
+                                },
+                                (SetOperator::Except, true) => match count {
+                                    Some(count) if *count > 0 => {
+                                        *count -= 1;
+                                        false
+                                    }
+                                    _ => true,
+                                },
+                                (SetOperator::Intersect, false) => count.is_some() && seen.insert(row),
+                                _ => count.is_none() && seen.insert(row),
+                            }
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            }
+        };
+
+        Ok(batches.into_iter().filter(|batch| batch.num_rows() > 0).collect())
+    }
+
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        Some(vec![self.left.clone(), self.right.clone()])
+    }
+
+    fn describe(&self) -> String {
+        format!("SetOperation: op={}, all={}", self.op, self.all)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use super::SetOperation;
+    use crate::common::set_operator::SetOperator;
+    use crate::datasource::memory::MemoryTable;
+    use crate::physical::plan::{PhysicalPlan, Scan};
+    use crate::test_utils::assert_batch_eq;
+
+    fn scan(data: Vec<Option<i32>>) -> Arc<dyn PhysicalPlan> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(data))]).unwrap();
+        let source = Arc::new(MemoryTable::try_new(schema.clone(), vec![batch]).unwrap());
+        Arc::new(Scan::new(schema, source, None))
+    }
+
+    fn execute(op: SetOperator, all: bool) -> Vec<RecordBatch> {
+        let left = scan(vec![Some(1), Some(1), Some(2), None, None, Some(3)]);
+        let right = scan(vec![Some(1), None, Some(3), Some(4)]);
+
+        SetOperation::new(op, all, left.schema(), left, right)
+            .execute()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_union() {
+        assert_batch_eq(
+            &execute(SetOperator::Union, false),
+            vec![
+                "+---+", "| a |", "+---+", "| 1 |", "| 2 |", "|   |", "| 3 |", "| 4 |", "+---+",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_intersect() {
+        assert_batch_eq(
+            &execute(SetOperator::Intersect, true),
+            vec!["+---+", "| a |", "+---+", "| 1 |", "|   |", "| 3 |", "+---+"],
+        );
+        assert_batch_eq(
+            &execute(SetOperator::Intersect, false),
+            vec!["+---+", "| a |", "+---+", "| 1 |", "|   |", "| 3 |", "+---+"],
+        );
+    }
+
+    #[test]
+    fn test_except() {
+        assert_batch_eq(
+            &execute(SetOperator::Except, true),
+            vec!["+---+", "| a |", "+---+", "| 1 |", "| 2 |", "|   |", "+---+"],
+        );
+        assert_batch_eq(
+            &execute(SetOperator::Except, false),
+            vec!["+---+", "| a |", "+---+", "| 2 |", "+---+"],
+        );
+    }
+}
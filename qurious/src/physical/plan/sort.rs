@@ -1,3 +1,4 @@
+use std::fmt::Display;
 use std::sync::Arc;
 
 use arrow::array::RecordBatch;
@@ -5,6 +6,7 @@ use arrow::compute::{self, lexsort_to_indices, SortColumn, SortOptions};
 use arrow::datatypes::SchemaRef;
 
 use crate::error::Result;
+use crate::execution::memory_pool::{MemoryPool, MemoryReservation};
 use crate::physical::expr::PhysicalExpr;
 use crate::physical::plan::PhysicalPlan;
 
@@ -17,16 +19,46 @@ impl PhyscialSortExpr {
     pub fn new(expr: Arc<dyn PhysicalExpr>, options: SortOptions) -> Self {
         Self { expr, options }
     }
+
+    pub(crate) fn evaluate_to_sort_column(&self, batch: &RecordBatch) -> Result<SortColumn> {
+        self.expr.evaluate(batch).map(|values| SortColumn {
+            values,
+            options: Some(self.options),
+        })
+    }
+}
+
+/// Sort all the rows of the input in memory, the input batches are reserved in the memory pool and the sort fails
+/// if they exceed the memory limit of the query.
+impl Display for PhyscialSortExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} NULLS {}",
+            self.expr,
+            if self.options.descending { "DESC" } else { "ASC" },
+            if self.options.nulls_first { "FIRST" } else { "LAST" }
+        )
+    }
 }
 
 pub struct Sort {
     exprs: Vec<PhyscialSortExpr>,
     input: Arc<dyn PhysicalPlan>,
+    memory_pool: Arc<MemoryPool>,
 }
 
 impl Sort {
     pub fn new(exprs: Vec<PhyscialSortExpr>, input: Arc<dyn PhysicalPlan>) -> Self {
-        Self { exprs, input }
+        Self {
+            exprs,
+            input,
+            memory_pool: Arc::new(MemoryPool::unbounded()),
+        }
+    }
+
+    pub fn with_memory_pool(self, memory_pool: Arc<MemoryPool>) -> Self {
+        Self { memory_pool, ..self }
     }
 }
 
@@ -36,16 +68,15 @@ impl PhysicalPlan for Sort {
     }
 
     fn execute(&self) -> Result<Vec<RecordBatch>> {
-        let merged_batch = compute::concat_batches(&self.input.schema(), &self.input.execute()?)?;
+        let batches = self.input.execute()?;
+        let mut reservation = MemoryReservation::new(&self.memory_pool, "Sort");
+        reservation.try_grow(batches.iter().map(|batch| batch.get_array_memory_size()).sum())?;
+
+        let merged_batch = compute::concat_batches(&self.input.schema(), &batches)?;
         let sort_columns = self
             .exprs
             .iter()
-            .map(|expr| {
-                expr.expr.evaluate(&merged_batch).map(|array| SortColumn {
-                    values: array,
-                    options: Some(expr.options),
-                })
-            })
+            .map(|expr| expr.evaluate_to_sort_column(&merged_batch))
             .collect::<Result<Vec<_>>>()?;
 
         let indices = lexsort_to_indices(&sort_columns, None)?;
@@ -59,7 +90,13 @@ impl PhysicalPlan for Sort {
     }
 
     fn children(&self) -> Option<Vec<std::sync::Arc<dyn PhysicalPlan>>> {
-        self.input.children()
+        Some(vec![self.input.clone()])
+    }
+
+    fn describe(&self) -> String {
+        let exprs = self.exprs.iter().map(|expr| expr.to_string()).collect::<Vec<_>>();
+
+        format!("Sort: {}", exprs.join(", "))
     }
 }
 
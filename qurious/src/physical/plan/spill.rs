@@ -0,0 +1,147 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use arrow::ipc::reader::StreamReader;
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+
+use super::hash_partition;
+use crate::error::Result;
+use crate::physical::expr::PhysicalExpr;
+
+/// the number of files the rows are spilled to when an operator exceeds its memory limit
+pub(crate) const SPILL_PARTITIONS: usize = 8;
+/// a spilled file exceeding the memory limit is spilled again at most this many times
+pub(crate) const MAX_SPILL_LEVEL: u64 = 3;
+
+/// A temporary file removed when dropped
+struct TempPath(PathBuf);
+
+impl TempPath {
+    fn new() -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "qurious_spill_{}_{}.arrows",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        );
+        Self(std::env::temp_dir().join(name))
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Batches written to a temporary file in the Arrow IPC stream format by an operator whose state exceeds its memory
+/// limit, they are read back once all the batches are written. The file is removed when the spill is dropped.
+pub(crate) struct SpillFile {
+    writer: StreamWriter<BufWriter<File>>,
+    path: TempPath,
+}
+
+impl SpillFile {
+    pub(crate) fn try_new(schema: &SchemaRef) -> Result<Self> {
+        let path = TempPath::new();
+        let writer = StreamWriter::try_new(BufWriter::new(File::create(&path.0)?), schema)?;
+
+        Ok(Self { writer, path })
+    }
+
+    pub(crate) fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.writer.write(batch).map_err(|e| e.into())
+    }
+
+    /// the batches written, in the order they were written
+    pub(crate) fn read(mut self) -> Result<SpillReader> {
+        self.writer.finish()?;
+        let reader = StreamReader::try_new(BufReader::new(File::open(&self.path.0)?), None)?;
+
+        Ok(SpillReader {
+            reader,
+            _path: self.path,
+        })
+    }
+}
+
+/// The rows of the batches spilled to `SPILL_PARTITIONS` files by the hash of the values of the expressions, salted by
+/// the spill level so the rows of a file spilled again are split. The rows with equal values are in one file, no file
+/// is created for a partition without rows.
+pub(crate) fn spill_partitioned(
+    input: impl Iterator<Item = Result<RecordBatch>>,
+    schema: &SchemaRef,
+    exprs: &[Arc<dyn PhysicalExpr>],
+    level: u64,
+    spilled_files: &AtomicUsize,
+) -> Result<Vec<Option<SpillFile>>> {
+    let mut files: Vec<Option<SpillFile>> = (0..SPILL_PARTITIONS).map(|_| None).collect();
+    for batch in input {
+        let partitions = hash_partition(&batch?, exprs, SPILL_PARTITIONS, level + 1)?;
+        for (file, batch) in files.iter_mut().zip(partitions) {
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            if file.is_none() {
+                *file = Some(SpillFile::try_new(schema)?);
+                spilled_files.fetch_add(1, Ordering::Relaxed);
+            }
+            if let Some(file) = file {
+                file.write(&batch)?;
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// the batches of a spill file, the file is removed once the reader is dropped
+pub(crate) struct SpillReader {
+    reader: StreamReader<BufReader<File>>,
+    _path: TempPath,
+}
+
+impl Iterator for SpillReader {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.next().map(|batch| batch.map_err(|e| e.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use super::SpillFile;
+    use crate::error::Result;
+    use crate::test_utils::build_record_i32;
+
+    #[test]
+    fn test_spill_file() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let batches = vec![
+            build_record_i32(schema.clone(), vec![vec![1, 2]]),
+            build_record_i32(schema.clone(), vec![vec![3]]),
+        ];
+
+        let mut spill = SpillFile::try_new(&schema)?;
+        batches.iter().try_for_each(|batch| spill.write(batch))?;
+        let path = spill.path.0.clone();
+        assert!(path.exists());
+
+        let mut reader = spill.read()?;
+        assert_eq!(reader.by_ref().collect::<Result<Vec<_>>>()?, batches);
+        drop(reader);
+        assert!(!path.exists());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+use std::pin::Pin;
+
+use arrow::record_batch::RecordBatch;
+use futures::{stream, Stream};
+
+use crate::error::Result;
+
+/// The batches of an execution produced as the stream is polled, the stream can be sent to another thread
+pub type SendableRecordBatchStream = Pin<Box<dyn Stream<Item = Result<RecordBatch>> + Send>>;
+
+/// a stream of batches already computed
+pub fn batches_stream(batches: Vec<RecordBatch>) -> SendableRecordBatchStream {
+    Box::pin(stream::iter(batches.into_iter().map(Ok)))
+}
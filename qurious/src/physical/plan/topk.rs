@@ -0,0 +1,265 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+
+use arrow::array::{RecordBatch, RecordBatchOptions};
+use arrow::compute::interleave;
+use arrow::datatypes::SchemaRef;
+use arrow::row::{OwnedRow, RowConverter, SortField};
+
+use crate::arrow_err;
+use crate::error::{Error, Result};
+use crate::physical::plan::{PhyscialSortExpr, PhysicalPlan};
+
+/// The first `fetch` rows of the input in the order of the sort expressions. The input batches are streamed through a
+/// max-heap of at most `fetch` rows instead of sorting all the rows like `Sort`, the rows with equal sort keys are in
+/// the order of the input.
+pub struct TopK {
+    exprs: Vec<PhyscialSortExpr>,
+    input: Arc<dyn PhysicalPlan>,
+    fetch: usize,
+}
+
+/// a row kept in the heap, ordered by its sort key and then by its position in the input
+struct TopKRow {
+    key: OwnedRow,
+    /// the number of the row in the input
+    position: usize,
+    /// the index of the batch of the row in the kept batches
+    batch: usize,
+    /// the index of the row in its batch
+    row: usize,
+}
+
+impl PartialEq for TopKRow {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TopKRow {}
+
+impl PartialOrd for TopKRow {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TopKRow {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key).then(self.position.cmp(&other.position))
+    }
+}
+
+impl TopK {
+    pub fn new(exprs: Vec<PhyscialSortExpr>, input: Arc<dyn PhysicalPlan>, fetch: usize) -> Self {
+        Self { exprs, input, fetch }
+    }
+}
+
+impl PhysicalPlan for TopK {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let schema = self.input.schema();
+        let mut converter: Option<RowConverter> = None;
+        let mut heap = BinaryHeap::new();
+        // the batches with a row in the heap, and the number of their rows
+        let mut batches = vec![];
+        let mut kept_rows = 0;
+        let mut position = 0;
+
+        for batch in self.input.execute_iter()? {
+            let batch = batch?;
+            let sort_columns = self
+                .exprs
+                .iter()
+                .map(|expr| expr.evaluate_to_sort_column(&batch))
+                .collect::<Result<Vec<_>>>()?;
+            let converter = match &mut converter {
+                Some(converter) => converter,
+                None => {
+                    let fields = sort_columns
+                        .iter()
+                        .map(|column| {
+                            SortField::new_with_options(
+                                column.values.data_type().clone(),
+                                column.options.unwrap_or_default(),
+                            )
+                        })
+                        .collect();
+                    converter.insert(RowConverter::new(fields).map_err(|e| arrow_err!(e))?)
+                }
+            };
+            let columns = sort_columns.into_iter().map(|column| column.values).collect::<Vec<_>>();
+            let keys = converter.convert_columns(&columns).map_err(|e| arrow_err!(e))?;
+
+            let mut used = false;
+            for (row, key) in keys.iter().enumerate() {
+                // a row replaces the last row of the heap only if it is before it in the order
+                let full = heap.len() >= self.fetch;
+                let before_last = heap.peek().is_some_and(|last: &TopKRow| key < last.key.row());
+                if full && !before_last {
+                    continue;
+                }
+                if full {
+                    heap.pop();
+                }
+                heap.push(TopKRow {
+                    key: key.owned(),
+                    position: position + row,
+                    batch: batches.len(),
+                    row,
+                });
+                used = true;
+            }
+            position += batch.num_rows();
+            if used {
+                kept_rows += batch.num_rows();
+                batches.push(batch);
+            }
+
+            // the rows replaced in the kept batches are released once they are most of the kept rows
+            if batches.len() > 1 && kept_rows > 2 * self.fetch {
+                let mut rows = std::mem::take(&mut heap).into_vec();
+                let batch = take_rows(&schema, &batches, &rows)?;
+                for (index, row) in rows.iter_mut().enumerate() {
+                    row.batch = 0;
+                    row.row = index;
+                }
+                heap = BinaryHeap::from(rows);
+                kept_rows = batch.num_rows();
+                batches = vec![batch];
+            }
+        }
+
+        if heap.is_empty() {
+            return Ok(vec![RecordBatch::new_empty(schema)]);
+        }
+        take_rows(&schema, &batches, &heap.into_sorted_vec()).map(|batch| vec![batch])
+    }
+
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        Some(vec![self.input.clone()])
+    }
+
+    fn describe(&self) -> String {
+        let exprs = self.exprs.iter().map(|expr| expr.to_string()).collect::<Vec<_>>();
+        format!("TopK: {}, fetch={}", exprs.join(", "), self.fetch)
+    }
+}
+
+/// the rows of the kept batches in the order of the heap rows
+fn take_rows(schema: &SchemaRef, batches: &[RecordBatch], rows: &[TopKRow]) -> Result<RecordBatch> {
+    let indices = rows.iter().map(|row| (row.batch, row.row)).collect::<Vec<_>>();
+    let columns = (0..schema.fields().len())
+        .map(|i| {
+            let arrays = batches.iter().map(|batch| batch.column(i).as_ref()).collect::<Vec<_>>();
+            interleave(&arrays, &indices).map_err(|e| arrow_err!(e))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let options = RecordBatchOptions::new().with_row_count(Some(indices.len()));
+
+    RecordBatch::try_new_with_options(schema.clone(), columns, &options).map_err(|e| arrow_err!(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, RecordBatch};
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use super::TopK;
+    use crate::datasource::memory::MemoryTable;
+    use crate::physical::expr::Column;
+    use crate::physical::plan::{PhyscialSortExpr, PhysicalPlan, Scan};
+    use crate::test_utils::assert_batch_eq;
+
+    #[test]
+    fn test_topk() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![
+                    Some(3),
+                    None,
+                    Some(7),
+                    Some(1),
+                    Some(7),
+                    Some(5),
+                    Some(3),
+                ])),
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5, 6, 7])),
+            ],
+        )
+        .unwrap();
+        let table = Arc::new(MemoryTable::try_new(schema.clone(), vec![batch]).unwrap());
+        let topk = |descending: bool, fetch: usize| {
+            let input = Arc::new(Scan::new(schema.clone(), table.clone(), None).with_batch_size(2));
+            let options = SortOptions {
+                descending,
+                nulls_first: false,
+            };
+            TopK::new(
+                vec![PhyscialSortExpr::new(Arc::new(Column::new("a", 0)), options)],
+                input,
+                fetch,
+            )
+            .execute()
+            .unwrap()
+        };
+
+        // the equal keys are in the order of the input
+        assert_batch_eq(
+            &topk(true, 3),
+            vec![
+                "+---+---+",
+                "| a | b |",
+                "+---+---+",
+                "| 7 | 3 |",
+                "| 7 | 5 |",
+                "| 5 | 6 |",
+                "+---+---+",
+            ],
+        );
+        assert_batch_eq(
+            &topk(false, 4),
+            vec![
+                "+---+---+",
+                "| a | b |",
+                "+---+---+",
+                "| 1 | 4 |",
+                "| 3 | 1 |",
+                "| 3 | 7 |",
+                "| 5 | 6 |",
+                "+---+---+",
+            ],
+        );
+        // the fetch beyond the input keeps all the rows
+        assert_batch_eq(
+            &topk(false, 10),
+            vec![
+                "+---+---+",
+                "| a | b |",
+                "+---+---+",
+                "| 1 | 4 |",
+                "| 3 | 1 |",
+                "| 3 | 7 |",
+                "| 5 | 6 |",
+                "| 7 | 3 |",
+                "| 7 | 5 |",
+                "|   | 2 |",
+                "+---+---+",
+            ],
+        );
+        assert_eq!(topk(false, 0)[0].num_rows(), 0);
+    }
+}
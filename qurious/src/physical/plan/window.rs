@@ -0,0 +1,358 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow::array::{new_empty_array, Array, ArrayRef, BooleanArray, Int64Array, RecordBatch, UInt32Array};
+use arrow::compute::{self, lexsort_to_indices, SortColumn};
+use arrow::datatypes::{DataType, SchemaRef};
+
+use crate::common::window_frame::{WindowFrame, WindowFrameBound, WindowFrameUnits};
+use crate::datatypes::scalar::ScalarValue;
+use crate::error::{Error, Result};
+use crate::internal_err;
+use crate::physical::expr::{Accumulator, AggregateExpr, PhysicalExpr};
+use crate::physical::plan::{PhyscialSortExpr, PhysicalPlan};
+
+#[derive(Debug)]
+pub enum WindowFunction {
+    RowNumber,
+    Rank,
+    DenseRank,
+    /// `LAG` (negative offset) and `LEAD` (positive offset), rows out of the partition get the default value
+    Shift {
+        expr: Arc<dyn PhysicalExpr>,
+        offset: i64,
+        default: ScalarValue,
+    },
+    Aggregate(Arc<dyn AggregateExpr>),
+}
+
+pub struct WindowExpr {
+    func: WindowFunction,
+    partition_by: Vec<Arc<dyn PhysicalExpr>>,
+    order_by: Vec<PhyscialSortExpr>,
+    window_frame: WindowFrame,
+    return_type: DataType,
+}
+
+impl WindowExpr {
+    pub fn new(
+        func: WindowFunction,
+        partition_by: Vec<Arc<dyn PhysicalExpr>>,
+        order_by: Vec<PhyscialSortExpr>,
+        window_frame: WindowFrame,
+        return_type: DataType,
+    ) -> Self {
+        Self {
+            func,
+            partition_by,
+            order_by,
+            window_frame,
+            return_type,
+        }
+    }
+
+    /// Evaluate the window function for every row of the batch, the result keeps the order of the input rows.
+    pub fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let partition_columns = self
+            .partition_by
+            .iter()
+            .map(|expr| expr.evaluate(batch).map(|values| SortColumn { values, options: None }))
+            .collect::<Result<Vec<_>>>()?;
+        let order_columns = self
+            .order_by
+            .iter()
+            .map(|expr| expr.evaluate_to_sort_column(batch))
+            .collect::<Result<Vec<_>>>()?;
+
+        // sort the rows by the partition keys then the order keys, rows of the same partition are adjacent
+        let sort_columns = partition_columns
+            .iter()
+            .chain(order_columns.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        let indices = if sort_columns.is_empty() {
+            UInt32Array::from_iter_values(0..batch.num_rows() as u32)
+        } else {
+            lexsort_to_indices(&sort_columns, None)?
+        };
+        let sorted = |columns: &[SortColumn]| {
+            columns
+                .iter()
+                .map(|c| compute::take(&c.values, &indices, None))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let partition_values = sorted(&partition_columns)?;
+        let order_values = sorted(&order_columns)?;
+        // the argument of the function in the sorted order
+        let values = match &self.func {
+            WindowFunction::Shift { expr, .. } => Some(compute::take(&expr.evaluate(batch)?, &indices, None)?),
+            WindowFunction::Aggregate(aggr_expr) => {
+                Some(compute::take(&aggr_expr.expression().evaluate(batch)?, &indices, None)?)
+            }
+            _ => None,
+        };
+
+        let mut results = vec![];
+        for partition in ranges(&partition_values, 0..batch.num_rows())? {
+            let peers = ranges(&order_values, partition.clone())?;
+            results.push(self.evaluate_partition(values.as_ref(), partition, peers)?);
+        }
+
+        let sorted_result = if results.is_empty() {
+            new_empty_array(&self.return_type)
+        } else {
+            compute::concat(&results.iter().map(|a| a.as_ref()).collect::<Vec<_>>())?
+        };
+
+        // scatter the results back to the input order
+        let mut inverse = vec![0; indices.len()];
+        for (sorted_index, row) in indices.values().iter().enumerate() {
+            inverse[*row as usize] = sorted_index as u32;
+        }
+
+        compute::take(&sorted_result, &UInt32Array::from(inverse), None).map_err(|e| e.into())
+    }
+
+    /// evaluate the window function for one partition, `peers` are the ranges of rows sharing the same order keys
+    fn evaluate_partition(
+        &self,
+        values: Option<&ArrayRef>,
+        partition: Range<usize>,
+        peers: Vec<Range<usize>>,
+    ) -> Result<ArrayRef> {
+        match (&self.func, values) {
+            (WindowFunction::RowNumber, _) => Ok(Arc::new(Int64Array::from_iter_values(
+                (1..=partition.len()).map(|n| n as i64),
+            ))),
+            (WindowFunction::Rank, _) => {
+                Ok(Arc::new(Int64Array::from_iter_values(peers.iter().flat_map(|peer| {
+                    peer.clone().map(|_| (peer.start - partition.start + 1) as i64)
+                }))))
+            }
+            (WindowFunction::DenseRank, _) => Ok(Arc::new(Int64Array::from_iter_values(
+                peers
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(rank, peer)| peer.clone().map(move |_| rank as i64 + 1)),
+            ))),
+            (WindowFunction::Shift { offset, default, .. }, Some(values)) => {
+                let shifted = partition
+                    .clone()
+                    .map(|i| {
+                        let target = i as i64 + offset;
+                        (target >= partition.start as i64 && target < partition.end as i64).then_some(target as u32)
+                    })
+                    .collect::<UInt32Array>();
+                let shifted_values = compute::cast(&compute::take(values, &shifted, None)?, &self.return_type)?;
+                let default = compute::cast(&default.to_array(shifted.len())?, &self.return_type)?;
+                let out_of_partition = shifted.iter().map(|i| Some(i.is_none())).collect::<BooleanArray>();
+
+                compute::kernels::zip::zip(&out_of_partition, &default, &shifted_values).map_err(|e| e.into())
+            }
+            (WindowFunction::Aggregate(aggr_expr), Some(values)) => {
+                let frames = peers
+                    .iter()
+                    .flat_map(|peer| peer.clone().map(|i| self.frame(i, &partition, peer)))
+                    .collect::<Vec<_>>();
+
+                let mut results = Vec::with_capacity(frames.len());
+                if self.window_frame.start_bound == WindowFrameBound::Preceding(None) {
+                    // the frame always starts at the partition start and its end never goes backward,
+                    // so the rows can be accumulated incrementally
+                    let mut accumulator = aggr_expr.create_accumulator()?;
+                    let mut accumulated = partition.start;
+                    for frame in frames {
+                        if frame.end > accumulated {
+                            accumulator.accumluate(&values.slice(accumulated, frame.end - accumulated))?;
+                            accumulated = frame.end;
+                        }
+                        results.push(self.scalar_to_array(accumulator.as_mut())?);
+                    }
+                } else {
+                    for frame in frames {
+                        let mut accumulator = aggr_expr.create_accumulator()?;
+                        if !frame.is_empty() {
+                            accumulator.accumluate(&values.slice(frame.start, frame.len()))?;
+                        }
+                        results.push(self.scalar_to_array(accumulator.as_mut())?);
+                    }
+                }
+
+                compute::concat(&results.iter().map(|a| a.as_ref()).collect::<Vec<_>>()).map_err(|e| e.into())
+            }
+            (func, None) => internal_err!("Window function {:?} requires an argument", func),
+        }
+    }
+
+    /// the rows of the window frame of the row `i`, `peer` is the range of rows sharing the same order keys with it
+    fn frame(&self, i: usize, partition: &Range<usize>, peer: &Range<usize>) -> Range<usize> {
+        let is_range = self.window_frame.units == WindowFrameUnits::Range;
+        let start = match self.window_frame.start_bound {
+            WindowFrameBound::Preceding(None) => partition.start,
+            WindowFrameBound::Preceding(Some(n)) => i.saturating_sub(n as usize).max(partition.start),
+            WindowFrameBound::CurrentRow if is_range => peer.start,
+            WindowFrameBound::CurrentRow => i,
+            WindowFrameBound::Following(n) => i + n.unwrap_or_default() as usize,
+        };
+        let end = match self.window_frame.end_bound {
+            WindowFrameBound::Following(None) => partition.end,
+            WindowFrameBound::Following(Some(n)) => i + n as usize + 1,
+            WindowFrameBound::CurrentRow if is_range => peer.end,
+            WindowFrameBound::CurrentRow => i + 1,
+            WindowFrameBound::Preceding(n) => (i + 1).saturating_sub(n.unwrap_or_default() as usize),
+        };
+        let end = end.min(partition.end);
+
+        start.min(end)..end
+    }
+
+    fn scalar_to_array(&self, accumulator: &mut dyn Accumulator) -> Result<ArrayRef> {
+        let value = accumulator.evaluate()?.to_array(1)?;
+        compute::cast(&value, &self.return_type).map_err(|e| e.into())
+    }
+}
+
+/// split the rows in `range` into ranges of rows with equal values, the values must be sorted
+fn ranges(values: &[ArrayRef], range: Range<usize>) -> Result<Vec<Range<usize>>> {
+    if range.is_empty() {
+        return Ok(vec![]);
+    }
+    if values.is_empty() {
+        return Ok(vec![range]);
+    }
+
+    let values = values
+        .iter()
+        .map(|v| v.slice(range.start, range.len()))
+        .collect::<Vec<_>>();
+
+    Ok(compute::partition(&values)?
+        .ranges()
+        .into_iter()
+        .map(|r| r.start + range.start..r.end + range.start)
+        .collect())
+}
+
+pub struct Window {
+    schema: SchemaRef,
+    input: Arc<dyn PhysicalPlan>,
+    window_exprs: Vec<WindowExpr>,
+}
+
+impl Window {
+    pub fn new(schema: SchemaRef, input: Arc<dyn PhysicalPlan>, window_exprs: Vec<WindowExpr>) -> Self {
+        Self {
+            schema,
+            input,
+            window_exprs,
+        }
+    }
+}
+
+impl PhysicalPlan for Window {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn execute(&self) -> Result<Vec<RecordBatch>> {
+        let batch = compute::concat_batches(&self.input.schema(), &self.input.execute()?)?;
+        if batch.num_rows() == 0 {
+            return Ok(vec![]);
+        }
+
+        let mut columns = batch.columns().to_vec();
+        for window_expr in &self.window_exprs {
+            columns.push(window_expr.evaluate(&batch)?);
+        }
+
+        Ok(vec![RecordBatch::try_new(self.schema.clone(), columns)?])
+    }
+
+    fn children(&self) -> Option<Vec<Arc<dyn PhysicalPlan>>> {
+        Some(vec![self.input.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_table_scan;
+    use crate::physical::expr::{Column, SumAggregateExpr};
+    use crate::test_utils::assert_batch_eq;
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{Field, Schema};
+
+    fn window_expr(func: WindowFunction, window_frame: WindowFrame) -> WindowExpr {
+        WindowExpr::new(
+            func,
+            vec![Arc::new(Column::new("a", 0))],
+            vec![PhyscialSortExpr::new(
+                Arc::new(Column::new("b", 1)),
+                SortOptions {
+                    descending: false,
+                    nulls_first: true,
+                },
+            )],
+            window_frame,
+            DataType::Int64,
+        )
+    }
+
+    #[test]
+    fn test_window() {
+        let input = build_table_scan!(
+            ("a", Int64Type, DataType::Int64, vec![1, 2, 1, 2, 1]),
+            ("b", Int64Type, DataType::Int64, vec![30, 10, 10, 20, 20]),
+        );
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Int64, false),
+            Field::new("rn", DataType::Int64, true),
+            Field::new("sum", DataType::Int64, true),
+            Field::new("lag", DataType::Int64, true),
+        ]));
+        let rows_frame = WindowFrame {
+            units: WindowFrameUnits::Rows,
+            start_bound: WindowFrameBound::Preceding(Some(1)),
+            end_bound: WindowFrameBound::CurrentRow,
+        };
+
+        let window = Window::new(
+            schema,
+            input,
+            vec![
+                window_expr(WindowFunction::RowNumber, WindowFrame::new(true)),
+                window_expr(
+                    WindowFunction::Aggregate(Arc::new(SumAggregateExpr::new(
+                        Arc::new(Column::new("b", 1)),
+                        DataType::Int64,
+                    ))),
+                    rows_frame,
+                ),
+                window_expr(
+                    WindowFunction::Shift {
+                        expr: Arc::new(Column::new("b", 1)),
+                        offset: -1,
+                        default: ScalarValue::Int64(Some(0)),
+                    },
+                    WindowFrame::new(true),
+                ),
+            ],
+        );
+
+        assert_batch_eq(
+            &window.execute().unwrap(),
+            vec![
+                "+---+----+----+-----+-----+",
+                "| a | b  | rn | sum | lag |",
+                "+---+----+----+-----+-----+",
+                "| 1 | 30 | 3  | 50  | 20  |",
+                "| 2 | 10 | 1  | 10  | 0   |",
+                "| 1 | 10 | 1  | 10  | 0   |",
+                "| 2 | 20 | 2  | 30  | 10  |",
+                "| 1 | 20 | 2  | 30  | 10  |",
+                "+---+----+----+-----+-----+",
+            ],
+        );
+    }
+}
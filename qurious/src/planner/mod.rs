@@ -1,29 +1,35 @@
 pub mod sql;
 
-use std::{fmt::Debug, sync::Arc};
+use std::{collections::HashSet, fmt::Debug, sync::Arc};
 
 use arrow::{
-    compute::SortOptions,
-    datatypes::{SchemaBuilder, SchemaRef},
+    compute::{can_cast_types, SortOptions},
+    datatypes::{DataType, SchemaBuilder, SchemaRef},
 };
 
 use crate::{
     arrow_err,
-    datatypes::scalar::ScalarValue,
+    common::join_type::JoinType,
+    datatypes::{operator::Operator, scalar::ScalarValue},
     error::{Error, Result},
+    execution::{config::SessionConfig, memory_pool::MemoryPool},
     internal_err,
     logical::{
-        expr::{alias::Alias, AggregateOperator, BinaryExpr, CastExpr, Column, Function, Like, LogicalExpr},
+        expr::{
+            alias::Alias, AggregateExpr, AggregateOperator, BinaryExpr, CaseExpr, CastExpr, Column, Function, InList,
+            Like, LogicalExpr, SubQuery, WindowFunctionKind,
+        },
         plan::{
-            Aggregate, CrossJoin, EmptyRelation, Filter, Join, LogicalPlan, Projection, Sort, SubqueryAlias, TableScan,
-            Values,
+            Aggregate, CrossJoin, EmptyRelation, Filter, Join, LateralJoin, LogicalPlan, Projection, RecursiveQuery,
+            Sort, SubqueryAlias, TableScan, Values, Window,
         },
     },
     physical::{
         self,
-        expr::{IsNotNull, IsNull, Negative, PhysicalExpr},
+        expr::{IsNotNull, IsNull, Negative, OuterRefs, PhysicalExpr},
         plan::{ColumnIndex, JoinFilter, JoinSide, PhysicalPlan},
     },
+    provider::table::TableProvider,
 };
 
 pub trait QueryPlanner: Debug + Send + Sync {
@@ -32,11 +38,125 @@ pub trait QueryPlanner: Debug + Send + Sync {
     fn create_physical_expr(&self, input_schema: &SchemaRef, expr: &LogicalExpr) -> Result<Arc<dyn PhysicalExpr>>;
 }
 
-#[derive(Default, Debug)]
-pub struct DefaultQueryPlanner;
+#[derive(Debug)]
+pub struct DefaultQueryPlanner {
+    /// the maximum number of iterations of a recursive query
+    max_recursion_depth: usize,
+    /// the maximum number of rows of a batch produced by a table scan
+    batch_size: usize,
+    /// the equality joins whose sides both have at least this many rows are sort-merge joins
+    sort_merge_join_rows: usize,
+    /// the number of threads an operator runs on, an operator evaluating a subquery runs on one thread
+    target_partitions: usize,
+    /// the memory size of the input rows a grouped aggregation buffers before spilling them
+    aggregate_memory_limit: usize,
+    /// the memory size of the rows of a side a hash join buffers before spilling both sides
+    join_memory_limit: usize,
+    /// the memory shared by the operators of the query, the plans of the subqueries share it with the query
+    memory_pool: Arc<MemoryPool>,
+    /// the precision of the HyperLogLog sketches of `APPROX_DISTINCT`
+    approx_distinct_precision: u8,
+    /// the outer row values of the subquery being planned, `None` for the top-level query
+    outer_refs: Option<Arc<OuterRefs>>,
+    /// wrap each operator to collect its runtime metrics, used by `EXPLAIN ANALYZE`
+    collect_metrics: bool,
+}
+
+impl DefaultQueryPlanner {
+    pub fn new(config: &SessionConfig) -> Self {
+        Self {
+            max_recursion_depth: config.max_recursion_depth,
+            batch_size: config.batch_size,
+            sort_merge_join_rows: config.sort_merge_join_rows,
+            target_partitions: config.target_partitions,
+            aggregate_memory_limit: config.aggregate_memory_limit,
+            join_memory_limit: config.join_memory_limit,
+            memory_pool: Arc::new(config.memory_limit.map_or_else(MemoryPool::unbounded, MemoryPool::new)),
+            approx_distinct_precision: config.approx_distinct_precision,
+            outer_refs: None,
+            collect_metrics: false,
+        }
+    }
+
+    pub fn with_metrics(self) -> Self {
+        Self {
+            collect_metrics: true,
+            ..self
+        }
+    }
+
+    /// the number of threads of an operator evaluating the expressions, a correlated subquery binds the values of
+    /// the outer row before each execution so an operator evaluating a subquery runs on one thread
+    fn partitions_for(&self, exprs: &[LogicalExpr]) -> usize {
+        match exprs.iter().any(|expr| expr.contains_subquery()) {
+            true => 1,
+            false => self.target_partitions,
+        }
+    }
+}
+
+impl Default for DefaultQueryPlanner {
+    fn default() -> Self {
+        Self::new(&SessionConfig::default())
+    }
+}
 
 impl QueryPlanner for DefaultQueryPlanner {
     fn create_physical_plan(&self, plan: &LogicalPlan) -> Result<Arc<dyn PhysicalPlan>> {
+        let physical_plan = self.physical_plan(plan)?;
+
+        // an alias is planned as its input, which is already instrumented
+        if self.collect_metrics && !matches!(plan, LogicalPlan::SubqueryAlias(_)) {
+            Ok(Arc::new(physical::plan::Instrumented::new(physical_plan)))
+        } else {
+            Ok(physical_plan)
+        }
+    }
+
+    fn create_physical_expr(&self, input_schema: &SchemaRef, expr: &LogicalExpr) -> Result<Arc<dyn PhysicalExpr>> {
+        match expr {
+            LogicalExpr::Column(c) => self.physical_expr_column(input_schema, c),
+            LogicalExpr::Literal(v) => self.physical_expr_literal(v),
+            LogicalExpr::BinaryExpr(b) => self.physical_expr_binary(input_schema, b),
+            LogicalExpr::Cast(c) => self.physical_expr_cast(input_schema, c),
+            LogicalExpr::Case(c) => self.physical_expr_case(input_schema, c),
+            LogicalExpr::Alias(Alias { expr, .. }) => self.create_physical_expr(input_schema, expr),
+            LogicalExpr::AggregateExpr(a) => self.create_physical_expr(input_schema, &a.as_column()?),
+            LogicalExpr::Function(f) => self.physical_expr_function(input_schema, f),
+            LogicalExpr::IsNull(f) => self
+                .create_physical_expr(input_schema, f)
+                .map(|expr| Arc::new(IsNull::new(expr)) as Arc<dyn PhysicalExpr>),
+            LogicalExpr::IsNotNull(f) => self
+                .create_physical_expr(input_schema, f)
+                .map(|expr| Arc::new(IsNotNull::new(expr)) as Arc<dyn PhysicalExpr>),
+            LogicalExpr::Negative(neg) => self
+                .create_physical_expr(input_schema, neg)
+                .map(|expr| Arc::new(Negative::new(expr)) as Arc<dyn PhysicalExpr>),
+            LogicalExpr::Like(like) => self.physical_expr_like(input_schema, like),
+            LogicalExpr::SubQuery(subquery) => self
+                .physical_expr_subquery(input_schema, subquery)
+                .map(|subquery| Arc::new(subquery) as Arc<dyn PhysicalExpr>),
+            LogicalExpr::InList(in_list) => self.physical_expr_in_list(input_schema, in_list),
+            LogicalExpr::InSubQuery(in_subquery) => {
+                let expr = self.create_physical_expr(input_schema, &in_subquery.expr)?;
+                let subquery = self.physical_expr_subquery(input_schema, &in_subquery.subquery)?;
+                Ok(Arc::new(physical::expr::InSubQuery::new(
+                    expr,
+                    subquery,
+                    in_subquery.negated,
+                )))
+            }
+            LogicalExpr::Placeholder(placeholder) => {
+                internal_err!("Placeholder {} is not bound to a value", placeholder)
+            }
+            _ => unimplemented!("unsupported logical expression: {}", expr),
+        }
+    }
+}
+
+impl DefaultQueryPlanner {
+    // Physical plan functions
+    fn physical_plan(&self, plan: &LogicalPlan) -> Result<Arc<dyn PhysicalPlan>> {
         match plan {
             LogicalPlan::Projection(p) => self.physical_plan_projection(p),
             LogicalPlan::Filter(f) => self.physical_plan_filter(f),
@@ -45,6 +165,7 @@ impl QueryPlanner for DefaultQueryPlanner {
             LogicalPlan::EmptyRelation(v) => self.physical_empty_relation(v),
             LogicalPlan::CrossJoin(j) => self.physical_plan_cross_join(j),
             LogicalPlan::Join(join) => self.physical_plan_join(join),
+            LogicalPlan::LateralJoin(join) => self.physical_plan_lateral_join(join),
             LogicalPlan::SubqueryAlias(SubqueryAlias { input, .. }) => self.create_physical_plan(input),
             LogicalPlan::Sort(sort) => self.physical_plan_sort(sort),
             LogicalPlan::Limit(limit) => Ok(Arc::new(physical::plan::Limit::new(
@@ -52,6 +173,12 @@ impl QueryPlanner for DefaultQueryPlanner {
                 limit.fetch,
                 limit.skip,
             ))),
+            LogicalPlan::Sample(sample) => Ok(Arc::new(physical::plan::Sample::new(
+                self.create_physical_plan(&sample.input)?,
+                sample.method,
+                sample.fraction,
+                sample.seed,
+            ))),
             LogicalPlan::Values(Values { values, schema }) => values
                 .iter()
                 .map(|exprs| {
@@ -62,6 +189,28 @@ impl QueryPlanner for DefaultQueryPlanner {
                 })
                 .collect::<Result<Vec<_>>>()
                 .map(|exprs| Arc::new(physical::plan::Values::new(schema.clone(), exprs)) as Arc<dyn PhysicalPlan>),
+            LogicalPlan::RecursiveQuery(r) => self.physical_plan_recursive_query(r),
+            LogicalPlan::SetOperation(s) => Ok(Arc::new(physical::plan::SetOperation::new(
+                s.op,
+                s.all,
+                s.schema(),
+                self.create_physical_plan(&s.left)?,
+                self.create_physical_plan(&s.right)?,
+            ))),
+            LogicalPlan::Window(w) => self.physical_plan_window(w),
+            LogicalPlan::DistinctOn(d) => {
+                let input_schema = d.input.schema();
+                let on_expr = d
+                    .on_expr
+                    .iter()
+                    .map(|expr| self.create_physical_expr(&input_schema, expr))
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(Arc::new(physical::plan::DistinctOn::new(
+                    self.create_physical_plan(&d.input)?,
+                    on_expr,
+                )))
+            }
 
             stmt => Err(Error::InternalError(format!(
                 "[{}] Statement not supported here should be handled in ExecuteSession",
@@ -70,35 +219,6 @@ impl QueryPlanner for DefaultQueryPlanner {
         }
     }
 
-    fn create_physical_expr(&self, input_schema: &SchemaRef, expr: &LogicalExpr) -> Result<Arc<dyn PhysicalExpr>> {
-        match expr {
-            LogicalExpr::Column(c) => self.physical_expr_column(input_schema, c),
-            LogicalExpr::Literal(v) => self.physical_expr_literal(v),
-            LogicalExpr::BinaryExpr(b) => self.physical_expr_binary(input_schema, b),
-            LogicalExpr::Cast(c) => self.physical_expr_cast(input_schema, c),
-            LogicalExpr::Alias(Alias { expr, .. }) => self.create_physical_expr(input_schema, expr),
-            LogicalExpr::AggregateExpr(a) => self.create_physical_expr(input_schema, &a.as_column()?),
-            LogicalExpr::Function(f) => self.physical_expr_function(input_schema, f),
-            LogicalExpr::IsNull(f) => self
-                .create_physical_expr(input_schema, f)
-                .map(|expr| Arc::new(IsNull::new(expr)) as Arc<dyn PhysicalExpr>),
-            LogicalExpr::IsNotNull(f) => self
-                .create_physical_expr(input_schema, f)
-                .map(|expr| Arc::new(IsNotNull::new(expr)) as Arc<dyn PhysicalExpr>),
-            LogicalExpr::Negative(neg) => self
-                .create_physical_expr(input_schema, neg)
-                .map(|expr| Arc::new(Negative::new(expr)) as Arc<dyn PhysicalExpr>),
-            LogicalExpr::Like(like) => self.physical_expr_like(input_schema, like),
-            LogicalExpr::SubQuery(plan) => self
-                .create_physical_plan(&plan.subquery)
-                .map(|plan| Arc::new(physical::expr::SubQuery { plan }) as Arc<dyn PhysicalExpr>),
-            _ => unimplemented!("unsupported logical expression: {}", expr),
-        }
-    }
-}
-
-impl DefaultQueryPlanner {
-    // Physical plan functions
     fn physical_plan_projection(&self, projection: &Projection) -> Result<Arc<dyn PhysicalPlan>> {
         let physical_plan = self.create_physical_plan(&projection.input)?;
 
@@ -109,18 +229,20 @@ impl DefaultQueryPlanner {
             .map(|e| self.create_physical_expr(&input_schema, e))
             .collect::<Result<Vec<_>>>()?;
 
-        Ok(Arc::new(physical::plan::Projection::new(
-            projection.schema(),
-            physical_plan,
-            exprs,
-        )))
+        Ok(Arc::new(
+            physical::plan::Projection::new(projection.schema(), physical_plan, exprs)
+                .with_target_partitions(self.partitions_for(&projection.exprs)),
+        ))
     }
 
     fn physical_plan_filter(&self, filter: &Filter) -> Result<Arc<dyn PhysicalPlan>> {
-        Ok(Arc::new(physical::plan::Filter::new(
-            self.create_physical_plan(&filter.input)?,
-            self.create_physical_expr(&filter.schema(), &filter.expr)?,
-        )))
+        Ok(Arc::new(
+            physical::plan::Filter::new(
+                self.create_physical_plan(&filter.input)?,
+                self.create_physical_expr(&filter.schema(), &filter.expr)?,
+            )
+            .with_target_partitions(self.partitions_for(std::slice::from_ref(&filter.expr))),
+        ))
     }
 
     fn physical_plan_aggregate(&self, aggregate: &Aggregate) -> Result<Arc<dyn PhysicalPlan>> {
@@ -140,58 +262,64 @@ impl DefaultQueryPlanner {
                     return internal_err!("LogicalExpr should be AggregateExpr, but got {:?}", e);
                 };
                 // use base plan for aggregate expr
-                self.create_physical_expr(&aggregate.input.schema(), &agg_expr.expr)
-                    .and_then(|expr| {
-                        // get AggreateExpr return datatype
-                        let return_type = e.data_type(&aggregate.input.schema())?;
-                        match agg_expr.op {
-                            AggregateOperator::Sum => {
-                                Ok(Arc::new(physical::expr::SumAggregateExpr::new(expr, return_type))
-                                    as Arc<dyn physical::expr::AggregateExpr>)
-                            }
-                            AggregateOperator::Max => {
-                                Ok(Arc::new(physical::expr::MaxAggregateExpr::new(expr, return_type))
-                                    as Arc<dyn physical::expr::AggregateExpr>)
-                            }
-                            AggregateOperator::Min => {
-                                Ok(Arc::new(physical::expr::MinAggregateExpr::new(expr, return_type))
-                                    as Arc<dyn physical::expr::AggregateExpr>)
-                            }
-                            AggregateOperator::Count => Ok(Arc::new(physical::expr::CountAggregateExpr::new(expr))
-                                as Arc<dyn physical::expr::AggregateExpr>),
-                            AggregateOperator::Avg => Ok(Arc::new(physical::expr::AvgAggregateExpr::new(
-                                expr,
-                                agg_expr.expr.data_type(&aggregate.input.schema())?,
-                                return_type,
-                            ))),
-                        }
-                    })
+                let return_type = e.data_type(&aggregate.input.schema())?;
+                let mut aggr_expr = self.create_aggregate_expr(
+                    &aggregate.input.schema(),
+                    &agg_expr.op,
+                    &agg_expr.expr,
+                    &agg_expr.args,
+                    return_type.clone(),
+                )?;
+                if agg_expr.distinct {
+                    aggr_expr = Arc::new(physical::expr::DistinctAggregateExpr::new(aggr_expr));
+                }
+                // the rows are filtered before the distinct values are taken
+                if let Some(filter) = &agg_expr.filter {
+                    let filter = self.create_physical_expr(&aggregate.input.schema(), filter)?;
+                    aggr_expr = Arc::new(physical::expr::FilteredAggregateExpr::new(
+                        aggr_expr,
+                        filter,
+                        return_type,
+                    ));
+                }
+                Ok(aggr_expr)
             })
             .collect::<Result<Vec<_>>>()?;
 
         // not group by
         if group_expr.is_empty() {
-            return Ok(Arc::new(physical::plan::NoGroupingAggregate::new(
+            let mut plan = physical::plan::NoGroupingAggregate::new(aggregate.schema.clone(), input, aggr_expr);
+            if let Some(table) = counted_table(aggregate) {
+                plan = plan.with_counted_table(table);
+            }
+            return Ok(Arc::new(plan));
+        }
+
+        // the rows of a group are next to each other when the input is sorted on the group columns
+        if sorted_on_groups(aggregate) {
+            return Ok(Arc::new(physical::plan::SortAggregate::new(
                 aggregate.schema.clone(),
                 input,
+                group_expr,
                 aggr_expr,
             )));
         }
 
-        Ok(Arc::new(physical::plan::HashAggregate::new(
-            aggregate.schema.clone(),
-            input,
-            group_expr,
-            aggr_expr,
-        )))
+        let exprs = [aggregate.group_expr.as_slice(), aggregate.aggr_expr.as_slice()].concat();
+        Ok(Arc::new(
+            physical::plan::HashAggregate::new(aggregate.schema.clone(), input, group_expr, aggr_expr)
+                .with_target_partitions(self.partitions_for(&exprs))
+                .with_memory_limit(self.aggregate_memory_limit)
+                .with_memory_pool(self.memory_pool.clone()),
+        ))
     }
 
     fn physical_plan_table_scan(&self, table_scan: &TableScan) -> Result<Arc<dyn PhysicalPlan>> {
-        Ok(Arc::new(physical::plan::Scan::new(
-            table_scan.schema(),
-            table_scan.source.clone(),
-            None,
-        )) as Arc<dyn PhysicalPlan>)
+        Ok(Arc::new(
+            physical::plan::Scan::new(table_scan.schema(), table_scan.source.clone(), None)
+                .with_filters(table_scan.filter.clone().into_iter().collect())
+                .with_batch_size(self.batch_size),
+        ) as Arc<dyn PhysicalPlan>)
     }
 
     fn physical_plan_cross_join(&self, cross_join: &CrossJoin) -> Result<Arc<dyn PhysicalPlan>> {
@@ -200,19 +328,129 @@ impl DefaultQueryPlanner {
         Ok(Arc::new(physical::plan::CrossJoin::new(left, right)))
     }
 
+    fn physical_plan_lateral_join(&self, join: &LateralJoin) -> Result<Arc<dyn PhysicalPlan>> {
+        let left = self.create_physical_plan(join.left.as_ref())?;
+        let subquery = self.physical_expr_subquery(&left.schema(), &join.subquery)?;
+        Ok(Arc::new(physical::plan::LateralJoin::new(left, subquery)))
+    }
+
     fn physical_plan_join(&self, join: &Join) -> Result<Arc<dyn PhysicalPlan>> {
         let left = self.create_physical_plan(join.left.as_ref())?;
         let right = self.create_physical_plan(join.right.as_ref())?;
+        let ls = left.schema();
+        let rs = right.schema();
+
+        if let Some(join) = self.physical_index_join(join, &left, &rs)? {
+            return Ok(Arc::new(join));
+        }
 
-        let using_columns = join.filter.using_columns();
+        // the joins on equality keys are hash joins, or sort-merge joins if both sides are sorted on the keys or
+        // are large, the other conditions filter the pairs of matched rows
+        let (mut on, filter) = split_join_keys(&join.filter, &ls, &rs);
+        if !on.is_empty() {
+            let sort_merge = match sorted_on(&join.left, &join.right, &on) {
+                Some(sorted) => {
+                    on = sorted;
+                    true
+                }
+                None => matches!(
+                    (estimate_rows(&join.left), estimate_rows(&join.right)),
+                    (Some(left_rows), Some(right_rows)) if left_rows.min(right_rows) >= self.sort_merge_join_rows
+                ),
+            };
+            let null_safe = on.iter().map(|(_, _, null_safe)| *null_safe).collect();
+            let target_partitions = self.partitions_for(std::slice::from_ref(&join.filter));
+            let on = on
+                .iter()
+                .map(|(l, r, _)| Ok((self.create_physical_expr(&ls, l)?, self.create_physical_expr(&rs, r)?)))
+                .collect::<Result<Vec<_>>>()?;
+            let join_filter = filter
+                .map(|filter| self.physical_join_filter(&ls, &rs, &filter))
+                .transpose()?;
+
+            if sort_merge {
+                return physical::plan::SortMergeJoin::try_new(left, right, join.join_type, on, join_filter)?
+                    .with_null_safe(null_safe)
+                    .map(|j| Arc::new(j) as Arc<dyn PhysicalPlan>);
+            }
+            return physical::plan::HashJoin::try_new(left, right, join.join_type, on, join_filter)?
+                .with_null_safe(null_safe)
+                .map(|j| {
+                    let j = j
+                        .with_target_partitions(target_partitions)
+                        .with_memory_limit(self.join_memory_limit)
+                        .with_memory_pool(self.memory_pool.clone());
+                    Arc::new(j) as Arc<dyn PhysicalPlan>
+                });
+        }
 
+        let join_filter = self.physical_join_filter(&ls, &rs, &join.filter)?;
+        // the rows of the smaller side are matched one at a time with all the rows of the other side
+        let loop_side = match (estimate_rows(&join.left), estimate_rows(&join.right)) {
+            (Some(left_rows), Some(right_rows)) if left_rows < right_rows => JoinSide::Left,
+            _ => JoinSide::Right,
+        };
+
+        physical::plan::Join::try_new(left, right, join.join_type, Some(join_filter))
+            .map(|j| Arc::new(j.with_loop_side(loop_side)) as Arc<dyn PhysicalPlan>)
+    }
+
+    /// The inner or left join of the left plan with an unfiltered scan of a table having an index of the columns
+    /// the equality keys compare, the rows of the table equal to the keys of each left row are looked up in the
+    /// index. None if the join can not use an index.
+    fn physical_index_join(
+        &self,
+        join: &Join,
+        left: &Arc<dyn PhysicalPlan>,
+        rs: &SchemaRef,
+    ) -> Result<Option<physical::plan::IndexJoin>> {
+        let LogicalPlan::TableScan(scan) = join.right.as_ref() else {
+            return Ok(None);
+        };
+        if scan.filter.is_some() || !matches!(join.join_type, JoinType::Inner | JoinType::Left) {
+            return Ok(None);
+        }
         let ls = left.schema();
+        let (on, filter) = split_join_keys(&join.filter, &ls, rs);
+        let Some(columns) = on
+            .iter()
+            .map(|(_, right, null_safe)| match right {
+                LogicalExpr::Column(column) if !null_safe => Some(column.name.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Ok(None);
+        };
+        if columns.is_empty()
+            || columns.iter().collect::<HashSet<_>>().len() != columns.len()
+            || !scan.source.has_index(&columns)
+        {
+            return Ok(None);
+        }
+
+        let on = on
+            .iter()
+            .zip(columns)
+            .map(|((key, _, _), column)| Ok((self.create_physical_expr(&ls, key)?, column)))
+            .collect::<Result<Vec<_>>>()?;
+        let filter = filter
+            .map(|filter| self.physical_join_filter(&ls, rs, &filter))
+            .transpose()?;
+
+        physical::plan::IndexJoin::try_new(left.clone(), scan.source.clone(), rs.clone(), join.join_type, on, filter)
+            .map(Some)
+    }
+
+    /// the filter is evaluated on the columns it uses taken from both sides
+    fn physical_join_filter(&self, ls: &SchemaRef, rs: &SchemaRef, filter: &LogicalExpr) -> Result<JoinFilter> {
+        let using_columns = filter.using_columns();
+
         let li = using_columns
             .iter()
             .filter_map(|c| ls.fields().find(&c.name))
             .map(|(i, f)| (f.clone(), (i, JoinSide::Left)));
 
-        let rs = right.schema();
         let ri = using_columns
             .iter()
             .filter_map(|c| rs.fields().find(&c.name))
@@ -220,16 +458,13 @@ impl DefaultQueryPlanner {
 
         let (filter_schema, column_indices): (SchemaBuilder, Vec<ColumnIndex>) = li.chain(ri).unzip();
         let filter_schema = Arc::new(filter_schema.finish());
-        let filter_expr = self.create_physical_expr(&filter_schema, &join.filter)?;
+        let filter_expr = self.create_physical_expr(&filter_schema, filter)?;
 
-        let join_filter = JoinFilter {
+        Ok(JoinFilter {
             expr: filter_expr,
             schema: filter_schema,
             column_indices,
-        };
-
-        physical::plan::Join::try_new(left, right, join.join_type, Some(join_filter))
-            .map(|j| Arc::new(j) as Arc<dyn PhysicalPlan>)
+        })
     }
 
     fn physical_empty_relation(&self, empty: &EmptyRelation) -> Result<Arc<dyn PhysicalPlan>> {
@@ -239,6 +474,109 @@ impl DefaultQueryPlanner {
         )))
     }
 
+    fn physical_plan_recursive_query(&self, query: &RecursiveQuery) -> Result<Arc<dyn PhysicalPlan>> {
+        Ok(Arc::new(physical::plan::RecursiveQuery::new(
+            query.name.clone(),
+            query.schema(),
+            self.create_physical_plan(&query.static_term)?,
+            self.create_physical_plan(&query.recursive_term)?,
+            query.work_table.clone(),
+            query.is_distinct,
+            self.max_recursion_depth,
+        )))
+    }
+
+    fn physical_plan_window(&self, window: &Window) -> Result<Arc<dyn PhysicalPlan>> {
+        let input = self.create_physical_plan(&window.input)?;
+        let input_schema = window.input.schema();
+
+        let window_exprs = window
+            .window_expr
+            .iter()
+            .map(|e| {
+                let LogicalExpr::WindowFunction(window_func) = e else {
+                    return internal_err!("LogicalExpr should be WindowFunction, but got {:?}", e);
+                };
+                let return_type = e.data_type(&input_schema)?;
+                let func = match &window_func.func {
+                    WindowFunctionKind::RowNumber => physical::plan::WindowFunction::RowNumber,
+                    WindowFunctionKind::Rank => physical::plan::WindowFunction::Rank,
+                    WindowFunctionKind::DenseRank => physical::plan::WindowFunction::DenseRank,
+                    WindowFunctionKind::Lag | WindowFunctionKind::Lead => {
+                        let offset = match window_func.args.get(1) {
+                            Some(LogicalExpr::Literal(ScalarValue::Int64(Some(offset)))) => *offset,
+                            Some(expr) => {
+                                return internal_err!(
+                                    "{} offset must be an integer literal, got {}",
+                                    window_func.func,
+                                    expr
+                                )
+                            }
+                            None => 1,
+                        };
+                        let default = match window_func.args.get(2) {
+                            Some(LogicalExpr::Literal(value)) => value.clone(),
+                            Some(expr) => {
+                                return internal_err!(
+                                    "{} default value must be a literal, got {}",
+                                    window_func.func,
+                                    expr
+                                )
+                            }
+                            None => ScalarValue::Null,
+                        };
+
+                        physical::plan::WindowFunction::Shift {
+                            expr: self.create_physical_expr(&input_schema, &window_func.args[0])?,
+                            offset: if window_func.func == WindowFunctionKind::Lag {
+                                -offset
+                            } else {
+                                offset
+                            },
+                            default,
+                        }
+                    }
+                    WindowFunctionKind::Aggregate(op) => physical::plan::WindowFunction::Aggregate(
+                        self.create_aggregate_expr(
+                            &input_schema,
+                            op,
+                            &window_func.args[0],
+                            &window_func.args[1..],
+                            return_type.clone(),
+                        )?,
+                    ),
+                };
+                let partition_by = window_func
+                    .partition_by
+                    .iter()
+                    .map(|e| self.create_physical_expr(&input_schema, e))
+                    .collect::<Result<Vec<_>>>()?;
+                let order_by = window_func
+                    .order_by
+                    .iter()
+                    .map(|sort| {
+                        let options = SortOptions {
+                            descending: !sort.asc,
+                            nulls_first: sort.nulls_first,
+                        };
+                        self.create_physical_expr(&input_schema, &sort.expr)
+                            .map(|expr| physical::plan::PhyscialSortExpr::new(expr, options))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(physical::plan::WindowExpr::new(
+                    func,
+                    partition_by,
+                    order_by,
+                    window_func.window_frame,
+                    return_type,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Arc::new(physical::plan::Window::new(window.schema(), input, window_exprs)))
+    }
+
     fn physical_plan_sort(&self, sort: &Sort) -> Result<Arc<dyn PhysicalPlan>> {
         let input = self.create_physical_plan(&sort.input)?;
         sort.exprs
@@ -246,25 +584,221 @@ impl DefaultQueryPlanner {
             .map(|expr| {
                 let options = SortOptions {
                     descending: !expr.asc,
-                    nulls_first: true,
+                    nulls_first: expr.nulls_first,
                 };
                 let expr = self.create_physical_expr(&input.schema(), &expr.expr)?;
                 Ok(physical::plan::PhyscialSortExpr::new(expr, options))
             })
             .collect::<Result<_>>()
-            .map(|exprs| Arc::new(physical::plan::Sort::new(exprs, input)) as Arc<dyn PhysicalPlan>)
+            .map(|exprs| match sort.fetch {
+                // only the first rows of the order are kept
+                Some(fetch) => Arc::new(physical::plan::TopK::new(exprs, input, fetch)) as Arc<dyn PhysicalPlan>,
+                None => {
+                    let sort = physical::plan::Sort::new(exprs, input).with_memory_pool(self.memory_pool.clone());
+                    Arc::new(sort) as Arc<dyn PhysicalPlan>
+                }
+            })
     }
 }
 
 impl DefaultQueryPlanner {
+    fn create_aggregate_expr(
+        &self,
+        input_schema: &SchemaRef,
+        op: &AggregateOperator,
+        expr: &LogicalExpr,
+        args: &[LogicalExpr],
+        return_type: DataType,
+    ) -> Result<Arc<dyn physical::expr::AggregateExpr>> {
+        let physical_expr = self.create_physical_expr(input_schema, expr)?;
+        let arg = |index: usize| match args.get(index) {
+            Some(arg) => self.create_physical_expr(input_schema, arg),
+            None => internal_err!("{} expects {} arguments", op, op.num_args()),
+        };
+        // the fraction of a percentile is a constant between 0 and 1
+        let fraction = |index: usize| {
+            let fraction = match args.get(index) {
+                Some(LogicalExpr::Literal(ScalarValue::Float64(Some(fraction)))) => *fraction,
+                Some(LogicalExpr::Literal(ScalarValue::Int64(Some(fraction)))) => *fraction as f64,
+                arg => return internal_err!("{} expects a constant fraction, got {:?}", op, arg),
+            };
+            if !(0.0..=1.0).contains(&fraction) {
+                return internal_err!("{} fraction must be between 0 and 1, got {}", op, fraction);
+            }
+            Ok(fraction)
+        };
+
+        Ok(match op {
+            AggregateOperator::Sum => Arc::new(physical::expr::SumAggregateExpr::new(physical_expr, return_type)),
+            AggregateOperator::Max => Arc::new(physical::expr::MaxAggregateExpr::new(physical_expr, return_type)),
+            AggregateOperator::Min => Arc::new(physical::expr::MinAggregateExpr::new(physical_expr, return_type)),
+            AggregateOperator::Count => Arc::new(physical::expr::CountAggregateExpr::new(physical_expr)),
+            AggregateOperator::Avg => Arc::new(physical::expr::AvgAggregateExpr::new(
+                physical_expr,
+                expr.data_type(input_schema)?,
+                return_type,
+            )),
+            AggregateOperator::Stddev => Arc::new(physical::expr::StddevAggregateExpr::new(physical_expr, false)),
+            AggregateOperator::StddevPop => Arc::new(physical::expr::StddevAggregateExpr::new(physical_expr, true)),
+            AggregateOperator::Variance => Arc::new(physical::expr::VarianceAggregateExpr::new(physical_expr, false)),
+            AggregateOperator::VariancePop => Arc::new(physical::expr::VarianceAggregateExpr::new(physical_expr, true)),
+            AggregateOperator::Corr => Arc::new(physical::expr::CorrelationAggregateExpr::new(physical_expr, arg(0)?)),
+            AggregateOperator::CovarSamp => Arc::new(physical::expr::CovarianceAggregateExpr::new(
+                physical_expr,
+                arg(0)?,
+                false,
+            )),
+            AggregateOperator::CovarPop => Arc::new(physical::expr::CovarianceAggregateExpr::new(
+                physical_expr,
+                arg(0)?,
+                true,
+            )),
+            AggregateOperator::Median => Arc::new(physical::expr::PercentileAggregateExpr::new_median(physical_expr)),
+            AggregateOperator::PercentileCont { descending } => Arc::new(physical::expr::PercentileAggregateExpr::new(
+                physical_expr,
+                fraction(0)?,
+                true,
+                *descending,
+                return_type,
+            )),
+            AggregateOperator::PercentileDisc { descending } => Arc::new(physical::expr::PercentileAggregateExpr::new(
+                physical_expr,
+                fraction(0)?,
+                false,
+                *descending,
+                return_type,
+            )),
+            AggregateOperator::ApproxPercentile => Arc::new(physical::expr::ApproxPercentileAggregateExpr::new(
+                physical_expr,
+                fraction(0)?,
+            )),
+            AggregateOperator::ApproxDistinct => Arc::new(physical::expr::ApproxDistinctAggregateExpr::new(
+                physical_expr,
+                self.approx_distinct_precision,
+            )),
+            // the sql planner replaces it with the values of the grouping sets
+            AggregateOperator::Grouping => {
+                return internal_err!("GROUPING({}) must be used with GROUP BY", expr);
+            }
+        })
+    }
+
     fn physical_expr_like(&self, schema: &SchemaRef, like: &Like) -> Result<Arc<dyn PhysicalExpr>> {
         let expr = self.create_physical_expr(schema, &like.expr)?;
         let pattern = self.create_physical_expr(schema, &like.pattern)?;
-        Ok(Arc::new(physical::expr::Like::new(like.negated, expr, pattern)))
+        Ok(Arc::new(physical::expr::Like::new(
+            like.negated,
+            like.case_insensitive,
+            like.escape_char,
+            expr,
+            pattern,
+        )))
+    }
+
+    fn physical_expr_case(&self, schema: &SchemaRef, case: &CaseExpr) -> Result<Arc<dyn PhysicalExpr>> {
+        let operand = case
+            .operand
+            .as_ref()
+            .map(|expr| self.create_physical_expr(schema, expr))
+            .transpose()?;
+        let when_then = case
+            .when_then
+            .iter()
+            .map(|(when, then)| {
+                Ok((
+                    self.create_physical_expr(schema, when)?,
+                    self.create_physical_expr(schema, then)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let else_expr = case
+            .else_expr
+            .as_ref()
+            .map(|expr| self.create_physical_expr(schema, expr))
+            .transpose()?;
+
+        Ok(Arc::new(physical::expr::CaseExpr::new(
+            operand,
+            when_then,
+            else_expr,
+            case.data_type(schema)?,
+        )))
+    }
+
+    fn physical_expr_in_list(&self, schema: &SchemaRef, in_list: &InList) -> Result<Arc<dyn PhysicalExpr>> {
+        let expr = self.create_physical_expr(schema, &in_list.expr)?;
+        let values = in_list
+            .list
+            .iter()
+            .map(|item| match item {
+                LogicalExpr::Literal(value) => Some(value.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+
+        // a list of literals is evaluated once into a set of values
+        if let (Some(values), Ok(data_type)) = (values, in_list.expr.data_type(schema)) {
+            return physical::expr::InList::try_new_with_values(expr, &values, &data_type, in_list.negated)
+                .map(|expr| Arc::new(expr) as Arc<dyn PhysicalExpr>);
+        }
+
+        let list = in_list
+            .list
+            .iter()
+            .map(|item| self.create_physical_expr(schema, item))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(physical::expr::InList::new(expr, list, in_list.negated)))
+    }
+
+    fn physical_expr_subquery(&self, schema: &SchemaRef, subquery: &SubQuery) -> Result<physical::expr::SubQuery> {
+        // the outer columns are bound by the subquery expression before each execution of the subquery plan
+        let outer_refs = Arc::new(OuterRefs::default());
+        let planner = DefaultQueryPlanner {
+            max_recursion_depth: self.max_recursion_depth,
+            batch_size: self.batch_size,
+            sort_merge_join_rows: self.sort_merge_join_rows,
+            target_partitions: self.target_partitions,
+            aggregate_memory_limit: self.aggregate_memory_limit,
+            join_memory_limit: self.join_memory_limit,
+            memory_pool: self.memory_pool.clone(),
+            approx_distinct_precision: self.approx_distinct_precision,
+            outer_refs: Some(outer_refs.clone()),
+            // the subquery plan is not part of the plan tree, its metrics are not shown
+            collect_metrics: false,
+        };
+        let plan = planner.create_physical_plan(&subquery.subquery)?;
+
+        let outer_ref_columns = subquery
+            .outer_ref_columns
+            .iter()
+            .map(|expr| {
+                let LogicalExpr::Column(column) = expr else {
+                    return internal_err!("Outer reference should be a column, but got {}", expr);
+                };
+                let outer_column = Column {
+                    is_outer_ref: false,
+                    ..column.clone()
+                };
+                self.physical_expr_column(schema, &outer_column)
+                    .map(|expr| (column.quanlified_name(), expr))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(physical::expr::SubQuery::new(plan, outer_ref_columns, outer_refs))
     }
 
     // Physical expression functions
     fn physical_expr_column(&self, schema: &SchemaRef, column: &Column) -> Result<Arc<dyn PhysicalExpr>> {
+        if column.is_outer_ref {
+            return match &self.outer_refs {
+                Some(outer_refs) => Ok(Arc::new(physical::expr::OuterColumn::new(
+                    column.quanlified_name(),
+                    outer_refs.clone(),
+                ))),
+                None => internal_err!("Outer reference [{}] is not in a subquery", column),
+            };
+        }
+
         schema
             .index_of(&column.name)
             .map_err(|e| arrow_err!(e))
@@ -282,6 +816,13 @@ impl DefaultQueryPlanner {
     }
 
     fn physical_expr_cast(&self, schema: &SchemaRef, cast: &CastExpr) -> Result<Arc<dyn PhysicalExpr>> {
+        // the type of an outer reference is only known when the subquery is executed
+        if let Ok(data_type) = cast.expr.data_type(schema) {
+            if !can_cast_types(&data_type, &cast.data_type) {
+                return internal_err!("Cannot cast [{}] from {} to {}", cast.expr, data_type, cast.data_type);
+            }
+        }
+
         self.create_physical_expr(schema, &cast.expr)
             .map(|expr| Arc::new(physical::expr::CastExpr::new(expr, cast.data_type.clone())) as Arc<dyn PhysicalExpr>)
     }
@@ -295,3 +836,191 @@ impl DefaultQueryPlanner {
         Ok(Arc::new(physical::expr::Function::new(function.func.clone(), args)))
     }
 }
+
+/// the table of an unfiltered scan whose rows are counted by all the aggregate expressions, like `count(*)`
+fn counted_table(aggregate: &Aggregate) -> Option<Arc<dyn TableProvider>> {
+    let LogicalPlan::TableScan(TableScan {
+        source, filter: None, ..
+    }) = aggregate.input.as_ref()
+    else {
+        return None;
+    };
+    let count = LogicalExpr::AggregateExpr(AggregateExpr {
+        op: AggregateOperator::Count,
+        expr: Box::new(LogicalExpr::Literal(ScalarValue::from(1))),
+        args: vec![],
+        distinct: false,
+        filter: None,
+    });
+
+    (!aggregate.aggr_expr.is_empty() && aggregate.aggr_expr.iter().all(|expr| *expr == count)).then(|| source.clone())
+}
+
+/// An upper bound of the number of rows of a plan, estimated from the statistics of the scanned tables. None when a
+/// table does not know its number of rows or the plan can not be estimated.
+fn estimate_rows(plan: &LogicalPlan) -> Option<usize> {
+    match plan {
+        LogicalPlan::TableScan(scan) => scan.source.statistics()?.num_rows,
+        LogicalPlan::EmptyRelation(empty) => Some(empty.produce_one_row as usize),
+        LogicalPlan::Values(values) => Some(values.values.len()),
+        LogicalPlan::Aggregate(aggregate) if aggregate.group_expr.is_empty() => Some(1),
+        LogicalPlan::Limit(limit) => {
+            let rows = estimate_rows(&limit.input);
+            match limit.fetch {
+                Some(fetch) => Some(rows.map_or(fetch, |rows| rows.saturating_sub(limit.skip).min(fetch))),
+                None => rows.map(|rows| rows.saturating_sub(limit.skip)),
+            }
+        }
+        LogicalPlan::CrossJoin(_) | LogicalPlan::Join(_) => plan
+            .children()?
+            .into_iter()
+            .map(estimate_rows)
+            .try_fold(1_usize, |rows, child| Some(rows.saturating_mul(child?))),
+        LogicalPlan::Sort(Sort {
+            input,
+            fetch: Some(fetch),
+            ..
+        }) => Some(estimate_rows(input).map_or(*fetch, |rows| rows.min(*fetch))),
+        LogicalPlan::Projection(_)
+        | LogicalPlan::Filter(_)
+        | LogicalPlan::Aggregate(_)
+        | LogicalPlan::SubqueryAlias(_)
+        | LogicalPlan::Sort(_)
+        | LogicalPlan::Sample(_)
+        | LogicalPlan::Window(_)
+        | LogicalPlan::DistinctOn(_) => estimate_rows(plan.children()?.first()?),
+        _ => None,
+    }
+}
+
+/// The names of the columns the rows of a plan are sorted on, ascending with the NULLs first, in the order of the
+/// sort. Empty if the order of the rows is unknown.
+fn sort_order(plan: &LogicalPlan) -> Vec<String> {
+    match plan {
+        LogicalPlan::Sort(sort) => sort
+            .exprs
+            .iter()
+            .map_while(|sort_expr| match sort_expr.expr.as_ref() {
+                LogicalExpr::Column(column) if sort_expr.asc && sort_expr.nulls_first => Some(column.name.clone()),
+                _ => None,
+            })
+            .collect(),
+        // the columns are renamed by the aliases of the projection
+        LogicalPlan::Projection(projection) => sort_order(&projection.input)
+            .into_iter()
+            .map_while(|name| {
+                projection.exprs.iter().find_map(|expr| match expr {
+                    LogicalExpr::Column(column) if column.name == name => Some(name.clone()),
+                    LogicalExpr::Alias(Alias { expr, name: alias }) => match expr.as_ref() {
+                        LogicalExpr::Column(column) if column.name == name => Some(alias.clone()),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+            })
+            .collect(),
+        LogicalPlan::Filter(_) | LogicalPlan::Limit(_) | LogicalPlan::SubqueryAlias(_) => plan
+            .children()
+            .and_then(|children| children.first().map(|child| sort_order(child)))
+            .unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+/// Whether the input of the aggregate is sorted first on its group columns, in any order of the columns
+fn sorted_on_groups(aggregate: &Aggregate) -> bool {
+    let mut columns = vec![];
+    for expr in &aggregate.group_expr {
+        match expr {
+            LogicalExpr::Column(column) if !columns.contains(&column.name) => columns.push(column.name.clone()),
+            LogicalExpr::Column(_) => {}
+            _ => return false,
+        }
+    }
+
+    let order = sort_order(&aggregate.input);
+    order.len() >= columns.len()
+        && columns.iter().all(|name| order[..columns.len()].contains(name))
+}
+
+/// The join keys in the order both sides are sorted on, None unless each side is sorted on its column keys.
+fn sorted_on(
+    left: &LogicalPlan,
+    right: &LogicalPlan,
+    on: &[(LogicalExpr, LogicalExpr, bool)],
+) -> Option<Vec<(LogicalExpr, LogicalExpr, bool)>> {
+    let (left_order, right_order) = (sort_order(left), sort_order(right));
+    if left_order.len() < on.len() || right_order.len() < on.len() {
+        return None;
+    }
+
+    left_order
+        .iter()
+        .zip(&right_order)
+        .take(on.len())
+        .map(|(left_name, right_name)| {
+            on.iter()
+                .find(|(l, r, _)| match (l, r) {
+                    (LogicalExpr::Column(l), LogicalExpr::Column(r)) => &l.name == left_name && &r.name == right_name,
+                    _ => false,
+                })
+                .cloned()
+        })
+        .collect()
+}
+
+/// Split the equalities of an expression of the left side with an expression of the right side out of the
+/// conjunctions of a join filter, returns them with the remaining conditions. An equality is null-safe if it is an
+/// `IS NOT DISTINCT FROM`.
+fn split_join_keys(
+    filter: &LogicalExpr,
+    left: &SchemaRef,
+    right: &SchemaRef,
+) -> (Vec<(LogicalExpr, LogicalExpr, bool)>, Option<LogicalExpr>) {
+    // all the columns of the expression are from one side only
+    let from_side = |expr: &LogicalExpr, side: &SchemaRef, other: &SchemaRef| {
+        let columns = expr.using_columns();
+        !columns.is_empty()
+            && columns
+                .iter()
+                .all(|c| side.index_of(&c.name).is_ok() && other.index_of(&c.name).is_err())
+    };
+
+    let mut keys = vec![];
+    let mut remaining: Option<LogicalExpr> = None;
+    let mut stack = vec![filter];
+    while let Some(expr) = stack.pop() {
+        match expr {
+            LogicalExpr::BinaryExpr(BinaryExpr {
+                left: l,
+                op: Operator::And,
+                right: r,
+            }) => {
+                stack.push(r);
+                stack.push(l);
+            }
+            LogicalExpr::BinaryExpr(BinaryExpr {
+                left: l,
+                op: op @ (Operator::Eq | Operator::IsNotDistinctFrom),
+                right: r,
+            }) if from_side(l, left, right) && from_side(r, right, left) => {
+                keys.push((*l.clone(), *r.clone(), *op == Operator::IsNotDistinctFrom))
+            }
+            LogicalExpr::BinaryExpr(BinaryExpr {
+                left: l,
+                op: op @ (Operator::Eq | Operator::IsNotDistinctFrom),
+                right: r,
+            }) if from_side(r, left, right) && from_side(l, right, left) => {
+                keys.push((*r.clone(), *l.clone(), *op == Operator::IsNotDistinctFrom))
+            }
+            _ => {
+                remaining = Some(match remaining {
+                    Some(remaining) => LogicalExpr::BinaryExpr(BinaryExpr::new(remaining, Operator::And, expr.clone())),
+                    None => expr.clone(),
+                })
+            }
+        }
+    }
+
+    (keys, remaining)
+}
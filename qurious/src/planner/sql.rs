@@ -1,36 +1,54 @@
 use std::{collections::HashMap, sync::Arc};
 
-use arrow::datatypes::{Field, Schema, TimeUnit};
+use arrow::array::StringArray;
+use arrow::compute::kernels::cast_utils::{parse_interval_month_day_nano, parse_interval_year_month};
+use arrow::compute::{cast_with_options, CastOptions};
+use arrow::datatypes::{Field, Schema, SchemaRef, TimeUnit};
 use sqlparser::ast::{
-    Assignment, BinaryOperator, CopyOption, CopySource, CopyTarget, Cte, Expression, From, FunctionArgument, Ident,
-    Literal, Order, Select, SelectItem, Statement,
+    Assignment, BinaryOperator, CopyOption, CopySource, CopyTarget, Cte, DateTimeField, Distinct, Expression, From,
+    FunctionArgument, Ident, JoinConstraint, Literal, OnConflict, Order, OrderByExpr, PivotValue, Select, SelectItem,
+    SetOperator, Statement, TableConstraint, TableSample, With,
 };
 
 use crate::{
+    arrow_err,
     common::{
         join_type::JoinType,
+        set_operator,
         table_relation::TableRelation,
         table_schema::{TableSchema, TableSchemaRef},
         transformed::{TransformNode, Transformed, TransformedResult, TreeNodeRecursion},
+        window_frame::WindowFrame,
     },
-    datasource::file::{self, csv::CsvReadOptions},
-    datatypes::scalar::ScalarValue,
+    datasource::{
+        file::{
+            self, compression::FileCompression, csv::CsvReadOptions, json::JsonReadOptions, parquet::ParquetReadOptions,
+        },
+        memory::MemoryTable,
+    },
+    datatypes::{operator::Operator, scalar::ScalarValue},
     error::{Error, Result},
+    execution::config::SessionConfig,
     functions::UserDefinedFunction,
     internal_err,
     logical::{
         expr::*,
         plan::{
-            self, CreateMemoryTable, DdlStatement, DmlStatement, DropTable, Filter, LogicalPlan, SubqueryAlias, Values,
+            self, CreateMemoryTable, CreateView, DdlStatement, Deallocate, DistinctOn, DmlStatement, DropTable,
+            DropView, Execute, Filter, LogicalPlan, Prepare, PreparedStatement, Projection, RecursiveQuery, Sample,
+            SubqueryAlias, Values,
         },
         LogicalPlanBuilder,
     },
     provider::table::TableProvider,
-    utils::{get_file_type, normalize_ident},
+    utils::{get_file_type, normalize_ident, type_coercion::get_common_type},
 };
 
 use self::alias::Alias;
 
+/// the grouping expressions of each grouping set of a GROUP BY clause
+type GroupingSets = Vec<Vec<LogicalExpr>>;
+
 #[derive(Default, Debug)]
 struct Context {
     ctes: HashMap<String, LogicalPlan>,
@@ -38,6 +56,8 @@ struct Context {
     /// table alias -> original table name
     table_aliase: HashMap<String, TableRelation>,
     columns_alias: HashMap<String, LogicalExpr>,
+    /// column merged by a NATURAL or USING join -> relation of the merged column
+    join_using_columns: HashMap<String, Option<TableRelation>>,
 }
 
 pub struct SqlQueryPlanner<'a> {
@@ -45,6 +65,12 @@ pub struct SqlQueryPlanner<'a> {
     // TODO move those field to PlannerContext trait
     udfs: &'a HashMap<String, Arc<dyn UserDefinedFunction>>,
     relations: HashMap<TableRelation, Arc<dyn TableProvider>>,
+    /// the data types of the placeholders `$1`, `$2`, ... of the statement being prepared
+    param_types: Vec<arrow::datatypes::DataType>,
+    /// whether NULL sorts first for an ORDER BY without NULLS FIRST or NULLS LAST
+    default_nulls_first: bool,
+    /// the time zone of `NOW()`
+    timezone: Option<Arc<str>>,
 }
 
 // export the public functions
@@ -52,11 +78,15 @@ impl<'a> SqlQueryPlanner<'a> {
     pub fn new(
         relations: HashMap<TableRelation, Arc<dyn TableProvider>>,
         udfs: &'a HashMap<String, Arc<dyn UserDefinedFunction>>,
+        config: &SessionConfig,
     ) -> Self {
         SqlQueryPlanner {
             contexts: vec![Context::default()],
             relations,
             udfs,
+            param_types: vec![],
+            default_nulls_first: config.default_nulls_first,
+            timezone: config.timezone.as_deref().map(Arc::from),
         }
     }
 
@@ -64,40 +94,57 @@ impl<'a> SqlQueryPlanner<'a> {
         stmt: Statement,
         relations: HashMap<TableRelation, Arc<dyn TableProvider>>,
         udfs: &'a HashMap<String, Arc<dyn UserDefinedFunction>>,
+        config: &SessionConfig,
     ) -> Result<LogicalPlan> {
-        let mut planner = SqlQueryPlanner::new(relations, udfs);
+        SqlQueryPlanner::new(relations, udfs, config).statement_to_plan(stmt)
+    }
 
+    fn statement_to_plan(&mut self, stmt: Statement) -> Result<LogicalPlan> {
         match stmt {
-            Statement::Select(select) => planner.select_to_plan(*select),
+            Statement::Select(_) | Statement::SetOperation { .. } | Statement::Values(_) => self.query_to_plan(stmt),
             Statement::CreateTable {
                 table,
                 check_exists,
                 columns,
+                constraints,
                 query,
             } => {
-                let schema = Arc::new(Schema::new(
-                    columns
-                        .into_iter()
-                        .map(|col| {
-                            let name = col.name.clone();
-                            let data_type = sql_to_arrow_data_type(&col.datatype)?;
-                            Ok(Field::new(&name, data_type, col.nullable))
-                        })
-                        .collect::<Result<Vec<_>>>()?,
-                ));
+                let indexes = table_indexes(&columns, &constraints);
+                let schema = self.create_table_schema(&table, columns, constraints)?;
 
-                let input = if let Some(query) = query {
-                    planner.select_to_plan(query)?
+                let (input, schema) = if let Some(query) = query {
+                    let input = self.query_to_plan(*query)?;
+                    self.create_table_as_plan(input, schema)?
                 } else {
-                    LogicalPlan::EmptyRelation(plan::EmptyRelation {
+                    let input = LogicalPlan::EmptyRelation(plan::EmptyRelation {
                         schema: schema.clone(),
                         produce_one_row: false,
-                    })
+                    });
+                    (input, schema)
                 };
 
-                planner.create_table_to_plan(input, table, schema, check_exists)
+                self.create_table_to_plan(input, table, schema, indexes, check_exists)
+            }
+            Statement::CreateView {
+                or_replace,
+                name,
+                columns,
+                query,
+            } => self.create_view_to_plan(name, or_replace, columns, *query),
+            Statement::CreateSchema { schema, check_exists } => {
+                Ok(LogicalPlan::Ddl(DdlStatement::CreateSchema(plan::CreateSchema {
+                    name: schema,
+                    if_not_exists: check_exists,
+                })))
             }
-            Statement::DropTable { table, check_exists } => planner.drop_table_to_plan(table, check_exists),
+            Statement::DropSchema { schema, check_exists } => {
+                Ok(LogicalPlan::Ddl(DdlStatement::DropSchema(plan::DropSchema {
+                    name: schema,
+                    if_exists: check_exists,
+                })))
+            }
+            Statement::DropTable { table, check_exists } => self.drop_table_to_plan(table, check_exists),
+            Statement::DropView { view, check_exists } => self.drop_view_to_plan(view, check_exists),
             Statement::Insert {
                 table,
                 alias,
@@ -112,11 +159,6 @@ impl<'a> SqlQueryPlanner<'a> {
                         "Alias is not supported for INSERT statement".to_owned(),
                     ));
                 }
-                if on_conflict.is_some() {
-                    return Err(Error::InternalError(
-                        "ON CONFLICT is not supported for INSERT statement".to_owned(),
-                    ));
-                }
                 if returning.is_some() {
                     return Err(Error::InternalError(
                         "RETURNING is not supported for INSERT statement".to_owned(),
@@ -128,22 +170,22 @@ impl<'a> SqlQueryPlanner<'a> {
                     ));
                 }
 
-                let columns = if let Some(col) = columns {
-                    if col.is_empty() {
+                // make sure values have the same length
+                if let Some(len) = values.first().map(|v| v.len()) {
+                    if values.iter().any(|v| v.len() != len) {
                         return Err(Error::InternalError(
-                            "INSERT statement requires at least one column".to_owned(),
+                            "INSERT statement requires all VALUES to have the same length".to_owned(),
                         ));
                     }
-                    // make sure values have the same length
-                    let len = values.first().map(|v| v.len()).ok_or(Error::InternalError(
-                        "INSERT statement requires at least one value".to_owned(),
-                    ))?;
-                    if values.iter().any(|v| v.len() != len) {
+                }
+
+                let columns = if let Some(col) = columns {
+                    if col.is_empty() {
                         return Err(Error::InternalError(
-                            "INSERT statement requires all VALUES to have the same length".to_owned(),
+                            "INSERT statement requires at least one column".to_owned(),
                         ));
                     }
-                    if col.len() != len {
+                    if values.first().is_some_and(|v| v.len() != col.len()) {
                         return Err(Error::InternalError(
                             "INSERT statement requires all VALUES to have the same length as columns".to_owned(),
                         ));
@@ -153,19 +195,66 @@ impl<'a> SqlQueryPlanner<'a> {
                     vec![]
                 };
 
-                planner.insert_to_plan(table, columns, values, query)
+                self.insert_to_plan(table, columns, values, query.map(|query| *query), on_conflict)
             }
-            Statement::Delete { table, r#where } => planner.delete_to_plan(table, r#where),
+            Statement::Delete { table, r#where } => self.delete_to_plan(table, r#where),
+            Statement::Truncate { table } => self.truncate_to_plan(table),
+            Statement::Explain {
+                analyze,
+                verbose,
+                statement,
+            } => self
+                .query_to_plan(*statement)
+                .map(|plan| LogicalPlan::Explain(plan::Explain::new(plan, analyze, verbose))),
             Statement::Update {
                 table,
                 assignments,
                 r#where,
-            } => planner.update_to_plan(table, assignments, r#where),
+            } => self.update_to_plan(table, assignments, r#where),
+            Statement::Prepare {
+                name,
+                data_types,
+                statement,
+            } => {
+                self.param_types = data_types
+                    .iter()
+                    .map(sql_to_arrow_data_type)
+                    .collect::<Result<Vec<_>>>()?;
+                let input = self.statement_to_plan(*statement)?;
+
+                Ok(LogicalPlan::Prepared(PreparedStatement::Prepare(Prepare {
+                    name: normalize_ident(name),
+                    data_types: self.param_types.clone(),
+                    input: Box::new(input),
+                })))
+            }
+            Statement::Execute { name, parameters } => parameters
+                .into_iter()
+                .map(|parameter| self.sql_to_expr(parameter))
+                .collect::<Result<Vec<_>>>()
+                .map(|parameters| {
+                    LogicalPlan::Prepared(PreparedStatement::Execute(Execute {
+                        name: normalize_ident(name),
+                        parameters,
+                    }))
+                }),
+            Statement::Deallocate { name, .. } => {
+                Ok(LogicalPlan::Prepared(PreparedStatement::Deallocate(Deallocate {
+                    name: normalize_ident(name),
+                })))
+            }
             Statement::ShowTables => {
                 // Handle the ShowTables statement explicitly
                 // This could involve returning an appropriate error or handling it in a way that aligns with the application's logic
                 internal_err!("ShowTables statement is not supported in this context")
             }
+            Statement::ShowColumns { .. } => internal_err!("ShowColumns statement is not supported in this context"),
+            Statement::SetVariable { .. } | Statement::ShowVariable { .. } => {
+                internal_err!("configuration statements are executed by the session")
+            }
+            Statement::StartTransaction | Statement::Commit | Statement::Rollback => {
+                internal_err!("transaction statements are executed by the session")
+            }
             Statement::Copy {
                 source,
                 to,
@@ -173,27 +262,32 @@ impl<'a> SqlQueryPlanner<'a> {
                 options,
             } => {
                 if to {
-                    planner.copy_to_plan(source, target, options)
+                    self.copy_to_plan(source, target, options)
                 } else {
-                    planner.copy_from_plan(source, target, options)
+                    self.copy_from_plan(source, target, options)
                 }
             }
-            _ => todo!(),
         }
     }
 }
 
 // functions for Context
 impl<'a> SqlQueryPlanner<'a> {
+    /// register a relation in current context, if the relation has an alias, it's registered as the alias
+    /// and the columns will be qualified by the alias
     fn add_relation(&mut self, relation: TableRelation, schema: TableSchemaRef, alias: Option<String>) -> Result<()> {
         let context = self.current_context();
-        context.relations.insert(relation.clone(), schema);
 
         if let Some(alias) = alias {
             if context.table_aliase.contains_key(&alias) {
                 return internal_err!("Table alias {} already exists", alias);
             }
+            let alias_relation: TableRelation = alias.clone().into();
+            let schema = TableSchema::try_from_qualified_schema(alias_relation.clone(), schema.arrow_schema())?;
+            context.relations.insert(alias_relation, Arc::new(schema));
             context.table_aliase.insert(alias, relation);
+        } else {
+            context.relations.insert(relation, schema);
         }
 
         Ok(())
@@ -242,6 +336,10 @@ impl<'a> SqlQueryPlanner<'a> {
     /// return (relation, is_outer_ref)
     fn get_relation(&self, column_name: &str) -> Result<(Option<TableRelation>, bool)> {
         for (i, ctx) in self.contexts.iter().rev().enumerate() {
+            if let Some(relation) = ctx.join_using_columns.get(column_name) {
+                return Ok((relation.clone(), i > 0));
+            }
+
             let mut matched = vec![];
             for (relation, table_schema) in &ctx.relations {
                 if table_schema.has_field(None, column_name) {
@@ -261,14 +359,35 @@ impl<'a> SqlQueryPlanner<'a> {
     }
 
     /// find the relation of the table
-    fn find_relation(&self, table: &TableRelation) -> Option<(TableRelation, bool)> {
-        self.contexts.iter().rev().enumerate().find_map(|(i, ctx)| {
-            ctx.relations.contains_key(table).then(|| (table.clone(), i > 0)).or(ctx
-                .table_aliase
-                .get(&table.to_string())
-                .cloned()
-                .map(|r| (r, i > 0)))
-        })
+    /// the original name of an aliased table is still accepted as qualifier
+    fn find_relation(&self, table: &TableRelation) -> Result<Option<(TableRelation, bool)>> {
+        for (i, ctx) in self.contexts.iter().rev().enumerate() {
+            if ctx.relations.contains_key(table) {
+                return Ok(Some((table.clone(), i > 0)));
+            }
+            // a qualified table in FROM can be referenced by a less qualified name, `t.a` for `public.t`
+            if let Some(relation) = ctx.relations.keys().find(|relation| relation.matches(table)) {
+                return Ok(Some((relation.clone(), i > 0)));
+            }
+            // an aliased table can only be referenced by its alias
+            if let Some((alias, _)) = ctx.table_aliase.iter().find(|(_, relation)| relation.matches(table)) {
+                return Err(Error::PlanError(format!(
+                    "invalid reference to table {}, it is aliased as {}",
+                    table, alias
+                )));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// the schema of a relation in FROM and whether it is a relation of an outer query
+    fn relation_schema(&self, relation: &TableRelation) -> Option<(TableSchemaRef, bool)> {
+        self.contexts
+            .iter()
+            .rev()
+            .enumerate()
+            .find_map(|(i, ctx)| ctx.relations.get(relation).map(|schema| (schema.clone(), i > 0)))
     }
 
     fn add_column_alias(&mut self, name: String, expr: LogicalExpr) -> Result<()> {
@@ -292,11 +411,60 @@ impl<'a> SqlQueryPlanner<'a> {
 impl<'a> SqlQueryPlanner<'a> {
     fn copy_to_plan(
         &mut self,
-        _source: CopySource,
-        _target: CopyTarget,
-        _options: Vec<CopyOption>,
+        source: CopySource,
+        target: CopyTarget,
+        options: Vec<CopyOption>,
     ) -> Result<LogicalPlan> {
-        todo!()
+        let input = match source {
+            CopySource::Table { table_name, columns } => {
+                let plan = self.table_scan_to_plan(vec![From::Table {
+                    name: table_name,
+                    alias: None,
+                }])?;
+                if columns.is_empty() {
+                    plan
+                } else {
+                    let exprs = columns
+                        .into_iter()
+                        .map(|column| self.sql_to_expr(Expression::Identifier(column)))
+                        .collect::<Result<Vec<_>>>()?;
+                    LogicalPlanBuilder::project(plan, exprs)?
+                }
+            }
+            CopySource::Query(query) => self.query_to_plan(Statement::Select(query))?,
+        };
+
+        let CopyTarget::File { file: file_path } = target;
+        let options = CopyOptions::try_new(&file_path, options)?;
+        if options.row_group_size.is_some() && options.format != "parquet" {
+            return internal_err!("ROW_GROUP_SIZE is only supported by the parquet format");
+        }
+        let format = match options.format.as_str() {
+            "csv" | "tbl" => plan::FileFormat::Csv {
+                has_header: options.has_header,
+                delimiter: options.delimiter,
+                compression: match &options.compression {
+                    Some(compression) => compression.parse()?,
+                    None => FileCompression::from_path(&file_path),
+                },
+            },
+            "json" if options.compression.is_some() => {
+                return internal_err!("COPY TO does not support the compression of json files")
+            }
+            "json" => plan::FileFormat::Json,
+            "parquet" => {
+                if let Some(compression) = &options.compression {
+                    file::parquet::parse_compression(compression)?;
+                }
+                plan::FileFormat::Parquet {
+                    compression: options.compression,
+                    row_group_size: options.row_group_size,
+                }
+            }
+            format => return internal_err!("COPY TO does not support the file format [{}]", format),
+        };
+
+        Ok(LogicalPlan::CopyTo(plan::CopyTo::new(input, file_path, format)))
     }
 
     fn copy_from_plan(
@@ -319,35 +487,34 @@ impl<'a> SqlQueryPlanner<'a> {
             CopySource::Query(_) => return internal_err!("COPY FROM query is not supported"),
         };
 
-        let file_path = match target {
-            CopyTarget::File { file } => file,
-        };
-        let option_map = options
-            .into_iter()
-            .map(|option| match option {
-                CopyOption::Format(ident) => ("format", ident.to_string()),
-                CopyOption::Delimiter(ch) => ("delimiter", ch.to_string()),
-                CopyOption::Header(_) => ("has_header", "true".to_owned()),
-            })
-            .collect::<HashMap<_, _>>();
-        let file_extension = option_map
-            .get("format")
-            .cloned()
-            .or(get_file_type(&file_path).map(|s| s.to_string()))
-            .unwrap_or_default();
-        let target_relation = TableRelation::parse_file_path(&file_path);
-        let input = match file_extension.to_lowercase().as_str() {
+        let CopyTarget::File { file: file_path } = target;
+        let options = CopyOptions::try_new(&file_path, options)?;
+        let table = match options.format.as_str() {
             "csv" | "tbl" => {
-                let mut csv_options = CsvReadOptions::default();
-                csv_options.delimiter = option_map.get("delimiter").map(|s| s.as_bytes()[0]).unwrap_or(b',');
-                csv_options.has_header = option_map.get("has_header").map(|s| s == "true").unwrap_or(false);
-
-                file::csv::read_csv(file_path, csv_options)
-                    .and_then(|table| LogicalPlanBuilder::scan(target_relation.clone(), table, None))
-                    .map(|builder| builder.build())?
+                let csv_options = CsvReadOptions {
+                    delimiter: options.delimiter,
+                    has_header: options.has_header,
+                    compression: options.compression.map(|c| c.parse()).transpose()?,
+                    ..Default::default()
+                };
+                file::csv::read_csv(file_path.as_str(), csv_options)?
             }
-            _ => return internal_err!("COPY FROM only supports csv files"),
+            "json" => {
+                let json_options = JsonReadOptions {
+                    compression: options.compression.map(|c| c.parse()).transpose()?,
+                    ..Default::default()
+                };
+                file::json::read_json(file_path.as_str(), json_options)?
+            }
+            "parquet" => file::parquet::read_parquet(file_path.as_str())?,
+            "arrow" | "arrows" | "ipc" | "feather" => file::ipc::read_ipc(file_path.as_str())?,
+            #[cfg(feature = "avro")]
+            "avro" => file::avro::read_avro(file_path.as_str())?,
+            #[cfg(feature = "orc")]
+            "orc" => file::orc::read_orc(file_path.as_str())?,
+            format => return internal_err!("COPY FROM does not support the file format [{}]", format),
         };
+        let input = LogicalPlanBuilder::scan(TableRelation::parse_file_path(&file_path), table, None)?.build();
 
         self.insert_plan(relation, table_source, input, columns)
     }
@@ -374,14 +541,17 @@ impl<'a> SqlQueryPlanner<'a> {
 
         // update set a = 1
         // key: target_column value: expr
-        let mut assign_map: HashMap<String, LogicalExpr> = assignments
-            .into_iter()
-            .map(|assign| {
-                let name = assign.target.to_string();
-                let value = self.sql_to_expr(assign.value)?;
-                Ok((name, value))
-            })
-            .collect::<Result<_>>()?;
+        let mut assign_map: HashMap<String, LogicalExpr> = HashMap::new();
+        for assign in assignments {
+            let name = assign.target.to_string();
+            if table_schema.field_with_name(&name).is_err() {
+                return internal_err!("Column [{}] of table [{}] does not exist", name, relation);
+            }
+            let value = self.sql_to_expr(assign.value)?;
+            if assign_map.insert(name.clone(), value).is_some() {
+                return internal_err!("Column [{}] is assigned more than once", name);
+            }
+        }
 
         // zip table relation with schema field
         let exprs = table_schema
@@ -431,12 +601,27 @@ impl<'a> SqlQueryPlanner<'a> {
         }))
     }
 
+    fn truncate_to_plan(&mut self, table: String) -> Result<LogicalPlan> {
+        let table_schema = self.get_table_source(&table)?.schema();
+
+        Ok(LogicalPlan::Dml(DmlStatement {
+            relation: table.into(),
+            op: plan::DmlOperator::Truncate,
+            schema: table_schema.clone(),
+            input: Box::new(LogicalPlan::EmptyRelation(plan::EmptyRelation {
+                schema: table_schema,
+                produce_one_row: false,
+            })),
+        }))
+    }
+
     fn insert_to_plan(
         &mut self,
         table: String,
         columns: Vec<Expression>,
         values: Vec<Vec<Expression>>,
         query: Option<Select>,
+        on_conflict: Option<OnConflict>,
     ) -> Result<LogicalPlan> {
         let table_source = self.get_table_source(&table)?;
         // build source value plan
@@ -445,54 +630,321 @@ impl<'a> SqlQueryPlanner<'a> {
         } else {
             self.values_to_plan(values)?
         };
+        let columns = columns
+            .into_iter()
+            .map(|expr| match expr {
+                Expression::Identifier(name) => Ok(name),
+                _ => internal_err!(
+                    "INSERT statement requires column name to be an identifier, but got: {}",
+                    expr
+                ),
+            })
+            .collect::<Result<_>>()?;
 
-        self.insert_plan(
-            table.into(),
-            table_source,
-            source,
-            columns
-                .into_iter()
-                .map(|expr| match expr {
-                    Expression::Identifier(name) => Ok(name),
-                    _ => internal_err!(
-                        "INSERT statement requires column name to be an identifier, but got: {}",
-                        expr
-                    ),
-                })
-                .collect::<Result<_>>()?,
-        )
+        match on_conflict {
+            Some(on_conflict) => self.new_context_scope(|planner| {
+                planner.upsert_plan(table.into(), table_source, source, columns, on_conflict)
+            }),
+            None => self.insert_plan(table.into(), table_source, source, columns),
+        }
     }
 
-    fn drop_table_to_plan(&mut self, table: String, check_exists: bool) -> Result<LogicalPlan> {
-        let relation: TableRelation = table.clone().into();
+    /// plan `INSERT ... ON CONFLICT (keys)` as a join of the rows to insert, aliased as `excluded`, with the target
+    /// table on the equality of the conflict columns.
+    ///
+    /// `DO NOTHING` inserts the rows without a match, `DO UPDATE` upserts all the rows: a row with a match is
+    /// replaced by the assigned values and the others are inserted.
+    fn upsert_plan(
+        &mut self,
+        relation: TableRelation,
+        table_source: Arc<dyn TableProvider>,
+        source: LogicalPlan,
+        columns: Vec<Ident>,
+        on_conflict: OnConflict,
+    ) -> Result<LogicalPlan> {
+        let table_schema = table_source.schema();
+        let excluded: TableRelation = "excluded".into();
+        // the physical columns are resolved by name, the columns of excluded are renamed apart from the columns of
+        // the target table
+        let excluded_column = |name: &str| column(&format!("{}.{}", excluded, name));
+
+        let rows = self.insert_values(&table_source, source, columns)?;
+        let rows = LogicalPlanBuilder::project(
+            rows,
+            table_schema
+                .fields()
+                .iter()
+                .map(|f| column(f.name()).alias(format!("{}.{}", excluded, f.name()))),
+        )?;
+        let target = LogicalPlanBuilder::scan(relation.clone(), table_source, None)?.build();
+
+        self.add_relation(
+            relation.clone(),
+            TableSchema::try_from_qualified_schema(relation.clone(), table_schema.clone()).map(Arc::new)?,
+            None,
+        )?;
+        self.add_relation(
+            excluded.clone(),
+            TableSchema::try_from_qualified_schema(excluded.clone(), table_schema.clone()).map(Arc::new)?,
+            None,
+        )?;
+
+        let (constraints, assignments) = match on_conflict {
+            OnConflict::DoNothing { constraints } => (constraints, None),
+            OnConflict::DoUpdate { constraints, values } => (constraints, Some(values)),
+        };
+        let keys = constraints
+            .into_iter()
+            .map(|ident| {
+                let name = normalize_ident(ident);
+                if table_schema.field_with_name(&name).is_err() {
+                    return internal_err!("Column [{}] of table [{}] does not exist", name, relation);
+                }
+                Ok(name)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let on = keys
+            .iter()
+            .map(|key| {
+                eq(
+                    excluded_column(key),
+                    LogicalExpr::Column(Column::new(key, Some(relation.clone()), false)),
+                )
+            })
+            .reduce(and)
+            .ok_or(Error::InternalError(
+                "ON CONFLICT requires at least one column".to_owned(),
+            ))?;
+
+        let Some(assignments) = assignments else {
+            let exprs = table_schema
+                .fields()
+                .iter()
+                .map(|f| excluded_column(f.name()).alias(f.name()))
+                .collect::<Vec<_>>();
+            let input = LogicalPlanBuilder::from(rows)
+                .join_on(target, JoinType::LeftAnti, on)?
+                .add_project(exprs)?
+                .build();
+
+            return Ok(LogicalPlan::Dml(DmlStatement {
+                relation,
+                op: plan::DmlOperator::Insert,
+                schema: table_schema,
+                input: Box::new(input),
+            }));
+        };
 
-        if !check_exists && !self.relations.contains_key(&relation) {
-            return Err(Error::TableNotFound(table));
+        // do update set a = excluded.a
+        // key: target_column value: expr
+        let mut assign_map: HashMap<String, LogicalExpr> = HashMap::new();
+        for assign in assignments {
+            let (name, value) = match assign {
+                Expression::BinaryOperator(BinaryOperator::Eq(target, value)) => match *target {
+                    Expression::Identifier(ident) => (normalize_ident(ident), *value),
+                    target => return internal_err!("ON CONFLICT DO UPDATE can not assign to {}", target),
+                },
+                expr => return internal_err!("ON CONFLICT DO UPDATE requires assignments, but got: {}", expr),
+            };
+            if table_schema.field_with_name(&name).is_err() {
+                return internal_err!("Column [{}] of table [{}] does not exist", name, relation);
+            }
+            if keys.contains(&name) {
+                return internal_err!("ON CONFLICT DO UPDATE can not assign the conflict column [{}]", name);
+            }
+            let value = self
+                .sql_to_expr(value)?
+                .transform(|expr| match expr {
+                    LogicalExpr::Column(col) if col.relation.as_ref() == Some(&excluded) => {
+                        Ok(Transformed::yes(excluded_column(&col.name)))
+                    }
+                    _ => Ok(Transformed::no(expr)),
+                })
+                .data()?;
+            if assign_map.insert(name.clone(), value).is_some() {
+                return internal_err!("Column [{}] is assigned more than once", name);
+            }
         }
 
+        // a row without a match has NULL conflict columns of the target table
+        let not_matched = LogicalExpr::IsNull(Box::new(LogicalExpr::Column(Column::new(
+            &keys[0],
+            Some(relation.clone()),
+            false,
+        ))));
+        let exprs = table_schema
+            .fields()
+            .iter()
+            .map(|f| {
+                let inserted = excluded_column(f.name());
+                match assign_map.remove(f.name()) {
+                    Some(updated) => LogicalExpr::Case(CaseExpr::new(
+                        None,
+                        vec![(not_matched.clone(), inserted)],
+                        Some(updated),
+                    ))
+                    .cast_to(f.data_type())
+                    .alias(f.name()),
+                    None if keys.contains(f.name()) => inserted.alias(f.name()),
+                    None => LogicalExpr::Case(CaseExpr::new(
+                        None,
+                        vec![(not_matched.clone(), inserted)],
+                        Some(LogicalExpr::Column(Column::new(
+                            f.name(),
+                            Some(relation.clone()),
+                            false,
+                        ))),
+                    ))
+                    .alias(f.name()),
+                }
+            })
+            .collect::<Vec<_>>();
+        let input = LogicalPlanBuilder::from(rows)
+            .join_on(target, JoinType::Left, on)?
+            .add_project(exprs)?
+            .build();
+
+        Ok(LogicalPlan::Dml(DmlStatement {
+            relation,
+            op: plan::DmlOperator::Upsert(keys),
+            schema: table_schema,
+            input: Box::new(input),
+        }))
+    }
+
+    /// the table is resolved when the statement is executed, so `IF EXISTS` can skip a missing table
+    fn drop_table_to_plan(&mut self, table: String, check_exists: bool) -> Result<LogicalPlan> {
         Ok(LogicalPlan::Ddl(DdlStatement::DropTable(DropTable {
             name: table,
             if_exists: check_exists,
         })))
     }
 
+    fn drop_view_to_plan(&mut self, view: String, check_exists: bool) -> Result<LogicalPlan> {
+        Ok(LogicalPlan::Ddl(DdlStatement::DropView(DropView {
+            name: view,
+            if_exists: check_exists,
+        })))
+    }
+
+    /// the schema of the declared columns, the columns of a primary key are not nullable
+    fn create_table_schema(
+        &self,
+        table: &str,
+        columns: Vec<sqlparser::ast::Column>,
+        constraints: Vec<TableConstraint>,
+    ) -> Result<SchemaRef> {
+        let primary_keys = constraints
+            .iter()
+            .filter_map(|constraint| match constraint {
+                TableConstraint::PrimaryKey(names) => Some(names.clone()),
+                TableConstraint::Unique(_) => None,
+            })
+            .chain(
+                columns
+                    .iter()
+                    .filter(|col| col.primary_key)
+                    .map(|col| vec![col.name.clone()]),
+            )
+            .collect::<Vec<_>>();
+        if primary_keys.len() > 1 {
+            return internal_err!("Multiple primary keys for table [{}] are not allowed", table);
+        }
+        for constraint in &constraints {
+            let (TableConstraint::PrimaryKey(names) | TableConstraint::Unique(names)) = constraint;
+            if let Some(name) = names.iter().find(|name| !columns.iter().any(|col| &col.name == *name)) {
+                return internal_err!("Column [{}] named in key does not exist", name);
+            }
+        }
+
+        let mut fields: Vec<Field> = Vec::with_capacity(columns.len());
+        for col in columns {
+            if fields.iter().any(|f| f.name() == &col.name) {
+                return internal_err!("Column [{}] is specified more than once", col.name);
+            }
+            let data_type = sql_to_arrow_data_type(&col.datatype)?;
+            let nullable = col.nullable && !primary_keys.iter().flatten().any(|name| name == &col.name);
+            fields.push(Field::new(&col.name, data_type, nullable));
+        }
+
+        Ok(Arc::new(Schema::new(fields)))
+    }
+
+    /// the rows of `CREATE TABLE ... AS query` and the schema of the table. Without declared columns the table has
+    /// the columns of the query, all nullable, otherwise the query is cast to the declared columns.
+    fn create_table_as_plan(&self, input: LogicalPlan, schema: SchemaRef) -> Result<(LogicalPlan, SchemaRef)> {
+        let input_schema = input.schema();
+
+        if schema.fields().is_empty() {
+            let mut fields: Vec<Field> = Vec::with_capacity(input_schema.fields().len());
+            for field in input_schema.fields() {
+                if fields.iter().any(|f| f.name() == field.name()) {
+                    return internal_err!("Column [{}] is specified more than once", field.name());
+                }
+                fields.push(Field::new(field.name(), field.data_type().clone(), true));
+            }
+
+            return Ok((input, Arc::new(Schema::new(fields))));
+        }
+
+        if input_schema.fields().len() != schema.fields().len() {
+            return internal_err!(
+                "statement requires the {} columns, but got {} columns",
+                schema.fields().len(),
+                input_schema.fields().len(),
+            );
+        }
+        let exprs = schema
+            .fields()
+            .iter()
+            .zip(input_schema.fields())
+            .map(|(target, source)| column(source.name()).cast_to(target.data_type()).alias(target.name()))
+            .collect::<Vec<_>>();
+
+        LogicalPlanBuilder::project(input, exprs).map(|input| (input, schema))
+    }
+
     fn create_table_to_plan(
         &mut self,
         input: LogicalPlan,
         table: String,
         schema: Arc<Schema>,
+        indexes: Vec<Vec<String>>,
         check_exists: bool,
     ) -> Result<LogicalPlan> {
-        if check_exists {
-            if self.relations.contains_key(&table.clone().into()) {
-                return Err(Error::InternalError(format!("table [{}] already exists", table)));
-            }
-        }
-
         Ok(LogicalPlan::Ddl(DdlStatement::CreateMemoryTable(CreateMemoryTable {
             schema,
             name: table,
+            if_not_exists: check_exists,
+            indexes,
+            input: Box::new(input),
+        })))
+    }
+
+    fn create_view_to_plan(
+        &mut self,
+        name: String,
+        or_replace: bool,
+        columns: Vec<Ident>,
+        query: Statement,
+    ) -> Result<LogicalPlan> {
+        let definition = query.to_string();
+        let input = self
+            .new_context_scope(|planner| planner.query_to_plan(query))
+            .and_then(|plan| self.apply_column_aliases(plan, columns))?;
+
+        let schema = input.schema();
+        for (i, field) in schema.fields().iter().enumerate() {
+            if schema.fields().iter().skip(i + 1).any(|f| f.name() == field.name()) {
+                return internal_err!("Column [{}] is specified more than once", field.name());
+            }
+        }
+
+        Ok(LogicalPlan::Ddl(DdlStatement::CreateView(CreateView {
+            name,
+            or_replace,
             input: Box::new(input),
+            definition,
         })))
     }
 
@@ -505,19 +957,28 @@ impl<'a> SqlQueryPlanner<'a> {
                     .collect::<Result<Vec<_>>>()
             })
             .collect::<Result<Vec<_>>>()?;
-        // get the schema from the first row
-        let row = rows.first().ok_or(Error::InternalError("Empty values".to_owned()))?;
+        let len = rows
+            .first()
+            .ok_or(Error::InternalError("Empty values".to_owned()))?
+            .len();
+        if rows.iter().any(|row| row.len() != len) {
+            return internal_err!("VALUES lists must all be the same length");
+        }
         let empty_plan = LogicalPlan::EmptyRelation(plan::EmptyRelation {
             schema: Arc::new(Schema::empty()),
             produce_one_row: false,
         });
-        let schema = row
-            .iter()
-            .enumerate()
-            .map(|(i, expr)| {
-                let name = format!("column{}", i + 1);
-                let data_type = expr.field(&empty_plan).map(|f| f.data_type().clone())?;
-                Ok(Field::new(&name, data_type, true))
+        // the type of a column is the common type of its values in all the rows
+        let schema = (0..len)
+            .map(|i| {
+                let data_type = rows
+                    .iter()
+                    .try_fold(arrow::datatypes::DataType::Null, |data_type, row| {
+                        row[i]
+                            .field(&empty_plan)
+                            .and_then(|field| get_common_type(&data_type, field.data_type()))
+                    })?;
+                Ok(Field::new(format!("column{}", i + 1), data_type, true))
             })
             .collect::<Result<Vec<_>>>()
             .map(|fields| Arc::new(Schema::new(fields)))?;
@@ -528,7 +989,7 @@ impl<'a> SqlQueryPlanner<'a> {
     fn select_to_plan(&mut self, select: Select) -> Result<LogicalPlan> {
         // process `with` clause
         if let Some(with) = select.with {
-            self.cte_tables(with.cte_tables)?;
+            self.cte_tables(with.cte_tables, with.recursive)?;
         }
         // process `from` clause
         let plan = self.table_scan_to_plan(select.from)?;
@@ -543,62 +1004,24 @@ impl<'a> SqlQueryPlanner<'a> {
         let having = select.having.map(|expr| self.sql_to_expr(expr)).transpose()?;
         // get aggregate expressions
         let aggr_exprs = find_aggregate_exprs(column_exprs.iter().chain(having.iter()));
-        // process the GROUP BY clause or process aggregation in SELECT
+        // process the GROUP BY clause or process aggregation in SELECT, a HAVING clause without GROUP BY
+        // makes the query a single group
         let (mut plan, select_exprs_post_aggr, having_expr_post_aggr) =
-            if select.group_by.is_some() || !aggr_exprs.is_empty() {
-                let group_by_exprs = select
-                    .group_by
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|expr| {
-                        let col = self.sql_to_expr(expr)?;
-
-                        col.transform(|expr| match expr {
-                            LogicalExpr::Column(col) => {
-                                if col.relation.is_none() {
-                                    if let Some(data) = self.get_column_alias(&col.name) {
-                                        return Ok(Transformed::yes(data));
-                                    }
-                                }
-                                Ok(Transformed::no(LogicalExpr::Column(col)))
-                            }
-                            _ => Ok(Transformed::no(expr)),
-                        })
-                        .data()
-                    })
-                    .collect::<Result<_>>()?;
-
-                let having = having
-                    .map(|expr| {
-                        expr.transform(|expr| match expr {
-                            LogicalExpr::Column(col) => {
-                                if col.relation.is_none() {
-                                    if let Some(data) = self.get_column_alias(&col.name) {
-                                        return Ok(Transformed::yes(data));
-                                    }
-                                }
-                                Ok(Transformed::no(LogicalExpr::Column(col)))
-                            }
-                            _ => Ok(Transformed::no(expr)),
-                        })
-                        .data()
-                    })
-                    .transpose()?;
-
-                self.aggregate_plan(plan, column_exprs.clone(), aggr_exprs, group_by_exprs, having)?
+            if select.group_by.is_some() || !aggr_exprs.is_empty() || having.is_some() {
+                let (group_by_exprs, grouping_sets) = self.group_by_exprs(select.group_by.unwrap_or_default())?;
+
+                let having = having.map(|expr| self.resolve_column_alias(expr)).transpose()?;
+
+                self.aggregate_plan(
+                    plan,
+                    column_exprs.clone(),
+                    aggr_exprs,
+                    group_by_exprs,
+                    grouping_sets,
+                    having,
+                )?
             } else {
-                match having {
-                    Some(having_expr) => {
-                        // // allow scalar having
-                        // having_expr.apply(|expr| {
-
-                        // })
-                        return internal_err!(
-                    "HAVING clause [{having_expr}] requires a GROUP BY clause or be used in an aggregate function"
-                );
-                    }
-                    None => (plan, column_exprs, None),
-                }
+                (plan, column_exprs.clone(), None)
             };
         // process the HAVE clause
         if let Some(having_expr) = having_expr_post_aggr {
@@ -606,29 +1029,45 @@ impl<'a> SqlQueryPlanner<'a> {
                 .having(having_expr)
                 .map(|builder| builder.build())?;
         }
-        // do the final projection
-        plan = LogicalPlanBuilder::project(plan, select_exprs_post_aggr)?;
-        // process the ORDER BY clause
-        let plan = if !sort_exprs.is_empty() {
-            LogicalPlanBuilder::from(plan)
-                .sort(sort_exprs)
-                .map(|builder| builder.build())?
+        // process window functions, they are evaluated after the aggregation and HAVING clause
+        let window_exprs = find_window_exprs(&select_exprs_post_aggr);
+        let select_exprs_post_aggr = if !window_exprs.is_empty() {
+            let window_exprs_refs = window_exprs.iter().collect::<Vec<_>>();
+            let select_exprs_post_window = select_exprs_post_aggr
+                .into_iter()
+                .map(|expr| expr.rebase_expr(&window_exprs_refs))
+                .collect::<Result<Vec<_>>>()?;
+
+            plan = LogicalPlanBuilder::from(plan)
+                .window(window_exprs)
+                .map(|builder| builder.build())?;
+            select_exprs_post_window
         } else {
-            plan
+            select_exprs_post_aggr
         };
+        // do the final projection and process the DISTINCT and ORDER BY clauses
+        let plan = self.sort_to_plan(plan, &column_exprs, select_exprs_post_aggr, select.distinct, sort_exprs)?;
+
+        // process the LIMIT clause
+        self.limit_to_plan(plan, select.limit, select.offset)
+    }
 
-        if select.limit.is_none() && select.offset.is_none() {
+    fn limit_to_plan(
+        &mut self,
+        plan: LogicalPlan,
+        limit: Option<Expression>,
+        offset: Option<Expression>,
+    ) -> Result<LogicalPlan> {
+        if limit.is_none() && offset.is_none() {
             return Ok(plan);
         }
 
-        // process the LIMIT clause
-        let fetch = select.limit.and_then(|l| {
+        let fetch = limit.and_then(|l| {
             self.sql_to_expr(l)
                 .and_then(|v| get_expr_value(v).map(|v| v as usize))
                 .ok()
         });
-        let skip = select
-            .offset
+        let skip = offset
             .and_then(|o| {
                 self.sql_to_expr(o)
                     .and_then(|v| get_expr_value(v).map(|v| v as usize))
@@ -645,48 +1084,109 @@ impl<'a> SqlQueryPlanner<'a> {
             1 => {
                 let (plan, alias) = match froms.remove(0) {
                     From::Table { name, alias } => {
+                        let name = name.value();
                         let relation: TableRelation = name.clone().into();
 
-                        // try to get ctes table first and the from table registey
+                        // try to get ctes table first and the from table registey, the plan of a view is inlined
                         let scan = if let Some(plan) = self.get_cte_table(&name) {
                             plan
                         } else {
                             let source = self.get_table_source(&name)?;
-                            LogicalPlanBuilder::scan(relation.clone(), source, None)?.build()
+                            match source.get_logical_plan() {
+                                Some(plan) => self.apply_table_alias(plan.clone(), name.clone())?,
+                                None => LogicalPlanBuilder::scan(relation.clone(), source, None)?.build(),
+                            }
                         };
 
                         self.add_relation(relation, scan.table_schema(), alias.clone())?;
 
                         (scan, alias)
                     }
-                    From::TableFunction { name, args, alias } => (self.table_func_to_plan(name, args)?, alias),
+                    From::TableFunction { name, args, alias } => {
+                        (self.table_func_to_plan(name, args, alias.clone())?, alias)
+                    }
                     From::Join {
                         left,
                         right,
-                        on,
+                        constraint,
                         join_type,
                     } => {
                         let left = self.table_scan_to_plan(vec![*left])?;
-                        let right = self.table_scan_to_plan(vec![*right])?;
-
-                        if sqlparser::ast::JoinType::Cross == join_type {
-                            return LogicalPlanBuilder::from(left)
-                                .cross_join(right)
-                                .map(|builder| builder.build());
-                        }
+                        let right = match *right {
+                            From::SubQuery {
+                                query,
+                                alias,
+                                columns,
+                                lateral: true,
+                            } => {
+                                if join_type != sqlparser::ast::JoinType::Cross || constraint != JoinConstraint::None {
+                                    return internal_err!("LATERAL subquery is only supported in CROSS JOIN");
+                                }
+                                return self.lateral_join_to_plan(left, *query, alias, columns);
+                            }
+                            right => self.table_scan_to_plan(vec![right])?,
+                        };
 
-                        let filter_expr = on
-                            .ok_or(Error::InternalError("Join clause requires an ON clause".to_owned()))
-                            .and_then(|expr| self.sql_to_expr(expr))?;
+                        let plan = match constraint {
+                            JoinConstraint::None if join_type == sqlparser::ast::JoinType::Cross => {
+                                LogicalPlanBuilder::from(left).cross_join(right)?.build()
+                            }
+                            JoinConstraint::None => return internal_err!("Join clause requires an ON clause"),
+                            JoinConstraint::On(expr) => {
+                                let filter_expr = self.sql_to_expr(expr)?;
+                                LogicalPlanBuilder::from(left)
+                                    .join_on(right, JoinType::try_from(join_type)?, filter_expr)?
+                                    .build()
+                            }
+                            JoinConstraint::Using(columns) => {
+                                let using = columns.into_iter().map(normalize_ident).collect();
+                                self.join_using_to_plan(left, right, JoinType::try_from(join_type)?, using)?
+                            }
+                            JoinConstraint::Natural => {
+                                let right_schema = right.schema();
+                                let using = left
+                                    .schema()
+                                    .fields()
+                                    .iter()
+                                    .map(|field| field.name().clone())
+                                    .filter(|name| right_schema.field_with_name(name).is_ok())
+                                    .collect();
+                                self.join_using_to_plan(left, right, JoinType::try_from(join_type)?, using)?
+                            }
+                        };
 
-                        (
-                            LogicalPlanBuilder::from(left)
-                                .join_on(right, JoinType::from(join_type), filter_expr)?
-                                .build(),
-                            None,
-                        )
+                        (plan, None)
                     }
-                    _ => todo!(),
+                    From::SubQuery {
+                        query,
+                        alias,
+                        columns,
+                        lateral,
+                    } => (
+                        *self.derived_table_to_plan(*query, alias, columns, lateral)?.subquery,
+                        None,
+                    ),
+                    From::Values { values, alias, columns } => {
+                        (self.values_table_to_plan(values, alias, columns)?, None)
+                    }
+                    From::TableSample { table, sample } => {
+                        let input = self.table_scan_to_plan(vec![*table])?;
+                        (self.table_sample_to_plan(input, sample)?, None)
+                    }
+                    From::Pivot {
+                        table,
+                        aggregate,
+                        column,
+                        values,
+                        alias,
+                    } => (self.pivot_to_plan(*table, aggregate, column, values, alias)?, None),
+                    From::Unpivot {
+                        table,
+                        value,
+                        name,
+                        columns,
+                        alias,
+                    } => (self.unpivot_to_plan(*table, value, name, columns, alias)?, None),
                 };
 
                 if let Some(alias) = alias {
@@ -696,33 +1196,422 @@ impl<'a> SqlQueryPlanner<'a> {
                 }
             }
             _ => {
-                let mut plans = froms.into_iter().map(|f| self.table_scan_to_plan(vec![f]));
-                let mut left = LogicalPlanBuilder::from(plans.next().expect("")?);
-
-                for right in plans {
-                    left = left.cross_join(right?)?;
+                let mut froms = froms.into_iter();
+                let mut left = self.table_scan_to_plan(froms.next().into_iter().collect())?;
+
+                for right in froms {
+                    left = match right {
+                        From::SubQuery {
+                            query,
+                            alias,
+                            columns,
+                            lateral: true,
+                        } => self.lateral_join_to_plan(left, *query, alias, columns)?,
+                        right => {
+                            let right = self.table_scan_to_plan(vec![right])?;
+                            LogicalPlanBuilder::from(left).cross_join(right)?.build()
+                        }
+                    };
                 }
 
-                Ok(left.build())
+                Ok(left)
             }
         }
     }
 
-    fn table_func_to_plan(&mut self, name: String, mut args: Vec<FunctionArgument>) -> Result<LogicalPlan> {
-        let (table_name, provider) = match name.to_lowercase().as_str() {
-            "read_csv" | "read_parquet" | "read_json" => {
-                let path = parse_file_path(&mut args)?;
-                let relation = TableRelation::parse_file_path(&path);
-                let provider = self
-                    .relations
-                    .get(&relation)
-                    .cloned()
+    /// Plan a subquery in FROM as a relation named by its alias, renamed by the column names of the alias if
+    /// any. The columns of the other FROM items are outer references of the subquery, only a LATERAL subquery
+    /// is allowed to reference them.
+    fn derived_table_to_plan(
+        &mut self,
+        query: Statement,
+        alias: Option<String>,
+        columns: Vec<Ident>,
+        lateral: bool,
+    ) -> Result<SubQuery> {
+        let alias = alias.ok_or(Error::InternalError("subquery in FROM must have an alias".to_owned()))?;
+        let mut subquery = self.sql_to_subquery(query)?;
+
+        if !lateral {
+            let relations = &self.current_context().relations;
+            let sibling = subquery.outer_ref_columns.iter().find(|expr| match expr {
+                LogicalExpr::Column(Column {
+                    relation: Some(relation),
+                    ..
+                }) => relations.contains_key(relation),
+                _ => false,
+            });
+            if let Some(column) = sibling {
+                return internal_err!(
+                    "subquery in FROM can not reference {} of another FROM item, use LATERAL instead",
+                    column
+                );
+            }
+        }
+
+        let plan = self
+            .apply_column_aliases(*subquery.subquery, columns)
+            .and_then(|plan| self.apply_table_alias(plan, alias.clone()))?;
+        self.add_relation(alias.into(), plan.table_schema(), None)?;
+        subquery.subquery = Box::new(plan);
+
+        Ok(subquery)
+    }
+
+    /// `TABLESAMPLE method (percent) [REPEATABLE (seed)]`, the percent and the seed must be constants
+    fn table_sample_to_plan(&mut self, input: LogicalPlan, sample: TableSample) -> Result<LogicalPlan> {
+        let percent = match self.sql_to_expr(sample.percent)? {
+            LogicalExpr::Literal(ScalarValue::Int64(Some(v))) => v as f64,
+            LogicalExpr::Literal(ScalarValue::Float64(Some(v))) => v,
+            expr => return internal_err!("TABLESAMPLE percentage must be a numeric constant, got {}", expr),
+        };
+        if !(0.0..=100.0).contains(&percent) {
+            return internal_err!("TABLESAMPLE percentage must be between 0 and 100, got {}", percent);
+        }
+        let seed = sample
+            .seed
+            .map(|seed| {
+                self.sql_to_expr(seed)
+                    .and_then(get_expr_value)
+                    .map(|seed| seed as u64)
+                    .map_err(|_| {
+                        Error::InternalError("TABLESAMPLE REPEATABLE seed must be an integer constant".to_owned())
+                    })
+            })
+            .transpose()?;
+
+        Ok(LogicalPlan::Sample(Sample::new(
+            input,
+            sample.method.into(),
+            percent / 100.0,
+            seed,
+        )))
+    }
+
+    /// `table PIVOT (aggregate FOR column IN (value, ...)) AS alias`, rewritten into a grouping by the other
+    /// columns of the table with an aggregate per value, whose arguments are `CASE WHEN column = value THEN arg END`
+    /// so that only the rows of the value are aggregated
+    fn pivot_to_plan(
+        &mut self,
+        table: From,
+        aggregate: Expression,
+        column: Ident,
+        values: Vec<PivotValue>,
+        alias: Option<String>,
+    ) -> Result<LogicalPlan> {
+        let alias = alias
+            .or_else(|| default_table_alias(&table))
+            .ok_or(Error::InternalError(
+                "PIVOT of a subquery must have an alias".to_owned(),
+            ))?;
+        let column = normalize_ident(column);
+        let Expression::Function(func, args) = aggregate else {
+            return internal_err!("PIVOT requires an aggregate function, got {}", aggregate);
+        };
+
+        // the columns of the table other than the pivot column and the aggregated columns are the grouping columns
+        let (fields, aggregated) = self.new_context_scope(|planner| {
+            let plan = planner.table_scan_to_plan(vec![table.clone()])?;
+            let aggregate = planner.sql_to_expr(Expression::Function(func.clone(), args.clone()))?;
+            if !matches!(aggregate, LogicalExpr::AggregateExpr(_)) {
+                return internal_err!("PIVOT requires an aggregate function, got {}", aggregate);
+            }
+            let fields = plan
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| f.name().clone())
+                .collect::<Vec<_>>();
+            Ok((fields, aggregate.using_columns()))
+        })?;
+        if !fields.contains(&column) {
+            return internal_err!("PIVOT column {} not found", column);
+        }
+        let group_by = fields
+            .iter()
+            .filter(|name| **name != column && aggregated.iter().all(|c| &c.name != *name))
+            .map(|name| quoted_identifier(name))
+            .collect::<Vec<_>>();
+
+        let mut columns = group_by
+            .iter()
+            .cloned()
+            .map(SelectItem::UnNamedExpr)
+            .collect::<Vec<_>>();
+        for PivotValue { value, alias } in values {
+            let condition = Expression::BinaryOperator(BinaryOperator::Eq(
+                Box::new(quoted_identifier(&column)),
+                Box::new(value.clone()),
+            ));
+            let args = args
+                .iter()
+                .map(|arg| {
+                    // `COUNT(*)` counts the rows of the value
+                    let result = match arg {
+                        Expression::Identifier(ident) if ident.value == "*" => Expression::Literal(Literal::Int(1)),
+                        arg => arg.clone(),
+                    };
+                    Expression::Case {
+                        operand: None,
+                        conditions: vec![(condition.clone(), result)],
+                        else_result: None,
+                    }
+                })
+                .collect();
+            // the column of a string value is named by the string, not the quoted literal
+            let alias = alias.unwrap_or_else(|| match &value {
+                Expression::Literal(Literal::String(s)) => s.clone(),
+                value => value.to_string(),
+            });
+            columns.push(SelectItem::ExprWithAlias(
+                Expression::Function(func.clone(), args),
+                alias,
+            ));
+        }
+
+        let select = Select {
+            with: None,
+            distinct: None,
+            columns,
+            from: vec![table],
+            r#where: None,
+            group_by: (!group_by.is_empty()).then_some(group_by),
+            having: None,
+            order_by: None,
+            limit: None,
+            offset: None,
+        };
+        let plan = self
+            .new_context_scope(|planner| planner.select_to_plan(select))
+            .and_then(|plan| self.apply_table_alias(plan, alias.clone()))?;
+        self.add_relation(alias.into(), plan.table_schema(), None)?;
+
+        Ok(plan)
+    }
+
+    /// `table UNPIVOT (value FOR name IN (column, ...)) AS alias`, rewritten into a `UNION ALL` of a query per
+    /// column selecting the other columns of the table, the name of the column and its non NULL values
+    fn unpivot_to_plan(
+        &mut self,
+        table: From,
+        value: Ident,
+        name: Ident,
+        columns: Vec<Ident>,
+        alias: Option<String>,
+    ) -> Result<LogicalPlan> {
+        let alias = alias
+            .or_else(|| default_table_alias(&table))
+            .ok_or(Error::InternalError(
+                "UNPIVOT of a subquery must have an alias".to_owned(),
+            ))?;
+        let (value, name) = (normalize_ident(value), normalize_ident(name));
+        let columns = columns.into_iter().map(normalize_ident).collect::<Vec<_>>();
+
+        let fields = self
+            .new_context_scope(|planner| planner.table_scan_to_plan(vec![table.clone()]))?
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>();
+        if let Some(column) = columns.iter().find(|column| !fields.contains(column)) {
+            return internal_err!("UNPIVOT column {} not found", column);
+        }
+        let others = fields
+            .iter()
+            .filter(|field| !columns.contains(field))
+            .map(|field| SelectItem::UnNamedExpr(quoted_identifier(field)))
+            .collect::<Vec<_>>();
+
+        let query = columns
+            .iter()
+            .map(|column| {
+                let mut items = others.clone();
+                items.push(SelectItem::ExprWithAlias(
+                    Expression::Literal(Literal::String(column.clone())),
+                    name.clone(),
+                ));
+                items.push(SelectItem::ExprWithAlias(quoted_identifier(column), value.clone()));
+
+                Statement::Select(Box::new(Select {
+                    with: None,
+                    distinct: None,
+                    columns: items,
+                    from: vec![table.clone()],
+                    r#where: Some(Expression::IsNotNull(Box::new(quoted_identifier(column)))),
+                    group_by: None,
+                    having: None,
+                    order_by: None,
+                    limit: None,
+                    offset: None,
+                }))
+            })
+            .reduce(|left, right| Statement::SetOperation {
+                op: SetOperator::Union,
+                all: true,
+                left: Box::new(left),
+                right: Box::new(right),
+                order_by: None,
+                limit: None,
+                offset: None,
+            })
+            .ok_or(Error::InternalError("UNPIVOT requires at least one column".to_owned()))?;
+
+        let plan = self
+            .new_context_scope(|planner| planner.query_to_plan(query))
+            .and_then(|plan| self.apply_table_alias(plan, alias.clone()))?;
+        self.add_relation(alias.into(), plan.table_schema(), None)?;
+
+        Ok(plan)
+    }
+
+    /// `(VALUES ...) AS alias (column, ...)` in FROM, a constant relation named by its alias
+    fn values_table_to_plan(
+        &mut self,
+        values: Vec<Vec<Expression>>,
+        alias: Option<String>,
+        columns: Vec<Ident>,
+    ) -> Result<LogicalPlan> {
+        let alias = alias.ok_or(Error::InternalError("VALUES in FROM must have an alias".to_owned()))?;
+        let plan = self
+            .values_to_plan(values)
+            .and_then(|plan| self.apply_column_aliases(plan, columns))
+            .and_then(|plan| self.apply_table_alias(plan, alias.clone()))?;
+        self.add_relation(alias.into(), plan.table_schema(), None)?;
+
+        Ok(plan)
+    }
+
+    /// `left CROSS JOIN LATERAL (query) AS alias`, the subquery is evaluated for each row of the left side
+    /// unless it does not reference any column of it, then it is a plain cross join.
+    fn lateral_join_to_plan(
+        &mut self,
+        left: LogicalPlan,
+        query: Statement,
+        alias: Option<String>,
+        columns: Vec<Ident>,
+    ) -> Result<LogicalPlan> {
+        let subquery = self.derived_table_to_plan(query, alias, columns, true)?;
+        let builder = LogicalPlanBuilder::from(left);
+
+        if subquery.outer_ref_columns.is_empty() {
+            builder.cross_join(*subquery.subquery)
+        } else {
+            builder.lateral_join(subquery)
+        }
+        .map(|builder| builder.build())
+    }
+
+    /// Join on the equality of the `using` columns of both sides, each pair of them is merged into one output
+    /// column placed first, followed by the other columns of the left side and then of the right side.
+    fn join_using_to_plan(
+        &mut self,
+        left: LogicalPlan,
+        right: LogicalPlan,
+        join_type: JoinType,
+        using: Vec<String>,
+    ) -> Result<LogicalPlan> {
+        let left_columns = left.table_schema().columns();
+        let mut right_columns = right.table_schema().columns();
+        let find_column = |columns: &[Column], name: &str| {
+            columns
+                .iter()
+                .find(|column| column.name == name)
+                .cloned()
+                .ok_or(Error::InternalError(format!(
+                    "column [{}] of the join must appear in both sides",
+                    name
+                )))
+        };
+
+        // the physical columns are resolved by name, rename the right using columns to tell them from the left ones
+        let right_exprs = right_columns
+            .iter()
+            .map(|column| {
+                let expr = LogicalExpr::Column(column.clone());
+                if using.contains(&column.name) {
+                    LogicalExpr::Alias(Alias::new(column.quanlified_name(), expr))
+                } else {
+                    expr
+                }
+            })
+            .collect::<Vec<_>>();
+        let right = LogicalPlanBuilder::project(right, right_exprs)?;
+
+        let mut filter = None;
+        let mut exprs = vec![];
+        for name in &using {
+            let left_column = find_column(&left_columns, name)?;
+            let relation = match join_type {
+                JoinType::Right | JoinType::Full => None,
+                _ => left_column.relation.clone(),
+            };
+            let left_column = LogicalExpr::Column(left_column);
+            let right_column = column(&find_column(&right_columns, name)?.quanlified_name());
+
+            let condition = eq(left_column.clone(), right_column.clone());
+            filter = Some(match filter {
+                Some(filter) => and(filter, condition),
+                None => condition,
+            });
+
+            self.current_context().join_using_columns.insert(name.clone(), relation);
+
+            exprs.push(match join_type {
+                JoinType::Inner | JoinType::Left | JoinType::LeftSemi | JoinType::LeftAnti => left_column,
+                JoinType::Right => LogicalExpr::Alias(Alias::new(name.clone(), right_column)),
+                JoinType::Full => LogicalExpr::Alias(Alias::new(
+                    name.clone(),
+                    LogicalExpr::Case(CaseExpr::new(
+                        None,
+                        vec![(LogicalExpr::IsNull(Box::new(left_column.clone())), right_column)],
+                        Some(left_column),
+                    )),
+                )),
+            });
+        }
+        // semi and anti joins only output the left side
+        if join_type.is_semi_or_anti() {
+            right_columns.clear();
+        }
+        exprs.extend(
+            left_columns
+                .into_iter()
+                .chain(right_columns)
+                .filter(|column| !using.contains(&column.name))
+                .map(LogicalExpr::Column),
+        );
+
+        // without using columns every row of the left side matches every row of the right side
+        let filter = filter.unwrap_or(LogicalExpr::Literal(ScalarValue::Boolean(Some(true))));
+        let plan = LogicalPlanBuilder::from(left)
+            .join_on(right, join_type, filter)?
+            .build();
+
+        LogicalPlanBuilder::project(plan, exprs)
+    }
+
+    fn table_func_to_plan(
+        &mut self,
+        name: String,
+        mut args: Vec<FunctionArgument>,
+        alias: Option<String>,
+    ) -> Result<LogicalPlan> {
+        let (table_name, provider) = match name.to_lowercase().as_str() {
+            "read_csv" | "read_parquet" | "read_json" | "read_ipc" | "read_avro" | "read_orc" | "read_delta"
+            | "read_iceberg" | "read_excel" => {
+                let path = parse_file_path(&mut args)?;
+                let relation = TableRelation::parse_file_path(&path);
+                let provider = self
+                    .relations
+                    .get(&relation)
+                    .cloned()
                     .ok_or(Error::TableNotFound(path))?;
 
                 self.add_relation(
                     relation.clone(),
                     TableSchema::try_from_qualified_schema(relation.clone(), provider.schema())?.into(),
-                    None,
+                    alias,
                 )?;
 
                 (relation, provider)
@@ -755,6 +1644,27 @@ impl<'a> SqlQueryPlanner<'a> {
         columns: Vec<Ident>,
     ) -> Result<LogicalPlan> {
         let table_schema = target_table_provider.schema();
+
+        self.insert_values(&target_table_provider, value_source, columns)
+            .map(|input| {
+                LogicalPlan::Dml(DmlStatement {
+                    relation: target_relation,
+                    op: plan::DmlOperator::Insert,
+                    schema: table_schema,
+                    input: Box::new(input),
+                })
+            })
+    }
+
+    /// project the source to the columns of the target table, the columns not in the column list get their
+    /// default values
+    fn insert_values(
+        &mut self,
+        target_table_provider: &Arc<dyn TableProvider>,
+        value_source: LogicalPlan,
+        columns: Vec<Ident>,
+    ) -> Result<LogicalPlan> {
+        let table_schema = target_table_provider.schema();
         // if value_indices[i] = Some(j), it means that the value of the i-th target table's column is
         // derived from the j-th output of the source.
         //
@@ -800,12 +1710,9 @@ impl<'a> SqlQueryPlanner<'a> {
             .map(|(i, value_index)| {
                 let target_field = table_schema.field(i);
                 match value_index {
-                    Some(v) => {
-                        let target_field = table_schema.field(v);
-                        Ok(column(source_schema.field(v).name())
-                            .cast_to(target_field.data_type())
-                            .alias(target_field.name()))
-                    }
+                    Some(v) => Ok(column(source_schema.field(v).name())
+                        .cast_to(target_field.data_type())
+                        .alias(target_field.name())),
                     None => {
                         let default_value = target_table_provider.get_column_default(target_field.name());
                         if !target_field.is_nullable() && default_value.is_none() {
@@ -822,14 +1729,88 @@ impl<'a> SqlQueryPlanner<'a> {
             })
             .collect::<Result<Vec<LogicalExpr>>>()?;
 
-        LogicalPlanBuilder::project(value_source, exprs).map(|input| {
-            LogicalPlan::Dml(DmlStatement {
-                relation: target_relation,
-                op: plan::DmlOperator::Insert,
-                schema: table_schema,
-                input: Box::new(input),
-            })
+        LogicalPlanBuilder::project(value_source, exprs)
+    }
+
+    /// replace the unqualified columns referencing an alias of the select list with the aliased expression
+    fn resolve_column_alias(&self, expr: LogicalExpr) -> Result<LogicalExpr> {
+        expr.transform(|expr| match expr {
+            LogicalExpr::Column(col) => {
+                if col.relation.is_none() {
+                    if let Some(data) = self.get_column_alias(&col.name) {
+                        return Ok(Transformed::yes(data));
+                    }
+                }
+                Ok(Transformed::no(LogicalExpr::Column(col)))
+            }
+            _ => Ok(Transformed::no(expr)),
         })
+        .data()
+    }
+
+    /// plan the GROUP BY clause, returns all the grouping expressions and the grouping sets if `ROLLUP`, `CUBE` or
+    /// `GROUPING SETS` is used. The grouping sets of the elements are combined by their cross product, eg.
+    /// `GROUP BY a, ROLLUP (b, c)` groups by `(a, b, c)`, `(a, b)` and `(a)`.
+    fn group_by_exprs(&mut self, group_by: Vec<Expression>) -> Result<(Vec<LogicalExpr>, Option<GroupingSets>)> {
+        let mut has_grouping_sets = false;
+        let mut grouping_sets = vec![vec![]];
+
+        for element in group_by {
+            let mut to_exprs = |exprs: Vec<Expression>| {
+                exprs
+                    .into_iter()
+                    .map(|expr| self.sql_to_expr(expr).and_then(|expr| self.resolve_column_alias(expr)))
+                    .collect::<Result<Vec<_>>>()
+            };
+
+            let element_sets = match element {
+                Expression::Rollup(exprs) => {
+                    let exprs = to_exprs(exprs)?;
+                    (0..=exprs.len()).rev().map(|len| exprs[..len].to_vec()).collect()
+                }
+                Expression::Cube(exprs) => {
+                    let exprs = to_exprs(exprs)?;
+                    (0..1usize << exprs.len())
+                        .rev()
+                        .map(|mask| {
+                            exprs
+                                .iter()
+                                .enumerate()
+                                .filter(|(i, _)| mask & (1 << (exprs.len() - 1 - i)) != 0)
+                                .map(|(_, expr)| expr.clone())
+                                .collect()
+                        })
+                        .collect()
+                }
+                Expression::GroupingSets(sets) => sets.into_iter().map(&mut to_exprs).collect::<Result<Vec<_>>>()?,
+                expr => {
+                    grouping_sets.iter_mut().try_for_each(|set| {
+                        set.extend(to_exprs(vec![expr.clone()])?);
+                        Ok::<_, Error>(())
+                    })?;
+                    continue;
+                }
+            };
+
+            has_grouping_sets = true;
+            grouping_sets = grouping_sets
+                .iter()
+                .flat_map(|set| {
+                    element_sets
+                        .iter()
+                        .map(move |element_set: &Vec<LogicalExpr>| [set.clone(), element_set.clone()].concat())
+                })
+                .collect();
+        }
+
+        let mut group_exprs = vec![];
+        for expr in grouping_sets.iter().flatten() {
+            if !group_exprs.contains(expr) {
+                group_exprs.push(expr.clone());
+            }
+        }
+
+        Ok((group_exprs, has_grouping_sets.then_some(grouping_sets)))
     }
 
     fn aggregate_plan(
@@ -838,6 +1819,7 @@ impl<'a> SqlQueryPlanner<'a> {
         select_exprs: Vec<LogicalExpr>,
         aggr_exprs: Vec<LogicalExpr>,
         group_exprs: Vec<LogicalExpr>,
+        grouping_sets: Option<Vec<Vec<LogicalExpr>>>,
         having: Option<LogicalExpr>,
     ) -> Result<(LogicalPlan, Vec<LogicalExpr>, Option<LogicalExpr>)> {
         let agg_and_group_by_column_exprs = aggr_exprs.iter().chain(group_exprs.iter()).collect::<Vec<_>>();
@@ -876,75 +1858,523 @@ impl<'a> SqlQueryPlanner<'a> {
             }
         }
 
-        let plan = LogicalPlanBuilder::from(input)
-            .aggregate(group_exprs, aggr_exprs)
-            .map(|plan| plan.build())?;
+        let (grouping_exprs, aggr_exprs) = aggr_exprs.into_iter().partition::<Vec<_>, _>(|expr| {
+            matches!(
+                expr,
+                LogicalExpr::AggregateExpr(AggregateExpr {
+                    op: AggregateOperator::Grouping,
+                    ..
+                })
+            )
+        });
+
+        let plan = if grouping_sets.is_none() && grouping_exprs.is_empty() {
+            LogicalPlanBuilder::from(input)
+                .aggregate(group_exprs, aggr_exprs)
+                .map(|plan| plan.build())?
+        } else {
+            let grouping_sets = grouping_sets.unwrap_or_else(|| vec![group_exprs.clone()]);
+            self.grouping_sets_plan(input, &group_exprs, grouping_sets, aggr_exprs, grouping_exprs)?
+        };
 
         Ok((plan, select_exprs_post_aggr, having_expr_post_aggr))
     }
 
-    fn order_by_exprs(&mut self, order_by: Vec<(Expression, Order)>) -> Result<Vec<SortExpr>> {
+    /// plan the union of an aggregation for each grouping set, the output columns are the same as the aggregation
+    /// grouped by all the grouping expressions. The grouping expressions not in a grouping set are NULL in its rows,
+    /// and `GROUPING(expr)` is evaluated for each grouping set.
+    fn grouping_sets_plan(
+        &self,
+        input: LogicalPlan,
+        group_exprs: &[LogicalExpr],
+        grouping_sets: Vec<Vec<LogicalExpr>>,
+        aggr_exprs: Vec<LogicalExpr>,
+        grouping_exprs: Vec<LogicalExpr>,
+    ) -> Result<LogicalPlan> {
+        for expr in &grouping_exprs {
+            if let LogicalExpr::AggregateExpr(AggregateExpr { expr: arg, .. }) = expr {
+                if !group_exprs.contains(arg) {
+                    return internal_err!("argument of [{}] must be a GROUP BY expression", expr);
+                }
+            }
+        }
+
+        let plans = grouping_sets
+            .into_iter()
+            .map(|set| {
+                let mut exprs = vec![];
+                for expr in group_exprs {
+                    if set.contains(expr) {
+                        exprs.push(expr.as_column()?);
+                    } else {
+                        let field = expr.field(&input)?;
+                        exprs.push(
+                            LogicalExpr::Literal(ScalarValue::Null)
+                                .cast_to(field.data_type())
+                                .alias(field.name()),
+                        );
+                    }
+                }
+                for expr in &aggr_exprs {
+                    exprs.push(expr.as_column()?);
+                }
+                for expr in &grouping_exprs {
+                    let LogicalExpr::AggregateExpr(AggregateExpr { expr: arg, .. }) = expr else {
+                        return internal_err!("LogicalExpr should be AggregateExpr, but got {:?}", expr);
+                    };
+                    let grouping = LogicalExpr::Literal(ScalarValue::Int64(Some(!set.contains(arg) as i64)));
+                    exprs.push(grouping.alias(expr.to_string()));
+                }
+
+                LogicalPlanBuilder::from(input.clone())
+                    .aggregate(set, aggr_exprs.clone())
+                    .and_then(|plan| LogicalPlanBuilder::project(plan.build(), exprs))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        plans
+            .into_iter()
+            .map(Ok)
+            .reduce(|left, right| {
+                let (left, right) = (left?, right?);
+                let schema = Arc::new(Schema::new(
+                    left.schema()
+                        .fields()
+                        .iter()
+                        .zip(right.schema().fields().iter())
+                        .map(|(l, r)| Field::new(l.name(), l.data_type().clone(), l.is_nullable() || r.is_nullable()))
+                        .collect::<Vec<_>>(),
+                ));
+
+                Ok(LogicalPlan::SetOperation(plan::SetOperation {
+                    op: set_operator::SetOperator::Union,
+                    all: true,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    schema,
+                }))
+            })
+            .unwrap_or_else(|| internal_err!("GROUP BY requires at least one grouping set"))
+    }
+
+    fn order_by_exprs(&mut self, order_by: Vec<OrderByExpr>) -> Result<Vec<SortExpr>> {
         order_by
             .into_iter()
-            .map(|(sort_expr, order)| {
-                self.sql_to_expr(sort_expr).map(|expr| SortExpr {
+            .map(|order_by| {
+                self.sql_to_expr(order_by.expr).map(|expr| SortExpr {
                     expr: Box::new(expr),
-                    asc: order == Order::Asc,
+                    asc: order_by.order == Order::Asc,
+                    nulls_first: order_by.nulls_first.unwrap_or(self.default_nulls_first),
                 })
             })
             .collect::<Result<Vec<_>>>()
     }
 
-    fn cte_tables(&mut self, ctes: Vec<Cte>) -> Result<()> {
-        ctes.into_iter().try_for_each(|cte| {
-            self.new_context_scope(|planner| planner.select_to_plan(*cte.query))
-                .and_then(|plan| self.apply_table_alias(plan, cte.alias.clone()))
-                .and_then(|plan| self.add_cte_table(cte.alias, plan))
-        })
-    }
-
-    fn column_exprs(
+    /// Project the select expressions, remove the duplicate rows of `DISTINCT` or `DISTINCT ON` and sort the result.
+    /// The sort keys and the `DISTINCT ON` keys are evaluated against the projection, so a key which is also selected
+    /// is replaced by the projected column, and the columns the keys reference but not selected are projected as well
+    /// and removed at last.
+    fn sort_to_plan(
         &mut self,
-        plan: &LogicalPlan,
-        empty_from: bool,
-        columns: Vec<SelectItem>,
-    ) -> Result<Vec<LogicalExpr>> {
-        columns
+        input: LogicalPlan,
+        column_exprs: &[LogicalExpr],
+        select_exprs: Vec<LogicalExpr>,
+        distinct: Option<Distinct>,
+        sort_exprs: Vec<SortExpr>,
+    ) -> Result<LogicalPlan> {
+        let plan = LogicalPlanBuilder::project(input.clone(), select_exprs.clone())?;
+        if sort_exprs.is_empty() && distinct.is_none() {
+            return Ok(plan);
+        }
+
+        let schema = plan.table_schema();
+        let output_columns = schema.columns();
+        let select_columns = column_exprs
+            .iter()
+            .zip(select_exprs.iter())
+            .zip(output_columns.iter())
+            .flat_map(|((column_expr, select_expr), column)| {
+                [column_expr, select_expr]
+                    .into_iter()
+                    .flat_map(|expr| match expr {
+                        LogicalExpr::Alias(alias) => vec![expr, alias.expr.as_ref()],
+                        _ => vec![expr],
+                    })
+                    .map(move |expr| (expr.clone(), column))
+            })
+            .collect::<Vec<_>>();
+        let rebase_expr = |expr: LogicalExpr| {
+            expr.transform(|expr| {
+                if let LogicalExpr::Column(col) = &expr {
+                    if schema.has_field(col.relation.clone(), &col.name) {
+                        return Ok(Transformed::no(expr));
+                    }
+                }
+                match select_columns.iter().find(|(select_expr, _)| select_expr == &expr) {
+                    Some((_, column)) => Ok(Transformed::yes(LogicalExpr::Column(Column::new(
+                        column.name.clone(),
+                        None::<TableRelation>,
+                        false,
+                    )))),
+                    None => Ok(Transformed::no(expr)),
+                }
+            })
+            .data()
+        };
+
+        let sort_exprs = sort_exprs
             .into_iter()
-            .flat_map(|col| match self.sql_select_item_to_expr(plan, col, empty_from) {
-                Ok(vec) => vec.into_iter().map(Ok).collect(),
-                Err(err) => vec![Err(err)],
+            .map(|sort| {
+                Ok(SortExpr {
+                    expr: Box::new(rebase_expr(*sort.expr)?),
+                    ..sort
+                })
             })
-            .collect::<Result<Vec<LogicalExpr>>>()
-    }
+            .collect::<Result<Vec<_>>>()?;
+        let distinct_on = match &distinct {
+            Some(Distinct::DISTINCT(exprs)) => Some(
+                exprs
+                    .iter()
+                    .map(|expr| self.sql_to_expr(expr.clone()).and_then(rebase_expr))
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            _ => None,
+        };
 
-    fn sql_to_expr(&mut self, expr: Expression) -> Result<LogicalExpr> {
-        match expr {
-            Expression::CompoundIdentifier(mut idents) => {
-                if idents.len() != 2 {
-                    return Err(Error::InternalError(format!(
-                        "CompoundIdentifier should have two parts, but got {}",
-                        idents.len()
-                    )));
+        let mut missing_columns = vec![];
+        let keys = sort_exprs
+            .iter()
+            .map(|sort| sort.expr.as_ref())
+            .chain(distinct_on.iter().flatten());
+        for key in keys {
+            for col in key.column_refs() {
+                if !col.is_outer_ref
+                    && !schema.has_field(col.relation.clone(), &col.name)
+                    && !missing_columns.contains(col)
+                {
+                    missing_columns.push(col.clone());
                 }
+            }
+        }
 
-                let name = normalize_ident(idents.remove(1));
-                let relation = idents.remove(0).value.into();
-                if let Some((relation, is_outer_ref)) = self.find_relation(&relation) {
-                    return Ok(LogicalExpr::Column(Column::new(name, Some(relation), is_outer_ref)));
+        let mut plan = if let Some(Distinct::ALL) = distinct {
+            if let Some(col) = missing_columns.first() {
+                return internal_err!(
+                    "for SELECT DISTINCT, ORDER BY expression [{}] must appear in the select list",
+                    col
+                );
+            }
+            // DISTINCT is DISTINCT ON all the output columns
+            let on_expr = output_columns.iter().cloned().map(LogicalExpr::Column).collect();
+            LogicalPlan::DistinctOn(DistinctOn::new(plan, on_expr))
+        } else if missing_columns.is_empty() {
+            plan
+        } else {
+            let exprs = select_exprs
+                .into_iter()
+                .chain(missing_columns.iter().cloned().map(LogicalExpr::Column))
+                .collect::<Vec<_>>();
+            LogicalPlanBuilder::project(input, exprs)?
+        };
+
+        if !sort_exprs.is_empty() {
+            plan = LogicalPlanBuilder::from(plan).sort(sort_exprs)?.build();
+        }
+        if let Some(on_expr) = distinct_on {
+            plan = LogicalPlan::DistinctOn(DistinctOn::new(plan, on_expr));
+        }
+
+        if missing_columns.is_empty() {
+            Ok(plan)
+        } else {
+            LogicalPlanBuilder::project(plan, output_columns.into_iter().map(LogicalExpr::Column))
+        }
+    }
+
+    fn cte_tables(&mut self, ctes: Vec<Cte>, recursive: bool) -> Result<()> {
+        ctes.into_iter().try_for_each(|cte| {
+            let plan = match *cte.query {
+                Statement::SetOperation {
+                    op: SetOperator::Union,
+                    all,
+                    left,
+                    right,
+                    ..
+                } if recursive => self.recursive_cte_to_plan(&cte.alias, cte.columns, *left, *right, !all)?,
+                query => self
+                    .new_context_scope(|planner| planner.query_to_plan(query))
+                    .and_then(|plan| self.apply_column_aliases(plan, cte.columns))?,
+            };
+
+            self.apply_table_alias(plan, cte.alias.clone())
+                .and_then(|plan| self.add_cte_table(cte.alias, plan))
+        })
+    }
+
+    fn query_to_plan(&mut self, query: Statement) -> Result<LogicalPlan> {
+        match query {
+            Statement::Select(select) => self.select_to_plan(*select),
+            Statement::Values(values) => self.values_to_plan(values),
+            Statement::SetOperation {
+                op,
+                all,
+                mut left,
+                right,
+                order_by,
+                limit,
+                offset,
+            } => {
+                // the CTEs are attached to the leftmost query, they are visible to all the inputs
+                if let Some(with) = leftmost_with(&mut left) {
+                    self.cte_tables(with.cte_tables, with.recursive)?;
                 }
+                let plan = self.set_operation_to_plan(op.into(), all, *left, *right)?;
+                let sort_exprs = self.order_by_exprs(order_by.unwrap_or_default())?;
+                let plan = if !sort_exprs.is_empty() {
+                    LogicalPlanBuilder::from(plan)
+                        .sort(sort_exprs)
+                        .map(|builder| builder.build())?
+                } else {
+                    plan
+                };
 
-                internal_err!(
-                    "Column [\"{}\"] not found in table [\"{}\"] or table not exists",
-                    name,
-                    relation
-                )
+                self.limit_to_plan(plan, limit, offset)
             }
-            Expression::Identifier(ident) => {
-                let col_name = normalize_ident(ident);
+            _ => internal_err!("Expected a query, but got: {}", query),
+        }
+    }
+
+    /// plan each input in its own scope and coerce both of them to the common schema,
+    /// the column names are taken from the left input
+    fn set_operation_to_plan(
+        &mut self,
+        op: set_operator::SetOperator,
+        all: bool,
+        left: Statement,
+        right: Statement,
+    ) -> Result<LogicalPlan> {
+        let left = self.new_context_scope(|planner| planner.query_to_plan(left))?;
+        let right = self.new_context_scope(|planner| planner.query_to_plan(right))?;
+        let (left_schema, right_schema) = (left.schema(), right.schema());
+        if left_schema.fields().len() != right_schema.fields().len() {
+            return internal_err!(
+                "{} queries have different number of columns: left has {} columns whereas right has {} columns",
+                op,
+                left_schema.fields().len(),
+                right_schema.fields().len()
+            );
+        }
+
+        let schema = Arc::new(Schema::new(
+            left_schema
+                .fields()
+                .iter()
+                .zip(right_schema.fields().iter())
+                .map(|(l, r)| {
+                    get_common_type(l.data_type(), r.data_type())
+                        .map(|data_type| Field::new(l.name(), data_type, l.is_nullable() || r.is_nullable()))
+                })
+                .collect::<Result<Vec<_>>>()?,
+        ));
+        let coerce = |planner: &mut Self, plan: LogicalPlan| {
+            let schema_types = schema.fields().iter().map(|f| f.data_type());
+            if plan.schema().fields().iter().map(|f| f.data_type()).eq(schema_types) {
+                Ok(plan)
+            } else {
+                planner.coerce_plan_to_schema(plan, &schema)
+            }
+        };
+
+        Ok(LogicalPlan::SetOperation(plan::SetOperation {
+            op,
+            all,
+            left: Box::new(coerce(self, left)?),
+            right: Box::new(coerce(self, right)?),
+            schema,
+        }))
+    }
+
+    /// plan `WITH RECURSIVE name AS (static_term UNION [ALL] recursive_term)`,
+    /// the self reference in the recursive term is planned as a scan of the work table
+    fn recursive_cte_to_plan(
+        &mut self,
+        name: &str,
+        columns: Vec<Ident>,
+        static_term: Statement,
+        recursive_term: Statement,
+        is_distinct: bool,
+    ) -> Result<LogicalPlan> {
+        let static_plan = self
+            .new_context_scope(|planner| planner.query_to_plan(static_term))
+            .and_then(|plan| self.apply_column_aliases(plan, columns))?;
+        // the rows produced by later iterations may contain nulls
+        let schema = Arc::new(Schema::new(
+            static_plan
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.as_ref().clone().with_nullable(true))
+                .collect::<Vec<_>>(),
+        ));
+        let work_table = Arc::new(MemoryTable::try_new(schema.clone(), vec![])?);
+        let work_table_source: Arc<dyn TableProvider> = work_table.clone();
+        let work_table_scan = LogicalPlanBuilder::scan(name, work_table_source.clone(), None)?.build();
+
+        let recursive_plan = self.new_context_scope(|planner| {
+            planner.add_cte_table(name.to_owned(), work_table_scan)?;
+            planner.query_to_plan(recursive_term)
+        })?;
+
+        if !scan_table(&recursive_plan, &work_table_source) {
+            return internal_err!("Recursive CTE [{}] must reference itself in the recursive term", name);
+        }
+
+        Ok(LogicalPlan::RecursiveQuery(RecursiveQuery {
+            name: name.to_owned(),
+            static_term: Box::new(static_plan),
+            recursive_term: Box::new(self.coerce_plan_to_schema(recursive_plan, &schema)?),
+            is_distinct,
+            work_table,
+        }))
+    }
+
+    /// cast and rename the output columns of the plan to match the given schema by position
+    fn coerce_plan_to_schema(&mut self, input: LogicalPlan, schema: &SchemaRef) -> Result<LogicalPlan> {
+        let input_schema = input.schema();
+        if input_schema.fields().len() != schema.fields().len() {
+            return internal_err!(
+                "UNION queries have different number of columns: left has {} columns whereas right has {} columns",
+                schema.fields().len(),
+                input_schema.fields().len()
+            );
+        }
+
+        let coerce = |expr: LogicalExpr, from: &Field, to: &Field| {
+            if from.data_type() != to.data_type() {
+                expr.cast_to(to.data_type()).alias(to.name())
+            } else {
+                expr.alias(to.name())
+            }
+        };
+        let fields = input_schema.fields().iter().zip(schema.fields().iter());
+        // keep the exprs of the projection, output columns may not have unique names
+        if let LogicalPlan::Projection(Projection { input, exprs, .. }) = input {
+            let exprs = exprs
+                .into_iter()
+                .zip(fields)
+                .map(|(expr, (from, to))| match expr {
+                    LogicalExpr::Alias(Alias { expr, .. }) => coerce(*expr, from, to),
+                    expr => coerce(expr, from, to),
+                })
+                .collect::<Vec<_>>();
+
+            return LogicalPlanBuilder::project(*input, exprs);
+        }
+
+        let exprs = fields
+            .map(|(from, to)| coerce(column(from.name()), from, to))
+            .collect::<Vec<_>>();
+
+        LogicalPlanBuilder::project(input, exprs)
+    }
+
+    /// rename the output columns of the plan, eg. `WITH t(a, b) AS (...)`
+    fn apply_column_aliases(&mut self, input: LogicalPlan, columns: Vec<Ident>) -> Result<LogicalPlan> {
+        if columns.is_empty() {
+            return Ok(input);
+        }
+
+        let schema = input.schema();
+        if schema.fields().len() != columns.len() {
+            return internal_err!(
+                "Source table contains {} columns but only {} names given as column alias",
+                schema.fields().len(),
+                columns.len()
+            );
+        }
+
+        let names = columns.into_iter().map(normalize_ident);
+        // rename the projection exprs in place, output columns may not have unique names, eg. `SELECT 1, 1`
+        if let LogicalPlan::Projection(Projection { input, exprs, .. }) = input {
+            let exprs = exprs
+                .into_iter()
+                .zip(names)
+                .map(|(expr, name)| match expr {
+                    LogicalExpr::Alias(Alias { expr, .. }) => expr.alias(name),
+                    expr => expr.alias(name),
+                })
+                .collect::<Vec<_>>();
+
+            return LogicalPlanBuilder::project(*input, exprs);
+        }
+
+        let exprs = schema
+            .fields()
+            .iter()
+            .zip(names)
+            .map(|(field, name)| column(field.name()).alias(name))
+            .collect::<Vec<_>>();
+
+        LogicalPlanBuilder::project(input, exprs)
+    }
+
+    fn column_exprs(
+        &mut self,
+        plan: &LogicalPlan,
+        empty_from: bool,
+        columns: Vec<SelectItem>,
+    ) -> Result<Vec<LogicalExpr>> {
+        columns
+            .into_iter()
+            .flat_map(|col| match self.sql_select_item_to_expr(plan, col, empty_from) {
+                Ok(vec) => vec.into_iter().map(Ok).collect(),
+                Err(err) => vec![Err(err)],
+            })
+            .collect::<Result<Vec<LogicalExpr>>>()
+    }
+
+    fn sql_to_expr(&mut self, expr: Expression) -> Result<LogicalExpr> {
+        match expr {
+            Expression::CompoundIdentifier(mut idents) => {
+                if !(2..=4).contains(&idents.len()) {
+                    return internal_err!(
+                        "CompoundIdentifier should be [[catalog.]schema.]table.column, but got {} parts",
+                        idents.len()
+                    );
+                }
+
+                let name = idents.pop().map(normalize_ident).unwrap_or_default();
+                let relation = idents
+                    .into_iter()
+                    .map(|ident| ident.value)
+                    .collect::<Vec<_>>()
+                    .join(".")
+                    .into();
+                if let Some((relation, is_outer_ref)) = self.find_relation(&relation)? {
+                    return Ok(LogicalExpr::Column(Column::new(name, Some(relation), is_outer_ref)));
+                }
+
+                internal_err!(
+                    "Column [\"{}\"] not found in table [\"{}\"] or table not exists",
+                    name,
+                    relation
+                )
+            }
+            Expression::Identifier(ident) => {
+                let col_name = normalize_ident(ident);
                 self.get_relation(&col_name)
                     .map(|(relation, is_outer_ref)| LogicalExpr::Column(Column::new(col_name, relation, is_outer_ref)))
             }
+            Expression::Placeholder(id) => match id[1..].parse::<usize>().ok().and_then(|n| n.checked_sub(1)) {
+                Some(index) if index < self.param_types.len() => Ok(LogicalExpr::Placeholder(Placeholder::new(
+                    id,
+                    self.param_types[index].clone(),
+                ))),
+                _ => Err(Error::PlanError(format!(
+                    "The data type of placeholder {} is not declared, use PREPARE name (data_type, ...) AS",
+                    id
+                ))),
+            },
             Expression::Literal(lit) => match lit {
                 Literal::Int(i) => Ok(LogicalExpr::Literal(ScalarValue::Int64(Some(i)))),
                 Literal::Float(f) => Ok(LogicalExpr::Literal(ScalarValue::Float64(Some(f)))),
@@ -961,14 +2391,94 @@ impl<'a> SqlQueryPlanner<'a> {
 
                 self.handle_function(&name, exprs)
             }
+            Expression::DistinctFunction(name, args) => {
+                let exprs = args
+                    .into_iter()
+                    .map(|expr| self.sql_to_expr(expr))
+                    .collect::<Result<Vec<_>>>()?;
+
+                match self.handle_function(&name, exprs)? {
+                    LogicalExpr::AggregateExpr(aggregate) if aggregate.op != AggregateOperator::Grouping => {
+                        Ok(LogicalExpr::AggregateExpr(AggregateExpr {
+                            distinct: true,
+                            ..aggregate
+                        }))
+                    }
+                    _ => internal_err!("DISTINCT is only supported by aggregate functions, got {}", name),
+                }
+            }
+            Expression::WithinGroup { name, args, order_by } => {
+                let descending = order_by.order == Order::Desc;
+                let op = match name.to_lowercase().as_str() {
+                    "percentile_cont" => AggregateOperator::PercentileCont { descending },
+                    "percentile_disc" => AggregateOperator::PercentileDisc { descending },
+                    _ => return internal_err!("WITHIN GROUP is not supported by {}", name),
+                };
+                if args.len() != 1 {
+                    return internal_err!("{} expects a single fraction, got {} arguments", op, args.len());
+                }
+                let expr = self.sql_to_expr(order_by.expr)?;
+                let args = args
+                    .into_iter()
+                    .map(|expr| self.sql_to_expr(expr))
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(LogicalExpr::AggregateExpr(AggregateExpr {
+                    op,
+                    expr: Box::new(expr),
+                    args,
+                    distinct: false,
+                    filter: None,
+                }))
+            }
+            Expression::AggregateFilter { function, filter } => {
+                let filter = self.sql_to_expr(*filter)?;
+                if !find_aggregate_exprs([&filter]).is_empty() {
+                    return internal_err!("aggregate functions are not allowed in FILTER: {}", filter);
+                }
+
+                match self.sql_to_expr(*function)? {
+                    LogicalExpr::AggregateExpr(aggregate) if aggregate.op != AggregateOperator::Grouping => {
+                        Ok(LogicalExpr::AggregateExpr(AggregateExpr {
+                            filter: Some(Box::new(filter)),
+                            ..aggregate
+                        }))
+                    }
+                    expr => internal_err!("FILTER is only supported by aggregate functions, got {}", expr),
+                }
+            }
+            Expression::WindowFunction { name, args, over } => {
+                let func = WindowFunctionKind::try_from(name.as_str())?;
+                let args = args
+                    .into_iter()
+                    .map(|expr| self.sql_function_args_to_expr(expr))
+                    .collect::<Result<Vec<_>>>()?;
+                let partition_by = over
+                    .partition_by
+                    .into_iter()
+                    .map(|expr| self.sql_to_expr(expr))
+                    .collect::<Result<Vec<_>>>()?;
+                let order_by = self.order_by_exprs(over.order_by)?;
+                let window_frame = match over.window_frame {
+                    Some(window_frame) => window_frame.try_into()?,
+                    None => WindowFrame::new(!order_by.is_empty()),
+                };
+
+                WindowFunction::try_new(func, args, partition_by, order_by, window_frame)
+                    .map(LogicalExpr::WindowFunction)
+            }
             Expression::Cast { expr, data_type } => {
                 let expr = self.sql_to_expr(*expr)?;
                 Ok(expr.cast_to(&sql_to_arrow_data_type(&data_type)?))
             }
-            Expression::TypedString { data_type, value } => Ok(LogicalExpr::Cast(CastExpr {
-                expr: Box::new(LogicalExpr::Literal(ScalarValue::Utf8(Some(value)))),
-                data_type: sql_to_arrow_data_type(&data_type)?,
-            })),
+            Expression::TypedString { data_type, value } => {
+                sql_to_typed_literal(&value, &sql_to_arrow_data_type(&data_type)?).map(LogicalExpr::Literal)
+            }
+            Expression::Interval {
+                value,
+                leading_field,
+                last_field,
+            } => sql_to_interval(&value, leading_field, last_field).map(LogicalExpr::Literal),
             Expression::Extract { field, expr } => {
                 let args = self.sql_to_expr(*expr)?;
                 self.handle_function(
@@ -984,24 +2494,88 @@ impl<'a> SqlQueryPlanner<'a> {
                 sqlparser::ast::UnaryOperator::Minus => LogicalExpr::Negative(Box::new(expr)),
                 _ => todo!("UnaryOperator: {:?}", expr),
             }),
-            Expression::SubQuery(sub_query) => self.new_context_scope(|planner| {
-                let subquery = planner.select_to_plan(*sub_query)?;
-                let outer_ref_columns = subquery.outer_ref_columns()?;
-
-                Ok(LogicalExpr::SubQuery(SubQuery {
-                    subquery: Box::new(subquery),
-                    outer_ref_columns,
-                }))
-            }),
-            Expression::Like { negated, left, right } => Ok(LogicalExpr::Like(Like {
+            Expression::SubQuery(sub_query) => {
+                let subquery = self.sql_to_subquery(Statement::Select(sub_query))?;
+                let columns = subquery.subquery.schema().fields().len();
+                if columns != 1 {
+                    return internal_err!("Scalar subquery must return exactly one column, got {} columns", columns);
+                }
+                Ok(LogicalExpr::SubQuery(subquery))
+            }
+            Expression::InList { field, list, negated } => Ok(LogicalExpr::InList(InList::new(
+                self.sql_to_expr(*field)?,
+                list.into_iter()
+                    .map(|expr| self.sql_to_expr(expr))
+                    .collect::<Result<Vec<_>>>()?,
+                negated,
+            ))),
+            Expression::InSubQuery { field, query, negated } => {
+                let expr = self.sql_to_expr(*field)?;
+                let subquery = self.sql_to_subquery(*query)?;
+                let columns = subquery.subquery.schema().fields().len();
+                if columns != 1 {
+                    return internal_err!("IN subquery must return exactly one column, got {} columns", columns);
+                }
+                Ok(LogicalExpr::InSubQuery(InSubQuery::new(expr, subquery, negated)))
+            }
+            Expression::Like {
                 negated,
+                case_insensitive,
+                left,
+                right,
+                escape_char,
+            } => Ok(LogicalExpr::Like(Like {
+                negated,
+                case_insensitive,
                 expr: Box::new(self.sql_to_expr(*left)?),
                 pattern: Box::new(self.sql_to_expr(*right)?),
+                escape_char,
             })),
+            // `expr BETWEEN low AND high` is `expr >= low AND expr <= high`
+            Expression::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                let expr = self.sql_to_expr(*expr)?;
+                let (low, high) = (self.sql_to_expr(*low)?, self.sql_to_expr(*high)?);
+                Ok(if negated {
+                    or(lt(expr.clone(), low), gt(expr, high))
+                } else {
+                    and(gt_eq(expr.clone(), low), lt_eq(expr, high))
+                })
+            }
+            Expression::Case {
+                operand,
+                conditions,
+                else_result,
+            } => {
+                let operand = operand.map(|expr| self.sql_to_expr(*expr)).transpose()?;
+                let when_then = conditions
+                    .into_iter()
+                    .map(|(when, then)| Ok((self.sql_to_expr(when)?, self.sql_to_expr(then)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                let else_expr = else_result.map(|expr| self.sql_to_expr(*expr)).transpose()?;
+                Ok(LogicalExpr::Case(CaseExpr::new(operand, when_then, else_expr)))
+            }
             _ => todo!("sql_to_expr: {:?}", expr),
         }
     }
 
+    /// plan the subquery in a new scope, the columns of the outer query it references are collected as outer references
+    fn sql_to_subquery(&mut self, query: Statement) -> Result<SubQuery> {
+        self.new_context_scope(|planner| {
+            let subquery = planner.query_to_plan(query)?;
+            let outer_ref_columns = subquery.outer_ref_columns()?;
+
+            Ok(SubQuery {
+                subquery: Box::new(subquery),
+                outer_ref_columns,
+            })
+        })
+    }
+
     fn handle_function(&self, name: &str, mut args: Vec<LogicalExpr>) -> Result<LogicalExpr> {
         if let Some(udf) = self.udfs.get(name.to_uppercase().as_str()) {
             return Ok(LogicalExpr::Function(Function {
@@ -1010,12 +2584,57 @@ impl<'a> SqlQueryPlanner<'a> {
             }));
         }
 
-        if let Ok(op) = name.try_into() {
+        // GROUPING(a, b) is a bit mask of the arguments, GROUPING(a) * 2 + GROUPING(b)
+        if name.eq_ignore_ascii_case("grouping") && args.len() > 1 {
+            return args
+                .into_iter()
+                .map(|arg| {
+                    LogicalExpr::AggregateExpr(AggregateExpr {
+                        op: AggregateOperator::Grouping,
+                        expr: Box::new(arg),
+                        args: vec![],
+                        distinct: false,
+                        filter: None,
+                    })
+                })
+                .reduce(|mask, grouping| {
+                    let mask = LogicalExpr::BinaryExpr(BinaryExpr::new(
+                        mask,
+                        Operator::Mul,
+                        LogicalExpr::Literal(ScalarValue::Int64(Some(2))),
+                    ));
+                    LogicalExpr::BinaryExpr(BinaryExpr::new(mask, Operator::Add, grouping))
+                })
+                .ok_or(Error::InternalError(
+                    "GROUPING requires at least one argument".to_string(),
+                ));
+        }
+
+        // NOW() is evaluated once while planning so every row of the statement sees the same instant
+        if name.eq_ignore_ascii_case("now") {
+            if !args.is_empty() {
+                return internal_err!("NOW() does not take any arguments");
+            }
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_err(|e| Error::InternalError(e.to_string()))?;
+            return Ok(LogicalExpr::Literal(ScalarValue::TimestampMillisecond(
+                Some(now.as_millis() as i64),
+                self.timezone.clone(),
+            )));
+        }
+
+        if let Ok(op) = AggregateOperator::try_from(name) {
+            if args.len() != op.num_args() {
+                return internal_err!("{} expects {} arguments, got {}", op, op.num_args(), args.len());
+            }
+            let expr = args.remove(0);
             return Ok(LogicalExpr::AggregateExpr(AggregateExpr {
                 op,
-                expr: Box::new(args.pop().ok_or(Error::InternalError(
-                    "Aggregate function should have at least one expr".to_string(),
-                ))?),
+                expr: Box::new(expr),
+                args,
+                distinct: false,
+                filter: None,
             }));
         }
 
@@ -1039,10 +2658,15 @@ impl<'a> SqlQueryPlanner<'a> {
             BinaryOperator::Lte(l, r) => lt_eq(self.sql_to_expr(*l)?, self.sql_to_expr(*r)?),
             BinaryOperator::Or(l, r) => or(self.sql_to_expr(*l)?, self.sql_to_expr(*r)?),
             BinaryOperator::And(l, r) => and(self.sql_to_expr(*l)?, self.sql_to_expr(*r)?),
+            BinaryOperator::IsDistinctFrom(l, r) => is_distinct_from(self.sql_to_expr(*l)?, self.sql_to_expr(*r)?),
+            BinaryOperator::IsNotDistinctFrom(l, r) => {
+                is_not_distinct_from(self.sql_to_expr(*l)?, self.sql_to_expr(*r)?)
+            }
             BinaryOperator::Sub(l, r) => sub(self.sql_to_expr(*l)?, self.sql_to_expr(*r)?),
             BinaryOperator::Mul(l, r) => mul(self.sql_to_expr(*l)?, self.sql_to_expr(*r)?),
             BinaryOperator::Add(l, r) => add(self.sql_to_expr(*l)?, self.sql_to_expr(*r)?),
             BinaryOperator::Div(l, r) => div(self.sql_to_expr(*l)?, self.sql_to_expr(*r)?),
+            BinaryOperator::StringConcat(l, r) => string_concat(self.sql_to_expr(*l)?, self.sql_to_expr(*r)?),
         })
     }
 
@@ -1059,37 +2683,32 @@ impl<'a> SqlQueryPlanner<'a> {
                 self.add_column_alias(alias.clone(), col.clone())?;
                 Ok(vec![LogicalExpr::Alias(Alias::new(alias, col))])
             }
-            SelectItem::Wildcard => {
+            SelectItem::Wildcard(exclude) => {
                 if empty_relation {
                     return internal_err!("SELECT * with no tables specified is not valid");
                 }
 
                 let mut cols = plan.table_schema().columns();
                 cols.sort();
-                Ok(cols.into_iter().map(LogicalExpr::Column).collect())
+                exclude_columns(cols, exclude)
             }
-            SelectItem::QualifiedWildcard(idents) => {
+            SelectItem::QualifiedWildcard(idents, exclude) => {
                 if empty_relation {
                     return Err(Error::InternalError(
                         "SELECT * with no tables specified is not valid".to_owned(),
                     ));
                 }
-                // expand schema
-                let quanlified_prefix = idents.join(".").into();
+                // expand the columns of the relation only, not all the columns of the FROM clause
+                let quanlified_prefix: TableRelation = idents.join(".").into();
 
-                if let Some((relation, is_outer_ref)) = self.find_relation(&quanlified_prefix) {
-                    return plan
-                        .schema()
+                if let Some((schema, is_outer_ref)) = self.relation_schema(&quanlified_prefix) {
+                    let cols = schema
+                        .arrow_schema()
                         .fields()
                         .iter()
-                        .map(|field| {
-                            Ok(LogicalExpr::Column(Column::new(
-                                field.name(),
-                                Some(relation.clone()),
-                                is_outer_ref,
-                            )))
-                        })
+                        .map(|field| Column::new(field.name(), Some(quanlified_prefix.clone()), is_outer_ref))
                         .collect();
+                    return exclude_columns(cols, exclude);
                 }
 
                 Err(Error::InternalError(format!(
@@ -1101,6 +2720,52 @@ impl<'a> SqlQueryPlanner<'a> {
     }
 }
 
+/// the options of `COPY ... [WITH] (option, ...)`, the format defaults to the extension of the file and a csv
+/// file has no header unless `HEADER` is given
+struct CopyOptions {
+    format: String,
+    delimiter: u8,
+    has_header: bool,
+    compression: Option<String>,
+    row_group_size: Option<usize>,
+}
+
+impl CopyOptions {
+    fn try_new(file_path: &str, options: Vec<CopyOption>) -> Result<Self> {
+        // the format of a compressed file is the extension before the extension of the compression, like `csv.gz`
+        let path = match FileCompression::from_path(file_path) {
+            FileCompression::Uncompressed => file_path,
+            _ => file_path.rsplit_once('.').map_or(file_path, |(path, _)| path),
+        };
+        let mut copy_options = CopyOptions {
+            format: get_file_type(path).unwrap_or_default().to_lowercase(),
+            delimiter: b',',
+            has_header: false,
+            compression: None,
+            row_group_size: None,
+        };
+        for option in options {
+            match option {
+                CopyOption::Format(format) => copy_options.format = format.value.to_lowercase(),
+                CopyOption::Delimiter(delimiter) if delimiter.is_ascii() => copy_options.delimiter = delimiter as u8,
+                CopyOption::Delimiter(delimiter) => {
+                    return internal_err!("COPY delimiter must be a single byte character, got [{}]", delimiter)
+                }
+                CopyOption::Header(has_header) => copy_options.has_header = has_header,
+                CopyOption::Option(name, value) => match (name.value.to_lowercase().as_str(), value) {
+                    ("compression", Literal::String(compression)) => copy_options.compression = Some(compression),
+                    ("row_group_size", Literal::Int(size)) if size > 0 => {
+                        copy_options.row_group_size = Some(size as usize)
+                    }
+                    (_, value) => return internal_err!("invalid COPY option [{} {}]", name, value),
+                },
+            }
+        }
+
+        Ok(copy_options)
+    }
+}
+
 pub(crate) fn parse_file_path(args: &mut Vec<FunctionArgument>) -> Result<String> {
     if args.len() == 0 {
         return Err(Error::InternalError(
@@ -1118,6 +2783,30 @@ pub(crate) fn parse_file_path(args: &mut Vec<FunctionArgument>) -> Result<String
     }
 }
 
+/// the columns of the indexes of a table, of its primary key, of its unique constraints and of the columns declared
+/// with `INDEX`
+fn table_indexes(columns: &[sqlparser::ast::Column], constraints: &[TableConstraint]) -> Vec<Vec<String>> {
+    let mut indexes: Vec<Vec<String>> = vec![];
+    let keys = constraints
+        .iter()
+        .map(|constraint| match constraint {
+            TableConstraint::PrimaryKey(names) | TableConstraint::Unique(names) => names.clone(),
+        })
+        .chain(
+            columns
+                .iter()
+                .filter(|col| col.primary_key || col.unique || col.index)
+                .map(|col| vec![col.name.clone()]),
+        );
+    for key in keys {
+        if !indexes.contains(&key) {
+            indexes.push(key);
+        }
+    }
+
+    indexes
+}
+
 pub(crate) fn parse_csv_options(mut args: Vec<FunctionArgument>) -> Result<CsvReadOptions> {
     let mut options = CsvReadOptions::default();
 
@@ -1159,6 +2848,12 @@ pub(crate) fn parse_csv_options(mut args: Vec<FunctionArgument>) -> Result<CsvRe
                         .map_err(|e| Error::InternalError(format!("Parse CsvOptions error, {}", e)))
                 })?
             }
+            "nullstr" => match value {
+                Expression::Literal(Literal::String(s)) => options.null_values.push(s),
+                _ => return internal_err!("Parse CsvOptions error, nullstr requires a string literal"),
+            },
+            "sample_size" => options.schema_infer_max_records = parse_sample_size(value)?,
+            "compression" => options.compression = Some(parse_compression(value)?),
             "columns" => todo!(),
             _ => {
                 return Err(Error::InternalError(format!(
@@ -1172,6 +2867,178 @@ pub(crate) fn parse_csv_options(mut args: Vec<FunctionArgument>) -> Result<CsvRe
     Ok(options)
 }
 
+pub(crate) fn parse_json_options(args: Vec<FunctionArgument>) -> Result<JsonReadOptions> {
+    let mut options = JsonReadOptions::default();
+    for arg in args {
+        let name = arg.id.map(|id| id.value.to_lowercase()).unwrap_or_default();
+        match name.as_str() {
+            "sample_size" => options.schema_infer_max_records = parse_sample_size(arg.value)?,
+            "compression" => options.compression = Some(parse_compression(arg.value)?),
+            _ => return internal_err!("Unknown option [{}] for read_json function", name),
+        }
+    }
+
+    Ok(options)
+}
+
+#[cfg(feature = "delta")]
+pub(crate) fn parse_delta_options(args: Vec<FunctionArgument>) -> Result<file::delta::DeltaReadOptions> {
+    let mut options = file::delta::DeltaReadOptions::default();
+    for arg in args {
+        let name = arg.id.map(|id| id.value.to_lowercase()).unwrap_or_default();
+        match (name.as_str(), arg.value) {
+            ("version", Expression::Literal(Literal::Int(version))) if version >= 0 => {
+                options.version = Some(version as u64)
+            }
+            ("timestamp", Expression::Literal(Literal::String(timestamp))) => {
+                options.timestamp = Some(parse_timestamp_millis(&timestamp)?)
+            }
+            ("version" | "timestamp", value) => {
+                return internal_err!("invalid {} [{}] of read_delta function", name, value)
+            }
+            _ => return internal_err!("Unknown option [{}] for read_delta function", name),
+        }
+    }
+
+    Ok(options)
+}
+
+pub(crate) fn parse_parquet_options(args: Vec<FunctionArgument>) -> Result<ParquetReadOptions> {
+    let mut options = ParquetReadOptions::default();
+    for arg in args {
+        let name = arg.id.map(|id| id.value.to_lowercase()).unwrap_or_default();
+        match (name.as_str(), arg.value) {
+            ("merge_schema", Expression::Literal(Literal::Boolean(merge))) => options.merge_schema = merge,
+            ("merge_schema", value) => {
+                return internal_err!("invalid merge_schema [{}] of read_parquet function", value)
+            }
+            _ => return internal_err!("Unknown option [{}] for read_parquet function", name),
+        }
+    }
+
+    Ok(options)
+}
+
+#[cfg(feature = "iceberg")]
+pub(crate) fn parse_iceberg_options(args: Vec<FunctionArgument>) -> Result<file::iceberg::IcebergReadOptions> {
+    let mut options = file::iceberg::IcebergReadOptions::default();
+    for arg in args {
+        let name = arg.id.map(|id| id.value.to_lowercase()).unwrap_or_default();
+        match (name.as_str(), arg.value) {
+            ("snapshot_id", Expression::Literal(Literal::Int(id))) => options.snapshot_id = Some(id),
+            ("timestamp", Expression::Literal(Literal::String(timestamp))) => {
+                options.timestamp = Some(parse_timestamp_millis(&timestamp)?)
+            }
+            ("snapshot_id" | "timestamp", value) => {
+                return internal_err!("invalid {} [{}] of read_iceberg function", name, value)
+            }
+            _ => return internal_err!("Unknown option [{}] for read_iceberg function", name),
+        }
+    }
+
+    Ok(options)
+}
+
+#[cfg(feature = "excel")]
+pub(crate) fn parse_excel_options(args: Vec<FunctionArgument>) -> Result<file::excel::ExcelReadOptions> {
+    let mut options = file::excel::ExcelReadOptions::default();
+    for arg in args {
+        let name = arg.id.map(|id| id.value.to_lowercase()).unwrap_or_default();
+        match (name.as_str(), arg.value) {
+            ("sheet", Expression::Literal(Literal::String(sheet))) => options.sheet = Some(sheet),
+            ("header", Expression::Literal(Literal::Boolean(header))) => options.has_header = header,
+            ("sample_size", value) => options.schema_infer_max_records = parse_sample_size(value)?,
+            ("sheet" | "header", value) => return internal_err!("invalid {} [{}] of read_excel function", name, value),
+            _ => return internal_err!("Unknown option [{}] for read_excel function", name),
+        }
+    }
+
+    Ok(options)
+}
+
+/// the milliseconds since the epoch of a timestamp like `2024-01-01 00:00:00`, a timestamp without a time zone
+/// is in UTC
+#[cfg(any(feature = "delta", feature = "iceberg"))]
+fn parse_timestamp_millis(timestamp: &str) -> Result<i64> {
+    arrow::compute::kernels::cast_utils::string_to_timestamp_nanos(timestamp)
+        .map(|nanos| nanos / 1_000_000)
+        .map_err(|e| Error::InternalError(format!("invalid timestamp {}: {}", timestamp, e)))
+}
+
+/// the number of rows the schema of a file is inferred from, -1 infers it from all the rows
+fn parse_sample_size(value: Expression) -> Result<Option<usize>> {
+    match value {
+        Expression::Literal(Literal::Int(n)) if n > 0 => Ok(Some(n as usize)),
+        Expression::UnaryOperator {
+            op: sqlparser::ast::UnaryOperator::Minus,
+            expr,
+        } if *expr == Expression::Literal(Literal::Int(1)) => Ok(None),
+        _ => internal_err!("sample_size requires a positive integer or -1"),
+    }
+}
+
+/// the compression of a file, `gzip`, `zstd`, `bzip2` or `none`
+fn parse_compression(value: Expression) -> Result<FileCompression> {
+    match value {
+        Expression::Literal(Literal::String(s)) => s.parse(),
+        _ => internal_err!("compression requires a string literal"),
+    }
+}
+
+/// check if the plan scans the given table source
+fn scan_table(plan: &LogicalPlan, source: &Arc<dyn TableProvider>) -> bool {
+    match plan {
+        LogicalPlan::TableScan(scan) => Arc::ptr_eq(&scan.source, source),
+        _ => plan
+            .children()
+            .is_some_and(|children| children.into_iter().any(|child| scan_table(child, source))),
+    }
+}
+
+/// take the WITH clause of the leftmost query of a set operation
+fn leftmost_with(query: &mut Statement) -> Option<With> {
+    match query {
+        Statement::Select(select) => select.with.take(),
+        Statement::SetOperation { left, .. } => leftmost_with(left),
+        _ => None,
+    }
+}
+
+/// the name of a PIVOT or UNPIVOT without alias, the name or the alias of the pivoted table
+fn default_table_alias(table: &From) -> Option<String> {
+    match table {
+        From::Table { name, alias } => Some(alias.clone().unwrap_or(name.value())),
+        From::SubQuery { alias, .. } | From::Values { alias, .. } => alias.clone(),
+        From::TableSample { table, .. } => default_table_alias(table),
+        From::Pivot { table, alias, .. } | From::Unpivot { table, alias, .. } => {
+            alias.clone().or_else(|| default_table_alias(table))
+        }
+        _ => None,
+    }
+}
+
+/// a quoted identifier of a column name, the case of the name is kept as is
+fn quoted_identifier(name: &str) -> Expression {
+    Expression::Identifier(Ident {
+        value: name.to_owned(),
+        quote_style: Some('"'),
+    })
+}
+
+/// remove the columns of an `EXCLUDE` list from the expansion of a wildcard
+fn exclude_columns(columns: Vec<Column>, exclude: Vec<Ident>) -> Result<Vec<LogicalExpr>> {
+    let exclude = exclude.into_iter().map(normalize_ident).collect::<Vec<_>>();
+    if let Some(name) = exclude.iter().find(|name| columns.iter().all(|c| &c.name != *name)) {
+        return internal_err!("Column {} in EXCLUDE list not found", name);
+    }
+
+    Ok(columns
+        .into_iter()
+        .filter(|c| !exclude.contains(&c.name))
+        .map(LogicalExpr::Column)
+        .collect())
+}
+
 fn find_columns_exprs(expr: &LogicalExpr) -> Vec<LogicalExpr> {
     let mut columns = vec![];
     expr.apply(|nested_expr| {
@@ -1210,6 +3077,103 @@ fn find_aggregate_exprs<'a>(exprs: impl IntoIterator<Item = &'a LogicalExpr>) ->
         })
 }
 
+fn find_window_exprs<'a>(exprs: impl IntoIterator<Item = &'a LogicalExpr>) -> Vec<LogicalExpr> {
+    let mut window_exprs = vec![];
+
+    for expr in exprs {
+        expr.apply(|nested_expr| {
+            if let LogicalExpr::WindowFunction(_) = nested_expr {
+                if !window_exprs.contains(nested_expr) {
+                    window_exprs.push(nested_expr.clone());
+                }
+            }
+            Ok(TreeNodeRecursion::Continue)
+        })
+        .expect("[find_window_exprs] should not fail");
+    }
+
+    window_exprs
+}
+
+/// `DATE '2024-01-01'`, the value is parsed when planning so the literal is a scalar of the type
+fn sql_to_typed_literal(value: &str, data_type: &arrow::datatypes::DataType) -> Result<ScalarValue> {
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let array = cast_with_options(&StringArray::from(vec![value]), data_type, &options).map_err(|e| arrow_err!(e))?;
+    ScalarValue::try_from_array(&array, 0)
+}
+
+/// An interval of years and months only is an `IntervalYearMonth`, any other interval is an `IntervalMonthDayNano`
+fn sql_to_interval(
+    value: &str,
+    leading_field: Option<DateTimeField>,
+    last_field: Option<DateTimeField>,
+) -> Result<ScalarValue> {
+    let year_month = matches!(leading_field, Some(DateTimeField::Year | DateTimeField::Month))
+        && matches!(last_field, None | Some(DateTimeField::Month));
+
+    let value = match (leading_field, last_field) {
+        (None, _) => value.to_owned(),
+        // the value is a number of the unit, eg. `INTERVAL '2' HOUR`
+        (Some(field), None) => format!("{} {}", value, field),
+        (Some(leading_field), Some(last_field)) => compound_interval(value, &leading_field, &last_field)?,
+    };
+
+    if year_month {
+        parse_interval_year_month(&value)
+            .map(|months| ScalarValue::IntervalYearMonth(Some(months)))
+            .map_err(|e| arrow_err!(e))
+    } else {
+        parse_interval_month_day_nano(&value)
+            .map(|interval| ScalarValue::IntervalMonthDayNano(Some(interval)))
+            .map_err(|e| arrow_err!(e))
+    }
+}
+
+/// Rewrite the value of `INTERVAL '1 02:30' DAY TO MINUTE` to `1 DAY 02 HOUR 30 MINUTE`, the fields from
+/// the leading field to the last field are separated by `-` between years and months, by a space between
+/// days and hours, and by `:` between the time fields.
+fn compound_interval(value: &str, leading_field: &DateTimeField, last_field: &DateTimeField) -> Result<String> {
+    const FIELDS: [DateTimeField; 6] = [
+        DateTimeField::Year,
+        DateTimeField::Month,
+        DateTimeField::Day,
+        DateTimeField::Hour,
+        DateTimeField::Minute,
+        DateTimeField::Second,
+    ];
+
+    let position = |field: &DateTimeField| FIELDS.iter().position(|f| f == field);
+    let fields = match (position(leading_field), position(last_field)) {
+        // years and months can not be mixed with the other fields
+        (Some(leading), Some(last)) if leading < last && (last <= 1 || leading >= 2) => &FIELDS[leading..=last],
+        _ => return internal_err!("Invalid interval qualifier {} TO {}", leading_field, last_field),
+    };
+
+    let (sign, digits) = match value.trim().strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", value.trim()),
+    };
+    let parts = digits.split(['-', ' ', ':']).collect::<Vec<_>>();
+    if parts.len() != fields.len() || parts.iter().any(|part| part.is_empty()) {
+        return internal_err!(
+            "Invalid interval value '{}' for {} TO {}",
+            value,
+            leading_field,
+            last_field
+        );
+    }
+
+    Ok(parts
+        .iter()
+        .zip(fields)
+        .map(|(part, field)| format!("{}{} {}", sign, part, field))
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
 fn sql_to_arrow_data_type(data_type: &sqlparser::datatype::DataType) -> Result<arrow::datatypes::DataType> {
     match data_type {
         sqlparser::datatype::DataType::Integer => Ok(arrow::datatypes::DataType::Int64),
@@ -1220,8 +3184,11 @@ fn sql_to_arrow_data_type(data_type: &sqlparser::datatype::DataType) -> Result<a
         sqlparser::datatype::DataType::Timestamp => {
             Ok(arrow::datatypes::DataType::Timestamp(TimeUnit::Millisecond, None))
         }
+        sqlparser::datatype::DataType::Time => Ok(arrow::datatypes::DataType::Time64(TimeUnit::Microsecond)),
+        sqlparser::datatype::DataType::Int8 => Ok(arrow::datatypes::DataType::Int8),
         sqlparser::datatype::DataType::Int16 => Ok(arrow::datatypes::DataType::Int16),
         sqlparser::datatype::DataType::Int64 => Ok(arrow::datatypes::DataType::Int64),
+        sqlparser::datatype::DataType::Float32 => Ok(arrow::datatypes::DataType::Float32),
         sqlparser::datatype::DataType::Decimal(precision, scale) => match (precision, scale) {
             // Check for invalid precision and scale
             (Some(precision), Some(scale))
@@ -1254,112 +3221,466 @@ mod tests {
 
     use std::collections::HashMap;
 
-    use sqlparser::parser::Parser;
+    use sqlparser::{ast::DateTimeField, parser::Parser};
 
     use crate::{
         build_mem_datasource,
         common::table_relation::TableRelation,
         datasource::file::{self, csv::CsvReadOptions},
         datatypes::scalar::ScalarValue,
+        execution::config::SessionConfig,
         functions::all_builtin_functions,
         utils,
     };
 
-    use super::SqlQueryPlanner;
+    use super::{compound_interval, SqlQueryPlanner};
 
     #[test]
     fn test_outer_field_reference() {
         quick_test(
-            "SELECT * FROM person WHERE id = (SELECT MIN(id) FROM other_tbl WHERE name = first_name)",
-            "Projection: (person.age, person.first_name, person.id, person.name)\n  Filter: person.id = (\n          Projection: (MIN(other_tbl.id))\n            Aggregate: group_expr=[], aggregat_expr=[MIN(other_tbl.id)]\n              Filter: other_tbl.name = person.first_name\n                TableScan: other_tbl\n)\n\n    TableScan: person\n",
+            "SELECT * FROM person WHERE id = (SELECT MIN(id) FROM other_tbl WHERE name = first_name)",
+            "Projection: (person.age, person.first_name, person.id, person.name)\n  Filter: person.id = (\n          Projection: (MIN(other_tbl.id))\n            Aggregate: group_expr=[], aggregat_expr=[MIN(other_tbl.id)]\n              Filter: other_tbl.name = person.first_name\n                TableScan: other_tbl\n)\n\n    TableScan: person\n",
+        );
+
+        quick_test("SELECT * FROM person WHERE id = (SELECT MIN(id) FROM tbl)", "Projection: (person.age, person.first_name, person.id, person.name)\n  Filter: person.id = (\n          Projection: (MIN(tbl.id))\n            Aggregate: group_expr=[], aggregat_expr=[MIN(tbl.id)]\n              TableScan: tbl\n)\n\n    TableScan: person\n");
+    }
+
+    #[test]
+    fn test_sub_query() {
+        quick_test("SELECT * FROM tbl WHERE tbl.id = (SELECT other_tbl.id FROM other_tbl LIMIT 1)", "Projection: (tbl.age, tbl.id, tbl.name)\n  Filter: tbl.id = (\n          Limit: fetch=1, skip=0\n            Projection: (other_tbl.id)\n              TableScan: other_tbl\n)\n\n    TableScan: tbl\n");
+
+        quick_test(
+            "SELECT id, (SELECT MAX(id) FROM other_tbl) FROM tbl",
+            "Projection: (tbl.id, (\n          Projection: (MAX(other_tbl.id))\n            Aggregate: group_expr=[], aggregat_expr=[MAX(other_tbl.id)]\n              TableScan: other_tbl\n)\n)\n  TableScan: tbl\n",
+        );
+
+        quick_test(
+            "SELECT id, (SELECT id, name FROM other_tbl) FROM tbl",
+            "Internal Error: Scalar subquery must return exactly one column, got 2 columns",
+        );
+    }
+
+    #[test]
+    fn test_set_operation() {
+        quick_test(
+            "SELECT id, name FROM person UNION ALL SELECT id, name FROM tbl ORDER BY id LIMIT 1",
+            "Limit: fetch=1, skip=0\n  Sort: id ASC\n    SetOperation: op=UNION, all=true\n      Projection: (person.id, person.name)\n        TableScan: person\n      Projection: (tbl.id, tbl.name)\n        TableScan: tbl\n",
+        );
+
+        quick_test(
+            "SELECT id FROM person INTERSECT SELECT age FROM tbl EXCEPT SELECT 1",
+            "SetOperation: op=EXCEPT, all=false\n  Projection: (CAST(id AS Int64) AS id)\n    SetOperation: op=INTERSECT, all=false\n      Projection: (person.id)\n        TableScan: person\n      Projection: (tbl.age)\n        TableScan: tbl\n  Projection: (Int64(1))\n    Empty Relation\n",
+        );
+
+        quick_test(
+            "SELECT id, name FROM person UNION SELECT id FROM tbl",
+            "Internal Error: UNION queries have different number of columns: left has 2 columns whereas right has 1 columns",
+        );
+    }
+
+    #[test]
+    fn test_in_list() {
+        quick_test(
+            "SELECT id FROM person WHERE id NOT IN (1, 2) AND name IN (first_name, 'abc')",
+            "Projection: (person.id)\n  Filter: person.id NOT IN (Int64(1), Int64(2)) AND person.name IN (person.first_name, Utf8('abc'))\n    TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT id FROM person WHERE id IN (SELECT id FROM tbl)",
+            "Projection: (person.id)\n  Filter: person.id IN (\n          Projection: (tbl.id)\n            TableScan: tbl\n)\n\n    TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT id FROM person WHERE id IN (SELECT id, name FROM tbl)",
+            "Internal Error: IN subquery must return exactly one column, got 2 columns",
+        );
+    }
+
+    #[test]
+    fn test_between() {
+        quick_test(
+            "SELECT id FROM person WHERE age BETWEEN 18 AND 65 AND id > 1",
+            "Projection: (person.id)\n  Filter: person.age >= Int64(18) AND person.age <= Int64(65) AND person.id > Int64(1)\n    TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT id FROM person WHERE age NOT BETWEEN 18 AND 65",
+            "Projection: (person.id)\n  Filter: person.age < Int64(18) OR person.age > Int64(65)\n    TableScan: person\n",
+        );
+    }
+
+    #[test]
+    fn test_case() {
+        quick_test(
+            "SELECT CASE WHEN age > 18 THEN 1 ELSE 2.5 END FROM person",
+            "Projection: (CASE WHEN person.age > Int64(18) THEN Int64(1) ELSE Float64(2.5) END)\n  TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT CASE id WHEN 1 THEN name END AS n FROM person",
+            "Projection: (CASE person.id WHEN Int64(1) THEN person.name END AS n)\n  TableScan: person\n",
+        );
+    }
+
+    #[test]
+    fn test_cast() {
+        quick_test(
+            "SELECT CAST(id AS VARCHAR(10)), age::real FROM person",
+            "Projection: (CAST(person.id AS Utf8), CAST(person.age AS Float32))\n  TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT CAST('12:30:00' AS TIME), CAST(1 AS DECIMAL(5))",
+            "Projection: (CAST(Utf8('12:30:00') AS Time64(Microsecond)), CAST(Int64(1) AS Decimal128(5, 0)))\n  Empty Relation\n",
+        );
+    }
+
+    #[test]
+    fn test_typed_literal() {
+        quick_test(
+            "SELECT DATE '2024-01-01', TIME '12:30:00', TIMESTAMP '2024-01-01 12:00:00'",
+            "Projection: (Date32('2024-01-01'), Time64('12:30:00'), TimestampMillisecond('2024-01-01 12:00:00'))\n  Empty Relation\n",
+        );
+
+        quick_test(
+            "SELECT DATE '2024-02-30'",
+            "Arrow Error: Cast error: Cannot cast string '2024-02-30' to value of Date32 type",
+        );
+    }
+
+    #[test]
+    fn test_interval() {
+        quick_test(
+            "SELECT INTERVAL '1-2' YEAR TO MONTH, INTERVAL '2' HOUR, INTERVAL '1 02:30' DAY TO MINUTE",
+            "Projection: (IntervalYearMonth(14), IntervalMonthDayNano(0, 0, 7200000000000), IntervalMonthDayNano(0, 1, 9000000000000))\n  Empty Relation\n",
+        );
+
+        assert_eq!(
+            compound_interval("-1 02", &DateTimeField::Day, &DateTimeField::Hour).unwrap(),
+            "-1 DAY -02 HOUR"
+        );
+        assert!(compound_interval("1-2", &DateTimeField::Year, &DateTimeField::Day).is_err());
+        assert!(compound_interval("1:2:3", &DateTimeField::Hour, &DateTimeField::Minute).is_err());
+    }
+
+    #[test]
+    fn test_copy() {
+        quick_test("COPY schools FROM './tests/testdata/file/case1.csv';", "Dml: op=[Insert Into] table=[schools]\n  Projection: (CAST(column_1 AS Int64) AS id, CAST(column_2 AS Utf8) AS name, CAST(column_3 AS Utf8) AS location)\n    TableScan: tmp_table(b563e59)\n");
+
+        quick_test(
+            "COPY schools FROM './tests/testdata/file/case1.csv' (FORMAT CSV, HEADER, DELIMITER ',')",
+            "Dml: op=[Insert Into] table=[schools]\n  Projection: (CAST(id AS Int64) AS id, CAST(name AS Utf8) AS name, CAST(location AS Utf8) AS location)\n    TableScan: tmp_table(b563e59)\n",
+        );
+
+        quick_test(
+            "COPY schools (id, name) TO 'schools.csv' (DELIMITER '|')",
+            "CopyTo: file=[schools.csv] format=[csv]\n  Projection: (schools.id, schools.name)\n    TableScan: schools\n",
+        );
+
+        quick_test(
+            "COPY (SELECT id FROM schools WHERE id > 1) TO 'schools.out' WITH (FORMAT PARQUET)",
+            "CopyTo: file=[schools.out] format=[parquet]\n  Projection: (schools.id)\n    Filter: schools.id > Int64(1)\n      TableScan: schools\n",
+        );
+
+        quick_test(
+            "COPY schools TO 'schools.txt'",
+            "Internal Error: COPY TO does not support the file format [txt]",
+        );
+    }
+
+    #[test]
+    fn test_udf() {
+        quick_test(
+            "SELECT my_udf(1, 2) AS result",
+            "Internal Error: Unknown function: my_udf",
+        );
+
+        quick_test(
+            "SELECT EXTRACT(YEAR FROM DATE '2022-09-08')",
+            "Projection: (EXTRACT(Utf8('YEAR'), Date32('2022-09-08')))\n  Empty Relation\n",
+        );
+    }
+
+    #[test]
+    fn test_aggregate() {
+        quick_test(
+            "SELECT SUM(id) FROM tbl ",
+            "Projection: (SUM(tbl.id))\n  Aggregate: group_expr=[], aggregat_expr=[SUM(tbl.id)]\n    TableScan: tbl\n",
+        );
+    }
+
+    #[test]
+    fn test_insert() {
+        quick_test(
+            "INSERT INTO tbl VALUES (1), (2), (3);",
+            "Internal Error: statement requires the 3 columns, but got 1 columns",
+        );
+        // insert with not exists column
+        quick_test("INSERT INTO tbl(noexists,id,name) VALUES (1,1,'')", "Arrow Error: Schema error: Unable to get field named \"noexists\". Valid fields: [\"id\", \"name\", \"age\"]");
+        // insert the result of a query into a table
+        quick_test(
+            "INSERT INTO tbl SELECT * FROM other_tbl;",
+            "Dml: op=[Insert Into] table=[tbl]\n  Projection: (CAST(age AS Int32) AS id, CAST(id AS Utf8) AS name, CAST(name AS Int32) AS age)\n    Projection: (other_tbl.age, other_tbl.id, other_tbl.name)\n      TableScan: other_tbl\n",
+        );
+        // insert values into the "i" column, inserting the default value into other columns
+        quick_test(
+            "INSERT INTO tbl(id,age) VALUES (1,10), (2,12), (3,13);",
+            "Dml: op=[Insert Into] table=[tbl]\n  Projection: (CAST(column1 AS Int32) AS id, CAST(Utf8('default_name') AS Utf8) AS name, CAST(column2 AS Int32) AS age)\n    Values: [[Int64(1), Int64(10)], [Int64(2), Int64(12)], [Int64(3), Int64(13)]]\n",
+        );
+        // the columns of the query are matched to the column list in order
+        quick_test(
+            "INSERT INTO tbl(name, id) SELECT name, id + 1 FROM other_tbl",
+            "Dml: op=[Insert Into] table=[tbl]\n  Projection: (CAST(other_tbl.id + Int64(1) AS Int32) AS id, CAST(name AS Utf8) AS name, CAST(Null AS Int32) AS age)\n    Projection: (other_tbl.name, other_tbl.id + Int64(1))\n      TableScan: other_tbl\n",
+        );
+        quick_test(
+            "INSERT INTO tbl(id, name) VALUES (1, 'a'), (2)",
+            "Internal Error: INSERT statement requires all VALUES to have the same length",
+        );
+    }
+
+    #[test]
+    fn test_insert_on_conflict() {
+        quick_test(
+            "INSERT INTO tbl VALUES (1, 'a', 10) ON CONFLICT (id) DO NOTHING",
+            "Dml: op=[Insert Into] table=[tbl]\n  Projection: (excluded.id AS id, excluded.name AS name, excluded.age AS age)\n    Left Anti Join: Filter: excluded.id = tbl.id\n      Projection: (id AS excluded.id, name AS excluded.name, age AS excluded.age)\n        Projection: (CAST(column1 AS Int32) AS id, CAST(column2 AS Utf8) AS name, CAST(column3 AS Int32) AS age)\n          Values: [[Int64(1), Utf8('a'), Int64(10)]]\n      TableScan: tbl\n",
+        );
+        quick_test(
+            "INSERT INTO tbl VALUES (1, 'a', 10) ON CONFLICT (id) DO UPDATE SET age = tbl.age + excluded.age",
+            "Dml: op=[Upsert] table=[tbl] keys=[id]\n  Projection: (excluded.id AS id, CASE WHEN tbl.id IS NULL THEN excluded.name ELSE tbl.name END AS name, CAST(CASE WHEN tbl.id IS NULL THEN excluded.age ELSE tbl.age + excluded.age END AS Int32) AS age)\n    Left Join: Filter: excluded.id = tbl.id\n      Projection: (id AS excluded.id, name AS excluded.name, age AS excluded.age)\n        Projection: (CAST(column1 AS Int32) AS id, CAST(column2 AS Utf8) AS name, CAST(column3 AS Int32) AS age)\n          Values: [[Int64(1), Utf8('a'), Int64(10)]]\n      TableScan: tbl\n",
+        );
+        quick_test(
+            "INSERT INTO tbl VALUES (1, 'a', 10) ON CONFLICT (noexists) DO NOTHING",
+            "Internal Error: Column [noexists] of table [tbl] does not exist",
+        );
+        quick_test(
+            "INSERT INTO tbl VALUES (1, 'a', 10) ON CONFLICT (id) DO UPDATE SET id = 2",
+            "Internal Error: ON CONFLICT DO UPDATE can not assign the conflict column [id]",
+        );
+        quick_test(
+            "INSERT INTO tbl VALUES (1, 'a', 10) ON CONFLICT (id) DO UPDATE SET age = age + 1",
+            "Internal Error: Column \"age\" is ambiguous",
+        );
+    }
+
+    #[test]
+    fn test_update() {
+        quick_test("UPDATE tbl SET id = 0 WHERE id IS NULL;", "Dml: op=[Update] table=[tbl]\n  Projection: (CAST(Int64(0) AS Int32) AS id, tbl.name, tbl.age)\n    Filter: tbl.id IS NULL\n      TableScan: tbl\n");
+
+        quick_test("UPDATE tbl SET id = 1, name = 2;", "Dml: op=[Update] table=[tbl]\n  Projection: (CAST(Int64(1) AS Int32) AS id, CAST(Int64(2) AS Utf8) AS name, tbl.age)\n    TableScan: tbl\n");
+        quick_test(
+            "UPDATE tbl SET age = age + 1 WHERE name = 'a'",
+            "Dml: op=[Update] table=[tbl]\n  Projection: (tbl.id, tbl.name, CAST(tbl.age + Int64(1) AS Int32) AS age)\n    Filter: tbl.name = Utf8('a')\n      TableScan: tbl\n",
+        );
+        quick_test(
+            "UPDATE tbl SET noexists = 1",
+            "Internal Error: Column [noexists] of table [tbl] does not exist",
+        );
+        quick_test(
+            "UPDATE tbl SET id = 1, id = 2",
+            "Internal Error: Column [id] is assigned more than once",
+        );
+    }
+
+    #[test]
+    fn test_delete() {
+        quick_test("DELETE FROM tblx", "Table Not Found: tblx");
+
+        quick_test(
+            "DELETE FROM tbl",
+            "Dml: op=[Delete From] table=[tbl]\n  TableScan: tbl\n",
+        );
+
+        quick_test(
+            "DELETE FROM tbl WHERE id = 1",
+            "Dml: op=[Delete From] table=[tbl]\n  Filter: tbl.id = Int64(1)\n    TableScan: tbl\n",
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        quick_test(
+            "EXPLAIN SELECT id FROM tbl WHERE age > 1",
+            "Explain: analyze=false, verbose=false\n  Projection: (tbl.id)\n    Filter: tbl.age > Int64(1)\n      TableScan: tbl\n",
+        );
+        quick_test(
+            "EXPLAIN ANALYZE VERBOSE SELECT 1",
+            "Explain: analyze=true, verbose=true\n  Projection: (Int64(1))\n    Empty Relation\n",
         );
-
-        quick_test("SELECT * FROM person WHERE id = (SELECT MIN(id) FROM tbl)", "Projection: (person.age, person.first_name, person.id, person.name)\n  Filter: person.id = (\n          Projection: (MIN(tbl.id))\n            Aggregate: group_expr=[], aggregat_expr=[MIN(tbl.id)]\n              TableScan: tbl\n)\n\n    TableScan: person\n");
     }
 
     #[test]
-    fn test_sub_query() {
-        quick_test("SELECT * FROM tbl WHERE tbl.id = (SELECT other_tbl.id FROM other_tbl LIMIT 1)", "Projection: (tbl.age, tbl.id, tbl.name)\n  Filter: tbl.id = (\n          Limit: fetch=1, skip=0\n            Projection: (other_tbl.id)\n              TableScan: other_tbl\n)\n\n    TableScan: tbl\n");
+    fn test_truncate() {
+        quick_test("TRUNCATE TABLE tblx", "Table Not Found: tblx");
+
+        quick_test("TRUNCATE tbl", "Dml: op=[Truncate] table=[tbl]\n  Empty Relation\n");
     }
 
     #[test]
-    fn test_copy() {
-        quick_test("COPY schools FROM './tests/testdata/file/case1.csv';", "Dml: op=[Insert Into] table=[schools]\n  Projection: (CAST(column_1 AS Int64) AS id, CAST(column_2 AS Utf8) AS name, CAST(column_3 AS Utf8) AS location)\n    TableScan: tmp_table(b563e59)\n");
+    fn test_prepare() {
+        quick_test(
+            "PREPARE q (INT, VARCHAR) AS SELECT id FROM tbl WHERE id > $1 AND name = $2",
+            "Prepare: name=[q], data_types=[Int64, Utf8]\n  Projection: (tbl.id)\n    Filter: tbl.id > $1 AND tbl.name = $2\n      TableScan: tbl\n",
+        );
+        quick_test(
+            "PREPARE q AS SELECT id FROM tbl WHERE id > $1",
+            "Plan Error: The data type of placeholder $1 is not declared, use PREPARE name (data_type, ...) AS",
+        );
+        quick_test(
+            "PREPARE q (INT) AS SELECT id FROM tbl WHERE id > $2",
+            "Plan Error: The data type of placeholder $2 is not declared, use PREPARE name (data_type, ...) AS",
+        );
+        quick_test(
+            "PREPARE ins (INT) AS INSERT INTO tbl (id) VALUES ($1)",
+            "Prepare: name=[ins], data_types=[Int64]\n  Dml: op=[Insert Into] table=[tbl]\n    Projection: (CAST(column1 AS Int32) AS id, CAST(Utf8('default_name') AS Utf8) AS name, CAST(Null AS Int32) AS age)\n      Values: [[$1]]\n",
+        );
+        quick_test(
+            "SELECT $1",
+            "Plan Error: The data type of placeholder $1 is not declared, use PREPARE name (data_type, ...) AS",
+        );
 
         quick_test(
-            "COPY schools FROM './tests/testdata/file/case1.csv' (FORMAT CSV, HEADER, DELIMITER ',')",
-            "Dml: op=[Insert Into] table=[schools]\n  Projection: (CAST(id AS Int64) AS id, CAST(name AS Utf8) AS name, CAST(location AS Utf8) AS location)\n    TableScan: tmp_table(b563e59)\n",
+            "EXECUTE q (1, 'a')",
+            "Execute: name=[q], parameters=[Int64(1), Utf8('a')]\n",
         );
+        quick_test("DEALLOCATE PREPARE q", "Deallocate: [q]\n");
     }
 
     #[test]
-    fn test_udf() {
+    fn test_qualified_wildcard() {
         quick_test(
-            "SELECT my_udf(1, 2) AS result",
-            "Internal Error: Unknown function: my_udf",
+            "SELECT p.*, o.age FROM person p JOIN orders o ON p.id = o.id",
+            "Projection: (p.id, p.name, p.first_name, p.age, o.age)\n  Inner Join: Filter: p.id = o.id\n    SubqueryAlias: p\n      TableScan: person\n    SubqueryAlias: o\n      TableScan: orders\n",
         );
-
         quick_test(
-            "SELECT EXTRACT(YEAR FROM DATE '2022-09-08')",
-            "Projection: (EXTRACT(Utf8('YEAR'), CAST(Utf8('2022-09-08') AS Date32)))\n  Empty Relation\n",
+            "SELECT person.* FROM person, schools",
+            "Projection: (person.id, person.name, person.first_name, person.age)\n  CrossJoin\n    TableScan: person\n    TableScan: schools\n",
+        );
+        quick_test(
+            "SELECT p.* EXCLUDE (first_name, age) FROM person p",
+            "Projection: (p.id, p.name)\n  SubqueryAlias: p\n    TableScan: person\n",
+        );
+        quick_test(
+            "SELECT * EXCEPT (id) FROM a, b",
+            "Projection: (a.name, b.name)\n  CrossJoin\n    TableScan: a\n    TableScan: b\n",
+        );
+        quick_test(
+            "SELECT * EXCLUDE (id, unknown) FROM a",
+            "Internal Error: Column unknown in EXCLUDE list not found",
+        );
+        quick_test(
+            "SELECT x.* FROM person p",
+            "Internal Error: Invalid qualified wildcard: x",
+        );
+        quick_test(
+            "SELECT person.* FROM person p",
+            "Internal Error: Invalid qualified wildcard: person",
         );
     }
 
     #[test]
-    fn test_aggregate() {
+    fn test_aliased_table_reference() {
         quick_test(
-            "SELECT SUM(id) FROM tbl ",
-            "Projection: (SUM(tbl.id))\n  Aggregate: group_expr=[], aggregat_expr=[SUM(tbl.id)]\n    TableScan: tbl\n",
+            "SELECT p.id FROM person AS p",
+            "Projection: (p.id)\n  SubqueryAlias: p\n    TableScan: person\n",
+        );
+        quick_test(
+            "SELECT person.id FROM person AS p",
+            "Plan Error: invalid reference to table person, it is aliased as p",
+        );
+        quick_test(
+            "SELECT p.id FROM person AS p WHERE person.age > 18",
+            "Plan Error: invalid reference to table person, it is aliased as p",
         );
     }
 
     #[test]
-    fn test_insert() {
+    fn test_table_sample() {
         quick_test(
-            "INSERT INTO tbl VALUES (1), (2), (3);",
-            "Internal Error: statement requires the 3 columns, but got 1 columns",
+            "SELECT id FROM person TABLESAMPLE BERNOULLI (10) WHERE age > 18",
+            "Projection: (person.id)\n  Filter: person.age > Int64(18)\n    Sample: method=BERNOULLI, fraction=0.1\n      TableScan: person\n",
         );
-        // insert with not exists column
-        quick_test("INSERT INTO tbl(noexists,id,name) VALUES (1,1,'')", "Arrow Error: Schema error: Unable to get field named \"noexists\". Valid fields: [\"id\", \"name\", \"age\"]");
-        // insert the result of a query into a table
         quick_test(
-            "INSERT INTO tbl SELECT * FROM other_tbl;",
-            "Dml: op=[Insert Into] table=[tbl]\n  Projection: (CAST(age AS Int32) AS id, CAST(id AS Utf8) AS name, CAST(name AS Int32) AS age)\n    Projection: (other_tbl.age, other_tbl.id, other_tbl.name)\n      TableScan: other_tbl\n",
+            "SELECT p.id FROM person AS p TABLESAMPLE SYSTEM (2.5) REPEATABLE (42)",
+            "Projection: (p.id)\n  Sample: method=SYSTEM, fraction=0.025, seed=42\n    SubqueryAlias: p\n      TableScan: person\n",
         );
-        // insert values into the "i" column, inserting the default value into other columns
         quick_test(
-            "INSERT INTO tbl(id,age) VALUES (1,10), (2,12), (3,13);",
-            "Dml: op=[Insert Into] table=[tbl]\n  Projection: (CAST(column1 AS Int32) AS id, CAST(Utf8('default_name') AS Utf8) AS name, CAST(column2 AS Utf8) AS name)\n    Values: [[Int64(1), Int64(10)], [Int64(2), Int64(12)], [Int64(3), Int64(13)]]\n",
+            "SELECT * FROM person TABLESAMPLE BERNOULLI (101)",
+            "Internal Error: TABLESAMPLE percentage must be between 0 and 100, got 101",
+        );
+        quick_test(
+            "SELECT * FROM person TABLESAMPLE BERNOULLI ('a')",
+            "Internal Error: TABLESAMPLE percentage must be a numeric constant, got Utf8('a')",
+        );
+        quick_test(
+            "SELECT * FROM person TABLESAMPLE BERNOULLI (10) REPEATABLE (1.5)",
+            "Internal Error: TABLESAMPLE REPEATABLE seed must be an integer constant",
         );
     }
 
     #[test]
-    fn test_update() {
-        quick_test("UPDATE tbl SET id = 0 WHERE id IS NULL;", "Dml: op=[Update] table=[tbl]\n  Projection: (CAST(Int64(0) AS Int32) AS id, tbl.name, tbl.age)\n    Filter: tbl.id IS NULL\n      TableScan: tbl\n");
-
-        quick_test("UPDATE tbl SET id = 1, name = 2;", "Dml: op=[Update] table=[tbl]\n  Projection: (CAST(Int64(1) AS Int32) AS id, CAST(Int64(2) AS Utf8) AS name, tbl.age)\n    TableScan: tbl\n");
+    fn test_pivot() {
+        quick_test(
+            "SELECT * FROM (SELECT name, age, id FROM person) AS p PIVOT (SUM(age) FOR name IN ('a', 'b' AS bob))",
+            "Projection: (p.a, p.bob, p.id)\n  SubqueryAlias: p\n    Projection: (p.id, SUM(CASE WHEN p.name = Utf8('a') THEN p.age END) AS a, SUM(CASE WHEN p.name = Utf8('b') THEN p.age END) AS bob)\n      Aggregate: group_expr=[p.id], aggregat_expr=[SUM(CASE WHEN p.name = Utf8('a') THEN p.age END),SUM(CASE WHEN p.name = Utf8('b') THEN p.age END)]\n        SubqueryAlias: p\n          Projection: (person.name, person.age, person.id)\n            TableScan: person\n",
+        );
+        quick_test(
+            "SELECT * FROM (SELECT name, id FROM person) AS p PIVOT (COUNT(*) FOR name IN ('a')) AS q",
+            "Projection: (q.a, q.id)\n  SubqueryAlias: q\n    Projection: (p.id, COUNT(CASE WHEN p.name = Utf8('a') THEN Int64(1) END) AS a)\n      Aggregate: group_expr=[p.id], aggregat_expr=[COUNT(CASE WHEN p.name = Utf8('a') THEN Int64(1) END)]\n        SubqueryAlias: p\n          Projection: (person.name, person.id)\n            TableScan: person\n",
+        );
+        quick_test(
+            "SELECT * FROM person PIVOT (age FOR name IN ('a'))",
+            "Internal Error: PIVOT requires an aggregate function, got age",
+        );
+        quick_test(
+            "SELECT * FROM (SELECT id FROM person) PIVOT (COUNT(*) FOR id IN (1))",
+            "Internal Error: PIVOT of a subquery must have an alias",
+        );
+        quick_test(
+            "SELECT * FROM person UNPIVOT (val FOR col IN (id, age)) WHERE val > 1",
+            "Projection: (person.col, person.first_name, person.name, person.val)\n  Filter: person.val > Int64(1)\n    SubqueryAlias: person\n      SetOperation: op=UNION, all=true\n        Projection: (person.name, person.first_name, Utf8('id') AS col, person.id AS val)\n          Filter: person.id IS NOT NULL\n            TableScan: person\n        Projection: (person.name, person.first_name, Utf8('age') AS col, person.age AS val)\n          Filter: person.age IS NOT NULL\n            TableScan: person\n",
+        );
+        quick_test(
+            "SELECT * FROM person UNPIVOT (val FOR col IN (height))",
+            "Internal Error: UNPIVOT column height not found",
+        );
     }
 
     #[test]
-    fn test_delete() {
-        quick_test("DELETE FROM tblx", "Table Not Found: tblx");
-
+    fn test_values() {
         quick_test(
-            "DELETE FROM tbl",
-            "Dml: op=[Delete From] table=[tbl]\n  TableScan: tbl\n",
+            "VALUES (1, 'a'), (NULL, 'b')",
+            "Values: [[Int64(1), Utf8('a')], [Null, Utf8('b')]]\n",
         );
-
+        quick_test("VALUES (1), (2.5)", "Values: [[Int64(1)], [Float64(2.5)]]\n");
         quick_test(
-            "DELETE FROM tbl WHERE id = 1",
-            "Dml: op=[Delete From] table=[tbl]\n  Filter: tbl.id = Int64(1)\n    TableScan: tbl\n",
+            "VALUES (1, 'a'), (2)",
+            "Internal Error: VALUES lists must all be the same length",
+        );
+        quick_test(
+            "SELECT t.x, y FROM (VALUES (1, 'a'), (2, 'b')) AS t(x, y) WHERE x > 1",
+            "Projection: (t.x, t.y)\n  Filter: t.x > Int64(1)\n    SubqueryAlias: t\n      Projection: (column1 AS x, column2 AS y)\n        Values: [[Int64(1), Utf8('a')], [Int64(2), Utf8('b')]]\n",
+        );
+        quick_test(
+            "SELECT * FROM tbl JOIN (VALUES (1, 'one')) v(id, label) ON tbl.id = v.id",
+            "Projection: (tbl.age, tbl.id, v.id, v.label, tbl.name)\n  Inner Join: Filter: tbl.id = v.id\n    TableScan: tbl\n    SubqueryAlias: v\n      Projection: (column1 AS id, column2 AS label)\n        Values: [[Int64(1), Utf8('one')]]\n",
+        );
+        quick_test(
+            "SELECT * FROM (VALUES (1))",
+            "Internal Error: VALUES in FROM must have an alias",
+        );
+        quick_test(
+            "SELECT * FROM (VALUES (1, 2)) AS t(x)",
+            "Internal Error: Source table contains 2 columns but only 1 names given as column alias",
         );
     }
 
     #[test]
     fn test_drop_table() {
-        quick_test("DROP TABLE tblx;", "Table Not Found: tblx");
+        // the table is resolved when the statement is executed
+        quick_test("DROP TABLE tblx;", "DropTable: [tblx]\n");
 
         quick_test("DROP TABLE IF EXISTS tbl;", "DropTable: [tbl]\n");
+        quick_test("DROP VIEW IF EXISTS public.v;", "DropView: [public.v]\n");
     }
 
     #[test]
@@ -1369,11 +3690,36 @@ mod tests {
             "CREATE TABLE t1(i INTEGER, j INTEGER);",
             "CreateMemoryTable: [t1]\n  Empty Relation\n",
         );
+        quick_test(
+            "CREATE TABLE t1(i INTEGER NOT NULL, j INTEGER, PRIMARY KEY (i), UNIQUE (i, j));",
+            "CreateMemoryTable: [t1]\n  Empty Relation\n",
+        );
+        quick_test(
+            "CREATE TABLE t1(i INTEGER PRIMARY KEY, j INTEGER, PRIMARY KEY (j));",
+            "Internal Error: Multiple primary keys for table [t1] are not allowed",
+        );
+        quick_test(
+            "CREATE TABLE t1(i INTEGER, UNIQUE (k));",
+            "Internal Error: Column [k] named in key does not exist",
+        );
+        quick_test(
+            "CREATE TABLE t1(i INTEGER, i VARCHAR);",
+            "Internal Error: Column [i] is specified more than once",
+        );
         // create a table from the result of a query
         quick_test(
             "CREATE TABLE t1 AS SELECT 42 AS i, 84 AS j;",
             "CreateMemoryTable: [t1]\n  Projection: (Int64(42) AS i, Int64(84) AS j)\n    Empty Relation\n",
         );
+        // the query is cast to the declared columns
+        quick_test(
+            "CREATE TABLE t1(i BIGINT, j VARCHAR) AS SELECT id, name FROM tbl UNION ALL SELECT id, name FROM tbl",
+            "CreateMemoryTable: [t1]\n  Projection: (CAST(id AS Int64) AS i, CAST(name AS Utf8) AS j)\n    SetOperation: op=UNION, all=true\n      Projection: (tbl.id, tbl.name)\n        TableScan: tbl\n      Projection: (tbl.id, tbl.name)\n        TableScan: tbl\n",
+        );
+        quick_test(
+            "CREATE TABLE t1(i BIGINT) AS SELECT id, name FROM tbl",
+            "Internal Error: statement requires the 1 columns, but got 2 columns",
+        );
         // create a table from a CSV file using AUTO-DETECT (i.e., automatically detecting column names and types)
         quick_test(
             "CREATE TABLE t1 AS SELECT * FROM read_csv('./tests/testdata/file/case1.csv');", 
@@ -1386,6 +3732,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_create_view() {
+        quick_test(
+            "CREATE VIEW v AS SELECT id, name FROM tbl WHERE age > 1",
+            "CreateView: [v]\n  Projection: (tbl.id, tbl.name)\n    Filter: tbl.age > Int64(1)\n      TableScan: tbl\n",
+        );
+        // the view columns are named by the column list
+        quick_test(
+            "CREATE OR REPLACE VIEW v (a, b) AS SELECT id, name FROM tbl",
+            "CreateView: [v]\n  Projection: (tbl.id AS a, tbl.name AS b)\n    TableScan: tbl\n",
+        );
+        quick_test(
+            "CREATE VIEW v (a) AS SELECT id, name FROM tbl",
+            "Internal Error: Source table contains 2 columns but only 1 names given as column alias",
+        );
+        quick_test(
+            "CREATE VIEW v AS SELECT id, id FROM tbl",
+            "Internal Error: Column [id] is specified more than once",
+        );
+    }
+
     #[test]
     fn test_read_parquet() {
         quick_test(
@@ -1407,13 +3774,20 @@ mod tests {
         quick_test("SELECT 1", "Projection: (Int64(1))\n  Empty Relation\n");
 
         quick_test("SELECT -1", "Projection: (- Int64(1))\n  Empty Relation\n");
+
+        quick_test(
+            "SELECT 1 + 1 AS a WHERE 1 = 1",
+            "Projection: (Int64(1) + Int64(1) AS a)\n  Filter: Int64(1) = Int64(1)\n    Empty Relation\n",
+        );
+
+        quick_test("SELECT now(1)", "Internal Error: NOW() does not take any arguments");
     }
 
     #[test]
     fn test_select_column() {
         quick_test(
-            "SELECT person.id,a.name as c FROM person as a",
-            "Projection: (person.id, a.name AS c)\n  SubqueryAlias: a\n    TableScan: person\n",
+            "SELECT a.id,a.name as c FROM person as a",
+            "Projection: (a.id, a.name AS c)\n  SubqueryAlias: a\n    TableScan: person\n",
         );
 
         quick_test(
@@ -1538,12 +3912,171 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_semi_anti_join() {
+        quick_test(
+            "SELECT * FROM person LEFT SEMI JOIN orders ON person.id = orders.id",
+            "Projection: (person.age, person.first_name, person.id, person.name)\n  Left Semi Join: Filter: person.id = orders.id\n    TableScan: person\n    TableScan: orders\n",
+        );
+
+        quick_test(
+            "SELECT first_name FROM person LEFT ANTI JOIN orders USING (id)",
+            "Projection: (person.first_name)\n  Projection: (person.id, person.name, person.first_name, person.age)\n    Left Anti Join: Filter: person.id = orders.id\n      TableScan: person\n      Projection: (orders.id AS orders.id, orders.name, orders.age)\n        TableScan: orders\n",
+        );
+    }
+
+    #[test]
+    fn test_derived_table() {
+        quick_test(
+            "SELECT s.id FROM (SELECT id FROM person WHERE age > 18) AS s",
+            "Projection: (s.id)\n  SubqueryAlias: s\n    Projection: (person.id)\n      Filter: person.age > Int64(18)\n        TableScan: person\n",
+        );
+        quick_test(
+            "SELECT id FROM (SELECT id FROM person)",
+            "Internal Error: subquery in FROM must have an alias",
+        );
+        quick_test(
+            "SELECT a.id FROM a, (SELECT id FROM b WHERE b.id = a.id) AS s",
+            "Internal Error: subquery in FROM can not reference a.id of another FROM item, use LATERAL instead",
+        );
+
+        // the column names of the alias rename the columns of the subquery
+        quick_test(
+            "SELECT x.c1, c2 FROM (SELECT id, name FROM person) AS x(c1, c2) WHERE c1 > 1",
+            "Projection: (x.c1, x.c2)\n  Filter: x.c1 > Int64(1)\n    SubqueryAlias: x\n      Projection: (person.id AS c1, person.name AS c2)\n        TableScan: person\n",
+        );
+        quick_test(
+            "SELECT * FROM (SELECT id, id + 1 FROM person) x(a, b)",
+            "Projection: (x.a, x.b)\n  SubqueryAlias: x\n    Projection: (person.id AS a, person.id + Int64(1) AS b)\n      TableScan: person\n",
+        );
+        quick_test(
+            "SELECT x.id FROM (SELECT id, name FROM person) AS x(c1, c2)",
+            "Arrow Error: Schema error: Unable to get field named \"id\". Valid fields: [\"c1\", \"c2\"]",
+        );
+        quick_test(
+            "SELECT * FROM (SELECT id, name FROM person) AS x(c1)",
+            "Internal Error: Source table contains 2 columns but only 1 names given as column alias",
+        );
+        quick_test(
+            "SELECT a.id, l.n FROM a CROSS JOIN LATERAL (SELECT name FROM b WHERE b.id = a.id) AS l(n)",
+            "Projection: (a.id, l.n)\n  LateralJoin: Outer References: [a.id]\n    TableScan: a\n    SubqueryAlias: l\n      Projection: (b.name AS n)\n        Filter: b.id = a.id\n          TableScan: b\n",
+        );
+    }
+
+    #[test]
+    fn test_lateral_join() {
+        quick_test(
+            "SELECT a.id, s.name FROM a CROSS JOIN LATERAL (SELECT name FROM b WHERE b.id = a.id) AS s",
+            "Projection: (a.id, s.name)\n  LateralJoin: Outer References: [a.id]\n    TableScan: a\n    SubqueryAlias: s\n      Projection: (b.name)\n        Filter: b.id = a.id\n          TableScan: b\n",
+        );
+        quick_test(
+            "SELECT a.id, s.id FROM a, LATERAL (SELECT id FROM b) AS s",
+            "Projection: (a.id, s.id)\n  CrossJoin\n    TableScan: a\n    SubqueryAlias: s\n      Projection: (b.id)\n        TableScan: b\n",
+        );
+        quick_test(
+            "SELECT * FROM a LEFT JOIN LATERAL (SELECT id FROM b WHERE b.id = a.id) AS s ON true",
+            "Internal Error: LATERAL subquery is only supported in CROSS JOIN",
+        );
+    }
+
+    #[test]
+    fn test_join_using() {
+        quick_test(
+            "SELECT * FROM person JOIN orders USING (id)",
+            "Projection: (orders.age, person.age, person.first_name, person.id, orders.name, person.name)\n  Projection: (person.id, person.name, person.first_name, person.age, orders.name, orders.age)\n    Inner Join: Filter: person.id = orders.id\n      TableScan: person\n      Projection: (orders.id AS orders.id, orders.name, orders.age)\n        TableScan: orders\n",
+        );
+
+        quick_test(
+            "SELECT id FROM person RIGHT JOIN orders USING (id, age)",
+            "Projection: (id)\n  Projection: (orders.id AS id, orders.age AS age, person.name, person.first_name, orders.name)\n    Right Join: Filter: person.id = orders.id AND person.age = orders.age\n      TableScan: person\n      Projection: (orders.id AS orders.id, orders.name, orders.age AS orders.age)\n        TableScan: orders\n",
+        );
+
+        quick_test(
+            "SELECT * FROM person JOIN orders USING (first_name)",
+            "Internal Error: column [first_name] of the join must appear in both sides",
+        );
+    }
+
+    #[test]
+    fn test_natural_join() {
+        quick_test(
+            "SELECT * FROM a NATURAL JOIN b",
+            "Projection: (a.id, a.name)\n  Projection: (a.id, a.name)\n    Inner Join: Filter: a.id = b.id AND a.name = b.name\n      TableScan: a\n      Projection: (b.id AS b.id, b.name AS b.name)\n        TableScan: b\n",
+        );
+
+        quick_test(
+            "SELECT id, age FROM person NATURAL FULL JOIN orders",
+            "Projection: (id, age)\n  Projection: (CASE WHEN person.id IS NULL THEN orders.id ELSE person.id END AS id, CASE WHEN person.name IS NULL THEN orders.name ELSE person.name END AS name, CASE WHEN person.age IS NULL THEN orders.age ELSE person.age END AS age, person.first_name)\n    Full Join: Filter: person.id = orders.id AND person.name = orders.name AND person.age = orders.age\n      TableScan: person\n      Projection: (orders.id AS orders.id, orders.name AS orders.name, orders.age AS orders.age)\n        TableScan: orders\n",
+        );
+    }
+
     #[test]
     fn test_with() {
         quick_test(
             "WITH t1 AS (SELECT * FROM person) SELECT * FROM t1",
             "Projection: (t1.age, t1.first_name, t1.id, t1.name)\n  SubqueryAlias: t1\n    Projection: (person.age, person.first_name, person.id, person.name)\n      TableScan: person\n",
         );
+
+        quick_test(
+            "WITH t1(a, b) AS (SELECT id, name FROM person) SELECT a FROM t1 WHERE b = 'x'",
+            "Projection: (t1.a)\n  Filter: t1.b = Utf8('x')\n    SubqueryAlias: t1\n      Projection: (person.id AS a, person.name AS b)\n        TableScan: person\n",
+        );
+
+        quick_test(
+            "WITH t1(a) AS (SELECT id, name FROM person) SELECT a FROM t1",
+            "Internal Error: Source table contains 2 columns but only 1 names given as column alias",
+        );
+        // reference the same cte twice
+        quick_test(
+            "WITH t1 AS (SELECT id FROM person) SELECT x.id FROM t1 AS x WHERE x.id > (SELECT MIN(id) FROM t1)",
+            "Projection: (x.id)\n  Filter: x.id > (\n          Projection: (MIN(t1.id))\n            Aggregate: group_expr=[], aggregat_expr=[MIN(t1.id)]\n              SubqueryAlias: t1\n                Projection: (person.id)\n                  TableScan: person\n)\n\n    SubqueryAlias: x\n      SubqueryAlias: t1\n        Projection: (person.id)\n          TableScan: person\n",
+        );
+    }
+
+    #[test]
+    fn test_with_recursive() {
+        quick_test(
+            "WITH RECURSIVE t(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM t WHERE n < 5) SELECT n FROM t",
+            "Projection: (t.n)\n  SubqueryAlias: t\n    RecursiveQuery: name=t, is_distinct=false\n      Projection: (Int64(1) AS n)\n        Empty Relation\n      Projection: (t.n + Int64(1) AS n)\n        Filter: t.n < Int64(5)\n          TableScan: t\n",
+        );
+
+        quick_test(
+            "WITH RECURSIVE t AS (SELECT id FROM person UNION SELECT id, id + 1 FROM t) SELECT * FROM t",
+            "Internal Error: UNION queries have different number of columns: left has 1 columns whereas right has 2 columns",
+        );
+
+        quick_test(
+            "WITH RECURSIVE t AS (SELECT id FROM person UNION SELECT id FROM person) SELECT * FROM t",
+            "Internal Error: Recursive CTE [t] must reference itself in the recursive term",
+        );
+
+        quick_test(
+            "WITH t AS (SELECT id FROM person UNION SELECT id FROM person) SELECT * FROM t",
+            "Projection: (t.id)\n  SubqueryAlias: t\n    SetOperation: op=UNION, all=false\n      Projection: (person.id)\n        TableScan: person\n      Projection: (person.id)\n        TableScan: person\n",
+        );
+    }
+
+    #[test]
+    fn test_window_function() {
+        quick_test(
+            "SELECT id, ROW_NUMBER() OVER (PARTITION BY name ORDER BY age DESC) AS rn FROM person",
+            "Projection: (person.id, ROW_NUMBER() OVER (PARTITION BY person.name ORDER BY person.age DESC RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) AS rn)\n  Window: window_expr=[ROW_NUMBER() OVER (PARTITION BY person.name ORDER BY person.age DESC RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW)]\n    TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT name, SUM(COUNT(id)) OVER (ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING) FROM person GROUP BY name",
+            "Projection: (person.name, SUM(COUNT(person.id)) OVER (ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING))\n  Window: window_expr=[SUM(COUNT(person.id)) OVER (ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING)]\n    Aggregate: group_expr=[person.name], aggregat_expr=[COUNT(person.id)]\n      TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT LAG(id, 1, 0, 1) OVER () FROM person",
+            "Internal Error: Window function LAG expects 1 to 3 arguments, got 4",
+        );
+
+        quick_test(
+            "SELECT SUM(id) OVER (ROWS BETWEEN UNBOUNDED FOLLOWING AND CURRENT ROW) FROM person",
+            "Internal Error: Invalid window frame: start bound cannot be UNBOUNDED FOLLOWING",
+        );
     }
 
     #[test]
@@ -1562,11 +4095,48 @@ mod tests {
         quick_test("SELECT * FROM person GROUP BY name", "Internal Error: column [person.age] must appear in the GROUP BY clause or be used in an aggregate function, validate columns: [person.name]");
     }
 
+    #[test]
+    fn test_having() {
+        quick_test(
+            "SELECT name, COUNT(*) AS c FROM person GROUP BY name HAVING c > 1",
+            "Projection: (person.name, COUNT(*) AS c)\n  Filter: COUNT(*) > Int64(1)\n    Aggregate: group_expr=[person.name], aggregat_expr=[COUNT(*)]\n      TableScan: person\n",
+        );
+
+        // HAVING without GROUP BY makes the query a single group
+        quick_test(
+            "SELECT 1 FROM person HAVING 1 > 0",
+            "Projection: (Int64(1))\n  Filter: Int64(1) > Int64(0)\n    Aggregate: group_expr=[], aggregat_expr=[]\n      TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT name FROM person GROUP BY name HAVING age > 1",
+            "Internal Error: column [person.age] must appear in the GROUP BY clause or be used in an aggregate function, validate columns: [person.name]",
+        );
+    }
+
+    #[test]
+    fn test_grouping_sets() {
+        quick_test(
+            "SELECT name, age, COUNT(*) FROM person GROUP BY ROLLUP (name, age)",
+            "Projection: (person.name, person.age, COUNT(*))\n  SetOperation: op=UNION, all=true\n    SetOperation: op=UNION, all=true\n      Projection: (person.name, person.age, COUNT(*))\n        Aggregate: group_expr=[person.name,person.age], aggregat_expr=[COUNT(*)]\n          TableScan: person\n      Projection: (person.name, CAST(Null AS Int32) AS age, COUNT(*))\n        Aggregate: group_expr=[person.name], aggregat_expr=[COUNT(*)]\n          TableScan: person\n    Projection: (CAST(Null AS Utf8) AS name, CAST(Null AS Int32) AS age, COUNT(*))\n      Aggregate: group_expr=[], aggregat_expr=[COUNT(*)]\n        TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT name, GROUPING(name) FROM person GROUP BY name",
+            "Projection: (person.name, GROUPING(person.name))\n  Projection: (person.name, Int64(0) AS GROUPING(person.name))\n    Aggregate: group_expr=[person.name], aggregat_expr=[]\n      TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT name, GROUPING(age) FROM person GROUP BY CUBE (name)",
+            "Internal Error: argument of [GROUPING(person.age)] must be a GROUP BY expression",
+        );
+    }
+
     #[test]
     fn test_order_by() {
         quick_test(
             "SELECT name FROM person ORDER BY name asc, age desc",
-            "Sort: person.name ASC, person.age DESC\n  Projection: (person.name)\n    TableScan: person\n",
+            "Projection: (person.name)\n  Sort: person.name ASC, person.age DESC\n    Projection: (person.name, person.age)\n      TableScan: person\n",
         );
 
         quick_test(
@@ -1578,6 +4148,45 @@ mod tests {
             "SELECT name as a FROM person ORDER BY a",
             "Sort: a ASC\n  Projection: (person.name AS a)\n    TableScan: person\n",
         );
+
+        quick_test(
+            "SELECT name FROM person ORDER BY name DESC NULLS LAST",
+            "Sort: person.name DESC NULLS LAST\n  Projection: (person.name)\n    TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT id + 1 FROM person ORDER BY id + 1",
+            "Sort: person.id + Int64(1) ASC\n  Projection: (person.id + Int64(1))\n    TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT name FROM person ORDER BY age + 1",
+            "Projection: (person.name)\n  Sort: person.age + Int64(1) ASC\n    Projection: (person.name, person.age)\n      TableScan: person\n",
+        );
+    }
+
+    #[test]
+    fn test_distinct() {
+        quick_test(
+            "SELECT DISTINCT name FROM person",
+            "DistinctOn: on_expr=[person.name]\n  Projection: (person.name)\n    TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT DISTINCT ON (name) name, age FROM person ORDER BY name, age DESC",
+            "DistinctOn: on_expr=[person.name]\n  Sort: person.name ASC, person.age DESC\n    Projection: (person.name, person.age)\n      TableScan: person\n",
+        );
+
+        // the DISTINCT ON and ORDER BY keys missing from the select list are projected away at the end
+        quick_test(
+            "SELECT DISTINCT ON (name) age FROM person ORDER BY name",
+            "Projection: (person.age)\n  DistinctOn: on_expr=[person.name]\n    Sort: person.name ASC\n      Projection: (person.age, person.name)\n        TableScan: person\n",
+        );
+
+        quick_test(
+            "SELECT DISTINCT name FROM person ORDER BY age",
+            "Internal Error: for SELECT DISTINCT, ORDER BY expression [person.age] must appear in the select list",
+        );
     }
 
     #[test]
@@ -1674,7 +4283,7 @@ mod tests {
             .into_iter()
             .map(|udf| (udf.name().to_uppercase().to_string(), udf))
             .collect();
-        let plan = SqlQueryPlanner::create_logical_plan(stmt, tables, &udfs);
+        let plan = SqlQueryPlanner::create_logical_plan(stmt, tables, &udfs, &SessionConfig::default());
         match plan {
             Ok(plan) => assert_eq!(utils::format(&plan, 0), expected, "SQL: {sql}"),
             Err(err) => assert_eq!(err.to_string(), expected, "SQL: {sql}"),
@@ -1,16 +1,21 @@
 use std::fmt;
 use std::sync::Arc;
 
-use arrow::array::RecordBatch;
+use arrow::array::{ArrayRef, RecordBatch, UInt32Array};
 use arrow::datatypes::SchemaRef;
 
 use crate::datatypes::scalar::ScalarValue;
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::internal_err;
 use crate::logical::expr::LogicalExpr;
+use crate::logical::plan::LogicalPlan;
 use crate::physical::expr::PhysicalExpr;
 use crate::physical::plan::PhysicalPlan;
 use std::fmt::Debug;
 
+/// A lazy sequence of record batches, a batch is read when the iterator is advanced
+pub type RecordBatchStream = Box<dyn Iterator<Item = Result<RecordBatch>> + Send>;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TableType {
     Base,
@@ -30,28 +35,167 @@ impl fmt::Display for TableType {
     }
 }
 
+/// How the scan of a data source evaluates a filter pushed to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterPushdown {
+    /// the filter is not pushed to the scan
+    Unsupported,
+    /// the scan uses the filter to skip data but may return rows it rejects, they are filtered again by the query
+    Inexact,
+    /// the scan only returns the rows the filter accepts, the query does not evaluate the filter again
+    Exact,
+}
+
+/// The statistics of a data source known without scanning it, a statistic is `None` when it is unknown
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Statistics {
+    /// the exact number of rows
+    pub num_rows: Option<usize>,
+    /// the number of bytes of the data, uncompressed
+    pub total_byte_size: Option<usize>,
+    /// the statistics of each column of the schema, empty when none is known
+    pub column_statistics: Vec<ColumnStatistics>,
+}
+
+/// The statistics of a column, the minimum and maximum of its non NULL values and its number of NULLs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnStatistics {
+    pub min_value: Option<ScalarValue>,
+    pub max_value: Option<ScalarValue>,
+    pub null_count: Option<usize>,
+}
+
 pub trait TableProvider: Debug + Send + Sync {
     fn schema(&self) -> SchemaRef;
 
     /// Perform a scan of the data source and return the results as RecordBatch
     fn scan(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<Vec<RecordBatch>>;
 
+    /// Perform a scan of the data source that reads the batches on demand. A data source reading its data from
+    /// storage overrides it, so that only the batches being processed are kept in memory.
+    fn scan_stream(&self, projection: Option<Vec<String>>, filters: &[LogicalExpr]) -> Result<RecordBatchStream> {
+        Ok(Box::new(self.scan(projection, filters)?.into_iter().map(Ok)))
+    }
+
+    /// The number of partitions of the data source, they are scanned independently by `scan_partition` and can be
+    /// read in parallel. A data source is a single partition by default.
+    fn output_partitions(&self) -> usize {
+        1
+    }
+
+    /// Perform a scan of a partition of the data source, the scans of the partitions in order return the rows of
+    /// `scan`. The filters are used as by `scan`.
+    fn scan_partition(
+        &self,
+        partition: usize,
+        projection: Option<Vec<String>>,
+        filters: &[LogicalExpr],
+    ) -> Result<Vec<RecordBatch>> {
+        match partition {
+            0 => self.scan(projection, filters),
+            _ => internal_err!("The table has no partition {}", partition),
+        }
+    }
+
+    /// Whether the scan uses the filters to skip data, the filters of a query are only pushed to the scan of a data
+    /// source that does. The scan may still return rows the filters reject, they are filtered again by the query.
+    fn supports_filters_pushdown(&self) -> bool {
+        false
+    }
+
+    /// How the scan evaluates a conjunct of the filters of a query, the conjuncts are pushed to the scan as
+    /// `Inexact` filters if the data source supports filters pushdown.
+    fn supports_filter_pushdown(&self, _filter: &LogicalExpr) -> FilterPushdown {
+        match self.supports_filters_pushdown() {
+            true => FilterPushdown::Inexact,
+            false => FilterPushdown::Unsupported,
+        }
+    }
+
+    /// The statistics of the data source. A count of all the rows of a data source knowing its number of rows does
+    /// not scan it, and the planner estimates the number of rows of the joined plans from them.
+    fn statistics(&self) -> Option<Statistics> {
+        None
+    }
+
+    /// Whether the data source has an index of the columns, in any order, the rows with given values of the columns
+    /// are looked up without a scan
+    fn has_index(&self, _columns: &[String]) -> bool {
+        false
+    }
+
+    /// The rows whose columns equal one of the keys, the keys are the rows of the arrays, an array for each column.
+    /// Returns the rows found in the order of the data source with the index of the key each row equals, a row equal
+    /// to several keys is returned for each of them. A key with a NULL equals no row.
+    fn lookup(&self, _columns: &[String], _keys: &[ArrayRef]) -> Result<(UInt32Array, RecordBatch)> {
+        internal_err!("The table has no index")
+    }
+
     /// Get the default value for a column, if available.
     fn get_column_default(&self, _column: &str) -> Option<ScalarValue> {
         None
     }
 
+    /// Append the rows produced by the input plan to the data source and return the number of inserted rows,
+    /// a read-only data source returns an error
     fn insert(&self, _input: Arc<dyn PhysicalPlan>) -> Result<u64> {
-        unimplemented!("insert_into not implemented")
+        internal_err!("The table does not support INSERT")
+    }
+
+    /// Update the records matching the filter, or all records without a filter, and return the number of
+    /// updated rows. `values` are the new values of each column of the schema in order, evaluated against
+    /// the records before the update.
+    fn update(&self, _values: Vec<Arc<dyn PhysicalExpr>>, _filter: Option<Arc<dyn PhysicalExpr>>) -> Result<u64> {
+        internal_err!("The table does not support UPDATE")
+    }
+
+    /// Insert the rows produced by the input plan and return the number of upserted rows, a row replaces the
+    /// records with the same values of the `keys` columns if there are any, otherwise it is appended.
+    /// The input rows must have distinct keys, a row with a NULL key is always appended.
+    fn upsert(&self, _input: Arc<dyn PhysicalPlan>, _keys: Vec<usize>) -> Result<u64> {
+        internal_err!("The table does not support UPSERT")
     }
 
     /// Delete records from the data source
     /// The input plan is the filter expression to apply to the data source
     fn delete(&self, _filter: Option<Arc<dyn PhysicalExpr>>) -> Result<u64> {
-        unimplemented!("delete not implemented")
+        internal_err!("The table does not support DELETE")
+    }
+
+    /// Remove all records of the data source and return the number of removed rows, the schema is kept
+    fn truncate(&self) -> Result<u64> {
+        internal_err!("The table does not support TRUNCATE")
+    }
+
+    /// A private copy of the data source, the changes of a transaction are made to the copy and written back to the
+    /// data source by `COMMIT`, so the other sessions don't see them before. A data source that does not support
+    /// transactions returns `None`, it cannot be changed in a transaction.
+    fn fork(&self) -> Result<Option<Arc<dyn TableProvider>>> {
+        Ok(None)
+    }
+
+    /// A copy of the rows of the data source, `None` if the data source does not support transactions
+    fn snapshot(&self) -> Result<Option<Vec<RecordBatch>>> {
+        Ok(None)
+    }
+
+    /// Replace the rows of the data source with a snapshot taken by `snapshot`, the rows of a copy made by `fork`
+    /// when its transaction is committed
+    fn restore(&self, _snapshot: Vec<RecordBatch>) -> Result<()> {
+        internal_err!("The table does not support COMMIT")
     }
 
     fn table_type(&self) -> TableType {
         TableType::Base
     }
+
+    /// The logical plan of a view, the planner inlines it wherever the table is referenced
+    fn get_logical_plan(&self) -> Option<&LogicalPlan> {
+        None
+    }
+
+    /// The SQL of the query of a view, shown by `information_schema.views`
+    fn get_table_definition(&self) -> Option<&str> {
+        None
+    }
 }
@@ -8,7 +8,7 @@ use arrow::{
 use sqlparser::parser::Parser;
 
 use crate::{
-    datasource::memory::MemoryTable, logical::plan::LogicalPlan, physical::plan::{PhysicalPlan, Scan}, planner::sql::SqlQueryPlanner
+    datasource::memory::MemoryTable, execution::config::SessionConfig, logical::plan::LogicalPlan, physical::plan::{PhysicalPlan, Scan}, planner::sql::SqlQueryPlanner
 };
 
 #[macro_export]
@@ -153,6 +153,24 @@ pub fn build_table_scan_i32(fields: Vec<(&str, Vec<i32>)>) -> Arc<dyn PhysicalPl
     Arc::new(Scan::new(Arc::new(schema), Arc::new(datasource), None))
 }
 
+/// a scan of nullable Int32 columns
+pub fn build_table_scan_nullable_i32(fields: Vec<(&str, Vec<Option<i32>>)>) -> Arc<dyn PhysicalPlan> {
+    let schema = Arc::new(Schema::new(
+        fields
+            .iter()
+            .map(|(name, _)| Field::new(name.to_string(), DataType::Int32, true))
+            .collect::<Vec<_>>(),
+    ));
+    let columns = fields
+        .into_iter()
+        .map(|(_, values)| Arc::new(Int32Array::from(values)) as Arc<dyn Array>)
+        .collect();
+    let batch = RecordBatch::try_new(schema.clone(), columns).unwrap();
+    let table = MemoryTable::try_new(schema.clone(), vec![batch]).unwrap();
+
+    Arc::new(Scan::new(schema, Arc::new(table), None))
+}
+
 pub fn sql_to_plan(sql: &str) -> LogicalPlan {
     let mut tables = HashMap::new();
 
@@ -306,5 +324,5 @@ pub fn sql_to_plan(sql: &str) -> LogicalPlan {
 
     let stmt = Parser::new(sql).parse().unwrap();
     let udsf = HashMap::default();
-    SqlQueryPlanner::create_logical_plan(stmt, tables, &udsf).unwrap()
+    SqlQueryPlanner::create_logical_plan(stmt, tables, &udsf, &SessionConfig::default()).unwrap()
 }
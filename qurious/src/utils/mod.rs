@@ -9,6 +9,7 @@ use arrow::datatypes::{Schema, SchemaBuilder};
 use sqlparser::ast::Ident;
 
 use crate::logical::plan::LogicalPlan;
+use crate::physical::plan::PhysicalPlan;
 
 pub fn version() -> String {
     format!("QURIOUS v{}", env!("CARGO_PKG_VERSION"))
@@ -30,6 +31,58 @@ pub fn format(plan: &LogicalPlan, ident: usize) -> String {
     sb
 }
 
+/// format the plan like [`format`], each operator is followed by its output schema
+pub fn format_with_schema(plan: &LogicalPlan, ident: usize) -> String {
+    let mut sb = String::new();
+
+    (0..ident).for_each(|_| sb.push_str("  "));
+
+    sb.push_str(&format!("{} {}\n", plan, format_schema(&plan.schema())));
+
+    if let Some(p) = plan.children() {
+        for ele in p {
+            sb.push_str(&format_with_schema(ele, ident + 1));
+        }
+    }
+
+    sb
+}
+
+/// format the physical plan as a tree of operators, each operator is followed by its output schema if `verbose`
+pub fn format_physical(plan: &Arc<dyn PhysicalPlan>, ident: usize, verbose: bool) -> String {
+    let mut sb = String::new();
+
+    (0..ident).for_each(|_| sb.push_str("  "));
+
+    sb.push_str(&plan.describe());
+    if verbose {
+        sb.push_str(&format!(" {}", format_schema(&plan.schema())));
+    }
+    sb.push('\n');
+
+    if let Some(p) = plan.children() {
+        for ele in p {
+            sb.push_str(&format_physical(&ele, ident + 1, verbose));
+        }
+    }
+
+    sb
+}
+
+/// format the fields of the schema as `[name:type;N]`, `;N` marks a nullable field
+pub fn format_schema(schema: &Schema) -> String {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let nullable = if field.is_nullable() { ";N" } else { "" };
+            format!("{}:{}{}", field.name(), field.data_type(), nullable)
+        })
+        .collect::<Vec<_>>();
+
+    format!("[{}]", fields.join(", "))
+}
+
 pub fn normalize_ident(i: Ident) -> String {
     match i.quote_style {
         Some(_) => i.value,
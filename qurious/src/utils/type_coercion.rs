@@ -18,6 +18,29 @@ pub fn get_result_type(lhs: &DataType, op: &Operator, rhs: &DataType) -> Result<
     coercion_types(lhs, op, rhs).map(|x| x.ret)
 }
 
+/// the type both sides can be cast to without losing information, eg. to reconcile the inputs of a set operation
+pub fn get_common_type(lhs: &DataType, rhs: &DataType) -> Result<DataType> {
+    match (lhs, rhs) {
+        _ if lhs == rhs => Ok(lhs.clone()),
+        (Null, dt) | (dt, Null) => Ok(dt.clone()),
+        (Utf8 | LargeUtf8, Utf8 | LargeUtf8) => Ok(LargeUtf8),
+        (Decimal128(p1, s1), Decimal128(p2, s2)) => {
+            let scale = *s1.max(s2);
+            let integer_digits = (*p1 as i8 - *s1).max(*p2 as i8 - *s2);
+            Ok(Decimal128((integer_digits + scale).min(38) as u8, scale))
+        }
+        (Decimal128(_, _), Int8 | Int16 | Int32 | Int64) => get_common_type(lhs, &coerce_numeric_type_to_decimal(rhs)?),
+        (Int8 | Int16 | Int32 | Int64, Decimal128(_, _)) => get_common_type(&coerce_numeric_type_to_decimal(lhs)?, rhs),
+        (Float16 | Float32 | Float64 | Decimal128(_, _), _) | (_, Float16 | Float32 | Float64 | Decimal128(_, _))
+            if lhs.is_numeric() && rhs.is_numeric() =>
+        {
+            Ok(Float64)
+        }
+        _ if lhs.is_integer() && rhs.is_integer() => Ok(Int64),
+        _ => internal_err!("can not find a common type for {lhs} and {rhs}"),
+    }
+}
+
 struct BinaryTypes {
     lhs: DataType,
     rhs: DataType,
@@ -35,6 +58,20 @@ impl BinaryTypes {
 }
 
 fn coercion_types(lhs: &DataType, op: &Operator, rhs: &DataType) -> Result<BinaryTypes> {
+    // a timestamp without a time zone is compared as a timestamp in the time zone of the other side
+    if let (Timestamp(unit, l_tz), Timestamp(_, r_tz)) = (lhs, rhs) {
+        let comparison = matches!(
+            op,
+            Operator::Eq | Operator::NotEq | Operator::Gt | Operator::GtEq | Operator::Lt | Operator::LtEq
+        );
+        if comparison && l_tz != r_tz {
+            return Ok(BinaryTypes {
+                ret: DataType::Boolean,
+                ..BinaryTypes::uniform(Timestamp(*unit, l_tz.clone().or(r_tz.clone())))
+            });
+        }
+    }
+
     match op {
         Operator::And
         | Operator::Or
@@ -48,10 +85,24 @@ fn coercion_types(lhs: &DataType, op: &Operator, rhs: &DataType) -> Result<Binar
             rhs: rhs.clone(),
             ret: DataType::Boolean,
         }),
+        // a NULL is compared as a value of the other side, eg. `a IS DISTINCT FROM NULL`
+        Operator::IsDistinctFrom | Operator::IsNotDistinctFrom => {
+            let (lhs, rhs) = match (lhs, rhs) {
+                (Null, dt) | (dt, Null) => (dt, dt),
+                _ => (lhs, rhs),
+            };
+            Ok(BinaryTypes {
+                lhs: lhs.clone(),
+                rhs: rhs.clone(),
+                ret: DataType::Boolean,
+            })
+        }
 
         Operator::Add | Operator::Sub | Operator::Mul | Operator::Div | Operator::Mod => try_coerce(lhs, op, rhs)
             .or(decimal_coercion(lhs, op, rhs))
             .or(numeric_coercion(lhs, rhs)),
+        // both sides are concatenated as strings, eg. `'a' || 1` is `a1`
+        Operator::StringConcat => Ok(BinaryTypes::uniform(Utf8)),
     }
 }
 
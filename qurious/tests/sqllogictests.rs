@@ -29,23 +29,25 @@ fn main() -> Result<()> {
 
     read_test_files(TEST_SQL_DIR, include_tpch)?
         .into_par_iter()
-        .map(make_test_session)
-        .try_for_each(|session| {
-            let session = session?;
-            let mut runner = sqllogictest::Runner::new(|| async { Ok(&session) });
-
-            runner.run_file(session.path.clone()).map_err(|e| {
+        .try_for_each(|path| {
+            // the connections of a file, the default one and each `connection name` of the file, are sessions on
+            // the same catalogs
+            let session = ExecuteSession::new()?;
+            let mut runner = sqllogictest::Runner::new(|| async {
+                session.new_session().map(|session| TestSession {
+                    session,
+                    path: path.clone(),
+                })
+            });
+
+            runner.run_file(path.clone()).map_err(|e| {
                 log::error!("{e}");
 
-                Error::InternalError(format!("case [{}] failed.", session.path.display()))
+                Error::InternalError(format!("case [{}] failed.", path.display()))
             })
         })
 }
 
-fn make_test_session(path: PathBuf) -> Result<TestSession> {
-    ExecuteSession::new().map(|session| TestSession { session, path })
-}
-
 fn read_test_files(path: &str, include_tpch: bool) -> Result<Vec<PathBuf>> {
     let mut files = vec![];
 
@@ -66,7 +68,7 @@ struct TestSession {
 }
 
 #[async_trait]
-impl AsyncDB for &TestSession {
+impl AsyncDB for TestSession {
     type Error = qurious::error::Error;
 
     type ColumnType = DefaultColumnType;
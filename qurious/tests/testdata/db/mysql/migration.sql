@@ -0,0 +1,10 @@
+CREATE TABLE products (
+    id BIGINT PRIMARY KEY,
+    name VARCHAR(50),
+    price DECIMAL(10, 2),
+    created DATETIME
+);
+
+INSERT INTO products VALUES
+    (1, 'apple', 1.50, '2024-01-01 12:00:00'),
+    (2, NULL, 2.00, NULL);
@@ -1,27 +1,55 @@
+use crate::parser::{binding_power, BindingPower};
 use crate::{datatype::DataType, error::Error};
 use std::fmt::{Display, Formatter};
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum Statement {
     CreateTable {
-        query: Option<Select>,
+        /// the query of `CREATE TABLE ... AS query`, a `SELECT` or a set operation
+        query: Option<Box<Statement>>,
         table: String,
         check_exists: bool,
         columns: Vec<Column>,
+        constraints: Vec<TableConstraint>,
     },
     CreateSchema {
         schema: String,
         check_exists: bool,
     },
+    /// `CREATE [OR REPLACE] VIEW name [(columns)] AS query`
+    CreateView {
+        or_replace: bool,
+        name: String,
+        /// optional column names of the view, eg. `CREATE VIEW v(a, b) AS ...`
+        columns: Vec<Ident>,
+        query: Box<Statement>,
+    },
     DropTable {
         table: String,
         check_exists: bool,
     },
+    DropView {
+        view: String,
+        check_exists: bool,
+    },
     DropSchema {
         schema: String,
         check_exists: bool,
     },
     Select(Box<Select>),
+    /// `left UNION | INTERSECT | EXCEPT [ALL] right`, the ORDER BY and LIMIT after the last query
+    /// apply to the result of the set operation
+    SetOperation {
+        op: SetOperator,
+        all: bool,
+        left: Box<Statement>,
+        right: Box<Statement>,
+        order_by: Option<Vec<OrderByExpr>>,
+        limit: Option<Expression>,
+        offset: Option<Expression>,
+    },
+    /// `VALUES (expr, ...), ...`, a constant relation
+    Values(Vec<Vec<Expression>>),
     Insert {
         table: String,
         alias: Option<String>,
@@ -29,7 +57,7 @@ pub enum Statement {
         values: Vec<Vec<Expression>>,
         on_conflict: Option<OnConflict>,
         returning: Option<Vec<SelectItem>>,
-        query: Option<Select>,
+        query: Option<Box<Select>>,
     },
     Update {
         table: String,
@@ -40,6 +68,32 @@ pub enum Statement {
         table: String,
         r#where: Option<Expression>,
     },
+    /// `EXPLAIN [ANALYZE] [VERBOSE] query`
+    Explain {
+        analyze: bool,
+        verbose: bool,
+        statement: Box<Statement>,
+    },
+    /// `TRUNCATE [TABLE] table`
+    Truncate {
+        table: String,
+    },
+    /// `PREPARE name [(data_type, ...)] AS statement`, the statement refers to the parameters as `$1`, `$2`, ...
+    Prepare {
+        name: Ident,
+        data_types: Vec<DataType>,
+        statement: Box<Statement>,
+    },
+    /// `EXECUTE name [(parameter, ...)]`
+    Execute {
+        name: Ident,
+        parameters: Vec<Expression>,
+    },
+    /// `DEALLOCATE [PREPARE] name`
+    Deallocate {
+        name: Ident,
+        prepare: bool,
+    },
     Copy {
         /// The source of 'COPY TO', or the target of 'COPY FROM'
         source: CopySource,
@@ -51,6 +105,42 @@ pub enum Statement {
         options: Vec<CopyOption>,
     },
     ShowTables,
+    /// `SHOW COLUMNS FROM table` or `DESCRIBE table`, the name, type and nullability of each column
+    ShowColumns {
+        table: ObjectName,
+    },
+    /// `SET name {= | TO} value [, ...]`, changes a configuration parameter of the session
+    SetVariable {
+        variable: Ident,
+        value: Vec<Expression>,
+    },
+    /// `SHOW name` or `SHOW ALL`, the value of a configuration parameter of the session
+    ShowVariable {
+        variable: Ident,
+    },
+    /// `BEGIN [TRANSACTION | WORK]` or `START TRANSACTION`
+    StartTransaction,
+    /// `COMMIT [TRANSACTION | WORK]`
+    Commit,
+    /// `ROLLBACK [TRANSACTION | WORK]`
+    Rollback,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SetOperator {
+    Union,
+    Intersect,
+    Except,
+}
+
+impl Display for SetOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetOperator::Union => write!(f, "UNION"),
+            SetOperator::Intersect => write!(f, "INTERSECT"),
+            SetOperator::Except => write!(f, "EXCEPT"),
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -78,6 +168,8 @@ pub enum CopyOption {
     Delimiter(char),
     /// HEADER \[ boolean \]
     Header(bool),
+    /// name value, an option of the file format like `COMPRESSION zstd` or `ROW_GROUP_SIZE 100000`
+    Option(Ident, Literal),
 }
 
 impl Display for CopyOption {
@@ -86,6 +178,7 @@ impl Display for CopyOption {
             CopyOption::Format(format) => write!(f, "FORMAT {}", format),
             CopyOption::Delimiter(delimiter) => write!(f, "DELIMITER '{}'", delimiter),
             CopyOption::Header(header) => write!(f, "HEADER {}", header),
+            CopyOption::Option(name, value) => write!(f, "{} {}", name, value),
         }
     }
 }
@@ -99,23 +192,23 @@ pub struct Select {
     pub r#where: Option<Expression>,
     pub group_by: Option<Vec<Expression>>,
     pub having: Option<Expression>,
-    pub order_by: Option<Vec<(Expression, Order)>>,
+    pub order_by: Option<Vec<OrderByExpr>>,
     pub limit: Option<Expression>,
     pub offset: Option<Expression>,
 }
 
 impl Display for Select {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "SELECT ")?;
         if let Some(w) = &self.with {
-            write!(f, "WITH {}", w)?;
+            write!(f, "WITH {} ", w)?;
         }
+        write!(f, "SELECT ")?;
         if let Some(d) = &self.distinct {
             match d {
-                Distinct::ALL => write!(f, "ALL ")?,
+                Distinct::ALL => write!(f, "DISTINCT ")?,
                 Distinct::DISTINCT(e) => write!(
                     f,
-                    "DISTINCT {}",
+                    "DISTINCT ON ({}) ",
                     e.iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", ")
                 )?,
             }
@@ -157,10 +250,7 @@ impl Display for Select {
             write!(
                 f,
                 " ORDER BY {}",
-                o.iter()
-                    .map(|(e, o)| format!("{} {}", e, o))
-                    .collect::<Vec<String>>()
-                    .join(", ")
+                o.iter().map(|o| o.to_string()).collect::<Vec<String>>().join(", ")
             )?;
         }
         if let Some(l) = &self.limit {
@@ -181,6 +271,9 @@ pub struct With {
 
 impl Display for With {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.recursive {
+            write!(f, "RECURSIVE ")?;
+        }
         write!(
             f,
             "{}",
@@ -196,12 +289,26 @@ impl Display for With {
 #[derive(Clone, PartialEq, Debug)]
 pub struct Cte {
     pub alias: String,
-    pub query: Box<Select>,
+    /// optional column names of the CTE, eg. `WITH t(a, b) AS (...)`
+    pub columns: Vec<Ident>,
+    pub query: Box<Statement>,
 }
 
 impl Display for Cte {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} AS ({})", self.alias, self.query)
+        write!(f, "{}", self.alias)?;
+        if !self.columns.is_empty() {
+            write!(
+                f,
+                "({})",
+                self.columns
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        write!(f, " AS ({})", self.query)
     }
 }
 
@@ -211,10 +318,10 @@ pub enum SelectItem {
     UnNamedExpr(Expression),
     /// expression with alias eg. `SELECT 1 AS one`
     ExprWithAlias(Expression, String),
-    /// `SELECT *`
-    Wildcard,
-    /// `SELECT table.*`
-    QualifiedWildcard(Vec<String>),
+    /// `SELECT * [EXCLUDE (column, ...)]`, the columns excluded from the expansion
+    Wildcard(Vec<Ident>),
+    /// `SELECT table.* [EXCLUDE (column, ...)]`
+    QualifiedWildcard(Vec<String>, Vec<Ident>),
 }
 
 impl Display for SelectItem {
@@ -222,12 +329,26 @@ impl Display for SelectItem {
         match self {
             SelectItem::UnNamedExpr(e) => write!(f, "{}", e),
             SelectItem::ExprWithAlias(e, a) => write!(f, "{} AS {}", e, a),
-            SelectItem::Wildcard => write!(f, "*"),
-            SelectItem::QualifiedWildcard(t) => write!(f, "{}.*", t.join(".")),
+            SelectItem::Wildcard(exclude) => write!(f, "*{}", display_exclude(exclude)),
+            SelectItem::QualifiedWildcard(t, exclude) => write!(f, "{}.*{}", t.join("."), display_exclude(exclude)),
         }
     }
 }
 
+fn display_exclude(exclude: &[Ident]) -> String {
+    if exclude.is_empty() {
+        return String::new();
+    }
+    format!(
+        " EXCLUDE ({})",
+        exclude
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct Column {
     pub name: String,
@@ -239,6 +360,24 @@ pub struct Column {
     pub references: Option<String>,
 }
 
+/// A constraint on the columns of a table, declared apart from the column definitions
+#[derive(Clone, PartialEq, Debug)]
+pub enum TableConstraint {
+    /// `PRIMARY KEY (columns)`
+    PrimaryKey(Vec<String>),
+    /// `UNIQUE (columns)`
+    Unique(Vec<String>),
+}
+
+impl Display for TableConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableConstraint::PrimaryKey(columns) => write!(f, "PRIMARY KEY ({})", columns.join(", ")),
+            TableConstraint::Unique(columns) => write!(f, "UNIQUE ({})", columns.join(", ")),
+        }
+    }
+}
+
 impl Display for Column {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} {}", self.name, self.datatype)?;
@@ -265,7 +404,8 @@ impl Display for Column {
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum OnConflict {
-    DoNothing,
+    /// `ON CONFLICT (constraint) DO NOTHING`
+    DoNothing { constraints: Vec<Ident> },
     /// `ON CONFLICT (constraint) DO UPDATE SET values[expressions]`
     DoUpdate {
         constraints: Vec<Ident>,
@@ -300,7 +440,7 @@ impl std::convert::From<&str> for Ident {
 impl Display for Ident {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match &self.quote_style {
-            Some(q) => write!(f, "{}{}{}", q, self.value, q),
+            Some(q) => write!(f, "{}{}{}", q, self.value.replace(*q, &format!("{q}{q}")), q),
             None => write!(f, "{}", self.value),
         }
     }
@@ -312,29 +452,47 @@ pub enum Order {
     Desc,
 }
 
+/// a sort key of `ORDER BY`, `expr [ASC | DESC] [NULLS FIRST | NULLS LAST]`
+#[derive(Clone, PartialEq, Debug)]
+pub struct OrderByExpr {
+    pub expr: Expression,
+    pub order: Order,
+    /// `None` if the order of the NULLs is not specified
+    pub nulls_first: Option<bool>,
+}
+
+impl Display for OrderByExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.expr, self.order)?;
+        match self.nulls_first {
+            Some(true) => write!(f, " NULLS FIRST"),
+            Some(false) => write!(f, " NULLS LAST"),
+            None => Ok(()),
+        }
+    }
+}
+
 impl Display for OnConflict {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let constraints = match self {
+            OnConflict::DoNothing { constraints } | OnConflict::DoUpdate { constraints, .. } => constraints,
+        };
+        write!(
+            f,
+            "ON CONFLICT ({}) ",
+            constraints
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )?;
         match self {
-            OnConflict::DoNothing => write!(f, "DO NOTHING"),
-            OnConflict::DoUpdate { constraints, values } => {
-                write!(
-                    f,
-                    "DO UPDATE SET {}",
-                    values.iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", ")
-                )?;
-                if !constraints.is_empty() {
-                    write!(
-                        f,
-                        " WHERE {}",
-                        constraints
-                            .iter()
-                            .map(|e| e.to_string())
-                            .collect::<Vec<String>>()
-                            .join(", ")
-                    )?;
-                }
-                Ok(())
-            }
+            OnConflict::DoNothing { .. } => write!(f, "DO NOTHING"),
+            OnConflict::DoUpdate { values, .. } => write!(
+                f,
+                "DO UPDATE SET {}",
+                values.iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", ")
+            ),
         }
     }
 }
@@ -352,6 +510,36 @@ impl Display for Statement {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Statement::Select(select) => write!(f, "{}", select),
+            Statement::SetOperation {
+                op,
+                all,
+                left,
+                right,
+                order_by,
+                limit,
+                offset,
+            } => {
+                write!(f, "{} {}", left, op)?;
+                if *all {
+                    write!(f, " ALL")?;
+                }
+                write!(f, " {}", right)?;
+                if let Some(o) = order_by {
+                    write!(
+                        f,
+                        " ORDER BY {}",
+                        o.iter().map(|o| o.to_string()).collect::<Vec<String>>().join(", ")
+                    )?;
+                }
+                if let Some(l) = limit {
+                    write!(f, " LIMIT {}", l)?;
+                }
+                if let Some(o) = offset {
+                    write!(f, " OFFSET {}", o)?;
+                }
+                Ok(())
+            }
+            Statement::Values(values) => write!(f, "VALUES {}", display_values(values)),
             Statement::Insert {
                 table,
                 alias,
@@ -361,31 +549,21 @@ impl Display for Statement {
                 returning,
                 query,
             } => {
-                write!(f, "INSERT INTO {} ", table,)?;
+                write!(f, "INSERT INTO {}", table)?;
                 if let Some(a) = alias {
-                    write!(f, "AS {}", a)?;
+                    write!(f, " AS {}", a)?;
                 }
                 if let Some(c) = columns {
                     write!(
                         f,
-                        "({}) ",
+                        " ({})",
                         c.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(", ")
                     )?;
                 }
-                write!(
-                    f,
-                    "VALUES {}",
-                    values
-                        .iter()
-                        .map(|v| {
-                            format!(
-                                "({})",
-                                v.iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", ")
-                            )
-                        })
-                        .collect::<Vec<String>>()
-                        .join(", ")
-                )?;
+                match query {
+                    Some(q) => write!(f, " {}", q)?,
+                    None => write!(f, " VALUES {}", display_values(values))?,
+                }
                 if let Some(o) = on_conflict {
                     write!(f, " {}", o)?;
                 }
@@ -396,9 +574,6 @@ impl Display for Statement {
                         r.iter().map(|r| r.to_string()).collect::<Vec<String>>().join(", ")
                     )?;
                 }
-                if let Some(q) = query {
-                    write!(f, " {}", q)?;
-                }
                 Ok(())
             }
             Statement::Update {
@@ -425,6 +600,50 @@ impl Display for Statement {
                 }
                 Ok(())
             }
+            Statement::Explain {
+                analyze,
+                verbose,
+                statement,
+            } => {
+                write!(f, "EXPLAIN ")?;
+                if *analyze {
+                    write!(f, "ANALYZE ")?;
+                }
+                if *verbose {
+                    write!(f, "VERBOSE ")?;
+                }
+                write!(f, "{}", statement)
+            }
+            Statement::Truncate { table } => write!(f, "TRUNCATE TABLE {}", table),
+            Statement::Prepare {
+                name,
+                data_types,
+                statement,
+            } => {
+                write!(f, "PREPARE {}", name)?;
+                if !data_types.is_empty() {
+                    write!(
+                        f,
+                        " ({})",
+                        data_types.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ")
+                    )?;
+                }
+                write!(f, " AS {}", statement)
+            }
+            Statement::Execute { name, parameters } => {
+                write!(f, "EXECUTE {}", name)?;
+                if !parameters.is_empty() {
+                    write!(
+                        f,
+                        " ({})",
+                        parameters.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ")
+                    )?;
+                }
+                Ok(())
+            }
+            Statement::Deallocate { name, prepare } => {
+                write!(f, "DEALLOCATE {}{}", if *prepare { "PREPARE " } else { "" }, name)
+            }
             Statement::CreateSchema { schema, check_exists } => {
                 write!(f, "CREATE SCHEMA ")?;
                 if *check_exists {
@@ -432,6 +651,30 @@ impl Display for Statement {
                 }
                 write!(f, "{}", schema)
             }
+            Statement::CreateView {
+                or_replace,
+                name,
+                columns,
+                query,
+            } => {
+                write!(f, "CREATE ")?;
+                if *or_replace {
+                    write!(f, "OR REPLACE ")?;
+                }
+                write!(f, "VIEW {}", name)?;
+                if !columns.is_empty() {
+                    write!(
+                        f,
+                        " ({})",
+                        columns
+                            .iter()
+                            .map(|c| c.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    )?;
+                }
+                write!(f, " AS {}", query)
+            }
             Statement::DropSchema { schema, check_exists } => {
                 write!(f, "DROP SCHEMA ")?;
                 if *check_exists {
@@ -444,6 +687,7 @@ impl Display for Statement {
                 table,
                 check_exists,
                 columns,
+                constraints,
             } => {
                 write!(f, "CREATE TABLE ")?;
                 if *check_exists {
@@ -456,6 +700,9 @@ impl Display for Statement {
                     }
                     write!(f, "{}", c)?;
                 }
+                for c in constraints {
+                    write!(f, ", {}", c)?;
+                }
                 write!(f, ")")?;
                 if let Some(q) = query {
                     write!(f, " AS {}", q)?;
@@ -469,6 +716,13 @@ impl Display for Statement {
                 }
                 write!(f, "{}", table)
             }
+            Statement::DropView { view, check_exists } => {
+                write!(f, "DROP VIEW ")?;
+                if *check_exists {
+                    write!(f, "IF EXISTS ")?;
+                }
+                write!(f, "{}", view)
+            }
             Statement::Copy {
                 source,
                 to,
@@ -499,7 +753,7 @@ impl Display for Statement {
                     write!(f, " FROM ")?;
                 }
                 match target {
-                    CopyTarget::File { file } => write!(f, "{}", file)?,
+                    CopyTarget::File { file } => write!(f, "{}", Literal::String(file.clone()))?,
                 }
                 if !options.is_empty() {
                     write!(
@@ -515,14 +769,65 @@ impl Display for Statement {
                 Ok(())
             }
             Statement::ShowTables => write!(f, "SHOW TABLES"),
+            Statement::ShowColumns { table } => write!(f, "SHOW COLUMNS FROM {}", table),
+            Statement::SetVariable { variable, value } => write!(
+                f,
+                "SET {} = {}",
+                variable,
+                value.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ")
+            ),
+            Statement::ShowVariable { variable } => write!(f, "SHOW {}", variable),
+            Statement::StartTransaction => write!(f, "BEGIN"),
+            Statement::Commit => write!(f, "COMMIT"),
+            Statement::Rollback => write!(f, "ROLLBACK"),
         }
     }
 }
 
+/// a FROM item operand, a join is parenthesized so it stays a single operand when parsed back
+fn display_nested_table(table: &From) -> String {
+    match table {
+        From::Join { .. } => format!("({})", table),
+        _ => table.to_string(),
+    }
+}
+
+/// ` AS alias(column, ...)` of a FROM item
+fn display_table_alias(alias: &Option<String>, columns: &[Ident]) -> String {
+    match alias {
+        Some(a) if columns.is_empty() => format!(" AS {}", a),
+        Some(a) => format!(
+            " AS {}({})",
+            a,
+            columns
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        None => String::new(),
+    }
+}
+
+fn display_values(values: &[Vec<Expression>]) -> String {
+    values
+        .iter()
+        .map(|v| {
+            format!(
+                "({})",
+                v.iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", ")
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
 #[derive(Clone, PartialEq, Debug)]
 
 pub enum Distinct {
+    /// `DISTINCT`, removes the duplicate rows
     ALL,
+    /// `DISTINCT ON (exprs)`, keeps the first row for each distinct value of the expressions
     DISTINCT(Vec<Expression>),
 }
 
@@ -530,7 +835,8 @@ pub enum Distinct {
 
 pub enum From {
     Table {
-        name: String,
+        /// `[[catalog.]schema.]table`, or a file in single quotes such as `'./data.csv'`
+        name: ObjectName,
         alias: Option<String>,
     },
     TableFunction {
@@ -541,11 +847,44 @@ pub enum From {
     SubQuery {
         query: Box<Statement>,
         alias: Option<String>,
+        /// optional column names of the alias, eg. `(SELECT a, b FROM t) AS x(c1, c2)`
+        columns: Vec<Ident>,
+        /// `LATERAL (subquery)`, the subquery can reference the columns of the preceding FROM items
+        lateral: bool,
+    },
+    /// `(VALUES (expr, ...), ...) [AS alias [(column, ...)]]`
+    Values {
+        values: Vec<Vec<Expression>>,
+        alias: Option<String>,
+        columns: Vec<Ident>,
+    },
+    /// `table TABLESAMPLE method (percent) [REPEATABLE (seed)]`, a sample of the rows of a table
+    TableSample {
+        table: Box<From>,
+        sample: TableSample,
+    },
+    /// `table PIVOT (aggregate FOR column IN (value [AS alias], ...)) [AS alias]`, a column per value
+    /// holding the aggregate of the rows with that value in the pivot column
+    Pivot {
+        table: Box<From>,
+        aggregate: Expression,
+        column: Ident,
+        values: Vec<PivotValue>,
+        alias: Option<String>,
+    },
+    /// `table UNPIVOT (value FOR name IN (column, ...)) [AS alias]`, a row per non NULL column
+    /// holding the column name in `name` and its value in `value`
+    Unpivot {
+        table: Box<From>,
+        value: Ident,
+        name: Ident,
+        columns: Vec<Ident>,
+        alias: Option<String>,
     },
     Join {
         left: Box<From>,
         right: Box<From>,
-        on: Option<Expression>,
+        constraint: JoinConstraint,
         join_type: JoinType,
     },
 }
@@ -578,34 +917,155 @@ impl Display for From {
                         .join(", ")
                 ),
             },
-            From::SubQuery { query, alias } => match alias {
-                Some(a) => write!(f, "({}) AS {}", query, a),
-                None => write!(f, "({})", query),
-            },
+            From::SubQuery {
+                query,
+                alias,
+                columns,
+                lateral,
+            } => {
+                if *lateral {
+                    write!(f, "LATERAL ")?;
+                }
+                write!(f, "({}){}", query, display_table_alias(alias, columns))
+            }
+            From::Values { values, alias, columns } => {
+                write!(
+                    f,
+                    "(VALUES {}){}",
+                    display_values(values),
+                    display_table_alias(alias, columns)
+                )
+            }
+            From::TableSample { table, sample } => write!(f, "{} {}", table, sample),
+            From::Pivot {
+                table,
+                aggregate,
+                column,
+                values,
+                alias,
+            } => write!(
+                f,
+                "{} PIVOT ({} FOR {} IN ({})){}",
+                display_nested_table(table),
+                aggregate,
+                column,
+                values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "),
+                display_table_alias(alias, &[])
+            ),
+            From::Unpivot {
+                table,
+                value,
+                name,
+                columns,
+                alias,
+            } => write!(
+                f,
+                "{} UNPIVOT ({} FOR {} IN ({})){}",
+                display_nested_table(table),
+                value,
+                name,
+                columns.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(", "),
+                display_table_alias(alias, &[])
+            ),
             From::Join {
                 left,
                 right,
-                on,
+                constraint,
                 join_type,
             } => {
                 write!(f, "{}", left)?;
+                if constraint == &JoinConstraint::Natural {
+                    write!(f, " NATURAL")?;
+                }
                 match join_type {
                     JoinType::Cross => write!(f, " CROSS JOIN ")?,
                     JoinType::Inner => write!(f, " INNER JOIN ")?,
                     JoinType::Left => write!(f, " LEFT JOIN ")?,
                     JoinType::Full => write!(f, " FULL JOIN ")?,
                     JoinType::Right => write!(f, " RIGHT JOIN ")?,
+                    JoinType::LeftSemi => write!(f, " LEFT SEMI JOIN ")?,
+                    JoinType::LeftAnti => write!(f, " LEFT ANTI JOIN ")?,
                 }
-                write!(f, "{}", right)?;
-                if let Some(on) = on {
-                    write!(f, " ON {}", on)?;
+                write!(f, "{}", display_nested_table(right))?;
+                match constraint {
+                    JoinConstraint::On(on) => write!(f, " ON {}", on),
+                    JoinConstraint::Using(columns) => write!(
+                        f,
+                        " USING ({})",
+                        columns.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(", ")
+                    ),
+                    JoinConstraint::Natural | JoinConstraint::None => Ok(()),
                 }
-                Ok(())
             }
         }
     }
 }
 
+/// a value of the pivot column in `PIVOT (... IN (value [AS alias], ...))`
+#[derive(Clone, PartialEq, Debug)]
+pub struct PivotValue {
+    pub value: Expression,
+    pub alias: Option<String>,
+}
+
+impl Display for PivotValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.alias {
+            Some(alias) => write!(f, "{} AS {}", self.value, alias),
+            None => write!(f, "{}", self.value),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct TableSample {
+    pub method: SampleMethod,
+    /// the percentage of the rows to sample, between 0 and 100
+    pub percent: Expression,
+    /// the seed of the random sampling, the same seed selects the same rows
+    pub seed: Option<Expression>,
+}
+
+impl Display for TableSample {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TABLESAMPLE {} ({})", self.method, self.percent)?;
+        if let Some(seed) = &self.seed {
+            write!(f, " REPEATABLE ({})", seed)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SampleMethod {
+    /// each row is selected with the probability of the percentage
+    Bernoulli,
+    /// each block of rows is selected with the probability of the percentage
+    System,
+}
+
+impl Display for SampleMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SampleMethod::Bernoulli => write!(f, "BERNOULLI"),
+            SampleMethod::System => write!(f, "SYSTEM"),
+        }
+    }
+}
+
+/// how the rows of both sides of a join are matched
+#[derive(Clone, PartialEq, Debug)]
+pub enum JoinConstraint {
+    /// `ON expr`
+    On(Expression),
+    /// `USING (column, ...)`, the listed columns are equal in both sides
+    Using(Vec<Ident>),
+    /// `NATURAL JOIN`, the columns with the same name in both sides are equal
+    Natural,
+    /// no condition, eg. `CROSS JOIN`
+    None,
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub enum JoinType {
     Cross,
@@ -613,6 +1073,10 @@ pub enum JoinType {
     Left,
     Full,
     Right,
+    /// `LEFT SEMI JOIN`, the left rows having a match in the right side
+    LeftSemi,
+    /// `LEFT ANTI JOIN`, the left rows having no match in the right side
+    LeftAnti,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -622,8 +1086,29 @@ pub enum Expression {
     /// multiple identifiers eg. `table.column`
     CompoundIdentifier(Vec<Ident>),
     Literal(Literal),
+    /// positional parameter of a prepared statement, eg. `$1`
+    Placeholder(String),
     BinaryOperator(BinaryOperator),
     Function(String, Vec<Expression>),
+    /// `func(DISTINCT args)`, an aggregate function of the distinct values of its arguments
+    DistinctFunction(String, Vec<Expression>),
+    /// `func(args) WITHIN GROUP (ORDER BY expr)`, an aggregate of the values of the expression in its order
+    WithinGroup {
+        name: String,
+        args: Vec<Expression>,
+        order_by: Box<OrderByExpr>,
+    },
+    /// `func(args) FILTER (WHERE condition)`, an aggregate of the rows satisfying the condition
+    AggregateFilter {
+        function: Box<Expression>,
+        filter: Box<Expression>,
+    },
+    /// `func(args) OVER (PARTITION BY ... ORDER BY ... frame)`
+    WindowFunction {
+        name: String,
+        args: Vec<Expression>,
+        over: WindowSpec,
+    },
     Struct(Vec<StructField>),
     Array(Vec<Expression>),
     /// `(SELECT ...)`
@@ -650,6 +1135,12 @@ pub enum Expression {
         data_type: DataType,
         value: String,
     },
+    /// `INTERVAL 'value' [leading_field [TO last_field]]` e.g. `INTERVAL '1-2' YEAR TO MONTH`
+    Interval {
+        value: String,
+        leading_field: Option<DateTimeField>,
+        last_field: Option<DateTimeField>,
+    },
     /// Extract a field from a timestamp
     Extract {
         field: DateTimeField,
@@ -664,12 +1155,125 @@ pub enum Expression {
         op: UnaryOperator,
         expr: Box<Expression>,
     },
-    /// `[NOT] LIKE <pattern>`
+    /// `[NOT] LIKE <pattern> [ESCAPE <escape_char>]`, `ILIKE` matches case-insensitively
     Like {
         negated: bool,
+        case_insensitive: bool,
         left: Box<Expression>,
         right: Box<Expression>,
+        escape_char: Option<char>,
+    },
+    /// `expr [NOT] BETWEEN low AND high`
+    Between {
+        expr: Box<Expression>,
+        negated: bool,
+        low: Box<Expression>,
+        high: Box<Expression>,
+    },
+    /// `CASE [operand] WHEN condition THEN result ... [ELSE result] END`
+    Case {
+        operand: Option<Box<Expression>>,
+        conditions: Vec<(Expression, Expression)>,
+        else_result: Option<Box<Expression>>,
     },
+    /// `ROLLUP (a, b)` in `GROUP BY`, groups by `(a, b)`, `(a)` and `()`
+    Rollup(Vec<Expression>),
+    /// `CUBE (a, b)` in `GROUP BY`, groups by every subset of the expressions
+    Cube(Vec<Expression>),
+    /// `GROUPING SETS ((a, b), (a), ())` in `GROUP BY`
+    GroupingSets(Vec<Vec<Expression>>),
+}
+
+/// the window specification of a window function, eg. `OVER (PARTITION BY a ORDER BY b ROWS 1 PRECEDING)`
+#[derive(Clone, PartialEq, Debug)]
+pub struct WindowSpec {
+    pub partition_by: Vec<Expression>,
+    pub order_by: Vec<OrderByExpr>,
+    pub window_frame: Option<WindowFrame>,
+}
+
+impl Display for WindowSpec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut clauses = vec![];
+        if !self.partition_by.is_empty() {
+            clauses.push(format!(
+                "PARTITION BY {}",
+                self.partition_by
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ));
+        }
+        if !self.order_by.is_empty() {
+            clauses.push(format!(
+                "ORDER BY {}",
+                self.order_by
+                    .iter()
+                    .map(|o| o.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ));
+        }
+        if let Some(frame) = &self.window_frame {
+            clauses.push(frame.to_string());
+        }
+        write!(f, "{}", clauses.join(" "))
+    }
+}
+
+/// `ROWS | RANGE BETWEEN start_bound AND end_bound`, the end bound is `CURRENT ROW` if omitted
+#[derive(Clone, PartialEq, Debug)]
+pub struct WindowFrame {
+    pub units: WindowFrameUnits,
+    pub start_bound: WindowFrameBound,
+    pub end_bound: Option<WindowFrameBound>,
+}
+
+impl Display for WindowFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.end_bound {
+            Some(end_bound) => write!(f, "{} BETWEEN {} AND {}", self.units, self.start_bound, end_bound),
+            None => write!(f, "{} {}", self.units, self.start_bound),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WindowFrameUnits {
+    Rows,
+    Range,
+}
+
+impl Display for WindowFrameUnits {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowFrameUnits::Rows => write!(f, "ROWS"),
+            WindowFrameUnits::Range => write!(f, "RANGE"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WindowFrameBound {
+    /// `CURRENT ROW`
+    CurrentRow,
+    /// `<N> PRECEDING` or `UNBOUNDED PRECEDING`
+    Preceding(Option<u64>),
+    /// `<N> FOLLOWING` or `UNBOUNDED FOLLOWING`
+    Following(Option<u64>),
+}
+
+impl Display for WindowFrameBound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowFrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+            WindowFrameBound::Preceding(None) => write!(f, "UNBOUNDED PRECEDING"),
+            WindowFrameBound::Preceding(Some(n)) => write!(f, "{} PRECEDING", n),
+            WindowFrameBound::Following(None) => write!(f, "UNBOUNDED FOLLOWING"),
+            WindowFrameBound::Following(Some(n)) => write!(f, "{} FOLLOWING", n),
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -729,10 +1333,20 @@ impl Display for DateTimeField {
     }
 }
 
+/// an operand of an operator, in parentheses if it binds looser than `precedence`
+fn display_operand(expr: &Expression, precedence: u8) -> String {
+    if binding_power(expr).precedence < precedence {
+        format!("({})", expr)
+    } else {
+        expr.to_string()
+    }
+}
+
 impl Display for Expression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Expression::Literal(l) => write!(f, "{}", l),
+            Expression::Placeholder(p) => write!(f, "{}", p),
             Expression::BinaryOperator(o) => write!(f, "{}", o),
             Expression::Function(n, args) => {
                 write!(
@@ -745,12 +1359,50 @@ impl Display for Expression {
                         .join(", ")
                 )
             }
+            Expression::DistinctFunction(n, args) => {
+                write!(
+                    f,
+                    "{}(DISTINCT {})",
+                    n,
+                    args.iter()
+                        .map(|arg| arg.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            }
+            Expression::WithinGroup { name, args, order_by } => {
+                write!(
+                    f,
+                    "{}({}) WITHIN GROUP (ORDER BY {})",
+                    name,
+                    args.iter()
+                        .map(|arg| arg.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    order_by
+                )
+            }
+            Expression::AggregateFilter { function, filter } => {
+                write!(f, "{} FILTER (WHERE {})", function, filter)
+            }
+            Expression::WindowFunction { name, args, over } => {
+                write!(
+                    f,
+                    "{}({}) OVER ({})",
+                    name,
+                    args.iter()
+                        .map(|arg| arg.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    over
+                )
+            }
             Expression::InList { field, list, negated } => {
                 write!(
                     f,
-                    "{} {} IN ({})",
-                    field,
-                    if *negated { "NOT" } else { "" },
+                    "{} {}IN ({})",
+                    display_operand(field, binding_power(self).left),
+                    if *negated { "NOT " } else { "" },
                     list.iter()
                         .map(|arg| arg.to_string())
                         .collect::<Vec<String>>()
@@ -758,14 +1410,20 @@ impl Display for Expression {
                 )
             }
             Expression::InSubQuery { field, query, negated } => {
-                write!(f, "{} {} IN ({})", field, if *negated { "NOT" } else { "" }, query)
+                write!(
+                    f,
+                    "{} {}IN ({})",
+                    display_operand(field, binding_power(self).left),
+                    if *negated { "NOT " } else { "" },
+                    query
+                )
             }
             Expression::Identifier(i) => write!(f, "{}", i),
             Expression::Struct(s) => write!(
                 f,
-                "({})",
+                "{{{}}}",
                 s.iter()
-                    .map(|field| format!("{} = {}", field.name, field.value))
+                    .map(|field| format!("{}: {}", field.name, field.value))
                     .collect::<Vec<String>>()
                     .join(", ")
             ),
@@ -785,24 +1443,110 @@ impl Display for Expression {
             Expression::TypedString { data_type, value } => {
                 write!(f, "{} '{}'", data_type, value)
             }
+            Expression::Interval {
+                value,
+                leading_field,
+                last_field,
+            } => {
+                write!(f, "INTERVAL '{}'", value)?;
+                if let Some(leading_field) = leading_field {
+                    write!(f, " {}", leading_field)?;
+                }
+                if let Some(last_field) = last_field {
+                    write!(f, " TO {}", last_field)?;
+                }
+                Ok(())
+            }
             Expression::Extract { field, expr } => {
                 write!(f, "EXTRACT({} FROM {})", field, expr)
             }
-            Expression::IsNull(expression) => write!(f, "{} IS NULL", expression),
-            Expression::IsNotNull(expression) => write!(f, "{} IS NOT NULL", expression),
+            Expression::IsNull(expression) => {
+                write!(f, "{} IS NULL", display_operand(expression, binding_power(self).left))
+            }
+            Expression::IsNotNull(expression) => write!(
+                f,
+                "{} IS NOT NULL",
+                display_operand(expression, binding_power(self).left)
+            ),
+            // a nested sign is in parentheses too, `--a` would be a comment
             Expression::UnaryOperator { op, expr } => {
-                write!(f, "{}{}", op, expr)
+                write!(f, "{}{}", op, display_operand(expr, binding_power(self).right))
             }
             Expression::SubQuery(select) => write!(f, "({})", select),
-            Expression::Like { negated, left, right } => {
+            Expression::Like {
+                negated,
+                case_insensitive,
+                left,
+                right,
+                escape_char,
+            } => {
+                write!(
+                    f,
+                    "{} {}{} {}",
+                    display_operand(left, binding_power(self).left),
+                    if *negated { "NOT " } else { "" },
+                    if *case_insensitive { "ILIKE" } else { "LIKE" },
+                    display_operand(right, binding_power(self).right)
+                )?;
+                if let Some(escape_char) = escape_char {
+                    write!(f, " ESCAPE '{}'", escape_char)?;
+                }
+                Ok(())
+            }
+            Expression::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                let power = binding_power(self);
                 write!(
                     f,
-                    "{} {} LIKE {}",
-                    left,
-                    if *negated { "NOT" } else { "" },
-                    right
+                    "{} {}BETWEEN {} AND {}",
+                    display_operand(expr, power.left),
+                    if *negated { "NOT " } else { "" },
+                    display_operand(low, power.right),
+                    display_operand(high, power.right)
                 )
             }
+            Expression::Case {
+                operand,
+                conditions,
+                else_result,
+            } => {
+                write!(f, "CASE")?;
+                if let Some(operand) = operand {
+                    write!(f, " {}", operand)?;
+                }
+                for (when, then) in conditions {
+                    write!(f, " WHEN {} THEN {}", when, then)?;
+                }
+                if let Some(else_result) = else_result {
+                    write!(f, " ELSE {}", else_result)?;
+                }
+                write!(f, " END")
+            }
+            Expression::Rollup(exprs) => write!(
+                f,
+                "ROLLUP ({})",
+                exprs.iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", ")
+            ),
+            Expression::Cube(exprs) => write!(
+                f,
+                "CUBE ({})",
+                exprs.iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", ")
+            ),
+            Expression::GroupingSets(sets) => write!(
+                f,
+                "GROUPING SETS ({})",
+                sets.iter()
+                    .map(|set| format!(
+                        "({})",
+                        set.iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", ")
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
         }
     }
 }
@@ -832,6 +1576,13 @@ where
     }
 }
 
+impl ObjectName {
+    /// the parts of the name joined by `.`, without their quotes
+    pub fn value(&self) -> String {
+        self.0.iter().map(|i| i.value.as_str()).collect::<Vec<_>>().join(".")
+    }
+}
+
 impl Display for ObjectName {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -876,8 +1627,10 @@ impl Display for Literal {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Literal::Int(i) => write!(f, "{}", i),
+            // keep the fraction so the literal is read back as a float
+            Literal::Float(fl) if fl.is_finite() && fl.fract() == 0.0 => write!(f, "{:.1}", fl),
             Literal::Float(fl) => write!(f, "{}", fl),
-            Literal::String(s) => write!(f, "{}", s),
+            Literal::String(s) => write!(f, "'{}'", s.replace('\'', "''")),
             Literal::Boolean(b) => write!(f, "{}", b),
             Literal::Null => write!(f, "null"),
         }
@@ -951,29 +1704,48 @@ pub enum BinaryOperator {
     Gte(Box<Expression>, Box<Expression>),
     Lt(Box<Expression>, Box<Expression>),
     Lte(Box<Expression>, Box<Expression>),
+    /// `IS DISTINCT FROM`, NULLs are compared as values
+    IsDistinctFrom(Box<Expression>, Box<Expression>),
+    /// `IS NOT DISTINCT FROM`, the null-safe equality
+    IsNotDistinctFrom(Box<Expression>, Box<Expression>),
 
     // Mathematical
     Add(Box<Expression>, Box<Expression>),
     Sub(Box<Expression>, Box<Expression>),
     Mul(Box<Expression>, Box<Expression>),
     Div(Box<Expression>, Box<Expression>),
+
+    // String
+    /// `a || b`
+    StringConcat(Box<Expression>, Box<Expression>),
 }
 
 impl Display for BinaryOperator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            BinaryOperator::Sub(l, r) => write!(f, "{} - {}", l, r),
-            BinaryOperator::Add(l, r) => write!(f, "{} + {}", l, r),
-            BinaryOperator::Mul(l, r) => write!(f, "{} * {}", l, r),
-            BinaryOperator::Div(l, r) => write!(f, "{} / {}", l, r),
-            BinaryOperator::Eq(l, r) => write!(f, "{} = {}", l, r),
-            BinaryOperator::NotEq(l, r) => write!(f, "{} != {}", l, r),
-            BinaryOperator::And(l, r) => write!(f, "{} AND {}", l, r),
-            BinaryOperator::Or(l, r) => write!(f, "{} OR {}", l, r),
-            BinaryOperator::Gt(l, r) => write!(f, "{} > {}", l, r),
-            BinaryOperator::Gte(l, r) => write!(f, "{} >= {}", l, r),
-            BinaryOperator::Lt(l, r) => write!(f, "{} < {}", l, r),
-            BinaryOperator::Lte(l, r) => write!(f, "{} <= {}", l, r),
-        }
+        let (l, op, r) = match self {
+            BinaryOperator::Sub(l, r) => (l, "-", r),
+            BinaryOperator::Add(l, r) => (l, "+", r),
+            BinaryOperator::Mul(l, r) => (l, "*", r),
+            BinaryOperator::Div(l, r) => (l, "/", r),
+            BinaryOperator::Eq(l, r) => (l, "=", r),
+            BinaryOperator::NotEq(l, r) => (l, "!=", r),
+            BinaryOperator::And(l, r) => (l, "AND", r),
+            BinaryOperator::Or(l, r) => (l, "OR", r),
+            BinaryOperator::Gt(l, r) => (l, ">", r),
+            BinaryOperator::Gte(l, r) => (l, ">=", r),
+            BinaryOperator::Lt(l, r) => (l, "<", r),
+            BinaryOperator::Lte(l, r) => (l, "<=", r),
+            BinaryOperator::IsDistinctFrom(l, r) => (l, "IS DISTINCT FROM", r),
+            BinaryOperator::IsNotDistinctFrom(l, r) => (l, "IS NOT DISTINCT FROM", r),
+            BinaryOperator::StringConcat(l, r) => (l, "||", r),
+        };
+        let power = BindingPower::from(self);
+        write!(
+            f,
+            "{} {} {}",
+            display_operand(l, power.left),
+            op,
+            display_operand(r, power.right)
+        )
     }
 }
@@ -10,24 +10,35 @@ pub enum DataType {
     Date,
     Timestamp,
     Decimal(Option<u8>, Option<i8>),
+    Int8,
     Int16,
     Int64,
+    Float32,
+    Time,
 }
 
 impl Display for DataType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            DataType::Boolean => write!(f, "Boolean"),
-            DataType::Integer => write!(f, "Integer"),
-            DataType::Float => write!(f, "Float"),
-            DataType::String => write!(f, "String"),
-            DataType::Date => write!(f, "Date"),
-            DataType::Timestamp => write!(f, "Timestamp"),
-            DataType::Int16 => write!(f, "Int16"),
+            DataType::Boolean => write!(f, "BOOLEAN"),
+            DataType::Integer => write!(f, "INTEGER"),
+            DataType::Float => write!(f, "DOUBLE"),
+            DataType::String => write!(f, "VARCHAR"),
+            DataType::Date => write!(f, "DATE"),
+            DataType::Timestamp => write!(f, "TIMESTAMP"),
+            DataType::Int8 => write!(f, "TINYINT"),
+            DataType::Int16 => write!(f, "SMALLINT"),
             DataType::Decimal(precision, scale) => {
-                write!(f, "Decimal({:?}, {:?})", precision, scale)
+                write!(f, "DECIMAL")?;
+                match (precision, scale) {
+                    (Some(precision), Some(scale)) => write!(f, "({}, {})", precision, scale),
+                    (Some(precision), None) => write!(f, "({})", precision),
+                    _ => Ok(()),
+                }
             }
-            DataType::Int64 => write!(f, "Int64"),
+            DataType::Int64 => write!(f, "BIGINT"),
+            DataType::Float32 => write!(f, "REAL"),
+            DataType::Time => write!(f, "TIME"),
         }
     }
 }
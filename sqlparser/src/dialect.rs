@@ -0,0 +1,115 @@
+use std::fmt::Debug;
+
+use crate::token::Keyword;
+
+/// SQL dialect, controls the dialect specific parts of lexing and parsing. The default methods are the generic
+/// dialect, a dialect overrides the syntax it does not share with it.
+pub trait Dialect: Debug + Send + Sync {
+    /// The name of the dialect, lower case, like `mysql`
+    fn name(&self) -> &'static str;
+
+    /// Whether `ch` opens a quoted identifier in this dialect
+    fn is_delimited_identifier_start(&self, ch: char) -> bool {
+        ch == '"'
+    }
+
+    /// Whether `ch` opens a string literal in this dialect
+    fn is_string_literal_start(&self, ch: char) -> bool {
+        ch == '\''
+    }
+
+    /// Whether `keyword` is a keyword of this dialect, the other keywords are lexed as plain identifiers
+    fn is_keyword(&self, _keyword: &Keyword) -> bool {
+        true
+    }
+
+    /// Whether `a || b` concatenates strings, in MySQL it is a synonym of `OR`
+    fn pipes_as_concat(&self) -> bool {
+        true
+    }
+
+    /// Whether `expr::type` casts, MySQL has no such operator
+    fn supports_double_colon_cast(&self) -> bool {
+        true
+    }
+
+    /// Whether `LEFT SEMI JOIN` and `LEFT ANTI JOIN` are accepted, PostgreSQL and MySQL have no such joins
+    fn supports_semi_anti_join(&self) -> bool {
+        true
+    }
+}
+
+/// ANSI SQL, identifiers are quoted with double quotes: `"name"`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GenericDialect;
+
+impl Dialect for GenericDialect {
+    fn name(&self) -> &'static str {
+        "generic"
+    }
+}
+
+/// PostgreSQL, the generic dialect without the syntax PostgreSQL does not have, eg. `PIVOT`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PostgreSqlDialect;
+
+impl Dialect for PostgreSqlDialect {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn is_keyword(&self, keyword: &Keyword) -> bool {
+        !matches!(keyword, Keyword::Pivot | Keyword::Unpivot)
+    }
+
+    fn supports_semi_anti_join(&self) -> bool {
+        false
+    }
+}
+
+/// MySQL, identifiers are quoted with backticks: `` `name` ``, a double quoted `"text"` is a string literal
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MySqlDialect;
+
+impl Dialect for MySqlDialect {
+    fn name(&self) -> &'static str {
+        "mysql"
+    }
+
+    fn is_delimited_identifier_start(&self, ch: char) -> bool {
+        ch == '`'
+    }
+
+    fn is_string_literal_start(&self, ch: char) -> bool {
+        ch == '\'' || ch == '"'
+    }
+
+    fn is_keyword(&self, keyword: &Keyword) -> bool {
+        !matches!(
+            keyword,
+            Keyword::Pivot | Keyword::Unpivot | Keyword::Tablesample | Keyword::ILike
+        )
+    }
+
+    fn pipes_as_concat(&self) -> bool {
+        false
+    }
+
+    fn supports_double_colon_cast(&self) -> bool {
+        false
+    }
+
+    fn supports_semi_anti_join(&self) -> bool {
+        false
+    }
+}
+
+/// The dialect of the name, case insensitive, `None` for an unknown dialect
+pub fn dialect_from_str(name: &str) -> Option<Box<dyn Dialect>> {
+    match name.to_ascii_lowercase().as_str() {
+        "generic" => Some(Box::new(GenericDialect)),
+        "postgres" | "postgresql" => Some(Box::new(PostgreSqlDialect)),
+        "mysql" => Some(Box::new(MySqlDialect)),
+        _ => None,
+    }
+}
@@ -1,26 +1,29 @@
+use crate::token::{Location, Token, TokenType};
+
 macro_rules! generate_error_enum {
     ($($variant:ident => $reason:expr),*) => {
         #[derive(Debug)]
         pub enum Error {
-            $($variant(crate::token::Token)),*,
-            ParseIntError(std::num::ParseIntError,crate::token::Token),
-            ParseFloatError(std::num::ParseFloatError,crate::token::Token),
+            $($variant(Token)),*,
+            ParseIntError(std::num::ParseIntError,Token),
+            ParseFloatError(std::num::ParseFloatError,Token),
             DuplicateColumn(String),
             UnKnownInfixOperator(String),
             ParserError(String),
+            Syntax(Box<ParserError>),
         }
 
         impl std::fmt::Display for Error {
             fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
                 match self {
                     $(Error::$variant(token) => {
-                        write!(f, "error: {} line: {} column: {}", $reason, token.location.line, token.location.column)
+                        write!(f, "error: {}", ParserError::new($reason, token.clone()))
                     })*
                     Error::ParseIntError(e, token) => {
-                        write!(f, "error: {} line: {} column: {}", e, token.location.line, token.location.column)
+                        write!(f, "error: {}", ParserError::new(e.to_string(), token.clone()))
                     }
                     Error::ParseFloatError(e, token) => {
-                        write!(f, "error: {} line: {} column: {}", e, token.location.line, token.location.column)
+                        write!(f, "error: {}", ParserError::new(e.to_string(), token.clone()))
                     }
                     Error::DuplicateColumn(column) => {
                         write!(f, "error: duplicate column: {}", column)
@@ -31,6 +34,21 @@ macro_rules! generate_error_enum {
                     Error::ParserError(msg) => {
                         write!(f, "error: {}", msg)
                     }
+                    Error::Syntax(e) => {
+                        write!(f, "error: {}", e)
+                    }
+                }
+            }
+        }
+
+        impl Error {
+            /// the offending token of a syntax error
+            pub fn token(&self) -> Option<&Token> {
+                match self {
+                    $(Error::$variant(token))|* => Some(token),
+                    Error::ParseIntError(_, token) | Error::ParseFloatError(_, token) => Some(token),
+                    Error::Syntax(e) => Some(&e.token),
+                    _ => None,
                 }
             }
         }
@@ -45,6 +63,99 @@ generate_error_enum! {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// A syntax error at a token of the source, displayed with the source line and a caret under the token:
+///
+/// ```text
+/// unexpected token `FROM` at line 1, column 8, expected one of: identifier, `*`
+/// SELECT FROM t
+///        ^^^^
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParserError {
+    pub message: String,
+    /// the offending token, its location carries the line of the source
+    pub token: Token,
+    /// the token types which would have been valid instead of the token
+    pub expected: Vec<TokenType>,
+}
+
+impl ParserError {
+    pub fn new(message: impl Into<String>, token: Token) -> Self {
+        Self {
+            message: message.into(),
+            token,
+            expected: vec![],
+        }
+    }
+
+    /// the error of an unexpected token where one of `expected` was valid
+    pub fn expected(token: Token, expected: Vec<TokenType>) -> Self {
+        let message = match token.token_type {
+            TokenType::EOF => "unexpected EOF",
+            _ => "unexpected token",
+        };
+        Self {
+            message: message.to_owned(),
+            token,
+            expected,
+        }
+    }
+
+    /// the line of the token, starting at 1
+    pub fn line(&self) -> usize {
+        self.token.location.line + 1
+    }
+
+    /// the column of the first char of the token, starting at 1
+    pub fn column(&self) -> usize {
+        self.token.location.column + 1
+    }
+}
+
+impl From<ParserError> for Error {
+    fn from(e: ParserError) -> Self {
+        Error::Syntax(Box::new(e))
+    }
+}
+
+impl std::fmt::Display for ParserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if self.token.token_type != TokenType::EOF {
+            write!(f, " `{}`", self.token.literal)?;
+        }
+        write!(f, " at line {}, column {}", self.line(), self.column())?;
+        match self.expected.as_slice() {
+            [] => {}
+            [expected] => write!(f, ", expected {}", expected)?,
+            expected => write!(
+                f,
+                ", expected one of: {}",
+                expected.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ")
+            )?,
+        }
+
+        // the caret is under the chars of the token on its first line, tabs are kept to stay aligned
+        let Location { line_str, column, .. } = &self.token.location;
+        let len = self.token.span.end - self.token.span.start;
+        let indent = line_str
+            .chars()
+            .take(*column)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let width = line_str
+            .chars()
+            .skip(*column)
+            .scan(0, |bytes, c| {
+                *bytes += c.len_utf8();
+                (*bytes <= len).then_some(())
+            })
+            .count()
+            .max(1);
+        write!(f, "\n{}\n{}{}", line_str, indent, "^".repeat(width))
+    }
+}
+
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -1,46 +1,97 @@
-use std::{iter::Peekable, str::Chars};
+use std::{iter::Peekable, str::CharIndices};
 
-use crate::token::{Location, Token, TokenType};
+use crate::{
+    dialect::{Dialect, GenericDialect},
+    error::{Error, Result},
+    token::{Location, Span, Token, TokenType},
+};
 
 const EMPTY_CHAR: char = '\0';
 
 pub struct Lexer<'a> {
-    peekable: Peekable<Chars<'a>>,
+    dialect: &'a dyn Dialect,
+    input: &'a str,
+    peekable: Peekable<CharIndices<'a>>,
     peeked: Option<Token>,
     lines: Vec<&'a str>,
     cur_line: usize,
+    /// column of `cur_ch` in the current line, reset on every new line
     cur_pos: usize,
     cur_ch: char,
+    /// byte offset of `cur_ch` in the input
+    cur_offset: usize,
+    /// span of the last token returned by `next_token`
+    last_span: Option<Span>,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(input: &'a str) -> Self {
+        Self::with_dialect(input, &GenericDialect)
+    }
+
+    pub fn with_dialect(input: &'a str, dialect: &'a dyn Dialect) -> Self {
         let lines = input.lines().collect();
-        let mut peekable = input.chars().peekable();
+        let mut peekable = input.char_indices().peekable();
+        let (cur_offset, cur_ch) = peekable.next().unwrap_or((input.len(), EMPTY_CHAR));
         Lexer {
-            cur_ch: peekable.next().unwrap_or(EMPTY_CHAR),
+            dialect,
+            input,
+            cur_ch,
+            cur_offset,
             peekable,
             lines,
             peeked: None,
             cur_line: 0,
             cur_pos: 0,
+            last_span: None,
         }
     }
 
+    pub fn dialect(&self) -> &'a dyn Dialect {
+        self.dialect
+    }
+
     pub fn peek(&mut self) -> Option<&Token> {
         if self.peeked.is_none() {
-            self.peeked = Some(self.next());
+            self.peeked = Some(self.read_next_token());
         }
         self.peeked.as_ref()
     }
 
-    pub fn next(&mut self) -> Token {
-        if let Some(tok) = self.peeked.take() {
-            return tok;
+    /// Returns the next token. Illegal characters are reported as [`TokenType::ILLIGAL`] tokens
+    /// and lexing resumes right after them, so callers can keep going and collect more errors.
+    pub fn next_token(&mut self) -> Token {
+        let tok = match self.peeked.take() {
+            Some(tok) => tok,
+            None => self.read_next_token(),
+        };
+        self.last_span = Some(tok.span);
+        tok
+    }
+
+    /// Puts back the last token returned by `next_token`, so it is returned again. False if `token` is not that
+    /// token or a token was peeked since.
+    pub fn push_back(&mut self, token: Token) -> bool {
+        if self.peeked.is_some() || self.last_span != Some(token.span) {
+            return false;
         }
+        self.last_span = None;
+        self.peeked = Some(token);
+        true
+    }
 
+    fn read_next_token(&mut self) -> Token {
         self.skip();
 
+        let start = self.cur_offset;
+        let location = self.location();
+        let mut tok = self.read_token();
+        tok.span = Span::new(start, self.cur_offset.max(start));
+        tok.location = location;
+        tok
+    }
+
+    fn read_token(&mut self) -> Token {
         let literal = char::from(self.cur_ch).to_string();
         let tok = match self.cur_ch {
             EMPTY_CHAR => Token::new(TokenType::EOF, "".to_owned(), self.location()),
@@ -71,13 +122,20 @@ impl<'a> Lexer<'a> {
             }
             ';' => Token::new(TokenType::Semicolon, literal, self.location()),
             ':' => {
-                self.read_char();
-                if self.cur_ch == ':' {
+                if self.peek_char() == &':' {
                     self.read_char();
-
-                    return Token::new(TokenType::DoubleColon, "::".to_owned(), self.location());
+                    Token::new(TokenType::DoubleColon, "::".to_owned(), self.location())
+                } else {
+                    Token::new(TokenType::Colon, literal, self.location())
+                }
+            }
+            '|' => {
+                if self.peek_char() == &'|' {
+                    self.read_char();
+                    Token::new(TokenType::DoublePipe, "||".to_owned(), self.location())
+                } else {
+                    Token::new(TokenType::ILLIGAL, literal, self.location())
                 }
-                Token::new(TokenType::Colon, literal, self.location())
             }
             '.' => Token::new(TokenType::Period, literal, self.location()),
             '(' => Token::new(TokenType::LParen, literal, self.location()),
@@ -88,26 +146,22 @@ impl<'a> Lexer<'a> {
             '[' => Token::new(TokenType::LSquareBrace, literal, self.location()),
             ']' => Token::new(TokenType::RSquareBrace, literal, self.location()),
             '}' => Token::new(TokenType::RBrace, literal, self.location()),
-            '-' => {
-                if self.peek_char() == &'-' {
-                    while self.cur_ch != '\n' && self.cur_ch != EMPTY_CHAR {
-                        self.read_char();
-                    }
-                    return self.next();
-                }
-
-                Token::new(TokenType::Minus, literal, self.location())
-            }
+            '-' => Token::new(TokenType::Minus, literal, self.location()),
             '*' => Token::new(TokenType::Asterisk, literal, self.location()),
             '/' => Token::new(TokenType::Slash, literal, self.location()),
             '?' => Token::new(TokenType::Question, literal, self.location()),
-            '\'' => {
+            q if self.dialect.is_string_literal_start(q) => {
                 let mut s = String::new();
                 loop {
                     self.read_char();
                     match self.cur_ch {
-                        '\'' => {
-                            break;
+                        c if c == q => {
+                            // a doubled quote is an escaped quote
+                            if self.peek_char() != &q {
+                                break;
+                            }
+                            self.read_char();
+                            s.push(q);
                         }
                         EMPTY_CHAR => return Token::new(TokenType::ILLIGAL, literal, self.location()),
                         _ => {
@@ -117,11 +171,55 @@ impl<'a> Lexer<'a> {
                 }
                 Token::new(TokenType::String, s, self.location())
             }
-            b if b.is_ascii_alphabetic() => {
+            q if self.dialect.is_delimited_identifier_start(q) => {
+                let mut s = String::new();
+                loop {
+                    self.read_char();
+                    match self.cur_ch {
+                        c if c == q => {
+                            // a doubled quote is an escaped quote
+                            if self.peek_char() != &q {
+                                break;
+                            }
+                            self.read_char();
+                            s.push(q);
+                        }
+                        EMPTY_CHAR => return Token::new(TokenType::ILLIGAL, literal, self.location()),
+                        c => s.push(c),
+                    }
+                }
+                Token::new(TokenType::QuotedIdent(q), s, self.location())
+            }
+            b if is_identifier_start(b) => {
+                // escape string literal: E'...'
+                if matches!(b, 'e' | 'E') && self.peek_char() == &'\'' {
+                    self.read_char();
+                    return self.read_escape_string();
+                }
+
                 let literal = self.read_literal();
-                let token_type = TokenType::lookup_ident(&literal);
+                // keywords are ASCII only, so a non-ASCII identifier is never a keyword
+                let token_type = match TokenType::lookup_ident(&literal) {
+                    TokenType::Keyword(keyword) if literal.is_ascii() && self.dialect.is_keyword(&keyword) => {
+                        TokenType::Keyword(keyword)
+                    }
+                    _ => TokenType::Ident,
+                };
                 return Token::new(token_type, literal, self.location());
             }
+            // positional parameter of a prepared statement: $1, $2, ...
+            '$' => {
+                if self.peek_char().is_ascii_digit() {
+                    self.read_char();
+                    let mut placeholder = literal;
+                    while self.cur_ch.is_ascii_digit() {
+                        placeholder.push(self.cur_ch);
+                        self.read_char();
+                    }
+                    return Token::new(TokenType::Placeholder, placeholder, self.location());
+                }
+                Token::new(TokenType::ILLIGAL, literal, self.location())
+            }
             b if b.is_ascii_digit() => {
                 let number = self.read_number();
                 if number.contains('.') {
@@ -145,23 +243,30 @@ impl<'a> Lexer<'a> {
     }
 
     fn read_char(&mut self) {
-        if let Some(ch) = self.peekable.next() {
-            self.cur_ch = ch;
-        } else {
+        let Some((offset, ch)) = self.peekable.next() else {
+            // EOF is located just after the last char
+            if self.cur_ch != EMPTY_CHAR {
+                self.cur_pos += 1;
+            }
+            self.cur_offset = self.input.len();
             self.cur_ch = EMPTY_CHAR;
             return;
-        }
+        };
 
         if self.cur_ch == '\n' {
             self.cur_line += 1;
+            self.cur_pos = 0;
+        } else {
+            self.cur_pos += 1;
         }
 
-        self.cur_pos += 1;
+        self.cur_offset = offset;
+        self.cur_ch = ch;
     }
 
     fn read_literal(&mut self) -> String {
         let mut literal = String::new();
-        while self.cur_ch.is_ascii_alphabetic() || self.cur_ch.is_ascii_alphanumeric() || self.cur_ch == '_' {
+        while is_identifier_part(self.cur_ch) {
             literal.push(self.cur_ch);
             self.read_char();
         }
@@ -169,6 +274,63 @@ impl<'a> Lexer<'a> {
         literal
     }
 
+    /// read an escape string literal, `cur_ch` is the opening quote.
+    /// Supports `\b \f \n \r \t \\ \' \xHH \uXXXX \UXXXXXXXX`, any other escaped char is taken as is.
+    fn read_escape_string(&mut self) -> Token {
+        let mut s = String::new();
+        loop {
+            self.read_char();
+            match self.cur_ch {
+                '\'' => {
+                    // a doubled quote is an escaped quote
+                    if self.peek_char() != &'\'' {
+                        break;
+                    }
+                    self.read_char();
+                    s.push('\'');
+                }
+                '\\' => {
+                    self.read_char();
+                    let ch = match self.cur_ch {
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'x' => self.read_hex_escape(2),
+                        'u' => self.read_hex_escape(4),
+                        'U' => self.read_hex_escape(8),
+                        c => c,
+                    };
+                    if ch == EMPTY_CHAR {
+                        return Token::new(TokenType::ILLIGAL, s, self.location());
+                    }
+                    s.push(ch);
+                }
+                EMPTY_CHAR => return Token::new(TokenType::ILLIGAL, s, self.location()),
+                c => s.push(c),
+            }
+        }
+        let tok = Token::new(TokenType::String, s, self.location());
+        self.read_char();
+        tok
+    }
+
+    /// read `len` hex digits after an escape, returns [`EMPTY_CHAR`] if it's not a valid code point
+    fn read_hex_escape(&mut self, len: usize) -> char {
+        let mut code = String::with_capacity(len);
+        while code.len() < len && self.peek_char().is_ascii_hexdigit() {
+            self.read_char();
+            code.push(self.cur_ch);
+        }
+
+        u32::from_str_radix(&code, 16)
+            .ok()
+            .filter(|_| code.len() == len)
+            .and_then(char::from_u32)
+            .unwrap_or(EMPTY_CHAR)
+    }
+
     fn read_number(&mut self) -> String {
         let mut number = String::new();
         while self.cur_ch.is_ascii_digit() || self.cur_ch == '.' {
@@ -182,6 +344,14 @@ impl<'a> Lexer<'a> {
     fn skip(&mut self) {
         loop {
             match self.cur_ch {
+                '-' => {
+                    if self.peek_char() != &'-' {
+                        return;
+                    }
+                    while self.cur_ch != '\n' && self.cur_ch != EMPTY_CHAR {
+                        self.read_char();
+                    }
+                }
                 a if a.is_whitespace() => {
                     self.read_char();
                 }
@@ -190,7 +360,7 @@ impl<'a> Lexer<'a> {
                 }
                 '\r' => {
                     self.read_char();
-                    if let Some('\n') = self.peekable.peek() {
+                    if let Some((_, '\n')) = self.peekable.peek() {
                         self.read_char();
                     }
                 }
@@ -200,20 +370,51 @@ impl<'a> Lexer<'a> {
     }
 
     fn peek_char(&mut self) -> &char {
-        self.peekable.peek().unwrap_or(&EMPTY_CHAR)
+        self.peekable.peek().map(|(_, ch)| ch).unwrap_or(&EMPTY_CHAR)
     }
 }
 
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    /// Yields tokens until the end of input, the EOF token itself is not returned.
+    fn next(&mut self) -> Option<Self::Item> {
+        let tok = self.next_token();
+        (tok.token_type != TokenType::EOF).then_some(tok)
+    }
+}
+
+/// Tokenize the whole input, fails on the first illegal token.
+pub fn tokenize(input: &str) -> Result<Vec<Token>> {
+    Lexer::new(input)
+        .map(|tok| match tok.token_type {
+            TokenType::ILLIGAL => Err(Error::UnexpectedToken(tok)),
+            _ => Ok(tok),
+        })
+        .collect()
+}
+
+/// An identifier starts with a letter (XID_Start) or an underscore.
+fn is_identifier_start(ch: char) -> bool {
+    ch.is_alphabetic() || ch == '_'
+}
+
+/// The rest of an identifier may contain letters, digits (XID_Continue) or underscores.
+fn is_identifier_part(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dialect::MySqlDialect;
     use crate::token::{Keyword, TokenType};
 
     #[test]
     fn test_float() {
         let input = "1.23";
         let mut l = Lexer::new(input);
-        let tok = l.next();
+        let tok = l.next_token();
         assert_eq!(tok.token_type, TokenType::Float);
         assert_eq!(tok.literal, "1.23");
     }
@@ -241,7 +442,7 @@ mod tests {
         ];
         let mut l = Lexer::new(input);
         for (expected_type, expected_literal) in tests {
-            let tok = l.next();
+            let tok = l.next_token();
             assert_eq!(tok.token_type, expected_type);
             assert_eq!(tok.literal, expected_literal);
         }
@@ -249,18 +450,35 @@ mod tests {
 
     #[test]
     fn test_two_char_token() {
-        let input = "=!=<=>=::";
+        let input = "=!=<=>=::||";
         let tests = vec![
             (TokenType::Eq, "="),
             (TokenType::NotEq, "!="),
             (TokenType::Lte, "<="),
             (TokenType::Gte, ">="),
             (TokenType::DoubleColon, "::"),
+            (TokenType::DoublePipe, "||"),
             (TokenType::EOF, ""),
         ];
         let mut l = Lexer::new(input);
         for (expected_type, expected_literal) in tests {
-            let tok = l.next();
+            let tok = l.next_token();
+            assert_eq!(tok.token_type, expected_type);
+            assert_eq!(tok.literal, expected_literal);
+        }
+    }
+
+    #[test]
+    fn test_placeholder() {
+        let mut l = Lexer::new("$1 = $12");
+        let tests = vec![
+            (TokenType::Placeholder, "$1"),
+            (TokenType::Eq, "="),
+            (TokenType::Placeholder, "$12"),
+            (TokenType::EOF, ""),
+        ];
+        for (expected_type, expected_literal) in tests {
+            let tok = l.next_token();
             assert_eq!(tok.token_type, expected_type);
             assert_eq!(tok.literal, expected_literal);
         }
@@ -299,7 +517,7 @@ mod tests {
         ];
         let mut l = Lexer::new(input);
         for (expected_type, expected_literal) in tests {
-            let tok = l.next();
+            let tok = l.next_token();
             assert_eq!(tok.token_type, expected_type);
             assert_eq!(tok.literal, expected_literal);
         }
@@ -319,9 +537,168 @@ mod tests {
         ];
         let mut l = Lexer::new(input);
         for (expected_type, expected_literal) in expected {
-            let tok = l.next();
+            let tok = l.next_token();
+            assert_eq!(tok.token_type, expected_type);
+            assert_eq!(tok.literal, expected_literal);
+        }
+    }
+
+    #[test]
+    fn test_unicode_identifier() {
+        let input = "SELECT 名前, prénom FROM 表_1";
+        let expected = vec![
+            (TokenType::Keyword(Keyword::Select), "SELECT"),
+            (TokenType::Ident, "名前"),
+            (TokenType::Comma, ","),
+            (TokenType::Ident, "prénom"),
+            (TokenType::Keyword(Keyword::From), "FROM"),
+            (TokenType::Ident, "表_1"),
+            (TokenType::EOF, ""),
+        ];
+        let mut l = Lexer::new(input);
+        for (expected_type, expected_literal) in expected {
+            let tok = l.next_token();
             assert_eq!(tok.token_type, expected_type);
             assert_eq!(tok.literal, expected_literal);
         }
     }
+
+    #[test]
+    fn test_column_per_line() {
+        let input = "SELECT a\nFROM t";
+        let mut l = Lexer::new(input);
+        let tokens = (0..4).map(|_| l.next_token()).collect::<Vec<_>>();
+
+        assert_eq!(tokens[2].literal, "FROM");
+        assert_eq!(tokens[2].location.line, 1);
+        assert_eq!(tokens[2].location.line_str, "FROM t");
+        assert_eq!(tokens[3].location.line, 1);
+        assert_eq!(tokens[3].location.column, 5);
+    }
+
+    #[test]
+    fn test_token_span() {
+        let input = "SELECT prénom, 'abc' -- comment\n FROM t::int";
+        let expected = vec![
+            ("SELECT", Span::new(0, 6)),
+            ("prénom", Span::new(7, 14)),
+            (",", Span::new(14, 15)),
+            ("abc", Span::new(16, 21)),
+            ("FROM", Span::new(34, 38)),
+            ("t", Span::new(39, 40)),
+            ("::", Span::new(40, 42)),
+            ("int", Span::new(42, 45)),
+            ("", Span::new(45, 45)),
+        ];
+        let mut l = Lexer::new(input);
+        for (expected_literal, expected_span) in expected {
+            let tok = l.next_token();
+            assert_eq!(tok.literal, expected_literal);
+            assert_eq!(tok.span, expected_span);
+            assert_eq!(
+                &input[tok.span.start..tok.span.end].trim_matches('\''),
+                &expected_literal
+            );
+        }
+    }
+
+    #[test]
+    fn test_continue_past_illegal_char() {
+        let input = "SELECT # a, $ b";
+        let expected = vec![
+            (TokenType::Keyword(Keyword::Select), "SELECT"),
+            (TokenType::ILLIGAL, "#"),
+            (TokenType::Ident, "a"),
+            (TokenType::Comma, ","),
+            (TokenType::ILLIGAL, "$"),
+            (TokenType::Ident, "b"),
+            (TokenType::EOF, ""),
+        ];
+        let mut l = Lexer::new(input);
+        for (expected_type, expected_literal) in expected {
+            let tok = l.next_token();
+            assert_eq!(tok.token_type, expected_type);
+            assert_eq!(tok.literal, expected_literal);
+        }
+    }
+
+    #[test]
+    fn test_iterator() {
+        let literals = Lexer::new("SELECT a FROM t;")
+            .map(|tok| tok.literal)
+            .collect::<Vec<_>>();
+        assert_eq!(literals, vec!["SELECT", "a", "FROM", "t", ";"]);
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("SELECT 1 + 2").unwrap();
+        let types = tokens.into_iter().map(|tok| tok.token_type).collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                TokenType::Keyword(Keyword::Select),
+                TokenType::Int,
+                TokenType::Plus,
+                TokenType::Int
+            ]
+        );
+
+        assert_eq!(
+            tokenize("SELECT #").unwrap_err().to_string(),
+            "error: unexpected token `#` at line 1, column 8\nSELECT #\n       ^"
+        );
+    }
+
+    #[test]
+    fn test_quoted_identifier() {
+        let mut l = Lexer::new(r#""my table" "a""b" `c`"#);
+        let expected = vec![
+            (TokenType::QuotedIdent('"'), "my table"),
+            (TokenType::QuotedIdent('"'), "a\"b"),
+            (TokenType::ILLIGAL, "`"),
+        ];
+        for (expected_type, expected_literal) in expected {
+            let tok = l.next_token();
+            assert_eq!(tok.token_type, expected_type);
+            assert_eq!(tok.literal, expected_literal);
+        }
+
+        // a double quoted text is a string in MySQL
+        let mut l = Lexer::with_dialect(r#"`select` `a``b` "c""d""#, &MySqlDialect);
+        let expected = vec![
+            (TokenType::QuotedIdent('`'), "select"),
+            (TokenType::QuotedIdent('`'), "a`b"),
+            (TokenType::String, "c\"d"),
+            (TokenType::EOF, ""),
+        ];
+        for (expected_type, expected_literal) in expected {
+            let tok = l.next_token();
+            assert_eq!(tok.token_type, expected_type);
+            assert_eq!(tok.literal, expected_literal);
+        }
+    }
+
+    #[test]
+    fn test_escape_string() {
+        let input = r"E'a\nb\tcé\x41\\\'d''' e'\U0001F600' E'\q' 'a\n'";
+        let expected = vec![
+            (TokenType::String, "a\nb\tcéA\\'d'"),
+            (TokenType::String, "😀"),
+            (TokenType::String, "q"),
+            (TokenType::String, r"a\n"),
+            (TokenType::EOF, ""),
+        ];
+        let mut l = Lexer::new(input);
+        for (expected_type, expected_literal) in expected {
+            let tok = l.next_token();
+            assert_eq!(tok.token_type, expected_type);
+            assert_eq!(tok.literal, expected_literal);
+        }
+
+        assert_eq!(Lexer::new(r"E'\u00'").next_token().token_type, TokenType::ILLIGAL);
+        assert_eq!(Lexer::new(r"E'abc").next_token().token_type, TokenType::ILLIGAL);
+        // an identifier named `e` is still an identifier
+        assert_eq!(Lexer::new("e = 1").next_token().token_type, TokenType::Ident);
+    }
 }
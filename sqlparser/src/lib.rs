@@ -1,6 +1,8 @@
 pub mod ast;
 pub mod datatype;
+pub mod dialect;
 pub mod error;
 pub mod lexer;
 pub mod parser;
 pub mod token;
+pub mod visitor;
@@ -1,10 +1,12 @@
 use crate::{
     ast::{
         self, Assignment, CopyOption, CopySource, CopyTarget, Cte, DateTimeField, Expression, FunctionArgument, Ident,
-        ObjectName, OnConflict, Order, Select, SelectItem, Statement, StructField, With,
+        ObjectName, OnConflict, OrderByExpr, Select, SelectItem, SetOperator, Statement, StructField, TableConstraint,
+        WindowFrame, WindowFrameBound, WindowFrameUnits, WindowSpec, With,
     },
     datatype::DataType,
-    error::{Error, Result},
+    dialect::{Dialect, GenericDialect},
+    error::{Error, ParserError, Result},
     lexer::Lexer,
     token::{Keyword, Token, TokenType},
 };
@@ -21,66 +23,292 @@ pub struct Parser<'a> {
 
     pub tables: Vec<TableInfo>,
     pub ctes: Vec<TableInfo>,
+
+    /// the errors recovered from, `None` unless parsing with recovery
+    errors: Option<Vec<Error>>,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(sql: &'a str) -> Parser<'a> {
+        Self::with_dialect(sql, &GenericDialect)
+    }
+
+    pub fn with_dialect(sql: &'a str, dialect: &'a dyn Dialect) -> Parser<'a> {
         Parser {
-            lexer: Lexer::new(sql),
+            lexer: Lexer::with_dialect(sql, dialect),
             tables: Vec::new(),
             ctes: Vec::new(),
+            errors: None,
+        }
+    }
+
+    /// Parse a statement reporting all its syntax errors rather than only the first one. After an error in an item of
+    /// the select list or in a clause of a `SELECT`, the tokens up to the next item or clause are skipped and parsing
+    /// goes on, so the statement is only returned if it has no error.
+    pub fn parse_with_recovery(&mut self) -> std::result::Result<Statement, Vec<Error>> {
+        self.errors = Some(vec![]);
+        let result = self.parse().and_then(|statement| {
+            let token = self.lexer.next_token();
+            match token.token_type {
+                TokenType::Semicolon | TokenType::EOF => Ok(statement),
+                _ => Err(ParserError::expected(token, vec![TokenType::Semicolon, TokenType::EOF]).into()),
+            }
+        });
+        let mut errors = self.errors.take().unwrap_or_default();
+
+        match result {
+            Ok(statement) if errors.is_empty() => Ok(statement),
+            Ok(_) => Err(errors),
+            Err(e) => {
+                errors.push(e);
+                Err(errors)
+            }
+        }
+    }
+
+    /// Parse a script of statements separated by semicolons, empty statements are skipped. The tables of all the
+    /// statements are collected in `tables`.
+    pub fn parse_statements(&mut self) -> Result<Vec<Statement>> {
+        let mut statements = vec![];
+        while let Some(statement) = self.parse_next_statement()? {
+            statements.push(statement);
+        }
+
+        Ok(statements)
+    }
+
+    /// Parse the next statement of a script, `None` at the end of the script. A statement must be followed by a
+    /// semicolon or the end of the script.
+    pub fn parse_next_statement(&mut self) -> Result<Option<Statement>> {
+        while self.next_if_token(TokenType::Semicolon).is_some() {}
+        if matches!(self.lexer.peek().map(|t| &t.token_type), Some(TokenType::EOF)) {
+            return Ok(None);
+        }
+
+        // the CTEs are only visible in the statement that defines them
+        self.ctes.clear();
+        let statement = self.parse()?;
+
+        let token = self.lexer.next_token();
+        match token.token_type {
+            TokenType::Semicolon | TokenType::EOF => Ok(Some(statement)),
+            _ => Err(ParserError::expected(token, vec![TokenType::Semicolon, TokenType::EOF]).into()),
         }
     }
 
     pub fn parse(&mut self) -> Result<Statement> {
         let token = self.next_token()?;
         match token.token_type {
-            TokenType::Keyword(Keyword::Select) => self.parse_select_statement(),
+            TokenType::Keyword(Keyword::Select) => self
+                .parse_select_statement()
+                .and_then(|query| self.parse_set_operations(query)),
             TokenType::Keyword(Keyword::With) => self.parse_with_statment(),
+            TokenType::Keyword(Keyword::Values) => self.parse_values().map(Statement::Values),
             TokenType::Keyword(Keyword::Insert) => self.parse_insert_statement(),
             TokenType::Keyword(Keyword::Update) => self.parse_update_statement(),
             TokenType::Keyword(Keyword::Delete) => self.parse_delete_statement(),
+            TokenType::Keyword(Keyword::Truncate) => self.parse_truncate_statement(),
             TokenType::Keyword(Keyword::Create) => self.parse_create_statement(),
             TokenType::Keyword(Keyword::Drop) => self.parse_drop_statement(),
             TokenType::Keyword(Keyword::Copy) => self.parse_copy_statement(),
             TokenType::Keyword(Keyword::Show) => self.parse_show(),
-            _ => Err(Error::UnexpectedToken(token)),
+            TokenType::Keyword(Keyword::Describe) => self.parse_describe(),
+            TokenType::Keyword(Keyword::Explain) => self.parse_explain(),
+            TokenType::Keyword(Keyword::Prepare) => self.parse_prepare(),
+            TokenType::Keyword(Keyword::Execute) => self.parse_execute(),
+            TokenType::Keyword(Keyword::Deallocate) => self.parse_deallocate(),
+            TokenType::Keyword(Keyword::Set) => self.parse_set_variable(),
+            TokenType::Keyword(Keyword::Begin) => {
+                self.parse_transaction_keyword();
+                Ok(Statement::StartTransaction)
+            }
+            TokenType::Keyword(Keyword::Start) => {
+                if !self.parse_transaction_keyword() {
+                    return Err(Error::UnexpectedToken(self.lexer.next_token()));
+                }
+                Ok(Statement::StartTransaction)
+            }
+            TokenType::Keyword(Keyword::Commit) => {
+                self.parse_transaction_keyword();
+                Ok(Statement::Commit)
+            }
+            TokenType::Keyword(Keyword::Rollback) => {
+                self.parse_transaction_keyword();
+                Ok(Statement::Rollback)
+            }
+            _ => Err(ParserError::expected(
+                token,
+                [
+                    Keyword::Select,
+                    Keyword::With,
+                    Keyword::Values,
+                    Keyword::Insert,
+                    Keyword::Update,
+                    Keyword::Delete,
+                    Keyword::Truncate,
+                    Keyword::Create,
+                    Keyword::Drop,
+                    Keyword::Copy,
+                    Keyword::Show,
+                    Keyword::Describe,
+                    Keyword::Explain,
+                    Keyword::Prepare,
+                    Keyword::Execute,
+                    Keyword::Deallocate,
+                    Keyword::Set,
+                    Keyword::Begin,
+                    Keyword::Start,
+                    Keyword::Commit,
+                    Keyword::Rollback,
+                ]
+                .map(TokenType::Keyword)
+                .to_vec(),
+            )
+            .into()),
+        }
+    }
+
+    fn parse_prepare(&mut self) -> Result<Statement> {
+        let name = self.parse_ident()?;
+        let mut data_types = vec![];
+        if self.next_if_token(TokenType::LParen).is_some() {
+            data_types = self.parse_comma_separated(Parser::parse_data_type)?;
+            self.next_except(TokenType::RParen)?;
+        }
+        self.next_except(TokenType::Keyword(Keyword::As))?;
+
+        // only a query or a DML statement can be prepared
+        let token = self.peek()?;
+        if !matches!(
+            token.token_type,
+            TokenType::Keyword(Keyword::Select | Keyword::With | Keyword::Insert | Keyword::Update | Keyword::Delete)
+        ) {
+            return Err(Error::UnexpectedToken(token.clone()));
+        }
+        let statement = self.parse()?;
+
+        Ok(Statement::Prepare {
+            name,
+            data_types,
+            statement: Box::new(statement),
+        })
+    }
+
+    fn parse_execute(&mut self) -> Result<Statement> {
+        let name = self.parse_ident()?;
+        let mut parameters = vec![];
+        if self.next_if_token(TokenType::LParen).is_some() {
+            parameters = self.parse_comma_separated(|parser| parser.parse_expression(0))?;
+            self.next_except(TokenType::RParen)?;
         }
+
+        Ok(Statement::Execute { name, parameters })
+    }
+
+    /// the optional `TRANSACTION` or `WORK` of a transaction statement, they are not keywords
+    fn parse_transaction_keyword(&mut self) -> bool {
+        self.next_if_ident("transaction")
+            .or_else(|| self.next_if_ident("work"))
+            .is_some()
+    }
+
+    fn parse_deallocate(&mut self) -> Result<Statement> {
+        let prepare = self.next_if_token(TokenType::Keyword(Keyword::Prepare)).is_some();
+        let name = self.parse_ident()?;
+
+        Ok(Statement::Deallocate { name, prepare })
+    }
+
+    fn parse_explain(&mut self) -> Result<Statement> {
+        // `ANALYZE` and `VERBOSE` are not keywords
+        let analyze = self.next_if_ident("analyze").is_some();
+        let verbose = self.next_if_ident("verbose").is_some();
+        let statement = self.parse_query_statement()?;
+
+        Ok(Statement::Explain {
+            analyze,
+            verbose,
+            statement: Box::new(statement),
+        })
     }
 
     fn parse_show(&mut self) -> Result<Statement> {
         let token = self.next_token()?;
         match token.token_type {
             TokenType::Keyword(Keyword::Tables) => Ok(Statement::ShowTables),
-            _ => Err(Error::UnexpectedToken(token)),
+            TokenType::Keyword(Keyword::Columns) => {
+                if self.next_if_token(TokenType::Keyword(Keyword::In)).is_none() {
+                    self.next_except(TokenType::Keyword(Keyword::From))?;
+                }
+                self.parse_describe()
+            }
+            TokenType::Keyword(Keyword::All) => Ok(Statement::ShowVariable {
+                variable: Ident::from("all"),
+            }),
+            _ if token.token_type.is_identifier() => Ok(Statement::ShowVariable {
+                variable: ident_from_token(token),
+            }),
+            _ => Err(ParserError::expected(
+                token,
+                vec![
+                    TokenType::Keyword(Keyword::Tables),
+                    TokenType::Keyword(Keyword::Columns),
+                    TokenType::Keyword(Keyword::All),
+                    TokenType::Ident,
+                ],
+            )
+            .into()),
+        }
+    }
+
+    /// `SET name {= | TO} value [, ...]`
+    fn parse_set_variable(&mut self) -> Result<Statement> {
+        let variable = self.parse_ident()?;
+        if self.next_if_token(TokenType::Keyword(Keyword::To)).is_none() {
+            self.next_except(TokenType::Eq)?;
         }
+        let value = self.parse_comma_separated(|parser| parser.parse_expression(0))?;
+
+        Ok(Statement::SetVariable { variable, value })
+    }
+
+    /// the table of `DESCRIBE table` or `SHOW COLUMNS {FROM | IN} table`
+    fn parse_describe(&mut self) -> Result<Statement> {
+        let table = self.parse_object_name()?;
+        self.tables.push(TableInfo {
+            name: table.to_string(),
+            alias: None,
+            args: vec![],
+        });
+
+        Ok(Statement::ShowColumns { table })
     }
 
     fn parse_copy_statement(&mut self) -> Result<Statement> {
-        let token = self.peek()?;
-        let source = match token.token_type {
-            TokenType::Keyword(Keyword::Select) => {
-                self.parse_select().map(|query| CopySource::Query(Box::new(query)))?
-            }
-            _ => {
-                let table = self.parse_object_name()?;
+        let source = if self.next_if_token(TokenType::LParen).is_some() {
+            self.next_except(TokenType::Keyword(Keyword::Select))?;
+            let query = self.parse_select()?;
+            self.next_except(TokenType::RParen)?;
 
-                let mut columns = vec![];
-                if self.next_if_token(TokenType::LParen).is_some() {
-                    columns = self.parse_comma_separated(Parser::parse_ident)?;
-                    self.next_except(TokenType::RParen)?;
-                }
+            CopySource::Query(Box::new(query))
+        } else {
+            let table = self.parse_object_name()?;
 
-                self.tables.push(TableInfo {
-                    name: table.to_string(),
-                    alias: None,
-                    args: vec![],
-                });
+            let mut columns = vec![];
+            if self.next_if_token(TokenType::LParen).is_some() {
+                columns = self.parse_comma_separated(Parser::parse_ident)?;
+                self.next_except(TokenType::RParen)?;
+            }
 
-                CopySource::Table {
-                    table_name: table,
-                    columns,
-                }
+            self.tables.push(TableInfo {
+                name: table.to_string(),
+                alias: None,
+                args: vec![],
+            });
+
+            CopySource::Table {
+                table_name: table,
+                columns,
             }
         };
 
@@ -94,7 +322,11 @@ impl<'a> Parser<'a> {
 
         let target = self.parse_literal_string().map(|file| CopyTarget::File { file })?;
         let mut options = vec![];
-        if self.next_if_token(TokenType::LParen).is_some() {
+        let with = self.next_if_token(TokenType::Keyword(Keyword::With)).is_some();
+        if with {
+            self.next_except(TokenType::LParen)?;
+        }
+        if with || self.next_if_token(TokenType::LParen).is_some() {
             options = self.parse_comma_separated(Parser::parse_copy_option)?;
             self.next_except(TokenType::RParen)?;
         }
@@ -108,7 +340,8 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_drop_statement(&mut self) -> Result<Statement> {
-        match self.next_token()?.token_type {
+        let token = self.next_token()?;
+        match token.token_type {
             TokenType::Keyword(Keyword::Schema) => {
                 let check_exists = self.parse_if_exists()?;
                 let schema = self.next_ident()?;
@@ -117,84 +350,93 @@ impl<'a> Parser<'a> {
             }
             TokenType::Keyword(Keyword::Table) => {
                 let check_exists = self.parse_if_exists()?;
-                let table = self.next_ident()?;
-
-                self.add_relation_table(TableInfo {
-                    name: table.clone(),
-                    alias: None,
-                    args: vec![],
-                });
+                let table = self.parse_object_name()?.to_string();
 
                 Ok(Statement::DropTable { table, check_exists })
             }
-            _ => unimplemented!(),
+            TokenType::Ident if token.literal.eq_ignore_ascii_case("view") => {
+                let check_exists = self.parse_if_exists()?;
+                let view = self.parse_object_name()?.to_string();
+
+                Ok(Statement::DropView { view, check_exists })
+            }
+            _ => Err(Error::UnexpectedToken(token)),
         }
     }
 
     fn parse_create_statement(&mut self) -> Result<Statement> {
+        // `VIEW` and `REPLACE` are not keywords, `REPLACE` is also a function name
+        if self.next_if_token(TokenType::Keyword(Keyword::Or)).is_some() {
+            let token = self.next_token()?;
+            if !token.literal.eq_ignore_ascii_case("replace") || self.next_if_ident("view").is_none() {
+                return Err(Error::UnexpectedToken(token));
+            }
+            return self.parse_create_view(true);
+        }
+
         let token = self.next_token()?;
         match token.token_type {
             TokenType::Keyword(Keyword::Schema) => self.parse_create_schema(),
             TokenType::Keyword(Keyword::Table) => self.parse_create_table(),
+            TokenType::Ident if token.literal.eq_ignore_ascii_case("view") => self.parse_create_view(false),
             _ => Err(Error::UnexpectedToken(token)),
         }
     }
 
+    fn parse_create_view(&mut self, or_replace: bool) -> Result<Statement> {
+        let name = self.parse_table_name()?;
+        let columns = if self.next_if_token(TokenType::LParen).is_some() {
+            let columns = self.parse_comma_separated(Parser::parse_ident)?;
+            self.next_except(TokenType::RParen)?;
+            columns
+        } else {
+            vec![]
+        };
+        self.next_except(TokenType::Keyword(Keyword::As))?;
+        let query = self.parse_query_statement()?;
+
+        Ok(Statement::CreateView {
+            or_replace,
+            name,
+            columns,
+            query: Box::new(query),
+        })
+    }
+
     fn parse_create_table(&mut self) -> Result<Statement> {
         let check_exists = self.parse_if_not_exists()?;
-        let table = self.next_ident()?;
+        let table = self.parse_table_name()?;
         let mut columns = Vec::new();
-        // parse table columns
+        let mut constraints = Vec::new();
+        // parse table columns and constraints
         if self.next_if_token(TokenType::LParen).is_some() {
             loop {
                 if self.next_if_token(TokenType::RParen).is_some() {
                     break;
                 }
-                let mut nullable = true;
-                let name = self.next_ident()?;
-                let datatype = self.parse_data_type()?;
-                let primary_key = if self.next_if_token(TokenType::Keyword(Keyword::Primary)).is_some() {
+                if self.next_if_token(TokenType::Keyword(Keyword::Primary)).is_some() {
                     self.next_except(TokenType::Keyword(Keyword::Key))?;
-
-                    nullable = false;
-                    true
+                    constraints.push(TableConstraint::PrimaryKey(self.parse_constraint_columns()?));
+                } else if self.next_if_token(TokenType::Keyword(Keyword::Unique)).is_some() {
+                    constraints.push(TableConstraint::Unique(self.parse_constraint_columns()?));
                 } else {
-                    false
-                };
-                let unique = self.next_if_token(TokenType::Keyword(Keyword::Unique)).is_some();
-
-                if self.next_if_token(TokenType::Keyword(Keyword::Not)).is_some() {
-                    self.next_except(TokenType::Keyword(Keyword::Null))?;
-
-                    nullable = false;
+                    columns.push(self.parse_column_definition()?);
                 }
 
-                columns.push(ast::Column {
-                    name,
-                    datatype,
-                    nullable,
-                    unique,
-                    references: None,
-                    primary_key,
-                    index: false,
-                });
-
                 if self.next_if_token(TokenType::Comma).is_none() {
+                    self.next_except(TokenType::RParen)?;
                     break;
                 }
             }
         }
-        // parse query
+        // parse query, `AS FROM table` is short for `AS SELECT * FROM table`
         let query = if self.next_if_token(TokenType::Keyword(Keyword::As)).is_some() {
-            if self.next_if_token(TokenType::Keyword(Keyword::Select)).is_some() {
-                Some(self.parse_select()?)
-            } else {
-                self.next_except(TokenType::Keyword(Keyword::From))?;
+            let query = if self.next_if_token(TokenType::Keyword(Keyword::From)).is_some() {
                 let table = self.parse_table_reference()?;
-                Some(Select {
+                Statement::Select(Box::new(Select {
                     with: None,
                     distinct: None,
-                    columns: vec![SelectItem::Wildcard],
+                    columns: vec![SelectItem::Wildcard(vec![])],
                     from: vec![table],
                     r#where: None,
                     group_by: None,
@@ -202,8 +444,11 @@ impl<'a> Parser<'a> {
                     order_by: None,
                     limit: None,
                     offset: None,
-                })
-            }
+                }))
+            } else {
+                self.parse_query_statement()?
+            };
+            Some(Box::new(query))
         } else {
             None
         };
@@ -212,10 +457,54 @@ impl<'a> Parser<'a> {
             query,
             table,
             columns,
+            constraints,
             check_exists,
         })
     }
 
+    /// parse `name type [constraint ...]`, the column constraints may be in any order
+    fn parse_column_definition(&mut self) -> Result<ast::Column> {
+        let name = self.next_ident()?;
+        let datatype = self.parse_data_type()?;
+        let mut column = ast::Column {
+            name,
+            datatype,
+            nullable: true,
+            unique: false,
+            references: None,
+            primary_key: false,
+            index: false,
+        };
+
+        loop {
+            if self.next_if_token(TokenType::Keyword(Keyword::Primary)).is_some() {
+                self.next_except(TokenType::Keyword(Keyword::Key))?;
+                column.primary_key = true;
+                column.nullable = false;
+            } else if self.next_if_token(TokenType::Keyword(Keyword::Unique)).is_some() {
+                column.unique = true;
+            } else if self.next_if_ident("index").is_some() {
+                column.index = true;
+            } else if self.next_if_token(TokenType::Keyword(Keyword::Not)).is_some() {
+                self.next_except(TokenType::Keyword(Keyword::Null))?;
+                column.nullable = false;
+            } else if self.next_if_token(TokenType::Keyword(Keyword::Null)).is_none() {
+                break;
+            }
+        }
+
+        Ok(column)
+    }
+
+    /// parse the column list of a table constraint, `(column, ...)`
+    fn parse_constraint_columns(&mut self) -> Result<Vec<String>> {
+        self.next_except(TokenType::LParen)?;
+        let columns = self.parse_comma_separated(|parser| parser.next_ident())?;
+        self.next_except(TokenType::RParen)?;
+
+        Ok(columns)
+    }
+
     fn parse_create_schema(&mut self) -> Result<Statement> {
         let check_exists: bool = self.parse_if_not_exists()?;
         let schema = self.next_ident()?;
@@ -226,7 +515,7 @@ impl<'a> Parser<'a> {
     fn parse_delete_statement(&mut self) -> Result<Statement> {
         self.next_except(TokenType::Keyword(Keyword::From))?;
 
-        let table = self.next_ident()?;
+        let table = self.parse_table_name()?;
 
         self.add_relation_table(TableInfo {
             name: table.clone(),
@@ -243,8 +532,28 @@ impl<'a> Parser<'a> {
         Ok(Statement::Delete { table, r#where })
     }
 
+    fn parse_truncate_statement(&mut self) -> Result<Statement> {
+        self.next_if_token(TokenType::Keyword(Keyword::Table));
+
+        let table = self.parse_table_name()?;
+
+        self.add_relation_table(TableInfo {
+            name: table.clone(),
+            alias: None,
+            args: vec![],
+        });
+
+        Ok(Statement::Truncate { table })
+    }
+
     fn parse_update_statement(&mut self) -> Result<Statement> {
-        let table = self.next_ident()?;
+        let table = self.parse_table_name()?;
+
+        self.add_relation_table(TableInfo {
+            name: table.clone(),
+            alias: None,
+            args: vec![],
+        });
 
         self.next_except(TokenType::Keyword(Keyword::Set))?;
 
@@ -266,7 +575,7 @@ impl<'a> Parser<'a> {
     fn parse_insert_statement(&mut self) -> Result<Statement> {
         self.next_except(TokenType::Keyword(Keyword::Into))?;
 
-        let table = self.next_ident()?;
+        let table = self.parse_table_name()?;
         let alias = self.parse_alias()?;
 
         self.add_relation_table(TableInfo {
@@ -290,13 +599,13 @@ impl<'a> Parser<'a> {
             None
         };
         let query = if self.next_if_token(TokenType::Keyword(Keyword::Select)).is_some() {
-            Some(self.parse_select()?)
+            Some(Box::new(self.parse_select()?))
         } else if self.next_if_token(TokenType::Keyword(Keyword::From)).is_some() {
             let table = self.parse_table_reference()?;
-            Some(Select {
+            Some(Box::new(Select {
                 with: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![table],
                 r#where: None,
                 group_by: None,
@@ -304,7 +613,7 @@ impl<'a> Parser<'a> {
                 order_by: None,
                 limit: None,
                 offset: None,
-            })
+            }))
         } else {
             None
         };
@@ -342,14 +651,28 @@ impl<'a> Parser<'a> {
         self.parse_select().map(|s| Statement::Select(Box::new(s)))
     }
 
+    /// parse a query, a `SELECT` or a set operation with optional CTEs
+    fn parse_query_statement(&mut self) -> Result<Statement> {
+        let token = self.next_token()?;
+        match token.token_type {
+            TokenType::Keyword(Keyword::Select) => self
+                .parse_select_statement()
+                .and_then(|query| self.parse_set_operations(query)),
+            TokenType::Keyword(Keyword::With) => self.parse_with_statment(),
+            _ => Err(Error::UnexpectedToken(token)),
+        }
+    }
+
     fn parse_with_statment(&mut self) -> Result<Statement> {
         let with = self.parse_cte_with()?;
         let token = self.next_token()?;
         match token.token_type {
-            TokenType::Keyword(Keyword::Select) => self.parse_select().map(|mut select| {
+            TokenType::Keyword(Keyword::Select) => {
+                let mut select = self.parse_select()?;
+                // the CTEs are attached to the leftmost query of a set operation
                 select.with = Some(with);
-                Statement::Select(Box::new(select))
-            }),
+                self.parse_set_operations(Statement::Select(Box::new(select)))
+            }
             _ => Err(Error::UnexpectedToken(token)),
         }
     }
@@ -362,45 +685,39 @@ impl<'a> Parser<'a> {
         let from = if self.next_if_token(TokenType::Keyword(Keyword::From)).is_none() {
             vec![]
         } else {
-            self.parse_from_statment()?
+            let from = self.parse_from_statment();
+            self.recover(from, &[])?.unwrap_or_default()
         };
 
         let r#where = if self.next_if_token(TokenType::Keyword(Keyword::Where)).is_some() {
-            Some(self.parse_expression(0)?)
+            let expr = self.parse_expression(0);
+            self.recover(expr, &[])?
         } else {
             None
         };
 
         let group_by = if self.next_if_token(TokenType::Keyword(Keyword::Group)).is_some() {
-            Some(self.parse_group_by()?)
+            let group_by = self.parse_group_by();
+            self.recover(group_by, &[])?
         } else {
             None
         };
 
         let having = if self.next_if_token(TokenType::Keyword(Keyword::Having)).is_some() {
-            Some(self.parse_expression(0)?)
+            let expr = self.parse_expression(0);
+            self.recover(expr, &[])?
         } else {
             None
         };
 
         let order_by = if self.next_if_token(TokenType::Keyword(Keyword::Order)).is_some() {
-            Some(self.parse_order_by()?)
+            let order_by = self.parse_order_by();
+            self.recover(order_by, &[])?
         } else {
             None
         };
 
-        let mut limit = None;
-        let mut offset = None;
-
-        for _ in 0..2 {
-            if self.next_if_token(TokenType::Keyword(Keyword::Limit)).is_some() {
-                limit = Some(self.parse_expression(0)?);
-            }
-
-            if self.next_if_token(TokenType::Keyword(Keyword::Offset)).is_some() {
-                offset = Some(self.parse_expression(0)?)
-            }
-        }
+        let (limit, offset) = self.parse_limit_offset()?;
 
         Ok(Select {
             with: None,
@@ -417,23 +734,37 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_cte_with(&mut self) -> Result<With> {
+        let recursive = self.next_if_token(TokenType::Keyword(Keyword::Recursive)).is_some();
         let mut ctes = vec![];
         loop {
             let cte_table_name = self.parse_ident()?.value;
+            let columns = if self.next_if_token(TokenType::LParen).is_some() {
+                let columns = self.parse_comma_separated(Parser::parse_ident)?;
+                self.next_except(TokenType::RParen)?;
+                columns
+            } else {
+                vec![]
+            };
 
             self.next_except(TokenType::Keyword(Keyword::As))?;
 
             self.next_except(TokenType::LParen)?;
 
-            let token = self.next_token()?;
-            match token.token_type {
-                TokenType::Keyword(Keyword::Select) => ctes.push(Cte {
-                    alias: cte_table_name.clone(),
-                    query: Box::new(self.parse_select()?),
-                }),
-                _ => return Err(Error::UnexpectedToken(token)),
+            // a recursive cte can reference itself, register it before parsing the query
+            if recursive {
+                self.add_cte_table(TableInfo {
+                    name: cte_table_name.clone(),
+                    alias: None,
+                    args: vec![],
+                });
             }
 
+            ctes.push(Cte {
+                alias: cte_table_name.clone(),
+                columns,
+                query: Box::new(self.parse_query_body()?),
+            });
+
             self.add_cte_table(TableInfo {
                 name: cte_table_name,
                 alias: None,
@@ -448,18 +779,109 @@ impl<'a> Parser<'a> {
         }
 
         Ok(With {
-            recursive: false,
+            recursive,
             cte_tables: ctes,
         })
     }
 
+    /// parse a select optionally followed by set operations, eg. `SELECT 1 UNION ALL SELECT 2`
+    fn parse_query_body(&mut self) -> Result<Statement> {
+        self.next_except(TokenType::Keyword(Keyword::Select))?;
+        let query = self.parse_select_statement()?;
+        self.parse_set_operations(query)
+    }
+
+    /// parse the set operations following the first query, the ORDER BY and LIMIT parsed with the last
+    /// query apply to the result of the whole set operation
+    fn parse_set_operations(&mut self, query: Statement) -> Result<Statement> {
+        let mut query = self.parse_set_expr(query, 0)?;
+
+        if let Statement::SetOperation {
+            right,
+            order_by,
+            limit,
+            offset,
+            ..
+        } = &mut query
+        {
+            let mut last = right.as_mut();
+            while let Statement::SetOperation { right, .. } = last {
+                last = right.as_mut();
+            }
+            if let Statement::Select(select) = last {
+                *order_by = select.order_by.take();
+                *limit = select.limit.take();
+                *offset = select.offset.take();
+            }
+        }
+
+        Ok(query)
+    }
+
+    /// `INTERSECT` binds tighter than `UNION` and `EXCEPT`, operators of the same precedence are left associative
+    fn parse_set_expr(&mut self, mut lhs: Statement, precedence: u8) -> Result<Statement> {
+        loop {
+            let (op, op_precedence) = match self.lexer.peek().map(|t| &t.token_type) {
+                Some(TokenType::Keyword(Keyword::Union)) => (SetOperator::Union, 1),
+                Some(TokenType::Keyword(Keyword::Except)) => (SetOperator::Except, 1),
+                Some(TokenType::Keyword(Keyword::Intersect)) => (SetOperator::Intersect, 2),
+                _ => break,
+            };
+            if op_precedence <= precedence {
+                break;
+            }
+            self.next_token()?;
+
+            let all = self.next_if_token(TokenType::Keyword(Keyword::All)).is_some();
+            if !all {
+                self.next_if_token(TokenType::Keyword(Keyword::Distinct));
+            }
+            self.next_except(TokenType::Keyword(Keyword::Select))?;
+            let rhs = self.parse_select_statement()?;
+
+            lhs = Statement::SetOperation {
+                op,
+                all,
+                left: Box::new(lhs),
+                right: Box::new(self.parse_set_expr(rhs, op_precedence)?),
+                order_by: None,
+                limit: None,
+                offset: None,
+            };
+        }
+
+        Ok(lhs)
+    }
+
     fn parse_copy_option(&mut self) -> Result<CopyOption> {
         let token = self.next_token()?;
 
         match token.token_type {
             TokenType::Keyword(Keyword::Format) => self.parse_ident().map(CopyOption::Format),
-            TokenType::Keyword(Keyword::Header) => Ok(CopyOption::Header(true)),
+            TokenType::Keyword(Keyword::Header) => {
+                // HEADER alone is HEADER true
+                let header = self.next_if_token(TokenType::Keyword(Keyword::False)).is_none();
+                if header {
+                    self.next_if_token(TokenType::Keyword(Keyword::True));
+                }
+                Ok(CopyOption::Header(header))
+            }
             TokenType::Keyword(Keyword::Delimiter) => self.parse_literal_char().map(CopyOption::Delimiter),
+            _ if token.token_type.is_identifier() => {
+                let name = ident_from_token(token);
+                // an identifier value is read as a string
+                let value = match self.parse_expression(0)? {
+                    Expression::Literal(literal) => literal,
+                    Expression::Identifier(ident) => ast::Literal::String(ident.value),
+                    value => {
+                        return Err(Error::ParserError(format!(
+                            "the value of the COPY option {} must be a literal, got {}",
+                            name, value
+                        )))
+                    }
+                };
+                Ok(CopyOption::Option(name, value))
+            }
             _ => Err(Error::UnexpectedToken(token)),
         }
     }
@@ -479,7 +901,7 @@ impl<'a> Parser<'a> {
         self.next_except(TokenType::Keyword(Keyword::Do))?;
 
         if self.next_if_token(TokenType::Keyword(Keyword::Nothing)).is_some() {
-            Ok(OnConflict::DoNothing)
+            Ok(OnConflict::DoNothing { constraints })
         } else {
             self.next_except(TokenType::Keyword(Keyword::Update))?;
             self.next_except(TokenType::Keyword(Keyword::Set))?;
@@ -539,7 +961,7 @@ impl<'a> Parser<'a> {
         Ok(check_exists)
     }
 
-    fn parse_order_by(&mut self) -> Result<Vec<(Expression, Order)>> {
+    fn parse_order_by(&mut self) -> Result<Vec<OrderByExpr>> {
         self.next_except(TokenType::Keyword(Keyword::By))?;
 
         let mut order_fields = vec![];
@@ -553,8 +975,24 @@ impl<'a> Parser<'a> {
             if self.next_if_token(TokenType::Keyword(Keyword::Desc)).is_some() {
                 order = ast::Order::Desc;
             }
+            // NULLS FIRST | NULLS LAST, the words are not keywords so they can still be used as identifiers
+            let nulls_first = if self.next_if_ident("nulls").is_some() {
+                if self.next_if_ident("first").is_some() {
+                    Some(true)
+                } else if self.next_if_ident("last").is_some() {
+                    Some(false)
+                } else {
+                    return Err(Error::UnexpectedToken(self.next_token()?));
+                }
+            } else {
+                None
+            };
 
-            order_fields.push((expr, order));
+            order_fields.push(OrderByExpr {
+                expr,
+                order,
+                nulls_first,
+            });
 
             if self.next_if_token(TokenType::Comma).is_none() {
                 break;
@@ -569,7 +1007,7 @@ impl<'a> Parser<'a> {
 
         let mut group_by = Vec::new();
         while self.next_if_token(TokenType::Semicolon).is_none() {
-            group_by.push(self.parse_expression(0)?);
+            group_by.push(self.parse_grouping_element()?);
             if self.next_if_token(TokenType::Comma).is_none() {
                 break;
             }
@@ -578,6 +1016,43 @@ impl<'a> Parser<'a> {
         Ok(group_by)
     }
 
+    /// an expression, `ROLLUP (...)`, `CUBE (...)` or `GROUPING SETS (...)`, the words are not keywords
+    /// so `ROLLUP` and `CUBE` are parsed as function calls first
+    fn parse_grouping_element(&mut self) -> Result<Expression> {
+        match self.parse_expression(0)? {
+            Expression::Function(name, args) if name.eq_ignore_ascii_case("rollup") => Ok(Expression::Rollup(args)),
+            Expression::Function(name, args) if name.eq_ignore_ascii_case("cube") => Ok(Expression::Cube(args)),
+            Expression::Identifier(ident)
+                if ident.value.eq_ignore_ascii_case("grouping") && self.next_if_ident("sets").is_some() =>
+            {
+                self.next_except(TokenType::LParen)?;
+                let mut sets = vec![];
+                loop {
+                    if self.next_if_token(TokenType::LParen).is_some() {
+                        let mut set = vec![];
+                        while self.next_if_token(TokenType::RParen).is_none() {
+                            set.push(self.parse_expression(0)?);
+                            if self.next_if_token(TokenType::Comma).is_none() {
+                                self.next_except(TokenType::RParen)?;
+                                break;
+                            }
+                        }
+                        sets.push(set);
+                    } else {
+                        sets.push(vec![self.parse_expression(0)?]);
+                    }
+                    if self.next_if_token(TokenType::Comma).is_none() {
+                        break;
+                    }
+                }
+                self.next_except(TokenType::RParen)?;
+
+                Ok(Expression::GroupingSets(sets))
+            }
+            expr => Ok(expr),
+        }
+    }
+
     fn parse_distinct(&mut self) -> Result<Option<ast::Distinct>> {
         if self.next_if_token(TokenType::Keyword(Keyword::Distinct)).is_some() {
             if self.next_if_token(TokenType::Keyword(Keyword::On)).is_some() {
@@ -605,88 +1080,135 @@ impl<'a> Parser<'a> {
             if self.next_if_token(TokenType::Comma).is_some() {
                 continue;
             }
-            let expr = self.parse_expression(0)?;
-            let alias = self.parse_alias()?;
-
-            let col = match expr {
-                Expression::CompoundIdentifier(ref idents) => {
-                    if idents.last().filter(|a| a.value == "*").is_some() {
-                        SelectItem::QualifiedWildcard(
-                            idents
-                                .iter()
-                                .filter_map(|i| if i.value == "*" { None } else { Some(i.value.clone()) })
-                                .collect(),
-                        )
-                    } else if let Some(alias) = alias {
-                        SelectItem::ExprWithAlias(expr, alias)
-                    } else {
-                        SelectItem::UnNamedExpr(expr)
-                    }
+            let column = self.parse_select_item();
+            columns.extend(self.recover(column, &[TokenType::Comma])?);
+
+            if self.next_if_token(TokenType::Comma).is_none() {
+                break;
+            }
+        }
+
+        Ok(columns)
+    }
+
+    fn parse_select_item(&mut self) -> Result<SelectItem> {
+        let expr = self.parse_expression(0)?;
+        let exclude = self.parse_wildcard_exclude(&expr)?;
+        let alias = self.parse_alias()?;
+
+        let col = match expr {
+            Expression::CompoundIdentifier(ref idents) => {
+                if idents.last().filter(|a| a.value == "*").is_some() {
+                    SelectItem::QualifiedWildcard(
+                        idents
+                            .iter()
+                            .filter_map(|i| if i.value == "*" { None } else { Some(i.value.clone()) })
+                            .collect(),
+                        exclude,
+                    )
+                } else if let Some(alias) = alias {
+                    SelectItem::ExprWithAlias(expr, alias)
+                } else {
+                    SelectItem::UnNamedExpr(expr)
                 }
-                Expression::Identifier(ref ident) => {
-                    if ident.value == "*" && alias.is_none() {
-                        SelectItem::Wildcard
-                    } else if alias.is_some() {
-                        SelectItem::ExprWithAlias(expr, alias.unwrap())
-                    } else {
-                        SelectItem::UnNamedExpr(expr)
-                    }
+            }
+            Expression::Identifier(ref ident) => {
+                if ident.value == "*" && alias.is_none() {
+                    SelectItem::Wildcard(exclude)
+                } else if alias.is_some() {
+                    SelectItem::ExprWithAlias(expr, alias.unwrap())
+                } else {
+                    SelectItem::UnNamedExpr(expr)
                 }
-                Expression::Literal(_)
-                | Expression::BinaryOperator(_)
-                | Expression::Function(_, _)
-                | Expression::InSubQuery { .. } => match alias {
-                    Some(a) => SelectItem::ExprWithAlias(expr, a),
-                    None => SelectItem::UnNamedExpr(expr),
-                },
-                _ => {
-                    if let Some(alias) = alias {
-                        SelectItem::ExprWithAlias(expr, alias)
-                    } else {
-                        SelectItem::UnNamedExpr(expr)
-                    }
+            }
+            Expression::Literal(_)
+            | Expression::BinaryOperator(_)
+            | Expression::Function(_, _)
+            | Expression::DistinctFunction(_, _)
+            | Expression::AggregateFilter { .. }
+            | Expression::InSubQuery { .. } => match alias {
+                Some(a) => SelectItem::ExprWithAlias(expr, a),
+                None => SelectItem::UnNamedExpr(expr),
+            },
+            _ => {
+                if let Some(alias) = alias {
+                    SelectItem::ExprWithAlias(expr, alias)
+                } else {
+                    SelectItem::UnNamedExpr(expr)
                 }
-            };
+            }
+        };
 
-            columns.push(col);
+        Ok(col)
+    }
 
-            if self.next_if_token(TokenType::Comma).is_none() {
-                break;
-            }
+    /// `EXCLUDE (column, ...)` or `EXCEPT (column, ...)` after a wildcard
+    fn parse_wildcard_exclude(&mut self, expr: &Expression) -> Result<Vec<Ident>> {
+        let is_wildcard = match expr {
+            Expression::Identifier(ident) => ident.value == "*",
+            Expression::CompoundIdentifier(idents) => idents.last().is_some_and(|i| i.value == "*"),
+            _ => false,
+        };
+        if !is_wildcard
+            || (self.next_if_ident("exclude").is_none()
+                && self.next_if_token(TokenType::Keyword(Keyword::Except)).is_none())
+        {
+            return Ok(vec![]);
         }
 
+        self.next_except(TokenType::LParen)?;
+        let columns = self.parse_comma_separated(Parser::parse_ident)?;
+        self.next_except(TokenType::RParen)?;
+
         Ok(columns)
     }
 
     fn parse_from_statment(&mut self) -> Result<Vec<ast::From>> {
-        let relation = self.parse_table_reference()?;
-        let mut table_ref = vec![relation];
+        let mut table_ref = vec![self.parse_joined_table()?];
+        while self.next_if_token(TokenType::Comma).is_some() {
+            table_ref.push(self.parse_joined_table()?);
+        }
+
+        Ok(table_ref)
+    }
+
+    /// a table reference followed by any number of joins, which associate to the left
+    fn parse_joined_table(&mut self) -> Result<ast::From> {
+        let mut table = self.parse_table_reference()?;
         loop {
+            let natural = self.next_if_token(TokenType::Keyword(Keyword::Natural)).is_some();
             if let Some(join_type) = self.parse_join_type()? {
                 let right = self.parse_table_reference()?;
-                let on = if join_type == ast::JoinType::Cross {
-                    None
-                } else {
-                    self.next_except(TokenType::Keyword(Keyword::On))?;
-                    Some(self.parse_expression(0)?)
+                let constraint = match (natural, &join_type) {
+                    (true, ast::JoinType::Cross) => {
+                        return Err(Error::ParserError("CROSS JOIN can not be NATURAL".to_owned()))
+                    }
+                    (true, _) => ast::JoinConstraint::Natural,
+                    (false, ast::JoinType::Cross) => ast::JoinConstraint::None,
+                    (false, _) if self.next_if_token(TokenType::Keyword(Keyword::Using)).is_some() => {
+                        self.next_except(TokenType::LParen)?;
+                        let columns = self.parse_comma_separated(Parser::parse_ident)?;
+                        self.next_except(TokenType::RParen)?;
+                        ast::JoinConstraint::Using(columns)
+                    }
+                    (false, _) => {
+                        self.next_except(TokenType::Keyword(Keyword::On))?;
+                        ast::JoinConstraint::On(self.parse_expression(0)?)
+                    }
                 };
 
-                let left = table_ref.pop().ok_or(Error::ParserError("no left table".to_string()))?;
-
-                table_ref.push(ast::From::Join {
-                    left: Box::new(left),
+                table = ast::From::Join {
+                    left: Box::new(table),
                     right: Box::new(right),
-                    on,
+                    constraint,
                     join_type,
-                });
-            } else if self.next_if_token(TokenType::Comma).is_some() {
-                table_ref.push(self.parse_table_reference()?);
+                };
+            } else if natural {
+                return Err(Error::ParserError("expected JOIN after NATURAL".to_owned()));
             } else {
-                break;
+                return Ok(table);
             }
         }
-
-        Ok(table_ref)
     }
 
     fn parse_join_type(&mut self) -> Result<Option<ast::JoinType>> {
@@ -701,35 +1223,138 @@ impl<'a> Parser<'a> {
         };
         // consumer keyword token,such as: left \ right \ full \ cross \ inner
         if token.token_type != TokenType::Keyword(Keyword::Join) {
-            self.lexer.next();
+            self.lexer.next_token();
         }
+        let join_type = match join_type {
+            ast::JoinType::Left if self.lexer.dialect().supports_semi_anti_join() => {
+                if self.next_if_ident("semi").is_some() {
+                    ast::JoinType::LeftSemi
+                } else if self.next_if_ident("anti").is_some() {
+                    ast::JoinType::LeftAnti
+                } else {
+                    join_type
+                }
+            }
+            _ => join_type,
+        };
         // consumer next keyword token 'join'
         self.next_except(TokenType::Keyword(Keyword::Join))?;
 
-        Ok(Some(join_type))
+        Ok(Some(join_type))
+    }
+
+    /// a table factor followed by any number of `PIVOT (...)` or `UNPIVOT (...)` operators
+    fn parse_table_reference(&mut self) -> Result<ast::From> {
+        let mut table = self.parse_table_factor()?;
+
+        loop {
+            if self.next_if_token(TokenType::Keyword(Keyword::Pivot)).is_some() {
+                table = self.parse_pivot(table)?;
+            } else if self.next_if_token(TokenType::Keyword(Keyword::Unpivot)).is_some() {
+                table = self.parse_unpivot(table)?;
+            } else {
+                return Ok(table);
+            }
+        }
+    }
+
+    /// `(aggregate FOR column IN (value [AS alias], ...)) [AS alias]` after PIVOT
+    fn parse_pivot(&mut self, table: ast::From) -> Result<ast::From> {
+        self.next_except(TokenType::LParen)?;
+        let aggregate = self.parse_expression(0)?;
+        if self.next_if_ident("for").is_none() {
+            return Err(Error::UnexpectedToken(self.next_token()?));
+        }
+        let column = self.parse_ident()?;
+        self.next_except(TokenType::Keyword(Keyword::In))?;
+        self.next_except(TokenType::LParen)?;
+        let values = self.parse_comma_separated(|parser| {
+            Ok(ast::PivotValue {
+                value: parser.parse_expression(0)?,
+                alias: parser.parse_alias()?,
+            })
+        })?;
+        self.next_except(TokenType::RParen)?;
+        self.next_except(TokenType::RParen)?;
+
+        Ok(ast::From::Pivot {
+            table: Box::new(table),
+            aggregate,
+            column,
+            values,
+            alias: self.parse_alias()?,
+        })
+    }
+
+    /// `(value FOR name IN (column, ...)) [AS alias]` after UNPIVOT
+    fn parse_unpivot(&mut self, table: ast::From) -> Result<ast::From> {
+        self.next_except(TokenType::LParen)?;
+        let value = self.parse_ident()?;
+        if self.next_if_ident("for").is_none() {
+            return Err(Error::UnexpectedToken(self.next_token()?));
+        }
+        let name = self.parse_ident()?;
+        self.next_except(TokenType::Keyword(Keyword::In))?;
+        self.next_except(TokenType::LParen)?;
+        let columns = self.parse_comma_separated(Parser::parse_ident)?;
+        self.next_except(TokenType::RParen)?;
+        self.next_except(TokenType::RParen)?;
+
+        Ok(ast::From::Unpivot {
+            table: Box::new(table),
+            value,
+            name,
+            columns,
+            alias: self.parse_alias()?,
+        })
     }
 
-    fn parse_table_reference(&mut self) -> Result<ast::From> {
-        if self.next_if_token(TokenType::LParen).is_some() {
+    fn parse_table_factor(&mut self) -> Result<ast::From> {
+        let lateral = self.next_if_token(TokenType::Keyword(Keyword::Lateral)).is_some();
+        if lateral {
+            self.next_except(TokenType::LParen)?;
+        }
+        if lateral || self.next_if_token(TokenType::LParen).is_some() {
+            if !lateral && self.next_if_token(TokenType::Keyword(Keyword::Values)).is_some() {
+                return self.parse_values_table();
+            }
+            // a parenthesized join tree, `a JOIN (b JOIN c ON ...) ON ...`
+            if !lateral && self.peek()?.token_type != TokenType::Keyword(Keyword::Select) {
+                let table = self.parse_joined_table()?;
+                self.next_except(TokenType::RParen)?;
+                return Ok(table);
+            }
             self.next_except(TokenType::Keyword(Keyword::Select))?;
-            let subquery = self.parse_select_statement()?;
+            let subquery = self
+                .parse_select_statement()
+                .and_then(|query| self.parse_set_operations(query))?;
             self.next_except(TokenType::RParen)?;
+            let (alias, columns) = self.parse_table_alias()?;
 
             return Ok(ast::From::SubQuery {
                 query: Box::new(subquery),
-                alias: self.parse_alias()?,
+                alias,
+                columns,
+                lateral,
             });
         }
 
-        let mut table_name = self.next_token().map(|i| i.literal)?;
+        let token = self.next_token()?;
+        let mut idents = vec![match token.token_type {
+            // a file read as a table, `FROM './data.csv'`
+            TokenType::String => Ident {
+                value: token.literal,
+                quote_style: Some('\''),
+            },
+            _ => ident_from_token(token),
+        }];
+        while self.next_if_token(TokenType::Period).is_some() {
+            idents.push(self.parse_ident_part()?);
+        }
+        let table_name = ObjectName(idents);
         let mut is_table_function = false;
         let mut args = Vec::new();
 
-        while let Some(preiod) = self.next_if_token(TokenType::Period) {
-            table_name.push_str(&preiod.literal);
-            table_name.push_str(&self.next_ident()?);
-        }
-
         // parse table function
         if self.next_if_token(TokenType::LParen).is_some() {
             is_table_function = true;
@@ -742,14 +1367,14 @@ impl<'a> Parser<'a> {
         let alias = self.parse_alias()?;
 
         self.add_relation_table(TableInfo {
-            name: table_name.clone(),
+            name: table_name.value(),
             alias: alias.clone(),
             args: args.clone(),
         });
 
         let table = if is_table_function {
             ast::From::TableFunction {
-                name: table_name,
+                name: table_name.value(),
                 args,
                 alias,
             }
@@ -760,14 +1385,108 @@ impl<'a> Parser<'a> {
             }
         };
 
+        if !is_table_function && self.next_if_token(TokenType::Keyword(Keyword::Tablesample)).is_some() {
+            return Ok(ast::From::TableSample {
+                table: Box::new(table),
+                sample: self.parse_table_sample()?,
+            });
+        }
+
         Ok(table)
     }
 
+    /// `BERNOULLI | SYSTEM (percent) [REPEATABLE (seed)]` after TABLESAMPLE
+    fn parse_table_sample(&mut self) -> Result<ast::TableSample> {
+        let method = if self.next_if_ident("bernoulli").is_some() {
+            ast::SampleMethod::Bernoulli
+        } else if self.next_if_ident("system").is_some() {
+            ast::SampleMethod::System
+        } else {
+            return Err(Error::UnexpectedToken(self.next_token()?));
+        };
+
+        self.next_except(TokenType::LParen)?;
+        let percent = self.parse_expression(0)?;
+        self.next_except(TokenType::RParen)?;
+
+        let mut seed = None;
+        if self.next_if_ident("repeatable").is_some() {
+            self.next_except(TokenType::LParen)?;
+            seed = Some(self.parse_expression(0)?);
+            self.next_except(TokenType::RParen)?;
+        }
+
+        Ok(ast::TableSample { method, percent, seed })
+    }
+
+    /// `LIMIT count` and `OFFSET start` in any order, or the standard
+    /// `[OFFSET start {ROW | ROWS}] FETCH {FIRST | NEXT} count {ROW | ROWS} ONLY`
+    fn parse_limit_offset(&mut self) -> Result<(Option<Expression>, Option<Expression>)> {
+        let mut limit = None;
+        let mut offset = None;
+
+        for _ in 0..2 {
+            if limit.is_none() && self.next_if_token(TokenType::Keyword(Keyword::Limit)).is_some() {
+                limit = Some(self.parse_expression(0)?);
+            }
+
+            if offset.is_none() && self.next_if_token(TokenType::Keyword(Keyword::Offset)).is_some() {
+                offset = Some(self.parse_expression(0)?);
+                self.next_if_rows();
+            }
+
+            if limit.is_none() && self.next_if_token(TokenType::Keyword(Keyword::Fetch)).is_some() {
+                if self.next_if_ident("first").is_none() && self.next_if_ident("next").is_none() {
+                    return Err(Error::UnexpectedToken(self.next_token()?));
+                }
+                limit = Some(self.parse_expression(0)?);
+                if !self.next_if_rows() {
+                    return Err(Error::UnexpectedToken(self.next_token()?));
+                }
+                if self.next_if_ident("only").is_none() {
+                    return Err(Error::UnexpectedToken(self.next_token()?));
+                }
+            }
+        }
+
+        Ok((limit, offset))
+    }
+
+    fn next_if_rows(&mut self) -> bool {
+        self.next_if_token(TokenType::Keyword(Keyword::Row))
+            .or_else(|| self.next_if_token(TokenType::Keyword(Keyword::Rows)))
+            .is_some()
+    }
+
+    /// `(VALUES ...) [AS alias [(column, ...)]]`, the opening parenthesis and VALUES are consumed
+    fn parse_values_table(&mut self) -> Result<ast::From> {
+        let values = self.parse_values()?;
+        self.next_except(TokenType::RParen)?;
+        let (alias, columns) = self.parse_table_alias()?;
+
+        Ok(ast::From::Values { values, alias, columns })
+    }
+
+    /// `[AS] alias [(column, ...)]` of a derived table
+    fn parse_table_alias(&mut self) -> Result<(Option<String>, Vec<Ident>)> {
+        let alias = self.parse_alias()?;
+        let mut columns = vec![];
+        if alias.is_some() && self.next_if_token(TokenType::LParen).is_some() {
+            columns = self.parse_comma_separated(Parser::parse_ident)?;
+            self.next_except(TokenType::RParen)?;
+        }
+
+        Ok((alias, columns))
+    }
+
     fn parse_alias(&mut self) -> Result<Option<String>> {
         if self.next_if_token(TokenType::Keyword(Keyword::As)).is_some() {
             self.next_ident().map(Some)
-        } else if let Some(ident) = self.next_if_token(TokenType::Ident) {
-            Ok(Some(ident.literal))
+        } else if matches!(
+            self.lexer.peek().map(|t| &t.token_type),
+            Some(TokenType::Ident | TokenType::QuotedIdent(_))
+        ) {
+            Ok(Some(self.lexer.next_token().literal))
         } else {
             Ok(None)
         }
@@ -779,7 +1498,10 @@ impl<'a> Parser<'a> {
         if self.next_if_token(TokenType::Keyword(Keyword::Select)).is_some() {
             Ok(Expression::InSubQuery {
                 field: Box::new(lhs),
-                query: Box::new(self.parse_select_statement()?),
+                query: Box::new(
+                    self.parse_select_statement()
+                        .and_then(|query| self.parse_set_operations(query))?,
+                ),
                 negated,
             })
         } else {
@@ -799,40 +1521,85 @@ impl<'a> Parser<'a> {
     fn parse_data_type(&mut self) -> Result<DataType> {
         let token = self.next_token()?;
         match token.token_type {
-            TokenType::String | TokenType::Keyword(Keyword::VarChar) => Ok(DataType::String),
+            TokenType::String | TokenType::Keyword(Keyword::VarChar) => {
+                // arrow strings are unbounded, the length is accepted but not enforced
+                self.parse_optional_type_length()?;
+                Ok(DataType::String)
+            }
             TokenType::Int | TokenType::Keyword(Keyword::Int) | TokenType::Keyword(Keyword::Integer) => {
                 Ok(DataType::Integer)
             }
             TokenType::Keyword(Keyword::SmallInt) => Ok(DataType::Int16),
             TokenType::Keyword(Keyword::BigInt) => Ok(DataType::Int64),
-            TokenType::Float | TokenType::Keyword(Keyword::Double) => Ok(DataType::Float),
+            TokenType::Keyword(Keyword::Double) => {
+                if token.literal.eq_ignore_ascii_case("double") {
+                    self.next_if_ident("precision");
+                }
+                Ok(DataType::Float)
+            }
+            TokenType::Float => Ok(DataType::Float),
             TokenType::Keyword(Keyword::Bool) | TokenType::Keyword(Keyword::Boolean) => Ok(DataType::Boolean),
             TokenType::Keyword(Keyword::Date) => Ok(DataType::Date),
-            TokenType::Keyword(Keyword::Decimal) => {
-                let (precision, scale) = if self.next_if_token(TokenType::LParen).is_some() {
-                    let precision = self
-                        .next_token()?
-                        .literal
-                        .parse()
-                        .map_err(|e| Error::ParseIntError(e, token.clone()))?;
-                    self.next_except(TokenType::Comma)?;
-                    let scale = self
-                        .next_token()?
-                        .literal
-                        .parse()
-                        .map_err(|e| Error::ParseIntError(e, token.clone()))?;
-                    self.next_except(TokenType::RParen)?;
-                    (Some(precision), Some(scale))
-                } else {
-                    (None, None)
-                };
-                Ok(DataType::Decimal(precision, scale))
-            }
-            _ => Err(Error::ParserError(format!(
-                "[parse_data_type] unexpected token {:?}",
-                token
-            ))),
+            TokenType::Keyword(Keyword::Timestamp) | TokenType::Keyword(Keyword::Datetime) => Ok(DataType::Timestamp),
+            TokenType::Keyword(Keyword::Decimal) => self.parse_decimal_type(&token),
+            // the type names which are not keywords, so they can still be used as identifiers
+            TokenType::Ident => match token.literal.to_lowercase().as_str() {
+                "text" | "string" => Ok(DataType::String),
+                "char" | "character" => {
+                    self.next_if_ident("varying");
+                    self.parse_optional_type_length()?;
+                    Ok(DataType::String)
+                }
+                "tinyint" => Ok(DataType::Int8),
+                "real" => Ok(DataType::Float32),
+                "numeric" => self.parse_decimal_type(&token),
+                "time" => Ok(DataType::Time),
+                _ => Err(Error::UnKnownDataType(token)),
+            },
+            _ => Err(Error::UnKnownDataType(token)),
+        }
+    }
+
+    /// `DECIMAL [(precision [, scale])]`
+    fn parse_decimal_type(&mut self, token: &Token) -> Result<DataType> {
+        if self.next_if_token(TokenType::LParen).is_none() {
+            return Ok(DataType::Decimal(None, None));
+        }
+
+        let precision = self
+            .next_token()?
+            .literal
+            .parse()
+            .map_err(|e| Error::ParseIntError(e, token.clone()))?;
+        let scale = if self.next_if_token(TokenType::Comma).is_some() {
+            let scale = self
+                .next_token()?
+                .literal
+                .parse()
+                .map_err(|e| Error::ParseIntError(e, token.clone()))?;
+            Some(scale)
+        } else {
+            None
+        };
+        self.next_except(TokenType::RParen)?;
+
+        Ok(DataType::Decimal(Some(precision), scale))
+    }
+
+    /// the optional `(length)` of a character type
+    fn parse_optional_type_length(&mut self) -> Result<Option<u64>> {
+        if self.next_if_token(TokenType::LParen).is_none() {
+            return Ok(None);
         }
+
+        let token = self.next_except(TokenType::Int)?;
+        let length = token
+            .literal
+            .parse()
+            .map_err(|e| Error::ParseIntError(e, token.clone()))?;
+        self.next_except(TokenType::RParen)?;
+
+        Ok(Some(length))
     }
 
     fn parse_date_time_field(&mut self) -> Result<DateTimeField> {
@@ -851,6 +1618,43 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// `INTERVAL 'value' [leading_field [TO last_field]]`
+    fn parse_interval(&mut self) -> Result<Expression> {
+        let token = self.next_token()?;
+        if token.token_type != TokenType::String {
+            return Err(Error::UnexpectedToken(token));
+        }
+
+        let leading_field = self.next_if_date_time_field()?;
+        let last_field = if leading_field.is_some() && self.next_if_token(TokenType::Keyword(Keyword::To)).is_some() {
+            Some(self.parse_date_time_field()?)
+        } else {
+            None
+        };
+
+        Ok(Expression::Interval {
+            value: token.literal,
+            leading_field,
+            last_field,
+        })
+    }
+
+    fn next_if_date_time_field(&mut self) -> Result<Option<DateTimeField>> {
+        let is_field = self.lexer.peek().is_some_and(|t| {
+            matches!(
+                t.token_type,
+                TokenType::Keyword(
+                    Keyword::Year | Keyword::Month | Keyword::Day | Keyword::Hour | Keyword::Minute | Keyword::Second
+                )
+            )
+        });
+        if !is_field {
+            return Ok(None);
+        }
+
+        self.parse_date_time_field().map(Some)
+    }
+
     fn parse_expression(&mut self, precedence: u8) -> Result<Expression> {
         let mut lhs = if let Some(prefix) = self.next_if_operator::<PrefixOperator>(precedence) {
             prefix.build(self.parse_expression(prefix.precedence())?)
@@ -870,21 +1674,50 @@ impl<'a> Parser<'a> {
                     self.peek()?
                 )))?;
             lhs = match infix {
-                InfixOperator::Like => Expression::Like {
+                InfixOperator::Like | InfixOperator::ILike => Expression::Like {
                     negated,
+                    case_insensitive: matches!(infix, InfixOperator::ILike),
                     left: Box::new(lhs),
                     right: self.parse_expression(infix.precedence()).map(Box::new)?,
+                    escape_char: if self.next_if_token(TokenType::Keyword(Keyword::Escape)).is_some() {
+                        Some(self.parse_literal_char()?)
+                    } else {
+                        None
+                    },
                 },
                 InfixOperator::In => self.parse_in_expr(lhs, negated)?,
+                InfixOperator::Between => {
+                    // the bounds bind tighter than the `AND` between them
+                    let low = self.parse_expression(infix.precedence()).map(Box::new)?;
+                    self.next_except(TokenType::Keyword(Keyword::And))?;
+                    Expression::Between {
+                        expr: Box::new(lhs),
+                        negated,
+                        low,
+                        high: self.parse_expression(infix.precedence()).map(Box::new)?,
+                    }
+                }
                 InfixOperator::DoubleColon => self.parse_data_type().map(|dt| Expression::Cast {
                     expr: Box::new(lhs),
                     data_type: dt,
                 })?,
                 InfixOperator::Is => {
-                    if self.parse_keywords(&[Keyword::Null]) {
-                        Expression::IsNull(Box::new(lhs))
-                    } else if self.parse_keywords(&[Keyword::Not, Keyword::Null]) {
-                        Expression::IsNotNull(Box::new(lhs))
+                    let negated = self.next_if_token(TokenType::Keyword(Keyword::Not)).is_some();
+                    if self.next_if_token(TokenType::Keyword(Keyword::Null)).is_some() {
+                        if negated {
+                            Expression::IsNotNull(Box::new(lhs))
+                        } else {
+                            Expression::IsNull(Box::new(lhs))
+                        }
+                    } else if self.next_if_token(TokenType::Keyword(Keyword::Distinct)).is_some() {
+                        self.next_except(TokenType::Keyword(Keyword::From))?;
+                        // the right side binds like the other comparison operators
+                        let rhs = self.parse_expression(InfixOperator::Eq.precedence()).map(Box::new)?;
+                        Expression::BinaryOperator(if negated {
+                            ast::BinaryOperator::IsNotDistinctFrom(Box::new(lhs), rhs)
+                        } else {
+                            ast::BinaryOperator::IsDistinctFrom(Box::new(lhs), rhs)
+                        })
                     } else {
                         return Err(Error::ParserError(format!(
                             "[parse_expression] unexpected token {:?}",
@@ -919,6 +1752,20 @@ impl<'a> Parser<'a> {
                     expr: Box::new(expr),
                 })
             }
+            TokenType::Keyword(Keyword::Case) => self.parse_case_expr(),
+            TokenType::Keyword(Keyword::Interval) => self.parse_interval(),
+            TokenType::Keyword(Keyword::Cast) => {
+                self.next_except(TokenType::LParen)?;
+                let expr = self.parse_expression(0)?;
+                self.next_except(TokenType::Keyword(Keyword::As))?;
+                let data_type = self.parse_data_type()?;
+                self.next_except(TokenType::RParen)?;
+
+                Ok(Expression::Cast {
+                    expr: Box::new(expr),
+                    data_type,
+                })
+            }
 
             TokenType::Asterisk => Ok(ast::Expression::Identifier("*".into())),
             TokenType::Float => literal
@@ -930,6 +1777,7 @@ impl<'a> Parser<'a> {
                 .map(|i| ast::Expression::Literal(ast::Literal::Int(i)))
                 .map_err(|e| Error::ParseIntError(e, token)),
             TokenType::String => Ok(ast::Expression::Literal(ast::Literal::String(literal))),
+            TokenType::Placeholder => Ok(ast::Expression::Placeholder(literal)),
             TokenType::Keyword(Keyword::True) => Ok(ast::Expression::Literal(ast::Literal::Boolean(true))),
             TokenType::Keyword(Keyword::False) => Ok(ast::Expression::Literal(ast::Literal::Boolean(false))),
             TokenType::Keyword(Keyword::Null) => Ok(ast::Expression::Literal(ast::Literal::Null)),
@@ -957,20 +1805,90 @@ impl<'a> Parser<'a> {
                 }
                 Ok(ast::Expression::Array(list))
             }
-            TokenType::Ident => {
+            // `TIME '12:00:00'`, time is not a keyword so it can still be used as an identifier
+            TokenType::Ident
+                if literal.eq_ignore_ascii_case("time") && self.peek()?.token_type == TokenType::String =>
+            {
+                Ok(Expression::TypedString {
+                    data_type: DataType::Time,
+                    value: self.next_token()?.literal,
+                })
+            }
+            // `DATE '2021-01-01'`, otherwise date is an identifier
+            TokenType::Keyword(keyword @ (Keyword::Date | Keyword::Timestamp))
+                if self.peek()?.token_type == TokenType::String =>
+            {
+                Ok(Expression::TypedString {
+                    data_type: if keyword == Keyword::Date {
+                        DataType::Date
+                    } else {
+                        DataType::Timestamp
+                    },
+                    value: self.next_token()?.literal,
+                })
+            }
+            _ if token.token_type.is_identifier() => {
                 // parse function
                 if self.next_if_token(TokenType::LParen).is_some() {
+                    let distinct = self.next_if_token(TokenType::Keyword(Keyword::Distinct)).is_some();
                     let mut args = Vec::new();
                     while self.next_if_token(TokenType::RParen).is_none() {
                         args.push(self.parse_expression(0)?);
                         self.next_if_token(TokenType::Comma);
                     }
-                    Ok(ast::Expression::Function(literal, args))
+                    let function = if distinct {
+                        if args.is_empty() {
+                            return Err(Error::ParserError(format!(
+                                "{}(DISTINCT) requires an argument",
+                                literal
+                            )));
+                        }
+                        ast::Expression::DistinctFunction(literal, args)
+                    } else if self.next_if_ident("within").is_some() {
+                        // `WITHIN GROUP (ORDER BY expr)`, within is not a keyword so it can still be used as an
+                        // identifier
+                        self.next_except(TokenType::Keyword(Keyword::Group))?;
+                        self.next_except(TokenType::LParen)?;
+                        self.next_except(TokenType::Keyword(Keyword::Order))?;
+                        let mut order_by = self.parse_order_by()?;
+                        self.next_except(TokenType::RParen)?;
+                        if order_by.len() != 1 {
+                            return Err(Error::ParserError(format!(
+                                "WITHIN GROUP of {} requires exactly one ORDER BY expression",
+                                literal
+                            )));
+                        }
+                        ast::Expression::WithinGroup {
+                            name: literal,
+                            args,
+                            order_by: Box::new(order_by.remove(0)),
+                        }
+                    } else if self.next_if_token(TokenType::Keyword(Keyword::Over)).is_some() {
+                        return Ok(ast::Expression::WindowFunction {
+                            name: literal,
+                            args,
+                            over: self.parse_window_spec()?,
+                        });
+                    } else {
+                        ast::Expression::Function(literal, args)
+                    };
+                    // `FILTER (WHERE condition)`, filter is not a keyword so it can still be used as an identifier
+                    if self.next_if_ident("filter").is_some() {
+                        self.next_except(TokenType::LParen)?;
+                        self.next_except(TokenType::Keyword(Keyword::Where))?;
+                        let filter = self.parse_expression(0)?;
+                        self.next_except(TokenType::RParen)?;
+                        return Ok(ast::Expression::AggregateFilter {
+                            function: Box::new(function),
+                            filter: Box::new(filter),
+                        });
+                    }
+                    Ok(function)
                 } else {
-                    let mut idents: Vec<Ident> = vec![literal.into()];
+                    let mut idents: Vec<Ident> = vec![ident_from_token(token)];
 
                     while self.next_if_token(TokenType::Period).is_some() {
-                        idents.push(self.next_ident().map(|s| s.into())?);
+                        idents.push(self.parse_ident_part()?);
                     }
                     if idents.len() > 1 {
                         Ok(ast::Expression::CompoundIdentifier(idents))
@@ -983,13 +1901,132 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_ident(&mut self) -> Result<Ident> {
-        self.next_except(TokenType::Ident).map(|ident| Ident {
-            value: ident.literal,
-            quote_style: None,
+    fn parse_case_expr(&mut self) -> Result<Expression> {
+        let operand = if self.peek()?.token_type != TokenType::Keyword(Keyword::When) {
+            Some(Box::new(self.parse_expression(0)?))
+        } else {
+            None
+        };
+
+        let mut conditions = vec![];
+        while self.next_if_token(TokenType::Keyword(Keyword::When)).is_some() {
+            let condition = self.parse_expression(0)?;
+            self.next_except(TokenType::Keyword(Keyword::Then))?;
+            conditions.push((condition, self.parse_expression(0)?));
+        }
+        if conditions.is_empty() {
+            return Err(Error::ParserError(
+                "CASE expression requires at least one WHEN clause".to_owned(),
+            ));
+        }
+
+        let else_result = if self.next_if_token(TokenType::Keyword(Keyword::Else)).is_some() {
+            Some(Box::new(self.parse_expression(0)?))
+        } else {
+            None
+        };
+        self.next_except(TokenType::Keyword(Keyword::End))?;
+
+        Ok(Expression::Case {
+            operand,
+            conditions,
+            else_result,
+        })
+    }
+
+    fn parse_window_spec(&mut self) -> Result<WindowSpec> {
+        self.next_except(TokenType::LParen)?;
+
+        let partition_by = if self.next_if_token(TokenType::Keyword(Keyword::Partition)).is_some() {
+            self.next_except(TokenType::Keyword(Keyword::By))?;
+            self.parse_comma_separated(|parser| parser.parse_expression(0))?
+        } else {
+            vec![]
+        };
+        let order_by = if self.next_if_token(TokenType::Keyword(Keyword::Order)).is_some() {
+            self.parse_order_by()?
+        } else {
+            vec![]
+        };
+        let window_frame = self.parse_window_frame()?;
+
+        self.next_except(TokenType::RParen)?;
+
+        Ok(WindowSpec {
+            partition_by,
+            order_by,
+            window_frame,
         })
     }
 
+    fn parse_window_frame(&mut self) -> Result<Option<WindowFrame>> {
+        let units = if self.next_if_token(TokenType::Keyword(Keyword::Rows)).is_some() {
+            WindowFrameUnits::Rows
+        } else if self.next_if_token(TokenType::Keyword(Keyword::Range)).is_some() {
+            WindowFrameUnits::Range
+        } else {
+            return Ok(None);
+        };
+
+        let (start_bound, end_bound) = if self.next_if_token(TokenType::Keyword(Keyword::Between)).is_some() {
+            let start_bound = self.parse_window_frame_bound()?;
+            self.next_except(TokenType::Keyword(Keyword::And))?;
+            (start_bound, Some(self.parse_window_frame_bound()?))
+        } else {
+            (self.parse_window_frame_bound()?, None)
+        };
+
+        Ok(Some(WindowFrame {
+            units,
+            start_bound,
+            end_bound,
+        }))
+    }
+
+    fn parse_window_frame_bound(&mut self) -> Result<WindowFrameBound> {
+        let token = self.next_token()?;
+        let offset = match token.token_type {
+            TokenType::Keyword(Keyword::Current) => {
+                self.next_except(TokenType::Keyword(Keyword::Row))?;
+                return Ok(WindowFrameBound::CurrentRow);
+            }
+            TokenType::Keyword(Keyword::Unbounded) => None,
+            TokenType::Int => Some(
+                token
+                    .literal
+                    .parse::<u64>()
+                    .map_err(|e| Error::ParseIntError(e, token.clone()))?,
+            ),
+            _ => return Err(Error::UnexpectedToken(token)),
+        };
+
+        let token = self.next_token()?;
+        match token.token_type {
+            TokenType::Keyword(Keyword::Preceding) => Ok(WindowFrameBound::Preceding(offset)),
+            TokenType::Keyword(Keyword::Following) => Ok(WindowFrameBound::Following(offset)),
+            _ => Err(Error::UnexpectedToken(token)),
+        }
+    }
+
+    fn parse_ident(&mut self) -> Result<Ident> {
+        let token = self.lexer.next_token();
+        if token.token_type.is_identifier() {
+            return Ok(ident_from_token(token));
+        }
+        Err(Error::UnexpectedToken(token))
+    }
+
+    /// parse a part of compound identifier, keywords and `*` are allowed here, e.g. `t.*` or `t.date`
+    fn parse_ident_part(&mut self) -> Result<Ident> {
+        let token = self.lexer.next_token();
+        match token.token_type {
+            TokenType::Ident | TokenType::QuotedIdent(_) => Ok(ident_from_token(token)),
+            TokenType::Asterisk | TokenType::Keyword(_) => Ok(token.literal.into()),
+            TokenType::EOF => Err(Error::UnexpectedEOF(token)),
+            _ => Err(Error::UnexpectedToken(token)),
+        }
+    }
+
     fn parse_literal_string(&mut self) -> Result<String> {
         self.next_except(TokenType::String).map(|s| s.literal)
     }
@@ -1009,6 +2046,17 @@ impl<'a> Parser<'a> {
         Ok(Assignment { target, value })
     }
 
+    /// a table name qualified by an optional schema and catalog, `[[catalog.]schema.]table`
+    fn parse_table_name(&mut self) -> Result<String> {
+        let mut name = self.next_ident()?;
+        while self.next_if_token(TokenType::Period).is_some() {
+            name.push('.');
+            name.push_str(&self.next_ident()?);
+        }
+
+        Ok(name)
+    }
+
     fn parse_object_name(&mut self) -> Result<ObjectName> {
         let mut idents = vec![];
         loop {
@@ -1044,7 +2092,7 @@ impl<'a> Parser<'a> {
                 id: None,
                 value: Expression::Literal(ast::Literal::String(token.literal)),
             }),
-            TokenType::Ident | TokenType::Keyword(Keyword::Header) => {
+            TokenType::Ident | TokenType::Keyword(_) if token.token_type.is_identifier() => {
                 self.next_except(TokenType::Eq)?;
                 Ok(FunctionArgument {
                     id: Some(Ident {
@@ -1058,17 +2106,8 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_keywords(&mut self, keywords: &[Keyword]) -> bool {
-        for &keyword in keywords {
-            if self.next_if_token(TokenType::Keyword(keyword)).is_some() {
-                return true;
-            }
-        }
-        false
-    }
-
     fn next_token(&mut self) -> Result<Token> {
-        let token = self.lexer.next();
+        let token = self.lexer.next_token();
         match token.token_type {
             TokenType::EOF => Err(Error::UnexpectedEOF(token)),
             TokenType::ILLIGAL => Err(Error::UnexpectedToken(token)),
@@ -1077,57 +2116,100 @@ impl<'a> Parser<'a> {
     }
 
     fn next_except(&mut self, except: TokenType) -> Result<Token> {
-        let token = self.lexer.next();
+        let token = self.lexer.next_token();
         if token.token_type == except {
             return Ok(token);
         }
 
-        Err(Error::UnexpectedToken(token))
+        Err(ParserError::expected(token, vec![except]).into())
     }
 
     fn next_ident(&mut self) -> Result<String> {
-        let token = self.lexer.next();
+        let token = self.lexer.next_token();
         match token.token_type {
-            TokenType::Asterisk | TokenType::Ident | TokenType::Keyword(_) => Ok(token.literal),
-            TokenType::EOF => Err(Error::UnexpectedEOF(token)),
-            _ => Err(Error::UnexpectedToken(token)),
+            TokenType::Asterisk | TokenType::Ident | TokenType::QuotedIdent(_) | TokenType::Keyword(_) => {
+                Ok(token.literal)
+            }
+            _ => Err(ParserError::expected(token, vec![TokenType::Ident]).into()),
         }
     }
 
     fn next_if_operator<O: Operator>(&mut self, precedence: u8) -> Option<O> {
+        let dialect = self.lexer.dialect();
         self.lexer
             .peek()
-            .and_then(|t| O::from(t))
+            .and_then(|t| O::from(t, dialect))
             .filter(|op| op.precedence() >= precedence)?;
-        O::from(&self.lexer.next())
+        O::from(&self.lexer.next_token(), dialect)
     }
 
     fn get_nexr_precedence(&mut self) -> u8 {
+        let dialect = self.lexer.dialect();
         self.lexer
             .peek()
-            .and_then(|t| Operator::from(t))
+            .and_then(|t| Operator::from(t, dialect))
             .map(|op: InfixOperator| op.precedence())
             .unwrap_or_default()
     }
 
+    /// When parsing with recovery, records the error of `result` and skips the tokens up to the next clause of the
+    /// `SELECT` or one of `sync` at the same nesting level, `None` if the error is recovered from. Otherwise the
+    /// error is returned as is.
+    fn recover<T>(&mut self, result: Result<T>, sync: &[TokenType]) -> Result<Option<T>> {
+        let error = match result {
+            Ok(value) => return Ok(Some(value)),
+            Err(e) => e,
+        };
+        let Some(errors) = self.errors.as_mut() else {
+            return Err(error);
+        };
+        // the offending token may be where parsing resumes, eg. `FROM` of `SELECT a + FROM t`
+        if let Some(token) = error.token().filter(|t| is_recovery_point(&t.token_type, sync)) {
+            self.lexer.push_back(token.clone());
+        }
+        errors.push(error);
+
+        let mut depth = 0;
+        while let Some(token) = self.lexer.peek() {
+            match token.token_type {
+                TokenType::LParen => depth += 1,
+                TokenType::RParen if depth > 0 => depth -= 1,
+                ref token_type if depth == 0 && is_recovery_point(token_type, sync) => break,
+                _ => {}
+            }
+            self.lexer.next_token();
+        }
+
+        Ok(None)
+    }
+
+    /// consume the next token if it is the given non-keyword word
+    fn next_if_ident(&mut self, ident: &str) -> Option<Token> {
+        self.lexer
+            .peek()
+            .filter(|t| t.token_type == TokenType::Ident && t.literal.eq_ignore_ascii_case(ident))?;
+        Some(self.lexer.next_token())
+    }
+
     fn next_if_token(&mut self, token: TokenType) -> Option<Token> {
         self.lexer.peek().filter(|t| t.token_type == token)?;
-        Some(self.lexer.next())
+        Some(self.lexer.next_token())
     }
 
     fn peek(&mut self) -> Result<&Token> {
         let localtion = self.lexer.location();
-        self.lexer.peek().ok_or(Error::UnexpectedEOF(Token {
-            token_type: TokenType::EOF,
-            literal: "".to_owned(),
-            location: localtion,
-        }))
+        self.lexer.peek().ok_or(Error::UnexpectedEOF(Token::new(
+            TokenType::EOF,
+            "".to_owned(),
+            localtion,
+        )))
     }
 }
 
 impl<'a> Parser<'a> {
     fn add_relation_table(&mut self, table: TableInfo) {
-        if !self.tables.contains(&table) && !self.ctes.contains(&table) {
+        // a CTE may be referenced with an alias, so only compare the name
+        if !self.tables.contains(&table) && !self.ctes.iter().any(|cte| cte.name == table.name) {
             self.tables.push(table);
         }
     }
@@ -1140,7 +2222,7 @@ impl<'a> Parser<'a> {
 }
 
 trait Operator: Sized {
-    fn from(token: &Token) -> Option<Self>;
+    fn from(token: &Token, dialect: &dyn Dialect) -> Option<Self>;
 
     fn precedence(&self) -> u8;
 }
@@ -1149,24 +2231,26 @@ enum PrefixOperator {
     Plus,
     Minus,
     Not,
-    Date,
-    Timestamp,
+    /// `NOT expr`, binds looser than the comparisons unlike `!`
+    KeywordNot,
 }
 
 impl Operator for PrefixOperator {
-    fn from(token: &Token) -> Option<Self> {
+    fn from(token: &Token, _dialect: &dyn Dialect) -> Option<Self> {
         match token.token_type {
             TokenType::Plus => Some(PrefixOperator::Plus),
             TokenType::Minus => Some(PrefixOperator::Minus),
             TokenType::Bang => Some(PrefixOperator::Not),
-            TokenType::Keyword(Keyword::Date) => Some(PrefixOperator::Date),
-            TokenType::Keyword(Keyword::Timestamp) => Some(PrefixOperator::Timestamp),
+            TokenType::Keyword(Keyword::Not) => Some(PrefixOperator::KeywordNot),
             _ => None,
         }
     }
 
     fn precedence(&self) -> u8 {
-        9
+        match self {
+            PrefixOperator::KeywordNot => 2,
+            _ => 9,
+        }
     }
 }
 
@@ -1181,22 +2265,32 @@ impl PrefixOperator {
                 op: ast::UnaryOperator::Minus,
                 expr: Box::new(rhs),
             },
-            PrefixOperator::Not => Expression::UnaryOperator {
+            PrefixOperator::Not | PrefixOperator::KeywordNot => Expression::UnaryOperator {
                 op: ast::UnaryOperator::Not,
                 expr: Box::new(rhs),
             },
-            PrefixOperator::Date => Expression::TypedString {
-                data_type: DataType::Date,
-                value: rhs.to_string(),
-            },
-            PrefixOperator::Timestamp => Expression::TypedString {
-                data_type: DataType::Timestamp,
-                value: rhs.to_string(),
-            },
         }
     }
 }
 
+/// whether parsing can resume at a token after an error, the end of the statement, the end of a subquery, a clause of
+/// a `SELECT` or one of `sync`
+fn is_recovery_point(token_type: &TokenType, sync: &[TokenType]) -> bool {
+    match token_type {
+        TokenType::EOF | TokenType::Semicolon | TokenType::RParen => true,
+        TokenType::Keyword(
+            Keyword::From
+            | Keyword::Where
+            | Keyword::Group
+            | Keyword::Having
+            | Keyword::Order
+            | Keyword::Limit
+            | Keyword::Offset,
+        ) => true,
+        token_type => sync.contains(token_type),
+    }
+}
+
 #[derive(Debug)]
 enum InfixOperator {
     Add,
@@ -1215,10 +2309,13 @@ enum InfixOperator {
     DoubleColon,
     Is,
     Like,
+    ILike,
+    Between,
+    StringConcat,
 }
 
 impl Operator for InfixOperator {
-    fn from(token: &Token) -> Option<Self> {
+    fn from(token: &Token, dialect: &dyn Dialect) -> Option<Self> {
         match token.token_type {
             TokenType::Plus => Some(InfixOperator::Add),
             TokenType::Minus => Some(InfixOperator::Sub),
@@ -1230,12 +2327,16 @@ impl Operator for InfixOperator {
             TokenType::Lte => Some(InfixOperator::Lte),
             TokenType::Eq => Some(InfixOperator::Eq),
             TokenType::NotEq => Some(InfixOperator::NotEq),
-            TokenType::DoubleColon => Some(InfixOperator::DoubleColon),
+            TokenType::DoubleColon if dialect.supports_double_colon_cast() => Some(InfixOperator::DoubleColon),
+            TokenType::DoublePipe if dialect.pipes_as_concat() => Some(InfixOperator::StringConcat),
+            TokenType::DoublePipe => Some(InfixOperator::Or),
             TokenType::Keyword(Keyword::And) => Some(InfixOperator::And),
             TokenType::Keyword(Keyword::Or) => Some(InfixOperator::Or),
             TokenType::Keyword(Keyword::In) => Some(InfixOperator::In),
             TokenType::Keyword(Keyword::Is) => Some(InfixOperator::Is),
             TokenType::Keyword(Keyword::Like) => Some(InfixOperator::Like),
+            TokenType::Keyword(Keyword::ILike) => Some(InfixOperator::ILike),
+            TokenType::Keyword(Keyword::Between) => Some(InfixOperator::Between),
             _ => None,
         }
     }
@@ -1244,9 +2345,13 @@ impl Operator for InfixOperator {
         match self {
             InfixOperator::Or => 1,
             InfixOperator::And => 2,
-            InfixOperator::Eq | InfixOperator::NotEq | InfixOperator::Like => 3,
+            InfixOperator::Eq
+            | InfixOperator::NotEq
+            | InfixOperator::Like
+            | InfixOperator::ILike
+            | InfixOperator::Between => 3,
             InfixOperator::Gt | InfixOperator::Gte | InfixOperator::Lt | InfixOperator::Lte => 4,
-            InfixOperator::Add | InfixOperator::Sub => 5,
+            InfixOperator::Add | InfixOperator::Sub | InfixOperator::StringConcat => 5,
             InfixOperator::Mul | InfixOperator::Div => 6,
             InfixOperator::In => 7,
             InfixOperator::DoubleColon | InfixOperator::Is => 8,
@@ -1268,7 +2373,108 @@ impl InfixOperator {
             };
         }
 
-        build_binary_operator!(Add, Sub, Mul, Div, Gt, Gte, Lt, Lte, Eq, NotEq, And, Or)
+        build_binary_operator!(Add, Sub, Mul, Div, Gt, Gte, Lt, Lte, Eq, NotEq, And, Or, StringConcat)
+    }
+}
+
+/// How the parser binds the top level operator of an expression, so an expression is written back with the parentheses
+/// it needs to be parsed the same way
+pub(crate) struct BindingPower {
+    /// the precedence the operator is parsed with, `u8::MAX` if the expression is not an operator
+    pub precedence: u8,
+    /// the smallest precedence of a left operand parsed without parentheses
+    pub left: u8,
+    /// the smallest precedence of a right operand parsed without parentheses
+    pub right: u8,
+}
+
+impl BindingPower {
+    /// a left associative infix operator, a right operand of the same precedence needs parentheses
+    fn infix(precedence: u8) -> Self {
+        Self {
+            precedence,
+            left: precedence,
+            right: precedence + 1,
+        }
+    }
+
+    /// a prefix operator such as `-a` or a postfix one such as `a IS NULL`
+    fn unary(precedence: u8) -> Self {
+        Self {
+            precedence,
+            left: precedence + 1,
+            right: precedence + 1,
+        }
+    }
+}
+
+impl std::convert::From<&ast::BinaryOperator> for BindingPower {
+    fn from(op: &ast::BinaryOperator) -> Self {
+        use ast::BinaryOperator::*;
+
+        let infix = match op {
+            Or(..) => InfixOperator::Or,
+            And(..) => InfixOperator::And,
+            Eq(..) => InfixOperator::Eq,
+            NotEq(..) => InfixOperator::NotEq,
+            Gt(..) => InfixOperator::Gt,
+            Gte(..) => InfixOperator::Gte,
+            Lt(..) => InfixOperator::Lt,
+            Lte(..) => InfixOperator::Lte,
+            Add(..) => InfixOperator::Add,
+            Sub(..) => InfixOperator::Sub,
+            Mul(..) => InfixOperator::Mul,
+            Div(..) => InfixOperator::Div,
+            StringConcat(..) => InfixOperator::StringConcat,
+            // `IS` binds the left operand, the right one is parsed like the right operand of a comparison
+            IsDistinctFrom(..) | IsNotDistinctFrom(..) => {
+                let comparison = InfixOperator::Eq.precedence();
+                return Self {
+                    precedence: comparison,
+                    left: InfixOperator::Is.precedence() + 1,
+                    right: comparison + 1,
+                };
+            }
+        };
+        Self::infix(infix.precedence())
+    }
+}
+
+/// the binding power of the top level operator of an expression, from the precedences of the parser's operators
+pub(crate) fn binding_power(expr: &Expression) -> BindingPower {
+    match expr {
+        Expression::BinaryOperator(op) => op.into(),
+        Expression::Like {
+            case_insensitive: false,
+            ..
+        } => BindingPower::infix(InfixOperator::Like.precedence()),
+        Expression::Like { .. } => BindingPower::infix(InfixOperator::ILike.precedence()),
+        Expression::Between { .. } => BindingPower::infix(InfixOperator::Between.precedence()),
+        Expression::InList { .. } | Expression::InSubQuery { .. } => {
+            BindingPower::unary(InfixOperator::In.precedence())
+        }
+        Expression::IsNull(_) | Expression::IsNotNull(_) => BindingPower::unary(InfixOperator::Is.precedence()),
+        Expression::UnaryOperator {
+            op: ast::UnaryOperator::Not,
+            ..
+        } => BindingPower::unary(PrefixOperator::KeywordNot.precedence()),
+        Expression::UnaryOperator { .. } => BindingPower::unary(PrefixOperator::Minus.precedence()),
+        _ => BindingPower {
+            precedence: u8::MAX,
+            left: 0,
+            right: 0,
+        },
+    }
+}
+
+fn ident_from_token(token: Token) -> Ident {
+    let quote_style = match token.token_type {
+        TokenType::QuotedIdent(q) => Some(q),
+        _ => None,
+    };
+    Ident {
+        value: token.literal,
+        quote_style,
     }
 }
 
@@ -1279,15 +2485,55 @@ mod tests {
     use super::Parser;
     use crate::ast::{
         self, Assignment, BinaryOperator, CopyOption, CopySource, CopyTarget, DateTimeField, Expression,
-        FunctionArgument, Ident, Select, SelectItem, Statement,
+        FunctionArgument, Ident, OrderByExpr, Select, SelectItem, SetOperator, Statement,
     };
     use crate::datatype::DataType;
-    use crate::error::Result;
+    use crate::dialect::{Dialect, GenericDialect, MySqlDialect, PostgreSqlDialect};
+    use crate::error::{Error, Result};
     use crate::parser::TableInfo;
+    use crate::token::TokenType;
+
+    fn order_by_expr(column: &str, order: ast::Order) -> OrderByExpr {
+        OrderByExpr {
+            expr: Expression::Identifier(column.into()),
+            order,
+            nulls_first: None,
+        }
+    }
+
+    fn assert_stmt_eq(sql: &str, stmt: Statement) {
+        let result = parse_stmt(sql).unwrap();
+        assert_eq!(result, stmt, "Runing SQL: {}", sql);
+    }
+
+    #[test]
+    fn test_nested_join() {
+        let table = |name: &str| ast::From::Table {
+            name: vec![name].into(),
+            alias: None,
+        };
+        let cross_join = |left, right| ast::From::Join {
+            left: Box::new(left),
+            right: Box::new(right),
+            constraint: ast::JoinConstraint::None,
+            join_type: ast::JoinType::Cross,
+        };
 
-    fn assert_stmt_eq(sql: &str, stmt: Statement) {
-        let result = parse_stmt(sql).unwrap();
-        assert_eq!(result, stmt, "Runing SQL: {}", sql);
+        let stmt = parse_stmt("SELECT * FROM a CROSS JOIN (b CROSS JOIN (c)) LEFT JOIN d USING (id)").unwrap();
+        let Statement::Select(select) = stmt else {
+            panic!("expected SELECT")
+        };
+        assert_eq!(
+            select.from,
+            vec![ast::From::Join {
+                left: Box::new(cross_join(table("a"), cross_join(table("b"), table("c")))),
+                right: Box::new(table("d")),
+                constraint: ast::JoinConstraint::Using(vec!["id".into()]),
+                join_type: ast::JoinType::Left,
+            }]
+        );
+
+        assert!(parse_stmt("SELECT * FROM a JOIN (b JOIN c ON b.id = c.id ON a.id = b.id").is_err());
     }
 
     #[test]
@@ -1297,18 +2543,18 @@ mod tests {
             Statement::Select(Box::new(Select {
                 with: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Join {
                     left: Box::new(ast::From::Join {
                         left: Box::new(ast::From::Table {
-                            name: "a".to_owned(),
+                            name: vec!["a"].into(),
                             alias: None,
                         }),
                         right: Box::new(ast::From::Table {
-                            name: "b".to_owned(),
+                            name: vec!["b"].into(),
                             alias: None,
                         }),
-                        on: Some(Expression::BinaryOperator(BinaryOperator::Eq(
+                        constraint: ast::JoinConstraint::On(Expression::BinaryOperator(BinaryOperator::Eq(
                             Box::new(Expression::CompoundIdentifier(vec![
                                 Ident {
                                     value: "a".to_owned(),
@@ -1333,10 +2579,10 @@ mod tests {
                         join_type: ast::JoinType::Left,
                     }),
                     right: Box::new(ast::From::Table {
-                        name: "c".to_owned(),
+                        name: vec!["c"].into(),
                         alias: None,
                     }),
-                    on: Some(Expression::BinaryOperator(BinaryOperator::Eq(
+                    constraint: ast::JoinConstraint::On(Expression::BinaryOperator(BinaryOperator::Eq(
                         Box::new(Expression::CompoundIdentifier(vec![
                             Ident {
                                 value: "c".to_owned(),
@@ -1374,23 +2620,23 @@ mod tests {
             Statement::Select(Box::new(Select {
                 with: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![
                     ast::From::Table {
-                        name: "a".to_owned(),
+                        name: vec!["a"].into(),
                         alias: None,
                     },
                     ast::From::Join {
                         left: Box::new(ast::From::Join {
                             left: Box::new(ast::From::Table {
-                                name: "b".to_owned(),
+                                name: vec!["b"].into(),
                                 alias: None,
                             }),
                             right: Box::new(ast::From::Table {
-                                name: "c".to_owned(),
+                                name: vec!["c"].into(),
                                 alias: None,
                             }),
-                            on: Some(Expression::BinaryOperator(BinaryOperator::Eq(
+                            constraint: ast::JoinConstraint::On(Expression::BinaryOperator(BinaryOperator::Eq(
                                 Box::new(Expression::CompoundIdentifier(vec![
                                     Ident {
                                         value: "a".to_owned(),
@@ -1415,10 +2661,10 @@ mod tests {
                             join_type: ast::JoinType::Left,
                         }),
                         right: Box::new(ast::From::Table {
-                            name: "d".to_owned(),
+                            name: vec!["d"].into(),
                             alias: None,
                         }),
-                        on: Some(Expression::BinaryOperator(BinaryOperator::Eq(
+                        constraint: ast::JoinConstraint::On(Expression::BinaryOperator(BinaryOperator::Eq(
                             Box::new(Expression::CompoundIdentifier(vec![
                                 Ident {
                                     value: "b".to_owned(),
@@ -1460,18 +2706,20 @@ mod tests {
             Statement::Select(Box::new(Select {
                 with: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: "users".to_owned(),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: Some(Expression::Like {
                     negated: false,
+                    case_insensitive: false,
                     left: Box::new(Expression::Identifier(Ident {
                         value: "name".to_owned(),
                         quote_style: None,
                     })),
                     right: Box::new(Expression::Literal(ast::Literal::String("%Joe%".to_owned()))),
+                    escape_char: None,
                 }),
                 group_by: None,
                 having: None,
@@ -1482,6 +2730,91 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ilike_escape() {
+        let expr = parse_expr("name NOT ILIKE 'a!%%' ESCAPE '!' AND id = 1").unwrap();
+        assert_eq!(
+            expr,
+            Expression::BinaryOperator(ast::BinaryOperator::And(
+                Box::new(Expression::Like {
+                    negated: true,
+                    case_insensitive: true,
+                    left: Box::new(Expression::Identifier("name".into())),
+                    right: Box::new(Expression::Literal(ast::Literal::String("a!%%".to_owned()))),
+                    escape_char: Some('!'),
+                }),
+                Box::new(Expression::BinaryOperator(ast::BinaryOperator::Eq(
+                    Box::new(Expression::Identifier("id".into())),
+                    Box::new(Expression::Literal(ast::Literal::Int(1))),
+                ))),
+            ))
+        );
+        assert_eq!(expr.to_string(), "name NOT ILIKE 'a!%%' ESCAPE '!' AND id = 1");
+        assert_eq!(parse_expr("name LIKE 'a%'").unwrap().to_string(), "name LIKE 'a%'");
+
+        assert!(parse_expr("name LIKE 'a%' ESCAPE '!!'").is_err());
+    }
+
+    #[test]
+    fn test_between() {
+        let expr = parse_expr("a NOT BETWEEN b + 1 AND 10 AND c").unwrap();
+        assert_eq!(
+            expr,
+            Expression::BinaryOperator(ast::BinaryOperator::And(
+                Box::new(Expression::Between {
+                    expr: Box::new(Expression::Identifier("a".into())),
+                    negated: true,
+                    low: Box::new(Expression::BinaryOperator(ast::BinaryOperator::Add(
+                        Box::new(Expression::Identifier("b".into())),
+                        Box::new(Expression::Literal(ast::Literal::Int(1))),
+                    ))),
+                    high: Box::new(Expression::Literal(ast::Literal::Int(10))),
+                }),
+                Box::new(Expression::Identifier("c".into())),
+            ))
+        );
+        assert_eq!(expr.to_string(), "a NOT BETWEEN b + 1 AND 10 AND c");
+
+        assert!(parse_expr("a BETWEEN 1").is_err());
+    }
+
+    #[test]
+    fn test_is_predicate() {
+        let expr = parse_expr("a IS NOT NULL AND b IS NULL").unwrap();
+        assert_eq!(
+            expr,
+            Expression::BinaryOperator(ast::BinaryOperator::And(
+                Box::new(Expression::IsNotNull(Box::new(Expression::Identifier("a".into())))),
+                Box::new(Expression::IsNull(Box::new(Expression::Identifier("b".into())))),
+            ))
+        );
+
+        let expr = parse_expr("a IS NOT DISTINCT FROM b + 1 OR a IS DISTINCT FROM NULL").unwrap();
+        assert_eq!(
+            expr,
+            Expression::BinaryOperator(ast::BinaryOperator::Or(
+                Box::new(Expression::BinaryOperator(ast::BinaryOperator::IsNotDistinctFrom(
+                    Box::new(Expression::Identifier("a".into())),
+                    Box::new(Expression::BinaryOperator(ast::BinaryOperator::Add(
+                        Box::new(Expression::Identifier("b".into())),
+                        Box::new(Expression::Literal(ast::Literal::Int(1))),
+                    ))),
+                ))),
+                Box::new(Expression::BinaryOperator(ast::BinaryOperator::IsDistinctFrom(
+                    Box::new(Expression::Identifier("a".into())),
+                    Box::new(Expression::Literal(ast::Literal::Null)),
+                ))),
+            ))
+        );
+        assert_eq!(
+            expr.to_string(),
+            "a IS NOT DISTINCT FROM b + 1 OR a IS DISTINCT FROM null"
+        );
+
+        assert!(parse_expr("a IS DISTINCT b").is_err());
+        assert!(parse_expr("a IS 1").is_err());
+    }
+
     #[test]
     fn test_filter_sub_query() {
         assert_stmt_eq(
@@ -1489,9 +2822,9 @@ mod tests {
             Statement::Select(Box::new(Select {
                 with: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: "users".to_owned(),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: Some(Expression::BinaryOperator(ast::BinaryOperator::Eq(
@@ -1516,7 +2849,7 @@ mod tests {
                             })],
                         ))],
                         from: vec![ast::From::Table {
-                            name: "commits".to_owned(),
+                            name: vec!["commits"].into(),
                             alias: None,
                         }],
                         r#where: None,
@@ -1539,6 +2872,48 @@ mod tests {
     #[test]
     fn test_show() {
         assert_stmt_eq("SHOW TABLES;", Statement::ShowTables);
+        assert_stmt_eq(
+            "SHOW COLUMNS FROM public.t",
+            Statement::ShowColumns {
+                table: vec!["public", "t"].into(),
+            },
+        );
+        assert_stmt_eq(
+            "DESCRIBE t",
+            Statement::ShowColumns {
+                table: vec!["t"].into(),
+            },
+        );
+        assert!(parse_stmt("SHOW COLUMNS t").is_err());
+        assert_stmt_eq(
+            "SHOW batch_size",
+            Statement::ShowVariable {
+                variable: "batch_size".into(),
+            },
+        );
+        assert_stmt_eq("SHOW ALL", Statement::ShowVariable { variable: "all".into() });
+    }
+
+    #[test]
+    fn test_set_variable() {
+        assert_stmt_eq(
+            "SET batch_size = 1024",
+            Statement::SetVariable {
+                variable: "batch_size".into(),
+                value: vec![Expression::Literal(ast::Literal::Int(1024))],
+            },
+        );
+        assert_stmt_eq(
+            "SET search_path TO s1, 's2'",
+            Statement::SetVariable {
+                variable: "search_path".into(),
+                value: vec![
+                    Expression::Identifier("s1".into()),
+                    Expression::Literal(ast::Literal::String("s2".to_owned())),
+                ],
+            },
+        );
+        assert!(parse_stmt("SET batch_size 1024").is_err());
     }
 
     #[test]
@@ -1597,6 +2972,27 @@ mod tests {
                 ],
             },
         );
+
+        let Statement::Copy { source, options, .. } =
+            parse_stmt("COPY (SELECT a FROM t) TO 'a.json' WITH (FORMAT json, HEADER false)").unwrap()
+        else {
+            panic!("expected COPY")
+        };
+        assert!(matches!(source, CopySource::Query(_)));
+        assert_eq!(options[1], CopyOption::Header(false));
+
+        let Statement::Copy { options, .. } =
+            parse_stmt("COPY t TO 't.parquet' (FORMAT parquet, COMPRESSION zstd, ROW_GROUP_SIZE 1000)").unwrap()
+        else {
+            panic!("expected COPY")
+        };
+        assert_eq!(
+            options[1..],
+            [
+                CopyOption::Option("COMPRESSION".into(), ast::Literal::String("zstd".to_owned())),
+                CopyOption::Option("ROW_GROUP_SIZE".into(), ast::Literal::Int(1000)),
+            ]
+        );
     }
 
     #[test]
@@ -1609,9 +3005,9 @@ mod tests {
             Statement::Select(Box::new(Select {
                 with: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: "person".to_owned(),
+                    name: vec!["person"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -1814,6 +3210,11 @@ mod tests {
             }]
         );
 
+        let mut parser = Parser::new("WITH cte AS (SELECT 1) SELECT * FROM cte AS c");
+        let _ = parser.parse().unwrap();
+
+        assert!(parser.tables.is_empty());
+
         let mut parser = Parser::new("SELECT * FROM person");
         let _ = parser.parse().unwrap();
 
@@ -1852,18 +3253,124 @@ mod tests {
                 args: vec![]
             }]
         );
+
+        let mut parser = Parser::new("UPDATE person SET name = 'x'");
+        let _ = parser.parse().unwrap();
+
+        assert_eq!(
+            parser.tables,
+            vec![TableInfo {
+                name: "person".to_owned(),
+                alias: None,
+                args: vec![]
+            }]
+        );
     }
 
     #[test]
     fn test_parser_error() {
-        let stmt = parse_stmt("SELEC").err().unwrap();
-        assert_eq!(stmt.to_string(), "error: unexpected token line: 0 column: 4");
+        let err = parse_stmt("SELEC").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "error: unexpected token `SELEC` at line 1, column 1, expected one of: SELECT, WITH, VALUES, INSERT, \
+             UPDATE, DELETE, TRUNCATE, CREATE, DROP, COPY, SHOW, DESCRIBE, EXPLAIN, PREPARE, EXECUTE, \
+             DEALLOCATE, SET, BEGIN, START, COMMIT, ROLLBACK\nSELEC\n^^^^^"
+        );
+
+        let err = parse_stmt("SELECT * FROM").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "error: unexpected EOF at line 1, column 14\nSELECT * FROM\n             ^"
+        );
 
-        let stmt = parse_stmt("SELECT * FROM").err().unwrap();
-        assert_eq!(stmt.to_string(), "error: unexpected EOF line: 0 column: 12");
+        let err = parse_stmt("SELECT a\nFROM t\nWHERE (a = 1 b").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "error: unexpected token `b` at line 3, column 14, expected `)`\nWHERE (a = 1 b\n             ^"
+        );
+        let Error::Syntax(err) = err else {
+            panic!("expected a syntax error, got {:?}", err);
+        };
+        assert_eq!((err.line(), err.column()), (3, 14));
+        assert_eq!(err.token.location.line_str, "WHERE (a = 1 b");
+        assert_eq!(err.expected, vec![TokenType::RParen]);
+
+        let err = Parser::new("SELECT 1 x y").parse_statements().err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "error: unexpected token `y` at line 1, column 12, expected one of: `;`, EOF\nSELECT 1 x y\n           ^"
+        );
+    }
+
+    #[test]
+    fn test_parse_with_recovery() {
+        let errors = Parser::new("SELECT a +, b, f(( FROM t WHERE a = = 1 GROUP BY ORDER BY b")
+            .parse_with_recovery()
+            .unwrap_err();
+        let tokens = errors
+            .iter()
+            .map(|e| e.token().map(|t| (t.literal.as_str(), t.location.column)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec![
+                Some((",", 10)),
+                Some(("FROM", 19)),
+                Some(("=", 36)),
+                Some(("ORDER", 49))
+            ]
+        );
+
+        // an error in a subquery does not hide the errors after it
+        let errors = Parser::new("SELECT * FROM (SELECT 1 + FROM u) AS x WHERE x. > 1")
+            .parse_with_recovery()
+            .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].token().unwrap().literal, "FROM");
+        assert_eq!(errors[1].token().unwrap().literal, ">");
+
+        let errors = Parser::new("SELECT a FROM t b c").parse_with_recovery().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].token().unwrap().literal, "c");
+
+        let sql = "SELECT a, b FROM t WHERE a > 1";
+        assert_eq!(
+            Parser::new(sql).parse_with_recovery().unwrap(),
+            parse_stmt(sql).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_non_reserved_keyword() {
+        assert_eq!(
+            parse_stmt("SELECT year, t.key, date FROM t AS date WHERE rows > 1 ORDER BY year DESC")
+                .unwrap()
+                .to_string(),
+            "SELECT year, t.key, date FROM t AS date WHERE rows > 1 ORDER BY year DESC"
+        );
+        assert_eq!(
+            parse_expr("DATE '2021-01-01'").unwrap(),
+            Expression::TypedString {
+                data_type: DataType::Date,
+                value: "2021-01-01".to_owned(),
+            }
+        );
+        assert_eq!(
+            parse_stmt("WITH key(year) AS (SELECT 1) SELECT year FROM key")
+                .unwrap()
+                .to_string(),
+            "WITH key(year) AS (SELECT 1) SELECT year FROM key"
+        );
 
-        let stmt = parse_stmt("SELECT * FROM users WHERE").err().unwrap();
-        assert_eq!(stmt.to_string(), "error: unexpected EOF line: 0 column: 24");
+        // a reserved keyword is only an identifier when quoted
+        assert!(parse_stmt("SELECT from FROM t").is_err());
+        assert_eq!(
+            parse_expr("\"order\"").unwrap(),
+            Expression::Identifier(Ident {
+                value: "order".to_owned(),
+                quote_style: Some('"'),
+            })
+        );
     }
 
     #[test]
@@ -1883,6 +3390,7 @@ mod tests {
                     index: false,
                 }],
                 check_exists: false,
+                constraints: vec![],
             },
         );
 
@@ -1901,6 +3409,7 @@ mod tests {
                     index: false,
                 }],
                 check_exists: false,
+                constraints: vec![],
             },
         );
 
@@ -1919,6 +3428,7 @@ mod tests {
                     index: false,
                 }],
                 check_exists: false,
+                constraints: vec![],
             },
         );
 
@@ -1937,6 +3447,7 @@ mod tests {
                     index: false,
                 }],
                 check_exists: false,
+                constraints: vec![],
             },
         );
 
@@ -1966,6 +3477,7 @@ mod tests {
                     },
                 ],
                 check_exists: false,
+                constraints: vec![],
             },
         );
 
@@ -1995,6 +3507,7 @@ mod tests {
                     },
                 ],
                 check_exists: true,
+                constraints: vec![],
             },
         );
 
@@ -2024,6 +3537,7 @@ mod tests {
                     },
                 ],
                 check_exists: false,
+                constraints: vec![],
             },
         );
 
@@ -2053,6 +3567,7 @@ mod tests {
                     },
                 ],
                 check_exists: false,
+                constraints: vec![],
             },
         );
 
@@ -2082,16 +3597,17 @@ mod tests {
                     },
                 ],
                 check_exists: false,
+                constraints: vec![],
             },
         );
 
         assert_stmt_eq(
             "CREATE TABLE t1 AS SELECT * FROM read_csv('path/file.csv');",
             Statement::CreateTable {
-                query: Some(Select {
+                query: Some(Box::new(Statement::Select(Box::new(Select {
                     with: None,
                     distinct: None,
-                    columns: vec![SelectItem::Wildcard],
+                    columns: vec![SelectItem::Wildcard(vec![])],
                     from: vec![ast::From::TableFunction {
                         name: "read_csv".to_owned(),
                         args: vec![FunctionArgument {
@@ -2106,63 +3622,353 @@ mod tests {
                     order_by: None,
                     limit: None,
                     offset: None,
-                }),
+                })))),
+                table: "t1".to_owned(),
+                columns: vec![],
+                check_exists: false,
+                constraints: vec![],
+            },
+        );
+
+        assert_stmt_eq(
+            "CREATE TABLE t1 AS FROM read_csv_auto ('path/file.csv');",
+            Statement::CreateTable {
+                query: Some(Box::new(Statement::Select(Box::new(Select {
+                    with: None,
+                    distinct: None,
+                    columns: vec![SelectItem::Wildcard(vec![])],
+                    from: vec![ast::From::TableFunction {
+                        name: "read_csv_auto".to_owned(),
+                        args: vec![FunctionArgument {
+                            id: None,
+                            value: Expression::Literal(ast::Literal::String("path/file.csv".to_owned())),
+                        }],
+                        alias: None,
+                    }],
+                    r#where: None,
+                    group_by: None,
+                    having: None,
+                    order_by: None,
+                    limit: None,
+                    offset: None,
+                })))),
                 table: "t1".to_owned(),
                 columns: vec![],
                 check_exists: false,
+                constraints: vec![],
+            },
+        );
+    }
+
+    #[test]
+    fn test_parse_create_table_constraints() {
+        let column = |name: &str, nullable: bool, primary_key: bool, unique: bool| ast::Column {
+            name: name.to_owned(),
+            datatype: DataType::Integer,
+            nullable,
+            unique,
+            references: None,
+            primary_key,
+            index: false,
+        };
+
+        // the column constraints may be in any order
+        assert_stmt_eq(
+            "CREATE TABLE t (a INT NOT NULL PRIMARY KEY, b INT NULL UNIQUE, c INT UNIQUE NOT NULL)",
+            Statement::CreateTable {
+                query: None,
+                table: "t".to_owned(),
+                columns: vec![
+                    column("a", false, true, false),
+                    column("b", true, false, true),
+                    column("c", false, false, true),
+                ],
+                check_exists: false,
+                constraints: vec![],
+            },
+        );
+
+        assert_stmt_eq(
+            "CREATE TABLE t (a INT, b INT, PRIMARY KEY (a), UNIQUE (a, b))",
+            Statement::CreateTable {
+                query: None,
+                table: "t".to_owned(),
+                columns: vec![column("a", true, false, false), column("b", true, false, false)],
+                check_exists: false,
+                constraints: vec![
+                    ast::TableConstraint::PrimaryKey(vec!["a".to_owned()]),
+                    ast::TableConstraint::Unique(vec!["a".to_owned(), "b".to_owned()]),
+                ],
             },
         );
 
-        assert_stmt_eq(
-            "CREATE TABLE t1 AS FROM read_csv_auto ('path/file.csv');",
-            Statement::CreateTable {
-                query: Some(Select {
-                    with: None,
-                    distinct: None,
-                    columns: vec![SelectItem::Wildcard],
-                    from: vec![ast::From::TableFunction {
-                        name: "read_csv_auto".to_owned(),
-                        args: vec![FunctionArgument {
-                            id: None,
-                            value: Expression::Literal(ast::Literal::String("path/file.csv".to_owned())),
-                        }],
-                        alias: None,
-                    }],
-                    r#where: None,
-                    group_by: None,
-                    having: None,
-                    order_by: None,
-                    limit: None,
-                    offset: None,
-                }),
-                table: "t1".to_owned(),
-                columns: vec![],
-                check_exists: false,
-            },
+        let Statement::CreateTable { columns, .. } =
+            parse_stmt("CREATE TABLE t (a INT INDEX NOT NULL, b INT)").unwrap()
+        else {
+            panic!("expected CREATE TABLE");
+        };
+        assert_eq!(
+            columns.iter().map(|column| column.index).collect::<Vec<_>>(),
+            vec![true, false]
+        );
+
+        assert!(parse_stmt("CREATE TABLE t (a INT, PRIMARY KEY a)").is_err());
+        assert!(parse_stmt("CREATE TABLE t (a INT NOT)").is_err());
+        assert!(parse_stmt("CREATE TABLE t (a INT").is_err());
+    }
+
+    #[test]
+    fn test_parse_create_table_as() {
+        let stmt = parse_stmt("CREATE TABLE t AS SELECT a FROM t1 UNION ALL SELECT a FROM t2").unwrap();
+        assert!(matches!(
+            stmt,
+            Statement::CreateTable { query: Some(query), .. } if matches!(*query, Statement::SetOperation { .. })
+        ));
+
+        let stmt = parse_stmt("CREATE TABLE t AS WITH c AS (SELECT 1 AS a) SELECT a FROM c").unwrap();
+        assert!(matches!(
+            stmt,
+            Statement::CreateTable { query: Some(query), .. } if matches!(&*query, Statement::Select(select) if select.with.is_some())
+        ));
+
+        assert!(parse_stmt("CREATE TABLE t AS VALUES (1)").is_err());
+    }
+
+    #[test]
+    fn test_parse_create_view() {
+        let stmt = parse_stmt("CREATE VIEW v AS SELECT a FROM t").unwrap();
+        assert!(matches!(
+            &stmt,
+            Statement::CreateView { or_replace: false, name, columns, .. } if name == "v" && columns.is_empty()
+        ));
+        assert_eq!(stmt.to_string(), "CREATE VIEW v AS SELECT a FROM t");
+
+        let stmt =
+            parse_stmt("create or replace view v (x, y) as select a, b from t union select c, d from u").unwrap();
+        let Statement::CreateView {
+            or_replace,
+            columns,
+            query,
+            ..
+        } = stmt
+        else {
+            panic!("expected create view statement");
+        };
+        assert!(or_replace);
+        assert_eq!(columns, vec![Ident::from("x"), Ident::from("y")]);
+        assert!(matches!(*query, Statement::SetOperation { .. }));
+
+        assert!(parse_stmt("CREATE OR VIEW v AS SELECT 1").is_err());
+        assert!(parse_stmt("CREATE OR REPLACE TABLE v AS SELECT 1").is_err());
+        assert!(parse_stmt("CREATE VIEW v SELECT 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_drop_schema() -> Result<()> {
+        let stmt = parse_stmt("DROP SCHEMA test;")?;
+
+        assert_eq!(
+            stmt,
+            Statement::DropSchema {
+                schema: "test".to_owned(),
+                check_exists: false,
+            }
+        );
+
+        let stmt = parse_stmt("DROP SCHEMA IF EXISTS test;")?;
+
+        assert_eq!(
+            stmt,
+            Statement::DropSchema {
+                schema: "test".to_owned(),
+                check_exists: true,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_drop_table() -> Result<()> {
+        let stmt = parse_stmt("DROP TABLE IF EXISTS qurious.public.t;")?;
+        assert_eq!(
+            stmt,
+            Statement::DropTable {
+                table: "qurious.public.t".to_owned(),
+                check_exists: true,
+            }
+        );
+        assert_eq!(stmt.to_string(), "DROP TABLE IF EXISTS qurious.public.t");
+
+        let stmt = parse_stmt("drop view v")?;
+        assert_eq!(
+            stmt,
+            Statement::DropView {
+                view: "v".to_owned(),
+                check_exists: false,
+            }
+        );
+        assert_eq!(stmt.to_string(), "DROP VIEW v");
+
+        assert!(parse_stmt("DROP INDEX i").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_explain() -> Result<()> {
+        let stmt = parse_stmt("EXPLAIN SELECT a FROM t")?;
+        assert!(
+            matches!(&stmt, Statement::Explain { analyze: false, verbose: false, statement } if matches!(**statement, Statement::Select(_)))
+        );
+        assert_eq!(stmt.to_string(), "EXPLAIN SELECT a FROM t");
+
+        let stmt = parse_stmt("explain verbose with c as (select 1) select * from c")?;
+        assert!(matches!(
+            stmt,
+            Statement::Explain {
+                analyze: false,
+                verbose: true,
+                ..
+            }
+        ));
+
+        let stmt = parse_stmt("EXPLAIN ANALYZE VERBOSE SELECT a FROM t")?;
+        assert!(matches!(
+            stmt,
+            Statement::Explain {
+                analyze: true,
+                verbose: true,
+                ..
+            }
+        ));
+        assert_eq!(stmt.to_string(), "EXPLAIN ANALYZE VERBOSE SELECT a FROM t");
+        assert!(parse_stmt("EXPLAIN VERBOSE ANALYZE SELECT a FROM t").is_err());
+
+        assert!(parse_stmt("EXPLAIN DELETE FROM t").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_truncate() -> Result<()> {
+        let stmt = parse_stmt("TRUNCATE TABLE t")?;
+        assert_eq!(stmt, Statement::Truncate { table: "t".to_owned() });
+        assert_eq!(stmt.to_string(), "TRUNCATE TABLE t");
+
+        assert_eq!(parse_stmt("truncate t")?, Statement::Truncate { table: "t".to_owned() });
+        assert!(parse_stmt("TRUNCATE").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_prepare() -> Result<()> {
+        let stmt = parse_stmt("PREPARE q (INT, VARCHAR) AS SELECT a FROM t WHERE a = $1 AND b = $2")?;
+        let Statement::Prepare {
+            name,
+            data_types,
+            statement,
+        } = &stmt
+        else {
+            panic!("expected PREPARE, got {:?}", stmt);
+        };
+        assert_eq!(name, &Ident::from("q"));
+        assert_eq!(data_types, &vec![DataType::Integer, DataType::String]);
+        assert!(matches!(**statement, Statement::Select(_)));
+        assert_eq!(
+            stmt.to_string(),
+            "PREPARE q (INTEGER, VARCHAR) AS SELECT a FROM t WHERE a = $1 AND b = $2"
+        );
+
+        let stmt = parse_stmt("prepare ins as insert into t values ($1, $2)")?;
+        assert!(
+            matches!(stmt, Statement::Prepare { data_types, statement, .. } if data_types.is_empty() && matches!(*statement, Statement::Insert { .. }))
         );
+        assert!(parse_stmt("PREPARE q AS DROP TABLE t").is_err());
+        assert!(parse_stmt("PREPARE q SELECT 1").is_err());
+
+        Ok(())
     }
 
     #[test]
-    fn test_parse_drop_schema() -> Result<()> {
-        let stmt = parse_stmt("DROP SCHEMA test;")?;
+    fn test_transaction_statements() -> Result<()> {
+        assert_eq!(parse_stmt("BEGIN")?, Statement::StartTransaction);
+        assert_eq!(parse_stmt("begin transaction")?, Statement::StartTransaction);
+        assert_eq!(parse_stmt("START TRANSACTION")?, Statement::StartTransaction);
+        assert_eq!(parse_stmt("COMMIT WORK")?, Statement::Commit);
+        assert_eq!(parse_stmt("ROLLBACK")?, Statement::Rollback);
+        assert!(parse_stmt("START").is_err());
+        // the keywords are not reserved
+        assert_eq!(
+            parse_stmt("SELECT start, commit FROM t")?.to_string(),
+            "SELECT start, commit FROM t"
+        );
+
+        Ok(())
+    }
 
+    #[test]
+    fn test_parse_execute_and_deallocate() -> Result<()> {
+        let stmt = parse_stmt("EXECUTE q (1, 'a')")?;
         assert_eq!(
             stmt,
-            Statement::DropSchema {
-                schema: "test".to_owned(),
-                check_exists: false,
+            Statement::Execute {
+                name: Ident::from("q"),
+                parameters: vec![
+                    Expression::Literal(ast::Literal::Int(1)),
+                    Expression::Literal(ast::Literal::String("a".to_owned())),
+                ],
+            }
+        );
+        assert_eq!(parse_stmt("EXECUTE q (1, 2)")?.to_string(), "EXECUTE q (1, 2)");
+        assert_eq!(
+            parse_stmt("EXECUTE q")?,
+            Statement::Execute {
+                name: Ident::from("q"),
+                parameters: vec![],
             }
         );
 
-        let stmt = parse_stmt("DROP SCHEMA IF EXISTS test;")?;
-
+        let stmt = parse_stmt("DEALLOCATE PREPARE q")?;
         assert_eq!(
             stmt,
-            Statement::DropSchema {
-                schema: "test".to_owned(),
-                check_exists: true,
+            Statement::Deallocate {
+                name: Ident::from("q"),
+                prepare: true,
             }
         );
+        assert_eq!(stmt.to_string(), "DEALLOCATE PREPARE q");
+        assert_eq!(
+            parse_stmt("deallocate q")?,
+            Statement::Deallocate {
+                name: Ident::from("q"),
+                prepare: false,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_statements() -> Result<()> {
+        let mut parser = Parser::new(
+            "CREATE TABLE t(a INT);\n INSERT INTO t VALUES (1);;\n WITH c AS (SELECT a FROM t) SELECT * FROM c; SELECT * FROM c",
+        );
+        let statements = parser.parse_statements()?;
+        assert_eq!(statements.len(), 4);
+        assert!(matches!(statements[0], Statement::CreateTable { .. }));
+        assert!(matches!(statements[1], Statement::Insert { .. }));
+        assert!(matches!(statements[2], Statement::Select(_)));
+        // the CTE of the third statement is a table in the fourth one
+        assert_eq!(
+            parser.tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            vec!["t", "c"]
+        );
+
+        assert_eq!(Parser::new(" ; ;").parse_statements()?, vec![]);
+        // statements must be separated by a semicolon
+        assert!(Parser::new("SELECT 1 SELECT 2").parse_statements().is_err());
 
         Ok(())
     }
@@ -2382,7 +4188,12 @@ mod tests {
                         ast::Expression::Literal(ast::Literal::String("name2".to_owned())),
                     ],
                 ],
-                on_conflict: Some(ast::OnConflict::DoNothing),
+                on_conflict: Some(ast::OnConflict::DoNothing {
+                    constraints: vec![ast::Ident {
+                        value: "id".to_owned(),
+                        quote_style: None,
+                    }],
+                }),
                 returning: None,
             }
         );
@@ -2568,12 +4379,12 @@ mod tests {
                 values: vec![],
                 on_conflict: None,
                 returning: None,
-                query: Some(ast::Select {
+                query: Some(Box::new(ast::Select {
                     with: None,
                     distinct: None,
-                    columns: vec![ast::SelectItem::Wildcard],
+                    columns: vec![ast::SelectItem::Wildcard(vec![])],
                     from: vec![ast::From::Table {
-                        name: String::from("other_tbl"),
+                        name: vec!["other_tbl"].into(),
                         alias: None,
                     }],
                     r#where: None,
@@ -2582,7 +4393,7 @@ mod tests {
                     order_by: None,
                     limit: None,
                     offset: None,
-                }),
+                })),
             }
         );
 
@@ -2597,12 +4408,12 @@ mod tests {
                 values: vec![],
                 on_conflict: None,
                 returning: None,
-                query: Some(ast::Select {
+                query: Some(Box::new(ast::Select {
                     with: None,
                     distinct: None,
-                    columns: vec![ast::SelectItem::Wildcard],
+                    columns: vec![ast::SelectItem::Wildcard(vec![])],
                     from: vec![ast::From::Table {
-                        name: String::from("other_tbl"),
+                        name: vec!["other_tbl"].into(),
                         alias: None,
                     }],
                     r#where: None,
@@ -2611,7 +4422,7 @@ mod tests {
                     order_by: None,
                     limit: None,
                     offset: None,
-                }),
+                })),
             }
         );
 
@@ -2629,7 +4440,7 @@ mod tests {
                 values: vec![],
                 on_conflict: None,
                 returning: None,
-                query: Some(ast::Select {
+                query: Some(Box::new(ast::Select {
                     with: None,
                     distinct: None,
                     columns: vec![
@@ -2637,7 +4448,7 @@ mod tests {
                         ast::SelectItem::UnNamedExpr(ast::Expression::Identifier("name".into())),
                     ],
                     from: vec![ast::From::Table {
-                        name: String::from("other_tbl"),
+                        name: vec!["other_tbl"].into(),
                         alias: None,
                     }],
                     r#where: None,
@@ -2646,7 +4457,7 @@ mod tests {
                     order_by: None,
                     limit: None,
                     offset: None,
-                }),
+                })),
             }
         );
     }
@@ -2663,7 +4474,7 @@ mod tests {
                     "p_id".to_owned(),
                 )],
                 from: vec![ast::From::Table {
-                    name: "person".to_owned(),
+                    name: vec!["person"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -2683,9 +4494,9 @@ mod tests {
                 limit: None,
                 offset: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -2742,7 +4553,7 @@ mod tests {
                     SelectItem::UnNamedExpr(ast::Expression::CompoundIdentifier(vec!["t".into(), "id".into()])),
                 ],
                 from: vec![ast::From::Table {
-                    name: String::from("test"),
+                    name: vec!["test"].into(),
                     alias: Some(String::from("t")),
                 }],
                 r#where: None,
@@ -2759,9 +4570,9 @@ mod tests {
                 limit: None,
                 offset: None,
                 distinct: None,
-                columns: vec![SelectItem::QualifiedWildcard(vec!["t".to_owned()])],
+                columns: vec![SelectItem::QualifiedWildcard(vec!["t".to_owned()], vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("person"),
+                    name: vec!["person"].into(),
                     alias: Some(String::from("t")),
                 }],
                 r#where: None,
@@ -2769,6 +4580,37 @@ mod tests {
                 having: None,
             })),
         );
+
+        let stmt = parse_stmt("SELECT t.* EXCLUDE (id, \"Name\"), * EXCEPT (age) FROM person AS t").unwrap();
+        let Statement::Select(select) = &stmt else {
+            panic!("expected a SELECT statement");
+        };
+        assert_eq!(
+            select.columns,
+            vec![
+                SelectItem::QualifiedWildcard(
+                    vec!["t".to_owned()],
+                    vec![
+                        Ident::from("id"),
+                        Ident {
+                            value: "Name".to_owned(),
+                            quote_style: Some('"'),
+                        },
+                    ],
+                ),
+                SelectItem::Wildcard(vec![Ident::from("age")]),
+            ]
+        );
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT t.* EXCLUDE (id, \"Name\"), * EXCLUDE (age) FROM person AS t"
+        );
+
+        // an alias named exclude is still allowed after an expression
+        let stmt = parse_stmt("SELECT id exclude FROM person").unwrap();
+        assert_eq!(stmt.to_string(), "SELECT id AS exclude FROM person");
+
+        assert!(parse_stmt("SELECT * EXCLUDE id FROM person").is_err());
     }
 
     #[test]
@@ -2783,7 +4625,7 @@ mod tests {
                 limit: None,
                 offset: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::TableFunction {
                     name: String::from("read_csv"),
                     args: vec![
@@ -2847,9 +4689,9 @@ mod tests {
                 offset: None,
                 distinct: None,
                 having: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("public.users"),
+                    name: vec!["public", "users"].into(),
                     alias: Some(String::from("u")),
                 }],
                 r#where: None,
@@ -2868,9 +4710,9 @@ mod tests {
                 offset: None,
                 distinct: None,
                 having: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("catalog.public.users"),
+                    name: vec!["catalog", "public", "users"].into(),
                     alias: Some(String::from("u")),
                 }],
                 r#where: None,
@@ -2889,7 +4731,7 @@ mod tests {
                 offset: None,
                 distinct: None,
                 having: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::SubQuery {
                     query: Box::new(ast::Statement::Select(Box::new(Select {
                         with: None,
@@ -2898,21 +4740,43 @@ mod tests {
                         offset: None,
                         having: None,
                         distinct: None,
-                        columns: vec![SelectItem::Wildcard],
+                        columns: vec![SelectItem::Wildcard(vec![])],
                         from: vec![ast::From::Table {
-                            name: String::from("users"),
+                            name: vec!["users"].into(),
                             alias: None,
                         }],
                         r#where: None,
                         group_by: None,
                     }))),
                     alias: Some(String::from("u")),
+                    columns: vec![],
+                    lateral: false,
                 }],
                 r#where: None,
                 group_by: None,
             }))
         );
 
+        let stmt = parse_stmt("select * from (select a, b from t) x(c1, \"C2\")").unwrap();
+        let Statement::Select(select) = &stmt else {
+            panic!("expected a SELECT statement");
+        };
+        let ast::From::SubQuery { alias, columns, .. } = &select.from[0] else {
+            panic!("expected a subquery in FROM");
+        };
+        assert_eq!(alias, &Some(String::from("x")));
+        assert_eq!(
+            columns,
+            &vec![
+                Ident::from("c1"),
+                Ident {
+                    value: "C2".to_owned(),
+                    quote_style: Some('"'),
+                },
+            ]
+        );
+        assert_eq!(stmt.to_string(), "SELECT * FROM (SELECT a, b FROM t) AS x(c1, \"C2\")");
+
         let stmt = parse_stmt("select * from users u join users u2 on u.id = u2.id;").unwrap();
 
         assert_eq!(
@@ -2924,18 +4788,18 @@ mod tests {
                 offset: None,
                 distinct: None,
                 having: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Join {
                     join_type: ast::JoinType::Inner,
                     left: Box::new(ast::From::Table {
-                        name: String::from("users"),
+                        name: vec!["users"].into(),
                         alias: Some(String::from("u")),
                     }),
                     right: Box::new(ast::From::Table {
-                        name: String::from("users"),
+                        name: vec!["users"].into(),
                         alias: Some(String::from("u2")),
                     }),
-                    on: Some(ast::Expression::BinaryOperator(ast::BinaryOperator::Eq(
+                    constraint: ast::JoinConstraint::On(ast::Expression::BinaryOperator(ast::BinaryOperator::Eq(
                         Box::new(ast::Expression::CompoundIdentifier(vec!["u".into(), "id".into()])),
                         Box::new(ast::Expression::CompoundIdentifier(vec!["u2".into(), "id".into()])),
                     ))),
@@ -2956,18 +4820,18 @@ mod tests {
                 offset: None,
                 distinct: None,
                 having: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Join {
                     join_type: ast::JoinType::Left,
                     left: Box::new(ast::From::Table {
-                        name: String::from("users"),
+                        name: vec!["users"].into(),
                         alias: Some(String::from("u")),
                     }),
                     right: Box::new(ast::From::Table {
-                        name: String::from("users"),
+                        name: vec!["users"].into(),
                         alias: Some(String::from("u2")),
                     }),
-                    on: Some(ast::Expression::BinaryOperator(ast::BinaryOperator::Eq(
+                    constraint: ast::JoinConstraint::On(ast::Expression::BinaryOperator(ast::BinaryOperator::Eq(
                         Box::new(ast::Expression::CompoundIdentifier(vec!["u".into(), "id".into()])),
                         Box::new(ast::Expression::CompoundIdentifier(vec!["u2".into(), "id".into()])),
                     ))),
@@ -2988,18 +4852,18 @@ mod tests {
                 offset: None,
                 distinct: None,
                 having: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Join {
                     join_type: ast::JoinType::Right,
                     left: Box::new(ast::From::Table {
-                        name: String::from("users"),
+                        name: vec!["users"].into(),
                         alias: Some(String::from("u")),
                     }),
                     right: Box::new(ast::From::Table {
-                        name: String::from("users"),
+                        name: vec!["users"].into(),
                         alias: Some(String::from("u2")),
                     }),
-                    on: Some(ast::Expression::BinaryOperator(ast::BinaryOperator::Eq(
+                    constraint: ast::JoinConstraint::On(ast::Expression::BinaryOperator(ast::BinaryOperator::Eq(
                         Box::new(ast::Expression::CompoundIdentifier(vec!["u".into(), "id".into()])),
                         Box::new(ast::Expression::CompoundIdentifier(vec!["u2".into(), "id".into()])),
                     ))),
@@ -3020,18 +4884,18 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Join {
                     join_type: ast::JoinType::Inner,
                     left: Box::new(ast::From::Table {
-                        name: String::from("users"),
+                        name: vec!["users"].into(),
                         alias: Some(String::from("u")),
                     }),
                     right: Box::new(ast::From::Table {
-                        name: String::from("users"),
+                        name: vec!["users"].into(),
                         alias: Some(String::from("u2")),
                     }),
-                    on: Some(ast::Expression::BinaryOperator(ast::BinaryOperator::Eq(
+                    constraint: ast::JoinConstraint::On(ast::Expression::BinaryOperator(ast::BinaryOperator::Eq(
                         Box::new(ast::Expression::CompoundIdentifier(vec!["u".into(), "id".into()])),
                         Box::new(ast::Expression::CompoundIdentifier(vec!["u2".into(), "id".into()])),
                     ))),
@@ -3052,18 +4916,18 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Join {
                     join_type: ast::JoinType::Full,
                     left: Box::new(ast::From::Table {
-                        name: String::from("users"),
+                        name: vec!["users"].into(),
                         alias: Some(String::from("u")),
                     }),
                     right: Box::new(ast::From::Table {
-                        name: String::from("users"),
+                        name: vec!["users"].into(),
                         alias: Some(String::from("u2")),
                     }),
-                    on: Some(ast::Expression::BinaryOperator(ast::BinaryOperator::Eq(
+                    constraint: ast::JoinConstraint::On(ast::Expression::BinaryOperator(ast::BinaryOperator::Eq(
                         Box::new(ast::Expression::CompoundIdentifier(vec!["u".into(), "id".into()])),
                         Box::new(ast::Expression::CompoundIdentifier(vec!["u2".into(), "id".into()])),
                     ))),
@@ -3084,50 +4948,339 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Join {
                     join_type: ast::JoinType::Cross,
                     left: Box::new(ast::From::Table {
-                        name: String::from("users"),
+                        name: vec!["users"].into(),
                         alias: Some(String::from("u")),
                     }),
                     right: Box::new(ast::From::Table {
-                        name: String::from("users"),
+                        name: vec!["users"].into(),
                         alias: Some(String::from("u2")),
                     }),
-                    on: None,
+                    constraint: ast::JoinConstraint::None,
+                }],
+                r#where: None,
+                group_by: None,
+            }))
+        );
+
+        let stmt = parse_stmt("select * from users u, persons p").unwrap();
+
+        assert_eq!(
+            stmt,
+            ast::Statement::Select(Box::new(Select {
+                with: None,
+                order_by: None,
+                limit: None,
+                offset: None,
+                having: None,
+                distinct: None,
+                columns: vec![SelectItem::Wildcard(vec![])],
+                from: vec![
+                    ast::From::Table {
+                        name: vec!["users"].into(),
+                        alias: Some(String::from("u")),
+                    },
+                    ast::From::Table {
+                        name: vec!["persons"].into(),
+                        alias: Some(String::from("p")),
+                    },
+                ],
+                r#where: None,
+                group_by: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_natural_join() {
+        let stmt = parse_stmt("SELECT * FROM users NATURAL LEFT JOIN persons p").unwrap();
+
+        assert_eq!(
+            stmt,
+            ast::Statement::Select(Box::new(Select {
+                with: None,
+                order_by: None,
+                limit: None,
+                offset: None,
+                having: None,
+                distinct: None,
+                columns: vec![SelectItem::Wildcard(vec![])],
+                from: vec![ast::From::Join {
+                    join_type: ast::JoinType::Left,
+                    left: Box::new(ast::From::Table {
+                        name: vec!["users"].into(),
+                        alias: None,
+                    }),
+                    right: Box::new(ast::From::Table {
+                        name: vec!["persons"].into(),
+                        alias: Some(String::from("p")),
+                    }),
+                    constraint: ast::JoinConstraint::Natural,
+                }],
+                r#where: None,
+                group_by: None,
+            }))
+        );
+        assert_eq!(stmt.to_string(), "SELECT * FROM users NATURAL LEFT JOIN persons AS p");
+
+        assert_eq!(
+            parse_stmt("SELECT * FROM users NATURAL CROSS JOIN persons")
+                .unwrap_err()
+                .to_string(),
+            "error: CROSS JOIN can not be NATURAL"
+        );
+        assert!(parse_stmt("SELECT * FROM users NATURAL persons").is_err());
+    }
+
+    #[test]
+    fn test_parse_semi_anti_join() {
+        let stmt = parse_stmt("SELECT * FROM users LEFT SEMI JOIN persons ON users.id = persons.id").unwrap();
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT * FROM users LEFT SEMI JOIN persons ON users.id = persons.id"
+        );
+
+        let stmt = parse_stmt("SELECT * FROM users LEFT ANTI JOIN persons p USING (id)").unwrap();
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT * FROM users LEFT ANTI JOIN persons AS p USING (id)"
+        );
+
+        // MySQL has no semi and anti joins
+        let mut parser = Parser::with_dialect("SELECT * FROM users LEFT SEMI JOIN persons", &MySqlDialect);
+        assert!(parser.parse().is_err());
+    }
+
+    #[test]
+    fn test_parse_join_using() {
+        let stmt = parse_stmt("SELECT * FROM users u JOIN persons USING (id, \"Name\")").unwrap();
+
+        assert_eq!(
+            stmt,
+            ast::Statement::Select(Box::new(Select {
+                with: None,
+                order_by: None,
+                limit: None,
+                offset: None,
+                having: None,
+                distinct: None,
+                columns: vec![SelectItem::Wildcard(vec![])],
+                from: vec![ast::From::Join {
+                    join_type: ast::JoinType::Inner,
+                    left: Box::new(ast::From::Table {
+                        name: vec!["users"].into(),
+                        alias: Some(String::from("u")),
+                    }),
+                    right: Box::new(ast::From::Table {
+                        name: vec!["persons"].into(),
+                        alias: None,
+                    }),
+                    constraint: ast::JoinConstraint::Using(vec![
+                        Ident::from("id"),
+                        Ident {
+                            value: "Name".to_owned(),
+                            quote_style: Some('"'),
+                        },
+                    ]),
+                }],
+                r#where: None,
+                group_by: None,
+            }))
+        );
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT * FROM users AS u INNER JOIN persons USING (id, \"Name\")"
+        );
+
+        assert!(parse_stmt("SELECT * FROM users JOIN persons USING id").is_err());
+    }
+
+    #[test]
+    fn test_parse_lateral_subquery() {
+        let stmt = parse_stmt("SELECT * FROM users u CROSS JOIN LATERAL (SELECT u.id) AS l").unwrap();
+
+        assert_eq!(
+            stmt,
+            ast::Statement::Select(Box::new(Select {
+                with: None,
+                order_by: None,
+                limit: None,
+                offset: None,
+                having: None,
+                distinct: None,
+                columns: vec![SelectItem::Wildcard(vec![])],
+                from: vec![ast::From::Join {
+                    join_type: ast::JoinType::Cross,
+                    left: Box::new(ast::From::Table {
+                        name: vec!["users"].into(),
+                        alias: Some(String::from("u")),
+                    }),
+                    right: Box::new(ast::From::SubQuery {
+                        query: Box::new(ast::Statement::Select(Box::new(Select {
+                            with: None,
+                            order_by: None,
+                            limit: None,
+                            offset: None,
+                            having: None,
+                            distinct: None,
+                            columns: vec![SelectItem::UnNamedExpr(Expression::CompoundIdentifier(vec![
+                                Ident::from("u"),
+                                Ident::from("id"),
+                            ]))],
+                            from: vec![],
+                            r#where: None,
+                            group_by: None,
+                        }))),
+                        alias: Some(String::from("l")),
+                        columns: vec![],
+                        lateral: true,
+                    }),
+                    constraint: ast::JoinConstraint::None,
                 }],
                 r#where: None,
                 group_by: None,
             }))
         );
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT * FROM users AS u CROSS JOIN LATERAL (SELECT u.id) AS l"
+        );
+
+        let stmt = parse_stmt("SELECT * FROM users u, LATERAL (SELECT u.id) l").unwrap();
+        assert_eq!(stmt.to_string(), "SELECT * FROM users AS u, LATERAL (SELECT u.id) AS l");
+
+        assert!(parse_stmt("SELECT * FROM users u, LATERAL users").is_err());
+    }
+
+    #[test]
+    fn test_parse_table_sample() {
+        let stmt = parse_stmt("SELECT * FROM t AS s TABLESAMPLE BERNOULLI (10.5) REPEATABLE (42)").unwrap();
+        let Statement::Select(select) = &stmt else {
+            panic!("expected a SELECT statement");
+        };
+        assert_eq!(
+            select.from,
+            vec![ast::From::TableSample {
+                table: Box::new(ast::From::Table {
+                    name: vec!["t"].into(),
+                    alias: Some(String::from("s")),
+                }),
+                sample: ast::TableSample {
+                    method: ast::SampleMethod::Bernoulli,
+                    percent: Expression::Literal(ast::Literal::Float(10.5)),
+                    seed: Some(Expression::Literal(ast::Literal::Int(42))),
+                },
+            }]
+        );
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT * FROM t AS s TABLESAMPLE BERNOULLI (10.5) REPEATABLE (42)"
+        );
+
+        let stmt = parse_stmt("SELECT * FROM t tablesample system(1) JOIN u ON t.a = u.b").unwrap();
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT * FROM t TABLESAMPLE SYSTEM (1) INNER JOIN u ON t.a = u.b"
+        );
+
+        assert!(parse_stmt("SELECT * FROM t TABLESAMPLE (10)").is_err());
+        assert!(parse_stmt("SELECT * FROM t TABLESAMPLE RANDOM (10)").is_err());
+    }
+
+    #[test]
+    fn test_parse_pivot() {
+        let stmt = parse_stmt("SELECT * FROM t PIVOT (SUM(v) FOR k IN ('a' AS x, 'b')) AS p").unwrap();
+        let Statement::Select(select) = &stmt else {
+            panic!("expected a SELECT statement");
+        };
+        assert_eq!(
+            select.from,
+            vec![ast::From::Pivot {
+                table: Box::new(ast::From::Table {
+                    name: vec!["t"].into(),
+                    alias: None,
+                }),
+                aggregate: Expression::Function(String::from("SUM"), vec![Expression::Identifier(Ident::from("v"))]),
+                column: Ident::from("k"),
+                values: vec![
+                    ast::PivotValue {
+                        value: Expression::Literal(ast::Literal::String(String::from("a"))),
+                        alias: Some(String::from("x")),
+                    },
+                    ast::PivotValue {
+                        value: Expression::Literal(ast::Literal::String(String::from("b"))),
+                        alias: None,
+                    },
+                ],
+                alias: Some(String::from("p")),
+            }]
+        );
 
-        let stmt = parse_stmt("select * from users u, persons p").unwrap();
+        let stmt = parse_stmt("SELECT * FROM t UNPIVOT (v FOR k IN (a, b)) u WHERE v > 1").unwrap();
+        let Statement::Select(select) = &stmt else {
+            panic!("expected a SELECT statement");
+        };
+        assert_eq!(
+            select.from,
+            vec![ast::From::Unpivot {
+                table: Box::new(ast::From::Table {
+                    name: vec!["t"].into(),
+                    alias: None,
+                }),
+                value: Ident::from("v"),
+                name: Ident::from("k"),
+                columns: vec![Ident::from("a"), Ident::from("b")],
+                alias: Some(String::from("u")),
+            }]
+        );
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT * FROM t UNPIVOT (v FOR k IN (a, b)) AS u WHERE v > 1"
+        );
+
+        assert!(parse_stmt("SELECT * FROM t PIVOT (SUM(v) k IN ('a'))").is_err());
+        assert!(parse_stmt("SELECT * FROM t UNPIVOT (v FOR k IN a, b)").is_err());
+    }
 
+    #[test]
+    fn test_parse_values() {
+        let stmt = parse_stmt("VALUES (1, 'a'), (2, 'b')").unwrap();
         assert_eq!(
             stmt,
-            ast::Statement::Select(Box::new(Select {
-                with: None,
-                order_by: None,
-                limit: None,
-                offset: None,
-                having: None,
-                distinct: None,
-                columns: vec![SelectItem::Wildcard],
-                from: vec![
-                    ast::From::Table {
-                        name: String::from("users"),
-                        alias: Some(String::from("u")),
-                    },
-                    ast::From::Table {
-                        name: String::from("persons"),
-                        alias: Some(String::from("p")),
-                    },
+            ast::Statement::Values(vec![
+                vec![
+                    Expression::Literal(ast::Literal::Int(1)),
+                    Expression::Literal(ast::Literal::String("a".to_owned())),
                 ],
-                r#where: None,
-                group_by: None,
-            }))
+                vec![
+                    Expression::Literal(ast::Literal::Int(2)),
+                    Expression::Literal(ast::Literal::String("b".to_owned())),
+                ],
+            ])
+        );
+
+        let stmt = parse_stmt("SELECT * FROM (VALUES (1), (2)) AS t(x)").unwrap();
+        let ast::Statement::Select(select) = &stmt else {
+            panic!("expected a SELECT statement");
+        };
+        assert_eq!(
+            select.from,
+            vec![ast::From::Values {
+                values: vec![
+                    vec![Expression::Literal(ast::Literal::Int(1))],
+                    vec![Expression::Literal(ast::Literal::Int(2))],
+                ],
+                alias: Some(String::from("t")),
+                columns: vec![Ident::from("x")],
+            }]
         );
+        assert_eq!(stmt.to_string(), "SELECT * FROM (VALUES (1), (2)) AS t(x)");
+
+        assert!(parse_stmt("SELECT * FROM (VALUES (1) AS t").is_err());
+        assert!(parse_stmt("SELECT * FROM LATERAL (VALUES (1)) AS t").is_err());
     }
 
     #[test]
@@ -3136,14 +5289,14 @@ mod tests {
             "SELECT * FROM users ORDER BY id;",
             ast::Statement::Select(Box::new(Select {
                 with: None,
-                order_by: Some(vec![(ast::Expression::Identifier("id".into()), ast::Order::Asc)]),
+                order_by: Some(vec![order_by_expr("id", ast::Order::Asc)]),
                 limit: None,
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3155,14 +5308,14 @@ mod tests {
             "SELECT * FROM users ORDER BY id ASC;",
             ast::Statement::Select(Box::new(Select {
                 with: None,
-                order_by: Some(vec![(ast::Expression::Identifier("id".into()), ast::Order::Asc)]),
+                order_by: Some(vec![order_by_expr("id", ast::Order::Asc)]),
                 limit: None,
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3175,17 +5328,17 @@ mod tests {
             ast::Statement::Select(Box::new(Select {
                 with: None,
                 order_by: Some(vec![
-                    (ast::Expression::Identifier("id".into()), ast::Order::Asc),
-                    (ast::Expression::Identifier("name".into()), ast::Order::Asc),
-                    (ast::Expression::Identifier("age".into()), ast::Order::Asc),
+                    order_by_expr("id", ast::Order::Asc),
+                    order_by_expr("name", ast::Order::Asc),
+                    order_by_expr("age", ast::Order::Asc),
                 ]),
                 limit: None,
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3197,14 +5350,14 @@ mod tests {
             "SELECT * FROM users ORDER BY id DESC;",
             ast::Statement::Select(Box::new(Select {
                 with: None,
-                order_by: Some(vec![(ast::Expression::Identifier("id".into()), ast::Order::Desc)]),
+                order_by: Some(vec![order_by_expr("id", ast::Order::Desc)]),
                 limit: None,
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3217,16 +5370,16 @@ mod tests {
             ast::Statement::Select(Box::new(Select {
                 with: None,
                 order_by: Some(vec![
-                    (ast::Expression::Identifier("id".into()), ast::Order::Desc),
-                    (ast::Expression::Identifier("name".into()), ast::Order::Asc),
+                    order_by_expr("id", ast::Order::Desc),
+                    order_by_expr("name", ast::Order::Asc),
                 ]),
                 limit: None,
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3239,22 +5392,52 @@ mod tests {
             ast::Statement::Select(Box::new(Select {
                 with: None,
                 order_by: Some(vec![
-                    (ast::Expression::Identifier("id".into()), ast::Order::Asc),
-                    (ast::Expression::Identifier("name".into()), ast::Order::Desc),
+                    order_by_expr("id", ast::Order::Asc),
+                    order_by_expr("name", ast::Order::Desc),
                 ]),
                 limit: None,
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
                 group_by: None,
             })),
         );
+
+        let Statement::Select(select) =
+            parse_stmt("SELECT * FROM users ORDER BY id + 1 DESC NULLS LAST, nulls NULLS FIRST").unwrap()
+        else {
+            panic!("expected select statement");
+        };
+        assert_eq!(
+            select.order_by,
+            Some(vec![
+                OrderByExpr {
+                    expr: Expression::BinaryOperator(BinaryOperator::Add(
+                        Box::new(Expression::Identifier("id".into())),
+                        Box::new(Expression::Literal(ast::Literal::Int(1))),
+                    )),
+                    order: ast::Order::Desc,
+                    nulls_first: Some(false),
+                },
+                OrderByExpr {
+                    expr: Expression::Identifier("nulls".into()),
+                    order: ast::Order::Asc,
+                    nulls_first: Some(true),
+                },
+            ])
+        );
+        assert_eq!(
+            select.to_string(),
+            "SELECT * FROM users ORDER BY id + 1 DESC NULLS LAST, nulls ASC NULLS FIRST"
+        );
+
+        assert!(parse_stmt("SELECT * FROM users ORDER BY id NULLS").is_err());
     }
 
     #[test]
@@ -3270,9 +5453,9 @@ mod tests {
                 offset: Some(ast::Expression::Literal(ast::Literal::Int(10))),
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3291,9 +5474,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3312,9 +5495,9 @@ mod tests {
                 offset: Some(ast::Expression::Literal(ast::Literal::Int(10))),
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3333,9 +5516,9 @@ mod tests {
                 offset: Some(ast::Expression::Literal(ast::Literal::Int(10))),
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3344,6 +5527,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_fetch_first() {
+        let stmt = parse_stmt("SELECT * FROM users OFFSET 10 ROWS FETCH FIRST 5 ROWS ONLY;").unwrap();
+        assert_eq!(stmt.to_string(), "SELECT * FROM users LIMIT 5 OFFSET 10");
+
+        let stmt = parse_stmt("SELECT * FROM users ORDER BY id FETCH NEXT 1 ROW ONLY;").unwrap();
+        assert_eq!(stmt.to_string(), "SELECT * FROM users ORDER BY id ASC LIMIT 1");
+
+        assert!(parse_stmt("SELECT * FROM users FETCH FIRST 5 ROWS;").is_err());
+        assert!(parse_stmt("SELECT * FROM users FETCH 5 ROWS ONLY;").is_err());
+    }
+
     #[test]
     fn test_parse_distinct_select_statement() {
         let stmt = parse_stmt("SELECT DISTINCT * FROM users;").unwrap();
@@ -3357,9 +5552,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: Some(ast::Distinct::ALL),
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3383,13 +5578,21 @@ mod tests {
                 ])),
                 columns: vec![SelectItem::UnNamedExpr(ast::Expression::Identifier("school".into()))],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
                 group_by: None,
             }))
         );
+
+        let stmt = parse_stmt("SELECT DISTINCT ON (id + 1, name) id, name FROM users ORDER BY id + 1").unwrap();
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT DISTINCT ON (id + 1, name) id, name FROM users ORDER BY id + 1 ASC"
+        );
+        let stmt = parse_stmt("SELECT DISTINCT name FROM users").unwrap();
+        assert_eq!(stmt.to_string(), "SELECT DISTINCT name FROM users");
     }
 
     #[test]
@@ -3403,9 +5606,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: Some(Expression::IsNull(Box::new(Expression::Identifier("id".into())))),
@@ -3422,9 +5625,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: Some(Expression::IsNotNull(Box::new(Expression::Identifier("id".into())))),
@@ -3441,9 +5644,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: Some(Expression::BinaryOperator(ast::BinaryOperator::Eq(
@@ -3463,9 +5666,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: Some(Expression::BinaryOperator(ast::BinaryOperator::And(
@@ -3491,9 +5694,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: Some(Expression::BinaryOperator(ast::BinaryOperator::Or(
@@ -3519,9 +5722,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: Some(Expression::InList {
@@ -3546,9 +5749,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: Some(Expression::InList {
@@ -3573,9 +5776,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: Some(Expression::InList {
@@ -3599,9 +5802,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: Some(Expression::InList {
@@ -3625,9 +5828,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: String::from("users"),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: Some(Expression::InSubQuery {
@@ -3641,7 +5844,7 @@ mod tests {
                         distinct: None,
                         columns: vec![SelectItem::UnNamedExpr(Expression::Identifier("id".into()))],
                         from: vec![ast::From::Table {
-                            name: String::from("users"),
+                            name: vec!["users"].into(),
                             alias: None,
                         }],
                         r#where: None,
@@ -3654,6 +5857,264 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_window_function() {
+        let expr = Parser::new("ROW_NUMBER() OVER (PARTITION BY a, b ORDER BY c DESC)")
+            .parse_expression(0)
+            .unwrap();
+        assert_eq!(
+            expr,
+            Expression::WindowFunction {
+                name: "ROW_NUMBER".to_owned(),
+                args: vec![],
+                over: ast::WindowSpec {
+                    partition_by: vec![Expression::Identifier("a".into()), Expression::Identifier("b".into())],
+                    order_by: vec![order_by_expr("c", ast::Order::Desc)],
+                    window_frame: None,
+                },
+            }
+        );
+
+        let expr = Parser::new("SUM(a) OVER (ORDER BY b ROWS BETWEEN 2 PRECEDING AND UNBOUNDED FOLLOWING)")
+            .parse_expression(0)
+            .unwrap();
+        let Expression::WindowFunction { over, .. } = &expr else {
+            panic!("expected window function");
+        };
+        assert_eq!(
+            over.window_frame,
+            Some(ast::WindowFrame {
+                units: ast::WindowFrameUnits::Rows,
+                start_bound: ast::WindowFrameBound::Preceding(Some(2)),
+                end_bound: Some(ast::WindowFrameBound::Following(None)),
+            })
+        );
+        assert_eq!(
+            expr.to_string(),
+            "SUM(a) OVER (ORDER BY b ASC ROWS BETWEEN 2 PRECEDING AND UNBOUNDED FOLLOWING)"
+        );
+
+        let expr = Parser::new("LAG(a, 1) OVER (RANGE CURRENT ROW)")
+            .parse_expression(0)
+            .unwrap();
+        assert_eq!(expr.to_string(), "LAG(a, 1) OVER (RANGE CURRENT ROW)");
+
+        assert!(Parser::new("SUM(a) OVER (ROWS 1)").parse_expression(0).is_err());
+        assert!(Parser::new("SUM(a) OVER (ORDER BY b").parse_expression(0).is_err());
+    }
+
+    #[test]
+    fn test_case_expr() {
+        let expr = parse_expr("CASE WHEN a > 1 THEN 'x' WHEN a IS NULL THEN 'y' END").unwrap();
+        assert_eq!(
+            expr,
+            Expression::Case {
+                operand: None,
+                conditions: vec![
+                    (
+                        Expression::BinaryOperator(ast::BinaryOperator::Gt(
+                            Box::new(Expression::Identifier("a".into())),
+                            Box::new(Expression::Literal(ast::Literal::Int(1))),
+                        )),
+                        Expression::Literal(ast::Literal::String("x".to_owned())),
+                    ),
+                    (
+                        Expression::IsNull(Box::new(Expression::Identifier("a".into()))),
+                        Expression::Literal(ast::Literal::String("y".to_owned())),
+                    ),
+                ],
+                else_result: None,
+            }
+        );
+
+        let expr = parse_expr("CASE a + 1 WHEN 1 THEN b ELSE CASE WHEN c THEN 1 END END").unwrap();
+        assert_eq!(
+            expr.to_string(),
+            "CASE a + 1 WHEN 1 THEN b ELSE CASE WHEN c THEN 1 END END"
+        );
+
+        assert!(parse_expr("CASE a END").is_err());
+        assert!(parse_expr("CASE WHEN a THEN 1").is_err());
+        assert!(parse_expr("CASE WHEN a 1 END").is_err());
+    }
+
+    #[test]
+    fn test_cast_expr() {
+        let expr = parse_expr("CAST(a + 1 AS VARCHAR(10)) = b::text").unwrap();
+        assert_eq!(
+            expr,
+            Expression::BinaryOperator(ast::BinaryOperator::Eq(
+                Box::new(Expression::Cast {
+                    expr: Box::new(Expression::BinaryOperator(ast::BinaryOperator::Add(
+                        Box::new(Expression::Identifier("a".into())),
+                        Box::new(Expression::Literal(ast::Literal::Int(1))),
+                    ))),
+                    data_type: DataType::String,
+                }),
+                Box::new(Expression::Cast {
+                    expr: Box::new(Expression::Identifier("b".into())),
+                    data_type: DataType::String,
+                }),
+            ))
+        );
+
+        let data_type = |sql: &str| match parse_expr(sql).unwrap() {
+            Expression::Cast { data_type, .. } => data_type,
+            expr => panic!("expected cast expression, got {}", expr),
+        };
+        assert_eq!(
+            data_type("CAST(a AS DECIMAL(10, 2))"),
+            DataType::Decimal(Some(10), Some(2))
+        );
+        assert_eq!(data_type("CAST(a AS NUMERIC(10))"), DataType::Decimal(Some(10), None));
+        assert_eq!(data_type("CAST(a AS DOUBLE PRECISION)"), DataType::Float);
+        assert_eq!(data_type("CAST(a AS REAL)"), DataType::Float32);
+        assert_eq!(data_type("CAST(a AS CHARACTER VARYING(3))"), DataType::String);
+        assert_eq!(data_type("CAST(a AS TIMESTAMP)"), DataType::Timestamp);
+        assert_eq!(data_type("a::time"), DataType::Time);
+        assert_eq!(data_type("a::tinyint"), DataType::Int8);
+
+        assert!(parse_expr("CAST(a VARCHAR)").is_err());
+        assert!(parse_expr("CAST(a AS VARCHAR(n))").is_err());
+        assert!(parse_expr("CAST(a AS unknown)").is_err());
+    }
+
+    #[test]
+    fn test_time_literal() {
+        assert_eq!(
+            parse_expr("TIME '12:00:00'").unwrap(),
+            Expression::TypedString {
+                data_type: DataType::Time,
+                value: "12:00:00".to_owned(),
+            }
+        );
+        // time is still an identifier when not followed by a string
+        assert_eq!(parse_expr("time > 1").unwrap().to_string(), "time > 1");
+    }
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(
+            parse_expr("INTERVAL '1 day'").unwrap(),
+            Expression::Interval {
+                value: "1 day".to_owned(),
+                leading_field: None,
+                last_field: None,
+            }
+        );
+
+        let expr = parse_expr("d + INTERVAL '1-2' YEAR TO MONTH").unwrap();
+        assert_eq!(
+            expr,
+            Expression::BinaryOperator(ast::BinaryOperator::Add(
+                Box::new(Expression::Identifier("d".into())),
+                Box::new(Expression::Interval {
+                    value: "1-2".to_owned(),
+                    leading_field: Some(DateTimeField::Year),
+                    last_field: Some(DateTimeField::Month),
+                }),
+            ))
+        );
+        assert_eq!(expr.to_string(), "d + INTERVAL '1-2' YEAR TO MONTH");
+        assert_eq!(
+            parse_expr("INTERVAL '2' HOUR > a").unwrap().to_string(),
+            "INTERVAL '2' HOUR > a"
+        );
+
+        assert!(parse_expr("INTERVAL 2 HOUR").is_err());
+        assert!(parse_expr("INTERVAL '1' YEAR TO").is_err());
+    }
+
+    #[test]
+    fn test_with_column_list() {
+        let stmt = parse_stmt("WITH t(a, b) AS (SELECT 1, 2) SELECT a FROM t").unwrap();
+        let Statement::Select(select) = stmt else {
+            panic!("expected select statement");
+        };
+        let with = select.with.unwrap();
+
+        assert_eq!(with.cte_tables[0].columns, vec![Ident::from("a"), Ident::from("b")]);
+        assert_eq!(with.to_string(), "t(a, b) AS (SELECT 1, 2)");
+    }
+
+    #[test]
+    fn test_with_recursive() {
+        let mut parser =
+            Parser::new("WITH RECURSIVE t(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM t WHERE n < 5) SELECT n FROM t");
+        let Statement::Select(select) = parser.parse().unwrap() else {
+            panic!("expected select statement");
+        };
+        let with = select.with.unwrap();
+
+        assert!(with.recursive);
+        assert!(matches!(
+            *with.cte_tables[0].query,
+            Statement::SetOperation {
+                op: SetOperator::Union,
+                all: true,
+                ..
+            }
+        ));
+        assert_eq!(
+            with.to_string(),
+            "RECURSIVE t(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM t WHERE n < 5)"
+        );
+        // the self reference is not a table to be resolved
+        assert!(parser.tables.is_empty());
+
+        let stmt = parse_stmt("WITH RECURSIVE t AS (SELECT 1 AS n UNION SELECT n FROM t) SELECT * FROM t").unwrap();
+        let Statement::Select(select) = stmt else {
+            panic!("expected select statement");
+        };
+        assert!(matches!(
+            *select.with.unwrap().cte_tables[0].query,
+            Statement::SetOperation { all: false, .. }
+        ));
+    }
+
+    #[test]
+    fn test_set_operation() {
+        // INTERSECT binds tighter than UNION
+        let stmt = parse_stmt("SELECT a FROM t UNION SELECT b FROM u INTERSECT ALL SELECT c FROM v").unwrap();
+        let Statement::SetOperation {
+            op: SetOperator::Union,
+            all: false,
+            right,
+            ..
+        } = stmt
+        else {
+            panic!("expected union, got {stmt}");
+        };
+        assert!(matches!(
+            *right,
+            Statement::SetOperation {
+                op: SetOperator::Intersect,
+                all: true,
+                ..
+            }
+        ));
+
+        // the ORDER BY and LIMIT of the last query apply to the whole set operation
+        let stmt =
+            parse_stmt("SELECT a FROM t EXCEPT DISTINCT SELECT b FROM u EXCEPT SELECT c FROM v ORDER BY a LIMIT 1")
+                .unwrap();
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT a FROM t EXCEPT SELECT b FROM u EXCEPT SELECT c FROM v ORDER BY a ASC LIMIT 1"
+        );
+        let Statement::SetOperation { left, right, .. } = stmt else {
+            panic!("expected set operation");
+        };
+        assert!(matches!(
+            *left,
+            Statement::SetOperation {
+                op: SetOperator::Except,
+                ..
+            }
+        ));
+        assert!(matches!(*right, Statement::Select(select) if select.order_by.is_none() && select.limit.is_none()));
+    }
+
     #[test]
     fn test_with() {
         let stmt = parse_stmt("WITH t1 AS (SELECT * FROM users) SELECT * FROM t1;").unwrap();
@@ -3665,13 +6126,14 @@ mod tests {
                     recursive: false,
                     cte_tables: vec![ast::Cte {
                         alias: "t1".to_owned(),
-                        query: Box::new(Select {
+                        columns: vec![],
+                        query: Box::new(ast::Statement::Select(Box::new(Select {
                             with: None,
                             order_by: None,
                             distinct: None,
-                            columns: vec![SelectItem::Wildcard],
+                            columns: vec![SelectItem::Wildcard(vec![])],
                             from: vec![ast::From::Table {
-                                name: "users".to_owned(),
+                                name: vec!["users"].into(),
                                 alias: None,
                             }],
                             r#where: None,
@@ -3679,7 +6141,7 @@ mod tests {
                             having: None,
                             limit: None,
                             offset: None,
-                        }),
+                        }))),
                     }]
                 }),
                 order_by: None,
@@ -3687,9 +6149,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: "t1".to_owned(),
+                    name: vec!["t1"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3718,13 +6180,14 @@ mod tests {
                     cte_tables: vec![
                         ast::Cte {
                             alias: "t1".to_owned(),
-                            query: Box::new(Select {
+                            columns: vec![],
+                            query: Box::new(ast::Statement::Select(Box::new(Select {
                                 with: None,
                                 order_by: None,
                                 distinct: None,
-                                columns: vec![SelectItem::Wildcard],
+                                columns: vec![SelectItem::Wildcard(vec![])],
                                 from: vec![ast::From::Table {
-                                    name: "users".to_owned(),
+                                    name: vec!["users"].into(),
                                     alias: None,
                                 }],
                                 r#where: None,
@@ -3732,17 +6195,18 @@ mod tests {
                                 having: None,
                                 limit: None,
                                 offset: None,
-                            }),
+                            }))),
                         },
                         ast::Cte {
                             alias: "t2".to_owned(),
-                            query: Box::new(Select {
+                            columns: vec![],
+                            query: Box::new(ast::Statement::Select(Box::new(Select {
                                 with: None,
                                 order_by: None,
                                 distinct: None,
-                                columns: vec![SelectItem::Wildcard],
+                                columns: vec![SelectItem::Wildcard(vec![])],
                                 from: vec![ast::From::Table {
-                                    name: "pepole".to_owned(),
+                                    name: vec!["pepole"].into(),
                                     alias: None,
                                 }],
                                 r#where: None,
@@ -3750,7 +6214,7 @@ mod tests {
                                 having: None,
                                 limit: None,
                                 offset: None,
-                            }),
+                            }))),
                         },
                     ]
                 }),
@@ -3759,14 +6223,14 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![
                     ast::From::Table {
-                        name: "t1".to_owned(),
+                        name: vec!["t1"].into(),
                         alias: None,
                     },
                     ast::From::Table {
-                        name: "t2".to_owned(),
+                        name: vec!["t2"].into(),
                         alias: None,
                     },
                 ],
@@ -3776,6 +6240,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_grouping_sets() {
+        let stmt = parse_stmt("SELECT a, b, SUM(c) FROM t GROUP BY ROLLUP (a, b)").unwrap();
+        assert_eq!(stmt.to_string(), "SELECT a, b, SUM(c) FROM t GROUP BY ROLLUP (a, b)");
+
+        let stmt = parse_stmt("SELECT a FROM t GROUP BY a, cube(b, c + 1)").unwrap();
+        assert_eq!(stmt.to_string(), "SELECT a FROM t GROUP BY a, CUBE (b, c + 1)");
+
+        let stmt = parse_stmt("SELECT a FROM t GROUP BY GROUPING SETS ((a, b), a, ()) HAVING a > 1").unwrap();
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT a FROM t GROUP BY GROUPING SETS ((a, b), (a), ()) HAVING a > 1"
+        );
+
+        // the words can still be used as identifiers
+        let stmt = parse_stmt("SELECT grouping FROM t GROUP BY grouping, rollup").unwrap();
+        assert_eq!(stmt.to_string(), "SELECT grouping FROM t GROUP BY grouping, rollup");
+
+        assert!(parse_stmt("SELECT a FROM t GROUP BY GROUPING SETS ((a, b)").is_err());
+    }
+
     #[test]
     fn test_parse_group_by() {
         assert_stmt_eq(
@@ -3787,9 +6272,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: "users".to_owned(),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3806,9 +6291,9 @@ mod tests {
                 offset: None,
                 having: None,
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: "users".to_owned(),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3831,9 +6316,9 @@ mod tests {
                     Box::new(Expression::Literal(ast::Literal::Int(1))),
                 ))),
                 distinct: None,
-                columns: vec![SelectItem::Wildcard],
+                columns: vec![SelectItem::Wildcard(vec![])],
                 from: vec![ast::From::Table {
-                    name: "users".to_owned(),
+                    name: vec!["users"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -3864,7 +6349,7 @@ mod tests {
                     SelectItem::UnNamedExpr(Expression::Identifier("name".into())),
                 ],
                 from: vec![ast::From::Table {
-                    name: "user".to_owned(),
+                    name: vec!["user"].into(),
                     alias: None,
                 }],
                 r#where: None,
@@ -4060,6 +6545,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_distinct_function() {
+        let stmt = parse_expr("COUNT(DISTINCT a)").unwrap();
+
+        assert_eq!(
+            stmt,
+            Expression::DistinctFunction("COUNT".to_owned(), vec![Expression::Identifier(Ident::from("a"))])
+        );
+        assert_eq!(stmt.to_string(), "COUNT(DISTINCT a)");
+
+        assert!(parse_expr("COUNT(DISTINCT)").is_err());
+    }
+
+    #[test]
+    fn test_parse_within_group() {
+        let stmt = parse_expr("PERCENTILE_DISC(0.25) WITHIN GROUP (ORDER BY a DESC)").unwrap();
+
+        assert_eq!(
+            stmt,
+            Expression::WithinGroup {
+                name: "PERCENTILE_DISC".to_owned(),
+                args: vec![Expression::Literal(ast::Literal::Float(0.25))],
+                order_by: Box::new(OrderByExpr {
+                    expr: Expression::Identifier(Ident::from("a")),
+                    order: ast::Order::Desc,
+                    nulls_first: None,
+                }),
+            }
+        );
+        assert_eq!(stmt.to_string(), "PERCENTILE_DISC(0.25) WITHIN GROUP (ORDER BY a DESC)");
+
+        assert!(parse_expr("PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY a, b)").is_err());
+    }
+
+    #[test]
+    fn test_parse_aggregate_filter() {
+        let stmt = parse_expr("COUNT(DISTINCT a) FILTER (WHERE b > 1)").unwrap();
+
+        assert_eq!(
+            stmt,
+            Expression::AggregateFilter {
+                function: Box::new(Expression::DistinctFunction(
+                    "COUNT".to_owned(),
+                    vec![Expression::Identifier(Ident::from("a"))]
+                )),
+                filter: Box::new(Expression::BinaryOperator(ast::BinaryOperator::Gt(
+                    Box::new(Expression::Identifier(Ident::from("b"))),
+                    Box::new(Expression::Literal(ast::Literal::Int(1))),
+                ))),
+            }
+        );
+        assert_eq!(stmt.to_string(), "COUNT(DISTINCT a) FILTER (WHERE b > 1)");
+
+        assert!(parse_expr("COUNT(a) FILTER (b > 1)").is_err());
+    }
+
     #[test]
     fn test_parse_prefix_expression() {
         let stmt = parse_expr("-123").unwrap();
@@ -4253,6 +6794,144 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_quoted_identifier() {
+        let quoted = |value: &str, q: char| Ident {
+            value: value.to_owned(),
+            quote_style: Some(q),
+        };
+
+        assert_eq!(
+            parse_expr(r#""My Table"."select""#).unwrap(),
+            Expression::CompoundIdentifier(vec![quoted("My Table", '"'), quoted("select", '"')])
+        );
+
+        let mut parser = Parser::with_dialect("`My Table`.`select`", &MySqlDialect);
+        assert_eq!(
+            parser.parse_expression(0).unwrap(),
+            Expression::CompoundIdentifier(vec![quoted("My Table", '`'), quoted("select", '`')])
+        );
+
+        // backticks are not allowed in the default dialect
+        assert!(parse_expr("`a`").is_err());
+
+        let mut parser = Parser::with_dialect("SELECT `id` FROM `user` AS `u`", &MySqlDialect);
+        assert_eq!(
+            parser.parse().unwrap(),
+            Statement::Select(Box::new(Select {
+                with: None,
+                distinct: None,
+                columns: vec![SelectItem::UnNamedExpr(Expression::Identifier(quoted("id", '`')))],
+                from: vec![ast::From::Table {
+                    name: ast::ObjectName(vec![quoted("user", '`')]),
+                    alias: Some("u".to_owned()),
+                }],
+                r#where: None,
+                group_by: None,
+                having: None,
+                order_by: None,
+                limit: None,
+                offset: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_dialect() {
+        let parse =
+            |sql: &str, dialect: &dyn Dialect| Parser::with_dialect(sql, dialect).parse().map(|s| s.to_string());
+
+        // `||` concatenates strings and binds tighter than comparisons, in MySQL it is `OR`
+        assert_eq!(
+            parse("SELECT a || 'x' = b FROM t", &GenericDialect).unwrap(),
+            "SELECT a || 'x' = b FROM t"
+        );
+        assert_eq!(
+            parse_expr("a || 'x' = b").unwrap(),
+            Expression::BinaryOperator(ast::BinaryOperator::Eq(
+                Box::new(Expression::BinaryOperator(ast::BinaryOperator::StringConcat(
+                    Box::new(Expression::Identifier("a".into())),
+                    Box::new(Expression::Literal(ast::Literal::String("x".to_owned()))),
+                ))),
+                Box::new(Expression::Identifier("b".into())),
+            ))
+        );
+        assert_eq!(
+            parse("SELECT a || b = c FROM t", &MySqlDialect).unwrap(),
+            "SELECT a OR b = c FROM t"
+        );
+
+        // the keywords of the syntax a dialect does not have are plain identifiers
+        assert_eq!(
+            parse("SELECT pivot FROM t AS tablesample", &MySqlDialect).unwrap(),
+            "SELECT pivot FROM t AS tablesample"
+        );
+        assert!(parse("SELECT pivot FROM t", &GenericDialect).is_err());
+        assert_eq!(
+            parse("SELECT * FROM t pivot", &PostgreSqlDialect).unwrap(),
+            "SELECT * FROM t AS pivot"
+        );
+        assert!(parse("SELECT * FROM t TABLESAMPLE SYSTEM (1)", &PostgreSqlDialect).is_ok());
+
+        // `::` is not an operator in MySQL, the expression ends before it
+        assert_eq!(
+            parse("SELECT a::int FROM t", &PostgreSqlDialect).unwrap(),
+            "SELECT CAST(a AS INTEGER) FROM t"
+        );
+        let mut parser = Parser::with_dialect("a::int", &MySqlDialect);
+        assert_eq!(parser.parse_expression(0).unwrap(), Expression::Identifier("a".into()));
+
+        assert!(parse("SELECT * FROM t LEFT SEMI JOIN u", &PostgreSqlDialect).is_err());
+
+        // double quotes are a string in MySQL, the identifiers are quoted with backticks
+        assert_eq!(
+            parse(r#"SELECT "a", `b` FROM t WHERE c = "x""y""#, &MySqlDialect).unwrap(),
+            r#"SELECT 'a', `b` FROM t WHERE c = 'x"y'"#
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let sqls = [
+            "SELECT (a + b) * c, a - (b - c), -(a + b), NOT (a AND b), (NOT a) = b FROM t",
+            "SELECT a FROM t WHERE (a = 1 OR b = 2) AND c NOT IN (1, 2) AND (a + b) IS NULL",
+            "SELECT (a IS DISTINCT FROM b) IS NULL, a IS NOT DISTINCT FROM b + c, -(-a) FROM t",
+            "SELECT 'it''s', 1.0, 2.5, NULL, true, $1, a || 'x' FROM \"My Table\"",
+            "SELECT a FROM t WHERE a LIKE 'x!%' ESCAPE '!' OR (a || b) ILIKE 'y'",
+            "SELECT CAST(a AS DECIMAL(10, 2)), a::BIGINT, DATE '2021-01-01', TIMESTAMP '2021-01-01 00:00:00' FROM t",
+            "SELECT INTERVAL '1-2' YEAR TO MONTH, EXTRACT(YEAR FROM d), CASE a WHEN 1 THEN 'x' ELSE 'y' END FROM t",
+            "SELECT {'a': 1}, [1, 'b'] FROM read_csv('./test.csv', header = true) AS t",
+            "SELECT * FROM './tests/test.csv'",
+            "SELECT * FROM s.\"My Table\" AS t, 'data.parquet' WHERE a NOT BETWEEN -b AND (b IS NULL) + 1",
+            "WITH RECURSIVE t(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM t WHERE n < 5) SELECT n FROM t",
+            "SELECT DISTINCT ON (a) a, sum(b) OVER (PARTITION BY a ORDER BY b DESC) FROM t ORDER BY a LIMIT 1 OFFSET 2",
+            "SELECT a, count(*) FROM t GROUP BY ROLLUP (a, b) HAVING count(*) > 1",
+            "SELECT * FROM t1 LEFT JOIN t2 ON t1.a = t2.a, LATERAL (SELECT a FROM t3) AS x(c)",
+            "SELECT * FROM t PIVOT (SUM(v) FOR k IN ('a' AS x, 'b')) AS p",
+            "SELECT * FROM a JOIN (b LEFT JOIN c ON b.x = c.x) ON a.x = b.x RIGHT JOIN d USING (x)",
+            "SELECT * FROM (a CROSS JOIN b) UNPIVOT (v FOR k IN (x, y))",
+            "SELECT a FROM t UNION ALL SELECT a FROM u ORDER BY a",
+            "INSERT INTO t (a, b) SELECT a, b FROM u",
+            "INSERT INTO t AS x VALUES (1, 'a') ON CONFLICT (a) DO UPDATE SET b = 'b' RETURNING a",
+            "UPDATE t SET a = 'x' WHERE b = 1",
+            "CREATE TABLE t AS WITH c AS (SELECT 1 AS a) SELECT a FROM c",
+            "CREATE TABLE t (a INT PRIMARY KEY, b VARCHAR NOT NULL, c REAL, d TINYINT, e TIME)",
+            "PREPARE q (INT, SMALLINT) AS SELECT a FROM t WHERE a = $1",
+            "EXECUTE q (1, 'a')",
+            "COPY t TO 'out.csv' WITH (FORMAT csv, DELIMITER '|', HEADER true)",
+            "COPY (SELECT a FROM t WHERE a > 1) TO 'out.parquet' WITH (FORMAT parquet)",
+            "COPY t TO 'out.csv.gz' (FORMAT csv, COMPRESSION gzip)",
+            "SET search_path TO s1, 's2'",
+            "SHOW timezone",
+        ];
+
+        for sql in sqls {
+            let stmt = parse_stmt(sql).unwrap();
+            let unparsed = stmt.to_string();
+            assert_eq!(parse_stmt(&unparsed).unwrap(), stmt, "{} unparsed as {}", sql, unparsed);
+        }
+    }
+
     fn parse_stmt(input: &str) -> Result<Statement> {
         let mut parser = Parser::new(input);
         parser.parse()
@@ -6,6 +6,7 @@ pub enum Keyword {
     Select,
     Insert,
     Delete,
+    Truncate,
     Create,
     Drop,
     Schema,
@@ -16,6 +17,8 @@ pub enum Keyword {
     If,
     Exists,
     Like,
+    ILike,
+    Escape,
     With,
     From,
     Where,
@@ -37,6 +40,7 @@ pub enum Keyword {
     Values,
     Limit,
     Offset,
+    Fetch,
     Group,
     Distinct,
     Having,
@@ -52,6 +56,33 @@ pub enum Keyword {
     Right,
     Full,
     Cross,
+    Natural,
+    Using,
+    Lateral,
+    Tablesample,
+    Pivot,
+    Unpivot,
+    Recursive,
+    Union,
+    Intersect,
+    Except,
+    All,
+    Case,
+    When,
+    Then,
+    Else,
+    End,
+    /// window function keywords
+    Over,
+    Partition,
+    Rows,
+    Range,
+    Between,
+    Unbounded,
+    Preceding,
+    Following,
+    Current,
+    Row,
     /// Copy statement keywords
     Copy,
     To,
@@ -60,7 +91,19 @@ pub enum Keyword {
     Delimiter,
     ///
     Show,
+    Explain,
     Tables,
+    Columns,
+    Describe,
+    /// prepared statement keywords
+    Prepare,
+    Execute,
+    Deallocate,
+    /// transaction keywords
+    Begin,
+    Start,
+    Commit,
+    Rollback,
 
     /// data types
     Int,
@@ -75,6 +118,8 @@ pub enum Keyword {
     SmallInt,
     BigInt,
     Decimal,
+    Cast,
+    Interval,
     /// extract a field from a timestamp
     Extract,
     Year,
@@ -91,9 +136,13 @@ pub enum TokenType {
     EOF,
     /// Identifiers + literals
     Ident,
+    /// identifier wrapped in quotes, holds the quote character, e.g. `"name"`
+    QuotedIdent(char),
     String,
     Int,
     Float,
+    /// positional parameter of a prepared statement, `$1`
+    Placeholder,
     /// Operators
     Assign,
     Plus,
@@ -112,6 +161,8 @@ pub enum TokenType {
     NotEq,
     Lte,
     Gte,
+    /// `||`, string concatenation or `OR` depending on the dialect
+    DoublePipe,
     /// Delimiters
     Comma,
     Semicolon,
@@ -124,7 +175,120 @@ pub enum TokenType {
     Keyword(Keyword),
 }
 
+impl Keyword {
+    /// Whether the keyword is reserved. A non-reserved keyword only has a meaning at some places of a statement,
+    /// elsewhere it is an identifier, eg. `SELECT year, key FROM t`.
+    pub fn is_reserved(&self) -> bool {
+        !matches!(
+            self,
+            Keyword::Schema
+                | Keyword::Key
+                | Keyword::If
+                | Keyword::Do
+                | Keyword::Conflict
+                | Keyword::Nothing
+                | Keyword::Asc
+                | Keyword::Desc
+                | Keyword::Recursive
+                | Keyword::Partition
+                | Keyword::Rows
+                | Keyword::Range
+                | Keyword::Unbounded
+                | Keyword::Preceding
+                | Keyword::Following
+                | Keyword::Current
+                | Keyword::Row
+                | Keyword::Copy
+                | Keyword::To
+                | Keyword::Format
+                | Keyword::Header
+                | Keyword::Delimiter
+                | Keyword::Show
+                | Keyword::Explain
+                | Keyword::Tables
+                | Keyword::Columns
+                | Keyword::Describe
+                | Keyword::Truncate
+                | Keyword::Prepare
+                | Keyword::Execute
+                | Keyword::Deallocate
+                | Keyword::Begin
+                | Keyword::Start
+                | Keyword::Commit
+                | Keyword::Rollback
+                | Keyword::Int
+                | Keyword::Integer
+                | Keyword::Bool
+                | Keyword::Boolean
+                | Keyword::Date
+                | Keyword::Datetime
+                | Keyword::VarChar
+                | Keyword::Timestamp
+                | Keyword::Double
+                | Keyword::SmallInt
+                | Keyword::BigInt
+                | Keyword::Decimal
+                | Keyword::Year
+                | Keyword::Month
+                | Keyword::Day
+                | Keyword::Hour
+                | Keyword::Minute
+                | Keyword::Second
+        )
+    }
+}
+
+impl std::fmt::Display for TokenType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            TokenType::ILLIGAL => return write!(f, "illegal character"),
+            TokenType::EOF => return write!(f, "EOF"),
+            TokenType::Ident | TokenType::QuotedIdent(_) => return write!(f, "identifier"),
+            TokenType::String => return write!(f, "string"),
+            TokenType::Int => return write!(f, "integer"),
+            TokenType::Float => return write!(f, "float"),
+            TokenType::Placeholder => return write!(f, "placeholder"),
+            TokenType::Keyword(keyword) => return write!(f, "{}", format!("{:?}", keyword).to_uppercase()),
+            TokenType::Assign => ":=",
+            TokenType::Plus => "+",
+            TokenType::Minus => "-",
+            TokenType::LParen => "(",
+            TokenType::RParen => ")",
+            TokenType::LBrace => "{",
+            TokenType::LSquareBrace => "[",
+            TokenType::RSquareBrace => "]",
+            TokenType::RBrace => "}",
+            TokenType::Asterisk => "*",
+            TokenType::Slash => "/",
+            TokenType::Lt => "<",
+            TokenType::Gt => ">",
+            TokenType::Eq => "=",
+            TokenType::NotEq => "!=",
+            TokenType::Lte => "<=",
+            TokenType::Gte => ">=",
+            TokenType::DoublePipe => "||",
+            TokenType::Comma => ",",
+            TokenType::Semicolon => ";",
+            TokenType::Colon => ":",
+            TokenType::Bang => "!",
+            TokenType::Period => ".",
+            TokenType::DoubleColon => "::",
+            TokenType::Question => "?",
+        };
+        write!(f, "`{}`", symbol)
+    }
+}
+
 impl TokenType {
+    /// Whether the token can be an identifier, a plain or quoted identifier or a non-reserved keyword
+    pub fn is_identifier(&self) -> bool {
+        match self {
+            TokenType::Ident | TokenType::QuotedIdent(_) => true,
+            TokenType::Keyword(keyword) => !keyword.is_reserved(),
+            _ => false,
+        }
+    }
+
     pub fn lookup_ident(ident: &str) -> TokenType {
         match ident.to_lowercase().as_str() {
             "select" => TokenType::Keyword(Keyword::Select),
@@ -141,12 +305,17 @@ impl TokenType {
             "smallint" => TokenType::Keyword(Keyword::SmallInt),
             "bigint" => TokenType::Keyword(Keyword::BigInt),
             "decimal" => TokenType::Keyword(Keyword::Decimal),
+            "cast" => TokenType::Keyword(Keyword::Cast),
+            "interval" => TokenType::Keyword(Keyword::Interval),
             "primary" => TokenType::Keyword(Keyword::Primary),
             "key" => TokenType::Keyword(Keyword::Key),
             "like" => TokenType::Keyword(Keyword::Like),
+            "ilike" => TokenType::Keyword(Keyword::ILike),
+            "escape" => TokenType::Keyword(Keyword::Escape),
             "with" => TokenType::Keyword(Keyword::With),
             "unique" => TokenType::Keyword(Keyword::Unique),
             "delete" => TokenType::Keyword(Keyword::Delete),
+            "truncate" => TokenType::Keyword(Keyword::Truncate),
             "drop" => TokenType::Keyword(Keyword::Drop),
             "create" => TokenType::Keyword(Keyword::Create),
             "schema" => TokenType::Keyword(Keyword::Schema),
@@ -171,6 +340,7 @@ impl TokenType {
             "values" => TokenType::Keyword(Keyword::Values),
             "limit" => TokenType::Keyword(Keyword::Limit),
             "offset" => TokenType::Keyword(Keyword::Offset),
+            "fetch" => TokenType::Keyword(Keyword::Fetch),
             "update" => TokenType::Keyword(Keyword::Update),
             "group" => TokenType::Keyword(Keyword::Group),
             "on" => TokenType::Keyword(Keyword::On),
@@ -187,6 +357,33 @@ impl TokenType {
             "right" => TokenType::Keyword(Keyword::Right),
             "full" => TokenType::Keyword(Keyword::Full),
             "cross" => TokenType::Keyword(Keyword::Cross),
+            "natural" => TokenType::Keyword(Keyword::Natural),
+            "using" => TokenType::Keyword(Keyword::Using),
+            "lateral" => TokenType::Keyword(Keyword::Lateral),
+            "tablesample" => TokenType::Keyword(Keyword::Tablesample),
+            "pivot" => TokenType::Keyword(Keyword::Pivot),
+            "unpivot" => TokenType::Keyword(Keyword::Unpivot),
+            "recursive" => TokenType::Keyword(Keyword::Recursive),
+            "union" => TokenType::Keyword(Keyword::Union),
+            "intersect" => TokenType::Keyword(Keyword::Intersect),
+            "except" => TokenType::Keyword(Keyword::Except),
+            "all" => TokenType::Keyword(Keyword::All),
+            "case" => TokenType::Keyword(Keyword::Case),
+            "when" => TokenType::Keyword(Keyword::When),
+            "then" => TokenType::Keyword(Keyword::Then),
+            "else" => TokenType::Keyword(Keyword::Else),
+            "end" => TokenType::Keyword(Keyword::End),
+            // window function
+            "over" => TokenType::Keyword(Keyword::Over),
+            "partition" => TokenType::Keyword(Keyword::Partition),
+            "rows" => TokenType::Keyword(Keyword::Rows),
+            "range" => TokenType::Keyword(Keyword::Range),
+            "between" => TokenType::Keyword(Keyword::Between),
+            "unbounded" => TokenType::Keyword(Keyword::Unbounded),
+            "preceding" => TokenType::Keyword(Keyword::Preceding),
+            "following" => TokenType::Keyword(Keyword::Following),
+            "current" => TokenType::Keyword(Keyword::Current),
+            "row" => TokenType::Keyword(Keyword::Row),
             "null" => TokenType::Keyword(Keyword::Null),
             "copy" => TokenType::Keyword(Keyword::Copy),
             "to" => TokenType::Keyword(Keyword::To),
@@ -194,7 +391,17 @@ impl TokenType {
             "header" => TokenType::Keyword(Keyword::Header),
             "delimiter" => TokenType::Keyword(Keyword::Delimiter),
             "show" => TokenType::Keyword(Keyword::Show),
+            "explain" => TokenType::Keyword(Keyword::Explain),
             "tables" => TokenType::Keyword(Keyword::Tables),
+            "columns" => TokenType::Keyword(Keyword::Columns),
+            "describe" => TokenType::Keyword(Keyword::Describe),
+            "prepare" => TokenType::Keyword(Keyword::Prepare),
+            "execute" => TokenType::Keyword(Keyword::Execute),
+            "deallocate" => TokenType::Keyword(Keyword::Deallocate),
+            "begin" => TokenType::Keyword(Keyword::Begin),
+            "start" => TokenType::Keyword(Keyword::Start),
+            "commit" => TokenType::Keyword(Keyword::Commit),
+            "rollback" => TokenType::Keyword(Keyword::Rollback),
             // extract a field from a timestamp
             "extract" => TokenType::Keyword(Keyword::Extract),
             "year" => TokenType::Keyword(Keyword::Year),
@@ -237,11 +444,25 @@ pub struct Location {
     pub column: usize,
 }
 
+/// Byte offsets `[start, end)` of a token in the source text
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Token {
     pub token_type: TokenType,
     pub literal: String,
     pub location: Location,
+    pub span: Span,
 }
 
 impl Token {
@@ -250,6 +471,7 @@ impl Token {
             token_type,
             literal,
             location,
+            span: Span::default(),
         }
     }
 
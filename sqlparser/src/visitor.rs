@@ -0,0 +1,502 @@
+use std::ops::ControlFlow;
+
+use crate::ast::{
+    BinaryOperator, CopySource, Distinct, Expression, From, JoinConstraint, OnConflict, Select, SelectItem, Statement,
+};
+
+/// Visits the statements, table references and expressions of a query in depth first order, see [`walk_statement`].
+///
+/// Each node is passed to the `pre_visit` method before its children and to the `post_visit` method after them,
+/// returning [`ControlFlow::Break`] from any method stops the traversal.
+pub trait Visitor {
+    type Break;
+
+    fn pre_visit_statement(&mut self, _statement: &Statement) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_statement(&mut self, _statement: &Statement) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_table(&mut self, _table: &From) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_table(&mut self, _table: &From) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expression(&mut self, _expr: &Expression) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_expression(&mut self, _expr: &Expression) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// Like [`Visitor`] but the nodes can be rewritten in place, see [`walk_statement_mut`].
+///
+/// The children of a node are visited after `pre_visit`, so they are the children of the rewritten node,
+/// a rewrite in `post_visit` is not visited again.
+pub trait VisitorMut {
+    type Break;
+
+    fn pre_visit_statement(&mut self, _statement: &mut Statement) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_statement(&mut self, _statement: &mut Statement) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_table(&mut self, _table: &mut From) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_table(&mut self, _table: &mut From) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expression(&mut self, _expr: &mut Expression) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_expression(&mut self, _expr: &mut Expression) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// The walk functions of [`Visitor`] and [`VisitorMut`], both traverse the AST the same way and differ only in
+/// the mutability of the references.
+macro_rules! walk_functions {
+    (
+        $visitor: ident,
+        $walk_statement: ident,
+        $walk_select: ident,
+        $walk_table: ident,
+        $walk_expression: ident
+        $(, $mut: tt)?
+    ) => {
+        /// Visit a statement and all the queries, table references and expressions in it.
+        pub fn $walk_statement<V: $visitor>(visitor: &mut V, statement: &$($mut)? Statement) -> ControlFlow<V::Break> {
+            visitor.pre_visit_statement(statement)?;
+
+            match statement {
+                Statement::CreateTable { query: Some(query), .. }
+                | Statement::CreateView { query, .. }
+                | Statement::Explain { statement: query, .. }
+                | Statement::Prepare { statement: query, .. } => $walk_statement(visitor, query)?,
+                Statement::Select(select) => $walk_select(visitor, select)?,
+                Statement::SetOperation {
+                    left,
+                    right,
+                    order_by,
+                    limit,
+                    offset,
+                    ..
+                } => {
+                    $walk_statement(visitor, left)?;
+                    $walk_statement(visitor, right)?;
+                    for order_by in order_by.into_iter().flatten() {
+                        $walk_expression(visitor, &$($mut)? order_by.expr)?;
+                    }
+                    for expr in limit.into_iter().chain(offset) {
+                        $walk_expression(visitor, expr)?;
+                    }
+                }
+                Statement::Values(values) => {
+                    for expr in values.into_iter().flatten() {
+                        $walk_expression(visitor, expr)?;
+                    }
+                }
+                Statement::Insert {
+                    columns,
+                    values,
+                    on_conflict,
+                    returning,
+                    query,
+                    ..
+                } => {
+                    for expr in columns.into_iter().flatten().chain(values.into_iter().flatten()) {
+                        $walk_expression(visitor, expr)?;
+                    }
+                    if let Some(OnConflict::DoUpdate { values, .. }) = on_conflict {
+                        for expr in values {
+                            $walk_expression(visitor, expr)?;
+                        }
+                    }
+                    for item in returning.into_iter().flatten() {
+                        if let SelectItem::UnNamedExpr(expr) | SelectItem::ExprWithAlias(expr, _) = item {
+                            $walk_expression(visitor, expr)?;
+                        }
+                    }
+                    if let Some(query) = query {
+                        $walk_select(visitor, query)?;
+                    }
+                }
+                Statement::Update {
+                    assignments, r#where, ..
+                } => {
+                    for assignment in assignments {
+                        $walk_expression(visitor, &$($mut)? assignment.value)?;
+                    }
+                    if let Some(expr) = r#where {
+                        $walk_expression(visitor, expr)?;
+                    }
+                }
+                Statement::Delete {
+                    r#where: Some(expr), ..
+                } => $walk_expression(visitor, expr)?,
+                Statement::Execute { parameters, .. } => {
+                    for expr in parameters {
+                        $walk_expression(visitor, expr)?;
+                    }
+                }
+                Statement::Copy {
+                    source: CopySource::Query(query),
+                    ..
+                } => $walk_select(visitor, query)?,
+                _ => {}
+            }
+
+            visitor.post_visit_statement(statement)
+        }
+
+        /// Visit the CTEs, the table references and the expressions of a `SELECT`. A `SELECT` is not always a
+        /// statement, eg. a scalar subquery, so it has no visit methods of its own.
+        pub fn $walk_select<V: $visitor>(visitor: &mut V, select: &$($mut)? Select) -> ControlFlow<V::Break> {
+            if let Some(with) = &$($mut)? select.with {
+                for cte in &$($mut)? with.cte_tables {
+                    $walk_statement(visitor, &$($mut)? cte.query)?;
+                }
+            }
+            if let Some(Distinct::DISTINCT(exprs)) = &$($mut)? select.distinct {
+                for expr in exprs {
+                    $walk_expression(visitor, expr)?;
+                }
+            }
+            for item in &$($mut)? select.columns {
+                if let SelectItem::UnNamedExpr(expr) | SelectItem::ExprWithAlias(expr, _) = item {
+                    $walk_expression(visitor, expr)?;
+                }
+            }
+            for table in &$($mut)? select.from {
+                $walk_table(visitor, table)?;
+            }
+            let group_by = (&$($mut)? select.group_by).into_iter().flatten();
+            for expr in (&$($mut)? select.r#where).into_iter().chain(group_by) {
+                $walk_expression(visitor, expr)?;
+            }
+            if let Some(expr) = &$($mut)? select.having {
+                $walk_expression(visitor, expr)?;
+            }
+            for order_by in (&$($mut)? select.order_by).into_iter().flatten() {
+                $walk_expression(visitor, &$($mut)? order_by.expr)?;
+            }
+            for expr in (&$($mut)? select.limit).into_iter().chain(&$($mut)? select.offset) {
+                $walk_expression(visitor, expr)?;
+            }
+
+            ControlFlow::Continue(())
+        }
+
+        /// Visit a table reference of `FROM`, the nested table references and the queries and expressions in them.
+        pub fn $walk_table<V: $visitor>(visitor: &mut V, table: &$($mut)? From) -> ControlFlow<V::Break> {
+            visitor.pre_visit_table(table)?;
+
+            match table {
+                From::Table { .. } => {}
+                From::TableFunction { args, .. } => {
+                    for arg in args {
+                        $walk_expression(visitor, &$($mut)? arg.value)?;
+                    }
+                }
+                From::SubQuery { query, .. } => $walk_statement(visitor, query)?,
+                From::Values { values, .. } => {
+                    for expr in values.into_iter().flatten() {
+                        $walk_expression(visitor, expr)?;
+                    }
+                }
+                From::TableSample { table, sample } => {
+                    $walk_table(visitor, table)?;
+                    $walk_expression(visitor, &$($mut)? sample.percent)?;
+                    if let Some(seed) = &$($mut)? sample.seed {
+                        $walk_expression(visitor, seed)?;
+                    }
+                }
+                From::Pivot {
+                    table,
+                    aggregate,
+                    values,
+                    ..
+                } => {
+                    $walk_table(visitor, table)?;
+                    $walk_expression(visitor, aggregate)?;
+                    for value in values {
+                        $walk_expression(visitor, &$($mut)? value.value)?;
+                    }
+                }
+                From::Unpivot { table, .. } => $walk_table(visitor, table)?,
+                From::Join {
+                    left,
+                    right,
+                    constraint,
+                    ..
+                } => {
+                    $walk_table(visitor, left)?;
+                    $walk_table(visitor, right)?;
+                    if let JoinConstraint::On(expr) = constraint {
+                        $walk_expression(visitor, expr)?;
+                    }
+                }
+            }
+
+            visitor.post_visit_table(table)
+        }
+
+        /// Visit an expression, its operands and the subqueries in it.
+        pub fn $walk_expression<V: $visitor>(visitor: &mut V, expr: &$($mut)? Expression) -> ControlFlow<V::Break> {
+            visitor.pre_visit_expression(expr)?;
+
+            match expr {
+                Expression::Identifier(_)
+                | Expression::CompoundIdentifier(_)
+                | Expression::Literal(_)
+                | Expression::Placeholder(_)
+                | Expression::TypedString { .. }
+                | Expression::Interval { .. } => {}
+                Expression::BinaryOperator(
+                    BinaryOperator::Eq(left, right)
+                    | BinaryOperator::NotEq(left, right)
+                    | BinaryOperator::And(left, right)
+                    | BinaryOperator::Or(left, right)
+                    | BinaryOperator::Gt(left, right)
+                    | BinaryOperator::Gte(left, right)
+                    | BinaryOperator::Lt(left, right)
+                    | BinaryOperator::Lte(left, right)
+                    | BinaryOperator::IsDistinctFrom(left, right)
+                    | BinaryOperator::IsNotDistinctFrom(left, right)
+                    | BinaryOperator::Add(left, right)
+                    | BinaryOperator::Sub(left, right)
+                    | BinaryOperator::Mul(left, right)
+                    | BinaryOperator::Div(left, right)
+                    | BinaryOperator::StringConcat(left, right),
+                )
+                | Expression::Like { left, right, .. } => {
+                    $walk_expression(visitor, left)?;
+                    $walk_expression(visitor, right)?;
+                }
+                Expression::Between { expr, low, high, .. } => {
+                    $walk_expression(visitor, expr)?;
+                    $walk_expression(visitor, low)?;
+                    $walk_expression(visitor, high)?;
+                }
+                Expression::Function(_, args)
+                | Expression::DistinctFunction(_, args)
+                | Expression::Array(args)
+                | Expression::Rollup(args)
+                | Expression::Cube(args) => {
+                    for arg in args {
+                        $walk_expression(visitor, arg)?;
+                    }
+                }
+                Expression::WithinGroup { args, order_by, .. } => {
+                    for arg in args {
+                        $walk_expression(visitor, arg)?;
+                    }
+                    $walk_expression(visitor, &$($mut)? order_by.expr)?;
+                }
+                Expression::AggregateFilter { function, filter } => {
+                    $walk_expression(visitor, function)?;
+                    $walk_expression(visitor, filter)?;
+                }
+                Expression::WindowFunction { args, over, .. } => {
+                    for expr in args.into_iter().chain(&$($mut)? over.partition_by) {
+                        $walk_expression(visitor, expr)?;
+                    }
+                    for order_by in &$($mut)? over.order_by {
+                        $walk_expression(visitor, &$($mut)? order_by.expr)?;
+                    }
+                }
+                Expression::Struct(fields) => {
+                    for field in fields {
+                        $walk_expression(visitor, &$($mut)? field.name)?;
+                        $walk_expression(visitor, &$($mut)? field.value)?;
+                    }
+                }
+                Expression::SubQuery(select) => $walk_select(visitor, select)?,
+                Expression::InList { field, list, .. } => {
+                    $walk_expression(visitor, field)?;
+                    for expr in list {
+                        $walk_expression(visitor, expr)?;
+                    }
+                }
+                Expression::InSubQuery { field, query, .. } => {
+                    $walk_expression(visitor, field)?;
+                    $walk_statement(visitor, query)?;
+                }
+                Expression::Cast { expr, .. }
+                | Expression::Extract { expr, .. }
+                | Expression::IsNull(expr)
+                | Expression::IsNotNull(expr)
+                | Expression::UnaryOperator { expr, .. } => $walk_expression(visitor, expr)?,
+                Expression::Case {
+                    operand,
+                    conditions,
+                    else_result,
+                } => {
+                    if let Some(operand) = operand {
+                        $walk_expression(visitor, operand)?;
+                    }
+                    for (condition, result) in conditions {
+                        $walk_expression(visitor, condition)?;
+                        $walk_expression(visitor, result)?;
+                    }
+                    if let Some(else_result) = else_result {
+                        $walk_expression(visitor, else_result)?;
+                    }
+                }
+                Expression::GroupingSets(sets) => {
+                    for expr in sets.into_iter().flatten() {
+                        $walk_expression(visitor, expr)?;
+                    }
+                }
+            }
+
+            visitor.post_visit_expression(expr)
+        }
+    };
+}
+
+walk_functions!(Visitor, walk_statement, walk_select, walk_table, walk_expression);
+walk_functions!(
+    VisitorMut,
+    walk_statement_mut,
+    walk_select_mut,
+    walk_table_mut,
+    walk_expression_mut,
+    mut
+);
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+
+    use super::{walk_statement, walk_statement_mut, Visitor, VisitorMut};
+    use crate::ast::{Expression, From, Literal, Statement};
+    use crate::parser::Parser;
+
+    fn parse(sql: &str) -> Statement {
+        Parser::new(sql).parse().unwrap()
+    }
+
+    #[derive(Default)]
+    struct Collector {
+        tables: Vec<String>,
+        columns: Vec<String>,
+        subqueries: usize,
+    }
+
+    impl Visitor for Collector {
+        type Break = ();
+
+        fn pre_visit_table(&mut self, table: &From) -> ControlFlow<Self::Break> {
+            if let From::Table { name, .. } = table {
+                self.tables.push(name.value());
+            }
+            ControlFlow::Continue(())
+        }
+
+        fn pre_visit_expression(&mut self, expr: &Expression) -> ControlFlow<Self::Break> {
+            match expr {
+                Expression::Identifier(ident) => self.columns.push(ident.value.clone()),
+                Expression::SubQuery(_) | Expression::InSubQuery { .. } => self.subqueries += 1,
+                _ => {}
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[test]
+    fn test_visitor() {
+        let stmt = parse(
+            "WITH c AS (SELECT a FROM t1) SELECT b, (SELECT MAX(x) FROM t2) FROM c JOIN t3 ON c.a = t3.a \
+             WHERE f > 0 AND d IN (SELECT e FROM t4)",
+        );
+        let mut collector = Collector::default();
+        assert_eq!(walk_statement(&mut collector, &stmt), ControlFlow::Continue(()));
+
+        assert_eq!(collector.tables, vec!["t1", "t2", "c", "t3", "t4"]);
+        assert_eq!(collector.columns, vec!["a", "b", "x", "f", "d", "e"]);
+        assert_eq!(collector.subqueries, 2);
+    }
+
+    #[test]
+    fn test_visitor_break() {
+        struct FindTable(&'static str);
+
+        impl Visitor for FindTable {
+            type Break = String;
+
+            fn post_visit_table(&mut self, table: &From) -> ControlFlow<Self::Break> {
+                match table {
+                    From::Table { name, alias } if name.value() == self.0 => {
+                        ControlFlow::Break(alias.clone().unwrap_or_default())
+                    }
+                    _ => ControlFlow::Continue(()),
+                }
+            }
+        }
+
+        let stmt = parse("SELECT * FROM t1 AS a, t2 AS b WHERE id IN (SELECT id FROM t2 AS c)");
+        assert_eq!(
+            walk_statement(&mut FindTable("t2"), &stmt),
+            ControlFlow::Break("b".to_owned())
+        );
+        assert_eq!(walk_statement(&mut FindTable("t3"), &stmt), ControlFlow::Continue(()));
+    }
+
+    #[test]
+    fn test_visitor_mut() {
+        /// qualify the tables with a schema and bind the parameters
+        struct Rewriter(Vec<Literal>);
+
+        impl VisitorMut for Rewriter {
+            type Break = String;
+
+            fn pre_visit_table(&mut self, table: &mut From) -> ControlFlow<Self::Break> {
+                if let From::Table { name, .. } = table {
+                    name.0.insert(0, "s".into());
+                }
+                ControlFlow::Continue(())
+            }
+
+            fn post_visit_expression(&mut self, expr: &mut Expression) -> ControlFlow<Self::Break> {
+                if let Expression::Placeholder(placeholder) = expr {
+                    let index = placeholder[1..].parse::<usize>().unwrap() - 1;
+                    match self.0.get(index) {
+                        Some(value) => *expr = Expression::Literal(value.clone()),
+                        None => return ControlFlow::Break(format!("no value of {}", placeholder)),
+                    }
+                }
+                ControlFlow::Continue(())
+            }
+        }
+
+        let mut stmt = parse("SELECT a + $1 FROM t JOIN u ON t.id = u.id WHERE b IN (SELECT c FROM v WHERE d = $2)");
+        let mut rewriter = Rewriter(vec![Literal::Int(1), Literal::String("x".to_owned())]);
+        assert_eq!(walk_statement_mut(&mut rewriter, &mut stmt), ControlFlow::Continue(()));
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT a + 1 FROM s.t INNER JOIN s.u ON t.id = u.id WHERE b IN (SELECT c FROM s.v WHERE d = 'x')"
+        );
+
+        let mut stmt = parse("DELETE FROM t WHERE a = $3");
+        assert_eq!(
+            walk_statement_mut(&mut rewriter, &mut stmt),
+            ControlFlow::Break("no value of $3".to_owned())
+        );
+    }
+}